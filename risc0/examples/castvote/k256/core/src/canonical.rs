@@ -0,0 +1,69 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The canonical text of a ballot, which is what gets hashed and signed.
+
+use serde_json::Value;
+
+/// Rewrites ballot JSON in canonical form: object keys sorted by their UTF-8 bytes, no
+/// whitespace outside strings, strings escaped minimally and numbers as `serde_json` prints them.
+///
+/// The host signs this form and every guest method canonicalizes the ballot it receives before
+/// hashing it, so re-indenting a ballot or reordering its keys does not invalidate its signature.
+/// Signers outside this crate, such as wallets, must sign the canonical form too.
+pub fn canonicalize_ballot(message: &str) -> Result<String, serde_json::Error> {
+    let value: Value = serde_json::from_str(message)?;
+    let mut out = String::with_capacity(message.len());
+    write_canonical(&value, &mut out);
+    Ok(out)
+}
+
+// Keys are sorted explicitly rather than relying on `serde_json::Map` being a `BTreeMap`, which
+// stops being true as soon as any crate in the build enables `preserve_order`.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::String(s) => write_string(s, out),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Null => out.push_str("null"),
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push_str(&serde_json::to_string(s).expect("strings always serialize"));
+}
@@ -0,0 +1,99 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ballot types and the rules a ballot's `choice` must follow for each of them.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::BallotError;
+
+/// How voters express their choice in a poll.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BallotType {
+    /// Exactly one option, given as a string.
+    #[default]
+    Single,
+    /// Any non-empty set of options, given as an array.
+    Approval,
+    /// A non-empty ordered list of distinct options, most preferred first. Not every option has
+    /// to be ranked.
+    Ranked,
+}
+
+/// The reserved `choice` of a voter who casts a blank ballot, whatever the ballot type. It is
+/// never an option: an abstention selects nothing and is committed as such.
+pub const ABSTAIN_CHOICE: &str = "abstain";
+
+/// The `choice` field of a ballot: either one option or a list of options, or an explicit
+/// abstention, written as `null` or [ABSTAIN_CHOICE].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Choice {
+    One(String),
+    Many(Vec<String>),
+    Abstain,
+}
+
+impl Choice {
+    /// Whether the voter abstained: `null`, or the reserved [ABSTAIN_CHOICE].
+    pub fn is_abstention(&self) -> bool {
+        match self {
+            Choice::Abstain => true,
+            Choice::One(option) => option == ABSTAIN_CHOICE,
+            Choice::Many(_) => false,
+        }
+    }
+
+    /// Checks the choice against the poll's ballot type and options and returns the selected
+    /// options in ballot order, none for an abstention. With no `options` configured any option
+    /// is accepted; the shape and duplicate checks still apply.
+    pub fn validate(&self, ballot_type: BallotType, options: &[String]) -> Result<Vec<String>, BallotError> {
+        if self.is_abstention() {
+            return Ok(Vec::new());
+        }
+        let selection = match (ballot_type, self) {
+            (BallotType::Single, Choice::One(option)) => vec![option.clone()],
+            (BallotType::Single, Choice::Many(_)) => {
+                return Err(BallotError::InvalidChoice { ballot_type, reason: "expected a single option" })
+            }
+            (_, Choice::One(_)) => {
+                return Err(BallotError::InvalidChoice { ballot_type, reason: "expected a list of options" })
+            }
+            (_, Choice::Many(selection)) if selection.is_empty() => {
+                return Err(BallotError::InvalidChoice { ballot_type, reason: "no option selected" })
+            }
+            (_, Choice::Many(selection)) => selection.clone(),
+            (_, Choice::Abstain) => unreachable!("abstentions return above"),
+        };
+
+        for (index, option) in selection.iter().enumerate() {
+            if !options.is_empty() && !options.contains(option) {
+                return Err(BallotError::UnknownOption(option.clone()));
+            }
+            if selection[..index].contains(option) {
+                return Err(BallotError::DuplicateOption(option.clone()));
+            }
+        }
+        Ok(selection)
+    }
+}
+
+/// Deserializes a ballot's `choice`, reading an explicit `null` as [Choice::Abstain] rather than
+/// as a missing field: a ballot without `choice` casts no vote, while one with `"choice": null`
+/// abstains.
+pub fn deserialize_ballot_choice<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Choice>, D::Error> {
+    Choice::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,70 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! ID commitments: a salted hash of the voter ID committed next to the nullifier, so a disputed
+//! nullifier can be tied to a registered voter without making the ID public.
+//!
+//! Voter IDs come from a small space, so a plain hash of one could be reversed by trying them
+//! all. Each ID is instead hashed with a per-voter secret that the election authority derives
+//! from its seed (see [derive_voter_secret]). To open one commitment the authority hands that
+//! voter's secret to an auditor, who learns nothing about any other voter's.
+
+use hkdf::Hkdf;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ElectionScope;
+
+/// HKDF `info` prefix of per-voter secrets; the election ID and voter ID follow it.
+pub const VOTER_SECRET_INFO: &[u8] = b"castvote voter secret v1:";
+
+/// Domain tag hashed in front of every ID commitment.
+pub const ID_COMMITMENT_TAG: &[u8] = b"castvote id commitment v1:";
+
+/// Derives the secret of `voter_id` in `election_id` as
+/// `HKDF-SHA256(ikm = authority_seed, info = VOTER_SECRET_INFO || len(election_id) ||
+/// election_id || voter_id)`, with the length as 8 big-endian bytes and the ID in its JSON form.
+pub fn derive_voter_secret(authority_seed: &[u8], election_id: &str, voter_id: &Value) -> [u8; 32] {
+    let mut secret = [0u8; 32];
+    let election_len = (election_id.len() as u64).to_be_bytes();
+    let voter_id = voter_id.to_string();
+    let info = [VOTER_SECRET_INFO, &election_len, election_id.as_bytes(), voter_id.as_bytes()];
+    Hkdf::<Sha256>::new(None, authority_seed)
+        .expand_multi_info(&info, &mut secret)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    secret
+}
+
+/// Computes the commitment `sha256(ID_COMMITMENT_TAG || voter_secret || voter_id)` to a voter
+/// ID, with the ID in its JSON form as the nullifier hashes it.
+pub fn id_commitment(voter_id: &Value, voter_secret: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ID_COMMITMENT_TAG);
+    hasher.update(voter_secret);
+    hasher.update(voter_id.to_string().as_bytes());
+    hasher.finalize().into()
+}
+
+impl ElectionScope {
+    /// The secret of `voter_id` in this election; see [derive_voter_secret].
+    pub fn voter_secret(&self, voter_id: &Value) -> [u8; 32] {
+        derive_voter_secret(&self.authority_seed, &self.election_id, voter_id)
+    }
+
+    /// The commitment to `voter_id` the guest commits in this election.
+    pub fn id_commitment(&self, voter_id: &Value) -> [u8; 32] {
+        id_commitment(voter_id, &self.voter_secret(voter_id))
+    }
+}
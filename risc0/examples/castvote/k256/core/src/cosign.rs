@@ -0,0 +1,148 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots that must be co-signed by several authorized keys, as some governance polls require.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode_legacy, decode_legacy_cbor, deserialize, encode_versioned, encode_versioned_with,
+    split_version, JournalCodec, JournalError, RevealInfo, JOURNAL_VERSION,
+};
+
+/// Input of the co-signing guest method, written before the [VoteParams](crate::VoteParams).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CosignInput {
+    /// The ballot JSON every co-signer signed.
+    pub ballot: String,
+    pub poll_id: u64,
+    /// Base64 SEC1 public keys allowed to co-sign.
+    pub authorized_keys: Vec<String>,
+    /// `(public key, signature)` pairs, both Base64, one per co-signer.
+    pub signatures: Vec<(String, String)>,
+    /// How many distinct authorized keys must have signed.
+    pub threshold: u32,
+}
+
+/// Reveal data committed by the co-signing guest method.
+///
+/// The `voter_key_hash` of [RevealInfo] is the hash of [authorized_set_bytes], so the journal
+/// names the authorized set the signatures were counted against.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CosignedReveal {
+    pub reveal: RevealInfo,
+    /// Number of distinct authorized keys with a valid signature.
+    pub valid_signatures: u32,
+    pub threshold: u32,
+}
+
+impl CosignedReveal {
+    /// Serializes the reveal data behind the same version byte as [RevealInfo::encode].
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [CosignedReveal::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [CosignedReveal::encode] and [CosignedReveal::encode_with].
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy::<(u32, u32)>(version, payload).map(
+                |(reveal, (valid_signatures, threshold))| Self { reveal, valid_signatures, threshold },
+            ),
+        }
+    }
+}
+
+/// Reasons a co-signed ballot is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CosignError {
+    /// A threshold of zero would accept an unsigned ballot.
+    ZeroThreshold,
+    /// More signers are required than there are authorized keys.
+    ThresholdTooHigh { threshold: u32, authorized: usize },
+    /// Fewer distinct authorized keys signed than the threshold requires.
+    BelowThreshold { found: u32, threshold: u32 },
+}
+
+impl fmt::Display for CosignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CosignError::ZeroThreshold => write!(f, "co-signing threshold must be at least 1"),
+            CosignError::ThresholdTooHigh { threshold, authorized } => {
+                write!(f, "threshold {threshold} exceeds the {authorized} authorized keys")
+            }
+            CosignError::BelowThreshold { found, threshold } => {
+                write!(f, "{found} valid co-signatures, {threshold} required")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CosignError {}
+
+/// Encodes the authorized key set independently of the order the keys were listed in: the keys
+/// sorted, duplicates removed and concatenated.
+///
+/// Keys must be canonically encoded (see [voter_key_hash](crate::voter_key_hash)).
+pub fn authorized_set_bytes(authorized: &[Vec<u8>]) -> Vec<u8> {
+    let mut keys = authorized.to_vec();
+    keys.sort();
+    keys.dedup();
+    keys.concat()
+}
+
+/// Counts the distinct keys of `authorized` among `valid_signers`, the keys whose signature
+/// verified, and checks the count against `threshold`.
+///
+/// A key signing twice counts once, and signers outside `authorized` do not count at all. Keys
+/// are compared byte for byte, so both lists must be canonically encoded.
+pub fn count_cosigners(
+    authorized: &[Vec<u8>],
+    valid_signers: &[Vec<u8>],
+    threshold: u32,
+) -> Result<u32, CosignError> {
+    let mut authorized = authorized.to_vec();
+    authorized.sort();
+    authorized.dedup();
+    if threshold == 0 {
+        return Err(CosignError::ZeroThreshold);
+    }
+    if threshold as usize > authorized.len() {
+        return Err(CosignError::ThresholdTooHigh { threshold, authorized: authorized.len() });
+    }
+
+    let mut signers: Vec<&Vec<u8>> = valid_signers
+        .iter()
+        .filter(|key| authorized.binary_search(key).is_ok())
+        .collect();
+    signers.sort();
+    signers.dedup();
+
+    let found = signers.len() as u32;
+    if found < threshold {
+        return Err(CosignError::BelowThreshold { found, threshold });
+    }
+    Ok(found)
+}
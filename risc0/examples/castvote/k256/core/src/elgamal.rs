@@ -0,0 +1,283 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Exponential ElGamal over secp256k1, for tallying encrypted votes without decrypting any
+//! single ballot.
+//!
+//! A vote is a one-hot vector over the poll's options and each entry `m` is encrypted separately
+//! as `(r·G, m·G + r·H)` under the election key `H`. Multiplying ciphertexts adds their
+//! plaintexts, so the entry-wise sum of every ballot's ciphertexts encrypts the per-option
+//! totals. The election key is the sum of the trustees' keys: every trustee must contribute a
+//! partial decryption before the totals can be read, and the totals are small enough to recover
+//! from `t·G` by search.
+
+use core::fmt;
+
+use k256::{
+    elliptic_curve::{group::{prime::PrimeCurveAffine, GroupEncoding}, PrimeField},
+    AffinePoint, ProjectivePoint, PublicKey, Scalar, SecretKey,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode_legacy, decode_legacy_cbor, deserialize, encode_versioned, encode_versioned_with,
+    split_version, BallotType, JournalCodec, JournalError, RevealInfo, JOURNAL_VERSION,
+};
+
+/// Reasons an ElGamal operation fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElGamalError {
+    /// A point is not a valid compressed secp256k1 encoding.
+    InvalidPoint,
+    /// A randomness value is not a non-zero scalar below the group order.
+    InvalidScalar,
+    /// Inputs that must have one entry per option have different lengths.
+    LengthMismatch { expected: usize, actual: usize },
+    /// Ranked ballots cannot be tallied by addition.
+    UnsupportedBallotType(BallotType),
+    /// A total is larger than the bound it was searched up to.
+    TotalOutOfRange { option: usize, max: u64 },
+    /// There are no ciphertexts or partial decryptions to combine.
+    Empty,
+}
+
+impl fmt::Display for ElGamalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElGamalError::InvalidPoint => write!(f, "not a valid secp256k1 point"),
+            ElGamalError::InvalidScalar => write!(f, "randomness is not a valid non-zero scalar"),
+            ElGamalError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {expected} entries, got {actual}")
+            }
+            ElGamalError::UnsupportedBallotType(ballot_type) => {
+                write!(f, "{ballot_type:?} ballots cannot be tallied homomorphically")
+            }
+            ElGamalError::TotalOutOfRange { option, max } => {
+                write!(f, "total of option {option} exceeds {max}")
+            }
+            ElGamalError::Empty => write!(f, "nothing to combine"),
+        }
+    }
+}
+
+impl std::error::Error for ElGamalError {}
+
+fn encode_point(point: ProjectivePoint) -> Vec<u8> {
+    point.to_affine().to_bytes().to_vec()
+}
+
+// The identity is rejected: an `alpha` of zero would leave the plaintext in the clear.
+fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, ElGamalError> {
+    let bytes = <[u8; 33]>::try_from(bytes).map_err(|_| ElGamalError::InvalidPoint)?;
+    Option::<AffinePoint>::from(AffinePoint::from_bytes(&bytes.into()))
+        .filter(|point| !bool::from(point.is_identity()))
+        .map(ProjectivePoint::from)
+        .ok_or(ElGamalError::InvalidPoint)
+}
+
+/// One exponential-ElGamal ciphertext `(r·G, m·G + r·H)`, both points compressed SEC1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub alpha: Vec<u8>,
+    pub beta: Vec<u8>,
+}
+
+impl Ciphertext {
+    fn points(&self) -> Result<(ProjectivePoint, ProjectivePoint), ElGamalError> {
+        Ok((decode_point(&self.alpha)?, decode_point(&self.beta)?))
+    }
+
+    fn from_points(alpha: ProjectivePoint, beta: ProjectivePoint) -> Self {
+        Self { alpha: encode_point(alpha), beta: encode_point(beta) }
+    }
+}
+
+/// Parses a 32-byte big-endian randomness value.
+pub fn parse_randomness(bytes: &[u8; 32]) -> Result<Scalar, ElGamalError> {
+    Option::<Scalar>::from(Scalar::from_repr((*bytes).into()))
+        .filter(|scalar| !bool::from(scalar.is_zero()))
+        .ok_or(ElGamalError::InvalidScalar)
+}
+
+/// Turns a validated selection into the vector of per-option entries to encrypt: 1 for every
+/// selected option, 0 otherwise.
+///
+/// Single-choice ballots yield a one-hot vector and approval ballots one entry per approved
+/// option; ranked ballots are rejected, since adding up rankings does not give a tally.
+pub fn one_hot(
+    selection: &[String],
+    ballot_type: BallotType,
+    options: &[String],
+) -> Result<Vec<u64>, ElGamalError> {
+    if ballot_type == BallotType::Ranked {
+        return Err(ElGamalError::UnsupportedBallotType(ballot_type));
+    }
+    Ok(options.iter().map(|option| u64::from(selection.contains(option))).collect())
+}
+
+/// Encrypts each entry of `vote` under `election_key` with the matching `randomness`.
+pub fn encrypt_vote(
+    election_key: &PublicKey,
+    vote: &[u64],
+    randomness: &[Scalar],
+) -> Result<Vec<Ciphertext>, ElGamalError> {
+    if randomness.len() != vote.len() {
+        return Err(ElGamalError::LengthMismatch { expected: vote.len(), actual: randomness.len() });
+    }
+    let key = election_key.to_projective();
+    Ok(vote
+        .iter()
+        .zip(randomness)
+        .map(|(&m, r)| {
+            Ciphertext::from_points(
+                ProjectivePoint::GENERATOR * r,
+                ProjectivePoint::GENERATOR * Scalar::from(m) + key * r,
+            )
+        })
+        .collect())
+}
+
+/// Multiplies the ciphertexts of every vote entry-wise, giving the encrypted per-option totals.
+pub fn aggregate(votes: &[Vec<Ciphertext>]) -> Result<Vec<Ciphertext>, ElGamalError> {
+    let (first, rest) = votes.split_first().ok_or(ElGamalError::Empty)?;
+    let mut totals = first.iter().map(Ciphertext::points).collect::<Result<Vec<_>, _>>()?;
+    for vote in rest {
+        if vote.len() != totals.len() {
+            return Err(ElGamalError::LengthMismatch { expected: totals.len(), actual: vote.len() });
+        }
+        for (total, ciphertext) in totals.iter_mut().zip(vote) {
+            let (alpha, beta) = ciphertext.points()?;
+            *total = (total.0 + alpha, total.1 + beta);
+        }
+    }
+    Ok(totals.into_iter().map(|(alpha, beta)| Ciphertext::from_points(alpha, beta)).collect())
+}
+
+/// Sums the trustees' public keys into the election key ballots are encrypted to.
+pub fn election_key(trustee_keys: &[PublicKey]) -> Result<PublicKey, ElGamalError> {
+    if trustee_keys.is_empty() {
+        return Err(ElGamalError::Empty);
+    }
+    let sum = trustee_keys.iter().map(PublicKey::to_projective).sum::<ProjectivePoint>();
+    PublicKey::from_affine(sum.to_affine()).map_err(|_| ElGamalError::InvalidPoint)
+}
+
+/// A trustee's share of the decryption of a tally: `s·alpha` per option, compressed SEC1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PartialDecryption(pub Vec<Vec<u8>>);
+
+/// Computes one trustee's partial decryption of `tally`. It reveals nothing on its own; only
+/// the shares of every trustee together reveal the totals.
+pub fn partial_decrypt(
+    trustee_secret: &SecretKey,
+    tally: &[Ciphertext],
+) -> Result<PartialDecryption, ElGamalError> {
+    let secret = *trustee_secret.to_nonzero_scalar();
+    let shares = tally
+        .iter()
+        .map(|ciphertext| Ok(encode_point(decode_point(&ciphertext.alpha)? * secret)))
+        .collect::<Result<_, ElGamalError>>()?;
+    Ok(PartialDecryption(shares))
+}
+
+/// Combines every trustee's partial decryption of `tally` and recovers the per-option totals,
+/// each of which must be at most `max_total` (e.g. the number of ballots).
+///
+/// This is n-of-n decryption, not a t-of-n threshold scheme: the election key is the plain sum of
+/// the trustees' keys, so `partials` must hold the share of each of them. Without one, the
+/// recovered points are meaningless and the call fails with [ElGamalError::TotalOutOfRange].
+pub fn combine_all_decryptions(
+    tally: &[Ciphertext],
+    partials: &[PartialDecryption],
+    max_total: u64,
+) -> Result<Vec<u64>, ElGamalError> {
+    if partials.is_empty() {
+        return Err(ElGamalError::Empty);
+    }
+    tally
+        .iter()
+        .enumerate()
+        .map(|(option, ciphertext)| {
+            let mut shared = ProjectivePoint::IDENTITY;
+            for partial in partials {
+                let share = partial.0.get(option).ok_or(ElGamalError::LengthMismatch {
+                    expected: tally.len(),
+                    actual: partial.0.len(),
+                })?;
+                shared += decode_point(share)?;
+            }
+            let target = decode_point(&ciphertext.beta)? - shared;
+            discrete_log(target, max_total).ok_or(ElGamalError::TotalOutOfRange { option, max: max_total })
+        })
+        .collect()
+}
+
+/// Finds `t <= max` with `t·G == target` by stepping through the multiples of `G`.
+fn discrete_log(target: ProjectivePoint, max: u64) -> Option<u64> {
+    let mut point = ProjectivePoint::IDENTITY;
+    for t in 0..=max {
+        if point == target {
+            return Some(t);
+        }
+        point += ProjectivePoint::GENERATOR;
+    }
+    None
+}
+
+/// Private input of the homomorphic guest method, read after the signed ballot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ElGamalInput {
+    /// Compressed SEC1 election key.
+    pub election_key: Vec<u8>,
+    /// One 32-byte big-endian randomness value per poll option. Never leaves the guest.
+    pub randomness: Vec<[u8; 32]>,
+}
+
+/// Reveal data committed by the homomorphic guest method: the usual [RevealInfo] with an empty
+/// `selection`, the election key and the encrypted vote, one ciphertext per poll option.
+///
+/// The receipt is the proof that the ciphertexts encrypt a valid vector: the guest builds them
+/// itself from the signed ballot's validated choice.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HomomorphicReveal {
+    pub reveal: RevealInfo,
+    pub election_key: Vec<u8>,
+    pub ciphertexts: Vec<Ciphertext>,
+}
+
+impl HomomorphicReveal {
+    /// Serializes the reveal data behind the same version byte as [RevealInfo::encode].
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [HomomorphicReveal::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [HomomorphicReveal::encode] and [HomomorphicReveal::encode_with].
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy::<(Vec<u8>, Vec<Ciphertext>)>(version, payload).map(
+                |(reveal, (election_key, ciphertexts))| Self { reveal, election_key, ciphertexts },
+            ),
+        }
+    }
+}
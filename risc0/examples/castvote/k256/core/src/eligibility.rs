@@ -0,0 +1,128 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Age eligibility proven without the ballot signature, for flows that check signatures
+//! elsewhere, e.g. on chain.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deserialize, encode_versioned, encode_versioned_with, scoped_nullifier, split_version, Ballot,
+    BallotError, Date, JournalCodec, JournalError, MessageFormat, SignedDigest, VoteParams,
+    JOURNAL_VERSION,
+};
+
+/// First journal version that binds an [AgeEligibility] to the ballot text.
+const FIRST_BOUND_VERSION: u8 = 14;
+
+/// `(ballot, poll_id, threshold)` as read by the age-eligibility guest method, written before
+/// the [VoteParams].
+pub type AgeEligibilityInput = (String, u64, u32);
+
+/// Journal of the age-eligibility guest method.
+///
+/// The nullifier is derived exactly as in [RevealInfo](crate::RevealInfo), so it can be matched
+/// against the journals of the signing methods. Only whether the voter meets `threshold` is
+/// revealed, not the age itself.
+///
+/// The signature is not checked here, so the journal commits the digest the signature has to
+/// cover: whoever checks it verifies against `message_digest`, which pins the proof to the ballot
+/// text it was computed from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AgeEligibility {
+    pub nullifier: String,
+    pub poll_id: u64,
+    /// Youngest eligible age the ballot was checked against.
+    pub threshold: u32,
+    pub is_eligible: bool,
+    /// How the signed text and its digest were derived from the ballot. `None` in journals
+    /// before version 14.
+    #[serde(default)]
+    pub message_format: Option<MessageFormat>,
+    /// Hex-encoded [MessageFormat::digest] of the signed text. `None` in journals before version
+    /// 14.
+    #[serde(default)]
+    pub message_digest: Option<String>,
+    /// Reference date the age was derived from a `birthdate` with, as in
+    /// [RevealInfo](crate::RevealInfo). Also `None` in journals before version 14.
+    #[serde(default)]
+    pub age_as_of: Option<Date>,
+}
+
+impl AgeEligibility {
+    /// Checks the age of `ballot`, derived as for [RevealInfo](crate::RevealInfo), against
+    /// `threshold`. An ineligible voter is not an error: the journal records the outcome.
+    ///
+    /// `signed_text` is the text `ballot` was parsed from, as returned by
+    /// [MessageFormat::signed_text] for `params.message_format`.
+    pub fn new(
+        ballot: &Ballot,
+        signed_text: &str,
+        poll_id: u64,
+        threshold: u32,
+        params: &VoteParams,
+    ) -> Result<Self, BallotError> {
+        params.check_poll_id(poll_id)?;
+        ballot.check_poll(poll_id)?;
+        let age = ballot.age(params)?;
+        let (SignedDigest::Sha256(digest) | SignedDigest::Prehash(digest)) =
+            params.message_format.digest(signed_text.as_bytes());
+        Ok(Self {
+            nullifier: hex::encode(scoped_nullifier(&ballot.id, poll_id, params.election.as_ref())),
+            poll_id,
+            threshold,
+            is_eligible: age >= threshold,
+            message_format: Some(params.message_format),
+            message_digest: Some(hex::encode(digest)),
+            age_as_of: ballot.birthdate.and(params.as_of),
+        })
+    }
+
+    /// Serializes the journal behind the same version byte as the reveal journals.
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [AgeEligibility::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [AgeEligibility::encode] and [AgeEligibility::encode_with]. Journals from
+    /// version 4, when the method was added, to 13 decode without the signed text binding.
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if (4..FIRST_BOUND_VERSION).contains(&version) => {
+                // CBOR maps lack the new fields, which default; bincode has their fixed prefix.
+                match payload.codec {
+                    JournalCodec::Cbor => deserialize(payload),
+                    JournalCodec::Bincode => deserialize(payload).map(
+                        |(nullifier, poll_id, threshold, is_eligible)| AgeEligibility {
+                            nullifier,
+                            poll_id,
+                            threshold,
+                            is_eligible,
+                            message_format: None,
+                            message_digest: None,
+                            age_as_of: None,
+                        },
+                    ),
+                }
+            }
+            (version, _) => Err(JournalError::UnsupportedVersion(version)),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding the Base64 signatures and public keys passed to the guest methods.
+//!
+//! The host runs the same checks before proving, so malformed input is reported before any
+//! proving cost is paid rather than as an opaque guest abort.
+
+use core::fmt;
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+
+/// Length of an ECDSA `r || s` signature and of an Ed25519 signature.
+pub const SIGNATURE_LEN: usize = 64;
+
+/// Length of an Ed25519 public key.
+pub const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// Standard alphabet, with or without trailing padding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Why a Base64 signature or public key was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The value is not valid Base64.
+    InvalidBase64 { field: &'static str, reason: String },
+    /// The value decodes to the wrong number of bytes.
+    WrongLength { field: &'static str, expected: &'static str, actual: usize },
+    /// A SEC1 public key does not start with a compressed or uncompressed point tag.
+    InvalidSec1Tag(u8),
+    /// A SEC1 public key encodes the point at infinity, which no private key maps to.
+    IdentityPoint,
+    /// A SEC1 public key has the right shape but is not a point on `curve`.
+    NotOnCurve { curve: &'static str },
+    /// `r` or `s` of an ECDSA signature is zero or not below the order of `curve`.
+    InvalidSignature { curve: &'static str },
+    /// An ECDSA signature has `s` in the upper half of the curve order; `(r, n - s)` verifies
+    /// too, so only the low-S form is accepted.
+    HighS,
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::InvalidBase64 { field, reason } => {
+                write!(f, "{field} is not valid Base64: {reason}")
+            }
+            EncodingError::WrongLength { field, expected, actual } => {
+                write!(f, "{field} must decode to {expected} bytes, got {actual}")
+            }
+            EncodingError::InvalidSec1Tag(tag) => {
+                write!(f, "public key has SEC1 tag {tag:#04x}, expected 0x02, 0x03 or 0x04")
+            }
+            EncodingError::IdentityPoint => write!(f, "public key is the point at infinity"),
+            EncodingError::NotOnCurve { curve } => {
+                write!(f, "public key is not a valid {curve} point")
+            }
+            EncodingError::InvalidSignature { curve } => {
+                write!(f, "signature is not a valid {curve} signature")
+            }
+            EncodingError::HighS => {
+                write!(f, "signature is not canonical: s must be in the lower half of the curve order")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// Decodes standard Base64, ignoring surrounding whitespace and missing padding.
+pub(crate) fn decode(field: &'static str, value: &str) -> Result<Vec<u8>, EncodingError> {
+    BASE64
+        .decode(value.trim())
+        .map_err(|e| EncodingError::InvalidBase64 { field, reason: e.to_string() })
+}
+
+pub(crate) fn decode_array<const N: usize>(
+    field: &'static str,
+    expected: &'static str,
+    value: &str,
+) -> Result<[u8; N], EncodingError> {
+    let bytes = decode(field, value)?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| EncodingError::WrongLength { field, expected, actual: bytes.len() })
+}
+
+/// Decodes a 64-byte signature, ECDSA `r || s` or Ed25519.
+pub fn decode_signature(value: &str) -> Result<[u8; SIGNATURE_LEN], EncodingError> {
+    decode_array("signature", "64", value)
+}
+
+/// Decodes a SEC1 ECDSA public key, compressed (33 bytes) or uncompressed (65 bytes).
+///
+/// Only the length and tag are checked (and the single `0x00` byte of the point at infinity
+/// rejected); [decode_k256_verifying_key] and [decode_p256_verifying_key] also check that the
+/// point is on the curve.
+pub fn decode_sec1_public_key(value: &str) -> Result<Vec<u8>, EncodingError> {
+    let bytes = decode("public key", value)?;
+    match (bytes.first(), bytes.len()) {
+        (Some(0x02 | 0x03), 33) | (Some(0x04), 65) => Ok(bytes),
+        (Some(0x00), 1) => Err(EncodingError::IdentityPoint),
+        (Some(&tag), _) if !(0x02..=0x04).contains(&tag) => Err(EncodingError::InvalidSec1Tag(tag)),
+        (_, actual) => Err(EncodingError::WrongLength {
+            field: "public key",
+            expected: "33 (compressed) or 65 (uncompressed)",
+            actual,
+        }),
+    }
+}
+
+/// Decodes a raw 32-byte Ed25519 public key.
+pub fn decode_ed25519_public_key(
+    value: &str,
+) -> Result<[u8; ED25519_PUBLIC_KEY_LEN], EncodingError> {
+    decode_array("public key", "32", value)
+}
+
+/// Decodes a SEC1 secp256k1 public key and checks that it is a point on the curve other than the
+/// identity, so keys from untrusted sources fail with a descriptive error.
+#[cfg(feature = "ecdsa")]
+pub fn decode_k256_verifying_key(value: &str) -> Result<k256::ecdsa::VerifyingKey, EncodingError> {
+    let bytes = decode_sec1_public_key(value)?;
+    k256::ecdsa::VerifyingKey::from_sec1_bytes(&bytes)
+        .map_err(|_| EncodingError::NotOnCurve { curve: "secp256k1" })
+}
+
+/// Encodes `key` as compressed SEC1, the canonical form hashed into the journal (see
+/// [voter_key_hash](crate::voter_key_hash)): a key passed compressed or uncompressed commits the
+/// same hash.
+#[cfg(feature = "ecdsa")]
+pub fn canonical_k256_key(key: &k256::ecdsa::VerifyingKey) -> Vec<u8> {
+    key.to_encoded_point(true).as_bytes().to_vec()
+}
+
+/// Like [canonical_k256_key], for P-256 keys.
+#[cfg(feature = "ecdsa")]
+pub fn canonical_p256_key(key: &p256::ecdsa::VerifyingKey) -> Vec<u8> {
+    key.to_encoded_point(true).as_bytes().to_vec()
+}
+
+/// Decodes a 64-byte `r || s` secp256k1 signature and rejects the high-S form, as Ethereum does.
+///
+/// ECDSA signatures are malleable: anyone can turn `(r, s)` into `(r, n - s)`, which verifies
+/// just the same. Accepting only low `s` leaves every signed ballot exactly one valid signature.
+#[cfg(feature = "ecdsa")]
+pub fn decode_k256_signature(value: &str) -> Result<k256::ecdsa::Signature, EncodingError> {
+    let signature = k256::ecdsa::Signature::from_slice(&decode_signature(value)?)
+        .map_err(|_| EncodingError::InvalidSignature { curve: "secp256k1" })?;
+    if signature.normalize_s().is_some() {
+        return Err(EncodingError::HighS);
+    }
+    Ok(signature)
+}
+
+/// Like [decode_k256_verifying_key], for P-256 keys.
+#[cfg(feature = "ecdsa")]
+pub fn decode_p256_verifying_key(value: &str) -> Result<p256::ecdsa::VerifyingKey, EncodingError> {
+    let bytes = decode_sec1_public_key(value)?;
+    p256::ecdsa::VerifyingKey::from_sec1_bytes(&bytes)
+        .map_err(|_| EncodingError::NotOnCurve { curve: "P-256" })
+}
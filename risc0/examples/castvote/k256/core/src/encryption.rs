@@ -0,0 +1,111 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots whose `choice` is encrypted to the election authority (ECIES over secp256k1).
+//!
+//! The voter signs a ballot carrying an [EncryptedChoice] instead of a plaintext `choice`. The
+//! guest checks the ciphertext is well-formed and commits it in place of the selection, so the
+//! journal proves eligibility and nullifier uniqueness without revealing how anyone voted; only
+//! the authority can decrypt the choices for tallying.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode_legacy, decode_legacy_cbor, deserialize, encode_versioned, encode_versioned_with,
+    encoding::{decode, decode_array},
+    split_version, EncodingError, JournalCodec, JournalError, RevealInfo, JOURNAL_VERSION,
+};
+
+/// Length of the AES-256-GCM nonce.
+pub const NONCE_LEN: usize = 12;
+
+/// Length of the AES-256-GCM authentication tag at the end of the ciphertext.
+pub const TAG_LEN: usize = 16;
+
+/// HKDF-SHA256 `info` used to derive the AES key from the ECDH shared secret. The salt is the
+/// compressed ephemeral public key.
+pub const ECIES_INFO: &[u8] = b"castvote ecies v1";
+
+/// The `encrypted_choice` field of a ballot: the JSON `choice` encrypted with AES-256-GCM under a
+/// key agreed between an ephemeral key and the authority's key. All fields are Base64.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedChoice {
+    /// Compressed SEC1 ephemeral public key (33 bytes).
+    pub ephemeral_key: String,
+    pub nonce: String,
+    /// Ciphertext followed by the authentication tag.
+    pub ciphertext: String,
+}
+
+/// The decoded parts of an [EncryptedChoice].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiphertextParts {
+    pub ephemeral_key: [u8; 33],
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedChoice {
+    /// Decodes the fields and checks their lengths and the ephemeral key's SEC1 tag. Whether the
+    /// key is a point on the curve is left to the curve crate.
+    pub fn decode(&self) -> Result<CiphertextParts, EncodingError> {
+        let ephemeral_key: [u8; 33] = decode_array("ephemeral key", "33", &self.ephemeral_key)?;
+        if !matches!(ephemeral_key[0], 0x02 | 0x03) {
+            return Err(EncodingError::InvalidSec1Tag(ephemeral_key[0]));
+        }
+        let nonce = decode_array("nonce", "12", &self.nonce)?;
+        let ciphertext = decode("ciphertext", &self.ciphertext)?;
+        if ciphertext.len() <= TAG_LEN {
+            return Err(EncodingError::WrongLength {
+                field: "ciphertext",
+                expected: "more than 16",
+                actual: ciphertext.len(),
+            });
+        }
+        Ok(CiphertextParts { ephemeral_key, nonce, ciphertext })
+    }
+}
+
+/// Reveal data committed by the encrypted-ballot guest method: the usual [RevealInfo], whose
+/// `selection` is empty, and the voter's still-encrypted choice.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedReveal {
+    pub reveal: RevealInfo,
+    pub encrypted_choice: EncryptedChoice,
+}
+
+impl EncryptedReveal {
+    /// Serializes the reveal data behind the same version byte as [RevealInfo::encode].
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [EncryptedReveal::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [EncryptedReveal::encode] and [EncryptedReveal::encode_with].
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy::<EncryptedChoice>(version, payload)
+                .map(|(reveal, encrypted_choice)| Self { reveal, encrypted_choice }),
+        }
+    }
+}
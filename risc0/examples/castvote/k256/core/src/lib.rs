@@ -0,0 +1,1081 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ballot handling shared by the host and every guest method.
+//!
+//! Each guest method verifies a different signature scheme, but they all parse the ballot, derive
+//! the nullifier and encode the reveal data the same way. Keeping that logic here means the
+//! journal layout cannot drift between methods.
+
+use core::{fmt, marker::PhantomData};
+use std::collections::BTreeMap;
+
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+mod canonical;
+mod choice;
+mod commitment;
+mod cosign;
+mod date;
+mod eligibility;
+#[cfg(feature = "elgamal")]
+mod elgamal;
+mod encoding;
+mod encryption;
+mod message;
+mod policy;
+mod poll;
+mod receipt;
+mod registry;
+mod rotation;
+mod unchecked;
+#[cfg(feature = "verify")]
+mod verify;
+
+pub use canonical::canonicalize_ballot;
+pub use choice::{BallotType, Choice, ABSTAIN_CHOICE};
+pub use commitment::{
+    derive_voter_secret, id_commitment, ID_COMMITMENT_TAG, VOTER_SECRET_INFO,
+};
+pub use cosign::{authorized_set_bytes, count_cosigners, CosignError, CosignInput, CosignedReveal};
+pub use date::{Date, DateError};
+pub use eligibility::{AgeEligibility, AgeEligibilityInput};
+#[cfg(feature = "elgamal")]
+pub use elgamal::{
+    aggregate, combine_all_decryptions, election_key, encrypt_vote, one_hot, parse_randomness,
+    partial_decrypt, Ciphertext, ElGamalError, ElGamalInput, HomomorphicReveal, PartialDecryption,
+};
+pub use encoding::{
+    decode_ed25519_public_key, decode_sec1_public_key, decode_signature, EncodingError,
+    ED25519_PUBLIC_KEY_LEN, SIGNATURE_LEN,
+};
+#[cfg(feature = "ecdsa")]
+pub use encoding::{
+    canonical_k256_key, canonical_p256_key, decode_k256_signature, decode_k256_verifying_key,
+    decode_p256_verifying_key,
+};
+pub use encryption::{
+    CiphertextParts, EncryptedChoice, EncryptedReveal, ECIES_INFO, NONCE_LEN, TAG_LEN,
+};
+pub use message::{
+    eip712_hash, verify_signed_ballot, SignedDigest, EIP712_BALLOT_TYPE, EIP712_DOMAIN_NAME,
+    EIP712_DOMAIN_VERSION,
+};
+pub use policy::EligibilityPolicy;
+pub use poll::{named_poll_id, PollIdentifier, PollMeta};
+pub use receipt::{check_receipt_id, RECEIPT_ID_MAX_LEN, RECEIPT_ID_MIN_LEN};
+pub use registry::{find_signer, registry_key_hash, RegistryError, RegistryInput};
+pub use rotation::{
+    key_schedule_bytes, key_schedule_hash, select_epoch, EpochReveal, KeyEpoch, RotatedKeysInput,
+    RotationError,
+};
+pub use unchecked::UncheckedReveal;
+#[cfg(feature = "verify")]
+pub use verify::{
+    check_verifier_parameters, simulate_onchain, verify_journal_and_seal, VerifyError,
+    GROTH16_SEAL_LEN,
+};
+
+/// Salt mixed into every nullifier of polls without an [ElectionScope].
+pub const NULLIFIER_SALT: &str = "secret";
+
+/// HKDF `info` prefix of per-election nullifier salts; the election ID follows it.
+pub const NULLIFIER_SALT_INFO: &[u8] = b"castvote nullifier salt v1:";
+
+/// Largest ballot, in bytes, accepted when [VoteParams::max_ballot_size] is not set.
+pub const DEFAULT_MAX_BALLOT_SIZE: u64 = 64 * 1024;
+
+/// Largest ballot, in bytes, a guest processes whatever [VoteParams::max_ballot_size] says, so a
+/// huge input cannot make a proof arbitrarily expensive.
+pub const MAX_BALLOT_SIZE_CAP: u64 = 1024 * 1024;
+
+/// The fields of a ballot that the guest reads.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Ballot {
+    /// Voter identifier. Kept as a raw JSON value because it is hashed in its JSON form.
+    pub id: Value,
+    /// Static age, used when the ballot carries no `birthdate`.
+    #[serde(default)]
+    pub age: Option<u32>,
+    /// Date of birth; takes precedence over `age` so the disclosed age never goes stale.
+    #[serde(default)]
+    pub birthdate: Option<Date>,
+    pub is_student: bool,
+    /// The option(s) voted for, if the ballot casts a vote; a list for approval and ranked polls.
+    /// `null` or [ABSTAIN_CHOICE] abstains, which is not the same as leaving it out.
+    #[serde(default, deserialize_with = "choice::deserialize_ballot_choice")]
+    pub choice: Option<Choice>,
+    /// The `choice` encrypted to the election authority, for ballots that keep it private.
+    #[serde(default)]
+    pub encrypted_choice: Option<EncryptedChoice>,
+    /// One choice per poll id, for ballots voting in several polls under a single signature.
+    #[serde(default)]
+    pub choices: Option<BTreeMap<u64, Choice>>,
+    /// The poll the ballot is cast in, if it names one; it must be the poll it is proven for.
+    #[serde(default, deserialize_with = "poll::deserialize_ballot_poll_id")]
+    pub poll_id: Option<PollIdentifier>,
+    /// A random ID the voter chose to look the proof up by (see [check_receipt_id]); committed
+    /// as is, and unrelated to the nullifier.
+    #[serde(default)]
+    pub receipt_id: Option<String>,
+    /// When the ballot was signed, as a Unix timestamp in seconds; picks the authority key
+    /// epoch a rotated-keys proof verifies against (see [select_epoch]).
+    #[serde(default)]
+    pub signed_at: Option<u64>,
+}
+
+/// Per-poll parameters written to the guest after the signature input.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteParams {
+    /// Reference date used to derive the age of ballots carrying a `birthdate`.
+    pub as_of: Option<Date>,
+    /// How the ballot bytes were turned into the digest that was signed.
+    pub message_format: MessageFormat,
+    /// How the ballot's `choice` is interpreted.
+    pub ballot_type: BallotType,
+    /// The options a ballot may select; any option if empty.
+    pub options: Vec<String>,
+    /// Ages the guest accepts; ballots outside it abort the proof.
+    #[serde(default)]
+    pub age_range: AgeRange,
+    /// Election the nullifiers are scoped to, or `None` for the fixed [NULLIFIER_SALT].
+    #[serde(default)]
+    pub election: Option<ElectionScope>,
+    /// Definition of the poll, whose hash is committed; `options` must equal its options.
+    #[serde(default)]
+    pub poll: Option<PollMeta>,
+    /// Whether only ballots with `is_student` set are accepted, as in student-only polls.
+    #[serde(default)]
+    pub require_student: bool,
+    /// Eligibility policy the ballot is evaluated against, whose hash is committed.
+    #[serde(default)]
+    pub policy: Option<EligibilityPolicy>,
+    /// How the guest serializes the journal payload.
+    #[serde(default)]
+    pub journal_codec: JournalCodec,
+    /// The string the poll ID was hashed from (see [PollIdentifier]), or `None` for a numeric
+    /// poll ID. The guest checks the hash and commits the name.
+    #[serde(default)]
+    pub poll_name: Option<String>,
+    /// Test-only: commit an [UncheckedReveal] recording whether the signature verified instead of
+    /// aborting on a bad one. Only guests built with the `test-signatures` feature accept it.
+    #[serde(default)]
+    pub allow_invalid_signature: bool,
+    /// Largest ballot, in bytes, the host and guest accept; [DEFAULT_MAX_BALLOT_SIZE] if `None`,
+    /// and never more than [MAX_BALLOT_SIZE_CAP].
+    #[serde(default)]
+    pub max_ballot_size: Option<u64>,
+    /// Largest poll ID the guest accepts, committed with the reveal data; `None` for no upper
+    /// bound. Poll ID 0 is rejected either way.
+    #[serde(default)]
+    pub max_poll_id: Option<u64>,
+    /// Base64 SEC1 public key of a service proving on the voters' behalf, committed by its hash
+    /// so the service's signature over the journal (see [RevealInfo::author_key_hash]) can be
+    /// attributed to it; `None` if the proof is not attributed.
+    #[serde(default)]
+    pub author_key: Option<String>,
+}
+
+/// Scopes nullifiers to one election: the salt is derived from a long-lived authority seed and
+/// the election ID (see [derive_nullifier_salt]), so every election gets its own reproducible
+/// salt while the authority keeps a single secret.
+///
+/// The seed is private guest input; only the election ID is committed.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ElectionScope {
+    pub election_id: String,
+    pub authority_seed: Vec<u8>,
+}
+
+impl ElectionScope {
+    /// The nullifier salt of this election.
+    pub fn salt(&self) -> String {
+        derive_nullifier_salt(&self.authority_seed, &self.election_id)
+    }
+}
+
+// The seed is a secret, so it is wiped when the scope is dropped.
+impl Drop for ElectionScope {
+    fn drop(&mut self) {
+        self.authority_seed.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for ElectionScope {}
+
+// The seed is a secret, so it is left out of debug output.
+impl fmt::Debug for ElectionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElectionScope")
+            .field("election_id", &self.election_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Derives the hex nullifier salt of `election_id` as
+/// `HKDF-SHA256(ikm = authority_seed, info = NULLIFIER_SALT_INFO || election_id)`.
+pub fn derive_nullifier_salt(authority_seed: &[u8], election_id: &str) -> String {
+    let mut salt = [0u8; 32];
+    Hkdf::<Sha256>::new(None, authority_seed)
+        .expand_multi_info(&[NULLIFIER_SALT_INFO, election_id.as_bytes()], &mut salt)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    hex::encode(salt)
+}
+
+/// Inclusive bounds on a voter's age, enforced by the guest and committed with the reveal data
+/// so verifiers know what was checked.
+///
+/// The default, 18 to 120, admits adult voters and rejects ages no real voter has.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for AgeRange {
+    fn default() -> Self {
+        Self { min: 18, max: 120 }
+    }
+}
+
+impl AgeRange {
+    /// Returns `age` if it lies within the range.
+    pub fn check(&self, age: u32) -> Result<u32, BallotError> {
+        if (self.min..=self.max).contains(&age) {
+            Ok(age)
+        } else {
+            Err(BallotError::AgeOutOfRange { age, range: *self })
+        }
+    }
+}
+
+impl fmt::Display for AgeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+/// How the signed bytes and their digest are derived from a ballot; see
+/// [MessageFormat::signed_text] and [MessageFormat::digest].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// The signature covers the SHA-256 digest of the canonical ballot (see
+    /// [canonicalize_ballot]).
+    #[default]
+    Sha256,
+    /// Ethereum `personal_sign`: the signature covers [eip191_hash] of the canonical ballot, as
+    /// produced by browser wallets. Only meaningful for secp256k1 keys.
+    Eip191,
+    /// The signature covers the SHA-256 digest of the ballot bytes exactly as given, so any
+    /// reformatting invalidates it.
+    Raw,
+    /// EIP-712 typed data: the signature covers [eip712_hash] of the canonical ballot, as
+    /// produced by `eth_signTypedData_v4`. Only meaningful for secp256k1 keys.
+    Eip712,
+}
+
+/// Prefix prepended by `personal_sign` (EIP-191 version `0x45`), followed by the decimal length of
+/// the message in bytes.
+pub const EIP191_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// Computes the `personal_sign` digest `keccak256(prefix || len(message) || message)`.
+///
+/// The length is the number of bytes, not characters, so multi-byte UTF-8 ballots hash the same
+/// way a wallet does.
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(EIP191_PREFIX.as_bytes());
+    hasher.update(message.len().to_string().as_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Reasons a correctly signed ballot can still be rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BallotError {
+    /// The ballot has neither an `age` nor a `birthdate`.
+    MissingAge,
+    /// The ballot has a `birthdate` but no reference date was supplied.
+    MissingAsOf,
+    /// The `birthdate` is after the reference date.
+    BornAfterAsOf { birthdate: Date, as_of: Date },
+    /// The age is outside the poll's plausible range.
+    AgeOutOfRange { age: u32, range: AgeRange },
+    /// The `choice` does not have the shape the ballot type requires.
+    InvalidChoice { ballot_type: BallotType, reason: &'static str },
+    /// The `choice` selects an option the poll does not offer.
+    UnknownOption(String),
+    /// The `choice` selects the same option more than once.
+    DuplicateOption(String),
+    /// A multi-poll ballot was proven for no poll at all.
+    NoPolls,
+    /// A poll of a multi-poll proof does not line up with the ballot's `choices`.
+    UnmatchedPoll { poll_id: u64, reason: &'static str },
+    /// The poll does not match the [PollMeta] the proof is bound to.
+    PollMismatch { poll_id: u64, reason: &'static str },
+    /// The poll requires a student ballot (see [VoteParams::require_student]) and this is not one.
+    NotAStudent,
+    /// The ballot breaks a rule of the poll's [EligibilityPolicy].
+    Ineligible { reason: &'static str },
+    /// A poll ID string is not usable as a [PollIdentifier].
+    InvalidPollId { reason: &'static str },
+    /// The `receipt_id` is not one [check_receipt_id] accepts.
+    InvalidReceiptId { reason: &'static str },
+    /// The ballot is larger than [VoteParams::max_ballot_size] allows.
+    BallotTooLarge { size: u64, limit: u64 },
+    /// Poll ID 0, almost always an uninitialized value rather than a real poll.
+    ZeroPollId,
+    /// The poll ID is above [VoteParams::max_poll_id].
+    PollIdAboveMax { poll_id: u64, max: u64 },
+    /// [VoteParams::author_key] is not a SEC1 public key.
+    InvalidAuthorKey(EncodingError),
+}
+
+impl fmt::Display for BallotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BallotError::MissingAge => write!(f, "ballot has neither `age` nor `birthdate`"),
+            BallotError::MissingAsOf => {
+                write!(f, "ballot has a `birthdate` but no reference date was given")
+            }
+            BallotError::BornAfterAsOf { birthdate, as_of } => {
+                write!(f, "birthdate {birthdate} is after the reference date {as_of}")
+            }
+            BallotError::AgeOutOfRange { age, range } => {
+                write!(f, "age {age} is outside the accepted range {range}")
+            }
+            BallotError::InvalidChoice { ballot_type, reason } => {
+                write!(f, "invalid {ballot_type:?} choice: {reason}")
+            }
+            BallotError::UnknownOption(option) => write!(f, "unknown option {option:?}"),
+            BallotError::DuplicateOption(option) => write!(f, "option {option:?} selected more than once"),
+            BallotError::NoPolls => write!(f, "a multi-poll ballot needs at least one poll"),
+            BallotError::UnmatchedPoll { poll_id, reason } => write!(f, "poll {poll_id} {reason}"),
+            BallotError::PollMismatch { poll_id, reason } => write!(f, "poll {poll_id} {reason}"),
+            BallotError::NotAStudent => {
+                write!(f, "the poll is for students only but `is_student` is false")
+            }
+            BallotError::Ineligible { reason } => {
+                write!(f, "ballot is not eligible under the policy: {reason}")
+            }
+            BallotError::InvalidPollId { reason } => write!(f, "poll ID {reason}"),
+            BallotError::InvalidReceiptId { reason } => write!(f, "receipt ID {reason}"),
+            BallotError::BallotTooLarge { size, limit } => {
+                write!(f, "ballot is {size} bytes, more than the {limit} allowed")
+            }
+            BallotError::ZeroPollId => write!(f, "poll ID 0 is not a valid poll"),
+            BallotError::PollIdAboveMax { poll_id, max } => {
+                write!(f, "poll ID {poll_id} is above the maximum of {max}")
+            }
+            BallotError::InvalidAuthorKey(e) => write!(f, "invalid author key: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BallotError {}
+
+/// Version of the reveal journal layout, committed as the first byte of every journal.
+///
+/// Bump it whenever [RevealInfo] changes shape, and keep decoding the older versions, so
+/// journals proven before the change can still be tallied: bincode ones through an entry in
+/// [REVEAL_FIELD_COUNTS].
+pub const JOURNAL_VERSION: u8 = 14;
+
+/// Reasons encoded reveal data cannot be decoded.
+#[derive(Debug)]
+pub enum JournalError {
+    /// There is not even a version byte.
+    Empty,
+    /// The version byte names a layout this build does not know.
+    UnsupportedVersion(u8),
+    /// The bytes after the version byte do not match its layout.
+    Malformed(bincode::Error),
+    /// The CBOR payload after the version byte does not match its layout.
+    MalformedCbor(ciborium::de::Error<std::io::Error>),
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Empty => write!(f, "journal is empty"),
+            JournalError::UnsupportedVersion(version) => write!(
+                f,
+                "journal version {version} is not supported (latest is {JOURNAL_VERSION})"
+            ),
+            JournalError::Malformed(e) => write!(f, "malformed journal: {e}"),
+            JournalError::MalformedCbor(e) => write!(f, "malformed CBOR journal: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for JournalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JournalError::Malformed(e) => Some(e),
+            JournalError::MalformedCbor(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The journal version CBOR journals were introduced at; no earlier CBOR journal exists.
+const FIRST_CBOR_VERSION: u8 = 7;
+
+/// Bit of the version byte set on journals whose payload is CBOR rather than bincode.
+pub const CBOR_VERSION_FLAG: u8 = 0x80;
+
+/// How a journal payload is serialized, recorded in the version byte (see
+/// [CBOR_VERSION_FLAG]) so decoding can dispatch on it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalCodec {
+    /// bincode: compact, but only practical to read from Rust.
+    #[default]
+    Bincode,
+    /// CBOR (RFC 8949): self-describing, with structs as maps keyed by field name, so tallying
+    /// tools in other languages can read it with a stock decoder.
+    Cbor,
+}
+
+/// Prefixes the bincode encoding of `value` with [JOURNAL_VERSION].
+fn encode_versioned<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+    encode_versioned_with(value, JournalCodec::Bincode)
+}
+
+/// Prefixes the `codec` encoding of `value` with [JOURNAL_VERSION], flagged for CBOR.
+fn encode_versioned_with<T: Serialize + ?Sized>(value: &T, codec: JournalCodec) -> Vec<u8> {
+    match codec {
+        JournalCodec::Bincode => {
+            let mut bytes = vec![JOURNAL_VERSION];
+            bincode::serialize_into(&mut bytes, value)
+                .expect("RevealInfo serialization is infallible");
+            bytes
+        }
+        JournalCodec::Cbor => {
+            let mut bytes = vec![JOURNAL_VERSION | CBOR_VERSION_FLAG];
+            ciborium::into_writer(value, &mut bytes)
+                .expect("RevealInfo serialization is infallible");
+            bytes
+        }
+    }
+}
+
+/// A journal payload and the codec it is serialized with.
+#[derive(Clone, Copy)]
+struct Payload<'a> {
+    codec: JournalCodec,
+    bytes: &'a [u8],
+}
+
+/// Splits `bytes` into the layout version and the payload it describes, taking the codec from
+/// the version byte.
+fn split_version(bytes: &[u8]) -> Result<(u8, Payload<'_>), JournalError> {
+    let (&version, bytes) = bytes.split_first().ok_or(JournalError::Empty)?;
+    let codec = if version & CBOR_VERSION_FLAG != 0 {
+        JournalCodec::Cbor
+    } else {
+        JournalCodec::Bincode
+    };
+    Ok((version & !CBOR_VERSION_FLAG, Payload { codec, bytes }))
+}
+
+impl VoteParams {
+    /// The largest ballot accepted, in bytes: [VoteParams::max_ballot_size] or its default,
+    /// capped at [MAX_BALLOT_SIZE_CAP].
+    pub fn ballot_size_limit(&self) -> u64 {
+        self.max_ballot_size.unwrap_or(DEFAULT_MAX_BALLOT_SIZE).min(MAX_BALLOT_SIZE_CAP)
+    }
+
+    /// Fails if a ballot of `size` bytes is larger than [VoteParams::ballot_size_limit]. Checked
+    /// before the ballot is hashed or parsed.
+    pub fn check_ballot_size(&self, size: u64) -> Result<(), BallotError> {
+        let limit = self.ballot_size_limit();
+        if size > limit {
+            return Err(BallotError::BallotTooLarge { size, limit });
+        }
+        Ok(())
+    }
+
+    /// Fails if `poll_id` is 0 or above [VoteParams::max_poll_id], catching poll IDs that were
+    /// never set or that overflowed.
+    pub fn check_poll_id(&self, poll_id: u64) -> Result<(), BallotError> {
+        if poll_id == 0 {
+            return Err(BallotError::ZeroPollId);
+        }
+        match self.max_poll_id {
+            Some(max) if poll_id > max => Err(BallotError::PollIdAboveMax { poll_id, max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Hex [voter_key_hash] of [VoteParams::author_key], if one is given, failing if it is not a
+    /// secp256k1 public key. The key is hashed in its [canonical_k256_key] form, so it commits
+    /// the same hash whether it was given compressed or uncompressed.
+    #[cfg(feature = "ecdsa")]
+    pub fn author_key_hash(&self) -> Result<Option<String>, BallotError> {
+        let Some(author_key) = &self.author_key else {
+            return Ok(None);
+        };
+        let key = decode_k256_verifying_key(author_key).map_err(BallotError::InvalidAuthorKey)?;
+        Ok(Some(hex::encode(voter_key_hash(&canonical_k256_key(&key)))))
+    }
+
+    /// Fails if the poll requires a student ballot and `ballot` is not one.
+    pub fn check_student(&self, ballot: &Ballot) -> Result<(), BallotError> {
+        if self.require_student && !ballot.is_student {
+            return Err(BallotError::NotAStudent);
+        }
+        Ok(())
+    }
+}
+
+impl Ballot {
+    /// Checks that the ballot is proven for the poll it names, if it names one, so a ballot
+    /// signed for one poll cannot be counted in another.
+    pub fn check_poll(&self, poll_id: u64) -> Result<(), BallotError> {
+        match &self.poll_id {
+            Some(named) if named.poll_id() != poll_id => {
+                let reason = "is not the poll the ballot names";
+                Err(BallotError::PollMismatch { poll_id, reason })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the voter's age, derived from `birthdate` when present.
+    pub fn age(&self, params: &VoteParams) -> Result<u32, BallotError> {
+        match (self.birthdate, self.age) {
+            (Some(birthdate), _) => {
+                let as_of = params.as_of.ok_or(BallotError::MissingAsOf)?;
+                birthdate
+                    .age_on(as_of)
+                    .ok_or(BallotError::BornAfterAsOf { birthdate, as_of })
+            }
+            (None, Some(age)) => Ok(age),
+            (None, None) => Err(BallotError::MissingAge),
+        }
+    }
+
+    /// Whether the ballot's `choice` is an explicit abstention (see [Choice::is_abstention]).
+    pub fn abstains(&self) -> bool {
+        self.choice.as_ref().is_some_and(Choice::is_abstention)
+    }
+
+    /// Returns the validated selection, empty if the ballot casts no vote or abstains.
+    ///
+    /// Fails for multi-poll ballots, whose `choices` would otherwise be dropped unnoticed.
+    pub fn selection(&self, params: &VoteParams) -> Result<Vec<String>, BallotError> {
+        if self.choices.is_some() {
+            return Err(BallotError::InvalidChoice {
+                ballot_type: params.ballot_type,
+                reason: "per-poll `choices` need the multi-poll method",
+            });
+        }
+        match &self.choice {
+            Some(choice) => choice.validate(params.ballot_type, &params.options),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the validated selection of every poll of a multi-poll ballot. All polls share the
+    /// ballot type and options of `params`.
+    pub fn poll_selections(&self, params: &VoteParams) -> Result<BTreeMap<u64, Vec<String>>, BallotError> {
+        let invalid = |reason| BallotError::InvalidChoice { ballot_type: params.ballot_type, reason };
+        if self.choice.is_some() || self.encrypted_choice.is_some() {
+            return Err(invalid("a multi-poll ballot takes `choices` per poll, not a single `choice`"));
+        }
+        let choices = self.choices.as_ref().ok_or_else(|| invalid("a multi-poll ballot needs `choices`"))?;
+        choices
+            .iter()
+            .map(|(&poll_id, choice)| Ok((poll_id, choice.validate(params.ballot_type, &params.options)?)))
+            .collect()
+    }
+}
+
+/// Data revealed in the journal of every guest method.
+///
+/// The fields added after version 1 default to `None` when missing, so an older CBOR journal,
+/// whose structs are maps keyed by field name, decodes straight into the current layout.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RevealInfo {
+    pub nullifier: String,
+    pub age: u32,
+    pub is_student: bool,
+    pub poll_id: u64,
+    /// Reference date the age was derived at, or `None` if the ballot declared a static age.
+    pub age_as_of: Option<Date>,
+    pub ballot_type: BallotType,
+    /// The validated options voted for, in ballot order (preference order for ranked polls).
+    pub selection: Vec<String>,
+    /// Hex `sha256` of the voter's public key (see [voter_key_hash]), identifying which voter
+    /// signed without revealing the key itself.
+    pub voter_key_hash: String,
+    /// The age bounds the guest enforced, or `None` for version 1 journals, which predate them.
+    #[serde(default)]
+    pub age_range: Option<AgeRange>,
+    /// The election the nullifier is scoped to, or `None` if it uses the fixed [NULLIFIER_SALT]
+    /// (always the case before version 3).
+    #[serde(default)]
+    pub election_id: Option<String>,
+    /// Hex [PollMeta::hash] of the poll definition the ballot was validated against, or `None`
+    /// if none was given (always the case before version 4).
+    #[serde(default)]
+    pub poll_hash: Option<String>,
+    /// How the signed bytes were derived from the ballot, or `None` before version 5.
+    #[serde(default)]
+    pub message_format: Option<MessageFormat>,
+    /// Whether the guest required `is_student` (see [VoteParams::require_student]), or `None`
+    /// before version 6.
+    #[serde(default)]
+    pub student_required: Option<bool>,
+    /// Hex [EligibilityPolicy::hash] of the policy the ballot was evaluated against, or `None`
+    /// if none was given (always the case before version 7).
+    #[serde(default)]
+    pub policy_hash: Option<String>,
+    /// The string `poll_id` was hashed from (see [PollIdentifier]), or `None` if the poll ID was
+    /// given as a number (always the case before version 8).
+    #[serde(default)]
+    pub poll_name: Option<String>,
+    /// The ballot's `receipt_id`, or `None` if it has none (always the case before version 9).
+    #[serde(default)]
+    pub receipt_id: Option<String>,
+    /// Hex [id_commitment] to the voter ID under the voter's secret in the election, or `None`
+    /// for polls without an [ElectionScope] (always the case before version 10).
+    #[serde(default)]
+    pub id_commitment: Option<String>,
+    /// The upper bound the guest enforced on `poll_id` (see [VoteParams::max_poll_id]), or
+    /// `None` if there was none (always the case before version 11).
+    #[serde(default)]
+    pub max_poll_id: Option<u64>,
+    /// Hex [voter_key_hash] of the [VoteParams::author_key] of the service that proved the
+    /// ballot and signs the journal, or `None` if the proof is not attributed (always the case
+    /// before version 12).
+    #[serde(default)]
+    pub author_key_hash: Option<String>,
+    /// Whether the voter explicitly abstained (see [Choice::is_abstention]): the nullifier is
+    /// spent and `selection` is empty. `Some(false)` for every other ballot, including one that
+    /// casts no vote at all; `None` before version 13.
+    #[serde(default)]
+    pub abstained: Option<bool>,
+}
+
+/// Number of [RevealInfo] fields in the bincode layout of each journal version, from version 1.
+///
+/// bincode writes a struct as its fields in order, and every field added after version 1 was
+/// appended, so an older journal holds the first fields of the current layout. Bumping
+/// [JOURNAL_VERSION] adds an entry here, and a field added with it goes last in [RevealInfo]
+/// as a `#[serde(default)]` `Option` read by [LegacyReveal]. These layouts only apply to bincode
+/// journals: CBOR ones are decoded with [decode_legacy_cbor].
+const REVEAL_FIELD_COUNTS: [usize; JOURNAL_VERSION as usize] =
+    [8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 20];
+
+/// Reads a [RevealInfo] from the first `fields` of its bincode layout, leaving the fields added
+/// since as `None`.
+#[derive(Clone, Copy)]
+struct LegacyReveal {
+    fields: usize,
+}
+
+impl LegacyReveal {
+    /// The layout of journal `version`, see [REVEAL_FIELD_COUNTS].
+    fn of(version: u8) -> Result<Self, JournalError> {
+        let fields = version.checked_sub(1).and_then(|i| REVEAL_FIELD_COUNTS.get(usize::from(i)));
+        match fields {
+            Some(&fields) => Ok(Self { fields }),
+            None => Err(JournalError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for LegacyReveal {
+    type Value = RevealInfo;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<RevealInfo, D::Error> {
+        deserializer.deserialize_tuple(self.fields, self)
+    }
+}
+
+impl<'de> Visitor<'de> for LegacyReveal {
+    type Value = RevealInfo;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the first {} fields of the reveal data", self.fields)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RevealInfo, A::Error> {
+        let mut read = 0;
+        // `field!(added)` is a field some layouts predate, `None` past the first `fields`.
+        macro_rules! field {
+            () => {{
+                read += 1;
+                seq.next_element()?.ok_or_else(|| de::Error::invalid_length(read - 1, &self))?
+            }};
+            (added) => {
+                if read < self.fields {
+                    field!()
+                } else {
+                    None
+                }
+            };
+        }
+        // Struct expression fields are evaluated in the order written, which is the layout order.
+        Ok(RevealInfo {
+            nullifier: field!(),
+            age: field!(),
+            is_student: field!(),
+            poll_id: field!(),
+            age_as_of: field!(),
+            ballot_type: field!(),
+            selection: field!(),
+            voter_key_hash: field!(),
+            age_range: field!(added),
+            election_id: field!(added),
+            poll_hash: field!(added),
+            message_format: field!(added),
+            student_required: field!(added),
+            policy_hash: field!(added),
+            poll_name: field!(added),
+            receipt_id: field!(added),
+            id_commitment: field!(added),
+            max_poll_id: field!(added),
+            author_key_hash: field!(added),
+            abstained: field!(added),
+        })
+    }
+}
+
+/// Reads `(Lead, RevealInfo, Rest)` with the reveal data in a [LegacyReveal] layout.
+struct LegacyAround<Lead, Rest> {
+    reveal: LegacyReveal,
+    around: PhantomData<(Lead, Rest)>,
+}
+
+impl<'de, Lead: Deserialize<'de>, Rest: Deserialize<'de>> DeserializeSeed<'de>
+    for LegacyAround<Lead, Rest>
+{
+    type Value = (Lead, RevealInfo, Rest);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_tuple(3, self)
+    }
+}
+
+impl<'de, Lead: Deserialize<'de>, Rest: Deserialize<'de>> Visitor<'de>
+    for LegacyAround<Lead, Rest>
+{
+    type Value = (Lead, RevealInfo, Rest);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the reveal data and the values around it")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &"three values");
+        let lead = seq.next_element()?.ok_or_else(|| missing(0))?;
+        let reveal = seq.next_element_seed(self.reveal)?.ok_or_else(|| missing(1))?;
+        let rest = seq.next_element()?.ok_or_else(|| missing(2))?;
+        Ok((lead, reveal, rest))
+    }
+}
+
+/// Reads a sequence of [RevealInfo], each in the same [LegacyReveal] layout.
+struct LegacyReveals(LegacyReveal);
+
+impl<'de> DeserializeSeed<'de> for LegacyReveals {
+    type Value = Vec<RevealInfo>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for LegacyReveals {
+    type Value = Vec<RevealInfo>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of reveal data")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut reveals = Vec::new();
+        while let Some(reveal) = seq.next_element_seed(self.0)? {
+            reveals.push(reveal);
+        }
+        Ok(reveals)
+    }
+}
+
+/// Like [deserialize] for a bincode payload of an older journal, read through `seed`.
+fn deserialize_legacy<'de, T: DeserializeSeed<'de>>(
+    seed: T,
+    payload: Payload<'de>,
+) -> Result<T::Value, JournalError> {
+    use bincode::Options;
+    // The options of `bincode::deserialize`.
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize_seed(seed, payload.bytes)
+        .map_err(JournalError::Malformed)
+}
+
+/// Decodes a bincode payload of an older journal `version` that starts with the reveal data and
+/// continues with `Rest`, the fields a guest method commits after it.
+fn decode_legacy<Rest: DeserializeOwned>(
+    version: u8,
+    payload: Payload<'_>,
+) -> Result<(RevealInfo, Rest), JournalError> {
+    decode_legacy_around::<(), Rest>(version, payload).map(|((), reveal, rest)| (reveal, rest))
+}
+
+/// Like [decode_legacy] for a payload that commits `Lead` before the reveal data. A `()` lead
+/// takes no bytes in bincode.
+fn decode_legacy_around<Lead: DeserializeOwned, Rest: DeserializeOwned>(
+    version: u8,
+    payload: Payload<'_>,
+) -> Result<(Lead, RevealInfo, Rest), JournalError> {
+    let reveal = LegacyReveal::of(version)?;
+    deserialize_legacy(LegacyAround { reveal, around: PhantomData }, payload)
+}
+
+/// Decodes the CBOR payload of an older journal `version` as `T`.
+///
+/// CBOR writes a struct as a map keyed by field name rather than as its fields in order, so the
+/// per-version field counts [decode_legacy] reads by do not apply. An older CBOR journal is the
+/// current layout without the fields added since, and [RevealInfo] defaults those to `None`.
+fn decode_legacy_cbor<T: DeserializeOwned>(
+    version: u8,
+    payload: Payload<'_>,
+) -> Result<T, JournalError> {
+    if !(FIRST_CBOR_VERSION..JOURNAL_VERSION).contains(&version) {
+        return Err(JournalError::UnsupportedVersion(version));
+    }
+    deserialize(payload)
+}
+
+/// Parses the signed ballot JSON.
+pub fn parse_ballot(message: &str) -> Result<Ballot, serde_json::Error> {
+    serde_json::from_str(message)
+}
+
+/// Hashes a voter's public key for the journal.
+///
+/// `public_key` must be the canonical encoding: compressed SEC1 for ECDSA keys (see
+/// `canonical_k256_key`), the raw 32 bytes for Ed25519 keys. Otherwise one key could commit two
+/// different hashes.
+pub fn voter_key_hash(public_key: &[u8]) -> [u8; 32] {
+    Sha256::digest(public_key).into()
+}
+
+/// Computes the nullifier `sha256(salt || id || poll_id)` for a voter in a poll, with the fixed
+/// [NULLIFIER_SALT].
+pub fn compute_nullifier(voter_id: &Value, poll_id: u64) -> [u8; 32] {
+    salted_nullifier(NULLIFIER_SALT, voter_id, poll_id)
+}
+
+/// Like [compute_nullifier] with another salt, such as an [ElectionScope::salt].
+pub fn salted_nullifier(salt: &str, voter_id: &Value, poll_id: u64) -> [u8; 32] {
+    Sha256::digest(format!("{}{}{}", salt, voter_id, poll_id).as_bytes()).into()
+}
+
+/// The nullifier every guest method commits for a voter in a poll: salted per `election` if
+/// given, with the fixed [NULLIFIER_SALT] otherwise. Auditors recompute it with this function.
+pub fn scoped_nullifier(voter_id: &Value, poll_id: u64, election: Option<&ElectionScope>) -> [u8; 32] {
+    match election {
+        Some(election) => salted_nullifier(&election.salt(), voter_id, poll_id),
+        None => compute_nullifier(voter_id, poll_id),
+    }
+}
+
+// Building reveal data hashes the author key in canonical form, which needs the `ecdsa` feature.
+#[cfg(feature = "ecdsa")]
+impl RevealInfo {
+    /// Builds the reveal data for a ballot whose signature has already been verified against
+    /// `public_key` (canonically encoded, see [voter_key_hash]).
+    ///
+    /// Only the derived age is revealed; the birthdate itself never leaves the guest. Fails if
+    /// the age is outside `params.age_range`, or if `params.require_student` is set and the
+    /// ballot is not a student's, or if it breaks a rule of `params.policy`, whose hash is
+    /// committed. The nullifier is salted per `params.election`, which also commits an
+    /// [id_commitment] to the voter ID.
+    /// With a `params.poll` definition, fails unless it is for `poll_id` and lists exactly
+    /// `params.options`, and commits its hash. With a `params.poll_name`, fails unless `poll_id`
+    /// is its [named_poll_id], and commits the name. A `receipt_id` in the ballot is checked
+    /// with [check_receipt_id] and committed. `poll_id` must pass [VoteParams::check_poll_id],
+    /// and `params.max_poll_id` is committed, as is the hash of a `params.author_key`. An
+    /// abstaining ballot commits an empty selection with `abstained` set.
+    pub fn new(
+        ballot: &Ballot,
+        poll_id: u64,
+        public_key: &[u8],
+        params: &VoteParams,
+    ) -> Result<Self, BallotError> {
+        let selection = ballot.selection(params)?;
+        Self::with_selection(ballot, poll_id, public_key, params, selection, ballot.abstains())
+    }
+
+    fn with_selection(
+        ballot: &Ballot,
+        poll_id: u64,
+        public_key: &[u8],
+        params: &VoteParams,
+        selection: Vec<String>,
+        abstained: bool,
+    ) -> Result<Self, BallotError> {
+        params.check_poll_id(poll_id)?;
+        ballot.check_poll(poll_id)?;
+        if let Some(receipt_id) = &ballot.receipt_id {
+            check_receipt_id(receipt_id)?;
+        }
+        if params.poll_name.as_deref().is_some_and(|name| named_poll_id(name) != poll_id) {
+            let reason = "is not the hash of the committed poll name";
+            return Err(BallotError::PollMismatch { poll_id, reason });
+        }
+        if let Some(poll) = &params.poll {
+            poll.check(poll_id, &params.options)?;
+        }
+        params.check_student(ballot)?;
+        let age = params.age_range.check(ballot.age(params)?)?;
+        if let Some(policy) = &params.policy {
+            policy.check(age, ballot.is_student, poll_id)?;
+        }
+        Ok(Self {
+            nullifier: hex::encode(scoped_nullifier(&ballot.id, poll_id, params.election.as_ref())),
+            age,
+            is_student: ballot.is_student,
+            poll_id,
+            age_as_of: ballot.birthdate.and(params.as_of),
+            ballot_type: params.ballot_type,
+            selection,
+            voter_key_hash: hex::encode(voter_key_hash(public_key)),
+            age_range: Some(params.age_range),
+            election_id: params.election.as_ref().map(|election| election.election_id.clone()),
+            poll_hash: params.poll.as_ref().map(|poll| hex::encode(poll.hash())),
+            message_format: Some(params.message_format),
+            student_required: Some(params.require_student),
+            policy_hash: params.policy.as_ref().map(|policy| hex::encode(policy.hash())),
+            poll_name: params.poll_name.clone(),
+            receipt_id: ballot.receipt_id.clone(),
+            id_commitment: params
+                .election
+                .as_ref()
+                .map(|election| hex::encode(election.id_commitment(&ballot.id))),
+            max_poll_id: params.max_poll_id,
+            author_key_hash: params.author_key_hash()?,
+            abstained: Some(abstained),
+        })
+    }
+
+    /// Builds one reveal entry per poll of a multi-poll ballot, in `poll_ids` order. Each entry
+    /// has its own nullifier, since the poll id is part of it.
+    ///
+    /// Every poll must have a choice in the ballot and every choice must be proven, so the
+    /// receipt neither invents a vote nor drops one the voter signed.
+    pub fn for_polls(
+        ballot: &Ballot,
+        poll_ids: &[u64],
+        public_key: &[u8],
+        params: &VoteParams,
+    ) -> Result<Vec<Self>, BallotError> {
+        if poll_ids.is_empty() {
+            return Err(BallotError::NoPolls);
+        }
+        let mut selections = ballot.poll_selections(params)?;
+        let mut reveals = Vec::with_capacity(poll_ids.len());
+        for &poll_id in poll_ids {
+            let Some(selection) = selections.remove(&poll_id) else {
+                let reason = if poll_ids.iter().filter(|&&id| id == poll_id).count() > 1 {
+                    "is listed more than once"
+                } else {
+                    "has no choice in the ballot"
+                };
+                return Err(BallotError::UnmatchedPoll { poll_id, reason });
+            };
+            let abstained = ballot.choices.as_ref().and_then(|choices| choices.get(&poll_id));
+            let abstained = abstained.is_some_and(Choice::is_abstention);
+            reveals.push(Self::with_selection(ballot, poll_id, public_key, params, selection, abstained)?);
+        }
+        if let Some(&poll_id) = selections.keys().next() {
+            return Err(BallotError::UnmatchedPoll { poll_id, reason: "has a choice in the ballot but is not proven" });
+        }
+        Ok(reveals)
+    }
+}
+
+impl RevealInfo {
+    /// Serializes the reveal data into the bytes that get ABI-wrapped into the journal: the
+    /// [JOURNAL_VERSION] byte followed by the bincode encoding.
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [RevealInfo::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [RevealInfo::encode] and [RevealInfo::encode_with], dispatching on the version
+    /// byte.
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy::<()>(version, payload).map(|(reveal, ())| reveal),
+        }
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(payload: Payload<'_>) -> Result<T, JournalError> {
+    match payload.codec {
+        JournalCodec::Bincode => {
+            bincode::deserialize(payload.bytes).map_err(JournalError::Malformed)
+        }
+        JournalCodec::Cbor => {
+            ciborium::from_reader(payload.bytes).map_err(JournalError::MalformedCbor)
+        }
+    }
+}
+
+/// Serializes the reveal data of a batch proof, in ballot order, behind the same version byte as
+/// [RevealInfo::encode].
+pub fn encode_reveals(reveals: &[RevealInfo]) -> Vec<u8> {
+    encode_versioned(reveals)
+}
+
+/// Like [encode_reveals], serializing with `codec`.
+pub fn encode_reveals_with(reveals: &[RevealInfo], codec: JournalCodec) -> Vec<u8> {
+    encode_versioned_with(reveals, codec)
+}
+
+/// Inverse of [encode_reveals] and [encode_reveals_with].
+pub fn decode_reveals(bytes: &[u8]) -> Result<Vec<RevealInfo>, JournalError> {
+    match split_version(bytes)? {
+        (JOURNAL_VERSION, payload) => deserialize(payload),
+        (version, payload) if payload.codec == JournalCodec::Cbor => {
+            decode_legacy_cbor(version, payload)
+        }
+        (version, payload) => {
+            deserialize_legacy(LegacyReveals(LegacyReveal::of(version)?), payload)
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! How the bytes a voter signs, and the digest the signature covers, are derived from a ballot.
+//!
+//! The host signs and every guest method verifies through [MessageFormat::signed_text] and
+//! [MessageFormat::digest], so the two sides cannot derive different messages.
+
+use std::error::Error;
+
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::{canonicalize_ballot, eip191_hash, parse_ballot, Ballot, MessageFormat, VoteParams};
+
+/// `name` of the EIP-712 domain ballots are signed under.
+pub const EIP712_DOMAIN_NAME: &str = "castvote";
+
+/// `version` of the EIP-712 domain ballots are signed under.
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+/// EIP-712 type of a ballot: its canonical JSON as a single string.
+pub const EIP712_BALLOT_TYPE: &str = "Ballot(string ballot)";
+
+const EIP712_DOMAIN_TYPE: &str = "EIP712Domain(string name,string version)";
+
+/// The digest a ballot signature covers, and how the signature scheme consumes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignedDigest {
+    /// SHA-256 of the signed text, handed to the scheme's `sign`/`verify`, which hash it again.
+    Sha256([u8; 32]),
+    /// A digest signed as is with `sign_prehash`/`verify_prehash`, as Ethereum wallets do.
+    Prehash([u8; 32]),
+}
+
+#[cfg(feature = "ecdsa")]
+impl SignedDigest {
+    /// Verifies a secp256k1 `signature` over this digest, with `verify_prehash` for
+    /// [SignedDigest::Prehash] and `verify` otherwise.
+    pub fn verify_k256(
+        self,
+        key: &k256::ecdsa::VerifyingKey,
+        signature: &k256::ecdsa::Signature,
+    ) -> Result<(), k256::ecdsa::Error> {
+        use k256::ecdsa::signature::{hazmat::PrehashVerifier, Verifier};
+
+        match self {
+            SignedDigest::Sha256(hash) => key.verify(&hash, signature),
+            SignedDigest::Prehash(digest) => key.verify_prehash(&digest, signature),
+        }
+    }
+}
+
+/// Derives the text the signature over `ballot` covers, parses the ballot from that text and
+/// hands both it and the digest to `verify`, returning the ballot with what `verify` returned.
+///
+/// Every signing guest method goes through here, so none parses other text than it verifies. An
+/// oversized `ballot` (see [VoteParams::check_ballot_size]) is refused before it is hashed.
+pub fn verify_signed_ballot<T>(
+    params: &VoteParams,
+    ballot: &str,
+    verify: impl FnOnce(&Ballot, SignedDigest) -> Result<T, Box<dyn Error>>,
+) -> Result<(Ballot, T), Box<dyn Error>> {
+    params.check_ballot_size(ballot.len() as u64)?;
+    let signed_text = params.message_format.signed_text(ballot)?;
+    let digest = params.message_format.digest(signed_text.as_bytes());
+    let ballot = parse_ballot(&signed_text)?;
+    let verified = verify(&ballot, digest)?;
+    Ok((ballot, verified))
+}
+
+impl MessageFormat {
+    /// The text the signature covers: `ballot` verbatim for [MessageFormat::Raw], its canonical
+    /// form (see [canonicalize_ballot]) otherwise.
+    pub fn signed_text(self, ballot: &str) -> Result<String, serde_json::Error> {
+        match self {
+            MessageFormat::Raw => Ok(ballot.to_string()),
+            MessageFormat::Sha256 | MessageFormat::Eip191 | MessageFormat::Eip712 => {
+                canonicalize_ballot(ballot)
+            }
+        }
+    }
+
+    /// The digest of `signed_text`, as returned by [MessageFormat::signed_text], that the
+    /// signature covers.
+    pub fn digest(self, signed_text: &[u8]) -> SignedDigest {
+        match self {
+            MessageFormat::Sha256 | MessageFormat::Raw => {
+                SignedDigest::Sha256(Sha256::digest(signed_text).into())
+            }
+            MessageFormat::Eip191 => SignedDigest::Prehash(eip191_hash(signed_text)),
+            MessageFormat::Eip712 => SignedDigest::Prehash(eip712_hash(signed_text)),
+        }
+    }
+
+    /// Whether only secp256k1 keys can sign in this format: the Ethereum wallet formats.
+    pub fn needs_secp256k1(self) -> bool {
+        matches!(self, MessageFormat::Eip191 | MessageFormat::Eip712)
+    }
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    Keccak256::digest(bytes).into()
+}
+
+/// Computes the EIP-712 digest `keccak256(0x19 0x01 || domainSeparator || hashStruct(message))`
+/// of the message `Ballot { ballot }` of type [EIP712_BALLOT_TYPE], under the domain named
+/// [EIP712_DOMAIN_NAME] with version [EIP712_DOMAIN_VERSION].
+pub fn eip712_hash(ballot: &[u8]) -> [u8; 32] {
+    let domain_separator = keccak256(
+        &[
+            keccak256(EIP712_DOMAIN_TYPE.as_bytes()),
+            keccak256(EIP712_DOMAIN_NAME.as_bytes()),
+            keccak256(EIP712_DOMAIN_VERSION.as_bytes()),
+        ]
+        .concat(),
+    );
+    let struct_hash =
+        keccak256(&[keccak256(EIP712_BALLOT_TYPE.as_bytes()), keccak256(ballot)].concat());
+    keccak256(&[&[0x19, 0x01][..], &domain_separator, &struct_hash].concat())
+}
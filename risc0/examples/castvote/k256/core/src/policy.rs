@@ -0,0 +1,79 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative eligibility policies evaluated in the guest.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::BallotError;
+
+/// Who may vote in a poll: a minimum age, whether voters must be students and which polls
+/// ballots may be cast in.
+///
+/// The guest evaluates every rule against the ballot and commits [EligibilityPolicy::hash], so a
+/// verifier holding the policy file can check which rules the proof enforced. Omitted rules
+/// allow everything; unknown fields are rejected, so a misspelled rule cannot be silently
+/// dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct EligibilityPolicy {
+    /// Youngest age accepted.
+    #[serde(default)]
+    pub min_age: u32,
+    /// Whether only ballots with `is_student` set are accepted.
+    #[serde(default)]
+    pub require_student: bool,
+    /// The polls ballots may be cast in, or `None` for any poll.
+    #[serde(default)]
+    pub allowed_polls: Option<Vec<u64>>,
+}
+
+impl EligibilityPolicy {
+    /// SHA-256 of the compact JSON `{"min_age":…,"require_student":…,"allowed_polls":…}`, fields
+    /// in that order and omitted rules written out with their defaults, as serialized by
+    /// `serde_json`. Two files stating the same rules therefore hash the same.
+    pub fn hash(&self) -> [u8; 32] {
+        let json =
+            serde_json::to_vec(self).expect("EligibilityPolicy serialization is infallible");
+        Sha256::digest(json).into()
+    }
+
+    /// Checks the rules about the voter: the minimum age and the student requirement.
+    pub fn check_voter(&self, age: u32, is_student: bool) -> Result<(), BallotError> {
+        if age < self.min_age {
+            return Err(BallotError::Ineligible { reason: "the voter is younger than `min_age`" });
+        }
+        if self.require_student && !is_student {
+            return Err(BallotError::Ineligible { reason: "the voter is not a student" });
+        }
+        Ok(())
+    }
+
+    /// Checks the rules about the poll: `poll_id` must be one of the `allowed_polls`.
+    pub fn check_poll(&self, poll_id: u64) -> Result<(), BallotError> {
+        match &self.allowed_polls {
+            Some(polls) if !polls.contains(&poll_id) => {
+                Err(BallotError::Ineligible { reason: "the poll is not in `allowed_polls`" })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks every rule against a ballot of a voter aged `age` cast in `poll_id`.
+    pub fn check(&self, age: u32, is_student: bool, poll_id: u64) -> Result<(), BallotError> {
+        self.check_voter(age, is_student)?;
+        self.check_poll(poll_id)
+    }
+}
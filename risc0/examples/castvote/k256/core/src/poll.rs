@@ -0,0 +1,140 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Poll definitions a proof can be bound to, and the identifiers polls are known by.
+
+use core::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::BallotError;
+
+/// Domain separator hashed in front of a poll name to derive its numeric poll ID.
+const POLL_NAME_DOMAIN: &str = "castvote/poll-id/";
+
+/// What a poll is: its ID, the question asked and the options a ballot may select.
+///
+/// The guest commits [PollMeta::hash] next to the reveal data, so a verifier holding the poll
+/// definition can check that the proof was made against it and not against an edited copy.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PollMeta {
+    pub poll_id: u64,
+    pub title: String,
+    pub options: Vec<String>,
+}
+
+impl PollMeta {
+    /// SHA-256 of the compact JSON `{"poll_id":…,"title":…,"options":[…]}`, fields in that
+    /// order, as serialized by `serde_json`.
+    pub fn hash(&self) -> [u8; 32] {
+        let json = serde_json::to_vec(self).expect("PollMeta serialization is infallible");
+        Sha256::digest(json).into()
+    }
+
+    /// Checks that a ballot for `poll_id`, validated against `options`, was cast under this
+    /// definition.
+    pub fn check(&self, poll_id: u64, options: &[String]) -> Result<(), BallotError> {
+        if self.poll_id != poll_id {
+            let reason = "is not the `poll_id` of the poll definition";
+            return Err(BallotError::PollMismatch { poll_id, reason });
+        }
+        if self.options != options {
+            let reason = "is checked against other options than its poll definition lists";
+            return Err(BallotError::PollMismatch { poll_id, reason });
+        }
+        Ok(())
+    }
+}
+
+/// A poll ID as given by the user: a number, or any other string such as a UUID or a slug.
+///
+/// Nullifiers and journals key polls by a `u64`. A numeric identifier is that number, exactly as
+/// before string IDs were accepted; a name is hashed to one (see [named_poll_id]) and committed
+/// next to it, so a verifier can tell which of the two it is looking at.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum PollIdentifier {
+    Numeric(u64),
+    Named(String),
+}
+
+impl PollIdentifier {
+    /// The `u64` the poll is keyed by in nullifiers and journals.
+    pub fn poll_id(&self) -> u64 {
+        match self {
+            PollIdentifier::Numeric(poll_id) => *poll_id,
+            PollIdentifier::Named(name) => named_poll_id(name),
+        }
+    }
+
+    /// The original string, unless the identifier is numeric.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            PollIdentifier::Numeric(_) => None,
+            PollIdentifier::Named(name) => Some(name),
+        }
+    }
+}
+
+/// Parses anything `u64` parses as [PollIdentifier::Numeric], and any other non-empty string as
+/// [PollIdentifier::Named].
+impl FromStr for PollIdentifier {
+    type Err = BallotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(BallotError::InvalidPollId { reason: "is empty" });
+        }
+        Ok(match s.parse() {
+            Ok(poll_id) => PollIdentifier::Numeric(poll_id),
+            Err(_) => PollIdentifier::Named(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for PollIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PollIdentifier::Numeric(poll_id) => write!(f, "{poll_id}"),
+            PollIdentifier::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Deserializes the optional `poll_id` a ballot carries: a JSON number, or a string read as
+/// [PollIdentifier::from_str] reads `--poll-id`.
+pub fn deserialize_ballot_poll_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<PollIdentifier>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(u64),
+        String(String),
+    }
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(poll_id) => Ok(Some(PollIdentifier::Numeric(poll_id))),
+        Raw::String(poll_id) => poll_id.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// The numeric poll ID of a poll known by `name`: the first 8 bytes, big-endian, of
+/// `sha256("castvote/poll-id/" || name)`.
+///
+/// Names are hashed as given, so `"Budget-2025"` and `"budget-2025"` are different polls.
+pub fn named_poll_id(name: &str) -> u64 {
+    let digest = Sha256::new().chain_update(POLL_NAME_DOMAIN).chain_update(name).finalize();
+    u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 digests are 32 bytes"))
+}
@@ -0,0 +1,42 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Receipt IDs: voter-chosen random identifiers for finding a proof again.
+//!
+//! A receipt ID is committed next to the nullifier but derived from nothing: the voter draws it at
+//! random and keeps it, and can later look their proof up in a public list by it. Unlike the
+//! nullifier, which stops double votes, it cannot be recomputed from the voter's identity.
+
+use crate::BallotError;
+
+/// Fewest random bytes a receipt ID may carry, so two voters never pick the same one by chance.
+pub const RECEIPT_ID_MIN_LEN: usize = 16;
+
+/// Most bytes a receipt ID may carry.
+pub const RECEIPT_ID_MAX_LEN: usize = 32;
+
+/// Checks that `receipt_id` is lowercase hex of [RECEIPT_ID_MIN_LEN] to [RECEIPT_ID_MAX_LEN]
+/// bytes. Only one spelling is accepted, so a lookup by the committed string always matches.
+pub fn check_receipt_id(receipt_id: &str) -> Result<(), BallotError> {
+    let invalid = |reason| Err(BallotError::InvalidReceiptId { reason });
+    if !receipt_id.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return invalid("must be lowercase hex");
+    }
+    match hex::decode(receipt_id) {
+        Ok(bytes) if (RECEIPT_ID_MIN_LEN..=RECEIPT_ID_MAX_LEN).contains(&bytes.len()) => Ok(()),
+        Ok(_) => invalid("must be 16 to 32 bytes"),
+        Err(_) => invalid("must be a whole number of bytes"),
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots signed by any one key of a small registry of authorized voters, the minimal way to let
+//! several voters use one guest method before a Merkle tree of voters is worth building.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{authorized_set_bytes, voter_key_hash};
+
+/// Input of the registry guest method, written before the [VoteParams](crate::VoteParams).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegistryInput {
+    /// Base64 signature over the canonical ballot.
+    pub signature: String,
+    pub ballot: String,
+    pub poll_id: u64,
+    /// Base64 SEC1 public keys of the voters allowed to sign.
+    pub authorized_keys: Vec<String>,
+}
+
+/// Reasons a registry ballot is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No keys were authorized, so no signature could ever be accepted.
+    Empty,
+    /// The signature verifies against none of the authorized keys.
+    NoMatchingKey { keys: usize },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Empty => write!(f, "the registry lists no authorized keys"),
+            RegistryError::NoMatchingKey { keys } => {
+                write!(f, "the signature verifies against none of the {keys} authorized keys")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Returns the index of the first of `keys` the signature verifies against, as decided by
+/// `verifies`.
+pub fn find_signer<K>(
+    keys: &[K],
+    verifies: impl FnMut(&K) -> bool,
+) -> Result<usize, RegistryError> {
+    if keys.is_empty() {
+        return Err(RegistryError::Empty);
+    }
+    keys.iter().position(verifies).ok_or(RegistryError::NoMatchingKey { keys: keys.len() })
+}
+
+/// The `voter_key_hash` committed for registry ballots: the hash of the whole registry (see
+/// [authorized_set_bytes]), which names the registry without revealing which of its keys signed.
+///
+/// Keys must be canonically encoded (see [voter_key_hash]).
+pub fn registry_key_hash(authorized: &[Vec<u8>]) -> [u8; 32] {
+    voter_key_hash(&authorized_set_bytes(authorized))
+}
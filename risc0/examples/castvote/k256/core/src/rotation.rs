@@ -0,0 +1,184 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Authority keys that rotate over time: each key signs the ballots of its own validity window,
+//! so rotating to a new key does not invalidate the ballots the old one signed.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode_legacy, decode_legacy_cbor, deserialize, encode_versioned, encode_versioned_with,
+    split_version, voter_key_hash, JournalCodec, JournalError, RevealInfo, JOURNAL_VERSION,
+};
+
+/// One authority key and the window it was valid in, as Unix timestamps in seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyEpoch {
+    /// Base64 SEC1 public key.
+    pub public_key: String,
+    /// First second the key is valid.
+    pub valid_from: u64,
+    /// First second the key is no longer valid; `None` for the current key.
+    #[serde(default)]
+    pub valid_until: Option<u64>,
+}
+
+impl KeyEpoch {
+    /// Whether a ballot signed at `signed_at` falls in this epoch: `valid_from` inclusive,
+    /// `valid_until` exclusive.
+    pub fn is_valid_at(&self, signed_at: u64) -> bool {
+        self.valid_from <= signed_at && self.valid_until.is_none_or(|until| signed_at < until)
+    }
+}
+
+/// Input of the rotated-keys guest method, written before the [VoteParams](crate::VoteParams).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RotatedKeysInput {
+    /// Base64 signature over the canonical ballot, which must carry a `signed_at` time.
+    pub signature: String,
+    pub ballot: String,
+    pub poll_id: u64,
+    /// The authority's keys over time, in any order.
+    pub epochs: Vec<KeyEpoch>,
+}
+
+/// Reveal data committed by the rotated-keys guest method.
+///
+/// The `voter_key_hash` of [RevealInfo] is [key_schedule_hash] of the whole schedule, and the
+/// epoch fields say which of its keys verified the ballot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EpochReveal {
+    pub reveal: RevealInfo,
+    /// Index of the verifying epoch in the input's `epochs`.
+    pub epoch: u32,
+    pub valid_from: u64,
+    pub valid_until: Option<u64>,
+}
+
+impl EpochReveal {
+    /// Serializes the reveal data behind the same version byte as [RevealInfo::encode].
+    pub fn encode(&self) -> Vec<u8> {
+        encode_versioned(self)
+    }
+
+    /// Like [EpochReveal::encode], serializing with `codec`.
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [EpochReveal::encode] and [EpochReveal::encode_with].
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy::<(u32, u64, Option<u64>)>(version, payload).map(
+                |(reveal, (epoch, valid_from, valid_until))| Self {
+                    reveal,
+                    epoch,
+                    valid_from,
+                    valid_until,
+                },
+            ),
+        }
+    }
+}
+
+/// Reasons a ballot is rejected against a key schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RotationError {
+    /// The schedule lists no keys.
+    Empty,
+    /// An epoch ends before it starts, so no ballot could fall in it.
+    EmptyEpoch { epoch: usize },
+    /// Two epochs overlap, so a signing time could pick either key.
+    Overlapping { first: usize, second: usize },
+    /// The ballot has no `signed_at` time to pick an epoch with.
+    NoSigningTime,
+    /// No epoch covers the ballot's signing time.
+    NoEpochAt { signed_at: u64 },
+}
+
+impl fmt::Display for RotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RotationError::Empty => write!(f, "the key schedule lists no keys"),
+            RotationError::EmptyEpoch { epoch } => {
+                write!(f, "key epoch {epoch} ends before it starts")
+            }
+            RotationError::Overlapping { first, second } => {
+                write!(f, "key epochs {first} and {second} overlap")
+            }
+            RotationError::NoSigningTime => write!(f, "the ballot has no signed_at time"),
+            RotationError::NoEpochAt { signed_at } => {
+                write!(f, "no key epoch covers signing time {signed_at}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RotationError {}
+
+/// Returns the index of the epoch valid at `signed_at`, after checking the schedule is
+/// non-empty and its epochs do not overlap.
+pub fn select_epoch(epochs: &[KeyEpoch], signed_at: u64) -> Result<usize, RotationError> {
+    if epochs.is_empty() {
+        return Err(RotationError::Empty);
+    }
+    let end = |epoch: &KeyEpoch| epoch.valid_until.unwrap_or(u64::MAX);
+    for (index, epoch) in epochs.iter().enumerate() {
+        if end(epoch) <= epoch.valid_from {
+            return Err(RotationError::EmptyEpoch { epoch: index });
+        }
+        for (other, later) in epochs.iter().enumerate().skip(index + 1) {
+            if epoch.valid_from < end(later) && later.valid_from < end(epoch) {
+                return Err(RotationError::Overlapping { first: index, second: other });
+            }
+        }
+    }
+    epochs
+        .iter()
+        .position(|epoch| epoch.is_valid_at(signed_at))
+        .ok_or(RotationError::NoEpochAt { signed_at })
+}
+
+/// Encodes a key schedule independently of the order its epochs were listed in: each epoch as
+/// its key, `valid_from` and `valid_until` (`u64::MAX` if open-ended) big-endian, sorted and
+/// concatenated.
+///
+/// Keys must be canonically encoded (see [voter_key_hash]), paired with their windows.
+pub fn key_schedule_bytes(schedule: &[(Vec<u8>, u64, Option<u64>)]) -> Vec<u8> {
+    let mut entries: Vec<Vec<u8>> = schedule
+        .iter()
+        .map(|(key, from, until)| {
+            let mut entry = key.clone();
+            entry.extend_from_slice(&from.to_be_bytes());
+            entry.extend_from_slice(&until.unwrap_or(u64::MAX).to_be_bytes());
+            entry
+        })
+        .collect();
+    entries.sort();
+    entries.dedup();
+    entries.concat()
+}
+
+/// The `voter_key_hash` committed for ballots verified against a key schedule: the hash of
+/// [key_schedule_bytes], which names every key and window the authority used.
+pub fn key_schedule_hash(schedule: &[(Vec<u8>, u64, Option<u64>)]) -> [u8; 32] {
+    voter_key_hash(&key_schedule_bytes(schedule))
+}
@@ -0,0 +1,62 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Journals of test builds that record a bad signature instead of aborting on it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode_legacy_around, decode_legacy_cbor, deserialize, encode_versioned_with, split_version,
+    JournalCodec, JournalError, RevealInfo, JOURNAL_VERSION,
+};
+
+/// The journal version test builds started committing [UncheckedReveal] at; no earlier unchecked
+/// journal exists.
+const FIRST_UNCHECKED_VERSION: u8 = 9;
+
+/// What a guest built with the `test-signatures` feature commits when
+/// [VoteParams::allow_invalid_signature](crate::VoteParams::allow_invalid_signature) is set:
+/// the reveal data and whether the signature verified, so negative tests can run the whole
+/// pipeline with a bad signature. Production guests never commit it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UncheckedReveal {
+    /// Serialized first, so the journal never decodes as a plain [RevealInfo].
+    pub is_signature_valid: bool,
+    pub reveal: RevealInfo,
+}
+
+impl UncheckedReveal {
+    /// Serializes the data behind the same version byte as [RevealInfo::encode_with].
+    pub fn encode_with(&self, codec: JournalCodec) -> Vec<u8> {
+        encode_versioned_with(self, codec)
+    }
+
+    /// Inverse of [UncheckedReveal::encode_with]. Also accepts the earlier journal versions test
+    /// builds committed, from version 9 on.
+    pub fn decode(bytes: &[u8]) -> Result<Self, JournalError> {
+        match split_version(bytes)? {
+            (JOURNAL_VERSION, payload) => deserialize(payload),
+            (version, _) if version < FIRST_UNCHECKED_VERSION => {
+                Err(JournalError::UnsupportedVersion(version))
+            }
+            (version, payload) if payload.codec == JournalCodec::Cbor => {
+                decode_legacy_cbor(version, payload)
+            }
+            (version, payload) => decode_legacy_around::<bool, ()>(version, payload).map(
+                |(is_signature_valid, reveal, ())| UncheckedReveal { is_signature_valid, reveal },
+            ),
+        }
+    }
+}
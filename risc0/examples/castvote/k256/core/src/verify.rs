@@ -0,0 +1,188 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verifying a vote from the `seal.dat` and `journal.dat` the prover writes.
+//!
+//! Only the verifier half of `risc0-zkvm` is used and nothing touches the filesystem, so this
+//! module can build for `wasm32-unknown-unknown` and run in a browser-based results viewer.
+
+use core::fmt;
+
+use alloy_sol_types::SolValue;
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    Groth16Receipt, Groth16ReceiptVerifierParameters, InnerReceipt, MaybePruned, Receipt,
+    ReceiptClaim,
+};
+
+use crate::{JournalError, RevealInfo};
+
+/// Why a seal and journal were not accepted.
+#[derive(Debug)]
+pub enum VerifyError {
+    /// The seal is too short to hold the 4-byte verifier selector.
+    MissingSelector,
+    /// The selector matches, but the full digest of the verifier parameters is not the one
+    /// expected.
+    VerifierParameters { expected: Digest, actual: Digest },
+    /// The selector does not name the Groth16 verifier this build knows. Fake dev-mode seals,
+    /// whose selector is all zeroes, end up here.
+    UnknownSelector([u8; 4]),
+    /// The seal after the selector is not the 256 bytes the Solidity verifier ABI-decodes.
+    SealLength(usize),
+    /// The seal is a valid proof, but of a different encoding of the journal: the contract
+    /// would hash the submitted bytes and reject it.
+    JournalDigestMismatch { proven: &'static str },
+    /// The seal does not prove the claimed execution of `image_id` with this journal.
+    Receipt(String),
+    /// The journal is not ABI-encoded `bytes`.
+    NotAbiBytes,
+    /// The proof is valid but the journal does not decode to reveal data.
+    Journal(JournalError),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::MissingSelector => write!(f, "seal is shorter than its 4-byte selector"),
+            VerifyError::VerifierParameters { expected, actual } => {
+                write!(f, "verifier parameters digest is {actual}, expected {expected}")
+            }
+            VerifyError::UnknownSelector(selector) => {
+                write!(f, "unknown verifier selector {}", hex::encode(selector))
+            }
+            VerifyError::SealLength(len) => {
+                write!(f, "seal has {len} bytes after the selector, expected {GROTH16_SEAL_LEN}")
+            }
+            VerifyError::JournalDigestMismatch { proven } => {
+                write!(f, "journal digest mismatch: the seal proves {proven}")
+            }
+            VerifyError::Receipt(e) => write!(f, "receipt does not verify: {e}"),
+            VerifyError::NotAbiBytes => write!(f, "journal is not ABI-encoded bytes"),
+            VerifyError::Journal(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl VerifyError {
+    /// Short name of the check that failed, as reported by `checkvote simulate-onchain`.
+    pub fn failed_check(&self) -> &'static str {
+        match self {
+            VerifyError::MissingSelector | VerifyError::UnknownSelector(_) => "selector mismatch",
+            VerifyError::VerifierParameters { .. } => "verifier parameters",
+            VerifyError::SealLength(_) => "seal length",
+            VerifyError::JournalDigestMismatch { .. } => "digest mismatch",
+            VerifyError::Receipt(_) => "invalid proof",
+            VerifyError::NotAbiBytes | VerifyError::Journal(_) => "journal decoding",
+        }
+    }
+}
+
+/// Length of a Groth16 seal after its selector: the proof points `a`, `b` and `c` as eight
+/// 32-byte words.
+pub const GROTH16_SEAL_LEN: usize = 256;
+
+/// Splits off the 4-byte selector and checks that it names the Groth16 verifier of this build.
+fn check_selector(seal: &[u8]) -> Result<&[u8], VerifyError> {
+    let (selector, seal) = seal.split_first_chunk::<4>().ok_or(VerifyError::MissingSelector)?;
+    let verifier_parameters = Groth16ReceiptVerifierParameters::default().digest();
+    if selector[..] != verifier_parameters.as_bytes()[..4] {
+        return Err(VerifyError::UnknownSelector(*selector));
+    }
+    Ok(seal)
+}
+
+/// Verifies the Groth16 `seal`, selector already removed, over `journal` for `image_id`.
+fn verify_groth16(seal: &[u8], journal: &[u8], image_id: Digest) -> Result<(), VerifyError> {
+    let verifier_parameters = Groth16ReceiptVerifierParameters::default().digest();
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+    let inner = Groth16Receipt::new(seal.to_vec(), MaybePruned::Value(claim), verifier_parameters);
+    Receipt::new(InnerReceipt::Groth16(inner), journal.to_vec())
+        .verify(image_id)
+        .map_err(|e| VerifyError::Receipt(e.to_string()))
+}
+
+/// Checks that `seal` would be verified under exactly the `expected` verifier parameters digest.
+///
+/// The selector is only the first four bytes of that digest, so on its own it does not rule out
+/// a verifier configured differently. Seals are verified with the Groth16 parameters of this
+/// build, so the check passes when the selector names them and their digest is `expected`.
+pub fn check_verifier_parameters(
+    seal: &[u8],
+    expected: impl Into<Digest>,
+) -> Result<(), VerifyError> {
+    check_selector(seal)?;
+    let expected = expected.into();
+    let actual = Groth16ReceiptVerifierParameters::default().digest();
+    if actual != expected {
+        return Err(VerifyError::VerifierParameters { expected, actual });
+    }
+    Ok(())
+}
+
+/// Verifies `seal` (selector-prefixed, as written to `seal.dat`) over `journal` for the guest
+/// `image_id` and returns the revealed data.
+///
+/// Only Groth16 seals are accepted; a fake dev-mode seal proves nothing and is rejected.
+pub fn verify_journal_and_seal(
+    seal: &[u8],
+    journal: &[u8],
+    image_id: impl Into<Digest>,
+) -> Result<RevealInfo, VerifyError> {
+    let seal = check_selector(seal)?;
+    verify_groth16(seal, journal, image_id.into())?;
+
+    let encoded = Vec::<u8>::abi_decode(journal).map_err(|_| VerifyError::NotAbiBytes)?;
+    RevealInfo::decode(&encoded).map_err(VerifyError::Journal)
+}
+
+/// Runs the checks the RISC Zero verifier router would run on `verify(seal, imageId,
+/// sha256(journal))`, in the same order, and returns the journal digest the contract would see.
+///
+/// The selector must name the Groth16 verifier, the seal must have the length the contract
+/// decodes, and the proof must verify against `image_id` and the digest of exactly these journal
+/// bytes. When the proof fails only because it covers the other encoding of the journal (the ABI
+/// wrapper added or stripped, as in `journal.dat` versus `journal_abi.dat`), the error says so.
+pub fn simulate_onchain(
+    seal: &[u8],
+    journal: &[u8],
+    image_id: impl Into<Digest>,
+) -> Result<Digest, VerifyError> {
+    let seal = check_selector(seal)?;
+    if seal.len() != GROTH16_SEAL_LEN {
+        return Err(VerifyError::SealLength(seal.len()));
+    }
+
+    let image_id = image_id.into();
+    let err = match verify_groth16(seal, journal, image_id) {
+        Ok(()) => return Ok(journal.digest()),
+        Err(err) => err,
+    };
+    let wrapped = journal.to_vec().abi_encode();
+    if verify_groth16(seal, &wrapped, image_id).is_ok() {
+        return Err(VerifyError::JournalDigestMismatch {
+            proven: "the ABI-encoded journal; submit journal.dat, not journal_abi.dat",
+        });
+    }
+    if let Ok(inner) = Vec::<u8>::abi_decode(journal) {
+        if verify_groth16(seal, &inner, image_id).is_ok() {
+            return Err(VerifyError::JournalDigestMismatch {
+                proven: "the journal without its ABI wrapper",
+            });
+        }
+    }
+    Err(err)
+}
@@ -12,6 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
+use risc0_build::GuestOptionsBuilder;
+
 fn main() {
-    risc0_build::embed_methods();
+    // Test builds of the guest record bad signatures instead of aborting on them.
+    let mut features = Vec::new();
+    if std::env::var_os("CARGO_FEATURE_TEST_SIGNATURES").is_some() {
+        features.push("test-signatures".to_string());
+    }
+    let options = GuestOptionsBuilder::default().features(features).build().unwrap();
+    risc0_build::embed_methods_with_options(HashMap::from([("k256-verify", options)]));
 }
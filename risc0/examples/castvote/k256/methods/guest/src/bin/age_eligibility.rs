@@ -0,0 +1,39 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{parse_ballot, AgeEligibility, AgeEligibilityInput, VoteParams};
+
+// Attests only the age predicate: the ballot signature is checked elsewhere (e.g. on chain), so
+// this method skips signature verification, by far the largest part of the other methods' cycles.
+// It commits the digest that signature must cover instead, tying the predicate to this ballot.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (message, poll_id, threshold): AgeEligibilityInput = env::read();
+    let params: VoteParams = env::read();
+
+    println!("START zkVM (age eligibility)...");
+
+    params.check_ballot_size(message.len() as u64)?;
+    let message = params.message_format.signed_text(&message)?;
+    let ballot = parse_ballot(&message)?;
+    let eligibility = AgeEligibility::new(&ballot, &message, poll_id, threshold, &params)?;
+    println!("Nullifier (hex): {}", eligibility.nullifier);
+    println!("Eligible (age >= {}): {}", threshold, eligibility.is_eligible);
+
+    env::commit_slice(eligibility.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
@@ -16,12 +16,17 @@ use core::{hint::black_box, ops::Add};
 
 use hex_literal::hex;
 use k256::{
+    ecdsa::{
+        signature::{hazmat::PrehashVerifier, Signer},
+        Signature, SigningKey, VerifyingKey,
+    },
     elliptic_curve::{
         bigint::{Encoding, U256},
         ops::{Invert, LinearCombination, Reduce},
     },
     FieldElement, ProjectivePoint, Scalar,
 };
+use sha2::{Digest, Sha256};
 use risc0_zkvm::guest::env;
 
 /// Basic function for benchmarking an operation.
@@ -104,8 +109,33 @@ fn benchmark_group() {
     });
 }
 
+/// Benchmark the steps of the single-ballot method's signature check, to show where its cycles
+/// go: decoding the voter's key, hashing the ballot and verifying the signature.
+fn benchmark_ecdsa() {
+    println!();
+    println!("ECDSA verification:");
+    let ballot = br#"{"age":30,"choice":"yes","id":7,"is_student":false}"#;
+    let key = SigningKey::from_bytes(&[7; 32].into()).unwrap();
+    let signature: Signature = key.sign(ballot);
+    let uncompressed = key.verifying_key().to_encoded_point(false);
+    let compressed = key.verifying_key().to_encoded_point(true);
+    let verifying_key = *key.verifying_key();
+    let prehash = Sha256::digest(ballot);
+
+    // The host passes the uncompressed key, so the guest never pays for decompression.
+    bench("decode_uncompressed_key", || {
+        VerifyingKey::from_sec1_bytes(uncompressed.as_bytes()).unwrap()
+    });
+    bench("decode_compressed_key", || {
+        VerifyingKey::from_sec1_bytes(compressed.as_bytes()).unwrap()
+    });
+    bench("sha256_ballot", || Sha256::digest(ballot));
+    bench("verify_prehash", || verifying_key.verify_prehash(&prehash, &signature).unwrap());
+}
+
 fn main() {
     benchmark_field();
     benchmark_scalar();
     benchmark_group();
+    benchmark_ecdsa();
 }
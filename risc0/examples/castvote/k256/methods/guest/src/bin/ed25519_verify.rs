@@ -0,0 +1,67 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risc0_zkvm::guest::env;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    decode_ed25519_public_key, decode_signature, verify_signed_ballot, RevealInfo, SignedDigest,
+    VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (base64_signature_str, message, poll_id, base64_public_key_str): (String, String, u64, String) =
+        env::read();
+    let params: VoteParams = env::read();
+    // The Ethereum wallet formats are secp256k1 conventions.
+    let format = params.message_format;
+    assert!(!format.needs_secp256k1(), "{:?} ballots need a secp256k1 key", format);
+
+    // Check both encodings before doing any work, so bad input fails with a descriptive error.
+    let signature_array = decode_signature(&base64_signature_str)?;
+    let public_key_array = decode_ed25519_public_key(&base64_public_key_str)?;
+
+    println!("START zkVM (Ed25519)...");
+
+    // 1. Import the voter's verifying key supplied by the host. Which voter signed is disclosed
+    // through the key hash in the journal.
+    let verifying_key = VerifyingKey::from_bytes(&public_key_array)?;
+
+    // 2. Import the 64-byte signature
+    println!("\n--- Importing and Verifying ---");
+    let signature = Signature::from_bytes(&signature_array);
+
+    // 3. Verify the signature; as with the ECDSA methods, it covers the SHA-256 digest.
+    let (ballot, ()) = verify_signed_ballot(&params, &message, |_, digest| {
+        let SignedDigest::Sha256(hashed_message) = digest else {
+            unreachable!("only the wallet formats are prehashed");
+        };
+        if let Err(e) = verifying_key.verify(&hashed_message, &signature) {
+            panic!("signature is not valid {:?}: {}", signature, e);
+        }
+        Ok(())
+    })?;
+    println!("✅ Signature is valid!");
+
+    // Calculate Nullifier = hash (salt, user_id, poll_id)
+    let reveal_data = RevealInfo::new(&ballot, poll_id, verifying_key.as_bytes(), &params)?;
+    println!("Nullifier (hex): {}", reveal_data.nullifier);
+
+    // Same journal layout as the ECDSA methods, so consumers need not know the signature scheme.
+    let encoded: Vec<u8> = reveal_data.encode_with(params.journal_codec);
+
+    env::commit_slice(encoded.abi_encode().as_slice());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use k256::ecdsa::VerifyingKey;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    authorized_set_bytes, canonical_k256_key, count_cosigners, decode_k256_signature,
+    decode_k256_verifying_key, verify_signed_ballot, CosignInput, CosignedReveal, RevealInfo,
+    VoteParams,
+};
+
+/// Decodes a Base64 SEC1 key and re-encodes it compressed, so one key always compares equal.
+fn canonical_key(base64_public_key: &str) -> Result<(VerifyingKey, Vec<u8>), Box<dyn std::error::Error>> {
+    let verifying_key = decode_k256_verifying_key(base64_public_key)?;
+    let canonical = canonical_k256_key(&verifying_key);
+    Ok((verifying_key, canonical))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input: CosignInput = env::read();
+    let params: VoteParams = env::read();
+
+    // Check every encoding before verifying anything, so bad input fails with a descriptive error.
+    let mut authorized = Vec::with_capacity(input.authorized_keys.len());
+    for (index, key) in input.authorized_keys.iter().enumerate() {
+        let (_, canonical) = canonical_key(key).map_err(|e| format!("authorized key {}: {}", index, e))?;
+        authorized.push(canonical);
+    }
+    let mut cosigners = Vec::with_capacity(input.signatures.len());
+    for (index, (key, signature)) in input.signatures.iter().enumerate() {
+        let (verifying_key, canonical) = canonical_key(key).map_err(|e| format!("co-signer {}: {}", index, e))?;
+        let signature = decode_k256_signature(signature).map_err(|e| format!("co-signer {}: {}", index, e))?;
+        cosigners.push((verifying_key, canonical, signature));
+    }
+
+    println!("START zkVM ({} co-signatures, threshold {})...", cosigners.len(), input.threshold);
+
+    // Every co-signer signs the same text, derived as the host derived it. An invalid signature
+    // is not fatal on its own; it just does not count towards the threshold.
+    let (ballot, valid_signers) = verify_signed_ballot(&params, &input.ballot, |_, digest| {
+        let mut valid_signers = Vec::with_capacity(cosigners.len());
+        for (index, (verifying_key, canonical, signature)) in cosigners.into_iter().enumerate() {
+            match digest.verify_k256(&verifying_key, &signature) {
+                Ok(()) => valid_signers.push(canonical),
+                Err(e) => println!("Co-signature {} is not valid: {}", index, e),
+            }
+        }
+        Ok(valid_signers)
+    })?;
+
+    let valid_signatures = count_cosigners(&authorized, &valid_signers, input.threshold)?;
+    println!("✅ {} of {} required co-signatures are valid", valid_signatures, input.threshold);
+
+    // The journal names the authorized set rather than any single signer.
+    let reveal = RevealInfo::new(&ballot, input.poll_id, &authorized_set_bytes(&authorized), &params)?;
+    println!("Nullifier (hex): {}", reveal.nullifier);
+    let cosigned = CosignedReveal { reveal, valid_signatures, threshold: input.threshold };
+
+    env::commit_slice(cosigned.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
@@ -13,67 +13,38 @@
 // limitations under the License.
 
 use risc0_zkvm::guest::env;
-use k256::{
-    ecdsa::{signature::Verifier, Signature, VerifyingKey},
-    EncodedPoint,
-};
-use k256::elliptic_curve::sec1::FromEncodedPoint; // Trait for from_encoded_point
 
 use k256::pkcs8::DecodePublicKey;
 use k256::pkcs8::EncodePublicKey;
-use sha2::{Sha256, Digest};
-use base64::{engine::general_purpose::STANDARD, Engine as _};
-use base64::{engine::general_purpose};
 use alloy_sol_types::SolValue;
 
-use serde::{Serialize, Deserialize};
-use bincode;
-use serde_json::{Value};
-#[derive(Serialize, Deserialize, Debug)]
-
-struct revealInfo {
-    nullifier: String,
-    age: u32,
-    is_student: bool,
-    poll_id: u64,
-}
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, verify_signed_ballot,
+    Ballot, RevealInfo, VoteParams,
+};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (base64_signature_str, message, poll_id) : (String, String, u64) = env::read();
+    let (base64_signature_str, message, poll_id, base64_public_key_str) : (String, String, u64, String) = env::read();
+    let params: VoteParams = env::read();
 
-    println!("START zkVM...");
-
-    // 1. Import Verifying Key from String (for verification by another party)
-    let exported_public_key_string= "BLZgb3PHEJ6B7Xta+jR4CEn1g3NluqLxNNRlrDfhPTbMATkwv04TOAJJMWuSlrtOfuO9SQNIdGeLlL+ppflRHN4=";
-    let imported_public_key_bytes = general_purpose::STANDARD.decode(&exported_public_key_string)?;
-    let verifying_key = VerifyingKey::from_encoded_point(
-        &EncodedPoint::from_bytes(&imported_public_key_bytes)?
-    )?;
-    // println!("Imported Verifying Key (from string): {:?}", verifying_key.to_encoded_point(false).as_bytes());
+    // Check both encodings before doing any work, so bad input fails with a descriptive error.
+    // Only the low-S form is accepted, so the signature is not malleable.
+    let signature = decode_k256_signature(&base64_signature_str)?;
+    let verifying_key = decode_k256_verifying_key(&base64_public_key_str)?;
 
-    // 2. Hash the message (must be the exact same hashing algorithm as in Go)
-    let mut hasher = Sha256::new();
-    hasher.update(message.as_bytes());
-    let hashed_message = hasher.finalize();
-    println!("Hashed Message (hex): {}", hex::encode(&hashed_message));
+    println!("START zkVM...");
 
-    let v: Value = serde_json::from_str(&message)?;
-    println!("Person id {} name {} at age {} poll_id {}", v["id"], v["name"], v["age"], poll_id);
+    // 1. The voter's verifying key (SEC1, Base64 encoded) was imported and checked to be on the
+    //    curve above
 
-    // 3. Signature from Go (use the hex string from Go's output, which is ASN.1 DER)
-    // let signature_base64 = "Hpev7tBpDDjBREQDp0yNwf/crqH2Pr1NVVm3p/KnjXRmFEneyKdTE5BcaCsNF4cpHVE7CNYgC++MoeOxqrNZbA=="
+    // 2. The signature (r || s, Base64 encoded) was decoded and checked to be low-S above
     println!("\n--- Importing and Verifying ---");
-    let imported_signature_bytes = general_purpose::STANDARD.decode(&base64_signature_str)?;
-
-    let imported_signature_array: [u8; 64] = imported_signature_bytes.as_slice().try_into()
-    .map_err(|_| "Failed to convert signature bytes to fixed-size array")?; // Handle potential length mismatch
-
-    let signature = Signature::from_bytes((&imported_signature_array).into())?;
-    println!("Imported Signature (from string): {}", base64_signature_str);
 
-
-    // 4. Verify the signature
-    let verification_result = verifying_key.verify(&hashed_message, &signature);
+    // 3. Hash the signed text, derived as the host derived it: the canonical ballot unless `Raw`.
+    // 4. Verify the signature. Wallet `personal_sign` signatures cover the EIP-191 digest as is.
+    let (ballot, verification_result) = verify_signed_ballot(&params, &message, |_, digest| {
+        Ok(digest.verify_k256(&verifying_key, &signature))
+    })?;
 
     let mut rs = vec![0; 1];
     
@@ -87,29 +58,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("❌ Signature verification failed: {}", e);
         },
     }
+    // Test builds may be asked to record a bad signature in the journal instead of aborting.
+    if params.allow_invalid_signature {
+        let public_key = canonical_k256_key(&verifying_key);
+        return commit_unchecked(&ballot, poll_id, &public_key, &params, rs[0] == 1);
+    }
     assert_eq!(rs[0], 1, "{}", format!("signature is not valid {:?}", signature));
     
     // Calculate Nullifier = hash (salt, user_id, poll_id)
-    let salt = "secret";
-    let nullifier_str = format!("{}{}{}", salt, v["id"], poll_id);
-    let mut nullifier_hasher = Sha256::new();
-    nullifier_hasher.update(nullifier_str.as_bytes());
-    let nullifier = nullifier_hasher.finalize();
-    println!("Nullifier (hex): {}", hex::encode(&nullifier));
+    // The key hash is taken over the compressed encoding, whichever form the host passed.
+    let public_key = canonical_k256_key(&verifying_key);
+    let reveal_data = RevealInfo::new(&ballot, poll_id, &public_key, &params)?;
+    // Only the nullifier is printed: debug-formatting the whole reveal costs cycles on every
+    // proof, and the journal carries it anyway.
+    println!("Nullifier (hex): {}", reveal_data.nullifier);
 
-    // Create an instance of the struct
-    let revealData = revealInfo {
-        nullifier: hex::encode(&nullifier),
-        age: v["age"].as_u64().unwrap() as u32,
-        is_student: v["is_student"].as_bool().unwrap() as bool,
-        poll_id: poll_id,
-        // name: v["name"].as_str().unwrap().to_string(),
-    };
-    
-    println!("revealData: {:?}", revealData);
-    // Convert to Vec<u8> using bincode
-    let encoded: Vec<u8> = bincode::serialize(&revealData).expect("Serialization failed");
+    let encoded: Vec<u8> = reveal_data.encode_with(params.journal_codec);
 
     env::commit_slice(encoded.abi_encode().as_slice());
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Commits an [UncheckedReveal](castvote_core::UncheckedReveal) recording whether the signature
+/// verified. Only built into test guests, via the `test-signatures` feature.
+#[cfg(feature = "test-signatures")]
+fn commit_unchecked(
+    ballot: &Ballot,
+    poll_id: u64,
+    public_key: &[u8],
+    params: &VoteParams,
+    is_signature_valid: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let reveal = RevealInfo::new(ballot, poll_id, public_key, params)?;
+    let unchecked = castvote_core::UncheckedReveal { is_signature_valid, reveal };
+    env::commit_slice(unchecked.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
+
+/// Production guests keep aborting on a bad signature, whatever the input asks for.
+#[cfg(not(feature = "test-signatures"))]
+fn commit_unchecked(
+    _ballot: &Ballot,
+    _poll_id: u64,
+    _public_key: &[u8],
+    _params: &VoteParams,
+    _is_signature_valid: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("allow_invalid_signature needs a guest built with the test-signatures feature".into())
+}
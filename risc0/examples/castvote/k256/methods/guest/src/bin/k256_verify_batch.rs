@@ -0,0 +1,72 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use risc0_zkvm::guest::env;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, encode_reveals_with,
+    verify_signed_ballot, RevealInfo, VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let ballots: Vec<(String, String, u64, String)> = env::read();
+    let params: VoteParams = env::read();
+
+    // Check every encoding before verifying anything, so one bad entry fails the batch up front.
+    let mut decoded = Vec::with_capacity(ballots.len());
+    for (index, (base64_signature_str, _, _, base64_public_key_str)) in ballots.iter().enumerate() {
+        // Only the low-S form is accepted, so no signature in the batch is malleable.
+        let signature = decode_k256_signature(base64_signature_str)
+            .map_err(|e| format!("ballot {}: {}", index, e))?;
+        let verifying_key = decode_k256_verifying_key(base64_public_key_str)
+            .map_err(|e| format!("ballot {}: {}", index, e))?;
+        decoded.push((signature, verifying_key));
+    }
+
+    println!("START zkVM (batch of {})...", ballots.len());
+
+    let mut reveals = Vec::with_capacity(ballots.len());
+    // A voter's nullifier for a poll is the same whatever ballot they sign, so a repeat is a
+    // double vote; committing it would leave the tally to catch it.
+    let mut seen = BTreeSet::new();
+    for (index, ((_, message, poll_id, _), (signature, verifying_key))) in ballots.iter().zip(&decoded).enumerate() {
+        // Each ballot may come from a different voter, so each carries its own key.
+        // A single bad signature aborts the whole batch: the receipt vouches for every entry.
+        let (ballot, ()) = verify_signed_ballot(&params, message, |_, digest| {
+            if let Err(e) = digest.verify_k256(verifying_key, signature) {
+                panic!("signature of ballot {} is not valid: {}", index, e);
+            }
+            Ok(())
+        })?;
+
+        let public_key = canonical_k256_key(&verifying_key);
+        let reveal_data = RevealInfo::new(&ballot, *poll_id, &public_key, &params)?;
+        if !seen.insert((reveal_data.poll_id, reveal_data.nullifier.clone())) {
+            return Err(format!(
+                "ballot {} repeats the nullifier of an earlier ballot for poll {}",
+                index, poll_id
+            )
+            .into());
+        }
+        println!("Ballot {} nullifier (hex): {}", index, reveal_data.nullifier);
+        reveals.push(reveal_data);
+    }
+
+    let encoded: Vec<u8> = encode_reveals_with(&reveals, params.journal_codec);
+    env::commit_slice(encoded.abi_encode().as_slice());
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use k256::PublicKey;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, verify_signed_ballot,
+    EncryptedReveal, RevealInfo, VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (base64_signature_str, message, poll_id, base64_public_key_str) : (String, String, u64, String) = env::read();
+    let params: VoteParams = env::read();
+
+    // Check both encodings before doing any work, so bad input fails with a descriptive error.
+    // Only the low-S form is accepted, so the signature is not malleable.
+    let signature = decode_k256_signature(&base64_signature_str)?;
+    let verifying_key = decode_k256_verifying_key(&base64_public_key_str)?;
+
+    println!("START zkVM (encrypted ballot)...");
+
+    // The signature covers the ballot text, ciphertext included, derived as the host derived it.
+    let (ballot, ()) = verify_signed_ballot(&params, &message, |_, digest| {
+        if let Err(e) = digest.verify_k256(&verifying_key, &signature) {
+            panic!("signature is not valid: {}", e);
+        }
+        Ok(())
+    })?;
+
+    // A plaintext choice next to the ciphertext would defeat the point of encrypting it.
+    if ballot.choice.is_some() {
+        return Err("an encrypted ballot must not carry a plaintext `choice`".into());
+    }
+    let encrypted_choice = ballot.encrypted_choice.clone().ok_or("ballot has no `encrypted_choice`")?;
+    let parts = encrypted_choice.decode()?;
+    // The authority could not decrypt a ciphertext whose ephemeral key is not on the curve.
+    PublicKey::from_sec1_bytes(&parts.ephemeral_key)
+        .map_err(|_| "ephemeral key is not a secp256k1 point")?;
+
+    let public_key = canonical_k256_key(&verifying_key);
+    let reveal = RevealInfo::new(&ballot, poll_id, &public_key, &params)?;
+    println!("Nullifier (hex): {}", reveal.nullifier);
+    let encrypted = EncryptedReveal { reveal, encrypted_choice };
+
+    env::commit_slice(encrypted.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use k256::PublicKey;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, encrypt_vote, one_hot,
+    parse_randomness, verify_signed_ballot, ElGamalInput, HomomorphicReveal, RevealInfo,
+    VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let ((base64_signature_str, message, poll_id, base64_public_key_str), elgamal): ((String, String, u64, String), ElGamalInput) = env::read();
+    let params: VoteParams = env::read();
+
+    // Check every encoding before doing any work, so bad input fails with a descriptive error.
+    // Only the low-S form is accepted, so the signature is not malleable.
+    let signature = decode_k256_signature(&base64_signature_str)?;
+    let verifying_key = decode_k256_verifying_key(&base64_public_key_str)?;
+    let election_key = PublicKey::from_sec1_bytes(&elgamal.election_key)
+        .map_err(|_| "election key is not a secp256k1 point")?;
+    let randomness = elgamal.randomness.iter().map(parse_randomness).collect::<Result<Vec<_>, _>>()?;
+
+    println!("START zkVM (homomorphic ballot)...");
+
+    let (ballot, ()) = verify_signed_ballot(&params, &message, |_, digest| {
+        if let Err(e) = digest.verify_k256(&verifying_key, &signature) {
+            panic!("signature is not valid: {}", e);
+        }
+        Ok(())
+    })?;
+
+    // Encrypting the validated selection here is what proves the ciphertexts hold a valid vote:
+    // one entry per option, each 0 or 1, exactly one 1 for single-choice polls.
+    let public_key = canonical_k256_key(&verifying_key);
+    let mut reveal = RevealInfo::new(&ballot, poll_id, &public_key, &params)?;
+    if reveal.selection.is_empty() {
+        return Err("an encrypted vote needs a `choice`".into());
+    }
+    let vote = one_hot(&reveal.selection, params.ballot_type, &params.options)?;
+    let ciphertexts = encrypt_vote(&election_key, &vote, &randomness)?;
+    // Only the ciphertexts may reveal the vote, and only to the trustees together.
+    reveal.selection.clear();
+    println!("Nullifier (hex): {}", reveal.nullifier);
+
+    let committed = HomomorphicReveal { reveal, election_key: elgamal.election_key, ciphertexts };
+    env::commit_slice(committed.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risc0_zkvm::guest::env;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, encode_reveals_with,
+    verify_signed_ballot, RevealInfo, VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (base64_signature_str, message, poll_ids, base64_public_key_str): (String, String, Vec<u64>, String) = env::read();
+    let params: VoteParams = env::read();
+
+    // Check both encodings before doing any work, so bad input fails with a descriptive error.
+    // Only the low-S form is accepted, so the signature is not malleable.
+    let signature = decode_k256_signature(&base64_signature_str)?;
+    let verifying_key = decode_k256_verifying_key(&base64_public_key_str)?;
+
+    println!("START zkVM ({} polls)...", poll_ids.len());
+
+    // One verification covers every poll: the per-poll choices are all part of the signed ballot.
+    let (ballot, ()) = verify_signed_ballot(&params, &message, |_, digest| {
+        if let Err(e) = digest.verify_k256(&verifying_key, &signature) {
+            panic!("signature is not valid: {}", e);
+        }
+        Ok(())
+    })?;
+
+    let public_key = canonical_k256_key(&verifying_key);
+    let reveals = RevealInfo::for_polls(&ballot, &poll_ids, &public_key, &params)?;
+    for reveal in &reveals {
+        println!("Poll {} nullifier (hex): {}", reveal.poll_id, reveal.nullifier);
+    }
+
+    env::commit_slice(encode_reveals_with(&reveals, params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
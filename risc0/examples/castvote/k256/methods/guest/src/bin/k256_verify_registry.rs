@@ -0,0 +1,52 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    authorized_set_bytes, canonical_k256_key, decode_k256_signature, decode_k256_verifying_key,
+    find_signer, verify_signed_ballot, RegistryInput, RevealInfo, VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input: RegistryInput = env::read();
+    let params: VoteParams = env::read();
+
+    // Check every encoding before verifying anything, so bad input fails with a descriptive error.
+    let signature = decode_k256_signature(&input.signature)?;
+    let mut verifying_keys = Vec::with_capacity(input.authorized_keys.len());
+    for (index, key) in input.authorized_keys.iter().enumerate() {
+        let verifying_key = decode_k256_verifying_key(key).map_err(|e| format!("authorized key {}: {}", index, e))?;
+        verifying_keys.push(verifying_key);
+    }
+
+    println!("START zkVM (registry of {} keys)...", verifying_keys.len());
+
+    // Any one authorized key will do; none verifying rejects the ballot. Which one it was is not
+    // printed either: prover logs would otherwise tell the signer apart.
+    let (ballot, _) = verify_signed_ballot(&params, &input.ballot, |_, digest| {
+        Ok(find_signer(&verifying_keys, |key| digest.verify_k256(key, &signature).is_ok())?)
+    })?;
+    println!("✅ Signature is valid for an authorized key");
+
+    // The journal names the registry rather than the signer, so which voter signed stays private.
+    let authorized: Vec<Vec<u8>> = verifying_keys.iter().map(canonical_k256_key).collect();
+    let reveal = RevealInfo::new(&ballot, input.poll_id, &authorized_set_bytes(&authorized), &params)?;
+    println!("Nullifier (hex): {}", reveal.nullifier);
+
+    env::commit_slice(reveal.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
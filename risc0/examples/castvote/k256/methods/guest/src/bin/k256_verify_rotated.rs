@@ -0,0 +1,70 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use risc0_zkvm::guest::env;
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_k256_key, decode_k256_signature, decode_k256_verifying_key, key_schedule_bytes,
+    select_epoch, verify_signed_ballot, EpochReveal, RevealInfo, RotatedKeysInput, RotationError,
+    VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input: RotatedKeysInput = env::read();
+    let params: VoteParams = env::read();
+
+    // Check every encoding before verifying anything, so bad input fails with a descriptive error.
+    let signature = decode_k256_signature(&input.signature)?;
+    let mut verifying_keys = Vec::with_capacity(input.epochs.len());
+    for (index, epoch) in input.epochs.iter().enumerate() {
+        let verifying_key = decode_k256_verifying_key(&epoch.public_key).map_err(|e| format!("key epoch {}: {}", index, e))?;
+        verifying_keys.push(verifying_key);
+    }
+
+    println!("START zkVM (schedule of {} key epochs)...", verifying_keys.len());
+
+    // The signing time is part of the signed ballot, so the prover cannot pick another epoch.
+    let (ballot, (index, signed_at)) =
+        verify_signed_ballot(&params, &input.ballot, |ballot, digest| {
+            let signed_at = ballot.signed_at.ok_or(RotationError::NoSigningTime)?;
+            let index = select_epoch(&input.epochs, signed_at)?;
+            digest.verify_k256(&verifying_keys[index], &signature).map_err(|e| {
+                format!("signature does not verify against key epoch {}: {}", index, e)
+            })?;
+            Ok((index, signed_at))
+        })?;
+    println!("✅ Signature is valid for key epoch {} (signed at {})", index, signed_at);
+
+    // The journal names the whole schedule, and which of its epochs verified.
+    let schedule: Vec<_> = input
+        .epochs
+        .iter()
+        .zip(&verifying_keys)
+        .map(|(epoch, key)| (canonical_k256_key(key), epoch.valid_from, epoch.valid_until))
+        .collect();
+    let reveal = RevealInfo::new(&ballot, input.poll_id, &key_schedule_bytes(&schedule), &params)?;
+    println!("Nullifier (hex): {}", reveal.nullifier);
+
+    let epoch = &input.epochs[index];
+    let epoch_reveal = EpochReveal {
+        reveal,
+        epoch: index as u32,
+        valid_from: epoch.valid_from,
+        valid_until: epoch.valid_until,
+    };
+    env::commit_slice(epoch_reveal.encode_with(params.journal_codec).abi_encode().as_slice());
+    Ok(())
+}
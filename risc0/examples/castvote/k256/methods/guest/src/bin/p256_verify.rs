@@ -0,0 +1,68 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risc0_zkvm::guest::env;
+use p256::{
+    ecdsa::{signature::Verifier, Signature},
+};
+use alloy_sol_types::SolValue;
+
+use castvote_core::{
+    canonical_p256_key, decode_p256_verifying_key, decode_signature, verify_signed_ballot,
+    RevealInfo, SignedDigest, VoteParams,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (base64_signature_str, message, poll_id, base64_public_key_str) : (String, String, u64, String) = env::read();
+    let params: VoteParams = env::read();
+    // The Ethereum wallet formats are secp256k1 conventions.
+    let format = params.message_format;
+    assert!(!format.needs_secp256k1(), "{:?} ballots need a secp256k1 key", format);
+
+    // Check both encodings before doing any work, so bad input fails with a descriptive error.
+    let imported_signature_array = decode_signature(&base64_signature_str)?;
+    let verifying_key = decode_p256_verifying_key(&base64_public_key_str)?;
+
+    println!("START zkVM (P-256)...");
+
+    // 1. The voter's P-256 verifying key (SEC1, Base64 encoded) was imported and checked to be on
+    //    the curve above
+
+    // 2. Import the fixed-size (r || s) signature
+    println!("\n--- Importing and Verifying ---");
+    let signature = Signature::from_bytes((&imported_signature_array).into())?;
+
+    // 3. Verify the signature over the hash of the signed text, derived as the host derived it
+    let (ballot, ()) = verify_signed_ballot(&params, &message, |_, digest| {
+        let SignedDigest::Sha256(hashed_message) = digest else {
+            unreachable!("only the wallet formats are prehashed");
+        };
+        if let Err(e) = verifying_key.verify(&hashed_message, &signature) {
+            panic!("signature is not valid {:?}: {}", signature, e);
+        }
+        Ok(())
+    })?;
+    println!("✅ Signature is valid!");
+
+    // Calculate Nullifier = hash (salt, user_id, poll_id)
+    let public_key = canonical_p256_key(&verifying_key);
+    let reveal_data = RevealInfo::new(&ballot, poll_id, &public_key, &params)?;
+    println!("Nullifier (hex): {}", reveal_data.nullifier);
+
+    // The journal layout is identical to the k256 method so downstream decoding is curve-agnostic.
+    let encoded: Vec<u8> = reveal_data.encode_with(params.journal_codec);
+
+    env::commit_slice(encoded.abi_encode().as_slice());
+    Ok(())
+}
@@ -0,0 +1,298 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auditing a directory of proofs: verifying every proof in it, decoding what each commits and
+//! finding nullifiers used by more than one proof.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use castvote_core::{AgeEligibility, RevealInfo};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    ReceiptClaim, VerifierContext,
+};
+use serde_json::json;
+
+use crate::{
+    journal::{
+        decode_method_journal, read_hex_file, read_image_id, read_journal_file, MethodJournal,
+    },
+    output::{atomic_write, write_json_file},
+    seal::FAKE_SELECTOR,
+};
+
+/// What the audit concluded about one proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofStatus {
+    /// The proof verifies and none of its nullifiers was seen before.
+    Valid,
+    /// The proof is missing a file, does not verify or does not decode.
+    Invalid(String),
+    /// The proof verifies, but an earlier proof already used one of its nullifiers.
+    Duplicate {
+        /// The proof that used the nullifier first.
+        first: String,
+    },
+}
+
+impl ProofStatus {
+    /// Short name of the status, as written to the reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofStatus::Valid => "valid",
+            ProofStatus::Invalid(_) => "invalid",
+            ProofStatus::Duplicate { .. } => "duplicate",
+        }
+    }
+}
+
+impl fmt::Display for ProofStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofStatus::Valid => write!(f, "valid"),
+            ProofStatus::Invalid(e) => write!(f, "invalid: {e}"),
+            ProofStatus::Duplicate { first } => {
+                write!(f, "duplicate nullifier, first used by {first}")
+            }
+        }
+    }
+}
+
+/// One proof directory and what the audit found in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedProof {
+    /// The proof directory, relative to the audited directory.
+    pub proof: String,
+    pub status: ProofStatus,
+    /// The reveal entries the journal commits; empty if the proof is invalid.
+    pub reveals: Vec<RevealInfo>,
+    /// What an age-only proof commits instead of reveal entries.
+    pub eligibility: Option<AgeEligibility>,
+}
+
+impl AuditedProof {
+    /// `(poll_id, nullifier)` of every entry the journal commits.
+    pub fn nullifiers(&self) -> Vec<(u64, &str)> {
+        let reveals = self.reveals.iter().map(|reveal| (reveal.poll_id, reveal.nullifier.as_str()));
+        let eligibility = self.eligibility.iter().map(|e| (e.poll_id, e.nullifier.as_str()));
+        reveals.chain(eligibility).collect()
+    }
+}
+
+/// The outcome of auditing every proof under a directory.
+#[derive(Debug, Default)]
+pub struct Audit {
+    proofs: Vec<AuditedProof>,
+    seen: HashMap<(u64, String), String>,
+}
+
+impl Audit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifies and adds the proof in `dir`, named `proof` in the reports. A proof that fails is
+    /// recorded as invalid rather than returned as an error, so one bad proof does not stop the
+    /// audit.
+    pub fn add_proof_dir(&mut self, proof: &str, dir: &Path) {
+        match verify_proof_dir(dir) {
+            Ok(journal) => self.add(proof, journal),
+            Err(e) => self.push(proof, ProofStatus::Invalid(format!("{e:#}")), None),
+        }
+    }
+
+    /// Adds a verified proof. The first proof seen for a nullifier in a poll wins; later ones are
+    /// recorded as duplicates.
+    pub fn add(&mut self, proof: &str, journal: MethodJournal) {
+        let nullifiers = journal.nullifiers();
+        let first = nullifiers.iter().find_map(|&(poll_id, nullifier)| {
+            self.seen.get(&(poll_id, nullifier.to_string())).cloned()
+        });
+        let status = match first {
+            Some(first) => ProofStatus::Duplicate { first },
+            None => {
+                for (poll_id, nullifier) in nullifiers {
+                    self.seen.insert((poll_id, nullifier.to_string()), proof.to_string());
+                }
+                ProofStatus::Valid
+            }
+        };
+        self.push(proof, status, Some(journal));
+    }
+
+    fn push(&mut self, proof: &str, status: ProofStatus, journal: Option<MethodJournal>) {
+        let (reveals, eligibility) = match journal {
+            Some(MethodJournal::Reveals(reveals)) => (reveals, None),
+            Some(MethodJournal::Eligibility(eligibility)) => (Vec::new(), Some(eligibility)),
+            None => (Vec::new(), None),
+        };
+        self.proofs.push(AuditedProof { proof: proof.to_string(), status, reveals, eligibility });
+    }
+
+    /// Every audited proof, in the order it was added.
+    pub fn proofs(&self) -> &[AuditedProof] {
+        &self.proofs
+    }
+
+    /// How many proofs were found valid.
+    pub fn valid(&self) -> usize {
+        self.count(|status| matches!(status, ProofStatus::Valid))
+    }
+
+    /// How many proofs were found invalid.
+    pub fn invalid(&self) -> usize {
+        self.count(|status| matches!(status, ProofStatus::Invalid(_)))
+    }
+
+    /// How many proofs reuse a nullifier of an earlier proof.
+    pub fn duplicates(&self) -> usize {
+        self.count(|status| matches!(status, ProofStatus::Duplicate { .. }))
+    }
+
+    fn count(&self, matches: impl Fn(&ProofStatus) -> bool) -> usize {
+        self.proofs.iter().filter(|proof| matches(&proof.status)).count()
+    }
+
+    /// Writes the `proof,status,poll_ids,nullifiers,detail` rows, poll IDs and nullifiers
+    /// separated by `;` for multi-entry journals.
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["proof", "status", "poll_ids", "nullifiers", "detail"])?;
+        for proof in &self.proofs {
+            let (poll_ids, nullifiers): (Vec<_>, Vec<_>) = proof
+                .nullifiers()
+                .into_iter()
+                .map(|(poll_id, nullifier)| (poll_id.to_string(), nullifier))
+                .unzip();
+            writer.write_record([
+                proof.proof.clone(),
+                proof.status.name().to_string(),
+                poll_ids.join(";"),
+                nullifiers.join(";"),
+                detail(&proof.status),
+            ])?;
+        }
+        atomic_write(path, writer.into_inner()?)
+    }
+
+    /// Writes the summary counts and every proof as JSON.
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let proofs: Vec<_> = self
+            .proofs
+            .iter()
+            .map(|proof| {
+                json!({
+                    "proof": proof.proof,
+                    "status": proof.status.name(),
+                    "detail": detail(&proof.status),
+                    "reveals": proof.reveals,
+                    "eligibility": proof.eligibility,
+                })
+            })
+            .collect();
+        let report = json!({
+            "valid": self.valid(),
+            "invalid": self.invalid(),
+            "duplicate": self.duplicates(),
+            "proofs": proofs,
+        });
+        write_json_file(path, &report)
+    }
+}
+
+fn detail(status: &ProofStatus) -> String {
+    match status {
+        ProofStatus::Valid => String::new(),
+        ProofStatus::Invalid(e) => e.clone(),
+        ProofStatus::Duplicate { first } => format!("nullifier first used by {first}"),
+    }
+}
+
+/// Verifies the `seal.dat`, `journal.dat` and `image_id.dat` the prover wrote to `dir` and
+/// decodes the journal as the method with that image ID committed it (see
+/// [decode_method_journal]).
+///
+/// Groth16 seals go through the same checks as `simulate-onchain`. Seals of dev-mode receipts,
+/// which only carry the claim digest, are accepted only when dev mode is enabled
+/// (`RISC0_DEV_MODE=1`), and then only if the digest matches the journal and image ID.
+pub fn verify_proof_dir(dir: &Path) -> Result<MethodJournal> {
+    let seal = read_hex_file(dir.join("seal.dat"))?;
+    let journal = read_journal_file(dir.join("journal.dat"))?;
+    let image_id_path = dir.join("image_id.dat");
+    let image_id = read_image_id(&image_id_path.to_string_lossy())?;
+    verify_seal(&seal, &journal, image_id)?;
+    decode_method_journal(image_id, &journal)
+}
+
+/// Verifies `seal` against `journal` and `image_id`, accepting dev-mode seals only as
+/// [verify_proof_dir] describes.
+pub fn verify_seal(seal: &[u8], journal: &[u8], image_id: Digest) -> Result<()> {
+    match seal.split_first_chunk::<4>() {
+        Some((&FAKE_SELECTOR, digest)) => {
+            if !VerifierContext::default().dev_mode() {
+                bail!("seal is from a dev-mode receipt, and dev mode is not enabled");
+            }
+            let claim = ReceiptClaim::ok(image_id, journal.to_vec()).digest();
+            if digest != claim.as_bytes() {
+                bail!("seal does not match the journal and image ID");
+            }
+        }
+        _ => {
+            castvote_core::simulate_onchain(seal, journal, image_id)
+                .map_err(|e| anyhow!("{} failed: {e}", e.failed_check()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Audits every proof directory under `dir`: each directory, at any depth, holding a
+/// `journal.dat`. Directories are visited in sorted path order so the choice of which duplicate
+/// counts as the first is reproducible.
+pub fn audit_dir(dir: &Path) -> Result<Audit> {
+    let mut proof_dirs = Vec::new();
+    find_proof_dirs(dir, &mut proof_dirs)?;
+    proof_dirs.sort();
+
+    let mut audit = Audit::new();
+    for proof_dir in &proof_dirs {
+        let proof = proof_dir.strip_prefix(dir).unwrap_or(proof_dir).display().to_string();
+        audit.add_proof_dir(&proof, proof_dir);
+    }
+    Ok(audit)
+}
+
+/// Adds `dir` and every directory below it that holds a `journal.dat` to `found`, unsorted.
+pub fn find_proof_dirs(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.join("journal.dat").is_file() {
+        found.push(dir.to_path_buf());
+    }
+    let entries = fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))?;
+    for entry in entries {
+        let path = entry.with_context(|| format!("reading {}", dir.display()))?.path();
+        if path.is_dir() {
+            find_proof_dirs(&path, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Path of the JSON report written next to the CSV report `out`.
+pub fn json_path(out: &Path) -> PathBuf {
+    out.with_extension("json")
+}
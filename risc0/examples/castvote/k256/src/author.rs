@@ -0,0 +1,136 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Proofs attributed to the service that generated them, for delegated proving.
+//!
+//! The guest commits the hash of the service's public key (see [VoteParams::author_key]), and
+//! after proving the service signs the journal with the matching private key. Anyone holding
+//! the journal, the public key and the signature can then tell who produced the proof. This
+//! says nothing about the voter: eligibility still rests on the voter's own signature, checked
+//! in the guest.
+
+use std::fmt;
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, decode_k256_verifying_key, voter_key_hash, EncodingError, RevealInfo,
+    VoteParams,
+};
+use k256::ecdsa::{
+    signature::{Signer as _, Verifier as _},
+    Signature, SigningKey,
+};
+
+/// Name of the file next to `journal.dat` holding the hex author signature.
+pub const AUTHOR_SIGNATURE_FILE: &str = "author_signature.dat";
+
+/// Prefixed to the journal before signing, so an author signature cannot be replayed as a
+/// signature over anything else.
+pub const AUTHOR_SIGNATURE_TAG: &[u8] = b"castvote/author-signature/";
+
+/// Why an author signature does not attribute a journal to a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorError {
+    /// The journal commits no author key, so a signature over it attributes nothing.
+    NotCommitted,
+    /// The journal commits an author key but no signature was given.
+    MissingSignature,
+    /// The entries of a batch journal commit different author keys.
+    Inconsistent,
+    /// The public key is not the one whose hash the journal commits.
+    KeyMismatch { committed: String, given: String },
+    /// The public key is not a secp256k1 public key.
+    Encoding(EncodingError),
+    /// The signature does not verify against the public key over the journal.
+    InvalidSignature,
+}
+
+impl fmt::Display for AuthorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorError::NotCommitted => write!(f, "the journal commits no author key"),
+            AuthorError::MissingSignature => {
+                write!(f, "the journal commits an author key but has no author signature")
+            }
+            AuthorError::Inconsistent => {
+                write!(f, "the journal entries commit different author keys")
+            }
+            AuthorError::KeyMismatch { committed, given } => write!(
+                f,
+                "the author key hashes to {given}, but the journal commits {committed}"
+            ),
+            AuthorError::Encoding(e) => write!(f, "{e}"),
+            AuthorError::InvalidSignature => {
+                write!(f, "the author signature does not verify over the journal")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuthorError::Encoding(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The Base64 compressed SEC1 public key of `key`, as passed to the guest in
+/// [VoteParams::author_key].
+pub fn author_public_key(key: &SigningKey) -> String {
+    general_purpose::STANDARD.encode(canonical_k256_key(key.verifying_key()))
+}
+
+/// Sets [VoteParams::author_key] to the public key of `key`.
+pub fn attribute_to(params: &mut VoteParams, key: &SigningKey) {
+    params.author_key = Some(author_public_key(key));
+}
+
+/// Signs the ABI-encoded `journal` as written to `journal.dat`, prefixed with
+/// [AUTHOR_SIGNATURE_TAG]. Returns the 64-byte `r || s` signature.
+pub fn sign_journal(key: &SigningKey, journal: &[u8]) -> Vec<u8> {
+    let signature: Signature = key.sign(&[AUTHOR_SIGNATURE_TAG, journal].concat());
+    signature.to_bytes().to_vec()
+}
+
+/// The author key hash all of `reveals` commit, failing if they commit different ones.
+pub fn committed_author(reveals: &[RevealInfo]) -> Result<Option<&str>, AuthorError> {
+    let mut hashes = reveals.iter().map(|reveal| reveal.author_key_hash.as_deref());
+    let first = hashes.next().flatten();
+    if hashes.any(|hash| hash != first) {
+        return Err(AuthorError::Inconsistent);
+    }
+    Ok(first)
+}
+
+/// Checks that `signature` (64-byte `r || s`) by the Base64 SEC1 `public_key` signs `journal`,
+/// whose `reveals` commit the hash of that key. The key is hashed in compressed form, as the
+/// guest hashes [VoteParams::author_key], so either encoding of it matches.
+pub fn verify_author_signature(
+    journal: &[u8],
+    reveals: &[RevealInfo],
+    public_key: &str,
+    signature: &[u8],
+) -> Result<(), AuthorError> {
+    let committed = committed_author(reveals)?.ok_or(AuthorError::NotCommitted)?;
+    let key = decode_k256_verifying_key(public_key).map_err(AuthorError::Encoding)?;
+    let given = hex::encode(voter_key_hash(&canonical_k256_key(&key)));
+    if given != committed {
+        return Err(AuthorError::KeyMismatch { committed: committed.to_string(), given });
+    }
+    let signature = Signature::from_slice(signature).map_err(|_| AuthorError::InvalidSignature)?;
+    key.verify(&[AUTHOR_SIGNATURE_TAG, journal].concat(), &signature)
+        .map_err(|_| AuthorError::InvalidSignature)
+}
@@ -0,0 +1,261 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host-side ballot validation.
+//!
+//! The guest rejects malformed ballots too, but only after the host has paid for prover setup.
+//! Checking the ballot shape before it is hashed and signed fails fast with a message that names
+//! the offending field.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use castvote_core::{
+    check_receipt_id, parse_ballot, Date, EncryptedChoice, PollIdentifier, VoteParams,
+};
+use serde_json::{Map, Value};
+
+/// A ballot field that does not match the expected schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BallotValidationError {
+    /// JSON pointer to the offending value, e.g. `/age`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for BallotValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        write!(f, "invalid ballot at {}: {}", path, self.message)
+    }
+}
+
+impl std::error::Error for BallotValidationError {}
+
+fn error(path: &str, message: impl Into<String>) -> BallotValidationError {
+    BallotValidationError {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Checks that `json` is a ballot the guest can process.
+///
+/// The schema is:
+/// - `id` (required): a number or a non-empty string;
+/// - `age` (required unless `birthdate` is given): an integer in `0..=u32::MAX`;
+/// - `birthdate` (optional): a `YYYY-MM-DD` date;
+/// - `is_student` (required): a boolean;
+/// - `name` (optional): a string;
+/// - `choice` (optional): a non-empty string, or a non-empty array of non-empty strings for
+///   approval and ranked polls, or `null` (like `"abstain"`) to abstain;
+/// - `choices` (optional, instead of `choice`): an object mapping each poll ID, as a decimal
+///   string, to a choice of the same form, for ballots voting in several polls;
+/// - `encrypted_choice` (optional, instead of `choice`): an object with the Base64
+///   `ephemeral_key`, `nonce` and `ciphertext` of an encrypted choice;
+/// - `poll_id` (optional, not with `choices`): the poll the ballot is cast in, a number or a
+///   non-empty string as accepted by `--poll-id`;
+/// - `receipt_id` (optional): 16 to 32 random bytes in lowercase hex, committed so the voter can
+///   look the proof up later.
+///
+/// Other fields are allowed and ignored by the guest.
+pub fn validate_ballot(json: &str) -> Result<(), BallotValidationError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| error("", format!("not valid JSON: {e}")))?;
+    let ballot = value
+        .as_object()
+        .ok_or_else(|| error("", "expected a JSON object"))?;
+
+    match required(ballot, "id")? {
+        Value::Number(_) => {}
+        Value::String(s) if !s.is_empty() => {}
+        _ => return Err(error("/id", "expected a number or a non-empty string")),
+    }
+
+    match ballot.get("age") {
+        Some(age) => {
+            if age.as_u64().and_then(|a| u32::try_from(a).ok()).is_none() {
+                return Err(error("/age", "expected a non-negative integer"));
+            }
+        }
+        None if ballot.contains_key("birthdate") => {}
+        None => return Err(error("/age", "missing required field (or provide `birthdate`)")),
+    }
+
+    if let Some(birthdate) = ballot.get("birthdate") {
+        let valid = birthdate.as_str().is_some_and(|s| s.parse::<Date>().is_ok());
+        if !valid {
+            return Err(error("/birthdate", "expected a YYYY-MM-DD date"));
+        }
+    }
+
+    if !required(ballot, "is_student")?.is_boolean() {
+        return Err(error("/is_student", "expected a boolean"));
+    }
+
+    if let Some(name) = ballot.get("name") {
+        if !name.is_string() {
+            return Err(error("/name", "expected a string"));
+        }
+    }
+
+    if let Some(choice) = ballot.get("choice") {
+        validate_choice("/choice", choice)?;
+    }
+
+    if let Some(choices) = ballot.get("choices") {
+        if ballot.contains_key("choice") || ballot.contains_key("encrypted_choice") {
+            return Err(error("/choices", "cannot be combined with `choice` or `encrypted_choice`"));
+        }
+        let choices = choices
+            .as_object()
+            .filter(|choices| !choices.is_empty())
+            .ok_or_else(|| error("/choices", "expected a non-empty object keyed by poll ID"))?;
+        for (poll_id, choice) in choices {
+            if poll_id.parse::<u64>().is_err() {
+                return Err(error(&format!("/choices/{poll_id}"), "expected a poll ID as key"));
+            }
+            validate_choice(&format!("/choices/{poll_id}"), choice)?;
+        }
+    }
+
+    if let Some(poll_id) = ballot.get("poll_id") {
+        if ballot.contains_key("choices") {
+            return Err(error("/poll_id", "cannot be combined with per-poll `choices`"));
+        }
+        if ballot_poll_id(poll_id).is_none() {
+            return Err(error("/poll_id", "expected a non-negative integer or a non-empty string"));
+        }
+    }
+
+    if let Some(receipt_id) = ballot.get("receipt_id") {
+        let checked = match receipt_id.as_str() {
+            Some(receipt_id) => check_receipt_id(receipt_id).map_err(|e| e.to_string()),
+            None => Err("expected a string".to_string()),
+        };
+        checked.map_err(|message| error("/receipt_id", message))?;
+    }
+
+    if let Some(encrypted) = ballot.get("encrypted_choice") {
+        if ballot.contains_key("choice") {
+            return Err(error("/encrypted_choice", "cannot be combined with a plaintext `choice`"));
+        }
+        let encrypted: EncryptedChoice = serde_json::from_value(encrypted.clone()).map_err(|_| {
+            error("/encrypted_choice", "expected an object with `ephemeral_key`, `nonce` and `ciphertext`")
+        })?;
+        encrypted.decode().map_err(|e| error("/encrypted_choice", e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Checks `ballot` against the schema, the poll's age range, student requirement, eligibility
+/// policy and choice rules, so a ballot the guest would reject is never signed. The policy's
+/// `allowed_polls` are checked by [Args::vote_params](crate::cli::Args::vote_params).
+///
+/// Returns the text to sign, as derived by the poll's message format: the canonical form (see
+/// [canonicalize_ballot](castvote_core::canonicalize_ballot)), or the ballot verbatim with
+/// [MessageFormat::Raw](castvote_core::MessageFormat::Raw).
+pub fn prepare_ballot(ballot: &str, params: &VoteParams) -> Result<String> {
+    params.check_ballot_size(ballot.len() as u64)?;
+    validate_ballot(ballot)?;
+    let parsed = parse_ballot(ballot)?;
+    params.age_range.check(parsed.age(params)?)?;
+    params.check_student(&parsed)?;
+    if let Some(policy) = &params.policy {
+        policy.check_voter(parsed.age(params)?, parsed.is_student)?;
+    }
+    if parsed.choices.is_some() {
+        parsed.poll_selections(params)?;
+    } else {
+        parsed.selection(params)?;
+    }
+    Ok(params.message_format.signed_text(ballot)?)
+}
+
+/// Reads the ballot at `path` and prepares it with [prepare_ballot].
+pub fn load_ballot(path: impl AsRef<Path>, params: &VoteParams) -> Result<String> {
+    let path = path.as_ref();
+    let ballot = read_ballot_file(path, params)?;
+    prepare_ballot(&ballot, params).with_context(|| path.display().to_string())
+}
+
+/// Reads the ballot at `path` like [read_text_file], first checking from its metadata that it is
+/// no larger than `params` allow, so an oversized file is rejected without being read.
+pub fn read_ballot_file(path: impl AsRef<Path>, params: &VoteParams) -> Result<String> {
+    let path = path.as_ref();
+    if let Ok(metadata) = fs::metadata(path) {
+        params.check_ballot_size(metadata.len()).with_context(|| path.display().to_string())?;
+    }
+    read_text_file(path)
+}
+
+/// Reads a UTF-8 ballot file, dropping the byte order mark some editors write in front of
+/// non-ASCII text, which `serde_json` would reject.
+///
+/// The text is otherwise kept byte for byte: it is hashed and signed as UTF-8, so every length
+/// derived from it, like the EIP-191 prefix, counts bytes rather than characters.
+pub fn read_text_file(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(match text.strip_prefix('\u{feff}') {
+        Some(text) => text.to_string(),
+        None => text,
+    })
+}
+
+fn required<'a>(ballot: &'a Map<String, Value>, field: &str) -> Result<&'a Value, BallotValidationError> {
+    ballot
+        .get(field)
+        .ok_or_else(|| error(&format!("/{field}"), "missing required field"))
+}
+
+/// Reads the `poll_id` a ballot carries: a number, or a string read as `--poll-id` reads it.
+fn ballot_poll_id(poll_id: &Value) -> Option<PollIdentifier> {
+    match poll_id {
+        Value::Number(poll_id) => poll_id.as_u64().map(PollIdentifier::Numeric),
+        Value::String(poll_id) => poll_id.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the poll the ballot file at `path` names in its `poll_id`, if it names one.
+pub fn read_ballot_poll_id(path: impl AsRef<Path>) -> Result<Option<PollIdentifier>> {
+    let path = path.as_ref();
+    let ballot: Value = serde_json::from_str(&read_text_file(path)?)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    match ballot.get("poll_id") {
+        None => Ok(None),
+        Some(poll_id) => ballot_poll_id(poll_id).map(Some).ok_or_else(|| {
+            let expected = "a non-negative integer or a non-empty string";
+            anyhow!("{}: `poll_id` must be {expected}", path.display())
+        }),
+    }
+}
+
+fn validate_choice(path: &str, choice: &Value) -> Result<(), BallotValidationError> {
+    match choice {
+        Value::Null => {}
+        Value::String(s) if !s.is_empty() => {}
+        Value::Array(options) if !options.is_empty() => {
+            for (index, option) in options.iter().enumerate() {
+                if option.as_str().is_none_or(str::is_empty) {
+                    return Err(error(&format!("{path}/{index}"), "expected a non-empty string"));
+                }
+            }
+        }
+        _ => return Err(error(path, "expected a non-empty string or array of strings, or null")),
+    }
+    Ok(())
+}
@@ -12,12 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+
+use anyhow::Result;
+use k256_example::{
+    estimate::CostModel,
+    self_test::{estimate_self_test, TestVector, BALLOT},
+};
 use k256_methods::BENCHMARK_ELF;
 use risc0_zkvm::{default_executor, ExecutorEnv};
 
-// Simple main to load and run the benchmark binary in the RISC Zero Executor.
-fn main() {
-    let env = ExecutorEnv::builder().build().unwrap();
+// Runs the primitive benchmarks in the RISC Zero Executor, then the single-ballot method end to
+// end on the built-in test vector, whose cycle count is the figure to compare before and after a
+// guest change.
+fn main() -> Result<()> {
+    let env = ExecutorEnv::builder().build()?;
     let exec = default_executor();
-    std::hint::black_box(exec.execute(env, BENCHMARK_ELF)).unwrap();
+    std::hint::black_box(exec.execute(env, BENCHMARK_ELF))?;
+
+    let estimate = estimate_self_test(&TestVector::builtin()?, BALLOT)?;
+    println!();
+    println!("Single-ballot k256_verify (self-test vector):");
+    println!("{}", estimate.report(&CostModel::default()));
+    Ok(())
 }
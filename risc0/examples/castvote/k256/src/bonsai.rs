@@ -0,0 +1,200 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Proving on Bonsai with `--prover bonsai`: unlike the prover behind `default_prover`, which
+//! polls silently, this one logs every change of the session's status and reports Bonsai's own
+//! error message when a session fails.
+
+use anyhow::Error;
+
+use crate::error::VoteError;
+
+// Verify-only builds leave out the Bonsai client.
+#[cfg(feature = "prove")]
+use {
+    crate::{
+        prove::{checked_image_id, ProveMode},
+        timings::{timed, Phase},
+    },
+    anyhow::{bail, Context, Result},
+    bonsai_sdk::blocking::Client,
+    castvote_core::VoteParams,
+    risc0_zkvm::{serde::to_vec, Receipt, VERSION},
+    serde::Serialize,
+    std::{thread, time::Duration},
+};
+
+/// Seconds between polls of a Bonsai session unless `--bonsai-poll-interval` says otherwise.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 1;
+
+/// Describes a Bonsai job status for the log: `queued`, `running`, `running (<state>)`, `done`,
+/// or the lowercased status of a job that ended otherwise, e.g. `failed` or `timed out`.
+pub fn describe_status(status: &str, state: Option<&str>) -> String {
+    match (status, state.filter(|state| !state.is_empty())) {
+        ("QUEUED", _) => "queued".to_string(),
+        ("RUNNING", None) => "running".to_string(),
+        ("RUNNING", Some(state)) => format!("running ({state})"),
+        ("SUCCEEDED", _) => "done".to_string(),
+        (other, _) => other.to_lowercase().replace('_', " "),
+    }
+}
+
+/// The status last logged for one Bonsai job, so each transition is logged once however often
+/// the job is polled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLog {
+    last: String,
+}
+
+impl Default for StatusLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusLog {
+    /// Starts at `queued`, the status of a job Bonsai has only just accepted.
+    pub fn new() -> Self {
+        Self { last: describe_status("QUEUED", None) }
+    }
+
+    /// Records a polled status and returns its description if it differs from the last one.
+    pub fn update(&mut self, status: &str, state: Option<&str>) -> Option<&str> {
+        let description = describe_status(status, state);
+        if description == self.last {
+            return None;
+        }
+        self.last = description;
+        Some(&self.last)
+    }
+}
+
+/// The error for a Bonsai job that ended with `status` instead of succeeding, carrying Bonsai's
+/// `error_msg`.
+pub fn job_failed(job: &str, uuid: &str, status: &str, error_msg: Option<&str>) -> Error {
+    let reason = error_msg.unwrap_or("Bonsai gave no error message");
+    let message = format!("Bonsai {job} {uuid} {}: {reason}", describe_status(status, None));
+    VoteError::ProvingFailed.error(message)
+}
+
+/// One poll of a Bonsai job: its status, the session's state, its error message and, once it
+/// has finished, the URL of the receipt.
+#[cfg(feature = "prove")]
+struct Polled {
+    status: String,
+    state: Option<String>,
+    error_msg: Option<String>,
+    output: Option<String>,
+}
+
+/// Polls a Bonsai job every `interval` until it ends, logging each transition, and returns the
+/// last poll of a job that succeeded.
+#[cfg(feature = "prove")]
+fn wait_for(
+    job: &str,
+    uuid: &str,
+    interval: Duration,
+    mut poll: impl FnMut() -> Result<Polled>,
+) -> Result<Polled> {
+    crate::status!("Bonsai {job} {uuid}: {}", describe_status("QUEUED", None));
+    let mut log = StatusLog::new();
+    loop {
+        let polled = poll().with_context(|| format!("polling Bonsai {job} {uuid}"))?;
+        if let Some(description) = log.update(&polled.status, polled.state.as_deref()) {
+            crate::status!("Bonsai {job} {uuid}: {description}");
+        }
+        match polled.status.as_str() {
+            "QUEUED" | "RUNNING" => thread::sleep(interval),
+            "SUCCEEDED" => return Ok(polled),
+            status => return Err(job_failed(job, uuid, status, polled.error_msg.as_deref())),
+        }
+    }
+}
+
+/// Proves the guest `elf` over `input` and `params` on Bonsai and verifies the receipt against
+/// `image_id`, polling the proving session and then, for a [ProveMode::Groth16] receipt, the
+/// SNARK job every `poll_interval`.
+///
+/// The connection comes from `BONSAI_API_URL` and `BONSAI_API_KEY`. `RISC0_DEV_MODE` does not
+/// apply: choosing Bonsai always asks it for a real proof, so [ProveMode::Reproducible] fails.
+#[cfg(feature = "prove")]
+pub fn prove_on_bonsai<T: Serialize>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+    poll_interval: Duration,
+    mode: ProveMode,
+) -> Result<Receipt> {
+    if mode == ProveMode::Reproducible {
+        bail!("reproducible receipts are dev-mode fakes, which Bonsai does not make");
+    }
+    let image = checked_image_id(elf, image_id)?.to_string();
+    // Bonsai executes and proves in one session, all of it timed as proving.
+    let receipt = timed(Phase::Proving, || {
+        bonsai_receipt(input, params, elf, image, poll_interval, mode)
+    })?;
+    timed(Phase::Verification, || receipt.verify(image_id))
+        .context("verifying receipt")
+        .context(VoteError::VerificationFailed)?;
+    Ok(receipt)
+}
+
+/// Proves `input` and `params` on Bonsai with the guest `elf`, uploaded as `image`, and returns
+/// the receipt unverified.
+#[cfg(feature = "prove")]
+fn bonsai_receipt<T: Serialize>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image: String,
+    poll_interval: Duration,
+    mode: ProveMode,
+) -> Result<Receipt> {
+    let client = Client::from_env(VERSION)
+        .context("connecting to Bonsai; set BONSAI_API_URL and BONSAI_API_KEY")?;
+
+    // The guest reads the same words `ExecutorEnv::write` would give it.
+    let mut words = to_vec(input)?;
+    words.extend(to_vec(params)?);
+    let bytes = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+    client.upload_img(&image, elf.to_vec()).context("uploading the guest to Bonsai")?;
+    let input_id = client.upload_input(bytes).context("uploading the input to Bonsai")?;
+    let session = client
+        .create_session(image, input_id, vec![], false)
+        .context("starting a Bonsai session")?;
+    let done = wait_for("session", &session.uuid, poll_interval, || {
+        let res = session.status(&client)?;
+        let (status, state, error_msg) = (res.status, res.state, res.error_msg);
+        Ok(Polled { status, state, error_msg, output: res.receipt_url })
+    })?;
+
+    let (job, uuid, done) = if mode == ProveMode::Succinct {
+        ("session", session.uuid, done)
+    } else {
+        let snark = client.create_snark(session.uuid).context("requesting a Groth16 proof")?;
+        let done = wait_for("SNARK", &snark.uuid, poll_interval, || {
+            let res = snark.status(&client)?;
+            let (status, error_msg) = (res.status, res.error_msg);
+            Ok(Polled { status, state: None, error_msg, output: res.output })
+        })?;
+        ("SNARK", snark.uuid, done)
+    };
+    let url = done
+        .output
+        .with_context(|| format!("Bonsai {job} {uuid} succeeded without a receipt"))?;
+    let bytes = client.download(&url).context("downloading the receipt from Bonsai")?;
+    bincode::deserialize(&bytes).context("decoding the Bonsai receipt")
+}
@@ -0,0 +1,173 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! A proof bundle: the seal, journal and image ID of one proof in a single JSON file, with
+//! when and by which version of the tool it was made, so a proof can be handed over and
+//! verified as one file rather than as a directory of `.dat` files.
+
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use castvote_core::RevealInfo;
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit::verify_seal,
+    author::{committed_author, verify_author_signature, AuthorError},
+    journal::decode_reveal_entries,
+    output::atomic_write,
+    prove::{checked_image_id, encode_seal},
+};
+
+/// The `format` every bundle carries, so a stray JSON file is not mistaken for one.
+pub const BUNDLE_FORMAT: &str = "castvote-proof-bundle";
+
+/// Version of the bundle layout. Bump it on any change to the fields.
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// Oldest bundle version this build still reads: version 1 bundles only lack the author fields.
+pub const MIN_BUNDLE_VERSION: u32 = 1;
+
+/// Everything needed to verify one proof, as written by `--bundle`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProofBundle {
+    /// Always [BUNDLE_FORMAT].
+    pub format: String,
+    /// The [BUNDLE_VERSION] the bundle was written with.
+    pub version: u32,
+    /// When the bundle was written, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Version of the tool that wrote the bundle.
+    pub tool_version: String,
+    /// Hex image ID of the guest method, as in `image_id.dat`.
+    pub image_id: String,
+    /// Hex seal, with the verifier selector, as in `seal.dat`.
+    pub seal: String,
+    /// Hex journal, as in `journal.dat`.
+    pub journal: String,
+    /// Base64 public key of the service that proved the ballot, whose hash the journal commits,
+    /// if the proof is attributed. Added in version 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_public_key: Option<String>,
+    /// Hex signature of that service over the journal, as in `author_signature.dat`. Added in
+    /// version 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_signature: Option<String>,
+}
+
+impl ProofBundle {
+    /// Bundles a seal and journal for the method with `image_id`, stamped with the current time.
+    pub fn new(seal: &[u8], journal: &[u8], image_id: Digest) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before 1970")
+            .as_secs();
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            created_at,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            image_id: image_id.to_string(),
+            seal: hex::encode(seal),
+            journal: hex::encode(journal),
+            author_public_key: None,
+            author_signature: None,
+        }
+    }
+
+    /// Adds the author's Base64 public key and signature over the journal.
+    pub fn with_author(self, public_key: String, signature: &[u8]) -> Self {
+        Self {
+            author_public_key: Some(public_key),
+            author_signature: Some(hex::encode(signature)),
+            ..self
+        }
+    }
+
+    /// Bundles `receipt`, proved by the guest `elf` with `image_id`, with the same seal and
+    /// journal as [write_artifacts](crate::prove::write_artifacts) writes.
+    pub fn from_receipt(receipt: &Receipt, elf: &'static [u8], image_id: [u32; 8]) -> Result<Self> {
+        let seal = encode_seal(receipt)?;
+        Ok(Self::new(&seal, &receipt.journal.bytes, checked_image_id(elf, image_id)?))
+    }
+
+    /// The seal bytes.
+    pub fn seal_bytes(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.seal).context("the bundle seal is not hex encoded")
+    }
+
+    /// The journal bytes.
+    pub fn journal_bytes(&self) -> Result<Vec<u8>> {
+        hex::decode(&self.journal).context("the bundle journal is not hex encoded")
+    }
+
+    /// The image ID.
+    pub fn image_id_digest(&self) -> Result<Digest> {
+        let bytes = hex::decode(&self.image_id).context("the bundle image ID is not hex encoded")?;
+        Digest::try_from(bytes.as_slice())
+            .map_err(|_| anyhow!("the bundle image ID is {} bytes, expected 32", bytes.len()))
+    }
+
+    /// Verifies the seal against the journal and image ID, as `audit` verifies a proof
+    /// directory, and decodes the reveal entries the journal commits. A journal that commits an
+    /// author key must come with that author's signature over it, and a signature with a
+    /// journal that commits the key it was made with.
+    pub fn verify(&self) -> Result<Vec<RevealInfo>> {
+        let journal = self.journal_bytes()?;
+        verify_seal(&self.seal_bytes()?, &journal, self.image_id_digest()?)?;
+        let reveals = decode_reveal_entries(&journal)?;
+        match (&self.author_public_key, &self.author_signature) {
+            (Some(public_key), Some(signature)) => {
+                let signature = hex::decode(signature)
+                    .context("the bundle author signature is not hex encoded")?;
+                verify_author_signature(&journal, &reveals, public_key, &signature)?;
+            }
+            (None, None) if committed_author(&reveals)?.is_none() => {}
+            (None, None) => return Err(AuthorError::MissingSignature.into()),
+            _ => bail!("the bundle has an author public key or signature without the other"),
+        }
+        Ok(reveals)
+    }
+}
+
+/// Writes `bundle` to `path` as pretty-printed JSON.
+pub fn write_bundle(path: &Path, bundle: &ProofBundle) -> Result<()> {
+    atomic_write(path, serde_json::to_vec_pretty(bundle)?)
+}
+
+/// Reads a bundle written by [write_bundle], rejecting other JSON files and bundles of a version
+/// outside [MIN_BUNDLE_VERSION] to [BUNDLE_VERSION].
+pub fn read_bundle(path: &Path) -> Result<ProofBundle> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let bundle: ProofBundle = serde_json::from_slice(&bytes)
+        .with_context(|| format!("{} is not a proof bundle", path.display()))?;
+    if bundle.format != BUNDLE_FORMAT {
+        bail!("{} is a {:?} file, not a proof bundle", path.display(), bundle.format);
+    }
+    if !(MIN_BUNDLE_VERSION..=BUNDLE_VERSION).contains(&bundle.version) {
+        bail!(
+            "{} is a version {} bundle; this build reads versions {MIN_BUNDLE_VERSION} to \
+             {BUNDLE_VERSION}",
+            path.display(),
+            bundle.version
+        );
+    }
+    Ok(bundle)
+}
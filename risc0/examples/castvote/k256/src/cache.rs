@@ -0,0 +1,157 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk cache of receipts, so reprocessing the same ballots does not prove them again.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use castvote_core::VoteParams;
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+
+use crate::output::atomic_write;
+#[cfg(feature = "prove")]
+use crate::prove::{prove_vote_on, ProveMode, ProverChoice};
+
+/// Directory receipts are cached in unless `--cache-dir` says otherwise.
+pub const DEFAULT_CACHE_DIR: &str = "/tmp/castvote/cache";
+
+/// Receipts stored under `<dir>/<image ID>/<key>.receipt`, one subdirectory per guest image.
+///
+/// The key covers everything the journal depends on, so a hit is a receipt for exactly the same
+/// statement. Receipts are verified again when read; an entry that no longer verifies, e.g. a
+/// dev-mode receipt read without `RISC0_DEV_MODE`, counts as a miss.
+#[derive(Debug, Clone)]
+pub struct ProofCache {
+    dir: PathBuf,
+}
+
+impl ProofCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the hex cache key `sha256(input || params || image_id)`, where `input` is the
+    /// guest input (signatures, ballots, poll IDs and public keys) as the guest reads it.
+    pub fn key<T: Serialize>(input: &T, params: &VoteParams, image_id: [u32; 8]) -> Result<String> {
+        let mut hasher = Sha256::new();
+        for words in [risc0_zkvm::serde::to_vec(input)?, risc0_zkvm::serde::to_vec(params)?] {
+            // Length-prefixed, so bytes cannot move between the input and the parameters.
+            hasher.update((words.len() as u64).to_le_bytes());
+            hasher.update(le_bytes(&words));
+        }
+        hasher.update(Digest::from(image_id).as_bytes());
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    fn image_dir(&self, image_id: [u32; 8]) -> PathBuf {
+        self.dir.join(hex::encode(Digest::from(image_id)))
+    }
+
+    fn entry_path(&self, key: &str, image_id: [u32; 8]) -> PathBuf {
+        self.image_dir(image_id).join(format!("{key}.receipt"))
+    }
+
+    /// Returns the cached receipt for `key`, if there is one that verifies against `image_id`.
+    pub fn get(&self, key: &str, image_id: [u32; 8]) -> Result<Option<Receipt>> {
+        let path = self.entry_path(key, image_id);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        let receipt = bincode::deserialize::<Receipt>(&bytes)
+            .ok()
+            .filter(|receipt| receipt.verify(image_id).is_ok());
+        if receipt.is_none() {
+            // Corrupt or no longer verifiable: drop it so it gets replaced.
+            let _ = fs::remove_file(&path);
+        }
+        Ok(receipt)
+    }
+
+    /// Stores `receipt` under `key`.
+    pub fn put(&self, key: &str, image_id: [u32; 8], receipt: &Receipt) -> Result<()> {
+        let dir = self.image_dir(image_id);
+        fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+        let bytes = bincode::serialize(receipt).context("serializing receipt")?;
+        atomic_write(self.entry_path(key, image_id), bytes)
+    }
+
+    /// Removes the entries of every image ID not in `current`, i.e. receipts for guests that have
+    /// since been rebuilt. Returns the number of image directories removed.
+    ///
+    /// Only subdirectories named like an image ID are considered, so pointing `--cache-dir` at a
+    /// directory with other contents does not delete them.
+    pub fn prune(&self, current: &[[u32; 8]]) -> Result<usize> {
+        let keep: Vec<String> = current.iter().map(|id| hex::encode(Digest::from(*id))).collect();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.dir.display())),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_image_dir = name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit());
+            if entry.file_type()?.is_dir() && is_image_dir && !keep.contains(&name) {
+                fs::remove_dir_all(entry.path())
+                    .with_context(|| format!("removing {}", entry.path().display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// The directory the cache lives in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+fn le_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// Like [prove_vote_on], but returns the cached receipt on a hit and caches fresh receipts.
+/// Without a cache it just proves, and so it does for anything but a [ProveMode::Groth16]
+/// receipt: the key does not cover the receipt kind.
+#[cfg(feature = "prove")]
+pub fn prove_vote_cached<T: Serialize>(
+    cache: Option<&ProofCache>,
+    prover: ProverChoice,
+    mode: ProveMode,
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+) -> Result<Receipt> {
+    let Some(cache) = cache.filter(|_| mode == ProveMode::Groth16) else {
+        return prove_vote_on(prover, mode, input, params, elf, image_id);
+    };
+    let key = ProofCache::key(input, params, image_id)?;
+    if let Some(receipt) = cache.get(&key, image_id)? {
+        crate::status!("Using cached receipt {key}");
+        return Ok(receipt);
+    }
+    let receipt = prove_vote_on(prover, mode, input, params, elf, image_id)?;
+    cache.put(&key, image_id, &receipt)?;
+    Ok(receipt)
+}
@@ -0,0 +1,145 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Resumable batch runs: ballots proven one receipt each, with every proven ballot recorded in a
+//! checkpoint file so a run that crashes hours in picks up where it stopped.
+//!
+//! Ballots are identified by their input hash, the [ProofCache](crate::cache::ProofCache) key,
+//! so a ballot is only skipped on resume if it would be proven with exactly the same input,
+//! parameters and guest.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::output::write_json_file;
+
+/// The ballots of a run proven so far, as saved in the checkpoint file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointState {
+    /// Input hashes of the ballots proven so far.
+    pub completed: BTreeSet<String>,
+}
+
+/// A checkpoint file and the state it holds, saved atomically after every recorded proof.
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    state: CheckpointState,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path`. With `resume` an existing checkpoint is loaded, and a
+    /// missing one starts empty; without it an existing checkpoint is an error, so a finished
+    /// run is not silently skipped by starting it again.
+    pub fn open(path: &Path, resume: bool) -> Result<Self> {
+        let state = match fs::read_to_string(path) {
+            Ok(_) if !resume => {
+                bail!("checkpoint {} exists; pass --resume to continue that run", path.display())
+            }
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("reading the checkpoint {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => CheckpointState::default(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        Ok(Self { path: path.to_path_buf(), state })
+    }
+
+    /// The checkpoint file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What has been recorded so far.
+    pub fn state(&self) -> &CheckpointState {
+        &self.state
+    }
+
+    /// Whether the ballot with `input_hash` was already proven.
+    pub fn is_completed(&self, input_hash: &str) -> bool {
+        self.state.completed.contains(input_hash)
+    }
+
+    /// Records the ballot with `input_hash` as proven and saves the checkpoint, atomically, so a
+    /// crash leaves either the previous checkpoint or this one.
+    pub fn record(&mut self, input_hash: &str) -> Result<()> {
+        if self.state.completed.insert(input_hash.to_string()) {
+            write_json_file(&self.path, &self.state)?;
+        }
+        Ok(())
+    }
+}
+
+/// Spaces out the start of successive proofs by at least `interval`.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimit {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last: None }
+    }
+
+    /// Sleeps until `interval` has passed since the previous call, then returns.
+    pub fn wait(&mut self) {
+        if let Some(last) = self.last {
+            if let Some(remaining) = self.interval.checked_sub(last.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+/// How many ballots a resumable run proved, and how many it skipped as already proven.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResumableRun {
+    pub proven: usize,
+    pub skipped: usize,
+}
+
+/// Proves every `(input_hash, ballot)` of `ballots` not yet in `checkpoint` with `prove`, in
+/// order and spaced out by `rate`, recording each in the checkpoint as soon as it is proven.
+///
+/// The first failure stops the run; everything proven before it stays recorded, so the next
+/// run with the same checkpoint neither proves those again nor skips the one that failed.
+pub fn run_resumable<T>(
+    ballots: &[(String, T)],
+    checkpoint: &mut Checkpoint,
+    rate: &mut RateLimit,
+    mut prove: impl FnMut(&str, &T) -> Result<()>,
+) -> Result<ResumableRun> {
+    let mut run = ResumableRun::default();
+    for (input_hash, ballot) in ballots {
+        if checkpoint.is_completed(input_hash) {
+            run.skipped += 1;
+            continue;
+        }
+        rate.wait();
+        prove(input_hash, ballot).with_context(|| format!("proving ballot {input_hash}"))?;
+        checkpoint.record(input_hash)?;
+        run.proven += 1;
+    }
+    Ok(run)
+}
@@ -0,0 +1,1230 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Command-line interface of the `checkvote` binary.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use castvote_core::{
+    AgeRange, BallotType, Date, ElectionScope, EligibilityPolicy, JournalCodec, MessageFormat,
+    PollIdentifier, PollMeta, VoteParams, DEFAULT_MAX_BALLOT_SIZE, MAX_BALLOT_SIZE_CAP,
+};
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
+use risc0_zkvm::sha::Digest;
+use k256_methods::{
+    AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID, ED25519_VERIFY_ELF, ED25519_VERIFY_ID, K256_VERIFY_ELF,
+    K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID, K256_VERIFY_HOMOMORPHIC_ELF,
+    K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID, K256_VERIFY_MULTI_ELF, K256_VERIFY_MULTI_ID,
+    P256_VERIFY_ELF, P256_VERIFY_ID,
+};
+
+use crate::{
+    ballot::{read_ballot_poll_id, read_text_file},
+    bonsai::DEFAULT_POLL_INTERVAL_SECS,
+    cache::{ProofCache, DEFAULT_CACHE_DIR},
+    estimate::DEFAULT_CYCLES_PER_SECOND,
+    keyfile::KeyPassword,
+    prove::{ProveMode, ProverChoice, RetryPolicy, DEFAULT_OUT_DIR},
+    today,
+};
+
+/// Shortest authority seed accepted: the seed is the only secret behind every nullifier salt.
+pub const MIN_AUTHORITY_SEED_LEN: usize = 32;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "checkvote",
+    version,
+    about = "Verify a poll vote",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Without a subcommand, signs and proves the given ballots
+    #[command(flatten)]
+    pub prove: Option<Args>,
+
+    /// Print nothing but the command's result on stdout: no status lines, progress or retry
+    /// notices on stderr
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Format of the result printed on stdout; logs always go to stderr
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+
+    /// Format a failure is reported in: the error chain as text on stderr, or one JSON object
+    /// `{"error", "message", "code"}` on stderr, or on stdout with `--quiet`
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Text, global = true)]
+    pub error_format: ErrorFormat,
+
+    /// Octal Unix permissions for every file written (e.g. `600`), set before any contents are
+    /// written; some outputs, like `--dump-input` and `--sig-out` files, hold signatures or
+    /// encryption randomness. Defaults to the umask. Unix only
+    #[arg(long, global = true, value_name = "MODE", value_parser = parse_file_mode)]
+    pub file_mode: Option<u32>,
+}
+
+/// Parses an octal permission mode such as `600` or `0o640`.
+fn parse_file_mode(mode: &str) -> Result<u32, String> {
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if mode <= 0o777 => Ok(mode),
+        _ => Err(format!("{mode:?} is not an octal mode between 000 and 777")),
+    }
+}
+
+/// Parses a 32-byte digest given as 64 hex characters, optionally `0x`-prefixed.
+fn parse_digest(digest: &str) -> Result<Digest, String> {
+    let digits = digest.strip_prefix("0x").unwrap_or(digest);
+    hex::decode(digits)
+        .ok()
+        .and_then(|bytes| Digest::try_from(bytes.as_slice()).ok())
+        .ok_or_else(|| format!("{digest:?} is not a digest of 64 hex characters"))
+}
+
+/// How a command prints its result on stdout.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Lines for a person to read.
+    #[default]
+    Text,
+    /// A single JSON object, and nothing else, for scripts.
+    Json,
+}
+
+/// How a failure is reported.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// The error and its causes, for a person to read.
+    #[default]
+    Text,
+    /// A single JSON object naming the failure category, for scripts.
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Tally decoded journals into per-election, per-poll, per-choice vote counts
+    Report {
+        /// Glob matching the hex `journal.dat` files to tally (e.g. "journals/*.dat")
+        #[arg(long)]
+        journals: String,
+
+        /// Output CSV with `election_id,poll_id,choice,count` rows; the same counts nested by
+        /// election and poll are written next to it with a `.json` extension, and rejected
+        /// journals with a `.rejected.csv` extension
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Also write a JSON histogram of the counted ballots' ages and `is_student` split per
+        /// poll to this file
+        #[arg(long)]
+        demographics: Option<PathBuf>,
+
+        /// Comma-separated lower bounds of the age buckets after the first, which starts at 0
+        /// [default: 18,25,35,50,65]. Requires `--demographics`
+        #[arg(long, value_delimiter = ',', requires = "demographics")]
+        age_buckets: Vec<u32>,
+    },
+
+    /// Keep tallying a proofs directory while the election runs: every new `journal.dat` is
+    /// decoded and counted once, deduplicated by nullifier, and the running totals printed
+    WatchTally {
+        /// Directory searched, at any depth, for `journal.dat` files
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// File the counted journals and nullifiers are saved to, so a restart does not count
+        /// them twice; defaults to `watch-tally.json` in `--dir`
+        #[arg(long)]
+        state: Option<PathBuf>,
+
+        /// Seconds between scans of the directory
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 5,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        interval: u64,
+
+        /// Scan once, print the totals and exit, e.g. from cron
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Verify every proof under a directory, each subdirectory holding the `seal.dat`,
+    /// `journal.dat` and `image_id.dat` written by the prover, and report valid, invalid and
+    /// duplicate-nullifier proofs
+    Audit {
+        /// Directory searched, at any depth, for proof directories
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// Output CSV with `proof,status,poll_ids,nullifiers,detail` rows; a JSON report with
+        /// the summary counts is written next to it with a `.json` extension
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Merge the `--nullifier-store` files of runs on several machines into one, reporting every
+    /// nullifier held by more than one of them: a double vote only merging reveals
+    MergeNullifiers {
+        /// Combined store to write, replacing any existing file
+        #[arg(long)]
+        out: PathBuf,
+
+        /// The stores to merge, each given once
+        #[arg(required = true)]
+        stores: Vec<PathBuf>,
+    },
+
+    /// Run the checks the on-chain RISC Zero verifier would run on a seal and journal, without a
+    /// chain: verifier selector, seal length, and the Groth16 proof against the image ID and the
+    /// journal digest
+    SimulateOnchain {
+        /// Hex `seal.dat` written by the prover
+        #[arg(long)]
+        seal: PathBuf,
+
+        /// Hex `journal.dat` written by the prover, exactly as it will be submitted
+        #[arg(long)]
+        journal: PathBuf,
+
+        /// Image ID as 64 hex characters, or the path of an `image_id.dat`
+        #[arg(long)]
+        image_id: String,
+
+        /// Also require the full digest of the verifier parameters, as 64 hex characters, to
+        /// be this one rather than only to start with the seal's 4-byte selector
+        #[arg(long, value_name = "HEX", value_parser = parse_digest)]
+        expected_verifier_params: Option<Digest>,
+    },
+
+    /// Print what a seal holds without verifying it: its verifier selector, its length, and
+    /// whether it is a fake dev-mode seal or a Groth16 one
+    InspectSeal {
+        /// `seal.dat` written by the prover, hex or raw binary
+        #[arg(long)]
+        seal: PathBuf,
+    },
+
+    /// Decode two single-ballot journals and print the fields that differ, flagging journals
+    /// committed with different format versions
+    DiffJournal {
+        /// Hex `journal.dat` of the expected proof
+        left: PathBuf,
+
+        /// Hex `journal.dat` of the proof to compare with it
+        right: PathBuf,
+    },
+
+    /// Compress a succinct receipt written by `--proof-type succinct` to Groth16 and write the
+    /// seal, journal and image ID, as proving with the default `--proof-type groth16` would
+    Compress {
+        /// The `succinct.bin` receipt file
+        #[arg(long)]
+        receipt: PathBuf,
+
+        /// Directory the seal, journal and image ID are written to
+        #[arg(long, default_value = DEFAULT_OUT_DIR)]
+        out_dir: PathBuf,
+
+        /// Also dump the whole Groth16 receipt as JSON to this file, gzip-compressed if it ends
+        /// in `.gz` (needs the `gzip` feature)
+        #[arg(long)]
+        receipt_out: Option<PathBuf>,
+
+        /// Also write the seal, journal and image ID as a single proof bundle to this file,
+        /// for `verify-bundle`
+        #[arg(long)]
+        bundle: Option<PathBuf>,
+    },
+
+    /// Verify a proof bundle written by `--bundle` and print the reveal entries its journal
+    /// commits. Dev-mode seals are only accepted with `RISC0_DEV_MODE=1`
+    VerifyBundle {
+        /// The bundle file
+        bundle: PathBuf,
+
+        /// Also require the full digest of the verifier parameters, as 64 hex characters, to
+        /// be this one rather than only to start with the seal's 4-byte selector
+        #[arg(long, value_name = "HEX", value_parser = parse_digest)]
+        expected_verifier_params: Option<Digest>,
+    },
+
+    /// Print every field a single-ballot journal commits as flat key-value pairs, one per line,
+    /// for spreadsheets and `grep`
+    JournalToKv {
+        /// Hex `journal.dat` written by the prover
+        #[arg(long)]
+        journal: PathBuf,
+
+        /// Flavor of the output
+        #[arg(long, value_enum, default_value_t = KvFormat::Env)]
+        format: KvFormat,
+    },
+
+    /// Print a Solidity library with a `RevealInfo` struct and a `decode` function for
+    /// single-ballot journals of the current version, generated from the journal layout
+    GenSolidity {
+        /// File the library is written to instead of stdout, e.g. `CastvoteJournal.sol`
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Check a signature over a message file against a public key and print valid or invalid,
+    /// without proving anything: for checking signatures made by other libraries first
+    VerifySig {
+        /// Base64 public key, as `--public-key` takes it
+        #[arg(long)]
+        pubkey: String,
+
+        /// File holding the signed message
+        #[arg(long)]
+        message_file: PathBuf,
+
+        /// Base64 signature, as `--signature` takes it
+        #[arg(long)]
+        signature: String,
+
+        /// Signature scheme of the key
+        #[arg(long, value_enum, default_value_t = Scheme::Ecdsa)]
+        scheme: Scheme,
+
+        /// Curve of the key when using `--scheme ecdsa`
+        #[arg(long, value_enum, default_value_t = Curve::K256)]
+        curve: Curve,
+
+        /// How the signed bytes are derived from the file; unlike when proving, the file is
+        /// taken as it is by default, so it need not be a ballot
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = MessageFormatArg::Raw,
+            conflicts_with = "eip191"
+        )]
+        message_format: MessageFormatArg,
+
+        /// The message was signed as an EIP-191 message, hashed with Keccak-256
+        #[arg(long)]
+        eip191: bool,
+    },
+
+    /// Recompute a ballot's nullifier and check that the journal commits it, tying a proof to a
+    /// specific ballot without the voter's key
+    CheckNullifier {
+        /// The ballot file the proof is claimed to be for
+        #[arg(long)]
+        ballot: PathBuf,
+
+        /// Poll the ballot was cast in, as passed to the prover
+        #[arg(long)]
+        poll_id: PollIdentifier,
+
+        /// Hex `journal.dat` written by the prover
+        #[arg(long)]
+        journal: PathBuf,
+
+        /// Election the nullifiers were scoped to, as passed to the prover
+        #[arg(long, requires = "authority_seed_file")]
+        election_id: Option<String>,
+
+        /// The election authority's seed file, as passed to the prover
+        #[arg(long, requires = "election_id")]
+        authority_seed_file: Option<PathBuf>,
+    },
+
+    /// Print one voter's secret in an election, derived from the authority seed, so an auditor
+    /// can open that voter's ID commitment with `open-id-commitment` and no other
+    VoterSecret {
+        /// The voter's `id` as in the ballot: JSON, so `7` is a number and `'"7"'` a string
+        #[arg(long)]
+        voter_id: String,
+
+        /// Election the ballot was proven for, as passed to the prover
+        #[arg(long)]
+        election_id: String,
+
+        /// The election authority's seed file, as passed to the prover
+        #[arg(long)]
+        authority_seed_file: PathBuf,
+    },
+
+    /// Check that a journal's ID commitment opens to a voter ID under the voter's secret, tying
+    /// its nullifiers to that voter without the ballot or the authority seed
+    OpenIdCommitment {
+        /// Hex `journal.dat` written by the prover
+        #[arg(long)]
+        journal: PathBuf,
+
+        /// The voter's `id` as in the ballot: JSON, so `7` is a number and `'"7"'` a string
+        #[arg(long)]
+        voter_id: String,
+
+        /// The voter's secret as printed by `voter-secret`, 64 hex characters
+        #[arg(long)]
+        voter_secret: String,
+    },
+
+    /// Prove how a ballot proven with `--elgamal-to` voted, by re-encrypting the vote from its
+    /// saved opening and comparing it with the ciphertexts the journal commits
+    Open {
+        /// Nullifier of the ballot, as printed after proving
+        #[arg(long)]
+        nullifier: String,
+
+        /// Hex `journal.dat` of the ballot's proof; defaults to the one in the default output
+        /// directory
+        #[arg(long)]
+        journal: Option<PathBuf>,
+
+        /// Openings file written when proving; defaults to the one in the default output
+        /// directory
+        #[arg(long)]
+        openings: Option<PathBuf>,
+    },
+
+    /// Reveal phase of a homomorphically tallied poll: decrypt the aggregated votes of every
+    /// poll with the authority's secret keys and print the per-option totals. Individual votes
+    /// stay encrypted
+    Reveal {
+        /// Glob matching the hex `journal.dat` files of ballots proven with `--elgamal-to`
+        #[arg(long)]
+        journals: String,
+
+        /// Private key file of a trustee, as written by `keygen --curve k256`; give every
+        /// trustee's key, in any order
+        #[arg(long, required = true)]
+        authority_key: Vec<PathBuf>,
+
+        /// Password of encrypted `--authority-key` files
+        #[arg(long, env = "CHECKVOTE_KEY_PASSWORD", hide_env_values = true)]
+        key_password: Option<KeyPassword>,
+
+        /// Comma-separated poll options, in the order they were encrypted in, to label the totals
+        #[arg(long, value_delimiter = ',')]
+        options: Vec<String>,
+    },
+
+    /// Re-sign `--no-prove` signed ballots under a new voter key, after checking each signature
+    /// against the old public key
+    Resign {
+        /// Base64 public key the ballots were signed with
+        #[arg(long)]
+        old_pub: String,
+
+        /// File holding the new Base64 private key
+        #[arg(long)]
+        new_priv: PathBuf,
+
+        /// Glob of `--sig-out` files to re-sign (e.g. `signed/*.json`)
+        #[arg(long)]
+        ballots: String,
+
+        /// Directory the re-signed files are written to, under their original names
+        #[arg(long)]
+        out_dir: PathBuf,
+
+        /// Signature scheme of both keys
+        #[arg(long, value_enum, default_value_t = Scheme::Ecdsa)]
+        scheme: Scheme,
+
+        /// Curve of both keys when using `--scheme ecdsa`
+        #[arg(long, value_enum, default_value_t = Curve::K256)]
+        curve: Curve,
+
+        /// How the signed bytes were derived from the ballots; the new signatures use the same
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = MessageFormatArg::JsonCanonical,
+            conflicts_with = "eip191"
+        )]
+        message_format: MessageFormatArg,
+
+        /// The ballots were signed as EIP-191 messages
+        #[arg(long)]
+        eip191: bool,
+
+        /// Check and re-sign every ballot, then list the files that would be written without
+        /// writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Generate a voter key pair and print both keys, Base64 encoded as `--new-priv` and
+    /// `--public-key` take them
+    Keygen {
+        /// Signature scheme of the key
+        #[arg(long, value_enum, default_value_t = Scheme::Ecdsa)]
+        scheme: Scheme,
+
+        /// Curve of the key when using `--scheme ecdsa`
+        #[arg(long, value_enum, default_value_t = Curve::K256)]
+        curve: Curve,
+
+        /// Hex seed to derive the key from instead of the system RNG, so the same seed always
+        /// prints the same key pair. For test vectors only: the key is as secret as the seed
+        #[arg(long)]
+        seed: Option<String>,
+    },
+
+    /// Encrypt a plain Base64 private key file under a password, for `--private-key-file`
+    EncryptKey {
+        /// File holding the Base64 private key, e.g. as printed by `keygen`
+        #[arg(long)]
+        key: PathBuf,
+
+        /// Where to write the encrypted key file; it is readable by its owner only
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Password to encrypt the key under; prefer the environment variable, since command
+        /// lines are visible to other users
+        #[arg(long, env = "CHECKVOTE_KEY_PASSWORD", hide_env_values = true)]
+        key_password: KeyPassword,
+    },
+
+    /// Print an example ballot with placeholder values, checked against the ballot schema, to
+    /// edit and prove (e.g. `checkvote template > ballot.json`)
+    Template {
+        /// Kind of poll the ballot is for: a single `choice`, or a list for approval and ranked
+        /// polls
+        #[arg(long, value_enum, default_value_t = BallotTypeArg::Single)]
+        ballot_type: BallotTypeArg,
+
+        /// Comma-separated options of the poll; the template votes for the first, or ranks them
+        /// all for a ranked poll
+        #[arg(long, value_delimiter = ',', default_value = "yes,no")]
+        options: Vec<String>,
+
+        /// Poll ID the ballot names in its `poll_id`
+        #[arg(long)]
+        poll_id: Option<PollIdentifier>,
+
+        /// Give a `birthdate` instead of a static `age`
+        #[arg(long)]
+        birthdate: bool,
+
+        /// Explain each field in a `//` comment above it; strip the comments before proving,
+        /// since JSON has none
+        #[arg(long)]
+        annotated: bool,
+    },
+
+    /// Run the pipeline on the built-in test vector and check every output against it, printing
+    /// PASS or FAIL per step: a smoke test after installing or upgrading
+    SelfTest {
+        /// Only check the host side, without proving the test vector in dev mode
+        #[arg(long)]
+        host_only: bool,
+    },
+
+    /// Sign the ballots and run the guest in the executor only, reporting cycles, segments and
+    /// the estimated proving time and cost instead of proving
+    Estimate {
+        #[command(flatten)]
+        args: Box<Args>,
+
+        /// Proving throughput the time estimate assumes, in proven cycles per second
+        #[arg(
+            long,
+            default_value_t = DEFAULT_CYCLES_PER_SECOND,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        cycles_per_second: u64,
+
+        /// Price per million proven cycles, to estimate the proving cost
+        #[arg(long)]
+        cost_per_mcycle: Option<f64>,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Input filename (e.g., a JSON or hex file); several files are proven together in a single
+    /// batch receipt
+    #[arg(required_unless_present_any = ["jsonl", "input", "from_env"])]
+    pub filenames: Vec<String>,
+
+    /// JSON Lines file with one ballot object per line, proven together in a single batch
+    /// receipt; lines that are not valid ballots are skipped and reported
+    #[arg(long, conflicts_with_all = ["filenames", "no_prove", "signature", "signature_file"])]
+    pub jsonl: Option<PathBuf>,
+
+    /// Base64 signatures for the `--jsonl` ballots, the one on line `n` for the ballot on line
+    /// `n`, verifying against `--public-key`; without it the ballots are signed here
+    #[arg(
+        long,
+        group = "external_signature",
+        requires_all = ["jsonl", "public_key"],
+        conflicts_with = "filenames"
+    )]
+    pub jsonl_signatures: Option<PathBuf>,
+
+    /// Prove the `--jsonl` ballots one receipt each, in a subdirectory of `--out-dir` named
+    /// after the ballot's input hash, recording every proven ballot in this checkpoint file
+    #[arg(
+        long,
+        requires = "jsonl",
+        conflicts_with_all = ["filenames", "receipt_out", "bundle", "dump_input", "explain"]
+    )]
+    pub checkpoint: Option<PathBuf>,
+
+    /// JSON file of the nullifiers proven so far, created if missing: a ballot whose nullifier it
+    /// holds fails with exit status 6 before proving, and each proof's nullifiers are added once
+    /// it is written
+    #[arg(long, conflicts_with_all = ["no_prove", "dump_input", "explain"])]
+    pub nullifier_store: Option<PathBuf>,
+
+    /// File holding the proving service's secp256k1 private key (as `--private-key-file`): the
+    /// guest commits the hash of its public key, and the journal is signed with it after proving,
+    /// into `author_signature.dat` and the `--bundle`, attributing the proof to the service
+    #[arg(long, conflicts_with_all = ["no_prove", "dump_input", "explain", "input", "from_env"])]
+    pub commit_author_signature: Option<PathBuf>,
+
+    /// Continue the run recorded in `--checkpoint`, skipping the ballots it has proven
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    /// Start the proofs of a `--checkpoint` run at least this many seconds apart, e.g. to stay
+    /// within a proving service's quota
+    #[arg(long, requires = "checkpoint")]
+    pub min_proof_interval: Option<u64>,
+
+    /// Poll ID: a number, or any other string such as a UUID or a slug, which is hashed to the
+    /// numeric ID nullifiers are keyed by and committed next to it. Defaults to the `poll_id` the
+    /// ballots carry; ballots naming a poll must name this one
+    #[arg(long)]
+    pub poll_id: Option<PollIdentifier>,
+
+    /// Comma-separated poll IDs to vote in with a single ballot and signature (e.g.
+    /// `--polls 1001,1002`); the ballot holds one entry per poll in `choices`, and the receipt
+    /// commits one reveal entry, with its own nullifier, per poll. Requires
+    /// `--scheme ecdsa --curve k256`
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = [
+            "poll_id", "jsonl", "no_prove", "signature", "signature_file", "encrypt_to",
+            "elgamal_to",
+        ]
+    )]
+    pub polls: Vec<u64>,
+
+    /// Largest poll ID the guest accepts; it rejects poll IDs above it, and poll ID 0 always, and
+    /// commits the bound as `max_poll_id`
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_poll_id: Option<u64>,
+
+    /// Signature scheme of the voter's key; selects the matching guest method
+    #[arg(long, value_enum, default_value_t = Scheme::Ecdsa)]
+    pub scheme: Scheme,
+
+    /// Curve of the voter's signing key when using `--scheme ecdsa`
+    #[arg(long, value_enum, default_value_t = Curve::K256)]
+    pub curve: Curve,
+
+    /// File holding the voter's private key: Base64 as printed by `keygen`, or encrypted by
+    /// `encrypt-key`; without it the scheme's built-in demo key signs
+    #[arg(long)]
+    pub private_key_file: Option<PathBuf>,
+
+    /// Password of an encrypted `--private-key-file`; prefer the environment variable, since
+    /// command lines are visible to other users
+    #[arg(long, env = "CHECKVOTE_KEY_PASSWORD", hide_env_values = true)]
+    pub key_password: Option<KeyPassword>,
+
+    /// Reference date (YYYY-MM-DD) for deriving the age of ballots with a `birthdate`;
+    /// defaults to today (UTC)
+    #[arg(long)]
+    pub as_of: Option<Date>,
+
+    /// Election the nullifiers are scoped to: the nullifier salt is derived from the
+    /// `--authority-seed-file` and this ID, and the ID is committed in the journal
+    #[arg(long, requires = "authority_seed_file")]
+    pub election_id: Option<String>,
+
+    /// File holding the election authority's secret seed (at least 32 bytes, used as is), from
+    /// which every election's nullifier salt is derived. Needs `--election-id`, except with an
+    /// `--input` or `--from-env` dump of an election, which never records the seed
+    #[arg(long)]
+    pub authority_seed_file: Option<PathBuf>,
+
+    /// Youngest age the guest accepts
+    #[arg(long, default_value_t = AgeRange::default().min)]
+    pub min_age: u32,
+
+    /// Oldest age the guest accepts; guards against implausible ages like 200
+    #[arg(long, default_value_t = AgeRange::default().max)]
+    pub max_age: u32,
+
+    /// Only accept ballots with `is_student` set, for student-only polls; without it
+    /// `is_student` is only disclosed
+    #[arg(long)]
+    pub require_student: bool,
+
+    /// Largest ballot, in bytes, accepted; larger files are rejected before they are hashed, and
+    /// the guest refuses them too. At most 1 MiB
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_BALLOT_SIZE,
+        value_parser = clap::value_parser!(u64).range(1..=MAX_BALLOT_SIZE_CAP)
+    )]
+    pub max_ballot_size: u64,
+
+    /// Sign and verify ballots as Ethereum `personal_sign` (EIP-191) messages hashed with
+    /// Keccak-256, as browser wallets do; requires `--scheme ecdsa --curve k256`
+    #[arg(long)]
+    pub eip191: bool,
+
+    /// How the signed bytes are derived from the ballot file; the guest derives them the same
+    /// way and commits the format
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MessageFormatArg::JsonCanonical,
+        conflicts_with = "eip191"
+    )]
+    pub message_format: MessageFormatArg,
+
+    /// How the guest serializes the journal payload; recorded in the journal's version byte
+    #[arg(long, value_enum, default_value_t = JournalCodecArg::Bincode)]
+    pub journal_codec: JournalCodecArg,
+
+    /// How the ballot's `choice` is interpreted
+    #[arg(long, value_enum, default_value_t = BallotTypeArg::Single)]
+    pub ballot_type: BallotTypeArg,
+
+    /// Comma-separated options a ballot may select (e.g. `--options yes,no`); without it, or
+    /// `--poll-meta`, any option is accepted
+    #[arg(long, value_delimiter = ',')]
+    pub options: Vec<String>,
+
+    /// JSON poll definition `{"poll_id", "title", "options"}`: ballots are checked against its
+    /// options, which replace `--options`, and the guest commits its hash so a verifier can bind
+    /// the proof to it. Its `poll_id` must be the `--poll-id`
+    #[arg(long, conflicts_with_all = ["options", "polls"])]
+    pub poll_meta: Option<PathBuf>,
+
+    /// JSON eligibility policy `{"min_age", "require_student", "allowed_polls"}`: the guest
+    /// evaluates every rule against the ballot and commits the policy's hash
+    #[arg(long)]
+    pub policy: Option<PathBuf>,
+
+    /// Directory the seal, journal and image ID are written to
+    #[arg(long, default_value = DEFAULT_OUT_DIR)]
+    pub out_dir: PathBuf,
+
+    /// Also dump the whole receipt as JSON to this file, gzip-compressed if it ends in `.gz`
+    /// (needs the `gzip` feature)
+    #[arg(long, conflicts_with = "no_prove")]
+    pub receipt_out: Option<PathBuf>,
+
+    /// Also write the seal, journal and image ID, with when and by which version they were
+    /// made, as a single proof bundle to this file, for `verify-bundle`
+    #[arg(long, conflicts_with = "no_prove")]
+    pub bundle: Option<PathBuf>,
+
+    /// Only sign the ballots and output the signatures as JSON, without proving; for handing
+    /// signed ballots to a separate proving service
+    #[arg(long)]
+    pub no_prove: bool,
+
+    /// Sign the ballots, check the signatures and compute the nullifiers, against
+    /// `--nullifier-store` too, then list the files the run would write instead of proving and
+    /// writing anything
+    #[arg(long, conflicts_with_all = ["no_prove", "dump_input", "explain"])]
+    pub dry_run: bool,
+
+    /// With `--dry-run`, also run the prover and verify the receipt, still writing nothing
+    #[arg(long, requires = "dry_run")]
+    pub with_prover: bool,
+
+    /// File the `--no-prove` JSON is written to instead of stdout
+    #[arg(long, requires = "no_prove")]
+    pub sig_out: Option<PathBuf>,
+
+    /// Directory receipts are cached in, keyed by a hash of the guest input, the poll parameters
+    /// and the image ID
+    #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+    pub cache_dir: PathBuf,
+
+    /// Always prove, neither reading nor writing the receipt cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Retry a proof that fails transiently (network errors, timeouts) up to N more times, with
+    /// exponential backoff; failures of the guest itself are never retried
+    #[arg(long, default_value_t = 0, value_name = "N")]
+    pub prove_retries: u32,
+
+    /// Abandon a proving attempt that takes longer than this many seconds; a timed-out attempt is
+    /// retried like any other transient failure
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub prove_timeout: Option<u64>,
+
+    /// Where proofs are made
+    #[arg(long, value_enum, default_value_t = ProverArg::Default)]
+    pub prover: ProverArg,
+
+    /// Kind of receipt to prove: `succinct` writes `succinct.bin` to `--out-dir` instead of the
+    /// on-chain artifacts, for `compress` to finish, possibly on another machine
+    #[arg(long, value_enum, default_value_t = ProofTypeArg::Groth16)]
+    pub proof_type: ProofTypeArg,
+
+    /// Seconds between polls of a Bonsai session's status [default: 1]. Requires
+    /// `--prover bonsai`
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub bonsai_poll_interval: Option<u64>,
+
+    /// Base64 SEC1 public key of the election authority: the ballot's `choice` is encrypted to it
+    /// before signing and only the ciphertext is committed in the journal. Requires
+    /// `--scheme ecdsa --curve k256` and a single ballot
+    #[arg(long, conflicts_with_all = ["no_prove", "jsonl", "signature", "signature_file"])]
+    pub encrypt_to: Option<String>,
+
+    /// Base64 SEC1 election key (the sum of the trustees' keys): the ballot's choice is committed
+    /// only as exponential-ElGamal ciphertexts, one per `--options` entry, for a homomorphic
+    /// tally. Requires `--scheme ecdsa --curve k256` and a single ballot
+    #[arg(
+        long,
+        conflicts_with_all = ["encrypt_to", "no_prove", "jsonl", "signature", "signature_file"]
+    )]
+    pub elgamal_to: Option<String>,
+
+    /// Base64 signature over the canonical ballot made elsewhere, e.g. on an air-gapped device;
+    /// the ballot is proven without being signed here. Requires `--public-key`
+    #[arg(long, group = "external_signature", requires = "public_key", conflicts_with = "no_prove")]
+    pub signature: Option<String>,
+
+    /// File holding the `--signature` value
+    #[arg(long, group = "external_signature", requires = "public_key", conflicts_with = "no_prove")]
+    pub signature_file: Option<PathBuf>,
+
+    /// Write the exact guest input and poll parameters to this JSON file instead of proving, to
+    /// prove later with `--input` or attach to a bug report
+    #[arg(long, conflicts_with_all = ["no_prove", "receipt_out", "bundle"])]
+    pub dump_input: Option<PathBuf>,
+
+    /// Instead of proving, replay the guest's checks on the host and print each step with its
+    /// inputs and outcome: key import, message hash, signature, eligibility, nullifier and
+    /// commitment. Covers the single-ballot and batch methods
+    #[arg(long, conflicts_with_all = ["no_prove", "dump_input", "receipt_out", "bundle"])]
+    pub explain: bool,
+
+    /// Prove a guest input saved with `--dump-input`, with the guest method and poll parameters
+    /// it was saved with; the ballot, key and poll flags are not used
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "filenames", "jsonl", "no_prove", "signature", "signature_file", "encrypt_to",
+            "elgamal_to", "dump_input", "election_id",
+        ]
+    )]
+    pub input: Option<PathBuf>,
+
+    /// Before proving, write the exact executor input, with the prover, proof type, dev mode and
+    /// prover options of the run, to this file, readable by its owner only, to replay the run
+    /// with `--from-env`; Bonsai credentials are never recorded
+    #[arg(
+        long,
+        conflicts_with_all = ["no_prove", "dump_input", "explain", "checkpoint", "from_env"]
+    )]
+    pub dump_env: Option<PathBuf>,
+
+    /// Prove an executor input saved with `--dump-env`, with the guest method, poll parameters,
+    /// prover and proof type it was saved with; the ballot, key, poll and prover flags are not
+    /// used
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "filenames", "jsonl", "no_prove", "signature", "signature_file", "encrypt_to",
+            "elgamal_to", "dump_input", "input", "proof_type", "prover", "bonsai_poll_interval",
+            "election_id",
+        ]
+    )]
+    pub from_env: Option<PathBuf>,
+
+    /// Base64 public key the external signature verifies against, encoded as the guest method
+    /// for `--scheme` and `--curve` expects
+    #[arg(long, requires = "external_signature")]
+    pub public_key: Option<String>,
+}
+
+/// Flavor of the key-value pairs printed by `journal-to-kv`.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KvFormat {
+    /// `key=value` lines, quoted so a shell can source them.
+    #[default]
+    Env,
+    /// One flat JSON object.
+    Json,
+    /// A `key,value` header and one row per field.
+    Csv,
+}
+
+/// Command-line spelling of [BallotType].
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BallotTypeArg {
+    /// `choice` is a single option.
+    Single,
+    /// `choice` is an array of any number of distinct options.
+    Approval,
+    /// `choice` is an array of distinct options in order of preference.
+    Ranked,
+}
+
+impl From<BallotTypeArg> for BallotType {
+    fn from(arg: BallotTypeArg) -> Self {
+        match arg {
+            BallotTypeArg::Single => BallotType::Single,
+            BallotTypeArg::Approval => BallotType::Approval,
+            BallotTypeArg::Ranked => BallotType::Ranked,
+        }
+    }
+}
+
+/// Command-line spelling of the [MessageFormat]s that are not selected with `--eip191`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageFormatArg {
+    /// The file exactly as it is, hashed with SHA-256; any reformatting breaks the signature.
+    Raw,
+    /// The canonical JSON of the ballot, hashed with SHA-256.
+    JsonCanonical,
+    /// The canonical JSON as EIP-712 typed data, as `eth_signTypedData_v4` signs it; requires
+    /// `--scheme ecdsa --curve k256`.
+    Eip712,
+}
+
+impl From<MessageFormatArg> for MessageFormat {
+    fn from(arg: MessageFormatArg) -> Self {
+        match arg {
+            MessageFormatArg::Raw => MessageFormat::Raw,
+            MessageFormatArg::JsonCanonical => MessageFormat::Sha256,
+            MessageFormatArg::Eip712 => MessageFormat::Eip712,
+        }
+    }
+}
+
+/// Command-line spelling of the [JournalCodec]s.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalCodecArg {
+    /// Compact, but only practical to read from Rust.
+    Bincode,
+    /// Self-describing CBOR, readable with a stock decoder in JavaScript, Go and most languages.
+    Cbor,
+}
+
+impl From<JournalCodecArg> for JournalCodec {
+    fn from(arg: JournalCodecArg) -> Self {
+        match arg {
+            JournalCodecArg::Bincode => JournalCodec::Bincode,
+            JournalCodecArg::Cbor => JournalCodec::Cbor,
+        }
+    }
+}
+
+/// Command-line spelling of the [ProverChoice]s.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverArg {
+    /// Bonsai if `BONSAI_API_URL` is set, otherwise the local prover; `RISC0_DEV_MODE=1` fakes
+    /// the proof either way.
+    Default,
+    /// Bonsai, logging each change of the session's status and Bonsai's error message if it
+    /// fails.
+    Bonsai,
+}
+
+/// Command-line spelling of the [ProveMode]s a user can ask for.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofTypeArg {
+    /// A Groth16 receipt and the seal, journal and image ID for on-chain verification.
+    Groth16,
+    /// A succinct STARK receipt only, to be compressed to Groth16 later with `compress`.
+    Succinct,
+}
+
+/// Signature scheme used by the voter's key.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// ECDSA over the curve selected with `--curve`.
+    Ecdsa,
+    /// Ed25519, for voter clients that cannot produce ECDSA signatures.
+    Ed25519,
+    /// No signature: only prove whether the ballot's voter is at least `--min-age`, for flows
+    /// that check the signature elsewhere, e.g. on chain. Much cheaper to prove.
+    AgeOnly,
+}
+
+/// Signature curve used by the voter's key.
+///
+/// Each curve has its own guest method, since the guest binary can only be compiled against one
+/// verifying-key type.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    /// secp256k1, as used by Ethereum and Bitcoin wallets.
+    K256,
+    /// secp256r1 (NIST P-256), as used by WebAuthn / passkeys.
+    P256,
+}
+
+impl Curve {
+    /// Returns the guest ELF and image ID that verify signatures on this curve.
+    pub fn method(self) -> (&'static [u8], [u32; 8]) {
+        match self {
+            Curve::K256 => (K256_VERIFY_ELF, K256_VERIFY_ID),
+            Curve::P256 => (P256_VERIFY_ELF, P256_VERIFY_ID),
+        }
+    }
+}
+
+/// Reads the authority seed at `seed_file` and returns the scope of `election_id`, or `None` if
+/// neither is given.
+pub fn election_scope(
+    election_id: Option<&str>,
+    seed_file: Option<&Path>,
+) -> Result<Option<ElectionScope>> {
+    let (election_id, path) = match (election_id, seed_file) {
+        (Some(election_id), Some(path)) => (election_id, path),
+        (None, Some(_)) => bail!("--authority-seed-file needs --election-id"),
+        (_, None) => return Ok(None),
+    };
+    let authority_seed =
+        fs::read(path).with_context(|| format!("reading authority seed {}", path.display()))?;
+    // Built before the length check so a rejected seed is wiped as well.
+    let scope = ElectionScope { election_id: election_id.to_string(), authority_seed };
+    if scope.authority_seed.len() < MIN_AUTHORITY_SEED_LEN {
+        bail!(
+            "authority seed {} has {} bytes; use at least {MIN_AUTHORITY_SEED_LEN}",
+            path.display(),
+            scope.authority_seed.len()
+        );
+    }
+    Ok(Some(scope))
+}
+
+/// Reads a `--poll-meta` poll definition.
+pub fn read_poll_meta(path: &Path) -> Result<PollMeta> {
+    let poll: PollMeta = serde_json::from_str(&read_text_file(path)?)
+        .with_context(|| format!("{} is not a poll definition", path.display()))?;
+    if poll.options.is_empty() {
+        bail!("poll definition {} lists no options", path.display());
+    }
+    Ok(poll)
+}
+
+/// Reads a `--policy` eligibility policy.
+pub fn read_policy(path: &Path) -> Result<EligibilityPolicy> {
+    serde_json::from_str(&read_text_file(path)?)
+        .with_context(|| format!("{} is not an eligibility policy", path.display()))
+}
+
+impl Args {
+    /// The numeric ID of the `--poll-id`, which [Args::resolve_poll_id] ensures is set unless
+    /// proving a saved `--input` or voting in several `--polls`.
+    pub fn poll_id(&self) -> u64 {
+        self.poll_id.as_ref().expect("--poll-id is resolved before proving").poll_id()
+    }
+
+    /// Takes `--poll-id` from the `poll_id` the ballot files carry if it is not given, and checks
+    /// that every ballot naming a poll names that one, so a ballot signed for one poll is never
+    /// proven for another. The guest checks it again.
+    pub fn resolve_poll_id(&mut self) -> Result<()> {
+        if self.input.is_some() || self.from_env.is_some() || !self.polls.is_empty() {
+            return Ok(());
+        }
+        // The ballot the poll ID was taken from, if it was not given.
+        let mut first = None;
+        for filename in &self.filenames {
+            let Some(named) = read_ballot_poll_id(filename)? else {
+                continue;
+            };
+            match (&self.poll_id, first) {
+                (None, _) => {
+                    first = Some(filename);
+                    self.poll_id = Some(named);
+                }
+                (Some(poll_id), _) if poll_id.poll_id() == named.poll_id() => {}
+                (Some(poll_id), None) => {
+                    bail!("{filename} is for poll {named}, not --poll-id {poll_id}")
+                }
+                (Some(poll_id), Some(first)) => {
+                    bail!("{filename} is for poll {named}, but {first} is for poll {poll_id}")
+                }
+            }
+        }
+        if self.poll_id.is_none() {
+            bail!("--poll-id is required unless the ballots carry a `poll_id`");
+        }
+        Ok(())
+    }
+
+    /// Builds the per-poll parameters handed to the guest, rejecting flag combinations the guest
+    /// methods do not support.
+    pub fn vote_params(&self) -> Result<VoteParams> {
+        let message_format = self.message_format();
+        if message_format.needs_secp256k1()
+            && (self.scheme != Scheme::Ecdsa || self.curve != Curve::K256)
+        {
+            let flag = if self.eip191 { "--eip191" } else { "--message-format eip712" };
+            bail!("{flag} requires --scheme ecdsa --curve k256");
+        }
+        if self.encrypt_to.is_some() && (self.scheme != Scheme::Ecdsa || self.curve != Curve::K256) {
+            bail!("--encrypt-to requires --scheme ecdsa --curve k256");
+        }
+        if self.elgamal_to.is_some() {
+            if self.scheme != Scheme::Ecdsa || self.curve != Curve::K256 {
+                bail!("--elgamal-to requires --scheme ecdsa --curve k256");
+            }
+            if self.ballot_type == BallotTypeArg::Ranked {
+                bail!("--elgamal-to cannot tally ranked ballots");
+            }
+            // The vote is encrypted as one entry per option, so without options it would be empty.
+            if self.options.is_empty() && self.poll_meta.is_none() {
+                bail!("--elgamal-to needs the poll's options, from --options or --poll-meta");
+            }
+        }
+        if !self.polls.is_empty() && (self.scheme != Scheme::Ecdsa || self.curve != Curve::K256) {
+            bail!("--polls requires --scheme ecdsa --curve k256");
+        }
+        if self.min_age > self.max_age {
+            bail!("--min-age {} is greater than --max-age {}", self.min_age, self.max_age);
+        }
+        let poll = self.poll_meta.as_deref().map(read_poll_meta).transpose()?;
+        let poll_id = self.poll_id.as_ref().map(PollIdentifier::poll_id);
+        if let (Some(poll), Some(poll_id)) = (&poll, poll_id) {
+            if poll.poll_id != poll_id {
+                bail!("--poll-meta is for poll {}, not --poll-id {poll_id}", poll.poll_id);
+            }
+        }
+        let policy = self.policy.as_deref().map(read_policy).transpose()?;
+        if let Some(policy) = &policy {
+            for &poll_id in poll_id.iter().chain(&self.polls) {
+                policy.check_poll(poll_id).with_context(|| format!("poll {poll_id}"))?;
+            }
+        }
+        let params = VoteParams {
+            as_of: Some(self.as_of.unwrap_or_else(today)),
+            message_format,
+            ballot_type: self.ballot_type.into(),
+            options: poll.as_ref().map_or_else(|| self.options.clone(), |poll| poll.options.clone()),
+            age_range: AgeRange { min: self.min_age, max: self.max_age },
+            election: self.election_scope()?,
+            poll,
+            require_student: self.require_student,
+            policy,
+            journal_codec: self.journal_codec.into(),
+            poll_name: self.poll_id.as_ref().and_then(PollIdentifier::name).map(str::to_string),
+            allow_invalid_signature: false,
+            max_ballot_size: Some(self.max_ballot_size),
+            max_poll_id: self.max_poll_id,
+            author_key: None,
+        };
+        for &poll_id in poll_id.iter().chain(&self.polls) {
+            params.check_poll_id(poll_id)?;
+        }
+        Ok(params)
+    }
+
+    /// The message format selected with `--eip191` or `--message-format`.
+    pub fn message_format(&self) -> MessageFormat {
+        if self.eip191 {
+            MessageFormat::Eip191
+        } else {
+            self.message_format.into()
+        }
+    }
+
+    /// Reads the authority seed and returns the election scope, if `--election-id` is given.
+    pub fn election_scope(&self) -> Result<Option<ElectionScope>> {
+        election_scope(self.election_id.as_deref(), self.authority_seed_file.as_deref())
+    }
+
+    /// Returns how failed and timed-out proofs are retried.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            timeout: self.prove_timeout.map(Duration::from_secs),
+            ..RetryPolicy::new(self.prove_retries)
+        }
+    }
+
+    /// Returns the prover selected with `--prover` and `--bonsai-poll-interval`.
+    pub fn prover(&self) -> Result<ProverChoice> {
+        match self.prover {
+            ProverArg::Default if self.bonsai_poll_interval.is_some() => {
+                bail!("--bonsai-poll-interval requires --prover bonsai")
+            }
+            ProverArg::Default => Ok(ProverChoice::Default),
+            ProverArg::Bonsai => {
+                let secs = self.bonsai_poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+                Ok(ProverChoice::Bonsai { poll_interval: Duration::from_secs(secs) })
+            }
+        }
+    }
+
+    /// Returns the kind of receipt selected with `--proof-type`.
+    pub fn prove_mode(&self) -> ProveMode {
+        match self.proof_type {
+            ProofTypeArg::Groth16 => ProveMode::Groth16,
+            ProofTypeArg::Succinct => ProveMode::Succinct,
+        }
+    }
+
+    /// Returns the receipt cache to use, or `None` with `--no-cache`.
+    pub fn proof_cache(&self) -> Option<ProofCache> {
+        (!self.no_cache).then(|| ProofCache::new(&self.cache_dir))
+    }
+
+    /// Returns the externally made signature from `--signature` or `--signature-file`, if any.
+    pub fn external_signature(&self) -> Result<Option<String>> {
+        if let Some(path) = &self.signature_file {
+            let signature = fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            return Ok(Some(signature.trim().to_string()));
+        }
+        Ok(self.signature.clone())
+    }
+
+    /// Returns the guest ELF and image ID for a single ballot signed with the selected scheme, or
+    /// the encrypted-ballot methods with `--encrypt-to` or `--elgamal-to`, or the multi-poll
+    /// method with `--polls`.
+    pub fn method(&self) -> (&'static [u8], [u32; 8]) {
+        if !self.polls.is_empty() {
+            return (K256_VERIFY_MULTI_ELF, K256_VERIFY_MULTI_ID);
+        }
+        if self.encrypt_to.is_some() {
+            return (K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID);
+        }
+        if self.elgamal_to.is_some() {
+            return (K256_VERIFY_HOMOMORPHIC_ELF, K256_VERIFY_HOMOMORPHIC_ID);
+        }
+        match self.scheme {
+            Scheme::Ecdsa => self.curve.method(),
+            Scheme::Ed25519 => (ED25519_VERIFY_ELF, ED25519_VERIFY_ID),
+            Scheme::AgeOnly => (AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Where the tool's output goes: results on stdout, everything else on stderr.
+//!
+//! Status lines, progress and retry notices are logs, so they go to stderr and `--quiet` drops
+//! them. Stdout only carries what the command was asked to produce, so it can be piped.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use k256_methods::{K256_VERIFY_BATCH_ID, K256_VERIFY_MULTI_ID};
+use serde::Serialize;
+
+use crate::{
+    journal::{decode_method_journal, MethodJournal},
+    timings::Timings,
+};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Suppresses (or restores) status lines and progress for the rest of the process.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether `--quiet` was given.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Prints a status line to stderr unless `--quiet` was given.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::console::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// What a proving run produced, printed to stdout as one JSON object with `--output json`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ProofSummary {
+    /// Hex seal, with the verifier selector, as in `seal.dat`.
+    pub seal: String,
+    /// Hex journal as in `journal.dat`.
+    pub journal: String,
+    /// Hex SHA-256 digest of the journal, as in `journal_digest.dat`.
+    pub journal_digest: String,
+    /// Hex image ID of the guest method.
+    pub image_id: String,
+    /// Nullifier of a single-ballot proof.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullifier: Option<String>,
+    /// Nullifiers of a batch proof, in ballot order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullifiers: Option<Vec<String>>,
+    /// Directory the artifacts were written to.
+    pub out_dir: String,
+    /// File the whole receipt was dumped to, if `--receipt-out` was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<String>,
+    /// File the proof bundle was written to, if `--bundle` was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<String>,
+    /// Hex signature of the proving service over the journal, as in `author_signature.dat`, if
+    /// `--commit-author-signature` was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_signature: Option<String>,
+    /// Seconds spent in each phase of the proof, as in `timings.json`; left out for `compress`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+}
+
+/// The `nullifier` and `nullifiers` of the [ProofSummary] of `journal`, decoded as the guest
+/// method with `image_id` committed it: the list for the batch and multi-poll methods, the single
+/// nullifier otherwise.
+pub fn summary_nullifiers(
+    image_id: [u32; 8],
+    journal: &[u8],
+) -> Result<(Option<String>, Option<Vec<String>>)> {
+    let nullifiers = match decode_method_journal(image_id, journal)? {
+        MethodJournal::Reveals(reveals) => {
+            reveals.into_iter().map(|reveal| reveal.nullifier).collect()
+        }
+        MethodJournal::Eligibility(eligibility) => vec![eligibility.nullifier],
+    };
+    if [K256_VERIFY_BATCH_ID, K256_VERIFY_MULTI_ID].contains(&image_id) {
+        Ok((None, Some(nullifiers)))
+    } else {
+        Ok((nullifiers.into_iter().next(), None))
+    }
+}
+
+/// What a `--proof-type succinct` run produced, printed to stdout as one JSON object with
+/// `--output json`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SuccinctSummary {
+    /// File the succinct receipt was written to, for `compress`.
+    pub receipt: String,
+    /// Hex journal, as the compressed proof will commit it.
+    pub journal: String,
+    /// Hex SHA-256 digest of the journal.
+    pub journal_digest: String,
+    /// Hex image ID of the guest method.
+    pub image_id: String,
+    /// Seconds spent in each phase of the proof, as in `timings.json`.
+    pub timings: Timings,
+}
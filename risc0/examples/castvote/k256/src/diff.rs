@@ -0,0 +1,150 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Field-by-field comparison of two single-ballot journals, for tracking down why a proof does
+//! not match the one that was expected.
+
+use std::fmt;
+
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+use castvote_core::{JournalCodec, RevealInfo, CBOR_VERSION_FLAG};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::journal::decode_journal;
+
+/// The layout version and codec a journal was committed with, read from its version byte.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalVersion {
+    pub version: u8,
+    pub codec: JournalCodec,
+}
+
+impl JournalVersion {
+    /// Reads the version byte of a journal: ABI `bytes` wrapping the versioned payload.
+    pub fn of(journal: &[u8]) -> Result<Self> {
+        let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+        let &byte = encoded.first().context("journal is empty")?;
+        let codec = if byte & CBOR_VERSION_FLAG != 0 {
+            JournalCodec::Cbor
+        } else {
+            JournalCodec::Bincode
+        };
+        Ok(Self { version: byte & !CBOR_VERSION_FLAG, codec })
+    }
+}
+
+impl fmt::Display for JournalVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let codec = match self.codec {
+            JournalCodec::Bincode => "bincode",
+            JournalCodec::Cbor => "cbor",
+        };
+        write!(f, "{} ({codec})", self.version)
+    }
+}
+
+/// A field whose value differs between the two journals.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub left: Value,
+    pub right: Value,
+}
+
+/// The differences between two journals, as found by [diff_journals].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct JournalDiff {
+    pub left_version: JournalVersion,
+    pub right_version: JournalVersion,
+    /// Differing fields, in journal order.
+    pub fields: Vec<FieldDiff>,
+}
+
+impl JournalDiff {
+    /// Whether the journals were committed with different layout versions or codecs. Fields an
+    /// older version predates decode as `null`, so they show up as differences too.
+    pub fn versions_differ(&self) -> bool {
+        self.left_version != self.right_version
+    }
+
+    /// Whether the journals decode to the same reveal data with the same version and codec.
+    pub fn is_identical(&self) -> bool {
+        !self.versions_differ() && self.fields.is_empty()
+    }
+}
+
+/// Decodes both journals with [decode_journal] and compares them field by field.
+pub fn diff_journals(left: &[u8], right: &[u8]) -> Result<JournalDiff> {
+    let left_version = JournalVersion::of(left).context("first journal")?;
+    let right_version = JournalVersion::of(right).context("second journal")?;
+    let left = reveal_fields(&decode_journal(left).context("first journal")?);
+    let right = reveal_fields(&decode_journal(right).context("second journal")?);
+    let fields = left
+        .into_iter()
+        .zip(right)
+        .filter(|((_, left), (_, right))| left != right)
+        .map(|((field, left), (_, right))| FieldDiff { field, left, right })
+        .collect();
+    Ok(JournalDiff { left_version, right_version, fields })
+}
+
+/// The fields of `reveal` in journal order, with their values as they appear in JSON output.
+pub fn reveal_fields(reveal: &RevealInfo) -> Vec<(&'static str, Value)> {
+    let RevealInfo {
+        nullifier,
+        age,
+        is_student,
+        poll_id,
+        age_as_of,
+        ballot_type,
+        selection,
+        voter_key_hash,
+        age_range,
+        election_id,
+        poll_hash,
+        message_format,
+        student_required,
+        policy_hash,
+        poll_name,
+        receipt_id,
+        id_commitment,
+        max_poll_id,
+        author_key_hash,
+        abstained,
+    } = reveal;
+    vec![
+        ("nullifier", json!(nullifier)),
+        ("age", json!(age)),
+        ("is_student", json!(is_student)),
+        ("poll_id", json!(poll_id)),
+        ("age_as_of", json!(age_as_of)),
+        ("ballot_type", json!(ballot_type)),
+        ("selection", json!(selection)),
+        ("voter_key_hash", json!(voter_key_hash)),
+        ("age_range", json!(age_range)),
+        ("election_id", json!(election_id)),
+        ("poll_hash", json!(poll_hash)),
+        ("message_format", json!(message_format)),
+        ("student_required", json!(student_required)),
+        ("policy_hash", json!(policy_hash)),
+        ("poll_name", json!(poll_name)),
+        ("receipt_id", json!(receipt_id)),
+        ("id_commitment", json!(id_commitment)),
+        ("max_poll_id", json!(max_poll_id)),
+        ("author_key_hash", json!(author_key_hash)),
+        ("abstained", json!(abstained)),
+    ]
+}
@@ -0,0 +1,112 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Encrypting a ballot's choice to the election authority, and decrypting it on the authority's
+//! side for tallying.
+//!
+//! ECIES over secp256k1: an ephemeral key agrees a secret with the authority's key, HKDF-SHA256
+//! turns it into an AES-256-GCM key, and the JSON `choice` is encrypted under it. The compressed
+//! ephemeral key is both the HKDF salt and the associated data.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonicalize_ballot, decode_sec1_public_key, Choice, EncryptedChoice, EncryptedReveal,
+    RevealInfo, VoteParams, ECIES_INFO,
+};
+use hkdf::Hkdf;
+use k256::{
+    ecdh::{diffie_hellman, EphemeralSecret, SharedSecret},
+    PublicKey, SecretKey,
+};
+use serde_json::Value;
+use sha2::Sha256;
+
+/// Parses the authority's Base64 SEC1 public key, compressed or uncompressed.
+pub fn parse_authority_key(public_key: &str) -> Result<PublicKey> {
+    let bytes = decode_sec1_public_key(public_key).context("authority key")?;
+    PublicKey::from_sec1_bytes(&bytes).context("authority key is not a secp256k1 point")
+}
+
+fn cipher(shared: &SharedSecret, ephemeral_key: &[u8]) -> Aes256Gcm {
+    let mut key = Key::<Aes256Gcm>::default();
+    Hkdf::<Sha256>::new(Some(ephemeral_key), shared.raw_secret_bytes())
+        .expand(ECIES_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Aes256Gcm::new(&key)
+}
+
+/// Encrypts `choice` to `authority_key` with a fresh ephemeral key and nonce.
+pub fn encrypt_choice(authority_key: &PublicKey, choice: &Choice) -> Result<EncryptedChoice> {
+    let ephemeral = EphemeralSecret::random(&mut OsRng);
+    let ephemeral_key = ephemeral.public_key().to_sec1_bytes();
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(choice)?;
+    let ciphertext = cipher(&ephemeral.diffie_hellman(authority_key), &ephemeral_key)
+        .encrypt(&nonce, Payload { msg: &plaintext, aad: &ephemeral_key })
+        .map_err(|_| anyhow!("encrypting the choice failed"))?;
+    Ok(EncryptedChoice {
+        ephemeral_key: general_purpose::STANDARD.encode(ephemeral_key),
+        nonce: general_purpose::STANDARD.encode(nonce),
+        ciphertext: general_purpose::STANDARD.encode(ciphertext),
+    })
+}
+
+/// Decrypts a choice encrypted with [encrypt_choice], failing if it was encrypted to another key
+/// or tampered with.
+pub fn decrypt_choice(authority_secret: &SecretKey, encrypted: &EncryptedChoice) -> Result<Choice> {
+    let parts = encrypted.decode()?;
+    let ephemeral = PublicKey::from_sec1_bytes(&parts.ephemeral_key)
+        .context("ephemeral key is not a secp256k1 point")?;
+    let shared = diffie_hellman(authority_secret.to_nonzero_scalar(), ephemeral.as_affine());
+    let plaintext = cipher(&shared, &parts.ephemeral_key)
+        .decrypt(
+            &Nonce::from(parts.nonce),
+            Payload { msg: &parts.ciphertext, aad: &parts.ephemeral_key },
+        )
+        .map_err(|_| anyhow!("choice was not encrypted to this authority key or was tampered with"))?;
+    serde_json::from_slice(&plaintext).context("decrypted choice is not a valid choice")
+}
+
+/// Replaces the plaintext `choice` of `ballot` with its encryption to `authority_key` and returns
+/// the ballot in canonical form, ready to sign.
+pub fn encrypt_ballot(ballot: &str, authority_key: &PublicKey) -> Result<String> {
+    let mut value: Value = serde_json::from_str(ballot)?;
+    let object = value.as_object_mut().context("ballot is not a JSON object")?;
+    let Some(choice) = object.remove("choice") else {
+        bail!("ballot has no `choice` to encrypt");
+    };
+    let encrypted = encrypt_choice(authority_key, &serde_json::from_value(choice)?)?;
+    object.insert("encrypted_choice".into(), serde_json::to_value(encrypted)?);
+    Ok(canonicalize_ballot(&value.to_string())?)
+}
+
+/// Decrypts the choice committed in an encrypted-ballot journal and returns the reveal data with
+/// the selection filled in, validated against the poll's ballot type and options like a plaintext
+/// ballot would have been in the guest.
+pub fn reveal_selection(
+    authority_secret: &SecretKey,
+    encrypted: &EncryptedReveal,
+    params: &VoteParams,
+) -> Result<RevealInfo> {
+    let choice = decrypt_choice(authority_secret, &encrypted.encrypted_choice)?;
+    let mut reveal = encrypted.reveal.clone();
+    reveal.selection = choice.validate(params.ballot_type, &params.options)?;
+    Ok(reveal)
+}
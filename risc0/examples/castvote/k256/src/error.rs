@@ -0,0 +1,172 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Failure categories of `checkvote`, each with its own exit status so scripts can branch on how
+//! a run failed.
+
+use std::fmt;
+
+use castvote_core::{BallotError, DateError, EncodingError, VerifyError};
+use serde::Serialize;
+
+use crate::{ballot::BallotValidationError, prove::Timeout};
+
+/// Exit status of failures that fit no [VoteError] category, such as unreadable files.
+pub const EXIT_OTHER: u8 = 1;
+
+/// `error` of an [ErrorReport] for failures that fit no [VoteError] category.
+pub const ERROR_OTHER: &str = "Other";
+
+/// What kind of failure ended a run.
+///
+/// Attach it to an error with `.context(VoteError::…)` where the category is known; [VoteError::of]
+/// reads it back, falling back on the error types in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteError {
+    /// A ballot, flag or input file is invalid. Exit status 2, as for command-line usage errors.
+    InvalidInput,
+    /// A ballot signature does not verify against the voter's key. Exit status 3.
+    InvalidSignature,
+    /// The prover failed or timed out, including a guest that rejected its input. Exit status 4.
+    ProvingFailed,
+    /// A receipt, seal or journal does not check out against what it was verified with. Exit
+    /// status 5.
+    VerificationFailed,
+    /// A nullifier was used more than once in a poll. Exit status 6.
+    DoubleVote,
+}
+
+impl VoteError {
+    /// The process exit status of this category.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            VoteError::InvalidInput => 2,
+            VoteError::InvalidSignature => 3,
+            VoteError::ProvingFailed => 4,
+            VoteError::VerificationFailed => 5,
+            VoteError::DoubleVote => 6,
+        }
+    }
+
+    /// An error of this category caused by `message`.
+    pub fn error<M>(self, message: M) -> anyhow::Error
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        anyhow::Error::msg(message).context(self)
+    }
+
+    /// Adds this category to `err` unless it already has one, so the most specific category,
+    /// attached closest to the failure, wins.
+    pub fn tag(self, err: anyhow::Error) -> anyhow::Error {
+        match VoteError::of(&err) {
+            Some(_) => err,
+            None => err.context(self),
+        }
+    }
+
+    /// The category of `err`: the one attached with `.context`, or else the one implied by a
+    /// known error type in its chain.
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        if let Some(&category) = err.downcast_ref::<VoteError>() {
+            return Some(category);
+        }
+        err.chain().find_map(|cause| {
+            if cause.is::<BallotError>()
+                || cause.is::<BallotValidationError>()
+                || cause.is::<DateError>()
+                || cause.is::<serde_json::Error>()
+            {
+                Some(VoteError::InvalidInput)
+            } else if cause.is::<EncodingError>() || cause.is::<k256::ecdsa::Error>() {
+                Some(VoteError::InvalidSignature)
+            } else if cause.is::<Timeout>() {
+                Some(VoteError::ProvingFailed)
+            } else if cause.is::<VerifyError>() {
+                Some(VoteError::VerificationFailed)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The exit status `err` ends the process with.
+    pub fn exit_code_of(err: &anyhow::Error) -> u8 {
+        VoteError::of(err).map_or(EXIT_OTHER, VoteError::exit_code)
+    }
+
+    /// The stable name of this category in an [ErrorReport], e.g. `InvalidSignature`.
+    pub fn name(self) -> &'static str {
+        match self {
+            VoteError::InvalidInput => "InvalidInput",
+            VoteError::InvalidSignature => "InvalidSignature",
+            VoteError::ProvingFailed => "ProvingFailed",
+            VoteError::VerificationFailed => "VerificationFailed",
+            VoteError::DoubleVote => "DoubleVote",
+        }
+    }
+}
+
+/// A failure as printed with `--error-format json`, for scripts that branch on the kind of
+/// failure rather than parse the message.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// The [VoteError::name] of the failure's category, or [ERROR_OTHER].
+    pub error: String,
+    /// The error and its causes, outermost first, separated by `: `.
+    pub message: String,
+    /// The exit status the process ends with.
+    pub code: u8,
+}
+
+impl ErrorReport {
+    /// Describes `err`. The category tags attached with `.context` are left out of `message`,
+    /// since `error` already names the category.
+    pub fn new(err: &anyhow::Error) -> Self {
+        // A tag attached with `.context` shows up in the chain as its own message.
+        let tags = [
+            VoteError::InvalidInput,
+            VoteError::InvalidSignature,
+            VoteError::ProvingFailed,
+            VoteError::VerificationFailed,
+            VoteError::DoubleVote,
+        ]
+        .map(|category| category.to_string());
+        let causes: Vec<String> = err
+            .chain()
+            .map(ToString::to_string)
+            .filter(|cause| !tags.contains(cause))
+            .collect();
+        ErrorReport {
+            error: VoteError::of(err).map_or(ERROR_OTHER, VoteError::name).to_string(),
+            message: causes.join(": "),
+            code: VoteError::exit_code_of(err),
+        }
+    }
+}
+
+impl fmt::Display for VoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoteError::InvalidInput => write!(f, "invalid input"),
+            VoteError::InvalidSignature => write!(f, "invalid signature"),
+            VoteError::ProvingFailed => write!(f, "proving failed"),
+            VoteError::VerificationFailed => write!(f, "verification failed"),
+            VoteError::DoubleVote => write!(f, "double vote"),
+        }
+    }
+}
+
+impl std::error::Error for VoteError {}
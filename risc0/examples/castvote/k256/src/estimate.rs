@@ -0,0 +1,132 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Estimating what a proof will cost by running the guest in the executor only.
+
+use std::{fmt, time::Duration};
+
+#[cfg(feature = "prove")]
+use {
+    crate::prove::checked_image_id,
+    anyhow::Result,
+    castvote_core::VoteParams,
+    risc0_zkvm::{default_executor, ExecutorEnv},
+    serde::Serialize,
+};
+
+/// Proving throughput assumed unless `--cycles-per-second` says otherwise: a rough figure for
+/// CPU proving on a recent desktop. Measure your own prover and pass its rate for real planning.
+pub const DEFAULT_CYCLES_PER_SECOND: u64 = 100_000;
+
+/// Rates turning cycle counts into a proving time and cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostModel {
+    /// Proven cycles per second, padding included.
+    pub cycles_per_second: u64,
+    /// Price per million proven cycles, in whatever currency the prover bills in, if known.
+    pub cost_per_mcycle: Option<f64>,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self { cycles_per_second: DEFAULT_CYCLES_PER_SECOND, cost_per_mcycle: None }
+    }
+}
+
+/// Execution statistics of one guest run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Estimate {
+    /// Cycles the guest actually executed.
+    pub user_cycles: u64,
+    /// Cycles the prover works through: every segment padded to its power of two.
+    pub total_cycles: u64,
+    pub segments: usize,
+}
+
+impl Estimate {
+    /// Expected proving time under `model`.
+    pub fn proving_time(&self, model: &CostModel) -> Duration {
+        Duration::from_secs_f64(self.total_cycles as f64 / model.cycles_per_second.max(1) as f64)
+    }
+
+    /// Expected price under `model`, if it has a price.
+    pub fn cost(&self, model: &CostModel) -> Option<f64> {
+        model.cost_per_mcycle.map(|rate| self.total_cycles as f64 / 1e6 * rate)
+    }
+
+    /// The estimate under `model` as one JSON object, for `--output json`.
+    pub fn to_json(&self, model: &CostModel) -> serde_json::Value {
+        serde_json::json!({
+            "segments": self.segments,
+            "user_cycles": self.user_cycles,
+            "total_cycles": self.total_cycles,
+            "cycles_per_second": model.cycles_per_second,
+            "proving_time_secs": self.proving_time(model).as_secs_f64(),
+            "cost": self.cost(model),
+        })
+    }
+
+    /// A human-readable summary of the estimate under `model`.
+    pub fn report(&self, model: &CostModel) -> EstimateReport<'_> {
+        EstimateReport { estimate: self, model: *model }
+    }
+}
+
+/// [Estimate] rendered for a terminal, see [Estimate::report].
+pub struct EstimateReport<'a> {
+    estimate: &'a Estimate,
+    model: CostModel,
+}
+
+impl fmt::Display for EstimateReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { estimate, model } = self;
+        writeln!(f, "segments:      {}", estimate.segments)?;
+        writeln!(f, "user cycles:   {}", estimate.user_cycles)?;
+        writeln!(f, "total cycles:  {}", estimate.total_cycles)?;
+        write!(
+            f,
+            "proving time:  ~{:.0}s at {} cycles/s",
+            estimate.proving_time(model).as_secs_f64(),
+            model.cycles_per_second
+        )?;
+        if let Some(cost) = estimate.cost(model) {
+            write!(f, "\nproving cost:  ~{cost:.4}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the guest `elf` over `input` and `params` in the executor, without proving, and returns
+/// its execution statistics.
+///
+/// The input must be exactly what would be proven: an invalid signature or ballot makes the guest
+/// fail here just as it would in the prover.
+#[cfg(feature = "prove")]
+pub fn estimate_vote<T: Serialize>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+) -> Result<Estimate> {
+    checked_image_id(elf, image_id)?;
+    let env = ExecutorEnv::builder().write(input)?.write(params)?.build()?;
+    let session = default_executor().execute(env, elf)?;
+    Ok(Estimate {
+        user_cycles: session.cycles(),
+        total_cycles: session.segments.iter().map(|segment| 1u64 << segment.po2).sum(),
+        segments: session.segments.len(),
+    })
+}
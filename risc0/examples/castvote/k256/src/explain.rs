@@ -0,0 +1,276 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--explain`: the guest's checks replayed on the host, one traced step at a time, so a ballot
+//! the guest would reject shows which check fails and on what. Nothing is executed or proven.
+
+use std::fmt;
+
+use alloy_sol_types::SolValue;
+use anyhow::{anyhow, Result};
+use castvote_core::{
+    canonical_k256_key, canonical_p256_key, decode_ed25519_public_key, decode_k256_verifying_key,
+    decode_p256_verifying_key, encode_reveals_with, parse_ballot, scoped_nullifier, voter_key_hash,
+    Ballot, RevealInfo, SignedDigest, VoteParams,
+};
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    cli::{Curve, Scheme},
+    input::BallotInput,
+    keys::verify_signature,
+};
+
+/// Characters of a Base64 key or signature shown before the rest is elided.
+const SHOWN_CHARS: usize = 12;
+
+/// The outcome of one step of the guest's logic, replayed on the host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExplainStep {
+    pub name: &'static str,
+    pub pass: bool,
+    /// The step's inputs and result, or why it failed. Voter IDs and ballot contents that are
+    /// not committed are left out.
+    pub detail: String,
+}
+
+impl ExplainStep {
+    fn pass(name: &'static str, detail: String) -> Self {
+        Self {
+            name,
+            pass: true,
+            detail,
+        }
+    }
+
+    fn fail(name: &'static str, err: impl fmt::Display) -> Self {
+        Self {
+            name,
+            pass: false,
+            detail: err.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ExplainStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pass {
+            true => write!(f, "PASS {}: {}", self.name, self.detail),
+            false => write!(f, "FAIL {}: {}", self.name, self.detail),
+        }
+    }
+}
+
+/// Replays what the single-ballot method for `scheme` and `curve` does with `input`: key import,
+/// the signed text and its digest, signature verification, the eligibility checks, the nullifier
+/// and the reveal entry. Returns the steps run and the entry, if every step passed.
+///
+/// Steps that need the result of a failed one are not run.
+pub fn explain_ballot(
+    scheme: Scheme,
+    curve: Curve,
+    input: &BallotInput,
+    params: &VoteParams,
+) -> (Vec<ExplainStep>, Option<RevealInfo>) {
+    let (signature, ballot, poll_id, public_key) = input;
+    let mut steps = Vec::new();
+
+    let key = import_key(scheme, curve, public_key);
+    steps.push(match &key {
+        Ok(key) => {
+            let key_hash = hex::encode(voter_key_hash(key));
+            let detail = format!(
+                "{} key {}, voter key hash {key_hash}",
+                key_kind(scheme, curve),
+                elide(public_key)
+            );
+            ExplainStep::pass("key import", detail)
+        }
+        Err(err) => ExplainStep::fail(
+            "key import",
+            format!(
+                "{} key {}: {err:#}",
+                key_kind(scheme, curve),
+                elide(public_key)
+            ),
+        ),
+    });
+
+    let signed_text = match params.message_format.signed_text(ballot) {
+        Ok(text) => text,
+        Err(err) => {
+            steps.push(ExplainStep::fail(
+                "message hash",
+                format!("the ballot is not JSON: {err}"),
+            ));
+            return (steps, None);
+        }
+    };
+    let digest = match params.message_format.digest(signed_text.as_bytes()) {
+        SignedDigest::Sha256(hash) => format!("SHA-256 {}", hex::encode(hash)),
+        SignedDigest::Prehash(digest) => format!("prehashed {}", hex::encode(digest)),
+    };
+    let detail = format!(
+        "{:?} signed text of {} bytes, digest {digest}",
+        params.message_format,
+        signed_text.len()
+    );
+    steps.push(ExplainStep::pass("message hash", detail));
+
+    let verified = verify_signature(
+        scheme,
+        curve,
+        &signed_text,
+        signature,
+        public_key,
+        params.message_format,
+    );
+    let verified_ok = verified.is_ok();
+    steps.push(match verified {
+        Ok(()) => ExplainStep::pass(
+            "signature verification",
+            format!("signature {} is valid", elide(signature)),
+        ),
+        Err(err) => ExplainStep::fail(
+            "signature verification",
+            format!("signature {}: {err:#}", elide(signature)),
+        ),
+    });
+
+    let ballot = match parse_ballot(&signed_text) {
+        Ok(ballot) => ballot,
+        Err(err) => {
+            steps.push(ExplainStep::fail(
+                "eligibility",
+                format!("the ballot does not parse: {err}"),
+            ));
+            return (steps, None);
+        }
+    };
+    let eligibility = check_eligibility(&ballot, *poll_id, params);
+    let eligible = eligibility.pass;
+    steps.push(eligibility);
+
+    let nullifier = hex::encode(scoped_nullifier(
+        &ballot.id,
+        *poll_id,
+        params.election.as_ref(),
+    ));
+    let salt = match &params.election {
+        Some(election) => format!("the salt of election {}", election.election_id),
+        None => "the fixed salt".to_string(),
+    };
+    let detail = format!("voter ID (redacted), poll {poll_id} and {salt}: {nullifier}");
+    steps.push(ExplainStep::pass("nullifier derivation", detail));
+
+    // Like the guest, commit nothing for a ballot that failed a check.
+    let (Ok(key), true, true) = (key, verified_ok, eligible) else {
+        return (steps, None);
+    };
+    match RevealInfo::new(&ballot, *poll_id, &key, params) {
+        Ok(reveal) => {
+            let detail = format!(
+                "age {}, student {}, selection {:?}",
+                reveal.age, reveal.is_student, reveal.selection
+            );
+            steps.push(ExplainStep::pass("reveal entry", detail));
+            (steps, Some(reveal))
+        }
+        Err(err) => {
+            steps.push(ExplainStep::fail("reveal entry", err));
+            (steps, None)
+        }
+    }
+}
+
+/// The journal the method commits for `reveals`: one entry for a single-ballot method, all of
+/// them in order for the batch method, ABI-encoded as the guest commits them.
+pub fn explain_commitment(reveals: &[RevealInfo], batch: bool, params: &VoteParams) -> ExplainStep {
+    let journal = match (batch, reveals) {
+        (false, [reveal]) => reveal.encode_with(params.journal_codec),
+        (false, _) => {
+            return ExplainStep::fail("commitment", anyhow!("expected a single reveal entry"))
+        }
+        (true, _) => encode_reveals_with(reveals, params.journal_codec),
+    }
+    .abi_encode();
+    let detail = format!(
+        "{:?} journal of {} bytes, digest {}",
+        params.journal_codec,
+        journal.len(),
+        hex::encode(Sha256::digest(&journal))
+    );
+    ExplainStep::pass("commitment", detail)
+}
+
+fn import_key(scheme: Scheme, curve: Curve, public_key: &str) -> Result<Vec<u8>> {
+    Ok(match (scheme, curve) {
+        (Scheme::Ecdsa, Curve::K256) => canonical_k256_key(&decode_k256_verifying_key(public_key)?),
+        (Scheme::Ecdsa, Curve::P256) => canonical_p256_key(&decode_p256_verifying_key(public_key)?),
+        (Scheme::Ed25519, _) => decode_ed25519_public_key(public_key)?.to_vec(),
+        (Scheme::AgeOnly, _) => return Err(anyhow!("--scheme age-only has no key")),
+    })
+}
+
+fn key_kind(scheme: Scheme, curve: Curve) -> &'static str {
+    match (scheme, curve) {
+        (Scheme::Ecdsa, Curve::K256) => "secp256k1",
+        (Scheme::Ecdsa, Curve::P256) => "P-256",
+        (Scheme::Ed25519, _) => "Ed25519",
+        (Scheme::AgeOnly, _) => "no",
+    }
+}
+
+/// The checks [RevealInfo::new] makes before building the entry, in the same order, traced.
+fn check_eligibility(ballot: &Ballot, poll_id: u64, params: &VoteParams) -> ExplainStep {
+    let age = match ballot.age(params) {
+        Ok(age) => age,
+        Err(err) => return ExplainStep::fail("eligibility", err),
+    };
+    let checks = ballot
+        .check_poll(poll_id)
+        .and_then(|()| params.check_student(ballot))
+        .and_then(|()| params.age_range.check(age))
+        .and_then(|age| match &params.policy {
+            Some(policy) => policy.check(age, ballot.is_student, poll_id),
+            None => Ok(()),
+        })
+        .and_then(|()| ballot.selection(params));
+    let rules = format!(
+        "age {age} in {}, student {} (required: {}), policy {}",
+        params.age_range,
+        ballot.is_student,
+        params.require_student,
+        if params.policy.is_some() {
+            "applied"
+        } else {
+            "none"
+        }
+    );
+    match checks {
+        Ok(selection) => {
+            ExplainStep::pass("eligibility", format!("{rules}, selection {selection:?}"))
+        }
+        Err(err) => ExplainStep::fail("eligibility", format!("{rules}: {err}")),
+    }
+}
+
+/// The first characters of a Base64 value and its length, enough to tell values apart.
+fn elide(value: &str) -> String {
+    match value.get(..SHOWN_CHARS) {
+        Some(shown) if value.len() > SHOWN_CHARS => format!("{shown}… ({} chars)", value.len()),
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Host side of homomorphically tallied polls: preparing the guest's private ElGamal input and
+//! combining the committed ciphertexts into an encrypted tally.
+//!
+//! The arithmetic itself lives in `castvote-core`, shared with the guest method; trustees call
+//! [partial_decrypt](castvote_core::partial_decrypt) on the aggregate and anyone can then run
+//! [combine_all_decryptions](castvote_core::combine_all_decryptions) to read the totals. When the
+//! authority holds every key, [reveal_tallies] does both in the reveal phase.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use aes_gcm::aead::OsRng;
+use anyhow::{bail, Context, Result};
+use castvote_core::{
+    aggregate, combine_all_decryptions, election_key, partial_decrypt, Ciphertext, ElGamalInput,
+    HomomorphicReveal,
+};
+use k256::{elliptic_curve::PrimeField, NonZeroScalar, PublicKey, SecretKey};
+use serde::Serialize;
+
+use crate::{
+    cli::{Curve, Scheme},
+    decode_homomorphic_journal,
+    error::VoteError,
+    keyfile::{read_private_key_file, KeyPassword},
+    keys::VoterKey,
+    read_journal_file,
+};
+
+/// Draws fresh randomness for encrypting a vote over `options` options to `election_key`.
+pub fn elgamal_input(election_key: &PublicKey, options: usize) -> ElGamalInput {
+    ElGamalInput {
+        election_key: election_key.to_sec1_bytes().to_vec(),
+        randomness: (0..options).map(|_| NonZeroScalar::random(&mut OsRng).to_repr().into()).collect(),
+    }
+}
+
+/// Aggregates the encrypted votes of `reveals` into the encrypted per-option totals of one poll.
+///
+/// Every reveal must come from `poll_id` and be encrypted to the same election key, since
+/// ciphertexts under different keys cannot be combined. Nullifiers are not checked here; drop
+/// duplicate ballots before aggregating.
+pub fn aggregate_reveals(reveals: &[HomomorphicReveal], poll_id: u64) -> Result<Vec<Ciphertext>> {
+    let Some(first) = reveals.first() else {
+        bail!("no encrypted votes to aggregate");
+    };
+    for reveal in reveals {
+        if reveal.reveal.poll_id != poll_id {
+            bail!("vote {} is for poll {}, not {poll_id}", reveal.reveal.nullifier, reveal.reveal.poll_id);
+        }
+        if reveal.election_key != first.election_key {
+            bail!("vote {} is encrypted to a different election key", reveal.reveal.nullifier);
+        }
+    }
+    let votes: Vec<_> = reveals.iter().map(|reveal| reveal.ciphertexts.clone()).collect();
+    Ok(aggregate(&votes)?)
+}
+
+/// The decrypted totals of one poll, as revealed by [reveal_tallies].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RevealedTally {
+    pub poll_id: u64,
+    /// Ballots counted, after dropping duplicate nullifiers.
+    pub ballots: usize,
+    /// Ballots dropped because their nullifier was already counted in the poll.
+    pub duplicates: usize,
+    /// Votes per poll option, in the order the options were encrypted in.
+    pub totals: Vec<u64>,
+}
+
+/// Reveals the per-option totals of every poll in `reveals` with the secret keys of all the
+/// authority's trustees: ballots are deduplicated by nullifier (the first one is kept), the
+/// ciphertexts of each poll are aggregated, and only the aggregate is ever decrypted, so no
+/// individual vote is revealed.
+///
+/// Every vote must be encrypted to the election key of `authority_keys`; a vote encrypted to
+/// another key would decrypt to garbage, so it fails the reveal instead.
+pub fn reveal_tallies(
+    reveals: &[HomomorphicReveal],
+    authority_keys: &[SecretKey],
+) -> Result<Vec<RevealedTally>> {
+    let public_keys: Vec<PublicKey> = authority_keys.iter().map(SecretKey::public_key).collect();
+    let key = election_key(&public_keys)?;
+
+    let mut polls: BTreeMap<u64, (Vec<HomomorphicReveal>, usize)> = BTreeMap::new();
+    let mut counted = HashSet::new();
+    for reveal in reveals {
+        let encrypted_to = PublicKey::from_sec1_bytes(&reveal.election_key).ok();
+        if encrypted_to != Some(key) {
+            let nullifier = &reveal.reveal.nullifier;
+            let message = format!("vote {nullifier} is not encrypted to the authority's key");
+            return Err(VoteError::InvalidInput.error(message));
+        }
+        let (votes, duplicates) = polls.entry(reveal.reveal.poll_id).or_default();
+        if counted.insert((reveal.reveal.poll_id, reveal.reveal.nullifier.clone())) {
+            votes.push(reveal.clone());
+        } else {
+            *duplicates += 1;
+        }
+    }
+
+    polls
+        .into_iter()
+        .map(|(poll_id, (votes, duplicates))| {
+            let tally = aggregate_reveals(&votes, poll_id)?;
+            let partials = authority_keys
+                .iter()
+                .map(|secret| partial_decrypt(secret, &tally))
+                .collect::<Result<Vec<_>, _>>()?;
+            let totals = combine_all_decryptions(&tally, &partials, votes.len() as u64)
+                .with_context(|| format!("decrypting the tally of poll {poll_id}"))?;
+            Ok(RevealedTally { poll_id, ballots: votes.len(), duplicates, totals })
+        })
+        .collect()
+}
+
+/// Decodes every homomorphic journal matching `pattern`, in sorted path order so the choice of
+/// which duplicate is kept is reproducible.
+pub fn read_homomorphic_journals(pattern: &str) -> Result<Vec<HomomorphicReveal>> {
+    let mut paths = glob::glob(pattern)
+        .with_context(|| format!("invalid glob pattern {pattern:?}"))?
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            decode_homomorphic_journal(&read_journal_file(path)?)
+                .with_context(|| path.display().to_string())
+                .context(VoteError::InvalidInput)
+        })
+        .collect()
+}
+
+/// Reads a trustee's secret key from a private key file as written by `keygen --curve k256`,
+/// plain Base64 or encrypted under `password`.
+pub fn read_authority_secret(
+    path: &Path,
+    password: Option<&KeyPassword>,
+) -> Result<SecretKey> {
+    match read_private_key_file(path, Scheme::Ecdsa, Curve::K256, password)? {
+        VoterKey::K256(key) => Ok(SecretKey::from(key.as_nonzero_scalar())),
+        _ => unreachable!("read as a secp256k1 key"),
+    }
+}
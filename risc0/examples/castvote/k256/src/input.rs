@@ -0,0 +1,417 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The exact input handed to a guest method, saved to a file and proven from later.
+//!
+//! A dump holds everything the prover writes to the `ExecutorEnv` but the election authority's
+//! seed, so attaching it to a bug report reproduces the run without the ballots, keys or flags
+//! that produced it. An environment dump adds the prover settings of the run and a digest of the
+//! exact words written.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use castvote_core::{
+    AgeEligibilityInput, ElGamalInput, ElectionScope, MessageFormat, VoteParams,
+};
+use k256_methods::{
+    AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID, ED25519_VERIFY_ELF, ED25519_VERIFY_ID, K256_VERIFY_BATCH_ELF, K256_VERIFY_BATCH_ID,
+    K256_VERIFY_ELF, K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID,
+    K256_VERIFY_HOMOMORPHIC_ELF, K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID,
+    K256_VERIFY_MULTI_ELF, K256_VERIFY_MULTI_ID, P256_VERIFY_ELF, P256_VERIFY_ID,
+};
+use risc0_zkvm::sha::{Digest, Impl, Sha256};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "prove")]
+use crate::prove::prover_options;
+use crate::{
+    cli::{election_scope, Curve, Scheme},
+    error::VoteError,
+    keys::verify_signature,
+    nullifier_store::expected_nullifier,
+    output::atomic_write_private,
+    prove::{ProveMode, ProverChoice},
+};
+
+/// Version of the dump layout; bumped whenever [InputDump] or [GuestInput] changes shape.
+pub const INPUT_FORMAT_VERSION: u32 = 1;
+
+/// Version of the environment dump layout; bumped whenever [EnvDump] changes shape.
+pub const ENV_FORMAT_VERSION: u32 = 1;
+
+/// The guest methods the tool proves with, by name.
+pub const METHODS: [(&str, &[u8], [u32; 8]); 8] = [
+    ("k256_verify", K256_VERIFY_ELF, K256_VERIFY_ID),
+    ("p256_verify", P256_VERIFY_ELF, P256_VERIFY_ID),
+    ("ed25519_verify", ED25519_VERIFY_ELF, ED25519_VERIFY_ID),
+    ("k256_verify_encrypted", K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID),
+    ("k256_verify_homomorphic", K256_VERIFY_HOMOMORPHIC_ELF, K256_VERIFY_HOMOMORPHIC_ID),
+    ("k256_verify_batch", K256_VERIFY_BATCH_ELF, K256_VERIFY_BATCH_ID),
+    ("k256_verify_multi", K256_VERIFY_MULTI_ELF, K256_VERIFY_MULTI_ID),
+    ("age_eligibility", AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID),
+];
+
+/// Name of the method with `image_id`, if it is one of [METHODS].
+pub fn method_name(image_id: [u32; 8]) -> Option<&'static str> {
+    METHODS.iter().find(|(_, _, id)| *id == image_id).map(|(name, _, _)| *name)
+}
+
+/// The signature scheme and curve the method with `image_id` verifies ballots with.
+pub fn method_scheme(image_id: [u32; 8]) -> (Scheme, Curve) {
+    match method_name(image_id) {
+        Some("p256_verify") => (Scheme::Ecdsa, Curve::P256),
+        Some("ed25519_verify") => (Scheme::Ed25519, Curve::K256),
+        _ => (Scheme::Ecdsa, Curve::K256),
+    }
+}
+
+/// The method called `name` in [METHODS].
+pub fn method_by_name(name: &str) -> Result<(&'static [u8], [u32; 8])> {
+    match METHODS.iter().find(|(method, _, _)| *method == name) {
+        Some((_, elf, image_id)) => Ok((elf, *image_id)),
+        None => bail!("unknown guest method {name:?}"),
+    }
+}
+
+/// `(signature, ballot, poll_id, public_key)` as read by the single-ballot guest methods.
+pub type BallotInput = (String, String, u64, String);
+
+/// `(signature, ballot, poll_ids, public_key)` as read by the multi-poll guest method.
+pub type MultiPollInput = (String, String, Vec<u64>, String);
+
+/// The first value written to the `ExecutorEnv`, in the shape its guest method reads.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "input", rename_all = "snake_case")]
+pub enum GuestInput {
+    /// One signed ballot, for every single-ballot method but the homomorphic one.
+    Ballot(BallotInput),
+    /// Every ballot of a batch, in order.
+    Batch(Vec<BallotInput>),
+    /// One signed ballot and its encryption randomness.
+    Homomorphic(BallotInput, ElGamalInput),
+    /// One signed ballot voting in several polls.
+    MultiPoll(MultiPollInput),
+    /// One unsigned ballot and the age threshold it is checked against.
+    AgeEligibility(AgeEligibilityInput),
+}
+
+impl GuestInput {
+    /// The `(signature, ballot, public_key)` of every signed ballot in the input, in order.
+    pub fn signed_ballots(&self) -> Vec<(&str, &str, &str)> {
+        fn signed((signature, ballot, _, public_key): &BallotInput) -> (&str, &str, &str) {
+            (signature, ballot, public_key)
+        }
+        match self {
+            GuestInput::Ballot(input) | GuestInput::Homomorphic(input, _) => vec![signed(input)],
+            GuestInput::Batch(inputs) => inputs.iter().map(signed).collect(),
+            GuestInput::MultiPoll((signature, ballot, _, public_key)) => {
+                vec![(signature, ballot, public_key)]
+            }
+            GuestInput::AgeEligibility(_) => Vec::new(),
+        }
+    }
+
+    /// The `(poll_id, nullifier)` of every reveal entry the guest will commit for the input, in
+    /// order, computed with [expected_nullifier].
+    pub fn nullifiers(&self, params: &VoteParams) -> Result<Vec<(u64, String)>> {
+        let election = params.election.as_ref();
+        let entry = |ballot: &str, poll_id: u64| {
+            Ok((poll_id, expected_nullifier(ballot, poll_id, election)?))
+        };
+        match self {
+            GuestInput::Ballot((_, ballot, poll_id, _))
+            | GuestInput::Homomorphic((_, ballot, poll_id, _), _)
+            | GuestInput::AgeEligibility((ballot, poll_id, _)) => {
+                Ok(vec![entry(ballot, *poll_id)?])
+            }
+            GuestInput::Batch(inputs) => {
+                inputs.iter().map(|(_, ballot, poll_id, _)| entry(ballot, *poll_id)).collect()
+            }
+            GuestInput::MultiPoll((_, ballot, poll_ids, _)) => {
+                poll_ids.iter().map(|&poll_id| entry(ballot, poll_id)).collect()
+            }
+        }
+    }
+
+    /// Checks every signature in the input against its ballot and public key the way the method
+    /// with `image_id` will, so a signature the guest would reject fails in milliseconds rather
+    /// than after the guest has run. Fails with [VoteError::InvalidSignature].
+    pub fn check_signatures(
+        &self,
+        image_id: [u32; 8],
+        message_format: MessageFormat,
+    ) -> Result<()> {
+        let (scheme, curve) = method_scheme(image_id);
+        let signed = self.signed_ballots();
+        let count = signed.len();
+        for (i, &(signature, ballot, public_key)) in signed.iter().enumerate() {
+            verify_signature(scheme, curve, ballot, signature, public_key, message_format)
+                .with_context(|| match count {
+                    1 => "checking the signature before proving".to_string(),
+                    _ => format!("checking the signature of ballot {} of {count}", i + 1),
+                })
+                .context(VoteError::InvalidSignature)?;
+        }
+        Ok(())
+    }
+
+    /// The words the prover writes to the `ExecutorEnv` for the input and `params`, in order:
+    /// the input in the shape its method reads, then the parameters.
+    pub fn env_words(&self, params: &VoteParams) -> Result<Vec<u32>> {
+        use risc0_zkvm::serde::to_vec;
+        let mut words = match self {
+            GuestInput::Ballot(input) => to_vec(input),
+            GuestInput::Batch(inputs) => to_vec(inputs),
+            GuestInput::Homomorphic(input, elgamal) => to_vec(&(input, elgamal)),
+            GuestInput::MultiPoll(input) => to_vec(input),
+            GuestInput::AgeEligibility(input) => to_vec(input),
+        }
+        .context("serializing the guest input")?;
+        words.extend(to_vec(params).context("serializing the poll parameters")?);
+        Ok(words)
+    }
+
+    /// SHA-256 of [GuestInput::env_words].
+    pub fn env_digest(&self, params: &VoteParams) -> Result<Digest> {
+        Ok(*Impl::hash_words(&self.env_words(params)?))
+    }
+}
+
+impl From<BallotInput> for GuestInput {
+    fn from(input: BallotInput) -> Self {
+        GuestInput::Ballot(input)
+    }
+}
+
+impl From<Vec<BallotInput>> for GuestInput {
+    fn from(inputs: Vec<BallotInput>) -> Self {
+        GuestInput::Batch(inputs)
+    }
+}
+
+impl From<(BallotInput, ElGamalInput)> for GuestInput {
+    fn from((input, elgamal): (BallotInput, ElGamalInput)) -> Self {
+        GuestInput::Homomorphic(input, elgamal)
+    }
+}
+
+impl From<MultiPollInput> for GuestInput {
+    fn from(input: MultiPollInput) -> Self {
+        GuestInput::MultiPoll(input)
+    }
+}
+
+impl From<AgeEligibilityInput> for GuestInput {
+    fn from(input: AgeEligibilityInput) -> Self {
+        GuestInput::AgeEligibility(input)
+    }
+}
+
+/// A guest input as saved by `--dump-input` and read back by `--input`.
+///
+/// The authority seed of an election is never recorded: it protects the nullifier salt of every
+/// election and the ID-commitment secret of every voter, so [InputDump::restore_seed] reads it
+/// again from `--authority-seed-file` before proving.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InputDump {
+    /// [INPUT_FORMAT_VERSION] of the build that wrote the dump.
+    pub version: u32,
+    /// Name of the guest method, see [METHODS].
+    pub method: String,
+    /// Hex image ID the input was prepared for.
+    pub image_id: String,
+    /// The second value written to the `ExecutorEnv`, with an empty authority seed.
+    pub params: VoteParams,
+    pub input: GuestInput,
+}
+
+impl InputDump {
+    /// Records `input` and `params` as prepared for the method with `image_id`.
+    pub fn new(input: GuestInput, params: &VoteParams, image_id: [u32; 8]) -> Result<Self> {
+        let method = method_name(image_id).context("input for an unknown guest method")?;
+        Ok(Self {
+            version: INPUT_FORMAT_VERSION,
+            method: method.into(),
+            image_id: Digest::from(image_id).to_string(),
+            params: without_seed(params),
+            input,
+        })
+    }
+
+    /// Puts back the authority seed of a dump made for an election, read from `seed_file`
+    /// (`--authority-seed-file`), so the parameters are again the ones the guest reads.
+    pub fn restore_seed(&mut self, seed_file: Option<&Path>) -> Result<()> {
+        match (&self.params.election, seed_file) {
+            (None, None) => Ok(()),
+            (None, Some(_)) => {
+                bail!("the input is not scoped to an election; drop --authority-seed-file")
+            }
+            (Some(election), None) => bail!(
+                "the input is for election {:?} and does not record its authority seed; pass \
+                 --authority-seed-file",
+                election.election_id
+            ),
+            (Some(election), Some(path)) => {
+                self.params.election = election_scope(Some(&election.election_id), Some(path))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the ELF and image ID of the method this build has under the dump's method name,
+    /// and whether the image ID still matches the one the dump was made for.
+    pub fn method(&self) -> Result<(&'static [u8], [u32; 8], bool)> {
+        let (elf, image_id) = method_by_name(&self.method)?;
+        Ok((elf, image_id, Digest::from(image_id).to_string() == self.image_id))
+    }
+}
+
+/// A guest input and the prover settings it was proven with, as saved by `--dump-env` and
+/// read back by `--from-env`.
+///
+/// Credentials, such as the Bonsai API key and URL, are never recorded, nor is the authority seed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EnvDump {
+    /// [ENV_FORMAT_VERSION] of the build that wrote the dump.
+    pub version: u32,
+    /// Version of the tool that wrote the dump.
+    pub tool_version: String,
+    /// Hex SHA-256 of the words written to the `ExecutorEnv`, see [GuestInput::env_digest].
+    pub env_digest: String,
+    pub prover: ProverSettings,
+    pub input: InputDump,
+}
+
+/// How the prover was set up for the run an [EnvDump] records.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProverSettings {
+    /// The prover selected with `--prover`.
+    pub choice: ProverChoice,
+    /// The kind of receipt selected with `--proof-type`.
+    pub mode: ProveMode,
+    /// Whether the prover made a fake dev-mode receipt.
+    pub dev_mode: bool,
+    /// Hash function of the STARK proving protocol.
+    pub hashfn: String,
+    /// Kind of receipt the prover was asked for.
+    pub receipt_kind: String,
+}
+
+#[cfg(feature = "prove")]
+impl ProverSettings {
+    /// The settings this build proves with on `choice` in `mode`, dev mode included.
+    pub fn current(choice: ProverChoice, mode: ProveMode) -> Self {
+        let (_, opts) = prover_options(mode);
+        Self {
+            choice,
+            mode,
+            dev_mode: opts.dev_mode(),
+            hashfn: opts.hashfn.clone(),
+            receipt_kind: format!("{:?}", opts.receipt_kind),
+        }
+    }
+}
+
+impl EnvDump {
+    /// Records `input` and `params` as prepared for the method with `image_id` and proven with
+    /// `prover`. The digest covers the authority seed the dump leaves out.
+    pub fn new(
+        input: GuestInput,
+        params: &VoteParams,
+        image_id: [u32; 8],
+        prover: ProverSettings,
+    ) -> Result<Self> {
+        let env_digest = input.env_digest(params)?;
+        Ok(Self {
+            version: ENV_FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").into(),
+            env_digest: env_digest.to_string(),
+            prover,
+            input: InputDump::new(input, params, image_id)?,
+        })
+    }
+
+    /// Checks that the recorded input still serializes to the words the digest was taken over,
+    /// which fails if the dump was edited, this build lays the input out differently or, after
+    /// [InputDump::restore_seed], the authority seed is not the one the run used.
+    pub fn check_digest(&self) -> Result<()> {
+        let actual = self.input.input.env_digest(&self.input.params)?.to_string();
+        if actual != self.env_digest {
+            bail!(
+                "the executor input hashes to {actual}, not the recorded {}; the dump was edited \
+                 or written by an incompatible build",
+                self.env_digest
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Writes `dump` to `path` as JSON, readable by the owner only: it holds whatever the guest reads
+/// privately, such as ElGamal randomness or a birthdate.
+pub fn write_input(path: &Path, dump: &InputDump) -> Result<()> {
+    atomic_write_private(path, format!("{}\n", serde_json::to_string_pretty(dump)?))
+}
+
+/// Reads a dump written by [write_input], rejecting versions this build does not know.
+pub fn read_input(path: &Path) -> Result<InputDump> {
+    let contents =
+        std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let version: Versioned = serde_json::from_slice(&contents)
+        .with_context(|| format!("{} is not an input dump", path.display()))?;
+    if version.version != INPUT_FORMAT_VERSION {
+        bail!(
+            "{} has input format version {}, this build reads version {INPUT_FORMAT_VERSION}",
+            path.display(),
+            version.version
+        );
+    }
+    serde_json::from_slice(&contents).with_context(|| format!("decoding {}", path.display()))
+}
+
+/// Writes `dump` to `path` as JSON, readable by the owner only, like [write_input].
+pub fn write_env(path: &Path, dump: &EnvDump) -> Result<()> {
+    atomic_write_private(path, format!("{}\n", serde_json::to_string_pretty(dump)?))
+}
+
+/// Reads a dump written by [write_env], rejecting versions this build does not know.
+pub fn read_env(path: &Path) -> Result<EnvDump> {
+    let contents =
+        std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let version: Versioned = serde_json::from_slice(&contents)
+        .with_context(|| format!("{} is not an environment dump", path.display()))?;
+    if version.version != ENV_FORMAT_VERSION {
+        bail!(
+            "{} has environment format version {}, this build reads version {ENV_FORMAT_VERSION}",
+            path.display(),
+            version.version
+        );
+    }
+    serde_json::from_slice(&contents).with_context(|| format!("decoding {}", path.display()))
+}
+
+/// `params` as a dump records them, with the authority seed of the election left out.
+fn without_seed(params: &VoteParams) -> VoteParams {
+    let election = params.election.as_ref().map(|election| ElectionScope {
+        election_id: election.election_id.clone(),
+        authority_seed: Vec::new(),
+    });
+    VoteParams { election, ..params.clone() }
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+}
@@ -0,0 +1,195 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of the journals committed by the guest methods.
+
+use std::{fs, path::Path};
+
+use alloy_sol_types::SolValue;
+use anyhow::{anyhow, Context, Result};
+use castvote_core::{
+    decode_reveals, AgeEligibility, CosignedReveal, EncryptedReveal, EpochReveal, HomomorphicReveal,
+    RevealInfo, UncheckedReveal,
+};
+use k256_methods::{
+    AGE_ELIGIBILITY_ID, ED25519_VERIFY_ID, K256_COSIGN_ID, K256_VERIFY_BATCH_ID,
+    K256_VERIFY_ENCRYPTED_ID, K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID, K256_VERIFY_MULTI_ID,
+    K256_VERIFY_REGISTRY_ID, K256_VERIFY_ROTATED_ID, P256_VERIFY_ID,
+};
+use risc0_zkvm::sha::Digest;
+
+/// Decodes a single-ballot journal: ABI `bytes` wrapping the versioned, bincode-encoded
+/// [RevealInfo]. Journals with a version this build does not know are rejected.
+pub fn decode_journal(journal: &[u8]) -> Result<RevealInfo> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    RevealInfo::decode(&encoded).context("journal does not contain reveal info")
+}
+
+/// Decodes the journal of the batch or multi-poll guest method: ABI `bytes` wrapping one
+/// [RevealInfo] per ballot or per poll, in input order.
+pub fn decode_batch_journal(journal: &[u8]) -> Result<Vec<RevealInfo>> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    decode_reveals(&encoded).context("journal does not contain batch reveal info")
+}
+
+/// Decodes any journal that starts with reveal data: the single entry of a single-ballot
+/// journal, or every entry of a batch or multi-poll journal.
+pub fn decode_reveal_entries(journal: &[u8]) -> Result<Vec<RevealInfo>> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    // A single reveal never passes for a vector: its nullifier length (64) would be read as the
+    // entry count, and the first hex digits of the nullifier as an impossibly long string length.
+    if let Ok(reveals) = decode_reveals(&encoded) {
+        return Ok(reveals);
+    }
+    let reveal = RevealInfo::decode(&encoded).context("journal does not contain reveal info")?;
+    Ok(vec![reveal])
+}
+
+/// Decodes the journal of the co-signing guest method, whose payload is a [CosignedReveal].
+pub fn decode_cosigned_journal(journal: &[u8]) -> Result<CosignedReveal> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    CosignedReveal::decode(&encoded).context("journal does not contain co-signed reveal info")
+}
+
+/// Decodes the journal of the rotated-keys guest method, whose payload is an [EpochReveal].
+pub fn decode_epoch_journal(journal: &[u8]) -> Result<EpochReveal> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    EpochReveal::decode(&encoded).context("journal does not contain key epoch reveal info")
+}
+
+/// Decodes the journal of the encrypted-ballot guest method, whose payload is an
+/// [EncryptedReveal].
+pub fn decode_encrypted_journal(journal: &[u8]) -> Result<EncryptedReveal> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    EncryptedReveal::decode(&encoded).context("journal does not contain encrypted reveal info")
+}
+
+/// Decodes the journal of the homomorphic guest method, whose payload is a
+/// [HomomorphicReveal].
+pub fn decode_homomorphic_journal(journal: &[u8]) -> Result<HomomorphicReveal> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    HomomorphicReveal::decode(&encoded).context("journal does not contain an encrypted vote")
+}
+
+/// Decodes the journal of the age-eligibility guest method, whose payload is an
+/// [AgeEligibility].
+pub fn decode_eligibility_journal(journal: &[u8]) -> Result<AgeEligibility> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    AgeEligibility::decode(&encoded).context("journal does not contain an age eligibility result")
+}
+
+/// Decodes the journal a test build of the single-ballot method commits with
+/// `allow_invalid_signature` set, whose payload is an [UncheckedReveal].
+pub fn decode_unchecked_journal(journal: &[u8]) -> Result<UncheckedReveal> {
+    let encoded = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    UncheckedReveal::decode(&encoded).context("journal does not contain unchecked reveal info")
+}
+
+/// What a proof's journal commits, decoded as the guest method that committed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MethodJournal {
+    /// Reveal entries: one per ballot or poll for the batch and multi-poll methods, a single one
+    /// otherwise. Methods that wrap the reveal data (encrypted, homomorphic, co-signed and
+    /// rotated-keys ballots) contribute the wrapped [RevealInfo].
+    Reveals(Vec<RevealInfo>),
+    /// The result of the age-eligibility method, which casts no vote.
+    Eligibility(AgeEligibility),
+}
+
+impl MethodJournal {
+    /// `(poll_id, nullifier)` of every entry.
+    pub fn nullifiers(&self) -> Vec<(u64, &str)> {
+        match self {
+            MethodJournal::Reveals(reveals) => {
+                reveals.iter().map(|reveal| (reveal.poll_id, reveal.nullifier.as_str())).collect()
+            }
+            MethodJournal::Eligibility(eligibility) => {
+                vec![(eligibility.poll_id, eligibility.nullifier.as_str())]
+            }
+        }
+    }
+}
+
+/// Decodes `journal` as committed by the guest method with `image_id`.
+///
+/// Under `--journal-codec cbor` the wrapped reveals are maps with the reveal data under
+/// `reveal`, so only the method says how to read them. A journal of an image this build does not
+/// have, e.g. from an older build, is decoded as the first journal type it parses as.
+pub fn decode_method_journal(image_id: impl Into<Digest>, journal: &[u8]) -> Result<MethodJournal> {
+    let image_id = image_id.into();
+    let is = |ids: &[[u32; 8]]| ids.iter().any(|&id| Digest::from(id) == image_id);
+    let single = |reveal| Ok(MethodJournal::Reveals(vec![reveal]));
+    if is(&[K256_VERIFY_BATCH_ID, K256_VERIFY_MULTI_ID]) {
+        Ok(MethodJournal::Reveals(decode_batch_journal(journal)?))
+    } else if is(&[K256_VERIFY_ID, K256_VERIFY_REGISTRY_ID, P256_VERIFY_ID, ED25519_VERIFY_ID]) {
+        single(decode_journal(journal)?)
+    } else if is(&[K256_VERIFY_ENCRYPTED_ID]) {
+        single(decode_encrypted_journal(journal)?.reveal)
+    } else if is(&[K256_VERIFY_HOMOMORPHIC_ID]) {
+        single(decode_homomorphic_journal(journal)?.reveal)
+    } else if is(&[K256_COSIGN_ID]) {
+        single(decode_cosigned_journal(journal)?.reveal)
+    } else if is(&[K256_VERIFY_ROTATED_ID]) {
+        single(decode_epoch_journal(journal)?.reveal)
+    } else if is(&[AGE_ELIGIBILITY_ID]) {
+        Ok(MethodJournal::Eligibility(decode_eligibility_journal(journal)?))
+    } else {
+        decode_unknown_journal(journal)
+    }
+}
+
+/// Decodes a journal of an unknown image as plain reveal entries, then as each wrapped reveal and
+/// last as an age-eligibility result. Fails with the error of the plain reveal entries.
+fn decode_unknown_journal(journal: &[u8]) -> Result<MethodJournal> {
+    let error = match decode_reveal_entries(journal) {
+        Ok(reveals) => return Ok(MethodJournal::Reveals(reveals)),
+        Err(e) => e,
+    };
+    let wrapped: [fn(&[u8]) -> Result<RevealInfo>; 4] = [
+        |journal| Ok(decode_encrypted_journal(journal)?.reveal),
+        |journal| Ok(decode_homomorphic_journal(journal)?.reveal),
+        |journal| Ok(decode_cosigned_journal(journal)?.reveal),
+        |journal| Ok(decode_epoch_journal(journal)?.reveal),
+    ];
+    if let Some(reveal) = wrapped.iter().find_map(|decode| decode(journal).ok()) {
+        return Ok(MethodJournal::Reveals(vec![reveal]));
+    }
+    decode_eligibility_journal(journal).map(MethodJournal::Eligibility).map_err(|_| error)
+}
+
+/// Reads a `journal.dat` file as written by the prover, i.e. hex-encoded journal bytes.
+pub fn read_journal_file(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    read_hex_file(path)
+}
+
+/// Reads any of the hex-encoded artifacts the prover writes, such as `seal.dat`.
+pub fn read_hex_file(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    hex::decode(contents.trim()).with_context(|| format!("{} is not hex encoded", path.display()))
+}
+
+/// Parses an image ID given as 64 hex characters (optionally `0x`-prefixed), or otherwise
+/// reads it from the `image_id.dat` file at that path.
+pub fn read_image_id(image_id: &str) -> Result<Digest> {
+    let hex_id = image_id.strip_prefix("0x").unwrap_or(image_id);
+    let bytes = if hex_id.len() == 64 && hex_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        hex::decode(hex_id)?
+    } else {
+        read_hex_file(image_id)?
+    };
+    Digest::try_from(bytes.as_slice())
+        .map_err(|_| anyhow!("image ID {image_id} is {} bytes, expected 32", bytes.len()))
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Bulk ballots from a JSON Lines file, one ballot object per line.
+//!
+//! A line that is not a valid ballot is skipped and reported by its line number rather than
+//! failing the whole file, so one bad record does not hold up thousands of good ones.
+
+use std::{collections::BTreeSet, path::Path};
+
+use anyhow::Result;
+use castvote_core::VoteParams;
+
+use crate::ballot::{prepare_ballot, read_text_file};
+
+/// A ballot read from a JSON Lines file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonlBallot {
+    /// 1-based line number in the file.
+    pub line: usize,
+    /// The ballot in canonical form, ready to sign.
+    pub ballot: String,
+}
+
+/// A line that was not turned into a vote, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedLine {
+    /// 1-based line number in the file.
+    pub line: usize,
+    pub reason: String,
+}
+
+/// The outcome of reading a JSON Lines file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonlBallots {
+    pub ballots: Vec<JsonlBallot>,
+    pub skipped: Vec<SkippedLine>,
+}
+
+impl JsonlBallots {
+    /// Moves the ballots on the lines of `skipped` to the skipped lines, e.g. because their
+    /// signatures do not verify. Collect them first and call this once: it walks every ballot.
+    pub fn skip_all(&mut self, skipped: Vec<SkippedLine>) {
+        let lines: BTreeSet<usize> = skipped.iter().map(|skipped| skipped.line).collect();
+        self.ballots.retain(|ballot| !lines.contains(&ballot.line));
+        self.skipped.extend(skipped);
+        self.skipped.sort_by_key(|skipped| skipped.line);
+    }
+}
+
+/// Parses JSON Lines `text`, preparing every line with [prepare_ballot]. Blank lines are ignored.
+pub fn parse_jsonl(text: &str, params: &VoteParams) -> JsonlBallots {
+    let mut result = JsonlBallots::default();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match prepare_ballot(line, params) {
+            Ok(ballot) => result.ballots.push(JsonlBallot { line: line_number, ballot }),
+            Err(e) => result.skipped.push(SkippedLine { line: line_number, reason: format!("{e:#}") }),
+        }
+    }
+    result
+}
+
+/// Reads the JSON Lines file at `path` with [parse_jsonl].
+pub fn read_jsonl(path: impl AsRef<Path>, params: &VoteParams) -> Result<JsonlBallots> {
+    Ok(parse_jsonl(&read_text_file(path)?, params))
+}
+
+/// Reads a signatures file accompanying a JSON Lines file: the Base64 signature on line `n`
+/// belongs to the ballot on line `n`. Returns one entry per line, `None` for blank lines.
+pub fn read_signature_lines(path: impl AsRef<Path>) -> Result<Vec<Option<String>>> {
+    Ok(read_text_file(path)?
+        .lines()
+        .map(|line| Some(line.trim().to_string()).filter(|line| !line.is_empty()))
+        .collect())
+}
@@ -0,0 +1,220 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Password-protected private key files.
+//!
+//! scrypt stretches the password into an AES-256-GCM key, which encrypts the raw 32-byte private
+//! key. The file is JSON holding the scrypt parameters, salt, nonce and ciphertext, so the cost
+//! can be raised later without breaking files written before. Decrypted key bytes only live in
+//! [Zeroizing] buffers, and [VoterKey]s zeroize their secret when dropped.
+
+use std::{convert::Infallible, fmt, path::Path, str::FromStr};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    ballot::read_text_file,
+    cli::{Curve, Scheme},
+    keys::{decode_private_key, VoterKey},
+    output::atomic_write_private,
+};
+
+/// Associated data of every encrypted key file; a later format would change it.
+const KEY_FILE_AAD: &[u8] = b"castvote encrypted key v1";
+
+/// Length of the random scrypt salt.
+pub const SALT_LEN: usize = 16;
+
+/// Largest `log_n` a key file may ask for: `N = 2^20` with the default `r` already takes 1 GiB.
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+
+/// Largest `r * p` a key file may ask for, which scales the work of every password guess.
+pub const MAX_SCRYPT_R_TIMES_P: u64 = 64;
+
+/// Cost parameters of scrypt, as defined in RFC 7914.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScryptParams {
+    /// log2 of the CPU/memory cost `N`.
+    pub log_n: u8,
+    /// Block size.
+    pub r: u32,
+    /// Parallelization.
+    pub p: u32,
+}
+
+impl ScryptParams {
+    /// Fails if the cost is above [MAX_SCRYPT_LOG_N] or [MAX_SCRYPT_R_TIMES_P]. The parameters
+    /// come from the key file, so an edited one could otherwise exhaust memory or stall for hours
+    /// before the password is even tried.
+    pub fn check(&self) -> Result<()> {
+        if self.log_n > MAX_SCRYPT_LOG_N {
+            bail!("scrypt log_n is {}, above the maximum of {MAX_SCRYPT_LOG_N}", self.log_n);
+        }
+        let r_times_p = u64::from(self.r) * u64::from(self.p);
+        if r_times_p > MAX_SCRYPT_R_TIMES_P {
+            bail!("scrypt r * p is {r_times_p}, above the maximum of {MAX_SCRYPT_R_TIMES_P}");
+        }
+        Ok(())
+    }
+}
+
+/// The recommended interactive-login cost: `N = 2^15`, `r = 8`, `p = 1`, about 32 MiB.
+impl Default for ScryptParams {
+    fn default() -> Self {
+        Self { log_n: 15, r: 8, p: 1 }
+    }
+}
+
+/// The password of an encrypted key file. It is zeroized when dropped and, being a secret, left
+/// out of debug output.
+#[derive(Clone)]
+pub struct KeyPassword(Zeroizing<String>);
+
+impl KeyPassword {
+    pub fn new(password: impl Into<String>) -> Self {
+        Self(Zeroizing::new(password.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ZeroizeOnDrop for KeyPassword {}
+
+impl FromStr for KeyPassword {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl fmt::Debug for KeyPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyPassword(..)")
+    }
+}
+
+/// A private key encrypted under a password, as stored in an encrypted key file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedKey {
+    pub scrypt: ScryptParams,
+    /// Base64 scrypt salt.
+    pub salt: String,
+    /// Base64 AES-GCM nonce.
+    pub nonce: String,
+    /// Base64 AES-GCM ciphertext of the 32-byte private key, tag included.
+    pub ciphertext: String,
+}
+
+impl EncryptedKey {
+    /// Encrypts the raw 32-byte `private_key` under `password`, with a fresh salt and nonce.
+    pub fn encrypt(
+        private_key: &[u8; 32],
+        password: &KeyPassword,
+        scrypt: ScryptParams,
+    ) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(password, &salt, scrypt)?
+            .encrypt(&nonce, Payload { msg: private_key, aad: KEY_FILE_AAD })
+            .map_err(|_| anyhow!("encrypting the private key failed"))?;
+        Ok(Self {
+            scrypt,
+            salt: general_purpose::STANDARD.encode(salt),
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the private key, failing on a wrong password or a tampered file.
+    pub fn decrypt(&self, password: &KeyPassword) -> Result<Zeroizing<[u8; 32]>> {
+        let decode = |field, value: &str| {
+            general_purpose::STANDARD.decode(value).with_context(|| format!("decoding the {field}"))
+        };
+        let salt = decode("salt", &self.salt)?;
+        let nonce: [u8; 12] = decode("nonce", &self.nonce)?
+            .try_into()
+            .map_err(|nonce: Vec<u8>| anyhow!("nonce must be 12 bytes, got {}", nonce.len()))?;
+        let ciphertext = decode("ciphertext", &self.ciphertext)?;
+        let plaintext = Zeroizing::new(
+            cipher(password, &salt, self.scrypt)?
+                .decrypt(&Nonce::from(nonce), Payload { msg: &ciphertext, aad: KEY_FILE_AAD })
+                .map_err(|_| anyhow!("wrong password, or the key file was tampered with"))?,
+        );
+        let mut private_key = Zeroizing::new([0u8; 32]);
+        if plaintext.len() != private_key.len() {
+            bail!("decrypted key must be 32 bytes, got {}", plaintext.len());
+        }
+        private_key.copy_from_slice(&plaintext);
+        Ok(private_key)
+    }
+}
+
+fn cipher(password: &KeyPassword, salt: &[u8], params: ScryptParams) -> Result<Aes256Gcm> {
+    params.check()?;
+    let params = scrypt::Params::new(params.log_n, params.r, params.p, 32)
+        .map_err(|e| anyhow!("invalid scrypt parameters: {e}"))?;
+    let mut key = Zeroizing::new(Key::<Aes256Gcm>::default());
+    scrypt::scrypt(password.as_str().as_bytes(), salt, &params, &mut key)
+        .expect("32 bytes is a valid scrypt output length");
+    Ok(Aes256Gcm::new(&key))
+}
+
+/// Reads a private key file: plain Base64 as printed by `keygen`, or an [EncryptedKey] as
+/// written by `encrypt-key`, decrypted with `password`.
+pub fn read_private_key_file(
+    path: &Path,
+    scheme: Scheme,
+    curve: Curve,
+    password: Option<&KeyPassword>,
+) -> Result<VoterKey> {
+    let contents = Zeroizing::new(read_text_file(path)?);
+    let key = if contents.trim_start().starts_with('{') {
+        let encrypted: EncryptedKey = serde_json::from_str(&contents)
+            .with_context(|| format!("{} is not an encrypted key file", path.display()))?;
+        let password = password.ok_or_else(|| {
+            let path = path.display();
+            anyhow!("{path} is encrypted; pass --key-password or set CHECKVOTE_KEY_PASSWORD")
+        })?;
+        VoterKey::from_bytes(scheme, curve, &*encrypted.decrypt(password)?)
+    } else {
+        VoterKey::from_base64(scheme, curve, contents.trim())
+    };
+    key.with_context(|| format!("reading the private key from {}", path.display()))
+}
+
+/// Encrypts the plain Base64 private key file at `key` under `password` and writes it to `out`,
+/// readable by its owner only.
+pub fn encrypt_key_file(
+    key: &Path,
+    out: &Path,
+    password: &KeyPassword,
+    scrypt: ScryptParams,
+) -> Result<()> {
+    let contents = Zeroizing::new(read_text_file(key)?);
+    let private_key = decode_private_key(contents.trim())
+        .with_context(|| format!("reading the private key from {}", key.display()))?;
+    let encrypted = EncryptedKey::encrypt(&private_key, password, scrypt)?;
+    atomic_write_private(out, serde_json::to_vec_pretty(&encrypted)?)
+}
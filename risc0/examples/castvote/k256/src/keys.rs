@@ -0,0 +1,326 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Importing the voter's key and signing ballots with it.
+
+use std::path::Path;
+
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, canonical_p256_key, decode_ed25519_public_key, decode_k256_signature,
+    decode_k256_verifying_key, decode_p256_verifying_key, decode_signature, EncodingError,
+    MessageFormat, SignedDigest, VoteParams,
+};
+use hkdf::Hkdf;
+use k256::ecdsa::signature::{hazmat::PrehashVerifier, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    ballot::load_ballot,
+    cli::{Args, Curve, Scheme},
+    keyfile::read_private_key_file,
+    signer::{sign_ballot_as, SoftwareSigner},
+};
+
+/// Demo secp256k1 private key (Base64).
+pub const K256_PRIVATE_KEY: &str = "WatoiP9UiA3fqB08TVHjBGniYDXUz/04mAGRLb7tyQY=";
+
+/// Demo P-256 private key (Base64).
+pub const P256_PRIVATE_KEY: &str = "MmH9jW5x0dKsZjBpFxA+KCqeCe0qZUI6sVqGNQawk8Y=";
+
+/// Demo Ed25519 secret key seed (Base64).
+pub const ED25519_PRIVATE_KEY: &str = "B6tP4z9L8Ub/Obd3fH79CcPSSfjp92ipaLJGojSfkyc=";
+
+/// HKDF salt of keys derived from a `keygen --seed`.
+pub const KEYGEN_SALT: &[u8] = b"castvote keygen";
+
+/// A voter's private key for one of the supported signature schemes.
+#[derive(Clone)]
+pub enum VoterKey {
+    K256(k256::ecdsa::SigningKey),
+    P256(p256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl VoterKey {
+    /// Returns the public key as passed to the guest methods: uncompressed SEC1 for ECDSA keys,
+    /// the raw 32 bytes for Ed25519, Base64 encoded either way.
+    pub fn public_key_base64(&self) -> String {
+        let bytes = match self {
+            VoterKey::K256(key) => key.verifying_key().to_encoded_point(false).as_bytes().to_vec(),
+            VoterKey::P256(key) => key.verifying_key().to_encoded_point(false).as_bytes().to_vec(),
+            VoterKey::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+        };
+        general_purpose::STANDARD.encode(bytes)
+    }
+
+    /// Returns the public key in the canonical encoding its hash is committed over (see
+    /// `castvote_core::voter_key_hash`): compressed SEC1 for ECDSA keys, the raw 32 bytes for
+    /// Ed25519.
+    pub fn canonical_public_key(&self) -> Vec<u8> {
+        match self {
+            VoterKey::K256(key) => canonical_k256_key(key.verifying_key()),
+            VoterKey::P256(key) => canonical_p256_key(key.verifying_key()),
+            VoterKey::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+        }
+    }
+
+    /// Decodes a Base64 32-byte private key (a scalar for ECDSA, the secret seed for Ed25519) for
+    /// `scheme` and `curve`.
+    pub fn from_base64(scheme: Scheme, curve: Curve, private_key: &str) -> Result<Self> {
+        Self::from_bytes(scheme, curve, &*decode_private_key(private_key)?)
+    }
+
+    /// Returns the private key as accepted by [VoterKey::from_base64].
+    pub fn private_key_base64(&self) -> String {
+        let bytes = Zeroizing::new(match self {
+            VoterKey::K256(key) => key.to_bytes().to_vec(),
+            VoterKey::P256(key) => key.to_bytes().to_vec(),
+            VoterKey::Ed25519(key) => key.to_bytes().to_vec(),
+        });
+        general_purpose::STANDARD.encode(&*bytes)
+    }
+
+    /// Generates a new random key for `scheme` and `curve`.
+    pub fn generate(scheme: Scheme, curve: Curve) -> Result<Self> {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::from_seed(scheme, curve, &seed)
+    }
+
+    /// Derives the key for `scheme` and `curve` from `seed` with HKDF-SHA256, so the same seed
+    /// always gives the same key; different schemes and curves get unrelated keys.
+    ///
+    /// Only meant for reproducible test vectors: anyone who knows the seed knows the key.
+    pub fn from_seed(scheme: Scheme, curve: Curve, seed: &[u8]) -> Result<Self> {
+        let label = match (scheme, curve) {
+            (Scheme::Ecdsa, Curve::K256) => "ecdsa-k256",
+            (Scheme::Ecdsa, Curve::P256) => "ecdsa-p256",
+            (Scheme::Ed25519, _) => "ed25519",
+            (Scheme::AgeOnly, _) => bail!("--scheme age-only has no voter keys"),
+        };
+        let hkdf = Hkdf::<Sha256>::new(Some(KEYGEN_SALT), seed);
+        // Outputs that are not a valid scalar (zero, or not below the curve order) are skipped;
+        // that takes more than one try with a probability of about 2^-128.
+        for counter in 0u32.. {
+            let mut bytes = Zeroizing::new([0u8; 32]);
+            hkdf.expand_multi_info(&[label.as_bytes(), &counter.to_be_bytes()], &mut *bytes)
+                .expect("32 bytes is a valid HKDF-SHA256 output length");
+            if let Ok(key) = Self::from_bytes(scheme, curve, &bytes) {
+                return Ok(key);
+            }
+        }
+        unreachable!("no valid key in 2^32 HKDF outputs")
+    }
+
+    /// Builds the key for `scheme` and `curve` from its raw 32 bytes (a scalar for ECDSA, the
+    /// secret seed for Ed25519).
+    pub fn from_bytes(scheme: Scheme, curve: Curve, bytes: &[u8; 32]) -> Result<Self> {
+        let key = match (scheme, curve) {
+            (Scheme::Ecdsa, Curve::K256) => VoterKey::K256(
+                k256::ecdsa::SigningKey::from_bytes(bytes.into())
+                    .context("invalid secp256k1 private key")?,
+            ),
+            (Scheme::Ecdsa, Curve::P256) => VoterKey::P256(
+                p256::ecdsa::SigningKey::from_bytes(bytes.into())
+                    .context("invalid P-256 private key")?,
+            ),
+            (Scheme::Ed25519, _) => VoterKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(bytes)),
+            (Scheme::AgeOnly, _) => bail!("--scheme age-only has no voter keys"),
+        };
+        Ok(key)
+    }
+}
+
+// Every variant wipes its secret when dropped, so a key is zeroized once it goes out of scope
+// after signing, clones included.
+impl ZeroizeOnDrop for VoterKey {}
+
+impl std::fmt::Debug for VoterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the private key.
+        let scheme = match self {
+            VoterKey::K256(_) => "K256",
+            VoterKey::P256(_) => "P256",
+            VoterKey::Ed25519(_) => "Ed25519",
+        };
+        write!(f, "VoterKey::{scheme}({})", self.public_key_base64())
+    }
+}
+
+/// Decodes a Base64 32-byte private key.
+pub fn decode_private_key(private_key: &str) -> Result<Zeroizing<[u8; 32]>> {
+    let bytes = Zeroizing::new(
+        general_purpose::STANDARD
+            .decode(private_key)
+            .context("decoding private key")?,
+    );
+    bytes
+        .as_slice()
+        .try_into()
+        .map(Zeroizing::new)
+        .map_err(|_| anyhow!("private key must be 32 bytes, got {}", bytes.len()))
+}
+
+/// Imports the key matching the signature scheme and curve selected in `args`: the
+/// `--private-key-file` if given, the scheme's demo key otherwise.
+///
+/// The key is zeroized when the returned [VoterKey] is dropped, i.e. once the ballots are signed.
+pub fn import_keys(args: &Args) -> Result<VoterKey> {
+    if let Some(path) = &args.private_key_file {
+        return read_private_key_file(path, args.scheme, args.curve, args.key_password.as_ref());
+    }
+    let private_key = match (args.scheme, args.curve) {
+        (Scheme::Ecdsa, Curve::K256) => K256_PRIVATE_KEY,
+        (Scheme::Ecdsa, Curve::P256) => P256_PRIVATE_KEY,
+        (Scheme::Ed25519, _) => ED25519_PRIVATE_KEY,
+        (Scheme::AgeOnly, _) => bail!("--scheme age-only has no voter key to sign with"),
+    };
+    VoterKey::from_base64(args.scheme, args.curve, private_key)
+}
+
+/// Reads the proving service's key from `--commit-author-signature`, if given, decrypting it with
+/// `--key-password` like the voter's key.
+pub fn import_author_key(args: &Args) -> Result<Option<k256::ecdsa::SigningKey>> {
+    let Some(path) = &args.commit_author_signature else {
+        return Ok(None);
+    };
+    match read_private_key_file(path, Scheme::Ecdsa, Curve::K256, args.key_password.as_ref())? {
+        VoterKey::K256(key) => Ok(Some(key)),
+        _ => unreachable!("a k256 key file reads as a k256 key"),
+    }
+}
+
+/// Signs `ballot` with `key` and returns the Base64 signature the guest expects.
+///
+/// ECDSA signatures are fixed-size `r || s` and Ed25519 signatures are 64 bytes, so every method
+/// reads the same input tuple. Each signature is checked against the key's public key before it is
+/// returned.
+pub fn sign(key: &VoterKey, ballot: &str, message_format: MessageFormat) -> Result<String> {
+    match (key, message_format.digest(ballot.as_bytes())) {
+        (VoterKey::K256(key), _) => {
+            sign_ballot_as(&SoftwareSigner::new(key.clone()), ballot, message_format)
+        }
+        (VoterKey::P256(key), SignedDigest::Sha256(message_hash)) => {
+            let signature: p256::ecdsa::Signature = key.sign(&message_hash);
+            key.verifying_key()
+                .verify(&message_hash, &signature)
+                .context("P-256 signature does not verify")?;
+            Ok(general_purpose::STANDARD.encode(signature.to_bytes()))
+        }
+        (VoterKey::Ed25519(key), SignedDigest::Sha256(message_hash)) => {
+            let signature = key.sign(&message_hash);
+            key.verifying_key()
+                .verify(&message_hash, &signature)
+                .context("Ed25519 signature does not verify")?;
+            Ok(general_purpose::STANDARD.encode(signature.to_bytes()))
+        }
+        (_, SignedDigest::Prehash(_)) => bail!("{message_format:?} ballots need a secp256k1 key"),
+    }
+}
+
+/// Decodes `signature` and `public_key` the way the guest method for `key`'s scheme does, so
+/// malformed input is reported before the prover is started.
+pub fn check_encodings(key: &VoterKey, signature: &str, public_key: &str) -> Result<(), EncodingError> {
+    decode_signature(signature)?;
+    match key {
+        VoterKey::K256(_) => {
+            decode_k256_signature(signature)?;
+            decode_k256_verifying_key(public_key).map(drop)
+        }
+        VoterKey::P256(_) => decode_p256_verifying_key(public_key).map(drop),
+        VoterKey::Ed25519(_) => decode_ed25519_public_key(public_key).map(drop),
+    }
+}
+
+/// Checks `signature` over `ballot` against `public_key` the way the guest method for `scheme`
+/// and `curve` will, for signatures made outside this tool (`--signature`).
+///
+/// Both values are Base64 as the guest reads them; malformed encodings are reported as
+/// [EncodingError]s.
+pub fn verify_signature(
+    scheme: Scheme,
+    curve: Curve,
+    ballot: &str,
+    signature: &str,
+    public_key: &str,
+    message_format: MessageFormat,
+) -> Result<()> {
+    let signature_bytes = decode_signature(signature)?;
+    let verified = match (scheme, curve, message_format.digest(ballot.as_bytes())) {
+        (Scheme::Ecdsa, Curve::K256, digest) => {
+            let key = decode_k256_verifying_key(public_key)?;
+            let signature = decode_k256_signature(signature)?;
+            match digest {
+                SignedDigest::Sha256(message_hash) => key.verify(&message_hash, &signature),
+                SignedDigest::Prehash(digest) => key.verify_prehash(&digest, &signature),
+            }
+        }
+        (Scheme::Ecdsa, Curve::P256, SignedDigest::Sha256(message_hash)) => {
+            let key = decode_p256_verifying_key(public_key)?;
+            let signature = p256::ecdsa::Signature::from_slice(&signature_bytes)
+                .context("invalid P-256 signature")?;
+            key.verify(&message_hash, &signature)
+        }
+        (Scheme::Ed25519, _, SignedDigest::Sha256(message_hash)) => {
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&decode_ed25519_public_key(public_key)?)
+                .context("invalid Ed25519 public key")?;
+            key.verify(&message_hash, &ed25519_dalek::Signature::from_bytes(&signature_bytes))
+        }
+        (Scheme::AgeOnly, _, _) => bail!("--scheme age-only has no signatures to check"),
+        (_, _, SignedDigest::Prehash(_)) => {
+            bail!("{message_format:?} ballots need a secp256k1 key")
+        }
+    };
+    verified.context("signature does not verify against the public key")
+}
+
+/// A ballot signed for proving elsewhere, as output by `--no-prove`.
+///
+/// Holds the fields of the guest input tuple, so a prover can rebuild it without the private key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignedBallot {
+    /// The ballot JSON that was signed, byte for byte.
+    pub ballot: String,
+    pub poll_id: u64,
+    /// Base64 signature, as returned by [sign].
+    pub signature: String,
+    /// Base64 public key, as returned by [VoterKey::public_key_base64].
+    pub public_key: String,
+}
+
+/// Loads and signs every ballot in `filenames`, checking each result the way the guest will.
+pub fn sign_files<P: AsRef<Path>>(
+    key: &VoterKey,
+    filenames: &[P],
+    poll_id: u64,
+    params: &VoteParams,
+) -> Result<Vec<SignedBallot>> {
+    filenames
+        .iter()
+        .map(|filename| {
+            let filename = filename.as_ref();
+            let ballot = load_ballot(filename, params)?;
+            let signature = sign(key, &ballot, params.message_format)?;
+            let public_key = key.public_key_base64();
+            check_encodings(key, &signature, &public_key)
+                .with_context(|| filename.display().to_string())?;
+            Ok(SignedBallot { ballot, poll_id, signature, public_key })
+        })
+        .collect()
+}
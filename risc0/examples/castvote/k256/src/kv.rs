@@ -0,0 +1,108 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Flat `key=value` exports of a single-ballot journal, for spreadsheet audits and `grep`.
+
+use anyhow::Result;
+use castvote_core::JournalCodec;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+use crate::{
+    cli::KvFormat,
+    diff::{reveal_fields, JournalVersion},
+    journal::decode_journal,
+};
+
+/// Decodes `journal` with [decode_journal] and lists what it commits as flat key-value pairs:
+/// the `version` and `codec` of its layout, then every field in journal order.
+///
+/// Fields a journal's version predates are kept as `null`, so journals of every version export
+/// the same keys. The committed age range, the one nested field, becomes `age_range_min` and
+/// `age_range_max`.
+pub fn journal_fields(journal: &[u8]) -> Result<Vec<(String, Value)>> {
+    let version = JournalVersion::of(journal)?;
+    let reveal = decode_journal(journal)?;
+    let codec = match version.codec {
+        JournalCodec::Bincode => "bincode",
+        JournalCodec::Cbor => "cbor",
+    };
+    let mut fields =
+        vec![("version".into(), json!(version.version)), ("codec".into(), json!(codec))];
+    for (name, value) in reveal_fields(&reveal) {
+        if name == "age_range" {
+            let range = reveal.age_range;
+            fields.push(("age_range_min".into(), json!(range.map(|range| range.min))));
+            fields.push(("age_range_max".into(), json!(range.map(|range| range.max))));
+        } else {
+            fields.push((name.into(), value));
+        }
+    }
+    Ok(fields)
+}
+
+/// The text of a value in the `env` and `csv` flavors: strings as they are, lists joined with
+/// commas and `null` as nothing.
+pub fn field_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(field_text).collect::<Vec<_>>().join(","),
+        value => value.to_string(),
+    }
+}
+
+/// Renders `fields` in `format`:
+///
+/// - `env`: one `key=value` line per field, single-quoting values a shell would split, so the
+///   output can be sourced;
+/// - `json`: one flat object, in field order, with numbers, booleans and lists kept as such;
+/// - `csv`: a `key,value` header, then one row per field.
+pub fn render_fields(fields: &[(String, Value)], format: KvFormat) -> Result<String> {
+    Ok(match format {
+        KvFormat::Env => fields
+            .iter()
+            .map(|(key, value)| format!("{key}={}\n", shell_quote(&field_text(value))))
+            .collect(),
+        KvFormat::Json => serde_json::to_string(&OrderedFields(fields))? + "\n",
+        KvFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(["key", "value"])?;
+            for (key, value) in fields {
+                writer.write_record([key.clone(), field_text(value)])?;
+            }
+            String::from_utf8(writer.into_inner()?)?
+        }
+    })
+}
+
+/// Quotes `text` for a POSIX shell unless it is made only of characters that need no quoting.
+fn shell_quote(text: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-.,:/+@%".contains(c);
+    if text.chars().all(plain) {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+/// Serializes fields as a JSON object in their own order, which a [serde_json::Map] would sort.
+struct OrderedFields<'a>(&'a [(String, Value)]);
+
+impl Serialize for OrderedFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, value)))
+    }
+}
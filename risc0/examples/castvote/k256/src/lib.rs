@@ -0,0 +1,87 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host-side building blocks of the castvote example, shared by the `checkvote` binary and by
+//! integrators embedding it.
+
+pub mod audit;
+pub mod author;
+pub mod ballot;
+pub mod bonsai;
+pub mod bundle;
+pub mod cache;
+pub mod checkpoint;
+pub mod cli;
+pub mod console;
+pub mod diff;
+pub mod encryption;
+pub mod error;
+pub mod estimate;
+pub mod explain;
+pub mod homomorphic;
+pub mod input;
+pub mod journal;
+pub mod jsonl;
+pub mod keyfile;
+pub mod keys;
+pub mod kv;
+pub mod nullifier;
+pub mod nullifier_store;
+pub mod opening;
+pub mod output;
+pub mod progress;
+pub mod prove;
+pub mod report;
+pub mod resign;
+pub mod seal;
+pub mod self_test;
+pub mod signer;
+pub mod solidity;
+pub mod submission;
+pub mod template;
+pub mod timings;
+pub mod watch;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use castvote_core::Date;
+
+pub use ballot::{
+    load_ballot, prepare_ballot, read_ballot_file, read_text_file, validate_ballot,
+    BallotValidationError,
+};
+pub use journal::{
+    decode_batch_journal, decode_cosigned_journal, decode_eligibility_journal,
+    decode_encrypted_journal, decode_epoch_journal, decode_homomorphic_journal, decode_journal,
+    decode_method_journal, decode_reveal_entries, read_hex_file, decode_unchecked_journal,
+    read_image_id, read_journal_file, MethodJournal,
+};
+pub use keys::{check_encodings, import_keys, sign, sign_files, verify_signature, SignedBallot, VoterKey};
+pub use output::{
+    atomic_write, atomic_write_private, atomic_write_with, ensure_writable_dir, write_hex_file,
+    write_json_file,
+};
+pub use prove::{verify_and_decode, verify_vote};
+pub use signer::{
+    parse_wallet_signature, sign_ballot, sign_ballot_eip191, BallotSigner, SoftwareSigner,
+};
+
+/// Returns today's date in UTC, the default reference date for deriving ages.
+pub fn today() -> Date {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before 1970")
+        .as_secs();
+    Date::from_unix_days((secs / 86_400) as i64)
+}
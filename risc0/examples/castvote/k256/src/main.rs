@@ -13,233 +13,1970 @@
 // limitations under the License.
 
 
-use k256_methods::{K256_VERIFY_ELF, K256_VERIFY_ID};
-// use aes_gcm::aead::{OsRng};
-// use aes_gcm::{AeadCore, Aes256Gcm};
+use k256_methods::{
+    AGE_ELIGIBILITY_ID, ED25519_VERIFY_ID, K256_COSIGN_ID, K256_VERIFY_BATCH_ELF,
+    K256_VERIFY_BATCH_ID, K256_VERIFY_ELF, K256_VERIFY_ENCRYPTED_ID, K256_VERIFY_HOMOMORPHIC_ID,
+    K256_VERIFY_ID, K256_VERIFY_MULTI_ID, K256_VERIFY_REGISTRY_ID, P256_VERIFY_ID,
+};
 // use selective_disclosure_methods::{SELECTIVE_DISCLOSURE_ELF,SELECTIVE_DISCLOSURE_ID};
 // use risc0_zkvm::{default_prover, ExecutorEnv};
-use risc0_zkvm::{compute_image_id,default_prover, ExecutorEnv, ProverOpts, VerifierContext, InnerReceipt, sha::Digestible};
-use std::fs::File;
-use std::io::Write;
-use anyhow::{Result, bail, Context};
-use alloy_sol_types::SolValue;
-use std::fs;
-// use serde_json::{self}; // <--- ADD THIS LINE
-
-use k256::{
-    ecdsa::{SigningKey, VerifyingKey, Signature, signature::Signer, signature::Verifier},
-    EncodedPoint,
+use anyhow::{anyhow, Context, Result};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::Duration,
 };
-// use rand_core::OsRng;
-use base64::{engine::general_purpose, Engine as _};
-use sha2::{Sha256, Digest};
-use hex;
+
 use clap::Parser;
-// struct Person {
-//     name: String,
-//     age: u32,
-//     is_student: bool,
-// }
-
-#[derive(Parser, Debug)]
-#[command(name = "checkvote", version, about = "Verify a poll vote")]
-struct Args {
-    /// Input filename (e.g., a JSON or hex file)
-    filename: String,
-
-    /// Poll ID
-    #[arg(long)]
-    poll_id: u64,
+use castvote_core::{
+    canonicalize_ballot, check_verifier_parameters, ElectionScope, MessageFormat, VoteParams,
+};
+use k256_example::{
+    cli::{election_scope, Args, Cli, Command, Curve, ErrorFormat, OutputFormat, Scheme},
+    atomic_write,
+    audit,
+    author::{attribute_to, author_public_key, sign_journal, AUTHOR_SIGNATURE_FILE},
+    bundle::{read_bundle, write_bundle, ProofBundle},
+    console::{self, summary_nullifiers, ProofSummary, SuccinctSummary},
+    diff::diff_journals,
+    kv::{journal_fields, render_fields},
+    ensure_writable_dir,
+    error::{ErrorReport, VoteError},
+    cache::{prove_vote_cached, ProofCache},
+    checkpoint::{run_resumable, Checkpoint, RateLimit, ResumableRun},
+    check_encodings,
+    encryption::{encrypt_ballot, parse_authority_key},
+    homomorphic::{
+        elgamal_input, read_authority_secret, read_homomorphic_journals, reveal_tallies,
+    },
+    keyfile::{encrypt_key_file, ScryptParams},
+    input::{
+        method_name, method_scheme, read_env, read_input, write_env, write_input, EnvDump,
+        GuestInput, InputDump, ProverSettings,
+    },
+    import_keys,
+    keys::import_author_key,
+    nullifier::{
+        check_id_commitment_file, check_nullifier_files, parse_voter_id, parse_voter_secret,
+    },
+    nullifier_store::{merge_stores, write_store, NullifierStore},
+    opening::{find_opening, save_opening, Opening, OPENINGS_FILE},
+    read_text_file,
+    resign::{dry_run_resign, resign_files, KeyRotation},
+    seal::{inspect_seal_file, SealEncoding, SealKind},
+    self_test::{self, TestVector},
+    solidity::generate_solidity,
+    submission::SubmissionTracker,
+    template::{render_template, TemplateOptions},
+    timings::{
+        read_timings, take_timings, timed, write_timings, Phase, RunTimings, Timings, TIMINGS_FILE,
+    },
+    watch::{TallyState, TallyWatcher, STATE_FILE},
+    output,
+    jsonl::{read_jsonl, read_signature_lines, JsonlBallot, SkippedLine},
+    load_ballot, read_ballot_file,
+    estimate::{estimate_vote, CostModel},
+    explain::{explain_ballot, explain_commitment},
+    prove::{
+        checked_image_id, compress_receipt, dump_receipt, encode_seal, journal_digest,
+        prove_vote_on, read_receipt_file, with_retries, with_timeout, write_artifacts,
+        write_receipt_file, ProveMode, ProverChoice, RetryPolicy, ARTIFACT_FILES,
+        DEFAULT_OUT_DIR, SUCCINCT_RECEIPT_FILE,
+    },
+    decode_homomorphic_journal, read_hex_file, read_image_id, read_journal_file, write_hex_file, write_json_file,
+    report::{self, AgeBuckets},
+    sign, sign_files, status, validate_ballot, verify_signature, VoterKey,
+};
+use k256::{ecdsa::SigningKey, SecretKey};
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::Serialize;
+use serde_json::json;
+use zeroize::Zeroizing;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let (error_format, quiet) = (cli.error_format, cli.quiet);
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            report_error(&err, error_format, quiet);
+            ExitCode::from(VoteError::exit_code_of(&err))
+        }
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// Prints the failure that ended the run. A JSON report goes to stdout with `--quiet`, where a
+/// script already reads the result from.
+fn report_error(err: &anyhow::Error, format: ErrorFormat, quiet: bool) {
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {err:?}"),
+        ErrorFormat::Json => {
+            let report = serde_json::to_string(&ErrorReport::new(err))
+                .expect("an error report always serializes");
+            if quiet {
+                println!("{report}");
+            } else {
+                eprintln!("{report}");
+            }
+        }
+    }
+}
 
-    let args = Args::parse();
-    println!("{:?}", args);
+/// Runs the command of `cli`; the category of a failure (see [VoteError]) picks the exit status.
+fn run(cli: Cli) -> Result<()> {
+    console::set_quiet(cli.quiet);
+    if cfg!(not(unix)) && cli.file_mode.is_some() {
+        return Err(VoteError::InvalidInput.error("--file-mode is only supported on Unix"));
+    }
+    output::set_file_mode(cli.file_mode);
+    let output = cli.output;
+    match (cli.command, cli.prove) {
+        (Some(Command::Report { journals, out, demographics, age_buckets }), _) => {
+            let buckets = if age_buckets.is_empty() {
+                AgeBuckets::default()
+            } else {
+                AgeBuckets::new(age_buckets).context(VoteError::InvalidInput)?
+            };
+            run_report(&journals, &out, demographics.map(|path| (path, buckets)), output)?
+        }
+        (Some(Command::Audit { dir, out }), _) => run_audit(&dir, &out, output)?,
+        (Some(Command::MergeNullifiers { out, stores }), _) => {
+            run_merge_nullifiers(&out, &stores, output)?
+        }
+        (Some(Command::WatchTally { dir, state, interval, once }), _) => {
+            let state = state.unwrap_or_else(|| dir.join(STATE_FILE));
+            run_watch_tally(&dir, &state, Duration::from_secs(interval), once, output)?
+        }
+        (
+            Some(Command::SimulateOnchain { seal, journal, image_id, expected_verifier_params }),
+            _,
+        ) => run_simulate_onchain(&seal, &journal, &image_id, expected_verifier_params, output)?,
+        (Some(Command::InspectSeal { seal }), _) => run_inspect_seal(&seal, output)?,
+        (Some(Command::DiffJournal { left, right }), _) => run_diff_journal(&left, &right, output)?,
+        (Some(Command::JournalToKv { journal, format }), _) => {
+            let journal = read_journal_file(&journal).context(VoteError::InvalidInput)?;
+            let fields = journal_fields(&journal).context(VoteError::InvalidInput)?;
+            print!("{}", render_fields(&fields, format)?);
+        }
+        (Some(Command::Compress { receipt, out_dir, receipt_out, bundle }), _) => {
+            run_compress(&receipt, &out_dir, receipt_out.as_deref(), bundle.as_deref(), output)?
+        }
+        (Some(Command::VerifyBundle { bundle, expected_verifier_params }), _) => {
+            run_verify_bundle(&bundle, expected_verifier_params, output)?
+        }
+        (Some(Command::GenSolidity { out }), _) => run_gen_solidity(out.as_deref())?,
+        (
+            Some(Command::VerifySig {
+                pubkey,
+                message_file,
+                signature,
+                scheme,
+                curve,
+                message_format,
+                eip191,
+            }),
+            _,
+        ) => {
+            let message_format = if eip191 { MessageFormat::Eip191 } else { message_format.into() };
+            let message = read_text_file(&message_file).context(VoteError::InvalidInput)?;
+            let (public_key, signature) = (pubkey.trim(), signature.trim());
+            run_verify_sig(scheme, curve, public_key, &message, signature, message_format, output)?
+        }
+        (Some(Command::SelfTest { host_only }), _) => run_self_test(host_only, output)?,
+        (Some(Command::Template { ballot_type, options, poll_id, birthdate, annotated }), _) => {
+            if annotated && output == OutputFormat::Json {
+                let message = "--annotated output is not JSON; drop --output json";
+                return Err(VoteError::InvalidInput.error(message));
+            }
+            let options =
+                TemplateOptions { ballot_type: ballot_type.into(), options, poll_id, birthdate };
+            println!("{}", render_template(&options, annotated).context(VoteError::InvalidInput)?);
+        }
+        (
+            Some(Command::CheckNullifier {
+                ballot,
+                poll_id,
+                journal,
+                election_id,
+                authority_seed_file,
+            }),
+            _,
+        ) => {
+            let election = election_scope(election_id.as_deref(), authority_seed_file.as_deref())?;
+            run_check_nullifier(&ballot, poll_id.poll_id(), &journal, election.as_ref(), output)?
+        }
+        (Some(Command::VoterSecret { voter_id, election_id, authority_seed_file }), _) => {
+            let election = election_scope(Some(&election_id), Some(&authority_seed_file))?
+                .expect("both the election ID and the seed file are given");
+            run_voter_secret(&parse_voter_id(&voter_id), &election, output)
+        }
+        (Some(Command::OpenIdCommitment { journal, voter_id, voter_secret }), _) => {
+            let voter_secret = parse_voter_secret(&voter_secret).context(VoteError::InvalidInput)?;
+            run_open_id_commitment(&journal, &parse_voter_id(&voter_id), &voter_secret, output)?
+        }
+        (Some(Command::Open { nullifier, journal, openings }), _) => {
+            let out_dir = Path::new(DEFAULT_OUT_DIR);
+            let journal = journal.unwrap_or_else(|| out_dir.join("journal.dat"));
+            let openings = openings.unwrap_or_else(|| out_dir.join(OPENINGS_FILE));
+            run_open(&nullifier, &journal, &openings, output)?
+        }
+        (Some(Command::Reveal { journals, authority_key, key_password, options }), _) => {
+            let secrets = authority_key
+                .iter()
+                .map(|path| read_authority_secret(path, key_password.as_ref()))
+                .collect::<Result<Vec<_>>>()
+                .context(VoteError::InvalidInput)?;
+            run_reveal(&journals, &secrets, &options, output)?
+        }
+        (
+            Some(Command::Resign {
+                old_pub,
+                new_priv,
+                ballots,
+                out_dir,
+                scheme,
+                curve,
+                message_format,
+                eip191,
+                dry_run,
+            }),
+            _,
+        ) => {
+            let message_format = if eip191 { MessageFormat::Eip191 } else { message_format.into() };
+            let new_priv_text = Zeroizing::new(read_text_file(&new_priv)?);
+            let new_key = VoterKey::from_base64(scheme, curve, new_priv_text.trim())
+                .with_context(|| format!("reading the new key from {}", new_priv.display()))?;
+            let rotation = KeyRotation {
+                scheme,
+                curve,
+                message_format,
+                old_public_key: old_pub.trim(),
+                new_key: &new_key,
+            };
+            run_resign(&ballots, &rotation, &out_dir, dry_run, output)?
+        }
+        (Some(Command::Keygen { scheme, curve, seed }), _) => {
+            run_keygen(scheme, curve, seed.as_deref(), output)?
+        }
+        (Some(Command::EncryptKey { key, out, key_password }), _) => {
+            encrypt_key_file(&key, &out, &key_password, ScryptParams::default())?;
+            status!("Wrote the encrypted key to {}", out.display());
+        }
+        (Some(Command::Estimate { mut args, cycles_per_second, cost_per_mcycle }), _) => {
+            args.resolve_poll_id().context(VoteError::InvalidInput)?;
+            prove(&args, Some(CostModel { cycles_per_second, cost_per_mcycle }), output)?
+        }
+        (None, Some(mut args)) => {
+            args.resolve_poll_id().context(VoteError::InvalidInput)?;
+            prove(&args, None, output)?
+        }
+        (None, None) => unreachable!("clap requires the proving arguments without a subcommand"),
+    }
+    Ok(())
+}
 
-    // Fixed private key for demonstration (Base64 encoded)
-    let exported_private_key_string = "WatoiP9UiA3fqB08TVHjBGniYDXUz/04mAGRLb7tyQY=";
+/// Tallies every journal matching `journals` and writes the counts to `out`, and the nested
+/// report and the rejected journals next to it.
+fn run_report(
+    journals: &str,
+    out: &Path,
+    demographics: Option<(PathBuf, AgeBuckets)>,
+    output: OutputFormat,
+) -> Result<()> {
+    let report = report::report_from_glob(journals)?;
+    report.write_counts_csv(out)?;
+    let json_out = report::json_path(out);
+    report.write_json(&json_out)?;
+    let rejected_out = report::rejected_path(out);
+    report.write_rejected_csv(&rejected_out)?;
+    if let Some((path, buckets)) = &demographics {
+        report::write_demographics_json(&report, buckets, path)?;
+    }
+    let demographics_out = demographics.map(|(path, _)| path);
 
-    // Fixed public key for demonstration (Base64 encoded)
-    let exported_public_key_string= "BLZgb3PHEJ6B7Xta+jR4CEn1g3NluqLxNNRlrDfhPTbMATkwv04TOAJJMWuSlrtOfuO9SQNIdGeLlL+ppflRHN4=";
-    
-    // 3. Sign Message
-    // /tmp/castvote/person.json
-    let file_content = fs::read_to_string(args.filename).expect("Failed to read JSON file");
+    if output == OutputFormat::Json {
+        let rejected: Vec<_> = report
+            .rejected()
+            .iter()
+            .map(|rejected| json!({"source": rejected.source, "reason": rejected.reason.to_string()}))
+            .collect();
+        println!(
+            "{}",
+            json!({
+                "results": out,
+                "rows": report.counts().len(),
+                "report": json_out,
+                "rejected_out": rejected_out,
+                "rejected": rejected,
+                "demographics": demographics_out,
+            })
+        );
+    } else {
+        status!("Wrote {} result rows to {}", report.counts().len(), out.display());
+        status!("Wrote the report by election and poll to {}", json_out.display());
+        if let Some(path) = &demographics_out {
+            let polls = report.demographics().len();
+            status!("Wrote demographics of {} polls to {}", polls, path.display());
+        }
+        for rejected in report.rejected() {
+            status!("Rejected {}: {}", rejected.source, rejected.reason);
+        }
+        let rejected = report.rejected().len();
+        status!("Wrote {} rejected journals to {}", rejected, rejected_out.display());
+    }
+    let double_votes = report.double_votes();
+    if double_votes > 0 {
+        let message = format!("{double_votes} journals reuse a nullifier counted before");
+        return Err(VoteError::DoubleVote.error(message));
+    }
+    Ok(())
+}
 
-    // 2. Parse JSON into struct
-    // let person: Person = serde_json::from_str(&file_content).expect("Failed to parse JSON");
+/// Audits every proof under `dir`, writes the CSV and JSON reports and prints a summary. Invalid
+/// proofs make the exit status non-zero, and so do duplicates if every proof verified.
+fn run_audit(dir: &Path, out: &Path, output: OutputFormat) -> Result<()> {
+    let audit = audit::audit_dir(dir)?;
+    audit.write_csv(out)?;
+    let json_out = audit::json_path(out);
+    audit.write_json(&json_out)?;
 
-    let message = file_content.clone();
-    let message_hash = Sha256::digest(&message); // Hash the message before signing
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            json!({
+                "csv": out,
+                "json": json_out,
+                "valid": audit.valid(),
+                "invalid": audit.invalid(),
+                "duplicate": audit.duplicates(),
+            })
+        );
+    } else {
+        for proof in audit.proofs() {
+            if proof.status != audit::ProofStatus::Valid {
+                status!("{}: {}", proof.proof, proof.status);
+            }
+        }
+        status!(
+            "Audited {} proofs: {} valid, {} invalid, {} duplicate",
+            audit.proofs().len(),
+            audit.valid(),
+            audit.invalid(),
+            audit.duplicates()
+        );
+        status!("Wrote the audit report to {} and {}", out.display(), json_out.display());
+    }
+    if audit.invalid() > 0 {
+        let message = format!("{} proofs failed verification", audit.invalid());
+        return Err(VoteError::VerificationFailed.error(message));
+    }
+    if audit.duplicates() > 0 {
+        let duplicates = audit.duplicates();
+        let message = format!("{duplicates} proofs reuse a nullifier of an earlier proof");
+        return Err(VoteError::DoubleVote.error(message));
+    }
+    Ok(())
+}
 
-    println!("\n--- Signing Message ---");
-    println!("Message: {:?}", message);
-    println!("Message Hash (hex): {}", hex::encode(&message_hash));
-    
-    // --- NEW: Import Private Key from String ---
-    println!("\n--- Importing Private Key ---");
-    let imported_private_key_bytes_vec = general_purpose::STANDARD.decode(&exported_private_key_string)?;
+/// Merges the nullifier `stores` into `out` and reports the nullifiers found in more than one,
+/// failing with [VoteError::DoubleVote] if there are any. The combined store is written either
+/// way, so the next runs reject every known nullifier.
+fn run_merge_nullifiers(out: &Path, stores: &[PathBuf], output: OutputFormat) -> Result<()> {
+    ensure_parent_writable(out)?;
+    let merged = merge_stores(stores).context(VoteError::InvalidInput)?;
+    write_store(out, &merged.nullifiers)?;
 
-    // Private keys for secp256k1 are 32 bytes (256 bits).
-    let imported_private_key_array: [u8; 32] = imported_private_key_bytes_vec
-        .as_slice()
-        .try_into()
-        .map_err(|_| "Failed to convert private key bytes to fixed-size array (expected 32 bytes)")?;
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            json!({
+                "out": out,
+                "stores": stores.len(),
+                "polls": merged.nullifiers.polls.len(),
+                "nullifiers": merged.nullifiers.len(),
+                "duplicates": merged.duplicates,
+            })
+        );
+    } else {
+        for duplicate in &merged.duplicates {
+            status!("Double vote: {duplicate}");
+        }
+        status!(
+            "Merged {} stores into {}: {} nullifiers in {} polls, {} in more than one store",
+            stores.len(),
+            out.display(),
+            merged.nullifiers.len(),
+            merged.nullifiers.polls.len(),
+            merged.duplicates.len()
+        );
+    }
+    if !merged.duplicates.is_empty() {
+        let message =
+            format!("{} nullifiers are in more than one store", merged.duplicates.len());
+        return Err(VoteError::DoubleVote.error(message));
+    }
+    Ok(())
+}
 
-    // Create a SigningKey from the raw bytes
-    let imported_signing_key = SigningKey::from_bytes((&imported_private_key_array).into())?;
-    // println!("Imported Signing Key (raw bytes): {:?}", imported_signing_key.to_bytes());
+/// Tallies the journals under `dir` every `interval`, resuming from and saving to `state`, and
+/// prints the totals whenever they change; with `once`, scans a single time and prints them.
+fn run_watch_tally(
+    dir: &Path,
+    state: &Path,
+    interval: Duration,
+    once: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut watcher = TallyWatcher::open(dir, state).context(VoteError::InvalidInput)?;
+    let tallied = watcher.state().journals.len();
+    status!("Watching {} ({tallied} journals already tallied)", dir.display());
+    let mut first = true;
+    loop {
+        let outcome = watcher.scan()?;
+        for (journal, reason) in &outcome.pending {
+            status!("Not tallied yet, retrying on the next scan: {journal}: {reason}");
+        }
+        if outcome.duplicates > 0 {
+            status!("Left out {} entries reusing a counted nullifier", outcome.duplicates);
+        }
+        if first || outcome.tallied > 0 {
+            print_tally(watcher.state(), output);
+        }
+        if once {
+            return Ok(());
+        }
+        first = false;
+        thread::sleep(interval);
+    }
+}
 
-    let signature: Signature = imported_signing_key.sign(&message_hash);
-    // println!("Signature (raw bytes): {:?}", signature.to_bytes());
+/// Prints the running totals of a [TallyState].
+fn print_tally(state: &TallyState, output: OutputFormat) {
+    match output {
+        OutputFormat::Json => {
+            let json = json!({
+                "journals": state.journals.len(),
+                "duplicates": state.duplicates,
+                "counts": state.counts,
+                "abstentions": state.abstentions,
+            });
+            println!("{json}");
+        }
+        OutputFormat::Text => {
+            println!("{} journals, {} duplicates", state.journals.len(), state.duplicates);
+            for (poll_id, counts) in &state.counts {
+                let counts: Vec<_> =
+                    counts.iter().map(|(choice, count)| format!("{choice}={count}")).collect();
+                println!("  poll {poll_id}: {}", counts.join(", "));
+            }
+            for (poll_id, abstentions) in &state.abstentions {
+                println!("  poll {poll_id}: {abstentions} abstained");
+            }
+        }
+    }
+}
 
-    // 4. Export Signature to String (Base64)
-    let exported_signature_string = general_purpose::STANDARD.encode(signature.to_bytes());
-    println!("Exported Signature (Base64): {}", exported_signature_string);
+/// Runs the checks of the on-chain verifier over the artifacts and prints which one, if any,
+/// failed; a failure also makes the exit status non-zero.
+fn run_simulate_onchain(
+    seal: &Path,
+    journal: &Path,
+    image_id: &str,
+    expected_verifier_params: Option<Digest>,
+    output: OutputFormat,
+) -> Result<()> {
+    let seal = read_hex_file(seal)?;
+    let journal = read_journal_file(journal)?;
+    let image_id = read_image_id(image_id)?;
+    let result = expected_verifier_params
+        .map_or(Ok(()), |expected| check_verifier_parameters(&seal, expected))
+        .and_then(|()| castvote_core::simulate_onchain(&seal, &journal, image_id));
+    match (&result, output) {
+        (Ok(journal_digest), OutputFormat::Json) => println!(
+            "{}",
+            json!({
+                "pass": true,
+                "image_id": image_id.to_string(),
+                "journal_digest": journal_digest.to_string(),
+            })
+        ),
+        (Err(err), OutputFormat::Json) => println!(
+            "{}",
+            json!({"pass": false, "check": err.failed_check(), "error": err.to_string()})
+        ),
+        (Ok(journal_digest), OutputFormat::Text) => {
+            println!("PASS: the verifier would accept this seal");
+            println!("  image id:       {image_id}");
+            println!("  journal digest: {journal_digest}");
+        }
+        (Err(err), OutputFormat::Text) => println!("FAIL ({}): {err}", err.failed_check()),
+    }
+    if result.is_err() {
+        return Err(VoteError::VerificationFailed.error("on-chain verification would revert"));
+    }
+    Ok(())
+}
 
-    // 5. Import Signature from String
-    println!("\n--- Importing signature and Verifying ---");
-    let imported_signature_bytes = general_purpose::STANDARD.decode(&exported_signature_string)?;
+/// Verifies the proof bundle at `path`, under exactly the `expected_verifier_params` if given,
+/// and prints the reveal entries its journal commits, or why it does not verify.
+fn run_verify_bundle(
+    path: &Path,
+    expected_verifier_params: Option<Digest>,
+    output: OutputFormat,
+) -> Result<()> {
+    let bundle = read_bundle(path).context(VoteError::InvalidInput)?;
+    let checked = match expected_verifier_params {
+        Some(expected) => bundle.seal_bytes().and_then(|seal| {
+            check_verifier_parameters(&seal, expected)
+                .map_err(|e| anyhow!("{} failed: {e}", e.failed_check()))
+        }),
+        None => Ok(()),
+    };
+    let reveals = match checked.and_then(|()| bundle.verify()) {
+        Ok(reveals) => reveals,
+        Err(err) => {
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", json!({"pass": false, "error": format!("{err:#}")}))
+                }
+                OutputFormat::Text => println!("FAIL: {err:#}"),
+            }
+            return Err(VoteError::VerificationFailed.error("the proof bundle does not verify"));
+        }
+    };
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "pass": true,
+                "image_id": bundle.image_id,
+                "created_at": bundle.created_at,
+                "tool_version": bundle.tool_version,
+                "author_public_key": bundle.author_public_key,
+                "reveals": reveals,
+            })
+        ),
+        OutputFormat::Text => {
+            println!("PASS: the proof bundle verifies");
+            println!("  image id:     {}", bundle.image_id);
+            println!("  created at:   {}", bundle.created_at);
+            println!("  tool version: {}", bundle.tool_version);
+            if let Some(author) = &bundle.author_public_key {
+                println!("  author:       {author}");
+            }
+            for reveal in &reveals {
+                println!("  poll {}: nullifier {}", reveal.poll_id, reveal.nullifier);
+            }
+        }
+    }
+    Ok(())
+}
 
-    let imported_signature_array: [u8; 64] = imported_signature_bytes.as_slice().try_into()
-    .map_err(|_| "Failed to convert signature bytes to fixed-size array")?; // Handle potential length mismatch
+/// Checks the pipeline against the built-in test vector, proving it in dev mode unless
+/// `host_only`, and prints a PASS or FAIL line per check; any failure makes the exit status
+/// non-zero.
+fn run_self_test(host_only: bool, output: OutputFormat) -> Result<()> {
+    let vector = TestVector::builtin()?;
+    let mut checks = self_test::run_self_test(&vector, self_test::BALLOT);
+    if !host_only {
+        status!("Proving the test vector in dev mode...");
+        checks.push(self_test::prove_self_test(&vector, self_test::BALLOT));
+    }
+    let pass = checks.iter().all(|check| check.pass);
+    match output {
+        OutputFormat::Json => println!("{}", json!({ "pass": pass, "checks": checks })),
+        OutputFormat::Text => {
+            for check in &checks {
+                println!("{check}");
+            }
+            println!("{}", if pass { "PASS" } else { "FAIL" });
+        }
+    }
+    if !pass {
+        return Err(VoteError::VerificationFailed.error("the self-test failed"));
+    }
+    Ok(())
+}
 
-    let imported_signature = Signature::from_bytes((&imported_signature_array).into())?;
-    // println!("Imported Signature (from string): {:?}", imported_signature.to_bytes());
+/// Prints the selector, length and kind of the seal at `path`.
+fn run_inspect_seal(path: &Path, output: OutputFormat) -> Result<()> {
+    let info = inspect_seal_file(path).context(VoteError::InvalidInput)?;
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&info)?),
+        OutputFormat::Text => {
+            let encoding = match info.encoding {
+                SealEncoding::Hex => "hex",
+                SealEncoding::Binary => "binary",
+            };
+            println!("encoding: {encoding}");
+            println!("selector: {}", hex::encode(info.selector));
+            let kind = match info.kind {
+                SealKind::Fake => "fake (all-zero selector: dev mode, rejected on-chain)",
+                SealKind::Groth16 => "groth16",
+                SealKind::Unknown => "unknown (not the Groth16 verifier of this build)",
+            };
+            println!("kind:     {kind}");
+            println!("length:   {} bytes, {} after the selector", info.seal_len, info.proof_len);
+            if let Some(expected) = info.expected_proof_len().filter(|&n| n != info.proof_len) {
+                println!("warning:  a {} seal has {expected} bytes after the selector", info.kind);
+            }
+        }
+    }
+    Ok(())
+}
 
+/// Prints the fields in which the journals at `left` and `right` differ.
+fn run_diff_journal(left: &Path, right: &Path, output: OutputFormat) -> Result<()> {
+    let left_journal = read_journal_file(left).context(VoteError::InvalidInput)?;
+    let right_journal = read_journal_file(right).context(VoteError::InvalidInput)?;
+    let diff = diff_journals(&left_journal, &right_journal).context(VoteError::InvalidInput)?;
+    match output {
+        OutputFormat::Json => {
+            let mut report = json!(diff);
+            report["versions_differ"] = json!(diff.versions_differ());
+            report["identical"] = json!(diff.is_identical());
+            println!("{report}");
+        }
+        OutputFormat::Text => {
+            let (left, right) = (left.display(), right.display());
+            if diff.versions_differ() {
+                println!(
+                    "versions differ: {left} is version {}, {right} is version {}; fields a \
+                     version predates decode as null",
+                    diff.left_version, diff.right_version
+                );
+            }
+            for field in &diff.fields {
+                println!("{}", field.field);
+                println!("  {left}: {}", field.left);
+                println!("  {right}: {}", field.right);
+            }
+            if diff.is_identical() {
+                println!("journals are identical");
+            }
+        }
+    }
+    Ok(())
+}
 
-    // 6. Import Verifying Key from String (for verification by another party)
-    let imported_public_key_bytes = general_purpose::STANDARD.decode(&exported_public_key_string)?;
-    let imported_verifying_key = VerifyingKey::from_encoded_point(
-        &EncodedPoint::from_bytes(&imported_public_key_bytes)?
-    )?;
-    // println!("Imported Verifying Key (from string): {:?}", imported_verifying_key.to_encoded_point(false).as_bytes());
+/// Writes the generated Solidity journal decoder to `out`, or prints it on stdout.
+fn run_gen_solidity(out: Option<&Path>) -> Result<()> {
+    let solidity = generate_solidity();
+    match out {
+        Some(path) => {
+            atomic_write(path, solidity)?;
+            status!("Wrote the Solidity journal decoder to {}", path.display());
+        }
+        None => print!("{solidity}"),
+    }
+    Ok(())
+}
 
-    // 7. Verify the Imported Signature using the Imported Verifying Key
-    let is_valid = imported_verifying_key.verify(&message_hash, &imported_signature).is_ok();
+/// Prints whether `signature` over `message` verifies against `public_key`, hashed as the
+/// prover hashes it in `message_format`. An invalid signature exits with the invalid-signature
+/// status.
+fn run_verify_sig(
+    scheme: Scheme,
+    curve: Curve,
+    public_key: &str,
+    message: &str,
+    signature: &str,
+    message_format: MessageFormat,
+    output: OutputFormat,
+) -> Result<()> {
+    let verified = message_format
+        .signed_text(message)
+        .context("the message is not a ballot; use --message-format raw")
+        .and_then(|text| {
+            verify_signature(scheme, curve, &text, signature, public_key, message_format)
+        });
+    match (&verified, output) {
+        (Ok(()), OutputFormat::Json) => println!("{}", json!({ "valid": true })),
+        (Err(e), OutputFormat::Json) => {
+            println!("{}", json!({ "valid": false, "reason": format!("{e:#}") }))
+        }
+        (Ok(()), OutputFormat::Text) => println!("valid"),
+        (Err(e), OutputFormat::Text) => println!("invalid: {e:#}"),
+    }
+    if verified.is_err() {
+        return Err(VoteError::InvalidSignature.error("the signature is not valid"));
+    }
+    Ok(())
+}
 
-    println!("\nSignature Verified: {}", is_valid);
+/// Recomputes the nullifier of `ballot` in `poll_id` and prints whether `journal` commits it; a
+/// mismatch also makes the exit status non-zero.
+fn run_check_nullifier(
+    ballot: &Path,
+    poll_id: u64,
+    journal: &Path,
+    election: Option<&ElectionScope>,
+    output: OutputFormat,
+) -> Result<()> {
+    let result = check_nullifier_files(ballot, poll_id, journal, election)?;
+    match (&result, output) {
+        (Ok(found), OutputFormat::Json) => println!(
+            "{}",
+            json!({"match": true, "nullifier": found.nullifier, "entry": found.index})
+        ),
+        (Err(mismatch), OutputFormat::Json) => {
+            println!("{}", json!({"match": false, "error": mismatch.to_string()}))
+        }
+        (Ok(found), OutputFormat::Text) => {
+            println!("MATCH: the journal commits the ballot's nullifier");
+            println!("  nullifier: {}", found.nullifier);
+            println!("  entry:     {}", found.index);
+        }
+        (Err(mismatch), OutputFormat::Text) => println!("MISMATCH: {mismatch}"),
+    }
+    if result.is_err() {
+        return Err(VoteError::VerificationFailed.error("the journal is not for this ballot"));
+    }
+    Ok(())
+}
 
-    // Test with a tampered message
-    let tampered_message = b"This is a tampered message.";
-    let tampered_message_hash = Sha256::digest(tampered_message);
-    let is_tampered_valid = imported_verifying_key.verify(&tampered_message_hash, &imported_signature).is_ok();
-    println!("Signature verified with tampered message: {}", is_tampered_valid);
+/// Prints the secret of `voter_id` in `election`, which opens that voter's ID commitment.
+fn run_voter_secret(voter_id: &serde_json::Value, election: &ElectionScope, output: OutputFormat) {
+    let voter_secret = hex::encode(election.voter_secret(voter_id));
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "voter_id": voter_id,
+                "election_id": election.election_id,
+                "voter_secret": voter_secret,
+            })
+        ),
+        OutputFormat::Text => println!("{voter_secret}"),
+    }
+}
 
-    let poll_id : u64 = args.poll_id;
-    let _ = disclose(&exported_signature_string, &message, poll_id);
+/// Checks that `journal` commits to `voter_id` under `voter_secret` and prints the nullifiers
+/// that belong to the voter.
+fn run_open_id_commitment(
+    journal: &Path,
+    voter_id: &serde_json::Value,
+    voter_secret: &[u8; 32],
+    output: OutputFormat,
+) -> Result<()> {
+    let result = check_id_commitment_file(journal, voter_id, voter_secret)?;
+    match (&result, output) {
+        (Ok(found), OutputFormat::Json) => {
+            let entries: Vec<_> = found
+                .iter()
+                .map(|found| json!({"nullifier": found.nullifier, "entry": found.index}))
+                .collect();
+            println!("{}", json!({"match": true, "voter_id": voter_id, "entries": entries}))
+        }
+        (Err(mismatch), OutputFormat::Json) => {
+            println!("{}", json!({"match": false, "error": mismatch.to_string()}))
+        }
+        (Ok(found), OutputFormat::Text) => {
+            println!("MATCH: the journal commits to voter {voter_id}");
+            for found in found {
+                println!("  nullifier: {} (entry {})", found.nullifier, found.index);
+            }
+        }
+        (Err(mismatch), OutputFormat::Text) => println!("MISMATCH: {mismatch}"),
+    }
+    if result.is_err() {
+        return Err(VoteError::VerificationFailed.error("the journal is not for this voter"));
+    }
+    Ok(())
+}
 
+/// Checks the saved opening of `nullifier` against the ciphertexts `journal` commits and prints
+/// the vote it proves.
+fn run_open(nullifier: &str, journal: &Path, openings: &Path, output: OutputFormat) -> Result<()> {
+    let opening = find_opening(openings, nullifier)?;
+    let committed = decode_homomorphic_journal(&read_journal_file(journal)?)?;
+    opening.verify(&committed).context(VoteError::VerificationFailed)?;
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "opened": true,
+                "nullifier": opening.nullifier,
+                "poll_id": opening.poll_id,
+                "selection": opening.selection,
+            })
+        ),
+        OutputFormat::Text => {
+            println!("OPENED: the journal encrypts this vote");
+            println!("  nullifier: {}", opening.nullifier);
+            println!("  poll:      {}", opening.poll_id);
+            println!("  selection: {}", opening.selection.join(", "));
+        }
+    }
     Ok(())
 }
 
-fn disclose(signature: &str, data: &str, poll_id: u64) -> Result<()> {
+/// Decrypts the tallies of the homomorphic journals matching `journals` with the authority's
+/// `secrets`, labelling the totals with `options` if given.
+fn run_reveal(
+    journals: &str,
+    secrets: &[SecretKey],
+    options: &[String],
+    output: OutputFormat,
+) -> Result<()> {
+    let reveals = read_homomorphic_journals(journals)?;
+    if reveals.is_empty() {
+        return Err(VoteError::InvalidInput.error(format!("no journals match {journals:?}")));
+    }
+    let tallies = reveal_tallies(&reveals, secrets)?;
+    let mislabelled = tallies
+        .iter()
+        .find(|tally| !options.is_empty() && tally.totals.len() != options.len());
+    if let Some(tally) = mislabelled {
+        let message = format!(
+            "poll {} has {} options, but {} were given",
+            tally.poll_id,
+            tally.totals.len(),
+            options.len()
+        );
+        return Err(VoteError::InvalidInput.error(message));
+    }
 
-    println!("signature {:?}", signature);
-    println!("data {}", data);
+    match output {
+        OutputFormat::Json => {
+            let polls: Vec<_> = tallies
+                .iter()
+                .map(|tally| {
+                    let mut poll = json!(tally);
+                    if !options.is_empty() {
+                        poll["options"] = json!(options);
+                    }
+                    poll
+                })
+                .collect();
+            println!("{}", json!({ "polls": polls }));
+        }
+        OutputFormat::Text => {
+            for tally in &tallies {
+                let dropped = match tally.duplicates {
+                    0 => String::new(),
+                    n => format!(", {n} duplicate(s) dropped"),
+                };
+                println!("Poll {}: {} ballots{dropped}", tally.poll_id, tally.ballots);
+                for (index, total) in tally.totals.iter().enumerate() {
+                    let label = options.get(index).cloned().unwrap_or_else(|| index.to_string());
+                    println!("  {label}: {total}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-    let input = (signature, data, poll_id);
+/// Re-signs the signed ballots matching `ballots` under the new key of `rotation`, refusing to
+/// write anything if any old signature does not verify, and with `dry_run` writing nothing at
+/// all, only listing the files it would write.
+fn run_resign(
+    ballots: &str,
+    rotation: &KeyRotation,
+    out_dir: &Path,
+    dry_run: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let summary = if dry_run {
+        dry_run_resign(ballots, rotation, out_dir)?
+    } else {
+        resign_files(ballots, rotation, out_dir)?
+    };
+    match output {
+        OutputFormat::Json => {
+            let rejected: Vec<_> = summary
+                .rejected
+                .iter()
+                .map(|r| json!({"source": r.source, "index": r.index, "reason": r.reason}))
+                .collect();
+            let report = if dry_run {
+                json!({
+                    "dry_run": true,
+                    "resigned": summary.resigned,
+                    "rejected": rejected,
+                    "would_write": summary.files,
+                })
+            } else {
+                json!({"resigned": summary.resigned, "rejected": rejected, "files": summary.files})
+            };
+            println!("{report}");
+        }
+        OutputFormat::Text => {
+            for r in &summary.rejected {
+                println!("REJECTED {} entry {}: {}", r.source.display(), r.index, r.reason);
+            }
+            for file in &summary.files {
+                if dry_run {
+                    status!("Would write {}", file.display());
+                } else {
+                    status!("Wrote {}", file.display());
+                }
+            }
+        }
+    }
+    if !summary.rejected.is_empty() {
+        let message = format!(
+            "{} signatures do not verify against the old key; nothing was re-signed",
+            summary.rejected.len()
+        );
+        return Err(VoteError::InvalidSignature.error(message));
+    }
+    if dry_run {
+        status!(
+            "Dry run: {} ballots would be re-signed in {} files, nothing was written",
+            summary.resigned,
+            summary.files.len()
+        );
+    } else {
+        status!("Re-signed {} ballots in {} files", summary.resigned, summary.files.len());
+    }
+    Ok(())
+}
 
-    let env = ExecutorEnv::builder()
-        .write(&input)
-        .unwrap()
-        .build()
-        .unwrap();
+/// Generates a key pair, from `seed` if given, and prints it.
+fn run_keygen(
+    scheme: Scheme,
+    curve: Curve,
+    seed: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let key = match seed {
+        Some(seed) => {
+            let seed = hex::decode(seed.strip_prefix("0x").unwrap_or(seed))
+                .map(Zeroizing::new)
+                .context("--seed is not hex encoded")
+                .context(VoteError::InvalidInput)?;
+            if seed.is_empty() {
+                return Err(VoteError::InvalidInput.error("--seed is empty"));
+            }
+            // Printed even with --quiet: a seeded key must never end up guarding real votes.
+            eprintln!(
+                "warning: keys derived from --seed are insecure, for test vectors only; never \
+                 use them in production"
+            );
+            VoterKey::from_seed(scheme, curve, &seed)?
+        }
+        None => VoterKey::generate(scheme, curve)?,
+    };
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "private_key": key.private_key_base64(),
+                "public_key": key.public_key_base64(),
+                "seeded": seed.is_some(),
+            })
+        ),
+        OutputFormat::Text => {
+            println!("private key: {}", key.private_key_base64());
+            println!("public key:  {}", key.public_key_base64());
+        }
+    }
+    Ok(())
+}
 
-    // Obtain the default prover.
-    let prover = default_prover();
+/// What becomes of a guest input once it is built.
+enum Run<'a> {
+    /// Prove it and write the artifacts.
+    Prove {
+        out_dir: &'a Path,
+        receipt_out: Option<&'a Path>,
+        bundle: Option<&'a Path>,
+        cache: Option<&'a ProofCache>,
+        retry: RetryPolicy,
+        prover: ProverChoice,
+        mode: ProveMode,
+        output: OutputFormat,
+        /// Rejects known double votes before proving and records the nullifiers proven.
+        nullifiers: Option<&'a RefCell<NullifierStore>>,
+        /// Signs the journal after proving, attributing the proof to the proving service.
+        author: Option<&'a SigningKey>,
+        /// Saves the executor input and prover settings before proving, for `--from-env`.
+        dump_env: Option<&'a Path>,
+        /// Checks the input and lists the files the run would write, writing none of them.
+        dry_run: Option<DryRun>,
+    },
+    /// Only execute it and print the estimated proving cost.
+    Estimate { model: CostModel, output: OutputFormat },
+    /// Save it to this file for a later `--input` run.
+    DumpInput(&'a Path),
+    /// Replay the guest's checks on the host and print each step.
+    Explain(OutputFormat),
+}
 
-    println!("start prove ");
-    // Produce a receipt by proving the specified ELF binary.
-    // let receipt = prover.prove(env, K256_VERIFY_ELF).unwrap().receipt;
+/// How far a `--dry-run` goes before listing what it would write.
+#[derive(Clone, Copy)]
+struct DryRun {
+    /// Also prove and verify the receipt, with `--with-prover`, rather than stop at the checks.
+    prove: bool,
+    /// The run would add the opening of an encrypted vote to [OPENINGS_FILE], for `--elgamal-to`.
+    opening: bool,
+}
 
-    let receipt = prover.prove_with_ctx(
-        env,
-        &VerifierContext::default(),
-        K256_VERIFY_ELF,
-        &ProverOpts::groth16(),
-    )?
-    .receipt;
+/// Signs the ballots named in `args` and proves them with the matching guest method, or with
+/// `estimate` only executes the guest and reports what proving would cost. `--dump-input` saves
+/// the guest input instead, and `--input` starts from a saved one, as `--from-env` does from a
+/// saved executor input with its prover settings. The result is printed on stdout as `output`.
+fn prove(args: &Args, estimate: Option<CostModel>, output: OutputFormat) -> Result<()> {
+    if args.no_prove {
+        if estimate.is_some() {
+            let message = "estimate needs the guest input; drop --no-prove";
+            return Err(VoteError::InvalidInput.error(message));
+        }
+        let params = args.vote_params().context(VoteError::InvalidInput)?;
+        return sign_only(args, &params, &import_keys(args)?);
+    }
 
-    println!("start verify ");
-    receipt.verify(K256_VERIFY_ID).unwrap();
+    let env = match &args.from_env {
+        Some(path) => Some(read_env(path).context(VoteError::InvalidInput)?),
+        None => None,
+    };
+    let mode = env.as_ref().map_or_else(|| args.prove_mode(), |env| env.prover.mode);
+    let cache = args.proof_cache();
+    if estimate.is_some() && args.nullifier_store.is_some() {
+        let message = "estimate proves nothing; drop --nullifier-store";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+    if estimate.is_some() && args.dry_run {
+        let message = "estimate writes nothing; drop --dry-run";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+    let dry_run = args
+        .dry_run
+        .then_some(DryRun { prove: args.with_prover, opening: args.elgamal_to.is_some() });
+    let author = import_author_key(args).context(VoteError::InvalidInput)?;
+    if author.is_some() && mode == ProveMode::Succinct {
+        let message = "a succinct proof is not signed; drop --commit-author-signature";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+    let nullifier_store = match &args.nullifier_store {
+        Some(path) => {
+            if dry_run.is_none() {
+                ensure_parent_writable(path)?;
+            }
+            Some(RefCell::new(NullifierStore::open(path).context(VoteError::InvalidInput)?))
+        }
+        None => None,
+    };
+    let run = match (estimate, &args.dump_input) {
+        (Some(_), Some(_)) => {
+            let message = "estimate runs the guest input; drop --dump-input";
+            return Err(VoteError::InvalidInput.error(message));
+        }
+        (Some(_), None) if args.explain => {
+            let message = "estimate runs the guest input; drop --explain";
+            return Err(VoteError::InvalidInput.error(message));
+        }
+        (Some(model), None) => Run::Estimate { model, output },
+        (None, Some(path)) => {
+            ensure_parent_writable(path)?;
+            Run::DumpInput(path)
+        }
+        (None, None) if args.explain => Run::Explain(output),
+        (None, None) => {
+            if args.bundle.is_some() && mode == ProveMode::Succinct {
+                let message = "a succinct proof has no seal; pass --bundle to compress";
+                return Err(VoteError::InvalidInput.error(message));
+            }
+            // Fail on an unusable output location now, not after minutes of proving. A dry run
+            // creates no directories and leaves the cache alone.
+            if dry_run.is_none() {
+                ensure_writable_dir(&args.out_dir)?;
+                let files = [&args.receipt_out, &args.bundle, &args.dump_env];
+                for path in files.into_iter().flatten() {
+                    ensure_parent_writable(path)?;
+                }
+                prune_cache(cache.as_ref())?;
+            }
+            let prover = match &env {
+                Some(env) => env.prover.choice,
+                None => args.prover().context(VoteError::InvalidInput)?,
+            };
+            Run::Prove {
+                out_dir: &args.out_dir,
+                receipt_out: args.receipt_out.as_deref(),
+                bundle: args.bundle.as_deref(),
+                cache: cache.as_ref(),
+                retry: args.retry_policy(),
+                prover,
+                mode,
+                output,
+                nullifiers: nullifier_store.as_ref(),
+                author: author.as_ref(),
+                dump_env: args.dump_env.as_deref(),
+                dry_run,
+            }
+        }
+    };
 
-    // Encode the seal with the selector.
-    let seal = encode_seal(&receipt)?;
+    let seed_file = args.authority_seed_file.as_deref();
+    if let Some(path) = &args.input {
+        let mut dump = read_input(path)?;
+        dump.restore_seed(seed_file).context(VoteError::InvalidInput)?;
+        return prove_dumped(path, dump, &run);
+    }
+    if let (Some(path), Some(mut env)) = (&args.from_env, env) {
+        env.input.restore_seed(seed_file).context(VoteError::InvalidInput)?;
+        return prove_from_env(path, env, &run);
+    }
 
-    // let seal_hex_string = vec_to_hex_string(&seal);
-    println!("seal hex_string: {}", hex::encode(&seal));
+    let mut params = args.vote_params().context(VoteError::InvalidInput)?;
+    if let Some(author) = &author {
+        attribute_to(&mut params, author);
+        status!("Author public key (Base64): {}", author_public_key(author));
+    }
+    if args.scheme == Scheme::AgeOnly {
+        return prove_age_only(args, &params, &run);
+    }
+    if let Some(jsonl) = &args.jsonl {
+        return prove_jsonl(args, &params, jsonl, &run);
+    }
 
+    if let Some(signature) = args.external_signature()? {
+        return prove_presigned(args, &params, signature, &run);
+    }
 
-    // Write seal to a file
-    let mut file = File::create("/tmp/castvote/seal.dat").expect("failed to create file");
-    file.write_all(hex::encode(&seal).as_bytes()).expect("failed to write");
+    let key = timed(Phase::KeyImport, || import_keys(args))?;
+    status!("Voter public key (Base64): {}", key.public_key_base64());
 
-    // Extract the journal from the receipt.
-    let journal = receipt.journal.bytes.clone();
+    if args.filenames.len() > 1 {
+        if args.encrypt_to.is_some() || args.elgamal_to.is_some() || !args.polls.is_empty() {
+            let message = "--encrypt-to, --elgamal-to and --polls take a single ballot";
+            return Err(VoteError::InvalidInput.error(message));
+        }
+        return prove_batch(&args.filenames, args.poll_id(), &params, &key, &run);
+    }
 
-    // Decode Journal: Upon receiving the proof, the application decodes the journal to extract
-    // the verified number. This ensures that the number being submitted to the blockchain matches
-    // the number that was verified off-chain.
+    let mut ballot = load_ballot(&args.filenames[0], &params)?;
+    if let Some(authority_key) = &args.encrypt_to {
+        ballot = encrypt_ballot(&ballot, &parse_authority_key(authority_key)?)?;
+        status!("Encrypted the choice to the authority key");
+    }
+    let signature = timed(Phase::Signing, || sign(&key, &ballot, params.message_format))?;
+    status!("Exported Signature (Base64): {}", signature);
 
+    let public_key = key.public_key_base64();
+    check_encodings(&key, &signature, &public_key)?;
 
-    println!("journal: {}", hex::encode(journal.clone()));
+    let (elf, image_id) = args.method();
+    if !args.polls.is_empty() {
+        let input = (signature, ballot, args.polls.clone(), public_key);
+        return disclose(&input, &params, elf, image_id, &run);
+    }
+    let input = (signature, ballot, args.poll_id(), public_key);
+    if let Some(election_key) = &args.elgamal_to {
+        let elgamal = elgamal_input(&parse_authority_key(election_key)?, params.options.len());
+        let opening = Opening::new(&input.1, input.2, &params, &elgamal)?;
+        disclose(&(input, elgamal), &params, elf, image_id, &run)?;
+        // A dry run listed the openings file with the other files it would write.
+        if let Run::Prove { out_dir, dry_run: None, .. } = &run {
+            let path = out_dir.join(OPENINGS_FILE);
+            save_opening(&path, opening)?;
+            status!("Saved the opening of the encrypted vote to {}", path.display());
+        }
+        return Ok(());
+    }
+    disclose(&input, &params, elf, image_id, &run)
+}
 
-    // Write the journal to a file
-    let mut file = File::create("/tmp/castvote/journal.dat").expect("failed to create file");
-    file.write_all(hex::encode(journal.clone()).as_bytes()).expect("failed to write");
+/// Checks that the file `path` can be created, with [ensure_writable_dir] on its directory.
+fn ensure_parent_writable(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => ensure_writable_dir(dir),
+        _ => ensure_writable_dir(Path::new(".")),
+    }
+}
 
-    let x = Vec::<u8>::abi_decode(&journal).context("decoding journal data")?;
-    
-    println!("journal abi_decode: {}", hex::encode(&x));
+/// Removes cached receipts of guests that have since been rebuilt, which can never be hits again.
+fn prune_cache(cache: Option<&ProofCache>) -> Result<()> {
+    if let Some(cache) = cache {
+        let current = [
+            K256_VERIFY_ID,
+            K256_VERIFY_BATCH_ID,
+            K256_VERIFY_MULTI_ID,
+            K256_COSIGN_ID,
+            K256_VERIFY_REGISTRY_ID,
+            K256_VERIFY_ENCRYPTED_ID,
+            K256_VERIFY_HOMOMORPHIC_ID,
+            P256_VERIFY_ID,
+            ED25519_VERIFY_ID,
+            AGE_ELIGIBILITY_ID,
+        ];
+        let removed = cache.prune(&current)?;
+        if removed > 0 {
+            status!("Removed {removed} stale image directories from {}", cache.dir().display());
+        }
+    }
+    Ok(())
+}
 
-    // Write the journal abi to a file
-    let mut file = File::create("/tmp/castvote/journal_abi.dat").expect("failed to create file");
-    file.write_all(hex::encode(&x).as_bytes()).expect("failed to write");
+/// Proves the guest input `dump` saved at `path` by `--dump-input` or `--dump-env`, with the
+/// method and parameters it was saved with.
+fn prove_dumped(path: &Path, dump: InputDump, run: &Run) -> Result<()> {
+    let (elf, image_id, unchanged) = dump.method()?;
+    if !unchanged {
+        status!(
+            "Warning: {} was saved for {} image {}, proving with the current image {}",
+            path.display(),
+            dump.method,
+            dump.image_id,
+            checked_image_id(elf, image_id)?
+        );
+    }
+    match dump.input {
+        GuestInput::Ballot(input) => disclose(&input, &dump.params, elf, image_id, run),
+        GuestInput::Batch(inputs) => disclose(&inputs, &dump.params, elf, image_id, run),
+        GuestInput::Homomorphic(input, elgamal) => {
+            disclose(&(input, elgamal), &dump.params, elf, image_id, run)
+        }
+        GuestInput::MultiPoll(input) => disclose(&input, &dump.params, elf, image_id, run),
+        GuestInput::AgeEligibility(input) => disclose(&input, &dump.params, elf, image_id, run),
+    }
+}
 
-    // Compute the Image ID
-    let image_id = hex::encode(compute_image_id(K256_VERIFY_ELF)?);
+/// Proves the executor input `env` saved at `path` by `--dump-env`, after checking that it still
+/// serializes to the recorded words, and warns about prover settings this build does not match.
+fn prove_from_env(path: &Path, env: EnvDump, run: &Run) -> Result<()> {
+    env.check_digest()
+        .with_context(|| format!("replaying {}", path.display()))
+        .context(VoteError::InvalidInput)?;
+    let current = ProverSettings::current(env.prover.choice, env.prover.mode);
+    if current.dev_mode != env.prover.dev_mode {
+        let (was, is) = match env.prover.dev_mode {
+            true => ("on", "off"),
+            false => ("off", "on"),
+        };
+        status!(
+            "Warning: {} was recorded with dev mode {was}, replaying with it {is}; set \
+             RISC0_DEV_MODE to match",
+            path.display()
+        );
+    }
+    if (&current.hashfn, &current.receipt_kind) != (&env.prover.hashfn, &env.prover.receipt_kind) {
+        status!(
+            "Warning: {} was recorded with hash function {} for a {} receipt, this build uses {} \
+             for a {} receipt",
+            path.display(),
+            env.prover.hashfn,
+            env.prover.receipt_kind,
+            current.hashfn,
+            current.receipt_kind
+        );
+    }
+    status!("Replaying executor input {} from {}", env.env_digest, path.display());
+    prove_dumped(path, env.input, run)
+}
+
+/// Proves only whether the voter of the single ballot in `args` is at least `--min-age`, with
+/// the signature-free age-eligibility method. Nothing is signed or checked against a key: the
+/// signature is left to whoever consumes the journal.
+fn prove_age_only(args: &Args, params: &VoteParams, run: &Run) -> Result<()> {
+    if args.filenames.len() != 1 || args.jsonl.is_some() || args.public_key.is_some() {
+        let message = "--scheme age-only proves a single ballot and checks no signature";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+    let path = &args.filenames[0];
+    // The age range and choice rules are not checked: an ineligible voter is a valid outcome.
+    let ballot = read_ballot_file(path, params)?;
+    validate_ballot(&ballot).with_context(|| path.clone())?;
+    let ballot = canonicalize_ballot(&ballot).with_context(|| path.clone())?;
+
+    let (elf, image_id) = args.method();
+    disclose(&(ballot, args.poll_id(), args.min_age), params, elf, image_id, run)
+}
+
+/// Proves the single ballot in `args` with a signature made elsewhere, which [disclose] checks
+/// against `--public-key` before proving. No private key is loaded.
+fn prove_presigned(
+    args: &Args,
+    params: &VoteParams,
+    signature: String,
+    run: &Run,
+) -> Result<()> {
+    if args.filenames.len() > 1 {
+        let message =
+            "--signature covers a single ballot; prove batches from `--no-prove` output instead";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+    let public_key = args.public_key.clone().expect("clap requires --public-key with --signature");
 
-    println!("Image ID: {}", image_id);
+    let ballot = load_ballot(&args.filenames[0], params)?;
+    let (elf, image_id) = args.method();
+    let input = (signature, ballot, args.poll_id(), public_key);
+    disclose(&input, params, elf, image_id, run)
+}
 
-    // Write the image id to a file
-    let mut file = File::create("/tmp/castvote/image_id.dat").expect("failed to create file");
-    file.write_all(image_id.as_bytes()).expect("failed to write");    
+/// Proves every valid ballot in the JSON Lines file `path` in one batch receipt, signed with the
+/// `--jsonl-signatures` file if given and with the voter key otherwise. Lines that do not parse
+/// or whose signature does not verify are skipped, exact resubmissions of an earlier line are
+/// rejected with a warning, and the totals are printed at the end.
+fn prove_jsonl(
+    args: &Args,
+    params: &VoteParams,
+    path: &Path,
+    run: &Run,
+) -> Result<()> {
+    if (args.scheme, args.curve) != (Scheme::Ecdsa, Curve::K256) {
+        let message = "--jsonl is only supported with --scheme ecdsa --curve k256";
+        return Err(VoteError::InvalidInput.error(message));
+    }
 
-    // // Dump receipe using serde
-    // let receipt_json = serde_json::to_string_pretty(&receipt).unwrap();
+    let mut read = read_jsonl(path, params)?;
+    let mut inputs = Vec::with_capacity(read.ballots.len());
+    let mut submissions = SubmissionTracker::new();
+    let mut duplicates = 0;
+    match &args.jsonl_signatures {
+        Some(signatures_path) => {
+            let signatures = read_signature_lines(signatures_path)?;
+            let public_key = args.public_key.clone().expect("clap requires --public-key");
+            let mut rejected = Vec::new();
+            for &JsonlBallot { line, ref ballot } in &read.ballots {
+                let Some(Some(signature)) = signatures.get(line - 1) else {
+                    let reason = format!("no signature on line {line} of {}", signatures_path.display());
+                    rejected.push(SkippedLine { line, reason });
+                    continue;
+                };
+                let verified = verify_signature(
+                    args.scheme,
+                    args.curve,
+                    ballot,
+                    signature,
+                    &public_key,
+                    params.message_format,
+                );
+                if let Err(e) = verified {
+                    rejected.push(SkippedLine { line, reason: format!("{e:#}") });
+                    continue;
+                }
+                let entry = format!("line {line}");
+                if let Err(duplicate) = submissions.check(entry, signature, ballot) {
+                    status!("Warning: {}: {duplicate}", path.display());
+                    duplicates += 1;
+                    continue;
+                }
+                let input = (signature.clone(), ballot.clone(), args.poll_id(), public_key.clone());
+                inputs.push(input);
+            }
+            read.skip_all(rejected);
+        }
+        None => {
+            let key = timed(Phase::KeyImport, || import_keys(args))?;
+            let public_key = key.public_key_base64();
+            for JsonlBallot { line, ballot } in &read.ballots {
+                let signature =
+                    timed(Phase::Signing, || sign(&key, ballot, params.message_format))?;
+                check_encodings(&key, &signature, &public_key)?;
+                let entry = format!("line {line}");
+                if let Err(duplicate) = submissions.check(entry, &signature, ballot) {
+                    status!("Warning: {}: {duplicate}", path.display());
+                    duplicates += 1;
+                    continue;
+                }
+                inputs.push((signature, ballot.clone(), args.poll_id(), public_key.clone()));
+            }
+        }
+    }
 
-    // // Write the JSON string to a file
-    // let mut file = File::create("./res/receipt_selective_disclosure_groth16.json").expect("failed to create file");
-    // file.write_all(receipt_json.as_bytes()).expect("failed to write");
+    for skipped in &read.skipped {
+        status!("Skipping {}:{}: {}", path.display(), skipped.line, skipped.reason);
+    }
+    if inputs.is_empty() {
+        let skipped = read.skipped.len();
+        let message = format!("no valid ballots in {} ({skipped} lines skipped)", path.display());
+        return Err(VoteError::InvalidInput.error(message));
+    }
 
-    // println!("Data written to file successfully.");
+    if let Some(checkpoint) = &args.checkpoint {
+        let resumed = prove_resumable(args, params, checkpoint, &inputs, run)?;
+        status!(
+            "{}: {} {} ballots, {} already proven, skipped {} lines, rejected {} duplicate \
+             submissions",
+            path.display(),
+            if is_dry_run(run) { "checked" } else { "proved" },
+            resumed.proven,
+            resumed.skipped,
+            read.skipped.len(),
+            duplicates
+        );
+        return Ok(());
+    }
 
-    // receipt.journal.decode.unwrap();
+    disclose(&inputs, params, K256_VERIFY_BATCH_ELF, K256_VERIFY_BATCH_ID, run)?;
+    status!(
+        "{}: {} {} ballots, skipped {} lines, rejected {} duplicate submissions",
+        path.display(),
+        match run {
+            Run::Prove { dry_run: Some(_), .. } => "checked",
+            Run::Prove { .. } => "proved",
+            Run::Estimate { .. } => "estimated",
+            Run::DumpInput(_) => "saved",
+            Run::Explain(_) => "explained",
+        },
+        inputs.len(),
+        read.skipped.len(),
+        duplicates
+    );
     Ok(())
 }
 
-pub fn encode_seal(receipt: &risc0_zkvm::Receipt) -> Result<Vec<u8>> {
-    let seal = match receipt.inner.clone() {
-        InnerReceipt::Fake(receipt) => {
-            let seal = receipt.claim.digest().as_bytes().to_vec();
-            let selector = &[0u8; 4];
-            // Create a new vector with the capacity to hold both selector and seal
-            let mut selector_seal = Vec::with_capacity(selector.len() + seal.len());
-            selector_seal.extend_from_slice(selector);
-            selector_seal.extend_from_slice(&seal);
-            selector_seal
+/// Proves each of the `--jsonl` `inputs` in its own receipt with the single-ballot method, into
+/// `<out-dir>/<input hash>`, skipping the ballots the `--checkpoint` records as proven and
+/// recording the others as they are proven. The [RunTimings] of every ballot with timings in its
+/// directory, including those proven by earlier runs, go to [TIMINGS_FILE] in `--out-dir`. A dry
+/// run checks the ballots the checkpoint does not record yet and leaves the checkpoint as it is.
+fn prove_resumable(
+    args: &Args,
+    params: &VoteParams,
+    checkpoint: &Path,
+    inputs: &[(String, String, u64, String)],
+    run: &Run,
+) -> Result<ResumableRun> {
+    let Run::Prove {
+        out_dir,
+        cache,
+        retry,
+        prover,
+        mode,
+        output,
+        nullifiers,
+        author,
+        dry_run,
+        ..
+    } = run
+    else {
+        return Err(VoteError::InvalidInput.error("--checkpoint only applies when proving"));
+    };
+    let mut checkpoint =
+        Checkpoint::open(checkpoint, args.resume).context(VoteError::InvalidInput)?;
+    let mut rate = RateLimit::new(Duration::from_secs(args.min_proof_interval.unwrap_or(0)));
+    let ballots = inputs
+        .iter()
+        .map(|input| Ok((ProofCache::key(input, params, K256_VERIFY_ID)?, input)))
+        .collect::<Result<Vec<_>>>()?;
+    // Key import and signing ran once for the whole file, before the first proof.
+    let setup = take_timings();
+    let prove_ballot = |input_hash: &str, input: &&(String, String, u64, String)| {
+        let ballot_dir = out_dir.join(input_hash);
+        match dry_run {
+            Some(_) => status!("Checking ballot {input_hash}"),
+            None => {
+                ensure_writable_dir(&ballot_dir)?;
+                status!("Proving ballot {input_hash}");
+            }
         }
-        InnerReceipt::Groth16(receipt) => {
-            let selector = &receipt.verifier_parameters.as_bytes()[..4];
-            // Create a new vector with the capacity to hold both selector and seal
-            let mut selector_seal = Vec::with_capacity(selector.len() + receipt.seal.len());
-            selector_seal.extend_from_slice(selector);
-            selector_seal.extend_from_slice(receipt.seal.as_ref());
-            selector_seal
+        let run = Run::Prove {
+            out_dir: &ballot_dir,
+            receipt_out: None,
+            bundle: None,
+            cache: *cache,
+            retry: *retry,
+            prover: *prover,
+            mode: *mode,
+            output: *output,
+            nullifiers: *nullifiers,
+            author: *author,
+            dump_env: None,
+            dry_run: *dry_run,
+        };
+        disclose(*input, params, K256_VERIFY_ELF, K256_VERIFY_ID, &run)
+    };
+    if dry_run.is_some() {
+        let mut resumed = ResumableRun::default();
+        for (input_hash, input) in &ballots {
+            if checkpoint.is_completed(input_hash) {
+                resumed.skipped += 1;
+                continue;
+            }
+            prove_ballot(input_hash, input)
+                .with_context(|| format!("checking ballot {input_hash}"))?;
+            resumed.proven += 1;
+        }
+        let would_write = [checkpoint.path().to_path_buf(), out_dir.join(TIMINGS_FILE)];
+        match output {
+            OutputFormat::Json => println!(
+                "{}",
+                json!({"dry_run": true, "would_record": resumed.proven, "would_write": would_write})
+            ),
+            OutputFormat::Text => {
+                status!("Would record {} ballots in {}", resumed.proven, would_write[0].display());
+                status!("Would write {}", would_write[1].display());
+            }
+        }
+        return Ok(resumed);
+    }
+    let resumed = run_resumable(&ballots, &mut checkpoint, &mut rate, prove_ballot);
+
+    let mut ballot_timings = BTreeMap::new();
+    for (input_hash, _) in &ballots {
+        if let Some(timings) = read_timings(&out_dir.join(input_hash))? {
+            ballot_timings.insert(input_hash.clone(), timings);
+        }
+    }
+    let timings = RunTimings::new(setup, ballot_timings);
+    write_json_file(out_dir.join(TIMINGS_FILE), &timings)?;
+    if let Some(total) = timings.phases.get("total") {
+        let [p50, p90, p99] = ["p50", "p90", "p99"].map(|p| total.percentiles[p]);
+        status!(
+            "Proof times over {} ballots: p50 {p50:.1}s, p90 {p90:.1}s, p99 {p99:.1}s, max {:.1}s",
+            total.count,
+            total.max
+        );
+    }
+    resumed
+}
+
+/// Signs the ballots named in `args` and outputs them as JSON for a separate prover, to
+/// `--sig-out` if given and to stdout otherwise.
+fn sign_only(args: &Args, params: &VoteParams, key: &VoterKey) -> Result<()> {
+    let signed = sign_files(key, &args.filenames, args.poll_id(), params)?;
+    let json = serde_json::to_string_pretty(&signed)?;
+    match &args.sig_out {
+        Some(path) => {
+            atomic_write(path, format!("{json}\n"))?;
+            status!("Wrote {} signed ballots to {}", signed.len(), path.display());
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// Signs every ballot in `filenames` and proves them together with the batch guest method, which
+/// commits one reveal entry per ballot in a single receipt. A file whose signed ballot repeats an
+/// earlier one is rejected with a warning rather than proved twice.
+fn prove_batch(
+    filenames: &[String],
+    poll_id: u64,
+    params: &VoteParams,
+    key: &VoterKey,
+    run: &Run,
+) -> Result<()> {
+    if !matches!(key, VoterKey::K256(_)) {
+        let message = "batch proving is only supported with --scheme ecdsa --curve k256";
+        return Err(VoteError::InvalidInput.error(message));
+    }
+
+    let mut inputs = Vec::with_capacity(filenames.len());
+    let mut submissions = SubmissionTracker::new();
+    let mut duplicates = 0;
+    for filename in filenames {
+        let ballot = load_ballot(filename, params)?;
+        let signature = timed(Phase::Signing, || sign(key, &ballot, params.message_format))?;
+        let public_key = key.public_key_base64();
+        check_encodings(key, &signature, &public_key).with_context(|| filename.clone())?;
+        // Signing is deterministic, so the same ballot fed twice signs to the same signature.
+        if let Err(duplicate) = submissions.check(filename.as_str(), &signature, &ballot) {
+            status!("Warning: {duplicate}");
+            duplicates += 1;
+            continue;
+        }
+        inputs.push((signature, ballot, poll_id, public_key));
+    }
+
+    disclose(&inputs, params, K256_VERIFY_BATCH_ELF, K256_VERIFY_BATCH_ID, run)?;
+    status!("Batch of {} ballots, rejected {} duplicate submissions", inputs.len(), duplicates);
+    Ok(())
+}
+
+/// Checks every signature in `input` on the host, then proves the guest `elf` over `input` and
+/// `params`, unless `cache` already holds the receipt, retrying transient failures and timeouts
+/// per `retry`, verifies the receipt against `image_id` and writes the seal, journal and image ID
+/// to `out_dir` and the whole receipt to `receipt_out`. With a nullifier store, a ballot whose
+/// nullifier it holds fails before proving, and the nullifiers are recorded once the proof is
+/// written, and with a `dump_env` path the executor input and prover settings are saved before
+/// proving. With [Run::Estimate] it only executes the guest and prints the cost estimate, and
+/// with [Run::DumpInput] it only saves the input. A dry run stops after the checks, or after
+/// proving with [DryRun::Prove], and lists what it would have written (see [dry_run_proof]).
+fn disclose<T: Serialize + Clone + Send + Into<GuestInput> + 'static>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+    run: &Run,
+) -> Result<()> {
+    let guest_input: GuestInput = input.clone().into();
+    if let Run::Explain(output) = run {
+        return explain(&guest_input, params, image_id, *output);
+    }
+    // A stale ELF fails here rather than after signing, a cache hit or minutes of proving. The ID
+    // is computed once per method, so batches and checkpoint runs pay for it once.
+    checked_image_id(elf, image_id)?;
+    // Whoever made the signatures, a mismatch fails here rather than in the guest.
+    guest_input.check_signatures(image_id, params.message_format)?;
+
+    let (out_dir, receipt_out, bundle, cache, retry, prover, mode, output, nullifiers, author) =
+        match run {
+            Run::Prove {
+                out_dir,
+                receipt_out,
+                bundle,
+                retry,
+                prover,
+                mode,
+                output,
+                nullifiers,
+                author,
+                dump_env,
+                dry_run: Some(dry_run),
+                ..
+            } => {
+                let files = DryRunFiles {
+                    out_dir,
+                    receipt_out: *receipt_out,
+                    bundle: *bundle,
+                    mode: *mode,
+                    author: author.is_some(),
+                    dump_env: *dump_env,
+                    opening: dry_run.opening,
+                    nullifiers: *nullifiers,
+                };
+                let journal = if dry_run.prove {
+                    let (prover, mode) = (*prover, *mode);
+                    let receipt = with_retries(retry, || {
+                        let (input, params) = (input.clone(), params.clone());
+                        with_timeout(retry.timeout, move || {
+                            prove_vote_on(prover, mode, &input, &params, elf, image_id)
+                        })
+                    })
+                    .map_err(|err| VoteError::ProvingFailed.tag(err))?;
+                    Some(receipt.journal.bytes)
+                } else {
+                    None
+                };
+                return dry_run_proof(&guest_input, params, &files, journal, *output);
+            }
+            Run::Prove {
+                out_dir,
+                receipt_out,
+                bundle,
+                cache,
+                retry,
+                prover,
+                mode,
+                output,
+                nullifiers,
+                author,
+                dump_env,
+                dry_run: None,
+            } => {
+                if let Some(path) = dump_env {
+                    let settings = ProverSettings::current(*prover, *mode);
+                    let dump = EnvDump::new(guest_input.clone(), params, image_id, settings)?;
+                    write_env(path, &dump)?;
+                    status!("Wrote executor input {} to {}", dump.env_digest, path.display());
+                }
+                (
+                    *out_dir,
+                    *receipt_out,
+                    *bundle,
+                    *cache,
+                    retry,
+                    *prover,
+                    *mode,
+                    *output,
+                    *nullifiers,
+                    *author,
+                )
+            }
+            Run::Estimate { model, output } => {
+                let estimate = estimate_vote(input, params, elf, image_id)?;
+                match output {
+                    OutputFormat::Text => println!("{}", estimate.report(model)),
+                    OutputFormat::Json => println!("{}", estimate.to_json(model)),
+                }
+                return Ok(());
+            }
+            Run::DumpInput(path) => {
+                let dump = InputDump::new(guest_input, params, image_id)?;
+                write_input(path, &dump)?;
+                status!("Wrote the {} input to {}", dump.method, path.display());
+                return Ok(());
+            }
+            Run::Explain(_) => unreachable!("explained above"),
+        };
+    let prove = || {
+        let receipt = with_retries(retry, || {
+            // A timed-out attempt is abandoned on its own thread, which must own what it proves.
+            let (input, params, cache) = (input.clone(), params.clone(), cache.cloned());
+            with_timeout(retry.timeout, move || {
+                prove_vote_cached(cache.as_ref(), prover, mode, &input, &params, elf, image_id)
+            })
+        })
+        .map_err(|err| VoteError::ProvingFailed.tag(err))?;
+        // Key import and signing before this proof, then every attempt at proving it.
+        let timings = take_timings();
+        write_timings(out_dir, &timings)?;
+        if mode == ProveMode::Succinct {
+            return write_succinct(&receipt, image_id, out_dir, receipt_out, &timings, output);
+        }
+        let timings = Some(&timings);
+        write_proof(&receipt, elf, image_id, out_dir, receipt_out, bundle, author, timings, output)
+    };
+    match nullifiers {
+        Some(store) => {
+            let nullifiers = guest_input.nullifiers(params).context(VoteError::InvalidInput)?;
+            store.borrow_mut().prove_once(&nullifiers, prove)
+        }
+        None => prove(),
+    }
+}
+
+/// Whether `run` is a `--dry-run` that writes nothing.
+fn is_dry_run(run: &Run) -> bool {
+    matches!(run, Run::Prove { dry_run: Some(_), .. })
+}
+
+/// Where a proving run would write, for a dry run to list.
+struct DryRunFiles<'a> {
+    out_dir: &'a Path,
+    receipt_out: Option<&'a Path>,
+    bundle: Option<&'a Path>,
+    mode: ProveMode,
+    author: bool,
+    dump_env: Option<&'a Path>,
+    opening: bool,
+    nullifiers: Option<&'a RefCell<NullifierStore>>,
+}
+
+impl DryRunFiles<'_> {
+    /// Every file the run would write, in the order it would write them.
+    fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.dump_env.into_iter().map(Path::to_path_buf).collect();
+        match self.mode {
+            ProveMode::Succinct => paths.push(self.out_dir.join(SUCCINCT_RECEIPT_FILE)),
+            _ => paths.extend(ARTIFACT_FILES.iter().map(|file| self.out_dir.join(file))),
+        }
+        if self.author {
+            paths.push(self.out_dir.join(AUTHOR_SIGNATURE_FILE));
+        }
+        paths.push(self.out_dir.join(TIMINGS_FILE));
+        paths.extend(self.receipt_out.into_iter().chain(self.bundle).map(Path::to_path_buf));
+        if let Some(store) = self.nullifiers {
+            paths.push(store.borrow().path().to_path_buf());
+        }
+        if self.opening {
+            paths.push(self.out_dir.join(OPENINGS_FILE));
+        }
+        paths
+    }
+}
+
+/// Computes the nullifiers of `input`, fails with [VoteError::DoubleVote] if the nullifier store
+/// of `files` already holds one, and prints them with the files a real run would write and the
+/// `journal` of the receipt if the dry run proved it. Nothing is written.
+fn dry_run_proof(
+    input: &GuestInput,
+    params: &VoteParams,
+    files: &DryRunFiles,
+    journal: Option<Vec<u8>>,
+    output: OutputFormat,
+) -> Result<()> {
+    let nullifiers = input.nullifiers(params).context(VoteError::InvalidInput)?;
+    if let Some(store) = files.nullifiers {
+        let store = store.borrow();
+        store
+            .check(&nullifiers)
+            .with_context(|| format!("checking {}", store.path().display()))
+            .context(VoteError::DoubleVote)?;
+    }
+    // Nothing goes to timings.json, so the next proof starts its own.
+    take_timings();
+    let paths = files.paths();
+    match output {
+        OutputFormat::Json => {
+            let nullifiers: Vec<_> = nullifiers
+                .iter()
+                .map(|(poll_id, nullifier)| json!({"poll_id": poll_id, "nullifier": nullifier}))
+                .collect();
+            let mut report =
+                json!({"dry_run": true, "nullifiers": nullifiers, "would_write": paths});
+            if let Some(journal) = &journal {
+                report["journal"] = json!(hex::encode(journal));
+            }
+            println!("{report}");
+        }
+        OutputFormat::Text => {
+            for (poll_id, nullifier) in &nullifiers {
+                println!("Nullifier for poll {poll_id}: {nullifier}");
+            }
+            if let Some(journal) = &journal {
+                println!("Journal: {}", hex::encode(journal));
+            }
+            for path in &paths {
+                status!("Would write {}", path.display());
+            }
+        }
+    }
+    status!("Dry run: nothing was written");
+    Ok(())
+}
+
+/// Prints the `--explain` trace of `input` for the method with `image_id`, one line per step,
+/// and fails as the guest would if a step fails.
+fn explain(
+    input: &GuestInput,
+    params: &VoteParams,
+    image_id: [u32; 8],
+    output: OutputFormat,
+) -> Result<()> {
+    let (inputs, batch) = match input {
+        GuestInput::Ballot(input) if method_name(image_id) != Some("k256_verify_encrypted") => {
+            (vec![input], false)
+        }
+        GuestInput::Batch(inputs) => (inputs.iter().collect(), true),
+        _ => {
+            let message = "--explain covers the single-ballot and batch methods only";
+            return Err(VoteError::InvalidInput.error(message));
         }
-        _ => bail!("Unsupported receipt type"),
     };
-    Ok(seal)
+    let (scheme, curve) = method_scheme(image_id);
+    let traces: Vec<_> =
+        inputs.iter().map(|input| explain_ballot(scheme, curve, input, params)).collect();
+    let reveals: Option<Vec<_>> = traces.iter().map(|(_, reveal)| reveal.clone()).collect();
+    let commitment = reveals.map(|reveals| explain_commitment(&reveals, batch, params));
+
+    match output {
+        OutputFormat::Json => {
+            let ballots: Vec<_> = traces.iter().map(|(steps, _)| json!({"steps": steps})).collect();
+            println!("{}", json!({"ballots": ballots, "commitment": commitment}));
+        }
+        OutputFormat::Text => {
+            for (i, (steps, _)) in traces.iter().enumerate() {
+                if traces.len() > 1 {
+                    println!("Ballot {} of {}:", i + 1, traces.len());
+                }
+                steps.iter().for_each(|step| println!("{step}"));
+            }
+            commitment.iter().for_each(|step| println!("{step}"));
+        }
+    }
+
+    let failed = traces.iter().flat_map(|(steps, _)| steps).find(|step| !step.pass);
+    match failed {
+        Some(step) if step.name == "signature verification" => {
+            Err(VoteError::InvalidSignature.error("the guest would reject the signature"))
+        }
+        Some(step) => {
+            let message = format!("the guest would reject the ballot at {}", step.name);
+            Err(VoteError::InvalidInput.error(message))
+        }
+        None => Ok(()),
+    }
 }
-    
\ No newline at end of file
+
+/// Writes the seal, journal and image ID of the Groth16 `receipt` to `out_dir` and the whole
+/// receipt to `receipt_out`, the [ProofBundle] to `bundle`, and prints the [ProofSummary], with
+/// the `timings` of the proof, with `--output json`. With an `author` key, the journal signed
+/// with it goes to [AUTHOR_SIGNATURE_FILE] in `out_dir` and into the bundle.
+#[allow(clippy::too_many_arguments)]
+fn write_proof(
+    receipt: &Receipt,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+    out_dir: &Path,
+    receipt_out: Option<&Path>,
+    bundle: Option<&Path>,
+    author: Option<&SigningKey>,
+    timings: Option<&Timings>,
+    output: OutputFormat,
+) -> Result<()> {
+    write_artifacts(receipt, elf, image_id, out_dir)?;
+    let author_signature =
+        author.map(|key| (author_public_key(key), sign_journal(key, &receipt.journal.bytes)));
+    if let Some((public_key, signature)) = &author_signature {
+        write_hex_file(out_dir.join(AUTHOR_SIGNATURE_FILE), signature)?;
+        status!("Signed the journal as {public_key}");
+    }
+
+    status!("journal: {}", hex::encode(&receipt.journal.bytes));
+    status!("journal digest: {}", journal_digest(receipt));
+    status!("Wrote seal, journal and image ID to {}", out_dir.display());
+    if let Some(path) = receipt_out {
+        dump_receipt(receipt, path)?;
+        status!("Wrote the receipt to {}", path.display());
+    }
+    if let Some(path) = bundle {
+        let mut proof_bundle = ProofBundle::from_receipt(receipt, elf, image_id)?;
+        if let Some((public_key, signature)) = &author_signature {
+            proof_bundle = proof_bundle.with_author(public_key.clone(), signature);
+        }
+        write_bundle(path, &proof_bundle)?;
+        status!("Wrote the proof bundle to {}", path.display());
+    }
+
+    if output == OutputFormat::Json {
+        let journal = &receipt.journal.bytes;
+        let (nullifier, nullifiers) = summary_nullifiers(image_id, journal)?;
+        let summary = ProofSummary {
+            seal: hex::encode(encode_seal(receipt)?),
+            journal: hex::encode(journal),
+            journal_digest: journal_digest(receipt).to_string(),
+            image_id: checked_image_id(elf, image_id)?.to_string(),
+            nullifier,
+            nullifiers,
+            out_dir: out_dir.display().to_string(),
+            receipt: receipt_out.map(|path| path.display().to_string()),
+            bundle: bundle.map(|path| path.display().to_string()),
+            author_signature: author_signature.map(|(_, signature)| hex::encode(signature)),
+            timings: timings.cloned(),
+        };
+        println!("{}", serde_json::to_string(&summary)?);
+    }
+    Ok(())
+}
+
+/// Writes the succinct `receipt` to [SUCCINCT_RECEIPT_FILE] in `out_dir` for a later `compress`
+/// and the whole receipt to `receipt_out`, and prints the [SuccinctSummary] with
+/// `--output json`.
+fn write_succinct(
+    receipt: &Receipt,
+    image_id: [u32; 8],
+    out_dir: &Path,
+    receipt_out: Option<&Path>,
+    timings: &Timings,
+    output: OutputFormat,
+) -> Result<()> {
+    let path = out_dir.join(SUCCINCT_RECEIPT_FILE);
+    write_receipt_file(receipt, &path)?;
+
+    status!("journal: {}", hex::encode(&receipt.journal.bytes));
+    status!("Wrote the succinct receipt to {}; `compress` it for the seal", path.display());
+    if let Some(path) = receipt_out {
+        dump_receipt(receipt, path)?;
+        status!("Wrote the receipt to {}", path.display());
+    }
+
+    if output == OutputFormat::Json {
+        let summary = SuccinctSummary {
+            receipt: path.display().to_string(),
+            journal: hex::encode(&receipt.journal.bytes),
+            journal_digest: journal_digest(receipt).to_string(),
+            image_id: Digest::from(image_id).to_string(),
+            timings: timings.clone(),
+        };
+        println!("{}", serde_json::to_string(&summary)?);
+    }
+    Ok(())
+}
+
+/// Compresses the succinct receipt at `path` to Groth16 and writes the artifacts as proving
+/// would, to `out_dir`, `receipt_out` and `bundle`.
+fn run_compress(
+    path: &Path,
+    out_dir: &Path,
+    receipt_out: Option<&Path>,
+    bundle: Option<&Path>,
+    output: OutputFormat,
+) -> Result<()> {
+    ensure_writable_dir(out_dir)?;
+    for path in receipt_out.into_iter().chain(bundle) {
+        ensure_parent_writable(path)?;
+    }
+    let receipt = read_receipt_file(path).context(VoteError::InvalidInput)?;
+    let (receipt, elf, image_id) = compress_receipt(&receipt)?;
+    write_proof(&receipt, elf, image_id, out_dir, receipt_out, bundle, None, None, output)
+}
+
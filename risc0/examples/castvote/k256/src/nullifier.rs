@@ -0,0 +1,221 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auditing a journal against a ballot: recomputing the nullifier the guest must have committed.
+//!
+//! No key is involved, only the ballot, the poll and, for election-scoped nullifiers, the
+//! authority seed, so an auditor can tie a receipt to a specific voter's ballot.
+//!
+//! Without the ballot or the seed, a journal's ID commitment can still be opened with the one
+//! voter secret the authority discloses (see [castvote_core::derive_voter_secret]).
+
+use std::{fmt, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use castvote_core::{
+    canonicalize_ballot, id_commitment, parse_ballot, scoped_nullifier, ElectionScope, RevealInfo,
+};
+use serde_json::Value;
+
+use crate::{
+    ballot::read_text_file,
+    journal::{decode_reveal_entries, read_journal_file},
+};
+
+/// The journal entry whose nullifier matched the ballot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullifierMatch {
+    /// Position of the entry in the journal; always 0 for single-ballot journals.
+    pub index: usize,
+    pub nullifier: String,
+}
+
+/// Why a journal does not hold the nullifier of a ballot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NullifierMismatch {
+    /// No entry of the journal is for the poll.
+    PollNotInJournal { poll_id: u64, committed: Vec<u64> },
+    /// The nullifiers were salted for another election than the one given, or the journal uses
+    /// the fixed salt and an election was given, or the other way round.
+    ElectionMismatch { expected: Option<String>, committed: Option<String> },
+    /// The salt matches, but the ballot's nullifier is not among those committed for the poll:
+    /// the proof is for another ballot, or the authority seed is not the one the prover used.
+    Nullifier { expected: String, committed: Vec<String> },
+}
+
+impl fmt::Display for NullifierMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NullifierMismatch::PollNotInJournal { poll_id, committed } => write!(
+                f,
+                "the journal has no entry for poll {poll_id} (it commits polls {committed:?})"
+            ),
+            NullifierMismatch::ElectionMismatch { expected, committed } => {
+                let salt = |election: &Option<String>| match election {
+                    Some(election_id) => format!("the salt of election {election_id:?}"),
+                    None => "the fixed salt".to_string(),
+                };
+                write!(f, "the journal's nullifiers use {}, ", salt(committed))?;
+                write!(f, "not {}", salt(expected))?;
+                if expected.is_none() {
+                    write!(f, "; pass --election-id and --authority-seed-file")?;
+                }
+                Ok(())
+            }
+            NullifierMismatch::Nullifier { expected, committed } => write!(
+                f,
+                "the ballot's nullifier {expected} is not committed in the journal (it commits {})",
+                committed.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NullifierMismatch {}
+
+/// Finds the entry of `reveals` committed for the voter `voter_id` in `poll_id`, recomputing its
+/// nullifier with the same salt rules as the guest (see [scoped_nullifier]).
+///
+/// The poll is checked first and the election scope second, so a mismatch names the first
+/// parameter that differs rather than just reporting different nullifiers.
+pub fn find_nullifier(
+    voter_id: &serde_json::Value,
+    poll_id: u64,
+    election: Option<&ElectionScope>,
+    reveals: &[RevealInfo],
+) -> Result<NullifierMatch, NullifierMismatch> {
+    let in_poll: Vec<_> =
+        reveals.iter().enumerate().filter(|(_, reveal)| reveal.poll_id == poll_id).collect();
+    if in_poll.is_empty() {
+        let committed = reveals.iter().map(|reveal| reveal.poll_id).collect();
+        return Err(NullifierMismatch::PollNotInJournal { poll_id, committed });
+    }
+
+    let expected_election = election.map(|election| election.election_id.clone());
+    let other_election = in_poll.iter().find(|(_, reveal)| reveal.election_id != expected_election);
+    if let Some((_, reveal)) = other_election {
+        return Err(NullifierMismatch::ElectionMismatch {
+            expected: expected_election,
+            committed: reveal.election_id.clone(),
+        });
+    }
+
+    let expected = hex::encode(scoped_nullifier(voter_id, poll_id, election));
+    match in_poll.iter().find(|(_, reveal)| reveal.nullifier == expected) {
+        Some(&(index, _)) => Ok(NullifierMatch { index, nullifier: expected }),
+        None => Err(NullifierMismatch::Nullifier {
+            expected,
+            committed: in_poll.iter().map(|(_, reveal)| reveal.nullifier.clone()).collect(),
+        }),
+    }
+}
+
+/// Reads the ballot file and the hex journal file and checks the journal with [find_nullifier].
+///
+/// The outer result fails if either file cannot be read or decoded; the inner one carries the
+/// verdict.
+pub fn check_nullifier_files(
+    ballot: &Path,
+    poll_id: u64,
+    journal: &Path,
+    election: Option<&ElectionScope>,
+) -> Result<Result<NullifierMatch, NullifierMismatch>> {
+    let text = read_text_file(ballot)?;
+    // The guest parses the canonical form, so the audit does too.
+    let parsed = canonicalize_ballot(&text)
+        .and_then(|canonical| parse_ballot(&canonical))
+        .with_context(|| format!("{} is not a ballot", ballot.display()))?;
+    let reveals = decode_reveal_entries(&read_journal_file(journal)?)
+        .with_context(|| journal.display().to_string())?;
+    Ok(find_nullifier(&parsed.id, poll_id, election, &reveals))
+}
+
+/// Why a journal does not commit to a voter ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdCommitmentMismatch {
+    /// No entry of the journal has an ID commitment: it was proven without `--election-id`, or
+    /// before journal version 10.
+    NotCommitted,
+    /// The journal commits to another voter ID, or the secret is not the one derived for it.
+    Commitment { expected: String, committed: Vec<String> },
+}
+
+impl fmt::Display for IdCommitmentMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdCommitmentMismatch::NotCommitted => {
+                write!(f, "the journal has no ID commitment; it was proven without an election")
+            }
+            IdCommitmentMismatch::Commitment { expected, committed } => write!(
+                f,
+                "the voter's ID commitment {expected} is not in the journal (it commits {})",
+                committed.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IdCommitmentMismatch {}
+
+/// Parses a voter ID given on the command line as it appears in the ballot: JSON if it parses as
+/// JSON, so `7` is the number and `"7"` the string, and a plain string otherwise.
+pub fn parse_voter_id(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+/// Parses a voter secret given as 64 hex characters.
+pub fn parse_voter_secret(text: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(text.trim()).context("the voter secret is not hex encoded")?;
+    let len = bytes.len();
+    bytes.try_into().map_err(|_| anyhow!("the voter secret is {len} bytes, expected 32"))
+}
+
+/// Finds every entry of `reveals` whose ID commitment opens to `voter_id` under `voter_secret`,
+/// and so whose nullifier belongs to that voter. A multi-poll journal has one per poll.
+pub fn find_id_commitment(
+    voter_id: &Value,
+    voter_secret: &[u8; 32],
+    reveals: &[RevealInfo],
+) -> Result<Vec<NullifierMatch>, IdCommitmentMismatch> {
+    let committed: Vec<_> =
+        reveals.iter().filter_map(|reveal| reveal.id_commitment.clone()).collect();
+    if committed.is_empty() {
+        return Err(IdCommitmentMismatch::NotCommitted);
+    }
+    let expected = hex::encode(id_commitment(voter_id, voter_secret));
+    let found: Vec<_> = reveals
+        .iter()
+        .enumerate()
+        .filter(|(_, reveal)| reveal.id_commitment.as_ref() == Some(&expected))
+        .map(|(index, reveal)| NullifierMatch { index, nullifier: reveal.nullifier.clone() })
+        .collect();
+    if found.is_empty() {
+        return Err(IdCommitmentMismatch::Commitment { expected, committed });
+    }
+    Ok(found)
+}
+
+/// Reads the hex journal file and checks it with [find_id_commitment].
+///
+/// The outer result fails if the journal cannot be read or decoded; the inner one carries the
+/// verdict.
+pub fn check_id_commitment_file(
+    journal: &Path,
+    voter_id: &Value,
+    voter_secret: &[u8; 32],
+) -> Result<Result<Vec<NullifierMatch>, IdCommitmentMismatch>> {
+    let reveals = decode_reveal_entries(&read_journal_file(journal)?)
+        .with_context(|| journal.display().to_string())?;
+    Ok(find_id_commitment(voter_id, voter_secret, &reveals))
+}
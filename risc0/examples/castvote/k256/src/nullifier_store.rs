@@ -0,0 +1,234 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Nullifiers already proven, kept in a file across runs so a second ballot from the same voter
+//! in a poll is rejected before proving, not after minutes of it.
+//!
+//! Nullifiers are deterministic, so the host computes the ones a guest input will commit with
+//! the same [scoped_nullifier] the guest uses. The guest still recomputes them and commits the
+//! authoritative values; the store only saves the proving time of a known double vote.
+//!
+//! Runs on several machines each keep their own store; [merge_stores] unions them and finds the
+//! double votes no single store could catch.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use castvote_core::{parse_ballot, scoped_nullifier, ElectionScope};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::VoteError, output::write_json_file};
+
+/// The nullifiers proven so far, by poll, as saved in the store file. Both levels are sorted, so
+/// the same nullifiers always save to the same bytes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NullifierSet {
+    pub polls: BTreeMap<u64, BTreeSet<String>>,
+}
+
+impl NullifierSet {
+    /// Whether `nullifier` was proven in `poll_id`.
+    pub fn contains(&self, poll_id: u64, nullifier: &str) -> bool {
+        self.polls.get(&poll_id).is_some_and(|nullifiers| nullifiers.contains(nullifier))
+    }
+
+    /// Adds `nullifier` to `poll_id`, returning whether it was new.
+    pub fn insert(&mut self, poll_id: u64, nullifier: &str) -> bool {
+        self.polls.entry(poll_id).or_default().insert(nullifier.to_string())
+    }
+
+    /// How many nullifiers there are, over all polls.
+    pub fn len(&self) -> usize {
+        self.polls.values().map(BTreeSet::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.polls.values().all(BTreeSet::is_empty)
+    }
+}
+
+/// A nullifier about to be proven that was proven before, or that the same input commits twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsedNullifier {
+    pub poll_id: u64,
+    pub nullifier: String,
+}
+
+impl fmt::Display for UsedNullifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nullifier {} was already used in poll {}", self.nullifier, self.poll_id)
+    }
+}
+
+impl std::error::Error for UsedNullifier {}
+
+/// The nullifier the guest will commit for `ballot` in `poll_id`, computed as the guest does:
+/// from the ballot's `id`, salted per `election` if given.
+pub fn expected_nullifier(
+    ballot: &str,
+    poll_id: u64,
+    election: Option<&ElectionScope>,
+) -> Result<String> {
+    let ballot = parse_ballot(ballot).context("parsing the ballot")?;
+    Ok(hex::encode(scoped_nullifier(&ballot.id, poll_id, election)))
+}
+
+/// A nullifier store file and the nullifiers it holds, saved atomically after every proof.
+#[derive(Debug)]
+pub struct NullifierStore {
+    path: PathBuf,
+    nullifiers: NullifierSet,
+}
+
+impl NullifierStore {
+    /// Opens the store at `path`, starting empty if there is no file yet.
+    pub fn open(path: &Path) -> Result<Self> {
+        let nullifiers = match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("reading the nullifier store {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => NullifierSet::default(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        Ok(Self { path: path.to_path_buf(), nullifiers })
+    }
+
+    /// The store file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What has been recorded so far.
+    pub fn nullifiers(&self) -> &NullifierSet {
+        &self.nullifiers
+    }
+
+    /// Fails on the first of the `(poll_id, nullifier)` pairs that the store already holds or
+    /// that repeats an earlier pair.
+    pub fn check(&self, nullifiers: &[(u64, String)]) -> Result<(), UsedNullifier> {
+        let mut seen = NullifierSet::default();
+        for (poll_id, nullifier) in nullifiers {
+            if self.nullifiers.contains(*poll_id, nullifier) || !seen.insert(*poll_id, nullifier) {
+                return Err(UsedNullifier { poll_id: *poll_id, nullifier: nullifier.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the `(poll_id, nullifier)` pairs and saves the store, atomically, so a crash
+    /// leaves either the previous store or this one.
+    pub fn record(&mut self, nullifiers: &[(u64, String)]) -> Result<()> {
+        let mut added = false;
+        for (poll_id, nullifier) in nullifiers {
+            added |= self.nullifiers.insert(*poll_id, nullifier);
+        }
+        if added {
+            write_json_file(&self.path, &self.nullifiers)?;
+        }
+        Ok(())
+    }
+
+    /// Runs `prove` unless one of `nullifiers` is used (see [NullifierStore::check]), which fails
+    /// with [VoteError::DoubleVote] without calling it. Once `prove` succeeds, the nullifiers are
+    /// recorded; a failed proof records nothing, so the ballot can be proven again.
+    pub fn prove_once<T>(
+        &mut self,
+        nullifiers: &[(u64, String)],
+        prove: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        self.check(nullifiers)
+            .with_context(|| format!("checking {}", self.path.display()))
+            .context(VoteError::DoubleVote)?;
+        let proven = prove()?;
+        self.record(nullifiers)?;
+        Ok(proven)
+    }
+}
+
+/// A nullifier held by more than one of the stores [merge_stores] merged: each machine proved a
+/// ballot for it, so the voter voted twice in the poll.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CrossStoreDuplicate {
+    pub poll_id: u64,
+    pub nullifier: String,
+    /// The stores holding it, in the order they were given.
+    pub sources: Vec<PathBuf>,
+}
+
+impl fmt::Display for CrossStoreDuplicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sources: Vec<_> = self.sources.iter().map(|path| path.display().to_string()).collect();
+        write!(
+            f,
+            "nullifier {} in poll {} is in {}",
+            self.nullifier,
+            self.poll_id,
+            sources.join(", ")
+        )
+    }
+}
+
+/// The union of several nullifier stores.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergedStores {
+    /// Every nullifier of every store, once.
+    pub nullifiers: NullifierSet,
+    /// The nullifiers in more than one store, by poll and then nullifier.
+    pub duplicates: Vec<CrossStoreDuplicate>,
+}
+
+/// Reads the nullifier stores at `paths` and merges them. Unlike [NullifierStore::open], a
+/// missing store is an error, as it is more likely a typo than a machine that proved nothing.
+///
+/// The result only depends on which stores are given: the union is sorted like any
+/// [NullifierSet], and a path given twice is read once, so merging a store with itself finds
+/// nothing. Merging the result with one of its sources again would report every nullifier of
+/// that source, so each store must be given once.
+pub fn merge_stores(paths: &[PathBuf]) -> Result<MergedStores> {
+    let mut sources: BTreeMap<(u64, String), Vec<PathBuf>> = BTreeMap::new();
+    let mut read = BTreeSet::new();
+    for path in paths {
+        let canonical =
+            fs::canonicalize(path).with_context(|| format!("reading {}", path.display()))?;
+        if !read.insert(canonical) {
+            continue;
+        }
+        let json =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let store: NullifierSet = serde_json::from_str(&json)
+            .with_context(|| format!("reading the nullifier store {}", path.display()))?;
+        for (poll_id, nullifiers) in store.polls {
+            for nullifier in nullifiers {
+                sources.entry((poll_id, nullifier)).or_default().push(path.clone());
+            }
+        }
+    }
+
+    let mut merged = MergedStores::default();
+    for ((poll_id, nullifier), sources) in sources {
+        merged.nullifiers.insert(poll_id, &nullifier);
+        if sources.len() > 1 {
+            merged.duplicates.push(CrossStoreDuplicate { poll_id, nullifier, sources });
+        }
+    }
+    Ok(merged)
+}
+
+/// Writes `nullifiers` to `path` as a nullifier store, atomically, for `--nullifier-store`.
+pub fn write_store(path: &Path, nullifiers: &NullifierSet) -> Result<()> {
+    write_json_file(path, nullifiers)
+}
@@ -0,0 +1,140 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Openings of homomorphically encrypted votes: what a voter keeps to prove later how they voted.
+//!
+//! The homomorphic guest commits only ElGamal ciphertexts of the vote. Whoever holds the vote and
+//! the randomness it was encrypted with can re-encrypt it and compare with the journal, which
+//! proves the vote to an observer without any trustee decrypting anything. The randomness is as
+//! sensitive as the vote itself, so openings are kept in an owner-only file and never printed.
+
+use std::{fmt, path::Path};
+
+use anyhow::{bail, Context, Result};
+use castvote_core::{
+    encrypt_vote, one_hot, parse_ballot, parse_randomness, BallotType, ElGamalInput,
+    HomomorphicReveal, RevealInfo, VoteParams,
+};
+use k256::{PublicKey, Scalar};
+use serde::{Deserialize, Serialize};
+
+use crate::{ballot::read_text_file, output::atomic_write_private};
+
+/// Name of the openings file in the output directory.
+pub const OPENINGS_FILE: &str = "openings.json";
+
+/// The opening of one encrypted vote.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Opening {
+    /// Nullifier of the ballot, which finds its entry in the journal.
+    pub nullifier: String,
+    pub poll_id: u64,
+    pub ballot_type: BallotType,
+    /// The poll's options, in the order of the ciphertexts.
+    pub options: Vec<String>,
+    /// The options voted for.
+    pub selection: Vec<String>,
+    /// Hex 32-byte ElGamal randomness per option, the blinding factors of the ciphertexts.
+    pub randomness: Vec<String>,
+}
+
+// The selection and the randomness are the secret; an opening must never end up in a log.
+impl fmt::Debug for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Opening")
+            .field("nullifier", &self.nullifier)
+            .field("poll_id", &self.poll_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Opening {
+    /// Builds the opening of `ballot` (canonical JSON) as the homomorphic guest will encrypt it
+    /// for `poll_id` with `elgamal`.
+    pub fn new(
+        ballot: &str,
+        poll_id: u64,
+        params: &VoteParams,
+        elgamal: &ElGamalInput,
+    ) -> Result<Self> {
+        let ballot = parse_ballot(ballot).context("parsing ballot")?;
+        // The key hash is not part of the opening, so any key gives the same nullifier.
+        let reveal = RevealInfo::new(&ballot, poll_id, &[], params)?;
+        Ok(Self {
+            nullifier: reveal.nullifier,
+            poll_id,
+            ballot_type: params.ballot_type,
+            options: params.options.clone(),
+            selection: reveal.selection,
+            randomness: elgamal.randomness.iter().map(hex::encode).collect(),
+        })
+    }
+
+    /// Re-encrypts the vote and checks that `committed` holds exactly these ciphertexts for this
+    /// ballot.
+    pub fn verify(&self, committed: &HomomorphicReveal) -> Result<()> {
+        if committed.reveal.nullifier != self.nullifier {
+            let committed = &committed.reveal.nullifier;
+            bail!("the journal commits nullifier {committed}, not {}", self.nullifier);
+        }
+        if committed.reveal.poll_id != self.poll_id {
+            bail!("the journal is for poll {}, not {}", committed.reveal.poll_id, self.poll_id);
+        }
+        let election_key = PublicKey::from_sec1_bytes(&committed.election_key)
+            .context("the committed election key is not a secp256k1 point")?;
+        let randomness =
+            self.randomness.iter().map(|r| decode_randomness(r)).collect::<Result<Vec<_>>>()?;
+        let vote = one_hot(&self.selection, self.ballot_type, &self.options)?;
+        if encrypt_vote(&election_key, &vote, &randomness)? != committed.ciphertexts {
+            bail!("the opening does not reproduce the committed ciphertexts");
+        }
+        Ok(())
+    }
+}
+
+fn decode_randomness(hex: &str) -> Result<Scalar> {
+    let bytes: [u8; 32] = hex::decode(hex)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .context("randomness is not 32 hex-encoded bytes")?;
+    Ok(parse_randomness(&bytes)?)
+}
+
+/// Reads the openings at `path`; a missing file holds none.
+pub fn read_openings(path: &Path) -> Result<Vec<Opening>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&read_text_file(path)?)
+        .with_context(|| format!("{} is not an openings file", path.display()))
+}
+
+/// Adds `opening` to the openings at `path`, replacing an earlier one for the same nullifier,
+/// and writes the file readable by its owner only.
+pub fn save_opening(path: &Path, opening: Opening) -> Result<()> {
+    let mut openings = read_openings(path)?;
+    openings.retain(|other| other.nullifier != opening.nullifier);
+    openings.push(opening);
+    atomic_write_private(path, format!("{}\n", serde_json::to_string_pretty(&openings)?))
+}
+
+/// Finds the opening for `nullifier` at `path`.
+pub fn find_opening(path: &Path, nullifier: &str) -> Result<Opening> {
+    let nullifier = nullifier.trim_start_matches("0x").to_ascii_lowercase();
+    read_openings(path)?
+        .into_iter()
+        .find(|opening| opening.nullifier == nullifier)
+        .with_context(|| format!("{} has no opening for nullifier {nullifier}", path.display()))
+}
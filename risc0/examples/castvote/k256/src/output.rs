@@ -0,0 +1,176 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Writing output artifacts without leaving partial files behind.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+/// Bytes hex-encoded at a time by [write_hex], so the hex form never exists in full.
+const HEX_CHUNK: usize = 4096;
+
+/// Marks [FILE_MODE] as unset; no real mode has bits above `0o7777`.
+const NO_FILE_MODE: u32 = u32::MAX;
+
+/// Mode of the files [atomic_write_private] writes.
+pub const PRIVATE_FILE_MODE: u32 = 0o600;
+
+static FILE_MODE: AtomicU32 = AtomicU32::new(NO_FILE_MODE);
+
+/// Sets the Unix permission bits of every file written from now on, as given by `--file-mode`;
+/// `None` leaves them to the umask. Ignored on other platforms.
+pub fn set_file_mode(mode: Option<u32>) {
+    FILE_MODE.store(mode.unwrap_or(NO_FILE_MODE), Ordering::Relaxed);
+}
+
+/// The mode set with [set_file_mode], if any.
+pub fn file_mode() -> Option<u32> {
+    Some(FILE_MODE.load(Ordering::Relaxed)).filter(|&mode| mode != NO_FILE_MODE)
+}
+
+/// Creates `dir` if needed and checks that files can be created in it, so an unusable output
+/// directory fails before any proving work rather than when the results are written.
+///
+/// Permission bits do not tell the whole story (ACLs, read-only mounts, running as root), so the
+/// check creates and removes a probe file.
+pub fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    let not_writable =
+        |e: std::io::Error| anyhow!("output directory {} is not writable: {e}", dir.display());
+    fs::create_dir_all(dir).map_err(not_writable)?;
+    let probe = dir.join(format!(".castvote-write-check.{}", std::process::id()));
+    File::create(&probe).map_err(not_writable)?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Writes `bytes` to `path` so that readers see either the previous contents or the new ones,
+/// never a truncated file.
+///
+/// The bytes go to a temporary file in the same directory, which is flushed to disk and then
+/// renamed over `path`. The rename is atomic as long as both live on the same filesystem.
+pub fn atomic_write(path: impl AsRef<Path>, bytes: impl AsRef<[u8]>) -> Result<()> {
+    atomic_write_with(path, |writer| Ok(writer.write_all(bytes.as_ref())?))
+}
+
+/// Like [atomic_write], with the contents streamed by `write` through a buffered writer instead
+/// of being held in memory first.
+pub fn atomic_write_with(
+    path: impl AsRef<Path>,
+    write: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<()> {
+    write_atomically(path.as_ref(), file_mode(), write)
+}
+
+/// Like [atomic_write] for secrets: the file is readable and writable by its owner only,
+/// whatever `--file-mode` says. On other platforms than Unix it gets the default permissions.
+pub fn atomic_write_private(path: impl AsRef<Path>, bytes: impl AsRef<[u8]>) -> Result<()> {
+    write_atomically(path.as_ref(), Some(PRIVATE_FILE_MODE), |writer| {
+        Ok(writer.write_all(bytes.as_ref())?)
+    })
+}
+
+fn write_atomically(
+    path: &Path,
+    mode: Option<u32>,
+    write: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<()> {
+    let tmp = temp_path(path)?;
+    let result = write_synced(&tmp, mode, write)
+        .and_then(|()| fs::rename(&tmp, path).context("renaming into place"));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("writing {}", path.display()))
+}
+
+/// Atomically writes `bytes` hex-encoded to `path`, encoding a chunk at a time.
+pub fn write_hex_file(path: impl AsRef<Path>, bytes: &[u8]) -> Result<()> {
+    atomic_write_with(path, |writer| write_hex(writer, bytes))
+}
+
+/// Streams `bytes` hex-encoded into `writer`.
+pub fn write_hex(writer: &mut dyn Write, bytes: &[u8]) -> Result<()> {
+    let mut buf = [0u8; 2 * HEX_CHUNK];
+    for chunk in bytes.chunks(HEX_CHUNK) {
+        let hex = &mut buf[..2 * chunk.len()];
+        hex::encode_to_slice(chunk, hex)?;
+        writer.write_all(hex)?;
+    }
+    Ok(())
+}
+
+/// Atomically writes `value` as JSON to `path`, serialized straight into the file. A path ending
+/// in `.gz` is gzip-compressed on the way, which needs the `gzip` feature.
+pub fn write_json_file(path: impl AsRef<Path>, value: &impl Serialize) -> Result<()> {
+    let path = path.as_ref();
+    let gzip = path.extension().is_some_and(|ext| ext == "gz");
+    atomic_write_with(path, |writer| {
+        if gzip {
+            write_gzip_json(writer, value)
+        } else {
+            Ok(serde_json::to_writer(writer, value)?)
+        }
+    })
+}
+
+#[cfg(feature = "gzip")]
+fn write_gzip_json(writer: &mut dyn Write, value: &impl Serialize) -> Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+    serde_json::to_writer(&mut encoder, value)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "gzip"))]
+fn write_gzip_json(_writer: &mut dyn Write, _value: &impl Serialize) -> Result<()> {
+    anyhow::bail!("writing .gz files needs the gzip feature; rebuild with --features gzip")
+}
+
+fn temp_path(path: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} is not a file path", path.display()))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(name);
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    Ok(path.with_file_name(tmp_name))
+}
+
+fn write_synced(
+    path: &Path,
+    mode: Option<u32>,
+    write: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<()> {
+    let file = File::create(path).context("creating temporary file")?;
+    // Restrict the file before anything secret is written to it; the rename keeps the mode.
+    #[cfg(not(unix))]
+    let _ = mode;
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode)).context("setting file mode")?;
+    }
+    let mut writer = BufWriter::new(file);
+    write(&mut writer).context("writing temporary file")?;
+    let file = writer.into_inner().map_err(|err| err.into_error()).context("writing temporary file")?;
+    file.sync_all().context("syncing temporary file")?;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feedback while a long blocking call, such as proving, runs.
+
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use castvote_core::Date;
+
+/// How often the spinner is redrawn on a terminal.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a status line is logged when stderr is not a terminal.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(30);
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Runs `f`, reporting progress on stderr until it returns, unless `--quiet` was given.
+///
+/// On a terminal a spinner with the elapsed time is redrawn in place. Otherwise, e.g. in CI logs,
+/// plain timestamped status lines are printed every [STATUS_INTERVAL] instead of control
+/// characters.
+pub fn with_progress<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    if crate::console::is_quiet() {
+        return f();
+    }
+    let interactive = io::stderr().is_terminal();
+    let interval = if interactive { SPINNER_INTERVAL } else { STATUS_INTERVAL };
+    with_progress_to(io::stderr(), interactive, interval, label, f)
+}
+
+/// Like [with_progress], writing to `out` with an explicit mode and update interval.
+pub fn with_progress_to<T, W: Write + Send>(
+    mut out: W,
+    interactive: bool,
+    interval: Duration,
+    label: &str,
+    f: impl FnOnce() -> T,
+) -> T {
+    let start = Instant::now();
+    let _ = writeln!(out, "[{}] {label} started", timestamp());
+
+    let (done, finished) = mpsc::channel::<()>();
+    let (result, mut out) = thread::scope(|scope| {
+        // The call being measured blocks, so updates come from a separate thread.
+        let ticker = scope.spawn(move || {
+            let mut frame = 0;
+            while let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(interval) {
+                let elapsed = start.elapsed().as_secs();
+                if interactive {
+                    let _ = write!(out, "\r{} {label}... {elapsed}s", SPINNER[frame % SPINNER.len()]);
+                    frame += 1;
+                } else {
+                    let _ = writeln!(out, "[{}] {label} running, {elapsed}s elapsed", timestamp());
+                }
+                let _ = out.flush();
+            }
+            if interactive && frame > 0 {
+                // Erase the spinner line.
+                let _ = write!(out, "\r\x1b[2K");
+            }
+            out
+        });
+        let result = f();
+        drop(done);
+        (result, ticker.join().expect("progress thread panicked"))
+    });
+
+    let _ = writeln!(out, "[{}] {label} finished in {:.1?}", timestamp(), start.elapsed());
+    let _ = out.flush();
+    result
+}
+
+/// Current UTC time as `YYYY-MM-DDTHH:MM:SSZ`.
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        Date::from_unix_days(days as i64),
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,513 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Proving signed ballots and writing the artifacts consumed on-chain.
+//!
+//! Without the `prove` feature only the consumer side is left: verifying receipts, encoding
+//! seals and writing artifacts.
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::Path,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use alloy_sol_types::SolValue;
+use anyhow::{bail, Context, Result};
+use castvote_core::RevealInfo;
+use k256_methods::K256_VERIFY_ID;
+use risc0_zkvm::{
+    compute_image_id,
+    sha::{Digest, Digestible},
+    InnerReceipt, Receipt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::VoteError,
+    input::METHODS,
+    journal::decode_journal,
+    output::{atomic_write, write_hex_file, write_json_file},
+};
+
+// Verify-only builds leave out the prover client and the executor.
+#[cfg(feature = "prove")]
+use {
+    crate::{
+        bonsai::prove_on_bonsai,
+        progress::with_progress,
+        timings::{timed, Phase},
+    },
+    castvote_core::VoteParams,
+    risc0_zkvm::{default_executor, default_prover, ExecutorEnv, ProverOpts, VerifierContext},
+};
+
+/// Directory the artifacts are written to unless `--out-dir` says otherwise.
+pub const DEFAULT_OUT_DIR: &str = "/tmp/castvote";
+
+/// Image IDs already checked against their ELF, keyed by the ELF's address and length, which are
+/// fixed for the `&'static` ELFs generated into `k256_methods`.
+static CHECKED_IMAGE_IDS: OnceLock<Mutex<HashMap<(usize, usize), Digest>>> = OnceLock::new();
+
+/// Returns the image ID of `elf` after checking that it matches `image_id`, the ID generated
+/// alongside the ELF at build time.
+///
+/// Computing an image ID hashes the whole ELF, so the result is cached for the life of the
+/// process and batch runs pay for it once. A mismatch means the ELF and the ID come from different
+/// builds: every receipt would fail verification, so it is reported before proving starts.
+pub fn checked_image_id(elf: &'static [u8], image_id: [u32; 8]) -> Result<Digest> {
+    let cache = CHECKED_IMAGE_IDS.get_or_init(Default::default);
+    let key = (elf.as_ptr() as usize, elf.len());
+    let cached = cache.lock().expect("image ID cache poisoned").get(&key).copied();
+    let computed = match cached {
+        Some(computed) => computed,
+        None => {
+            let computed = compute_image_id(elf).context("computing image ID")?;
+            cache.lock().expect("image ID cache poisoned").insert(key, computed);
+            computed
+        }
+    };
+
+    let expected = Digest::from(image_id);
+    if computed != expected {
+        bail!(
+            "guest ELF has image ID {computed} but was built with {expected}; rebuild the guest \
+             methods"
+        );
+    }
+    Ok(computed)
+}
+
+/// How [prove_vote_with] runs the prover.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProveMode {
+    /// A Groth16 receipt for on-chain verification, or a fake one if `RISC0_DEV_MODE=1` is set.
+    /// The journal only depends on the input, but a real seal carries fresh prover randomness.
+    #[default]
+    Groth16,
+    /// Always a fake dev-mode receipt, whatever `RISC0_DEV_MODE` says. Its seal is the digest of
+    /// the claim, so the same input gives a byte-identical journal and seal on every run.
+    Reproducible,
+    /// A succinct STARK receipt, to be compressed to Groth16 later, possibly on another machine,
+    /// with [compress_receipt]. It has no seal a contract can verify.
+    Succinct,
+}
+
+/// Which prover makes the proofs, selected with `--prover`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProverChoice {
+    /// Whatever `default_prover` picks from the environment: Bonsai if `BONSAI_API_URL` is set,
+    /// a fake prover with `RISC0_DEV_MODE=1`, the local prover otherwise.
+    #[default]
+    Default,
+    /// Bonsai, polling the session every `poll_interval` and logging each change of its status.
+    Bonsai { poll_interval: Duration },
+}
+
+/// The verifier context and prover options [prove_vote_with] uses in `mode`. Without
+/// [ProveMode::Reproducible], dev mode follows `RISC0_DEV_MODE`.
+#[cfg(feature = "prove")]
+pub fn prover_options(mode: ProveMode) -> (VerifierContext, ProverOpts) {
+    match mode {
+        ProveMode::Groth16 => (VerifierContext::default(), ProverOpts::groth16()),
+        ProveMode::Reproducible => (
+            VerifierContext::default().with_dev_mode(true),
+            ProverOpts::groth16().with_dev_mode(true),
+        ),
+        ProveMode::Succinct => (VerifierContext::default(), ProverOpts::succinct()),
+    }
+}
+
+/// Proves the guest `elf` over `input` and `params` and verifies the receipt against `image_id`.
+///
+/// With `RISC0_DEV_MODE=1` this returns a fake receipt within seconds, which is enough to test
+/// everything around the prover.
+#[cfg(feature = "prove")]
+pub fn prove_vote<T: Serialize>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+) -> Result<Receipt> {
+    prove_vote_with(input, params, elf, image_id, ProveMode::default())
+}
+
+/// Like [prove_vote_with], on the prover chosen with `--prover`.
+#[cfg(feature = "prove")]
+pub fn prove_vote_on<T: Serialize>(
+    prover: ProverChoice,
+    mode: ProveMode,
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+) -> Result<Receipt> {
+    match prover {
+        ProverChoice::Default => prove_vote_with(input, params, elf, image_id, mode),
+        ProverChoice::Bonsai { poll_interval } => {
+            prove_on_bonsai(input, params, elf, image_id, poll_interval, mode)
+        }
+    }
+}
+
+/// Like [prove_vote], running the prover in `mode`.
+///
+/// Signing is deterministic for every scheme (RFC 6979 for ECDSA), so with a fixed `--as-of` the
+/// whole pipeline is reproducible in [ProveMode::Reproducible].
+#[cfg(feature = "prove")]
+pub fn prove_vote_with<T: Serialize>(
+    input: &T,
+    params: &VoteParams,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+    mode: ProveMode,
+) -> Result<Receipt> {
+    checked_image_id(elf, image_id)?;
+    let env = || ExecutorEnv::builder().write(input)?.write(params)?.build();
+
+    // The prover executes the guest itself; running it once on its own first times execution
+    // apart from proving, and fails a ballot the guest rejects before the prover starts.
+    let env_before = env()?;
+    timed(Phase::Execution, || default_executor().execute(env_before, elf))
+        .context(VoteError::ProvingFailed)?;
+
+    let (ctx, opts) = prover_options(mode);
+
+    // Obtain the default prover.
+    let prover = default_prover();
+
+    let env = env()?;
+    let receipt = timed(Phase::Proving, || {
+        with_progress("proving", || prover.prove_with_ctx(env, &ctx, elf, &opts))
+    })
+    .context(VoteError::ProvingFailed)?
+    .receipt;
+
+    timed(Phase::Verification, || receipt.verify_with_context(&ctx, image_id))
+        .context("verifying receipt")
+        .context(VoteError::VerificationFailed)?;
+    Ok(receipt)
+}
+
+/// Verifies a receipt of the single-ballot `k256_verify` method and decodes its journal: the
+/// consumer-side counterpart of [prove_vote].
+///
+/// Never panics: a receipt that does not verify against `K256_VERIFY_ID`, including one whose
+/// journal no longer matches its seal, fails with [VoteError::VerificationFailed], and a journal
+/// that is not reveal info fails as in [decode_journal].
+pub fn verify_and_decode(receipt: &Receipt) -> Result<RevealInfo> {
+    receipt
+        .verify(K256_VERIFY_ID)
+        .context("verifying receipt")
+        .context(VoteError::VerificationFailed)?;
+    decode_journal(&receipt.journal.bytes)
+}
+
+/// [verify_and_decode], also requiring the journal to commit `expected_key_hash` as its
+/// `voter_key_hash` (see [castvote_core::voter_key_hash]) when one is given.
+///
+/// A valid receipt only shows that the guest verified the ballot against some key. When the
+/// caller knows which voter it proved for, this catches a guest that was given, and verified, a
+/// different key. A mismatch fails with [VoteError::VerificationFailed], naming both hashes.
+pub fn verify_vote(receipt: &Receipt, expected_key_hash: Option<&[u8; 32]>) -> Result<RevealInfo> {
+    let reveal = verify_and_decode(receipt)?;
+    if let Some(expected) = expected_key_hash.map(hex::encode) {
+        if reveal.voter_key_hash != expected {
+            let message = format!(
+                "the journal commits voter key hash {}, expected {expected}",
+                reveal.voter_key_hash
+            );
+            return Err(VoteError::VerificationFailed.error(message));
+        }
+    }
+    Ok(reveal)
+}
+
+/// How often, and how patiently, a failed proof is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first; 0 never retries.
+    pub retries: u32,
+    /// Wait before the first retry, doubled before every further one.
+    pub initial_backoff: Duration,
+    /// Upper bound on any single wait.
+    pub max_backoff: Duration,
+    /// Time limit on each attempt, if any; an attempt that exceeds it fails with [Timeout] and is
+    /// retried like any other transient failure.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RetryPolicy {
+    /// Retries up to `retries` times, waiting 2s, 4s, 8s, ... up to a minute in between.
+    pub fn new(retries: u32) -> Self {
+        Self {
+            retries,
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(60),
+            timeout: None,
+        }
+    }
+
+    /// The wait before retry number `retry` (starting at 0).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// Messages of failures that will recur on every attempt, whatever else the message says.
+const DETERMINISTIC: &[&str] =
+    &["guest panicked", "exit code", "session limit", "image id", "verifying receipt"];
+
+/// Messages of failures caused by the network or an overloaded prover.
+const TRANSIENT: &[&str] = &[
+    "timed out",
+    "timeout",
+    "connection",
+    "temporarily unavailable",
+    "too many requests",
+    "service unavailable",
+    "bad gateway",
+    "broken pipe",
+];
+
+/// Whether retrying could make `err` go away.
+///
+/// I/O errors are judged by their kind. Anything else is judged by its message, and unknown
+/// failures count as deterministic: retrying an unsatisfiable guest only wastes prover time.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    if err.chain().any(|cause| cause.is::<Timeout>()) {
+        return true;
+    }
+    if let Some(io) = err.chain().find_map(|cause| cause.downcast_ref::<io::Error>()) {
+        return matches!(
+            io.kind(),
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::TimedOut
+                | io::ErrorKind::Interrupted
+        );
+    }
+    let message = format!("{err:#}").to_lowercase();
+    !DETERMINISTIC.iter().any(|m| message.contains(m))
+        && TRANSIENT.iter().any(|m| message.contains(m))
+}
+
+/// Runs `prove` until it succeeds, fails with an error that is not [transient](is_transient),
+/// or has been retried `policy.retries` times, logging each failed attempt to stderr
+/// unless `--quiet` was given.
+pub fn with_retries<T>(policy: &RetryPolicy, mut prove: impl FnMut() -> Result<T>) -> Result<T> {
+    let attempts = policy.retries + 1;
+    for attempt in 1.. {
+        let err = match prove() {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        if attempt == attempts {
+            if attempts > 1 {
+                return Err(err.context(format!("proving failed after {attempts} attempts")));
+            }
+            return Err(err);
+        }
+        if !is_transient(&err) {
+            crate::status!("Attempt {attempt}/{attempts} failed, not retrying: {err:#}");
+            return Err(err);
+        }
+        let wait = policy.backoff(attempt - 1);
+        crate::status!("Attempt {attempt}/{attempts} failed: {err:#}; retrying in {}s", wait.as_secs_f32());
+        thread::sleep(wait);
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// A proving attempt that did not finish within its time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout(pub Duration);
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "proving did not finish within {}s", self.0.as_secs_f32())
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// Runs `prove` on a worker thread and gives up with a [Timeout] error once `timeout` passes.
+/// Without a timeout it just runs `prove` on the current thread.
+///
+/// A thread cannot be killed, so a timed-out attempt is abandoned rather than stopped: a remote
+/// proof keeps running on the service and a local one keeps its CPU cores busy until the segment
+/// it is proving, and the rest of the session, completes or the process exits. Its result is
+/// discarded.
+pub fn with_timeout<T: Send + 'static>(
+    timeout: Option<Duration>,
+    prove: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let Some(timeout) = timeout else {
+        return prove();
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("prover".into())
+        .spawn(move || {
+            // The receiver is gone if the attempt was abandoned.
+            let _ = sender.send(prove());
+        })
+        .context("starting the prover thread")?;
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Timeout(timeout).into()),
+        Err(RecvTimeoutError::Disconnected) => bail!("the prover thread panicked"),
+    }
+}
+
+/// The files [write_artifacts] writes into the output directory.
+pub const ARTIFACT_FILES: [&str; 5] =
+    ["seal.dat", "journal.dat", "journal_abi.dat", "journal_digest.dat", "image_id.dat"];
+
+/// Writes the hex-encoded `seal.dat`, `journal.dat`, `journal_abi.dat`, `journal_digest.dat` and
+/// `image_id.dat` for `receipt` into `out_dir`.
+pub fn write_artifacts(
+    receipt: &Receipt,
+    elf: &'static [u8],
+    image_id: [u32; 8],
+    out_dir: &Path,
+) -> Result<()> {
+    // Encode the seal with the selector.
+    let seal = encode_seal(receipt)?;
+    write_hex_file(out_dir.join("seal.dat"), &seal)?;
+
+    // The journal is what the verifier contract decodes, so it must match what was verified
+    // off-chain byte for byte.
+    let journal = &receipt.journal.bytes;
+    write_hex_file(out_dir.join("journal.dat"), journal)?;
+
+    let journal_abi = Vec::<u8>::abi_decode(journal).context("decoding journal data")?;
+    write_hex_file(out_dir.join("journal_abi.dat"), &journal_abi)?;
+    write_hex_file(out_dir.join("journal_digest.dat"), journal_digest(receipt).as_bytes())?;
+
+    let image_id = checked_image_id(elf, image_id)?;
+    write_hex_file(out_dir.join("image_id.dat"), image_id.as_bytes())?;
+    Ok(())
+}
+
+/// The SHA-256 digest of the receipt's journal: what the verifier contract checks the seal
+/// against, and what Solidity verifiers typically emit in an event. It identifies the proof on
+/// chain without the journal itself.
+pub fn journal_digest(receipt: &Receipt) -> Digest {
+    receipt.journal.digest()
+}
+
+/// File a [ProveMode::Succinct] receipt is written to in the output directory.
+pub const SUCCINCT_RECEIPT_FILE: &str = "succinct.bin";
+
+/// Writes `receipt` to `path` as bincode, the compact form a later `compress` reads back with
+/// [read_receipt_file].
+pub fn write_receipt_file(receipt: &Receipt, path: &Path) -> Result<()> {
+    atomic_write(path, bincode::serialize(receipt).context("encoding the receipt")?)
+}
+
+/// Reads a receipt written by [write_receipt_file].
+pub fn read_receipt_file(path: &Path) -> Result<Receipt> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    bincode::deserialize(&bytes).with_context(|| format!("{} is not a receipt", path.display()))
+}
+
+/// The guest method of this build that `receipt` claims to prove, found by the image ID in its
+/// claim.
+pub fn receipt_method(receipt: &Receipt) -> Result<(&'static [u8], [u32; 8])> {
+    let claim = receipt.claim().context("reading the receipt claim")?;
+    let image_id = claim.as_value().context("the receipt claim is pruned")?.pre.digest();
+    METHODS
+        .iter()
+        .find(|(_, _, id)| Digest::from(*id) == image_id)
+        .map(|(_, elf, id)| (*elf, *id))
+        .with_context(|| format!("the receipt is for image {image_id}, not a method of this build"))
+}
+
+/// Compresses a [ProveMode::Succinct] receipt to Groth16 (`ProverOpts::groth16()`: the
+/// identity-p254 recursion, then the Groth16 prover) and verifies both against the image ID the
+/// receipt claims. Returns the Groth16 receipt with the ELF and image ID of its method.
+///
+/// A receipt that is already Groth16, or a fake dev-mode one, comes back unchanged.
+#[cfg(feature = "prove")]
+pub fn compress_receipt(receipt: &Receipt) -> Result<(Receipt, &'static [u8], [u32; 8])> {
+    let (elf, image_id) = receipt_method(receipt).context(VoteError::InvalidInput)?;
+    receipt
+        .verify(image_id)
+        .context("verifying the receipt to compress")
+        .context(VoteError::VerificationFailed)?;
+
+    let prover = default_prover();
+    let opts = ProverOpts::groth16();
+    let compressed = with_progress("compressing", || prover.compress(&opts, receipt))
+        .context(VoteError::ProvingFailed)?;
+    compressed
+        .verify(image_id)
+        .context("verifying receipt")
+        .context(VoteError::VerificationFailed)?;
+    Ok((compressed, elf, image_id))
+}
+
+/// Dumps the whole `receipt` as JSON to `path`, gzip-compressed if it ends in `.gz`.
+///
+/// Succinct and composite receipts run to megabytes, so the JSON is streamed into the file
+/// rather than built as a string first.
+pub fn dump_receipt(receipt: &Receipt, path: &Path) -> Result<()> {
+    write_json_file(path, receipt)
+}
+
+/// Prefixes the seal with the 4-byte verifier selector, as expected by the RISC Zero verifier
+/// router contract.
+pub fn encode_seal(receipt: &Receipt) -> Result<Vec<u8>> {
+    let seal = match receipt.inner.clone() {
+        InnerReceipt::Fake(receipt) => {
+            let seal = receipt.claim.digest().as_bytes().to_vec();
+            let selector = &[0u8; 4];
+            // Create a new vector with the capacity to hold both selector and seal
+            let mut selector_seal = Vec::with_capacity(selector.len() + seal.len());
+            selector_seal.extend_from_slice(selector);
+            selector_seal.extend_from_slice(&seal);
+            selector_seal
+        }
+        InnerReceipt::Groth16(receipt) => {
+            let selector = &receipt.verifier_parameters.as_bytes()[..4];
+            // Create a new vector with the capacity to hold both selector and seal
+            let mut selector_seal = Vec::with_capacity(selector.len() + receipt.seal.len());
+            selector_seal.extend_from_slice(selector);
+            selector_seal.extend_from_slice(receipt.seal.as_ref());
+            selector_seal
+        }
+        _ => bail!("Unsupported receipt type"),
+    };
+    Ok(seal)
+}
@@ -0,0 +1,364 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-candidate results reports built from decoded journals.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use castvote_core::{BallotType, RevealInfo, ABSTAIN_CHOICE};
+use serde_json::{json, Value};
+
+use crate::{
+    journal::{decode_reveal_entries, read_journal_file},
+    output::{atomic_write, write_json_file},
+};
+
+/// Lower bounds of the age buckets of a demographics report when `--age-buckets` is not given.
+pub const DEFAULT_AGE_BUCKETS: [u32; 5] = [18, 25, 35, 50, 65];
+
+/// Why a journal was left out of the tally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The file could not be read or decoded as a reveal journal.
+    Undecodable(String),
+    /// An earlier journal already used this nullifier in the same poll.
+    DuplicateNullifier,
+    /// The ballot was valid but did not select any option.
+    MissingChoice,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectReason::Undecodable(e) => write!(f, "undecodable journal: {e}"),
+            RejectReason::DuplicateNullifier => write!(f, "duplicate nullifier"),
+            RejectReason::MissingChoice => write!(f, "no choice"),
+        }
+    }
+}
+
+/// A journal left out of the tally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    /// Where the journal came from, usually its file path.
+    pub source: String,
+    /// Nullifier of the journal, when it could be decoded.
+    pub nullifier: Option<String>,
+    pub reason: RejectReason,
+}
+
+/// A poll of one election: the `election_id` its nullifiers are scoped to (`None` for the fixed
+/// salt) and the `poll_id`. Polls of different elections may share a poll ID.
+pub type PollKey = (Option<String>, u64);
+
+/// Vote counts per `(election_id, poll_id, choice)`, deduplicated by nullifier within each poll
+/// of each election, so a voter voting in two elections is not a double vote.
+///
+/// Approval ballots count once for every selected option; ranked ballots count for their first
+/// preference only. Explicit abstentions use up their nullifier but are counted apart from the
+/// choices.
+#[derive(Debug, Default)]
+pub struct Report {
+    counts: BTreeMap<(Option<String>, u64, String), u64>,
+    abstentions: BTreeMap<PollKey, u64>,
+    seen: HashSet<(PollKey, String)>,
+    rejected: Vec<Rejected>,
+    demographics: BTreeMap<PollKey, PollDemographics>,
+}
+
+/// The revealed demographics of the ballots counted in one poll.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PollDemographics {
+    /// Ballots per disclosed age.
+    pub ages: BTreeMap<u32, u64>,
+    pub students: u64,
+    pub non_students: u64,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a decoded journal. The first journal seen for a nullifier wins; later ones are
+    /// rejected as duplicates.
+    pub fn add(&mut self, source: &str, reveal: RevealInfo) {
+        let poll: PollKey = (reveal.election_id.clone(), reveal.poll_id);
+        if !self.seen.insert((poll.clone(), reveal.nullifier.clone())) {
+            self.reject(source, Some(reveal.nullifier), RejectReason::DuplicateNullifier);
+            return;
+        }
+        if reveal.abstained == Some(true) {
+            *self.abstentions.entry(poll).or_default() += 1;
+            return;
+        }
+        if reveal.selection.is_empty() {
+            self.reject(source, Some(reveal.nullifier), RejectReason::MissingChoice);
+            return;
+        }
+        for choice in counted_choices(&reveal) {
+            let key = (poll.0.clone(), poll.1, choice.clone());
+            *self.counts.entry(key).or_default() += 1;
+        }
+        let demographics = self.demographics.entry(poll).or_default();
+        *demographics.ages.entry(reveal.age).or_default() += 1;
+        if reveal.is_student {
+            demographics.students += 1;
+        } else {
+            demographics.non_students += 1;
+        }
+    }
+
+    /// Decodes and adds a raw journal, recording it as rejected if it cannot be decoded. Batch
+    /// and multi-poll journals add every entry.
+    pub fn add_journal(&mut self, source: &str, journal: &[u8]) {
+        match decode_reveal_entries(journal) {
+            Ok(reveals) => reveals.into_iter().for_each(|reveal| self.add(source, reveal)),
+            Err(e) => self.reject(source, None, RejectReason::Undecodable(format!("{e:#}"))),
+        }
+    }
+
+    /// Reads, decodes and adds a hex `journal.dat` file.
+    pub fn add_journal_file(&mut self, path: &Path) {
+        let source = path.display().to_string();
+        match read_journal_file(path) {
+            Ok(journal) => self.add_journal(&source, &journal),
+            Err(e) => self.reject(&source, None, RejectReason::Undecodable(format!("{e:#}"))),
+        }
+    }
+
+    fn reject(&mut self, source: &str, nullifier: Option<String>, reason: RejectReason) {
+        self.rejected.push(Rejected {
+            source: source.to_string(),
+            nullifier,
+            reason,
+        });
+    }
+
+    /// Counts per `(election_id, poll_id, choice)`, ordered by election, poll and then choice,
+    /// unscoped polls first.
+    pub fn counts(&self) -> &BTreeMap<(Option<String>, u64, String), u64> {
+        &self.counts
+    }
+
+    /// Explicit abstentions per poll, ordered like the counts.
+    pub fn abstentions(&self) -> &BTreeMap<PollKey, u64> {
+        &self.abstentions
+    }
+
+    /// Journals left out of the counts, in the order they were added.
+    pub fn rejected(&self) -> &[Rejected] {
+        &self.rejected
+    }
+
+    /// Demographics of the counted ballots per poll, ordered like the counts. Duplicates,
+    /// abstentions and ballots without a choice are left out, as they are from the counts.
+    pub fn demographics(&self) -> &BTreeMap<PollKey, PollDemographics> {
+        &self.demographics
+    }
+
+    /// How many journals were rejected for reusing a nullifier already counted.
+    pub fn double_votes(&self) -> usize {
+        self.rejected
+            .iter()
+            .filter(|rejected| rejected.reason == RejectReason::DuplicateNullifier)
+            .count()
+    }
+
+    /// Writes the `election_id,poll_id,choice,count` rows, with an empty `election_id` for
+    /// unscoped polls. Abstentions are a row of their own with the reserved `abstain` choice.
+    pub fn write_counts_csv(&self, path: &Path) -> Result<()> {
+        let mut rows = self.counts.clone();
+        for ((election_id, poll_id), count) in &self.abstentions {
+            rows.insert((election_id.clone(), *poll_id, ABSTAIN_CHOICE.to_string()), *count);
+        }
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["election_id", "poll_id", "choice", "count"])?;
+        for ((election_id, poll_id, choice), count) in &rows {
+            writer.write_record([
+                election_id.clone().unwrap_or_default(),
+                poll_id.to_string(),
+                choice.clone(),
+                count.to_string(),
+            ])?;
+        }
+        atomic_write(path, writer.into_inner()?)
+    }
+
+    /// Writes the counts nested by election and then poll:
+    /// `{"elections": [{"election_id", "polls": [{"poll_id", "counts": {choice: count}}]}]}`,
+    /// with a `null` `election_id` for unscoped polls. A poll with explicit abstentions also
+    /// has an `"abstentions"` count.
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let mut elections: BTreeMap<&Option<String>, BTreeMap<u64, BTreeMap<&str, u64>>> =
+            BTreeMap::new();
+        for ((election_id, poll_id, choice), count) in &self.counts {
+            let poll = elections.entry(election_id).or_default().entry(*poll_id).or_default();
+            poll.insert(choice, *count);
+        }
+        for (election_id, poll_id) in self.abstentions.keys() {
+            elections.entry(election_id).or_default().entry(*poll_id).or_default();
+        }
+        let elections: Vec<Value> = elections
+            .into_iter()
+            .map(|(election_id, polls)| {
+                let polls: Vec<Value> = polls
+                    .into_iter()
+                    .map(|(poll_id, counts)| {
+                        let mut poll = json!({"poll_id": poll_id, "counts": counts});
+                        let key = (election_id.clone(), poll_id);
+                        if let Some(abstentions) = self.abstentions.get(&key) {
+                            poll["abstentions"] = json!(abstentions);
+                        }
+                        poll
+                    })
+                    .collect();
+                json!({"election_id": election_id, "polls": polls})
+            })
+            .collect();
+        write_json_file(path, &json!({"elections": elections}))
+    }
+
+    /// Writes the `source,nullifier,reason` rows of rejected journals.
+    pub fn write_rejected_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["source", "nullifier", "reason"])?;
+        for rejected in &self.rejected {
+            writer.write_record([
+                rejected.source.clone(),
+                rejected.nullifier.clone().unwrap_or_default(),
+                rejected.reason.to_string(),
+            ])?;
+        }
+        atomic_write(path, writer.into_inner()?)
+    }
+}
+
+/// Writes the per-poll age histogram, bucketed by `buckets`, and the `is_student` split as
+/// JSON.
+pub fn write_demographics_json(report: &Report, buckets: &AgeBuckets, path: &Path) -> Result<()> {
+    let polls: Vec<Value> = report
+        .demographics()
+        .iter()
+        .map(|((election_id, poll_id), demographics)| {
+            json!({
+                "election_id": election_id,
+                "poll_id": poll_id,
+                "ballots": demographics.students + demographics.non_students,
+                "age": buckets.histogram(demographics),
+                "is_student": {"true": demographics.students, "false": demographics.non_students},
+            })
+        })
+        .collect();
+    write_json_file(path, &json!({"age_buckets": buckets.bounds(), "polls": polls}))
+}
+
+/// Age buckets of a demographics report, given by their lower bounds: `[18, 25]` makes the
+/// buckets `0-17`, `18-24` and `25+`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgeBuckets {
+    bounds: Vec<u32>,
+}
+
+impl AgeBuckets {
+    /// Checks that the bounds are strictly increasing and above zero.
+    pub fn new(bounds: Vec<u32>) -> Result<Self> {
+        if bounds.first() == Some(&0) {
+            bail!("age bucket bounds must be above 0: the first bucket starts at 0");
+        }
+        if let Some(pair) = bounds.windows(2).find(|pair| pair[0] >= pair[1]) {
+            bail!("age bucket bounds must increase, but {} follows {}", pair[1], pair[0]);
+        }
+        Ok(Self { bounds })
+    }
+
+    /// Lower bounds of every bucket after the first.
+    pub fn bounds(&self) -> &[u32] {
+        &self.bounds
+    }
+
+    /// `[min, max]` of each bucket, `max` being `None` for the open-ended last one.
+    pub fn ranges(&self) -> Vec<(u32, Option<u32>)> {
+        let mins = std::iter::once(0).chain(self.bounds.iter().copied());
+        let maxes = self.bounds.iter().map(|bound| Some(bound - 1)).chain([None]);
+        mins.zip(maxes).collect()
+    }
+
+    /// One `{bucket, min, max, count}` object per bucket, youngest first.
+    pub fn histogram(&self, demographics: &PollDemographics) -> Vec<Value> {
+        self
+            .ranges()
+            .into_iter()
+            .map(|(min, max)| {
+                let in_bucket = |age: u32| age >= min && max.is_none_or(|max| age <= max);
+                let count: u64 = demographics
+                    .ages
+                    .iter()
+                    .filter(|(&age, _)| in_bucket(age))
+                    .map(|(_, count)| count)
+                    .sum();
+                let bucket = match max {
+                    Some(max) => format!("{min}-{max}"),
+                    None => format!("{min}+"),
+                };
+                json!({"bucket": bucket, "min": min, "max": max, "count": count})
+            })
+            .collect()
+    }
+}
+
+impl Default for AgeBuckets {
+    fn default() -> Self {
+        Self { bounds: DEFAULT_AGE_BUCKETS.to_vec() }
+    }
+}
+
+/// The options a reveal entry counts for: every selected option of single and approval ballots,
+/// the first preference of ranked ones.
+pub fn counted_choices(reveal: &RevealInfo) -> &[String] {
+    match reveal.ballot_type {
+        BallotType::Ranked => &reveal.selection[..reveal.selection.len().min(1)],
+        BallotType::Single | BallotType::Approval => &reveal.selection[..],
+    }
+}
+
+/// Builds a report from every journal file matching `pattern`, visited in sorted path order so the
+/// choice of which duplicate is kept is reproducible.
+pub fn report_from_glob(pattern: &str) -> Result<Report> {
+    let mut paths = glob::glob(pattern)
+        .with_context(|| format!("invalid glob pattern {pattern:?}"))?
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.sort();
+
+    let mut report = Report::new();
+    for path in &paths {
+        report.add_journal_file(path);
+    }
+    Ok(report)
+}
+
+/// Path of the rejected-entries file written next to the results file `out`.
+pub fn rejected_path(out: &Path) -> PathBuf {
+    out.with_extension("rejected.csv")
+}
+
+/// Path of the nested JSON report written next to the results file `out`.
+pub fn json_path(out: &Path) -> PathBuf {
+    out.with_extension("json")
+}
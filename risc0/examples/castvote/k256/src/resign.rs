@@ -0,0 +1,171 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Key rotation: re-signing ballots that were signed for proving elsewhere under a new voter key.
+//!
+//! Every signature is checked against the old public key first, so a rotation never launders a
+//! ballot the old key did not sign.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use castvote_core::MessageFormat;
+
+use crate::{
+    ballot::read_text_file,
+    cli::{Curve, Scheme},
+    keys::{check_encodings, sign, verify_signature, SignedBallot, VoterKey},
+    output::{atomic_write, ensure_writable_dir},
+};
+
+/// A signed ballot that was not re-signed because its signature does not verify against the old
+/// public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedSignature {
+    /// The file the entry was read from.
+    pub source: PathBuf,
+    /// Position of the entry in the file.
+    pub index: usize,
+    pub reason: String,
+}
+
+/// Outcome of [resign_files].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResignSummary {
+    /// Number of ballots re-signed under the new key.
+    pub resigned: usize,
+    pub rejected: Vec<RejectedSignature>,
+    /// The files written, or with [dry_run_resign] the files that would be; empty if any entry
+    /// was rejected.
+    pub files: Vec<PathBuf>,
+}
+
+/// How the ballots were signed and are to be re-signed.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyRotation<'a> {
+    pub scheme: Scheme,
+    pub curve: Curve,
+    pub message_format: MessageFormat,
+    /// Base64 public key the ballots were signed with.
+    pub old_public_key: &'a str,
+    pub new_key: &'a VoterKey,
+}
+
+impl KeyRotation<'_> {
+    /// Verifies `entry` against the old public key and signs its ballot with the new key.
+    ///
+    /// The ballot string is signed byte for byte as it was, so the new signature covers exactly
+    /// what the old one did.
+    pub fn resign(&self, entry: &SignedBallot) -> Result<SignedBallot> {
+        verify_signature(
+            self.scheme,
+            self.curve,
+            &entry.ballot,
+            &entry.signature,
+            self.old_public_key,
+            self.message_format,
+        )?;
+        let signature = sign(self.new_key, &entry.ballot, self.message_format)?;
+        let public_key = self.new_key.public_key_base64();
+        check_encodings(self.new_key, &signature, &public_key)?;
+        Ok(SignedBallot { ballot: entry.ballot.clone(), poll_id: entry.poll_id, signature, public_key })
+    }
+}
+
+/// Re-signs every entry of the `--sig-out` files matching `pattern` and writes each file, under
+/// the same name, to `out_dir`.
+///
+/// Nothing is written unless every entry verifies against the old key: a partial rotation would
+/// leave some voters' ballots behind under a key that is being retired.
+pub fn resign_files(pattern: &str, rotation: &KeyRotation, out_dir: &Path) -> Result<ResignSummary> {
+    resign(pattern, rotation, out_dir, true)
+}
+
+/// Checks and re-signs like [resign_files] but writes nothing, not even `out_dir`: the summary
+/// lists the files that would be written.
+pub fn dry_run_resign(
+    pattern: &str,
+    rotation: &KeyRotation,
+    out_dir: &Path,
+) -> Result<ResignSummary> {
+    resign(pattern, rotation, out_dir, false)
+}
+
+fn resign(
+    pattern: &str,
+    rotation: &KeyRotation,
+    out_dir: &Path,
+    write: bool,
+) -> Result<ResignSummary> {
+    let mut paths = glob::glob(pattern)
+        .with_context(|| format!("invalid glob pattern {pattern:?}"))?
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.sort();
+    if paths.is_empty() {
+        bail!("no files match {pattern:?}");
+    }
+    if rotation.new_key.public_key_base64() == rotation.old_public_key {
+        bail!("the new key is the old key");
+    }
+
+    if write {
+        ensure_writable_dir(out_dir)?;
+    }
+    // An output directory that does not exist yet holds no input.
+    let out_dir_canonical = fs::canonicalize(out_dir).ok();
+    let mut summary = ResignSummary::default();
+    let mut outputs = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        if Some(fs::canonicalize(parent)?) == out_dir_canonical {
+            bail!("{} is in the output directory; re-signing would overwrite it", path.display());
+        }
+        let entries: Vec<SignedBallot> = serde_json::from_str(&read_text_file(path)?)
+            .with_context(|| format!("{} is not a list of signed ballots", path.display()))?;
+
+        let mut resigned = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            match rotation.resign(entry) {
+                Ok(entry) => resigned.push(entry),
+                Err(e) => summary.rejected.push(RejectedSignature {
+                    source: path.clone(),
+                    index,
+                    reason: format!("{e:#}"),
+                }),
+            }
+        }
+        summary.resigned += resigned.len();
+        let out = out_dir.join(path.file_name().context("input file has no name")?);
+        if outputs.iter().any(|(other, _)| *other == out) {
+            bail!("two input files would be written to {}", out.display());
+        }
+        outputs.push((out, resigned));
+    }
+
+    if summary.rejected.is_empty() {
+        for (out, resigned) in outputs {
+            if write {
+                atomic_write(&out, format!("{}\n", serde_json::to_string_pretty(&resigned)?))?;
+            }
+            summary.files.push(out);
+        }
+    } else {
+        summary.resigned = 0;
+    }
+    Ok(summary)
+}
@@ -0,0 +1,137 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Inspecting `seal.dat` files: which verifier a seal is for and how long its proof is.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use castvote_core::GROTH16_SEAL_LEN;
+use risc0_zkvm::{sha::Digestible, Groth16ReceiptVerifierParameters};
+use serde::Serialize;
+
+/// Selector the prover writes in front of the claim digest of a dev-mode (fake) receipt.
+pub const FAKE_SELECTOR: [u8; 4] = [0; 4];
+
+/// How a seal file stores its bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SealEncoding {
+    /// Hex, as the prover writes `seal.dat`, optionally `0x`-prefixed.
+    Hex,
+    /// The seal bytes as they are, e.g. copied out of a transaction.
+    Binary,
+}
+
+/// Which verifier a seal's selector routes it to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SealKind {
+    /// A dev-mode seal: an all-zero selector followed by the claim digest. It proves nothing and
+    /// every verifier contract rejects it.
+    Fake,
+    /// A seal for the Groth16 verifier of the `risc0-zkvm` version this tool was built with.
+    Groth16,
+    /// Any other selector: another verifier, another `risc0-zkvm` version, or not a seal at all.
+    Unknown,
+}
+
+impl SealKind {
+    /// Short name of the kind, as printed by `checkvote inspect-seal`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SealKind::Fake => "fake",
+            SealKind::Groth16 => "groth16",
+            SealKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for SealKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a seal holds, as far as it can be told without verifying it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SealInfo {
+    pub encoding: SealEncoding,
+    /// The first four bytes, naming the verifier.
+    #[serde(serialize_with = "hex_selector")]
+    pub selector: [u8; 4],
+    pub kind: SealKind,
+    /// Length of the whole seal, selector included.
+    pub seal_len: usize,
+    /// Length of the proof after the selector.
+    pub proof_len: usize,
+}
+
+fn hex_selector<S: serde::Serializer>(selector: &[u8; 4], s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&hex::encode(selector))
+}
+
+impl SealInfo {
+    /// Parses `seal`, selector first, as read from a file stored with `encoding`.
+    pub fn parse(seal: &[u8], encoding: SealEncoding) -> Result<Self> {
+        let Some((selector, proof)) = seal.split_first_chunk::<4>() else {
+            bail!("seal has {} bytes, too short for its 4-byte selector", seal.len());
+        };
+        let groth16 = Groth16ReceiptVerifierParameters::default().digest();
+        let kind = if *selector == FAKE_SELECTOR {
+            SealKind::Fake
+        } else if selector[..] == groth16.as_bytes()[..4] {
+            SealKind::Groth16
+        } else {
+            SealKind::Unknown
+        };
+        let (seal_len, proof_len) = (seal.len(), proof.len());
+        Ok(Self { encoding, selector: *selector, kind, seal_len, proof_len })
+    }
+
+    /// The proof length a seal of this kind should have, if it has a fixed one: the 256 bytes of
+    /// Groth16 proof points, or the 32-byte claim digest of a fake seal.
+    pub fn expected_proof_len(&self) -> Option<usize> {
+        match self.kind {
+            SealKind::Fake => Some(32),
+            SealKind::Groth16 => Some(GROTH16_SEAL_LEN),
+            SealKind::Unknown => None,
+        }
+    }
+}
+
+/// Reads a seal file, hex or raw binary, telling the two apart by the content.
+///
+/// A file that is nothing but an even number of hex digits, give or take a `0x` prefix and
+/// surrounding whitespace, is read as hex. A raw seal almost never is: its first byte alone would
+/// have to be an ASCII digit or letter.
+pub fn read_seal_file(path: impl AsRef<Path>) -> Result<(Vec<u8>, SealEncoding)> {
+    let path = path.as_ref();
+    let contents = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let text = contents.trim_ascii();
+    let digits = text.strip_prefix(b"0x").unwrap_or(text);
+    if !digits.is_empty() && digits.len() % 2 == 0 && digits.iter().all(u8::is_ascii_hexdigit) {
+        let seal = hex::decode(digits).context("decoding hex seal")?;
+        return Ok((seal, SealEncoding::Hex));
+    }
+    Ok((contents, SealEncoding::Binary))
+}
+
+/// Reads and parses the seal file at `path`.
+pub fn inspect_seal_file(path: impl AsRef<Path>) -> Result<SealInfo> {
+    let path = path.as_ref();
+    let (seal, encoding) = read_seal_file(path)?;
+    SealInfo::parse(&seal, encoding).with_context(|| format!("inspecting {}", path.display()))
+}
@@ -0,0 +1,171 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `checkvote self-test`: the pipeline run against the committed test vector in
+//! `testdata/self-test`, to confirm that a build signs, proves and encodes exactly as expected.
+
+use std::fmt;
+
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+use castvote_core::{parse_ballot, RevealInfo, VoteParams};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ballot::prepare_ballot,
+    cli::{Curve, Scheme},
+    keys::{sign, VoterKey},
+};
+
+#[cfg(feature = "prove")]
+use {
+    crate::estimate::{estimate_vote, Estimate},
+    crate::prove::{prove_vote_with, ProveMode},
+    k256_methods::{K256_VERIFY_ELF, K256_VERIFY_ID},
+};
+
+/// The test vector built into the binary, so the self-test also runs where it is installed.
+pub const VECTOR: &str = include_str!("../testdata/self-test/vector.json");
+
+/// The ballot the test vector signs and proves.
+pub const BALLOT: &str = include_str!("../testdata/self-test/ballot.json");
+
+/// A ballot, a key and a poll, with everything the pipeline must derive from them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// Hex seed of the voter's secp256k1 ECDSA key, derived with [VoterKey::from_seed].
+    pub key_seed: String,
+    pub poll_id: u64,
+    pub params: VoteParams,
+    /// The voter's public key, as passed to the guest.
+    pub public_key: String,
+    /// The ballot signature, deterministic under RFC 6979.
+    pub signature: String,
+    /// The nullifier the guest commits, hex.
+    pub nullifier: String,
+    /// The journal the guest commits, hex, exactly as written to `journal.dat`.
+    pub journal: String,
+}
+
+impl TestVector {
+    /// The test vector built into the binary.
+    pub fn builtin() -> Result<Self> {
+        serde_json::from_str(VECTOR).context("parsing the built-in test vector")
+    }
+
+    /// The voter key derived from [key_seed](Self::key_seed).
+    pub fn key(&self) -> Result<VoterKey> {
+        let seed = hex::decode(&self.key_seed).context("test vector key seed is not hex")?;
+        VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, &seed)
+    }
+}
+
+/// The outcome of one step of the self-test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub pass: bool,
+    /// What went wrong; empty if the check passed.
+    pub detail: String,
+}
+
+impl SelfTestCheck {
+    fn compare(name: &'static str, actual: &str, expected: &str) -> Self {
+        let detail = match actual == expected {
+            true => String::new(),
+            false => format!("expected {expected}, got {actual}"),
+        };
+        Self { name, pass: detail.is_empty(), detail }
+    }
+
+    fn failed(name: &'static str, err: anyhow::Error) -> Self {
+        Self { name, pass: false, detail: format!("{err:#}") }
+    }
+}
+
+impl fmt::Display for SelfTestCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pass {
+            true => write!(f, "PASS {}", self.name),
+            false => write!(f, "FAIL {}: {}", self.name, self.detail),
+        }
+    }
+}
+
+/// What the host derives from the test vector's ballot, key and poll.
+struct Derived {
+    key: VoterKey,
+    ballot: String,
+    signature: String,
+}
+
+/// Runs the host side of the pipeline on `ballot` and checks every derived value against
+/// `vector`: the public key, the signature, the nullifier and the encoded journal.
+///
+/// A step that fails outright ends the run with a failed `pipeline` check.
+pub fn run_self_test(vector: &TestVector, ballot: &str) -> Vec<SelfTestCheck> {
+    let mut checks = Vec::new();
+    if let Err(err) = check_host(vector, ballot, &mut checks) {
+        checks.push(SelfTestCheck::failed("pipeline", err));
+    }
+    checks
+}
+
+fn derive(vector: &TestVector, ballot: &str) -> Result<Derived> {
+    let key = vector.key()?;
+    let ballot = prepare_ballot(ballot, &vector.params).context("preparing the ballot")?;
+    let signature = sign(&key, &ballot, vector.params.message_format)?;
+    Ok(Derived { key, ballot, signature })
+}
+
+fn check_host(vector: &TestVector, ballot: &str, checks: &mut Vec<SelfTestCheck>) -> Result<()> {
+    let Derived { key, ballot, signature } = derive(vector, ballot)?;
+    checks.push(SelfTestCheck::compare("public key", &key.public_key_base64(), &vector.public_key));
+    checks.push(SelfTestCheck::compare("signature", &signature, &vector.signature));
+
+    let (ballot, public_key) = (parse_ballot(&ballot)?, key.canonical_public_key());
+    let reveal = RevealInfo::new(&ballot, vector.poll_id, &public_key, &vector.params)?;
+    checks.push(SelfTestCheck::compare("nullifier", &reveal.nullifier, &vector.nullifier));
+    let journal = reveal.encode_with(vector.params.journal_codec).abi_encode();
+    checks.push(SelfTestCheck::compare("journal encoding", &hex::encode(journal), &vector.journal));
+    Ok(())
+}
+
+/// Proves the test vector's ballot in dev mode ([ProveMode::Reproducible]) and checks that the
+/// receipt verifies and commits the expected journal byte for byte.
+#[cfg(feature = "prove")]
+pub fn prove_self_test(vector: &TestVector, ballot: &str) -> SelfTestCheck {
+    let prove = || -> Result<String> {
+        let Derived { key, ballot, signature } = derive(vector, ballot)?;
+        let input = (signature, ballot.as_str(), vector.poll_id, key.public_key_base64());
+        let (elf, image_id) = (K256_VERIFY_ELF, K256_VERIFY_ID);
+        let receipt =
+            prove_vote_with(&input, &vector.params, elf, image_id, ProveMode::Reproducible)?;
+        Ok(hex::encode(&receipt.journal.bytes))
+    };
+    match prove() {
+        Ok(journal) => SelfTestCheck::compare("proof", &journal, &vector.journal),
+        Err(err) => SelfTestCheck::failed("proof", err),
+    }
+}
+
+/// Executes the test vector's ballot with the single-ballot method, without proving, and returns
+/// its cycle counts: the reference workload the `benchmark` binary reports.
+#[cfg(feature = "prove")]
+pub fn estimate_self_test(vector: &TestVector, ballot: &str) -> Result<Estimate> {
+    let Derived { key, ballot, signature } = derive(vector, ballot)?;
+    let input = (signature, ballot.as_str(), vector.poll_id, key.public_key_base64());
+    estimate_vote(&input, &vector.params, K256_VERIFY_ELF, K256_VERIFY_ID)
+}
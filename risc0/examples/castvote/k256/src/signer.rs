@@ -0,0 +1,137 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ballot signing behind a pluggable [BallotSigner], so keys can live in an HSM or hardware wallet.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{MessageFormat, SignedDigest};
+use k256::ecdsa::{
+    signature::{
+        hazmat::{PrehashSigner, PrehashVerifier},
+        Signer, Verifier,
+    },
+    Signature, SigningKey, VerifyingKey,
+};
+use zeroize::ZeroizeOnDrop;
+
+/// Something that can produce secp256k1 ballot signatures.
+///
+/// Implementations backed by hardware never need to expose the private key: the host only hands
+/// over the ballot digest and receives the signature.
+pub trait BallotSigner {
+    /// Signs the SHA-256 digest of a ballot.
+    fn sign(&self, msg_hash: &[u8]) -> Result<Signature>;
+
+    /// Signs a 32-byte digest as is, without hashing it again. Used for EIP-191 `personal_sign`
+    /// and EIP-712 ballots; signers that cannot sign raw digests keep the default, which refuses.
+    fn sign_prehash(&self, digest: &[u8; 32]) -> Result<Signature> {
+        let _ = digest;
+        bail!("this signer cannot sign prehashed digests")
+    }
+
+    /// Returns the key that verifies this signer's signatures.
+    fn public_key(&self) -> VerifyingKey;
+}
+
+/// A [BallotSigner] holding the private key in memory.
+pub struct SoftwareSigner {
+    key: SigningKey,
+}
+
+impl SoftwareSigner {
+    pub fn new(key: SigningKey) -> Self {
+        Self { key }
+    }
+
+    /// Imports a raw 32-byte secp256k1 private key encoded as Base64.
+    pub fn from_base64(private_key: &str) -> Result<Self> {
+        let bytes = crate::keys::decode_private_key(private_key)?;
+        let key = SigningKey::from_bytes((&*bytes).into()).context("invalid secp256k1 private key")?;
+        Ok(Self::new(key))
+    }
+}
+
+// `SigningKey` wipes its scalar when dropped.
+impl ZeroizeOnDrop for SoftwareSigner {}
+
+impl BallotSigner for SoftwareSigner {
+    fn sign(&self, msg_hash: &[u8]) -> Result<Signature> {
+        Ok(self.key.sign(msg_hash))
+    }
+
+    fn sign_prehash(&self, digest: &[u8; 32]) -> Result<Signature> {
+        Ok(self.key.sign_prehash(digest)?)
+    }
+
+    fn public_key(&self) -> VerifyingKey {
+        *self.key.verifying_key()
+    }
+}
+
+/// Signs a ballot with `signer` and returns the Base64 `r || s` signature expected by the guest.
+///
+/// The signature is checked against the signer's public key before it is returned, so a faulty
+/// signer is caught here rather than after minutes of proving.
+pub fn sign_ballot(signer: &dyn BallotSigner, ballot: &str) -> Result<String> {
+    sign_ballot_as(signer, ballot, MessageFormat::Sha256)
+}
+
+/// Signs a ballot the way `personal_sign` does and returns the Base64 `r || s` signature.
+///
+/// The signature covers [eip191_hash](castvote_core::eip191_hash) of the ballot bytes and is
+/// checked against the signer's public key before it is returned, as with [sign_ballot].
+pub fn sign_ballot_eip191(signer: &dyn BallotSigner, ballot: &str) -> Result<String> {
+    sign_ballot_as(signer, ballot, MessageFormat::Eip191)
+}
+
+/// Signs the digest `format` derives from `ballot` and returns the Base64 `r || s` signature,
+/// checked as with [sign_ballot].
+///
+/// `ballot` must already be the text the format signs (see [MessageFormat::signed_text]).
+pub fn sign_ballot_as(
+    signer: &dyn BallotSigner,
+    ballot: &str,
+    format: MessageFormat,
+) -> Result<String> {
+    let public_key = signer.public_key();
+    let signature = match format.digest(ballot.as_bytes()) {
+        SignedDigest::Sha256(message_hash) => {
+            let signature = signer.sign(&message_hash).context("signing ballot")?;
+            public_key.verify(&message_hash, &signature).map(|()| signature)
+        }
+        SignedDigest::Prehash(digest) => {
+            let signature = signer.sign_prehash(&digest).context("signing ballot")?;
+            public_key.verify_prehash(&digest, &signature).map(|()| signature)
+        }
+    }
+    .context("signer produced a signature that does not verify against its public key")?;
+    Ok(general_purpose::STANDARD.encode(signature.to_bytes()))
+}
+
+/// Parses a wallet signature: hex `r || s || v` (65 bytes, optionally `0x`-prefixed) as returned
+/// by `personal_sign`, or a bare 64-byte `r || s`.
+///
+/// The recovery byte is dropped since the guest checks against a known key rather than recovering
+/// one. High-`s` signatures are normalized so they verify with `k256`.
+pub fn parse_wallet_signature(signature: &str) -> Result<Signature> {
+    let bytes = hex::decode(signature.trim().trim_start_matches("0x"))
+        .context("wallet signature is not hex encoded")?;
+    let rs = match bytes.len() {
+        64 | 65 => &bytes[..64],
+        len => bail!("wallet signature must be 64 or 65 bytes, got {len}"),
+    };
+    let signature = Signature::from_slice(rs).context("invalid wallet signature")?;
+    Ok(signature.normalize_s().unwrap_or(signature))
+}
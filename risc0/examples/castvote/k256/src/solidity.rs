@@ -0,0 +1,340 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity for contracts that read single-ballot journals, generated by `gen-solidity`.
+//!
+//! A journal is not an ABI-encoded struct: it is ABI `bytes` holding the version byte and the
+//! bincode-encoded [RevealInfo]. [REVEAL_LAYOUT] describes that encoding field by field, and both
+//! the generated Solidity decoder and [read_reveal_fields] follow it, so the tests that hold
+//! [read_reveal_fields] to real journals also hold the contract to them.
+
+use std::fmt::Write as _;
+
+use alloy_sol_types::SolValue;
+use anyhow::{bail, Context, Result};
+use castvote_core::{CBOR_VERSION_FLAG, JOURNAL_VERSION};
+
+#[cfg(doc)]
+use castvote_core::RevealInfo;
+
+/// How bincode encodes one [RevealInfo] field, which fixes the Solidity type it decodes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// One byte, 0 or 1: `bool`.
+    Bool,
+    /// Four bytes, little-endian: `uint32`. Also how a unit-only enum's variant index is encoded.
+    U32,
+    /// Eight bytes, little-endian: `uint64`.
+    U64,
+    /// An eight-byte little-endian length and that many UTF-8 bytes: `string`.
+    String,
+    /// An eight-byte little-endian count and that many strings: `string[]`.
+    StringList,
+    /// Two `U32`s, the inclusive minimum and maximum: the library's `AgeRange` struct.
+    AgeRange,
+}
+
+impl FieldType {
+    /// The Solidity type the field decodes to.
+    pub fn solidity_type(self) -> &'static str {
+        match self {
+            FieldType::Bool => "bool",
+            FieldType::U32 => "uint32",
+            FieldType::U64 => "uint64",
+            FieldType::String => "string",
+            FieldType::StringList => "string[]",
+            FieldType::AgeRange => "AgeRange",
+        }
+    }
+
+    fn reader(self) -> &'static str {
+        match self {
+            FieldType::Bool => "readBool",
+            FieldType::U32 => "readU32",
+            FieldType::U64 => "readU64",
+            FieldType::String => "readString",
+            FieldType::StringList => "readStrings",
+            FieldType::AgeRange => "readAgeRange",
+        }
+    }
+}
+
+/// One [RevealInfo] field as the generated Solidity sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalField {
+    /// Member name in the Solidity struct.
+    pub name: &'static str,
+    pub ty: FieldType,
+    /// Encoded as an `Option`: a tag byte, 1 if the value follows. The struct gets a `has<Name>`
+    /// member next to the value, which is left unset when the tag is 0.
+    pub optional: bool,
+    /// What the value means, for the generated comment.
+    pub doc: &'static str,
+}
+
+const fn field(name: &'static str, ty: FieldType, doc: &'static str) -> JournalField {
+    JournalField { name, ty, optional: false, doc }
+}
+
+const fn optional(name: &'static str, ty: FieldType, doc: &'static str) -> JournalField {
+    JournalField { name, ty, optional: true, doc }
+}
+
+/// The fields of a bincode journal of version [JOURNAL_VERSION], in encoding order.
+pub const REVEAL_LAYOUT: &[JournalField] = &[
+    field("nullifier", FieldType::String, "Hex nullifier, unique per voter and poll."),
+    field("age", FieldType::U32, "The voter's age."),
+    field("isStudent", FieldType::Bool, "Whether the voter is a student."),
+    field("pollId", FieldType::U64, "The poll voted in."),
+    optional("ageAsOf", FieldType::String, "`YYYY-MM-DD` date the age was derived at."),
+    field("ballotType", FieldType::U32, "0 = single, 1 = approval, 2 = ranked."),
+    field("selection", FieldType::StringList, "The options voted for, in ballot order."),
+    field("voterKeyHash", FieldType::String, "Hex SHA-256 of the voter's public key."),
+    optional("ageRange", FieldType::AgeRange, "The age bounds the guest enforced."),
+    optional("electionId", FieldType::String, "The election the nullifier is scoped to."),
+    optional("pollHash", FieldType::String, "Hex hash of the poll definition."),
+    optional("messageFormat", FieldType::U32, "0 = sha256, 1 = eip191, 2 = raw, 3 = eip712."),
+    optional("studentRequired", FieldType::Bool, "Whether the guest required a student."),
+    optional("policyHash", FieldType::String, "Hex hash of the eligibility policy."),
+    optional("pollName", FieldType::String, "The string the poll ID was hashed from."),
+    optional("receiptId", FieldType::String, "The voter-chosen receipt ID."),
+    optional("idCommitment", FieldType::String, "Hex salted commitment to the voter ID."),
+    optional("maxPollId", FieldType::U64, "The upper bound the guest enforced on the poll ID."),
+    optional("authorKeyHash", FieldType::String, "Hex SHA-256 of the proving service's key."),
+    optional("abstained", FieldType::Bool, "Whether the voter explicitly abstained."),
+];
+
+/// The generated library, with `@VERSION@`, `@MEMBERS@` and `@READS@` filled in by
+/// [generate_solidity]. The helpers at the end read one [FieldType] each from `data` at `p`.
+const TEMPLATE: &str = r#"// SPDX-License-Identifier: Apache-2.0
+// Generated by `checkvote gen-solidity` for journal version @VERSION@. Do not edit: regenerate it
+// whenever the journal version changes.
+pragma solidity ^0.8.20;
+
+/// Decodes the journal of a single-ballot castvote proof: ABI `bytes` holding a version byte and
+/// the bincode-encoded reveal data. Only bincode journals of version @VERSION@ are accepted; decode
+/// CBOR journals off-chain.
+library CastvoteJournal {
+    uint8 internal constant VERSION = @VERSION@;
+
+    struct AgeRange {
+        uint32 min;
+        uint32 max;
+    }
+
+    struct RevealInfo {
+@MEMBERS@    }
+
+    function decode(bytes calldata journal) internal pure returns (RevealInfo memory r) {
+        bytes memory data = abi.decode(journal, (bytes));
+        require(data.length > 0, "CastvoteJournal: empty");
+        require(uint8(data[0]) == VERSION, "CastvoteJournal: unsupported version");
+        uint256 p = 1;
+@READS@        require(p == data.length, "CastvoteJournal: trailing bytes");
+    }
+
+    function readUint(bytes memory data, uint256 p, uint256 size)
+        private
+        pure
+        returns (uint256 value, uint256)
+    {
+        require(p + size <= data.length, "CastvoteJournal: truncated");
+        for (uint256 i = 0; i < size; i++) {
+            value |= uint256(uint8(data[p + i])) << (8 * i);
+        }
+        return (value, p + size);
+    }
+
+    function readBool(bytes memory data, uint256 p) private pure returns (bool, uint256) {
+        (uint256 value, uint256 next) = readUint(data, p, 1);
+        require(value <= 1, "CastvoteJournal: invalid bool");
+        return (value == 1, next);
+    }
+
+    function readU32(bytes memory data, uint256 p) private pure returns (uint32, uint256) {
+        (uint256 value, uint256 next) = readUint(data, p, 4);
+        return (uint32(value), next);
+    }
+
+    function readU64(bytes memory data, uint256 p) private pure returns (uint64, uint256) {
+        (uint256 value, uint256 next) = readUint(data, p, 8);
+        return (uint64(value), next);
+    }
+
+    function readString(bytes memory data, uint256 p)
+        private
+        pure
+        returns (string memory, uint256)
+    {
+        (uint256 length, uint256 start) = readUint(data, p, 8);
+        require(start + length <= data.length, "CastvoteJournal: truncated");
+        bytes memory value = new bytes(length);
+        for (uint256 i = 0; i < length; i++) {
+            value[i] = data[start + i];
+        }
+        return (string(value), start + length);
+    }
+
+    function readStrings(bytes memory data, uint256 p)
+        private
+        pure
+        returns (string[] memory values, uint256 next)
+    {
+        uint256 count;
+        (count, next) = readUint(data, p, 8);
+        require(count <= data.length - next, "CastvoteJournal: truncated");
+        values = new string[](count);
+        for (uint256 i = 0; i < count; i++) {
+            (values[i], next) = readString(data, next);
+        }
+    }
+
+    function readAgeRange(bytes memory data, uint256 p)
+        private
+        pure
+        returns (AgeRange memory range, uint256 next)
+    {
+        (range.min, next) = readU32(data, p);
+        (range.max, next) = readU32(data, next);
+    }
+}
+"#;
+
+/// The `has<Name>` member that records whether an optional field is present.
+fn presence_member(name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|c| c.to_ascii_uppercase()).into_iter();
+    format!("has{}", first.chain(chars).collect::<String>())
+}
+
+/// Generates a Solidity library with a `RevealInfo` struct and a `decode` function for journals
+/// of version [JOURNAL_VERSION], both following [REVEAL_LAYOUT].
+pub fn generate_solidity() -> String {
+    let (mut members, mut reads) = (String::new(), String::new());
+    // Writing to a String cannot fail.
+    for field in REVEAL_LAYOUT {
+        let read = format!("(r.{}, p) = {}(data, p);", field.name, field.ty.reader());
+        if field.optional {
+            let has = presence_member(field.name);
+            let _ = writeln!(members, "        // Whether `{}` is present.", field.name);
+            let _ = writeln!(members, "        bool {has};");
+            let _ = writeln!(members, "        // {} Unset unless `{has}`.", field.doc);
+            let _ = writeln!(reads, "        (r.{has}, p) = readBool(data, p);");
+            let _ = writeln!(reads, "        if (r.{has}) {{\n            {read}\n        }}");
+        } else {
+            let _ = writeln!(members, "        // {}", field.doc);
+            let _ = writeln!(reads, "        {read}");
+        }
+        let _ = writeln!(members, "        {} {};", field.ty.solidity_type(), field.name);
+    }
+    TEMPLATE
+        .replace("@VERSION@", &JOURNAL_VERSION.to_string())
+        .replace("@MEMBERS@", &members)
+        .replace("@READS@", &reads)
+}
+
+/// A field value read by [read_reveal_fields].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// An optional field whose tag byte was 0.
+    Absent,
+    Bool(bool),
+    U32(u32),
+    U64(u64),
+    String(String),
+    StringList(Vec<String>),
+    AgeRange { min: u32, max: u32 },
+}
+
+/// A position in a bincode payload.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let Some(end) = end else {
+            bail!("truncated at byte {}", self.pos);
+        };
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn uint(&mut self, size: usize) -> Result<u64> {
+        let bytes = self.take(size)?;
+        Ok(bytes.iter().rev().fold(0, |value, &byte| value << 8 | u64::from(byte)))
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.uint(1)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => bail!("invalid bool {other} at byte {}", self.pos - 1),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = usize::try_from(self.uint(8)?)?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).context("string is not UTF-8")
+    }
+
+    fn value(&mut self, ty: FieldType) -> Result<FieldValue> {
+        Ok(match ty {
+            FieldType::Bool => FieldValue::Bool(self.bool()?),
+            FieldType::U32 => FieldValue::U32(self.uint(4)? as u32),
+            FieldType::U64 => FieldValue::U64(self.uint(8)?),
+            FieldType::String => FieldValue::String(self.string()?),
+            FieldType::StringList => {
+                let count = self.uint(8)?;
+                let list = (0..count).map(|_| self.string()).collect::<Result<_>>()?;
+                FieldValue::StringList(list)
+            }
+            FieldType::AgeRange => {
+                let (min, max) = (self.uint(4)? as u32, self.uint(4)? as u32);
+                FieldValue::AgeRange { min, max }
+            }
+        })
+    }
+}
+
+/// Reads a single-ballot journal field by field following [REVEAL_LAYOUT], exactly as the
+/// generated `decode` does, and fails where it would revert.
+pub fn read_reveal_fields(journal: &[u8]) -> Result<Vec<(&'static str, FieldValue)>> {
+    let data = Vec::<u8>::abi_decode(journal).context("journal is not ABI-encoded bytes")?;
+    match data.first() {
+        None => bail!("journal is empty"),
+        Some(&version) if version == JOURNAL_VERSION | CBOR_VERSION_FLAG => {
+            bail!("CBOR journals cannot be decoded on-chain")
+        }
+        Some(&version) if version != JOURNAL_VERSION => {
+            bail!("journal version {version} is not {JOURNAL_VERSION}")
+        }
+        Some(_) => {}
+    }
+    let mut cursor = Cursor { bytes: &data, pos: 1 };
+    let mut fields = Vec::with_capacity(REVEAL_LAYOUT.len());
+    for field in REVEAL_LAYOUT {
+        let present = !field.optional || cursor.bool()?;
+        let value = if present { cursor.value(field.ty)? } else { FieldValue::Absent };
+        fields.push((field.name, value));
+    }
+    if cursor.pos != data.len() {
+        bail!("{} trailing bytes after the last field", data.len() - cursor.pos);
+    }
+    Ok(fields)
+}
@@ -0,0 +1,94 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Exact resubmissions within a batch.
+//!
+//! The same signature over the same ballot fed twice is almost certainly an accident, not a
+//! second voter. It is caught here, by hashing each `(signature, message)` pair, before anything
+//! is proved; the nullifier check in the guest and the tally still catch every other double vote.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+};
+
+use sha2::{Digest as _, Sha256};
+
+/// A batch entry whose signature and message repeat an earlier entry byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSubmission {
+    /// The repeated entry, e.g. a file name or `line 7`.
+    pub entry: String,
+    /// The entry first submitted with the same signature and message.
+    pub first: String,
+}
+
+impl fmt::Display for DuplicateSubmission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} resubmits the signed ballot of {}", self.entry, self.first)
+    }
+}
+
+impl std::error::Error for DuplicateSubmission {}
+
+/// The `(signature, message)` pairs seen so far in one run.
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionTracker {
+    seen: HashMap<[u8; 32], String>,
+}
+
+impl SubmissionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `entry`, returning a [DuplicateSubmission] instead if an earlier entry had the
+    /// same `signature` over the same `message`.
+    pub fn check(
+        &mut self,
+        entry: impl Into<String>,
+        signature: &str,
+        message: &str,
+    ) -> Result<(), DuplicateSubmission> {
+        match self.seen.entry(submission_hash(signature, message)) {
+            Entry::Occupied(first) => {
+                Err(DuplicateSubmission { entry: entry.into(), first: first.get().clone() })
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(entry.into());
+                Ok(())
+            }
+        }
+    }
+
+    /// How many distinct submissions were recorded.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+/// SHA-256 over both lengths and both strings, so no two pairs share an encoding.
+fn submission_hash(signature: &str, message: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in [signature, message] {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().into()
+}
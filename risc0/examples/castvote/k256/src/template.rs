@@ -0,0 +1,139 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `template`: an example ballot with placeholder values, for new users to edit rather than
+//! guess the shape of.
+//!
+//! The fields are the ones [parse_ballot](castvote_core::parse_ballot) reads for the guest and
+//! [validate_ballot](crate::validate_ballot) checks, and every template is checked against both
+//! before it is printed, so a template that drifts from the schema fails rather than misleads.
+
+use anyhow::{Context, Result};
+use castvote_core::{parse_ballot, BallotType, PollIdentifier};
+use serde_json::{json, Value};
+
+use crate::ballot::validate_ballot;
+
+/// Placeholder voter ID: any number or non-empty string the registrar issued.
+pub const TEMPLATE_ID: u64 = 12345;
+
+/// Placeholder age of a ballot without a `birthdate`.
+pub const TEMPLATE_AGE: u32 = 30;
+
+/// Placeholder date of birth, with `--birthdate`.
+pub const TEMPLATE_BIRTHDATE: &str = "1990-01-01";
+
+/// What the template ballot is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateOptions {
+    /// Kind of poll: the template votes for the first option, or for approval and ranked polls
+    /// lists them.
+    pub ballot_type: BallotType,
+    /// The poll's options; the first is voted for.
+    pub options: Vec<String>,
+    /// Poll the ballot names in its `poll_id`, if any.
+    pub poll_id: Option<PollIdentifier>,
+    /// Give a `birthdate` instead of a static `age`.
+    pub birthdate: bool,
+}
+
+/// One field of a template ballot, with what it means.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateField {
+    pub name: &'static str,
+    pub value: Value,
+    pub comment: &'static str,
+}
+
+/// The fields of the template ballot for `options`, in the order they are printed.
+pub fn template_fields(options: &TemplateOptions) -> Vec<TemplateField> {
+    let field = |name, value, comment| TemplateField { name, value, comment };
+    let mut fields = vec![field(
+        "id",
+        json!(TEMPLATE_ID),
+        "Voter ID (number or non-empty string), hashed into the nullifier and never disclosed.",
+    )];
+    fields.push(if options.birthdate {
+        field(
+            "birthdate",
+            json!(TEMPLATE_BIRTHDATE),
+            "Date of birth (YYYY-MM-DD); the age is derived from it as of --as-of.",
+        )
+    } else {
+        field(
+            "age",
+            json!(TEMPLATE_AGE),
+            "Age in years; must be within --min-age and --max-age.",
+        )
+    });
+    fields.push(field(
+        "is_student",
+        json!(false),
+        "Whether the voter is a student; must be true with --require-student.",
+    ));
+    let first = options.options.first().cloned().unwrap_or_default();
+    fields.push(match options.ballot_type {
+        BallotType::Single => {
+            field("choice", json!(first), "The option voted for, one of --options.")
+        }
+        BallotType::Approval => field(
+            "choice",
+            json!([first]),
+            "Every option approved of, each one of --options and none twice.",
+        ),
+        BallotType::Ranked => field(
+            "choice",
+            json!(options.options),
+            "The options in order of preference, each one of --options and none twice.",
+        ),
+    });
+    if let Some(poll_id) = &options.poll_id {
+        let value = match poll_id {
+            PollIdentifier::Numeric(poll_id) => json!(poll_id),
+            PollIdentifier::Named(name) => json!(name),
+        };
+        fields.push(field(
+            "poll_id",
+            value,
+            "The poll the ballot is cast in; it must match --poll-id.",
+        ));
+    }
+    fields
+}
+
+/// The template ballot for `options` as pretty-printed JSON, with each field explained in a `//`
+/// comment above it if `annotated`. Annotated output is not plain JSON: strip the comments before
+/// proving it.
+///
+/// Fails if the template does not pass [validate_ballot] or [parse_ballot], e.g. for an empty
+/// `--options`.
+pub fn render_template(options: &TemplateOptions, annotated: bool) -> Result<String> {
+    let fields = template_fields(options);
+    let render = |annotated: bool| {
+        let mut lines = Vec::with_capacity(fields.len() * 2);
+        for (i, field) in fields.iter().enumerate() {
+            if annotated {
+                lines.push(format!("  // {}", field.comment));
+            }
+            let comma = if i + 1 < fields.len() { "," } else { "" };
+            lines.push(format!("  {}: {}{comma}", json!(field.name), field.value));
+        }
+        format!("{{\n{}\n}}", lines.join("\n"))
+    };
+    let plain = render(false);
+    validate_ballot(&plain).context("the ballot template does not match the schema")?;
+    parse_ballot(&plain).context("the guest would not read the ballot template")?;
+    Ok(if annotated { render(true) } else { plain })
+}
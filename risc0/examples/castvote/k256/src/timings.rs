@@ -0,0 +1,183 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Wall-clock time spent in each phase of a proof, for capacity planning.
+//!
+//! Phases are measured with [timed] wherever they run, from key import to verifying the receipt,
+//! and collected with [take_timings] once the proof is written. Ballots are proven one at a time,
+//! so a single record for the whole process is enough.
+
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::output::write_json_file;
+
+/// File the timings of a proof are written to in its output directory.
+pub const TIMINGS_FILE: &str = "timings.json";
+
+/// The percentiles reported for each phase of a run of several proofs.
+pub const PERCENTILES: [u32; 3] = [50, 90, 99];
+
+/// A timed step of proving a ballot.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Reading and decoding the voter's private key.
+    KeyImport,
+    /// Signing the ballots.
+    Signing,
+    /// Running the guest in the executor, without proving.
+    Execution,
+    /// Proving, which executes the guest again, and compressing to Groth16.
+    Proving,
+    /// Verifying the receipt against the image ID.
+    Verification,
+}
+
+impl Phase {
+    /// The phase's key in [Timings] JSON.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::KeyImport => "key_import",
+            Phase::Signing => "signing",
+            Phase::Execution => "execution",
+            Phase::Proving => "proving",
+            Phase::Verification => "verification",
+        }
+    }
+}
+
+/// Seconds spent in each phase of one proof. Phases that did not run, such as proving for a
+/// receipt taken from the cache, are left out.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Timings(pub BTreeMap<Phase, f64>);
+
+impl Timings {
+    /// Adds `elapsed` to the time spent in `phase`.
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        *self.0.entry(phase).or_default() += elapsed.as_secs_f64();
+    }
+
+    /// Seconds spent in `phase`, if it ran.
+    pub fn get(&self, phase: Phase) -> Option<f64> {
+        self.0.get(&phase).copied()
+    }
+
+    /// Seconds spent in every phase together.
+    pub fn total(&self) -> f64 {
+        self.0.values().sum()
+    }
+}
+
+static CURRENT: Mutex<Timings> = Mutex::new(Timings(BTreeMap::new()));
+
+/// Runs `f`, adding the time it takes to `phase` of the proof in progress.
+pub fn timed<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+    let start = Instant::now();
+    let result = f();
+    CURRENT.lock().expect("timings poisoned").add(phase, start.elapsed());
+    result
+}
+
+/// Returns the timings recorded since the last call, and starts a new record.
+pub fn take_timings() -> Timings {
+    std::mem::take(&mut *CURRENT.lock().expect("timings poisoned"))
+}
+
+/// Writes `timings` to [TIMINGS_FILE] in `out_dir`.
+pub fn write_timings(out_dir: &Path, timings: &Timings) -> Result<()> {
+    write_json_file(out_dir.join(TIMINGS_FILE), timings)
+}
+
+/// Reads the [TIMINGS_FILE] in `out_dir`, if there is one.
+pub fn read_timings(out_dir: &Path) -> Result<Option<Timings>> {
+    let path = out_dir.join(TIMINGS_FILE);
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .with_context(|| format!("decoding {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// The distribution of one phase over the proofs of a run, in seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PhaseStats {
+    /// Number of proofs the phase ran for.
+    pub count: usize,
+    pub min: f64,
+    pub mean: f64,
+    /// Nearest-rank percentiles, keyed `p50`, `p90` and so on, see [PERCENTILES].
+    pub percentiles: BTreeMap<String, f64>,
+    pub max: f64,
+}
+
+impl PhaseStats {
+    /// The distribution of `samples`, or `None` if there are none.
+    pub fn of(samples: &[f64]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let percentiles =
+            PERCENTILES.iter().map(|&p| (format!("p{p}"), percentile(&sorted, p))).collect();
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+        Some(Self { count: sorted.len(), min, mean, percentiles, max })
+    }
+}
+
+/// The nearest-rank `p`th percentile of the non-empty, ascending `sorted`.
+pub fn percentile(sorted: &[f64], p: u32) -> f64 {
+    let rank = (p as usize * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// The timings of a run of one proof per ballot, written to [TIMINGS_FILE] in the run's output
+/// directory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunTimings {
+    /// Phases run once for the whole run rather than per proof: key import and signing.
+    pub setup: Timings,
+    /// Each ballot's timings, keyed by the name of its output subdirectory.
+    pub ballots: BTreeMap<String, Timings>,
+    /// Every phase's distribution over [RunTimings::ballots], plus `total` for whole proofs.
+    pub phases: BTreeMap<String, PhaseStats>,
+}
+
+impl RunTimings {
+    /// Aggregates the `ballots` timings of a run whose once-off phases took `setup`.
+    pub fn new(setup: Timings, ballots: BTreeMap<String, Timings>) -> Self {
+        let mut samples: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for timings in ballots.values() {
+            for (phase, &secs) in &timings.0 {
+                samples.entry(phase.name().into()).or_default().push(secs);
+            }
+            samples.entry("total".into()).or_default().push(timings.total());
+        }
+        let phases = samples
+            .into_iter()
+            .filter_map(|(name, samples)| Some((name, PhaseStats::of(&samples)?)))
+            .collect();
+        Self { setup, ballots, phases }
+    }
+}
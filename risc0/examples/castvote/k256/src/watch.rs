@@ -0,0 +1,169 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! A running tally of a proofs directory, updated as new journals appear.
+//!
+//! The directory is rescanned rather than tallied once, so results are available while the
+//! election is still running. What has been counted is saved to a state file after every change,
+//! so a restarted watcher picks up where it stopped instead of counting journals twice.
+//!
+//! Rescans are polled at a fixed interval rather than driven by filesystem notifications. Proof
+//! directories are often shared or container-mounted volumes, where inotify-style events are not
+//! delivered for files written by another host, so a watcher relying on them would need a polling
+//! fallback anyway; and a scan only lists directories and reads the new proofs, which is cheap
+//! next to proving one. Every proof is verified, as `audit` verifies it, before it is counted.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit::{find_proof_dirs, verify_proof_dir},
+    journal::MethodJournal,
+    output::write_json_file,
+    report::counted_choices,
+};
+
+/// Name of the state file kept in the watched directory unless `--state` says otherwise.
+pub const STATE_FILE: &str = "watch-tally.json";
+
+/// What the watcher has counted so far; saved to the state file after every change.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TallyState {
+    /// Vote counts per poll ID and choice.
+    pub counts: BTreeMap<u64, BTreeMap<String, u64>>,
+    /// Explicit abstentions per poll ID, kept apart from the vote counts.
+    #[serde(default)]
+    pub abstentions: BTreeMap<u64, u64>,
+    /// The `(poll_id, nullifier)` pairs already counted; a later journal reusing one is a
+    /// duplicate.
+    pub seen: BTreeSet<(u64, String)>,
+    /// Journal files already tallied, relative to the watched directory.
+    pub journals: BTreeSet<String>,
+    /// Reveal entries left out for reusing a counted nullifier.
+    pub duplicates: u64,
+}
+
+/// What one scan of the directory changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOutcome {
+    /// Journals tallied in this scan.
+    pub tallied: usize,
+    /// Reveal entries of those journals left out as duplicates.
+    pub duplicates: usize,
+    /// Journals that could not be verified or decoded yet, with the reason, reported the first
+    /// time they fail and again whenever the reason changes.
+    pub pending: Vec<(String, String)>,
+}
+
+/// Watches one proofs directory: every `journal.dat` in it, at any depth, is tallied once its
+/// `seal.dat` verifies against it and `image_id.dat`.
+#[derive(Debug)]
+pub struct TallyWatcher {
+    dir: PathBuf,
+    state_path: PathBuf,
+    state: TallyState,
+    /// Journals that failed to verify or decode, with the last reason, retried on every scan.
+    pending: BTreeMap<String, String>,
+}
+
+impl TallyWatcher {
+    /// Watches `dir`, resuming from the state saved at `state_path` if there is one.
+    pub fn open(dir: &Path, state_path: &Path) -> Result<Self> {
+        let state = match fs::read_to_string(state_path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("reading the tally state {}", state_path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TallyState::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("reading {}", state_path.display()))
+            }
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            state_path: state_path.to_path_buf(),
+            state,
+            pending: BTreeMap::new(),
+        })
+    }
+
+    /// The tally so far.
+    pub fn state(&self) -> &TallyState {
+        &self.state
+    }
+
+    /// Tallies every journal that appeared since the last scan, in sorted path order, and saves
+    /// the state if anything was counted.
+    ///
+    /// Each proof is checked with [verify_proof_dir]. One that does not verify or decode may
+    /// still be being written, so it is not counted and is checked again on the next scan; a
+    /// forged seal stays pending for good.
+    pub fn scan(&mut self) -> Result<ScanOutcome> {
+        let mut proof_dirs = Vec::new();
+        find_proof_dirs(&self.dir, &mut proof_dirs)?;
+        proof_dirs.sort();
+
+        let mut outcome = ScanOutcome::default();
+        for proof_dir in proof_dirs {
+            let path = proof_dir.join("journal.dat");
+            let name = path.strip_prefix(&self.dir).unwrap_or(&path).display().to_string();
+            if self.state.journals.contains(&name) {
+                continue;
+            }
+            match verify_proof_dir(&proof_dir) {
+                Ok(journal) => {
+                    self.pending.remove(&name);
+                    // An age-only proof casts no vote, so there is nothing in it to tally.
+                    let MethodJournal::Reveals(reveals) = journal else {
+                        self.state.journals.insert(name);
+                        continue;
+                    };
+                    for reveal in reveals {
+                        if !self.state.seen.insert((reveal.poll_id, reveal.nullifier.clone())) {
+                            self.state.duplicates += 1;
+                            outcome.duplicates += 1;
+                            continue;
+                        }
+                        if reveal.abstained == Some(true) {
+                            *self.state.abstentions.entry(reveal.poll_id).or_default() += 1;
+                            continue;
+                        }
+                        let counts = self.state.counts.entry(reveal.poll_id).or_default();
+                        for choice in counted_choices(&reveal) {
+                            *counts.entry(choice.clone()).or_default() += 1;
+                        }
+                    }
+                    self.state.journals.insert(name);
+                    outcome.tallied += 1;
+                }
+                Err(e) => {
+                    let reason = format!("{e:#}");
+                    if self.pending.get(&name) != Some(&reason) {
+                        outcome.pending.push((name.clone(), reason.clone()));
+                        self.pending.insert(name, reason);
+                    }
+                }
+            }
+        }
+        if outcome.tallied > 0 {
+            write_json_file(&self.state_path, &self.state)?;
+        }
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Explicit abstentions: a `null` or `"abstain"` choice commits an empty selection with the
+//! `abstained` flag, still spends the nullifier, and is tallied apart from the votes.
+
+use castvote_core::{parse_ballot, BallotError, RevealInfo, VoteParams};
+use k256_example::{report::Report, validate_ballot};
+use serde_json::{json, Value};
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], ..VoteParams::default() }
+}
+
+fn ballot(choice: Value) -> String {
+    json!({"id": 7, "age": 30, "is_student": false, "choice": choice}).to_string()
+}
+
+fn reveal(ballot: &str) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(ballot).unwrap(), 1001, &[], &params())
+}
+
+#[test]
+fn a_normal_choice_is_not_an_abstention() {
+    let reveal = reveal(&ballot(json!("yes"))).unwrap();
+    assert_eq!(reveal.selection, ["yes"]);
+    assert_eq!(reveal.abstained, Some(false));
+}
+
+#[test]
+fn null_and_abstain_commit_an_abstention_under_the_same_nullifier() {
+    let voted = reveal(&ballot(json!("yes"))).unwrap();
+    for choice in [Value::Null, json!("abstain")] {
+        let abstained = reveal(&ballot(choice.clone())).unwrap();
+        assert_eq!(abstained.abstained, Some(true), "{choice}");
+        assert!(abstained.selection.is_empty(), "{choice}");
+        assert_eq!(abstained.nullifier, voted.nullifier, "{choice}");
+        assert_eq!(RevealInfo::decode(&abstained.encode()).unwrap(), abstained);
+    }
+}
+
+#[test]
+fn a_ballot_without_a_choice_does_not_abstain() {
+    let ballot = r#"{"id": 7, "age": 30, "is_student": false}"#;
+    let reveal = reveal(ballot).unwrap();
+    assert!(reveal.selection.is_empty());
+    assert_eq!(reveal.abstained, Some(false));
+}
+
+#[test]
+fn a_spoiled_choice_is_rejected_rather_than_abstaining() {
+    assert_eq!(reveal(&ballot(json!("maybe"))), Err(BallotError::UnknownOption("maybe".into())));
+    assert!(reveal(&ballot(json!(["yes"]))).is_err());
+}
+
+#[test]
+fn abstentions_are_per_poll() {
+    let ballot = r#"{"id": 7, "age": 30, "is_student": false, "choices": {"1": "yes", "2": null}}"#;
+    let reveals = RevealInfo::for_polls(&parse_ballot(ballot).unwrap(), &[1, 2], &[], &params());
+    let reveals = reveals.unwrap();
+    assert_eq!(reveals[0].abstained, Some(false));
+    assert_eq!(reveals[1].abstained, Some(true));
+    assert!(reveals[1].selection.is_empty());
+}
+
+#[test]
+fn the_schema_accepts_a_null_choice() {
+    validate_ballot(&ballot(Value::Null)).unwrap();
+    let multi = r#"{"id": 7, "age": 30, "is_student": false, "choices": {"1": null}}"#;
+    validate_ballot(multi).unwrap();
+    assert!(validate_ballot(&ballot(json!(""))).is_err());
+}
+
+#[test]
+fn the_tally_counts_abstentions_apart_from_votes() {
+    let mut report = Report::new();
+    report.add("yes.dat", reveal(&ballot(json!("yes"))).unwrap());
+    let other = json!({"id": 8, "age": 30, "is_student": false, "choice": null}).to_string();
+    report.add("abstain.dat", reveal(&other).unwrap());
+    report.add("again.dat", reveal(&other).unwrap());
+
+    assert_eq!(report.counts().len(), 1);
+    assert_eq!(report.abstentions().get(&(None, 1001)), Some(&1));
+    assert_eq!(report.double_votes(), 1);
+    assert!(report.demographics()[&(None, 1001)].ages.values().eq([&1]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("results.csv");
+    report.write_counts_csv(&out).unwrap();
+    let csv = std::fs::read_to_string(out).unwrap();
+    assert_eq!(csv, "election_id,poll_id,choice,count\n,1001,abstain,1\n,1001,yes,1\n");
+}
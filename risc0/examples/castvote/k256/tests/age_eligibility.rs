@@ -0,0 +1,171 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The signature-free age-eligibility method behind `--scheme age-only`.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    canonicalize_ballot, parse_ballot, AgeEligibility, Date, JournalError, MessageFormat,
+    RevealInfo, VoteParams, JOURNAL_VERSION,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Scheme},
+    decode_eligibility_journal,
+    estimate::estimate_vote,
+    input::GuestInput,
+    sign, VoterKey,
+};
+use k256_methods::{AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID, K256_VERIFY_ELF, K256_VERIFY_ID};
+use risc0_zkvm::{default_executor, ExecutorEnv};
+use sha2::{Digest, Sha256};
+
+const POLL_ID: u64 = 1001;
+
+fn ballot(age: u32) -> String {
+    let ballot = format!(r#"{{"id":123,"name":"Alice","age":{age},"is_student":false}}"#);
+    canonicalize_ballot(&ballot).unwrap()
+}
+
+#[test]
+fn eligibility_is_age_at_least_threshold() {
+    let params = VoteParams::default();
+    for (age, eligible) in [(17, false), (18, true), (90, true)] {
+        let parsed = parse_ballot(&ballot(age)).unwrap();
+        let eligibility = AgeEligibility::new(&parsed, &ballot(age), POLL_ID, 18, &params).unwrap();
+        assert_eq!(eligibility.is_eligible, eligible, "age {age}");
+        assert_eq!((eligibility.poll_id, eligibility.threshold), (POLL_ID, 18));
+    }
+}
+
+#[test]
+fn nullifier_matches_the_signing_methods() {
+    let params = VoteParams::default();
+    let parsed = parse_ballot(&ballot(30)).unwrap();
+    let eligibility = AgeEligibility::new(&parsed, &ballot(30), POLL_ID, 18, &params).unwrap();
+    let reveal = RevealInfo::new(&parsed, POLL_ID, &[2; 33], &params).unwrap();
+    assert_eq!(eligibility.nullifier, reveal.nullifier);
+}
+
+#[test]
+fn journal_roundtrips_and_rejects_older_versions() {
+    let parsed = parse_ballot(&ballot(30)).unwrap();
+    let params = VoteParams::default();
+    let eligibility = AgeEligibility::new(&parsed, &ballot(30), POLL_ID, 18, &params).unwrap();
+    let encoded = eligibility.encode();
+    assert_eq!(encoded[0], JOURNAL_VERSION);
+    assert_eq!(AgeEligibility::decode(&encoded).unwrap(), eligibility);
+    assert_eq!(decode_eligibility_journal(&encoded.abi_encode()).unwrap(), eligibility);
+
+    let unbound = AgeEligibility {
+        message_format: None,
+        message_digest: None,
+        age_as_of: None,
+        ..eligibility.clone()
+    };
+    // Versions 4 to 13 committed the first four fields only, so their three `None` bytes go.
+    let mut older = unbound.encode();
+    older.truncate(older.len() - 3);
+    for version in [4, 13] {
+        older[0] = version;
+        assert_eq!(AgeEligibility::decode(&older).unwrap(), unbound, "version {version}");
+    }
+
+    let mut older = encoded;
+    // Eligibility journals were introduced in version 4.
+    older[0] = 3;
+    assert!(matches!(
+        AgeEligibility::decode(&older),
+        Err(JournalError::UnsupportedVersion(3))
+    ));
+}
+
+#[test]
+fn journal_binds_the_signed_text() {
+    let params = VoteParams::default();
+    let parsed = parse_ballot(&ballot(30)).unwrap();
+    let eligibility = AgeEligibility::new(&parsed, &ballot(30), POLL_ID, 18, &params).unwrap();
+    assert_eq!(eligibility.message_format, Some(MessageFormat::Sha256));
+    let digest = hex::encode(Sha256::digest(ballot(30)));
+    assert_eq!(eligibility.message_digest, Some(digest));
+    assert_eq!(eligibility.age_as_of, None);
+
+    // Another ballot of the same voter and age proves the same predicate under another digest.
+    let other = ballot(30).replace("Alice", "Mallory");
+    let swapped = AgeEligibility::new(&parse_ballot(&other).unwrap(), &other, POLL_ID, 18, &params)
+        .unwrap();
+    assert_eq!(swapped.nullifier, eligibility.nullifier);
+    assert_ne!(swapped.message_digest, eligibility.message_digest);
+
+    let as_of: Date = "2025-01-01".parse().unwrap();
+    let params = VoteParams { as_of: Some(as_of), ..VoteParams::default() };
+    let born = canonicalize_ballot(r#"{"id":123,"birthdate":"1990-06-01","is_student":false}"#)
+        .unwrap();
+    let parsed = parse_ballot(&born).unwrap();
+    let eligibility = AgeEligibility::new(&parsed, &born, POLL_ID, 18, &params).unwrap();
+    assert_eq!(eligibility.age_as_of, Some(as_of));
+}
+
+#[test]
+fn age_only_scheme_selects_the_method() {
+    let argv = ["checkvote", "res/person.json", "--poll-id", "1001", "--scheme", "age-only"];
+    let args = Cli::try_parse_from(argv).unwrap().prove.unwrap();
+    assert_eq!(args.scheme, Scheme::AgeOnly);
+    assert_eq!(args.method(), (AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID));
+
+    let input = GuestInput::from((ballot(30), POLL_ID, 18));
+    assert!(matches!(input, GuestInput::AgeEligibility(_)));
+}
+
+#[test]
+fn guest_commits_eligibility_without_a_signature() {
+    let params = VoteParams::default();
+    for (age, eligible) in [(16, false), (30, true)] {
+        let env = ExecutorEnv::builder()
+            .write(&(ballot(age), POLL_ID, 18u32))
+            .unwrap()
+            .write(&params)
+            .unwrap()
+            .build()
+            .unwrap();
+        let session = default_executor().execute(env, AGE_ELIGIBILITY_ELF).unwrap();
+        let eligibility = decode_eligibility_journal(&session.journal.bytes).unwrap();
+        assert_eq!(eligibility.is_eligible, eligible, "age {age}");
+        let digest = hex::encode(Sha256::digest(ballot(age)));
+        assert_eq!(eligibility.message_digest, Some(digest), "age {age}");
+    }
+}
+
+#[test]
+fn age_only_method_uses_far_fewer_cycles() {
+    let params = VoteParams::default();
+    let key = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap());
+    let signature = sign(&key, &ballot(30), MessageFormat::Sha256).unwrap();
+    let full_input = (signature, ballot(30), POLL_ID, key.public_key_base64());
+    let full = estimate_vote(&full_input, &params, K256_VERIFY_ELF, K256_VERIFY_ID).unwrap();
+
+    let age_input = (ballot(30), POLL_ID, 18u32);
+    let age_only = estimate_vote(&age_input, &params, AGE_ELIGIBILITY_ELF, AGE_ELIGIBILITY_ID)
+        .unwrap();
+
+    // Signature verification dominates the full method; without it at least half the cycles go.
+    assert!(
+        age_only.user_cycles * 2 < full.user_cycles,
+        "age-only {} cycles, full {} cycles",
+        age_only.user_cycles,
+        full.user_cycles
+    );
+}
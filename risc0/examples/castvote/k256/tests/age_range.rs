@@ -0,0 +1,111 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The plausible age range the guest enforces and commits.
+#![cfg(feature = "prove")]
+
+use castvote_core::{encode_reveals, parse_ballot, AgeRange, BallotError, RevealInfo, VoteParams};
+use k256_example::prepare_ballot;
+
+mod common;
+
+fn ballot(age: u32) -> String {
+    format!(r#"{{"id": 1, "age": {age}, "is_student": false}}"#)
+}
+
+fn reveal(age: u32, params: &VoteParams) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(&ballot(age)).unwrap(), 1001, &[], params)
+}
+
+#[test]
+fn lower_bound_is_inclusive() {
+    let params = VoteParams::default();
+    assert_eq!(reveal(18, &params).unwrap().age, 18);
+    assert_eq!(
+        reveal(17, &params),
+        Err(BallotError::AgeOutOfRange { age: 17, range: AgeRange { min: 18, max: 120 } })
+    );
+}
+
+#[test]
+fn upper_bound_is_inclusive() {
+    let params = VoteParams::default();
+    assert_eq!(reveal(120, &params).unwrap().age, 120);
+    assert!(matches!(reveal(121, &params), Err(BallotError::AgeOutOfRange { age: 121, .. })));
+}
+
+#[test]
+fn custom_range_is_committed() {
+    let params = VoteParams { age_range: AgeRange { min: 16, max: 99 }, ..Default::default() };
+    let committed = reveal(16, &params).unwrap();
+    assert_eq!(committed.age_range, Some(AgeRange { min: 16, max: 99 }));
+    assert_eq!(RevealInfo::decode(&committed.encode()).unwrap(), committed);
+    assert!(reveal(100, &params).is_err());
+}
+
+#[test]
+fn host_rejects_out_of_range_ballots_before_signing() {
+    let err = prepare_ballot(&ballot(200), &VoteParams::default()).unwrap_err();
+    assert!(format!("{err:#}").contains("outside the accepted range 18..=120"), "{err:#}");
+    assert!(prepare_ballot(&ballot(40), &VoteParams::default()).is_ok());
+}
+
+/// Version 1 layout: [RevealInfo] without the committed age range.
+#[derive(serde::Serialize)]
+struct RevealV1<'a> {
+    nullifier: &'a str,
+    age: u32,
+    is_student: bool,
+    poll_id: u64,
+    age_as_of: Option<castvote_core::Date>,
+    ballot_type: castvote_core::BallotType,
+    selection: Vec<String>,
+    voter_key_hash: &'a str,
+}
+
+#[test]
+fn version_1_journals_decode_without_a_range() {
+    let v1 = RevealV1 {
+        nullifier: "00",
+        age: 200,
+        is_student: false,
+        poll_id: 1001,
+        age_as_of: None,
+        ballot_type: Default::default(),
+        selection: Vec::new(),
+        voter_key_hash: "11",
+    };
+    let mut encoded = vec![1];
+    bincode::serialize_into(&mut encoded, &v1).unwrap();
+    let decoded = RevealInfo::decode(&encoded).unwrap();
+    assert_eq!((decoded.age, decoded.age_range), (200, None));
+
+    let mut batch = vec![1];
+    bincode::serialize_into(&mut batch, [&v1].as_slice()).unwrap();
+    let reveals = castvote_core::decode_reveals(&batch).unwrap();
+    assert_eq!(reveals, [decoded]);
+    assert_eq!(encode_reveals(&reveals)[0], castvote_core::JOURNAL_VERSION);
+}
+
+#[test]
+fn guest_commits_the_enforced_bounds() {
+    let committed = common::execute(&ballot(45), &VoteParams::default()).unwrap();
+    assert_eq!(committed.age_range, Some(AgeRange::default()));
+}
+
+#[test]
+fn guest_aborts_on_an_implausible_age() {
+    assert!(common::execute(&ballot(200), &VoteParams::default()).is_err());
+}
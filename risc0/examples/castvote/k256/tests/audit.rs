@@ -0,0 +1,164 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Audits of the proof directories in `testdata/audit`, sealed as dev-mode receipts: three good
+//! vote proofs, an age-only proof, a CBOR encrypted ballot, a repeated nullifier, a tampered
+//! journal, a missing seal and a seal for another image.
+
+use std::{fs, path::PathBuf};
+
+use k256_example::{
+    audit::{self, verify_proof_dir, Audit, ProofStatus},
+    read_journal_file,
+    seal::FAKE_SELECTOR,
+    MethodJournal,
+};
+use k256_methods::{AGE_ELIGIBILITY_ID, K256_VERIFY_ENCRYPTED_ID};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    ReceiptClaim,
+};
+
+fn proofs_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/audit/proofs")
+}
+
+fn fixture_audit() -> Audit {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    audit::audit_dir(&proofs_dir()).unwrap()
+}
+
+#[test]
+fn audit_classifies_every_proof() {
+    let audit = fixture_audit();
+    let statuses: Vec<_> =
+        audit.proofs().iter().map(|p| (p.proof.as_str(), p.status.name())).collect();
+    assert_eq!(
+        statuses,
+        [
+            ("01-alice", "valid"),
+            ("02-bob", "valid"),
+            ("03-alice-again", "duplicate"),
+            ("04-tampered-journal", "invalid"),
+            ("05-missing-seal", "invalid"),
+            ("06-wrong-image-id", "invalid"),
+            ("08-frank-age-only", "valid"),
+            ("09-grace-encrypted-cbor", "valid"),
+            ("precinct-2/07-erin", "valid"),
+        ]
+    );
+    assert_eq!((audit.valid(), audit.invalid(), audit.duplicates()), (5, 3, 1));
+}
+
+#[test]
+fn age_only_and_cbor_encrypted_proofs_decode() {
+    let audit = fixture_audit();
+    let age_only = &audit.proofs()[6];
+    let eligibility = age_only.eligibility.as_ref().unwrap();
+    assert_eq!((eligibility.poll_id, eligibility.threshold), (1001, 18));
+    assert!(eligibility.is_eligible);
+    assert!(age_only.reveals.is_empty());
+    assert_eq!(age_only.nullifiers(), [(1001, eligibility.nullifier.as_str())]);
+
+    let encrypted = &audit.proofs()[7];
+    assert_eq!(encrypted.reveals.len(), 1);
+    assert_eq!(
+        encrypted.reveals[0].nullifier,
+        "57b9bae378ec8b64cd7c251d55cc7a04bca50e0556f4cdc0db4987808a7600a2"
+    );
+    assert!(encrypted.eligibility.is_none());
+}
+
+/// Copies the fixture proof `name` to a proof directory under `dir` sealed for `image_id`.
+fn reseal(dir: &std::path::Path, name: &str, image_id: [u32; 8]) -> PathBuf {
+    let proof_dir = dir.join(name);
+    fs::create_dir_all(&proof_dir).unwrap();
+    let journal_path = proofs_dir().join(name).join("journal.dat");
+    fs::copy(&journal_path, proof_dir.join("journal.dat")).unwrap();
+    let journal = read_journal_file(&journal_path).unwrap();
+    let claim = ReceiptClaim::ok(Digest::from(image_id), journal).digest();
+    let seal = [&FAKE_SELECTOR, claim.as_bytes()].concat();
+    fs::write(proof_dir.join("seal.dat"), hex::encode(seal)).unwrap();
+    fs::write(proof_dir.join("image_id.dat"), hex::encode(Digest::from(image_id))).unwrap();
+    proof_dir
+}
+
+#[test]
+fn journals_of_known_methods_decode_by_image_id() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let dir = tempfile::tempdir().unwrap();
+
+    let age_only = reseal(dir.path(), "08-frank-age-only", AGE_ELIGIBILITY_ID);
+    let journal = verify_proof_dir(&age_only).unwrap();
+    assert!(matches!(journal, MethodJournal::Eligibility(ref e) if e.threshold == 18));
+
+    let encrypted = reseal(dir.path(), "09-grace-encrypted-cbor", K256_VERIFY_ENCRYPTED_ID);
+    let MethodJournal::Reveals(reveals) = verify_proof_dir(&encrypted).unwrap() else {
+        panic!("an encrypted ballot decodes to its reveal data");
+    };
+    assert_eq!(reveals.len(), 1);
+
+    // Sealed as an age-only proof, an encrypted ballot does not decode.
+    let mislabeled = reseal(dir.path(), "09-grace-encrypted-cbor", AGE_ELIGIBILITY_ID);
+    assert!(verify_proof_dir(&mislabeled).is_err());
+}
+
+#[test]
+fn duplicate_names_the_proof_that_used_the_nullifier_first() {
+    let audit = fixture_audit();
+    let duplicate = &audit.proofs()[2];
+    assert_eq!(duplicate.status, ProofStatus::Duplicate { first: "01-alice".into() });
+    assert_eq!(duplicate.reveals, audit.proofs()[0].reveals);
+}
+
+#[test]
+fn invalid_proofs_say_why() {
+    let audit = fixture_audit();
+    let detail = |index: usize| audit.proofs()[index].status.to_string();
+    assert!(detail(3).contains("does not match"), "{}", detail(3));
+    assert!(detail(4).contains("seal.dat"), "{}", detail(4));
+    assert!(detail(5).contains("does not match"), "{}", detail(5));
+    assert!(audit.proofs()[3..6].iter().all(|proof| proof.reveals.is_empty()));
+}
+
+#[test]
+fn reports_list_every_proof() {
+    let audit = fixture_audit();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("audit.csv");
+    audit.write_csv(&out).unwrap();
+    audit.write_json(&audit::json_path(&out)).unwrap();
+
+    let csv = fs::read_to_string(&out).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("proof,status,poll_ids,nullifiers,detail"));
+    let alice = &audit.proofs()[0].reveals[0];
+    let row = format!("01-alice,valid,{},{},", alice.poll_id, alice.nullifier);
+    assert_eq!(lines.next(), Some(row.as_str()));
+    assert_eq!(lines.count(), 8);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("audit.json")).unwrap()).unwrap();
+    assert_eq!((json["valid"].as_u64(), json["invalid"].as_u64()), (Some(5), Some(3)));
+    assert_eq!(json["duplicate"], 1);
+    assert_eq!(json["proofs"][2]["status"], "duplicate");
+    assert_eq!(json["proofs"][0]["reveals"][0]["nullifier"], alice.nullifier.as_str());
+}
+
+#[test]
+fn audit_of_an_empty_directory_is_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit = audit::audit_dir(dir.path()).unwrap();
+    assert!(audit.proofs().is_empty());
+}
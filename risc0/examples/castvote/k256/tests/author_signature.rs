@@ -0,0 +1,197 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Author signatures: the proving service's signature over a journal that commits the hash of
+//! its key, checked on its own and as part of a proof bundle.
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{parse_ballot, BallotError, EncodingError, RevealInfo, VoteParams};
+use clap::Parser;
+use k256::ecdsa::SigningKey;
+use k256_example::{
+    author::{
+        attribute_to, author_public_key, sign_journal, verify_author_signature, AuthorError,
+    },
+    bundle::ProofBundle,
+    cli::Cli,
+    seal::FAKE_SELECTOR,
+};
+use risc0_zkvm::{sha::Digestible, ReceiptClaim};
+
+const BALLOT: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes"}"#;
+
+fn service_key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32].into()).unwrap()
+}
+
+fn forger_key() -> SigningKey {
+    SigningKey::from_bytes(&[9; 32].into()).unwrap()
+}
+
+/// The journal of a ballot proved by the service holding `author`, if any.
+fn journal(author: Option<&SigningKey>) -> (Vec<u8>, Vec<RevealInfo>) {
+    journal_by(author.map(author_public_key))
+}
+
+/// Like [journal], with the author key given in whichever encoding `author_key` has.
+fn journal_by(author_key: Option<String>) -> (Vec<u8>, Vec<RevealInfo>) {
+    let options = vec!["yes".into(), "no".into()];
+    let params = VoteParams { options, author_key, ..Default::default() };
+    let reveal = RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[1; 33], &params).unwrap();
+    (reveal.encode().abi_encode(), vec![reveal])
+}
+
+/// The public key of `key` as Base64 uncompressed SEC1, unlike [author_public_key].
+fn uncompressed_public_key(key: &SigningKey) -> String {
+    general_purpose::STANDARD.encode(key.verifying_key().to_encoded_point(false))
+}
+
+/// The journal of the same ballot proved by the same service for another poll.
+fn journal_with_poll(key: &SigningKey, poll_id: u64) -> (Vec<u8>, RevealInfo) {
+    let (_, reveals) = journal(Some(key));
+    let reveal = RevealInfo { poll_id, ..reveals[0].clone() };
+    (reveal.encode().abi_encode(), reveal)
+}
+
+/// A bundle of `journal` with a dev-mode seal, which verifies with `RISC0_DEV_MODE` set.
+fn dev_bundle(journal: &[u8]) -> ProofBundle {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let image_id = [3; 8].into();
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec()).digest();
+    let seal = [&FAKE_SELECTOR, claim.as_bytes()].concat();
+    ProofBundle::new(&seal, journal, image_id)
+}
+
+#[test]
+fn journal_commits_the_author_key_hash() {
+    let (_, reveals) = journal(Some(&service_key()));
+    let hash = reveals[0].author_key_hash.as_deref().unwrap();
+    assert_eq!(hash.len(), 64);
+    let (_, unattributed) = journal(None);
+    assert_eq!(unattributed[0].author_key_hash, None);
+}
+
+#[test]
+fn valid_author_signature_verifies() {
+    let key = service_key();
+    let (journal, reveals) = journal(Some(&key));
+    let signature = sign_journal(&key, &journal);
+    assert_eq!(signature.len(), 64);
+    verify_author_signature(&journal, &reveals, &author_public_key(&key), &signature).unwrap();
+}
+
+#[test]
+fn forged_author_signature_is_rejected() {
+    let (key, forger) = (service_key(), forger_key());
+    let (journal, reveals) = journal(Some(&key));
+
+    // Signed by another key but presented as the service's.
+    let forged = sign_journal(&forger, &journal);
+    let err = verify_author_signature(&journal, &reveals, &author_public_key(&key), &forged);
+    assert_eq!(err, Err(AuthorError::InvalidSignature));
+
+    // Signed by another key and presented as that key's: not the key the journal commits.
+    let err = verify_author_signature(&journal, &reveals, &author_public_key(&forger), &forged);
+    assert!(matches!(err, Err(AuthorError::KeyMismatch { .. })), "{err:?}");
+
+    // The service's signature over a different journal.
+    let (other, _) = journal_with_poll(&key, 1002);
+    let signature = sign_journal(&key, &other);
+    let err = verify_author_signature(&journal, &reveals, &author_public_key(&key), &signature);
+    assert_eq!(err, Err(AuthorError::InvalidSignature));
+}
+
+#[test]
+fn unattributed_journal_has_no_author_to_verify() {
+    let key = service_key();
+    let (journal, reveals) = journal(None);
+    let signature = sign_journal(&key, &journal);
+    let err = verify_author_signature(&journal, &reveals, &author_public_key(&key), &signature);
+    assert_eq!(err, Err(AuthorError::NotCommitted));
+}
+
+#[test]
+fn malformed_author_key_is_rejected_before_proving() {
+    let params = VoteParams { author_key: Some("not a key".into()), ..Default::default() };
+    assert!(matches!(params.author_key_hash(), Err(BallotError::InvalidAuthorKey(_))));
+}
+
+#[test]
+fn either_encoding_of_the_author_key_matches() {
+    let key = service_key();
+    let (compressed, uncompressed) = (author_public_key(&key), uncompressed_public_key(&key));
+    let (journal, reveals) = journal_by(Some(compressed.clone()));
+    let (other_journal, other_reveals) = journal_by(Some(uncompressed.clone()));
+    assert_eq!(other_reveals[0].author_key_hash, reveals[0].author_key_hash);
+    assert_eq!(other_journal, journal);
+
+    // Proved with one encoding, checked with the other.
+    let signature = sign_journal(&key, &journal);
+    verify_author_signature(&journal, &reveals, &uncompressed, &signature).unwrap();
+    verify_author_signature(&other_journal, &other_reveals, &compressed, &signature).unwrap();
+}
+
+#[test]
+fn author_key_off_the_curve_is_rejected() {
+    // A compressed encoding of x = 5, for which x³ + 7 has no square root modulo p.
+    let off_curve = general_purpose::STANDARD.encode([[2].as_slice(), &[0; 31], &[5]].concat());
+    let params = VoteParams { author_key: Some(off_curve.clone()), ..Default::default() };
+    let err = params.author_key_hash().unwrap_err();
+    assert!(
+        matches!(err, BallotError::InvalidAuthorKey(EncodingError::NotOnCurve { .. })),
+        "{err}"
+    );
+
+    let key = service_key();
+    let (journal, reveals) = journal(Some(&key));
+    let signature = sign_journal(&key, &journal);
+    let err = verify_author_signature(&journal, &reveals, &off_curve, &signature).unwrap_err();
+    assert!(matches!(err, AuthorError::Encoding(EncodingError::NotOnCurve { .. })), "{err:?}");
+}
+
+#[test]
+fn bundle_checks_the_author_signature() {
+    let key = service_key();
+    let (journal, _) = journal(Some(&key));
+    let signature = sign_journal(&key, &journal);
+
+    let bundle = dev_bundle(&journal).with_author(author_public_key(&key), &signature);
+    assert_eq!(bundle.verify().unwrap()[0].poll_id, 1001);
+
+    let forged = sign_journal(&forger_key(), &journal);
+    let bundle = dev_bundle(&journal).with_author(author_public_key(&key), &forged);
+    let err = bundle.verify().unwrap_err();
+    assert_eq!(err.downcast_ref(), Some(&AuthorError::InvalidSignature));
+
+    let err = dev_bundle(&journal).verify().unwrap_err();
+    assert_eq!(err.downcast_ref(), Some(&AuthorError::MissingSignature));
+
+    // A bundle of an unattributed journal still verifies without one.
+    let (journal, _) = self::journal(None);
+    dev_bundle(&journal).verify().unwrap();
+}
+
+#[test]
+fn flag_only_applies_when_proving() {
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "ballot.json", "--poll-id", "1001"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let args = parse(&["--commit-author-signature", "service.key"]).unwrap();
+    assert_eq!(args.commit_author_signature.as_deref(), Some("service.key".as_ref()));
+    assert!(parse(&["--commit-author-signature", "service.key", "--no-prove"]).is_err());
+    assert!(parse(&["--commit-author-signature", "service.key", "--explain"]).is_err());
+}
@@ -0,0 +1,104 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots carrying their own `poll_id`: the default for `--poll-id`, which must agree with it,
+//! and checked again by the guest.
+
+use std::fs;
+
+use castvote_core::{
+    named_poll_id, parse_ballot, AgeEligibility, BallotError, PollIdentifier, RevealInfo,
+    VoteParams,
+};
+use clap::Parser;
+use k256_example::{cli::Cli, validate_ballot};
+
+const BALLOT: &str = r#"{"id": 123, "age": 30, "is_student": true}"#;
+
+fn ballot_for(poll_id: &str) -> String {
+    BALLOT.replace("{", &format!("{{\"poll_id\": {poll_id}, "))
+}
+
+/// Writes each ballot to its own file and resolves `--poll-id` for them.
+fn resolve(flags: &[&str], ballots: &[String]) -> anyhow::Result<Option<PollIdentifier>> {
+    let dir = tempfile::tempdir().unwrap();
+    let mut argv = vec!["checkvote".to_string()];
+    for (index, ballot) in ballots.iter().enumerate() {
+        let path = dir.path().join(format!("ballot-{index}.json"));
+        fs::write(&path, ballot).unwrap();
+        argv.push(path.to_str().unwrap().to_string());
+    }
+    argv.extend(flags.iter().map(|flag| flag.to_string()));
+    let mut args = Cli::try_parse_from(argv).unwrap().prove.unwrap();
+    args.resolve_poll_id()?;
+    Ok(args.poll_id)
+}
+
+#[test]
+fn embedded_poll_id_is_used_without_the_flag() {
+    assert_eq!(resolve(&[], &[ballot_for("1001")]).unwrap(), Some(PollIdentifier::Numeric(1001)));
+    let named = resolve(&[], &[ballot_for("\"spring-2026\"")]).unwrap();
+    assert_eq!(named, Some(PollIdentifier::Named("spring-2026".into())));
+}
+
+#[test]
+fn flag_and_embedded_poll_id_must_agree() {
+    let ballots = [ballot_for("1001"), ballot_for("\"1001\"")];
+    let resolved = resolve(&["--poll-id", "1001"], &ballots).unwrap();
+    assert_eq!(resolved, Some(PollIdentifier::Numeric(1001)));
+
+    let err = resolve(&["--poll-id", "1002"], &[ballot_for("1001")]).unwrap_err();
+    assert!(err.to_string().contains("is for poll 1001, not --poll-id 1002"), "{err}");
+    assert!(err.to_string().contains("ballot-0.json"), "{err}");
+}
+
+#[test]
+fn ballots_naming_different_polls_are_rejected() {
+    let err = resolve(&[], &[ballot_for("1001"), BALLOT.into(), ballot_for("1002")]).unwrap_err();
+    assert!(err.to_string().contains("is for poll 1002, but"), "{err}");
+    assert!(err.to_string().contains("ballot-2.json"), "{err}");
+}
+
+#[test]
+fn flag_alone_still_works() {
+    let resolved = resolve(&["--poll-id", "7"], &[BALLOT.into()]).unwrap();
+    assert_eq!(resolved, Some(PollIdentifier::Numeric(7)));
+}
+
+#[test]
+fn guest_rejects_a_ballot_proven_for_another_poll() {
+    let params = VoteParams::default();
+    let text = ballot_for("1001");
+    let ballot = parse_ballot(&text).unwrap();
+    assert!(RevealInfo::new(&ballot, 1001, &[], &params).is_ok());
+    let err = RevealInfo::new(&ballot, 1002, &[], &params).unwrap_err();
+    assert!(matches!(err, BallotError::PollMismatch { poll_id: 1002, .. }), "{err}");
+    assert!(AgeEligibility::new(&ballot, &text, 1002, 18, &params).is_err());
+
+    let named = parse_ballot(&ballot_for("\"spring-2026\"")).unwrap();
+    assert!(RevealInfo::new(&named, named_poll_id("spring-2026"), &[], &params).is_ok());
+    assert!(RevealInfo::new(&named, 1001, &[], &params).is_err());
+}
+
+#[test]
+fn schema_checks_the_embedded_poll_id() {
+    assert!(validate_ballot(&ballot_for("1001")).is_ok());
+    for bad in ["\"\"", "-1", "1.5", "true"] {
+        let err = validate_ballot(&ballot_for(bad)).unwrap_err();
+        assert_eq!(err.path, "/poll_id", "{bad}");
+    }
+    let multi = r#"{"id": 1, "age": 30, "is_student": true, "poll_id": 1, "choices": {"1": "a"}}"#;
+    assert_eq!(validate_ballot(multi).unwrap_err().path, "/poll_id");
+}
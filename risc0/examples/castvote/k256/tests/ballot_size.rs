@@ -0,0 +1,110 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Oversized ballots are rejected before they are hashed, signed or proven.
+
+use std::fs;
+
+use castvote_core::{BallotError, VoteParams, DEFAULT_MAX_BALLOT_SIZE, MAX_BALLOT_SIZE_CAP};
+use clap::Parser;
+use k256_example::{cli::Cli, error::VoteError, load_ballot, prepare_ballot, read_ballot_file};
+
+/// A valid ballot padded with a `name` to `size` bytes.
+fn ballot(size: usize) -> String {
+    let empty = r#"{"id":1,"age":30,"is_student":false,"name":""}"#;
+    let name = "x".repeat(size - empty.len());
+    format!(r#"{{"id":1,"age":30,"is_student":false,"name":"{name}"}}"#)
+}
+
+fn too_large(err: &anyhow::Error) -> Option<&BallotError> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<BallotError>())
+        .filter(|err| matches!(err, BallotError::BallotTooLarge { .. }))
+}
+
+#[test]
+fn limit_defaults_to_64_kib_and_is_capped() {
+    assert_eq!(VoteParams::default().ballot_size_limit(), DEFAULT_MAX_BALLOT_SIZE);
+    let huge = VoteParams { max_ballot_size: Some(u64::MAX), ..VoteParams::default() };
+    assert_eq!(huge.ballot_size_limit(), MAX_BALLOT_SIZE_CAP);
+}
+
+#[test]
+fn ballot_at_the_limit_is_accepted() {
+    let params = VoteParams { max_ballot_size: Some(100), ..VoteParams::default() };
+    assert!(prepare_ballot(&ballot(100), &params).is_ok());
+
+    let err = prepare_ballot(&ballot(101), &params).unwrap_err();
+    assert_eq!(too_large(&err), Some(&BallotError::BallotTooLarge { size: 101, limit: 100 }));
+    assert_eq!(VoteError::exit_code_of(&err), 2, "{err:#}");
+}
+
+#[test]
+fn oversized_file_is_rejected_before_it_is_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ballot.json");
+    let size = DEFAULT_MAX_BALLOT_SIZE as usize + 1;
+    fs::write(&path, ballot(size)).unwrap();
+
+    let params = VoteParams::default();
+    let err = read_ballot_file(&path, &params).unwrap_err();
+    assert!(too_large(&err).is_some(), "{err:#}");
+    let err = load_ballot(&path, &params).unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 2, "{err:#}");
+
+    let roomy = VoteParams { max_ballot_size: Some(size as u64), ..VoteParams::default() };
+    assert!(load_ballot(&path, &roomy).is_ok());
+}
+
+#[test]
+fn max_ballot_size_flag_reaches_the_guest_params() {
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "ballot.json", "--poll-id", "1001"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let params = parse(&[]).unwrap().vote_params().unwrap();
+    assert_eq!(params.max_ballot_size, Some(DEFAULT_MAX_BALLOT_SIZE));
+    let params = parse(&["--max-ballot-size", "4096"]).unwrap().vote_params().unwrap();
+    assert_eq!(params.max_ballot_size, Some(4096));
+
+    assert!(parse(&["--max-ballot-size", "0"]).is_err());
+    let above_cap = (MAX_BALLOT_SIZE_CAP + 1).to_string();
+    assert!(parse(&["--max-ballot-size", &above_cap]).is_err());
+}
+
+/// The guest refuses an oversized ballot even if the host let it through.
+#[cfg(feature = "prove")]
+#[test]
+fn guest_refuses_an_oversized_ballot() {
+    use base64::{engine::general_purpose, Engine as _};
+    use castvote_core::canonical_k256_key;
+    use k256::ecdsa::SigningKey;
+    use k256_example::{sign_ballot, SoftwareSigner};
+    use k256_methods::K256_VERIFY_ELF;
+    use risc0_zkvm::{default_executor, ExecutorEnv};
+
+    let key = SigningKey::from_bytes((&[1u8; 32]).into()).unwrap();
+    let public_key = general_purpose::STANDARD.encode(canonical_k256_key(key.verifying_key()));
+    let ballot = ballot(200);
+    let signature = sign_ballot(&SoftwareSigner::new(key), &ballot).unwrap();
+    let params = VoteParams { max_ballot_size: Some(100), ..VoteParams::default() };
+    let env = ExecutorEnv::builder()
+        .write(&(signature, ballot, 1001u64, public_key))
+        .unwrap()
+        .write(&params)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(default_executor().execute(env, K256_VERIFY_ELF).is_err());
+}
@@ -0,0 +1,124 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Single, approval and ranked-choice selections, checked on the host and in the executor.
+#![cfg(feature = "prove")]
+
+use castvote_core::{
+    canonicalize_ballot, parse_ballot, BallotError, BallotType, RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256_example::cli::Cli;
+
+mod common;
+
+fn ballot(choice: &str) -> String {
+    format!(r#"{{"id": 9, "age": 40, "is_student": false, "choice": {choice}}}"#)
+}
+
+fn params(ballot_type: BallotType) -> VoteParams {
+    VoteParams {
+        ballot_type,
+        options: vec!["alice".into(), "bob".into(), "carol".into()],
+        ..Default::default()
+    }
+}
+
+fn selection(ballot_type: BallotType, choice: &str) -> Result<Vec<String>, BallotError> {
+    parse_ballot(&ballot(choice)).unwrap().selection(&params(ballot_type))
+}
+
+#[test]
+fn single_choice_selects_one_option() {
+    assert_eq!(selection(BallotType::Single, r#""bob""#).unwrap(), ["bob"]);
+    assert!(matches!(
+        selection(BallotType::Single, r#"["bob"]"#),
+        Err(BallotError::InvalidChoice { .. })
+    ));
+}
+
+#[test]
+fn approval_keeps_every_selected_option() {
+    assert_eq!(
+        selection(BallotType::Approval, r#"["carol", "alice"]"#).unwrap(),
+        ["carol", "alice"]
+    );
+    assert!(matches!(
+        selection(BallotType::Approval, r#""alice""#),
+        Err(BallotError::InvalidChoice { .. })
+    ));
+    assert!(matches!(
+        selection(BallotType::Approval, "[]"),
+        Err(BallotError::InvalidChoice { .. })
+    ));
+}
+
+#[test]
+fn ranked_may_rank_a_subset_in_order() {
+    assert_eq!(selection(BallotType::Ranked, r#"["bob", "alice"]"#).unwrap(), ["bob", "alice"]);
+}
+
+#[test]
+fn duplicate_and_unknown_options_are_rejected() {
+    assert_eq!(
+        selection(BallotType::Ranked, r#"["bob", "alice", "bob"]"#),
+        Err(BallotError::DuplicateOption("bob".into()))
+    );
+    assert_eq!(
+        selection(BallotType::Approval, r#"["alice", "dave"]"#),
+        Err(BallotError::UnknownOption("dave".into()))
+    );
+    assert_eq!(
+        selection(BallotType::Single, r#""dave""#),
+        Err(BallotError::UnknownOption("dave".into()))
+    );
+}
+
+#[test]
+fn without_options_any_option_is_accepted() {
+    let params = VoteParams { ballot_type: BallotType::Approval, ..Default::default() };
+    let selection = |choice| parse_ballot(&ballot(choice)).unwrap().selection(&params);
+    assert_eq!(selection(r#"["dave", "erin"]"#).unwrap(), ["dave", "erin"]);
+    assert_eq!(selection(r#"["dave", "dave"]"#), Err(BallotError::DuplicateOption("dave".into())));
+    assert!(matches!(selection(r#""dave""#), Err(BallotError::InvalidChoice { .. })));
+}
+
+#[test]
+fn homomorphic_tally_needs_options() {
+    let argv = ["checkvote", "b.json", "--poll-id", "1001", "--elgamal-to", "key"];
+    let err = Cli::try_parse_from(argv).unwrap().prove.unwrap().vote_params().unwrap_err();
+    assert!(err.to_string().contains("needs the poll's options"), "{err}");
+}
+
+#[test]
+fn ballot_without_choice_selects_nothing() {
+    let ballot = parse_ballot(r#"{"id": 9, "age": 40, "is_student": false}"#).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[], &params(BallotType::Ranked)).unwrap();
+    assert!(reveal.selection.is_empty());
+}
+
+#[test]
+fn guest_commits_ranked_selection() {
+    let ballot = ballot(r#"["carol", "alice"]"#);
+    let reveal = common::execute(&ballot, &params(BallotType::Ranked)).unwrap();
+    assert_eq!(reveal.ballot_type, BallotType::Ranked);
+    assert_eq!(reveal.selection, ["carol", "alice"]);
+}
+
+#[test]
+fn guest_rejects_invalid_ranking() {
+    for ranking in [r#"["carol", "carol"]"#, r#"["carol", "dave"]"#] {
+        assert!(common::execute(&ballot(ranking), &params(BallotType::Ranked)).is_err());
+    }
+}
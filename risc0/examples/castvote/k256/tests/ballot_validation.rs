@@ -0,0 +1,65 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use k256_example::validate_ballot;
+
+fn rejected_at(json: &str) -> String {
+    validate_ballot(json).unwrap_err().path
+}
+
+#[test]
+fn sample_ballot_is_valid() {
+    validate_ballot(include_str!("../res/person.json")).unwrap();
+}
+
+#[test]
+fn optional_fields_are_accepted() {
+    validate_ballot(r#"{"id": "voter-1", "birthdate": "2000-01-31", "is_student": false, "choice": "yes"}"#)
+        .unwrap();
+    validate_ballot(r#"{"id": 2, "age": 30, "is_student": false, "choice": ["b", "a"]}"#).unwrap();
+}
+
+#[test]
+fn missing_required_fields_are_reported() {
+    assert_eq!(rejected_at(r#"{"age": 30, "is_student": true}"#), "/id");
+    assert_eq!(rejected_at(r#"{"id": 1, "is_student": true}"#), "/age");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": 30}"#), "/is_student");
+}
+
+#[test]
+fn wrong_types_are_reported() {
+    assert_eq!(rejected_at(r#"{"id": true, "age": 30, "is_student": true}"#), "/id");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": "30", "is_student": true}"#), "/age");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": -1, "is_student": true}"#), "/age");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": 30, "is_student": "yes"}"#), "/is_student");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": 30, "is_student": true, "name": 5}"#), "/name");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": 30, "is_student": true, "choice": ""}"#), "/choice");
+    assert_eq!(rejected_at(r#"{"id": 1, "age": 30, "is_student": true, "choice": []}"#), "/choice");
+    assert_eq!(
+        rejected_at(r#"{"id": 1, "age": 30, "is_student": true, "choice": ["yes", 2]}"#),
+        "/choice/1"
+    );
+    assert_eq!(
+        rejected_at(r#"{"id": 1, "birthdate": "2001-02-29", "is_student": true}"#),
+        "/birthdate"
+    );
+}
+
+#[test]
+fn non_object_ballots_are_rejected_at_root() {
+    assert_eq!(rejected_at("[1, 2]"), "");
+    assert_eq!(rejected_at("{not json"), "");
+    let message = validate_ballot("[]").unwrap_err().to_string();
+    assert_eq!(message, "invalid ballot at /: expected a JSON object");
+}
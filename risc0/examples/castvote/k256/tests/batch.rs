@@ -0,0 +1,104 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks of the batch guest method, run in the executor.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, canonicalize_ballot, compute_nullifier, decode_reveals, parse_ballot,
+    voter_key_hash, VoteParams,
+};
+use common::Input;
+use k256_example::{sign_ballot, BallotSigner, SoftwareSigner};
+use k256_methods::K256_VERIFY_BATCH_ELF;
+
+mod common;
+
+fn public_key(signer: &SoftwareSigner) -> Vec<u8> {
+    signer.public_key().to_encoded_point(false).as_bytes().to_vec()
+}
+
+fn signed(signer: &SoftwareSigner, message: String) -> Input {
+    let signature = sign_ballot(signer, &canonicalize_ballot(&message).unwrap()).unwrap();
+    (signature, message, 1001, general_purpose::STANDARD.encode(public_key(signer)))
+}
+
+fn ballot(id: u64) -> String {
+    format!(r#"{{"id": {id}, "name": "Voter {id}", "age": {}, "is_student": {}}}"#, 20 + id, id <= 2)
+}
+
+fn execute(ballots: &[Input]) -> anyhow::Result<Vec<u8>> {
+    common::execute_journal(K256_VERIFY_BATCH_ELF, &ballots, &VoteParams::default())
+}
+
+#[test]
+fn batch_commits_one_reveal_per_ballot() {
+    let signer = common::signer();
+    let ballots: Vec<_> = (1..=3).map(|id| signed(&signer, ballot(id))).collect();
+
+    let journal = execute(&ballots).unwrap();
+    let reveals = decode_reveals(&Vec::<u8>::abi_decode(&journal).unwrap()).unwrap();
+
+    assert_eq!(reveals.len(), 3);
+    for ((_, message, _, _), reveal) in ballots.iter().zip(&reveals) {
+        let ballot = parse_ballot(message).unwrap();
+        assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&ballot.id, 1001)));
+        assert_eq!(Some(reveal.age), ballot.age);
+        assert_eq!(reveal.poll_id, 1001);
+    }
+}
+
+#[test]
+fn one_invalid_signature_fails_the_whole_batch() {
+    let signer = common::signer();
+    let mut ballots: Vec<_> = (1..=3).map(|id| signed(&signer, ballot(id))).collect();
+    // Swap in the signature of a different ballot for the middle entry.
+    ballots[1].0 = ballots[0].0.clone();
+
+    assert!(execute(&ballots).is_err());
+}
+
+#[test]
+fn a_repeated_nullifier_fails_the_whole_batch() {
+    let signer = common::signer();
+    // The same voter ID with a different name: another ballot, but the same nullifier.
+    let second = ballot(1).replace("Voter 1", "Voter One");
+    let ballots: Vec<_> =
+        [ballot(1), ballot(2), second].into_iter().map(|b| signed(&signer, b)).collect();
+    assert!(execute(&ballots).is_err());
+
+    // The same voter may still vote in another poll of the same batch.
+    let mut other_poll = signed(&signer, ballot(1));
+    other_poll.2 = 1002;
+    execute(&[signed(&signer, ballot(1)), other_poll]).unwrap();
+}
+
+#[test]
+fn ballots_from_different_voters_share_a_batch() {
+    let alice = common::signer();
+    let bob = SoftwareSigner::new(k256::ecdsa::SigningKey::from_bytes((&[3u8; 32]).into()).unwrap());
+    let ballots = vec![signed(&alice, ballot(1)), signed(&bob, ballot(2))];
+
+    let journal = execute(&ballots).unwrap();
+    let reveals = decode_reveals(&Vec::<u8>::abi_decode(&journal).unwrap()).unwrap();
+
+    // The hash is over the compressed key, although the batch passed it uncompressed.
+    let key_hash = |signer: &SoftwareSigner| {
+        hex::encode(voter_key_hash(&canonical_k256_key(&signer.public_key())))
+    };
+    assert_eq!(reveals[0].voter_key_hash, key_hash(&alice));
+    assert_eq!(reveals[1].voter_key_hash, key_hash(&bob));
+}
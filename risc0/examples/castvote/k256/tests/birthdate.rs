@@ -0,0 +1,86 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use castvote_core::{parse_ballot, BallotError, Date, RevealInfo, VoteParams};
+
+fn date(s: &str) -> Date {
+    s.parse().unwrap()
+}
+
+fn params(as_of: &str) -> VoteParams {
+    VoteParams { as_of: Some(date(as_of)), ..Default::default() }
+}
+
+#[test]
+fn age_increments_on_birthday() {
+    let born = date("2006-05-20");
+    assert_eq!(born.age_on(date("2024-05-19")), Some(17));
+    assert_eq!(born.age_on(date("2024-05-20")), Some(18));
+    assert_eq!(born.age_on(date("2024-12-31")), Some(18));
+}
+
+#[test]
+fn leap_day_birthday_counts_from_march_first_in_common_years() {
+    let born = date("2004-02-29");
+    assert_eq!(born.age_on(date("2022-02-28")), Some(17));
+    assert_eq!(born.age_on(date("2022-03-01")), Some(18));
+    assert_eq!(born.age_on(date("2024-02-29")), Some(20));
+}
+
+#[test]
+fn invalid_dates_are_rejected() {
+    assert!("2023-02-29".parse::<Date>().is_err());
+    assert!("1900-02-29".parse::<Date>().is_err());
+    assert!("2000-02-29".parse::<Date>().is_ok());
+    assert!("2024-13-01".parse::<Date>().is_err());
+    assert!("2024-1-01".parse::<Date>().is_err());
+    assert!("20240101".parse::<Date>().is_err());
+}
+
+#[test]
+fn unix_days_round_trip_known_dates() {
+    assert_eq!(Date::from_unix_days(0), date("1970-01-01"));
+    assert_eq!(Date::from_unix_days(11_016), date("2000-02-29"));
+    assert_eq!(Date::from_unix_days(-1), date("1969-12-31"));
+}
+
+#[test]
+fn birthdate_takes_precedence_and_is_not_revealed() {
+    let ballot = parse_ballot(
+        r#"{"id": 1, "age": 99, "birthdate": "2000-06-15", "is_student": false}"#,
+    )
+    .unwrap();
+    let reveal = RevealInfo::new(&ballot, 7, &[], &params("2024-06-14")).unwrap();
+    assert_eq!(reveal.age, 23);
+    assert_eq!(reveal.age_as_of, Some(date("2024-06-14")));
+}
+
+#[test]
+fn static_age_is_used_without_birthdate() {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": true}"#).unwrap();
+    let reveal = RevealInfo::new(&ballot, 7, &[], &params("2024-06-14")).unwrap();
+    assert_eq!(reveal.age, 30);
+    assert_eq!(reveal.age_as_of, None);
+}
+
+#[test]
+fn birthdate_requires_reference_date() {
+    let ballot =
+        parse_ballot(r#"{"id": 1, "birthdate": "2000-06-15", "is_student": false}"#).unwrap();
+    assert_eq!(ballot.age(&VoteParams::default()), Err(BallotError::MissingAsOf));
+    assert!(matches!(
+        ballot.age(&params("1999-01-01")),
+        Err(BallotError::BornAfterAsOf { .. })
+    ));
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Status reporting of Bonsai sessions, without talking to Bonsai.
+
+use k256_example::{
+    bonsai::{describe_status, job_failed, StatusLog},
+    error::VoteError,
+};
+
+#[test]
+fn statuses_read_as_session_phases() {
+    assert_eq!(describe_status("RUNNING", None), "running");
+    assert_eq!(describe_status("RUNNING", Some("")), "running");
+    let proving = describe_status("RUNNING", Some("ProveSegments: 3/8"));
+    assert_eq!(proving, "running (ProveSegments: 3/8)");
+    assert_eq!(describe_status("SUCCEEDED", Some("Finalize")), "done");
+    assert_eq!(describe_status("TIMED_OUT", None), "timed out");
+}
+
+#[test]
+fn only_transitions_are_logged() {
+    let mut log = StatusLog::new();
+    assert_eq!(log.update("RUNNING", Some("Setup")), Some("running (Setup)"));
+    assert_eq!(log.update("RUNNING", Some("Setup")), None);
+    assert_eq!(log.update("RUNNING", Some("Executor")), Some("running (Executor)"));
+    assert_eq!(log.update("SUCCEEDED", None), Some("done"));
+    assert_eq!(log.update("SUCCEEDED", None), None);
+}
+
+#[test]
+fn failures_carry_bonsai_error_message() {
+    let err = job_failed("session", "abc-123", "FAILED", Some("guest panicked: bad ballot"));
+    let message = "Bonsai session abc-123 failed: guest panicked: bad ballot";
+    assert_eq!(err.root_cause().to_string(), message);
+    assert_eq!(VoteError::of(&err), Some(VoteError::ProvingFailed));
+
+    let err = job_failed("SNARK", "def-456", "ABORTED", None);
+    let message = "Bonsai SNARK def-456 aborted: Bonsai gave no error message";
+    assert_eq!(err.root_cause().to_string(), message);
+}
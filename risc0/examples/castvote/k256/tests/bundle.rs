@@ -0,0 +1,90 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Proof bundles: writing one, reading it back and verifying it, using the dev-mode proofs in
+//! `testdata/audit`.
+
+use std::{fs, path::PathBuf};
+
+use k256_example::{
+    audit::verify_proof_dir,
+    bundle::{read_bundle, write_bundle, ProofBundle, BUNDLE_FORMAT, BUNDLE_VERSION},
+    read_hex_file, read_image_id, MethodJournal,
+};
+
+fn alice_bundle() -> ProofBundle {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/audit/proofs/01-alice");
+    let seal = read_hex_file(dir.join("seal.dat")).unwrap();
+    let journal = read_hex_file(dir.join("journal.dat")).unwrap();
+    let image_id = read_image_id(&dir.join("image_id.dat").to_string_lossy()).unwrap();
+    ProofBundle::new(&seal, &journal, image_id)
+}
+
+#[test]
+fn bundle_round_trips_and_verifies() {
+    let bundle = alice_bundle();
+    assert_eq!((bundle.format.as_str(), bundle.version), (BUNDLE_FORMAT, BUNDLE_VERSION));
+    assert_eq!(bundle.tool_version, env!("CARGO_PKG_VERSION"));
+    assert!(bundle.created_at > 0);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("alice.bundle.json");
+    write_bundle(&path, &bundle).unwrap();
+    let read = read_bundle(&path).unwrap();
+    assert_eq!(read, bundle);
+
+    let proof_dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/audit/proofs/01-alice");
+    let reveals = MethodJournal::Reveals(read.verify().unwrap());
+    assert_eq!(reveals, verify_proof_dir(&proof_dir).unwrap());
+}
+
+#[test]
+fn tampered_bundle_does_not_verify() {
+    let mut bundle = alice_bundle();
+    let mut journal = bundle.journal_bytes().unwrap();
+    *journal.last_mut().unwrap() ^= 1;
+    bundle.journal = hex::encode(journal);
+    let err = bundle.verify().unwrap_err();
+    assert!(err.to_string().contains("does not match"), "{err}");
+
+    let mut bundle = alice_bundle();
+    bundle.image_id = "00".repeat(31);
+    let err = bundle.verify().unwrap_err();
+    assert!(err.to_string().contains("expected 32"), "{err}");
+}
+
+#[test]
+fn other_files_are_not_read_as_bundles() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bundle.json");
+
+    let mut bundle = alice_bundle();
+    bundle.version = BUNDLE_VERSION + 1;
+    write_bundle(&path, &bundle).unwrap();
+    let err = read_bundle(&path).unwrap_err();
+    assert!(err.to_string().contains("this build reads versions"), "{err}");
+
+    bundle.version = BUNDLE_VERSION;
+    bundle.format = "something-else".into();
+    write_bundle(&path, &bundle).unwrap();
+    let err = read_bundle(&path).unwrap_err();
+    assert!(err.to_string().contains("not a proof bundle"), "{err}");
+
+    fs::write(&path, r#"{"seal": "00"}"#).unwrap();
+    let err = read_bundle(&path).unwrap_err();
+    assert!(err.to_string().contains("not a proof bundle"), "{err}");
+}
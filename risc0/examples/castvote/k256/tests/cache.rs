@@ -0,0 +1,95 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The on-disk receipt cache, exercised with fake receipts instead of the prover.
+
+use std::fs;
+
+use castvote_core::VoteParams;
+use k256_example::cache::ProofCache;
+use risc0_zkvm::{FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+/// Stand-ins for the image IDs of two guest builds.
+const IMAGE_A: [u32; 8] = [1; 8];
+const IMAGE_B: [u32; 8] = [2; 8];
+
+fn fake_receipt(image_id: [u32; 8], journal: &[u8]) -> Receipt {
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+    Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal.to_vec())
+}
+
+fn input(signature: &str) -> (&str, &str, u64, &str) {
+    (signature, "{\"id\": 1}", 1001, "BLZg")
+}
+
+#[test]
+fn key_covers_input_params_and_image_id() {
+    let params = VoteParams::default();
+    let key = ProofCache::key(&input("sig"), &params, IMAGE_A).unwrap();
+
+    assert_eq!(key, ProofCache::key(&input("sig"), &params, IMAGE_A).unwrap());
+    assert_ne!(key, ProofCache::key(&input("other"), &params, IMAGE_A).unwrap());
+    assert_ne!(key, ProofCache::key(&input("sig"), &params, IMAGE_B).unwrap());
+    let ranked = VoteParams { options: vec!["yes".into()], ..Default::default() };
+    assert_ne!(key, ProofCache::key(&input("sig"), &ranked, IMAGE_A).unwrap());
+}
+
+#[test]
+fn stored_receipt_is_returned_for_its_image_id_only() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProofCache::new(dir.path());
+    let key = ProofCache::key(&input("sig"), &VoteParams::default(), IMAGE_A).unwrap();
+
+    assert!(cache.get(&key, IMAGE_A).unwrap().is_none());
+    let receipt = fake_receipt(IMAGE_A, b"journal");
+    cache.put(&key, IMAGE_A, &receipt).unwrap();
+
+    let hit = cache.get(&key, IMAGE_A).unwrap().unwrap();
+    assert_eq!(hit.journal.bytes, b"journal");
+    assert!(cache.get(&key, IMAGE_B).unwrap().is_none());
+}
+
+#[test]
+fn unverifiable_entry_is_a_miss() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProofCache::new(dir.path());
+
+    // A receipt for another image stored under this one's directory never verifies.
+    cache.put("stale", IMAGE_A, &fake_receipt(IMAGE_B, b"journal")).unwrap();
+    assert!(cache.get("stale", IMAGE_A).unwrap().is_none());
+
+    cache.put("corrupt", IMAGE_A, &fake_receipt(IMAGE_A, b"journal")).unwrap();
+    let image_dir = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+    fs::write(image_dir.join("corrupt.receipt"), b"garbage").unwrap();
+    assert!(cache.get("corrupt", IMAGE_A).unwrap().is_none());
+    assert!(!image_dir.join("corrupt.receipt").exists());
+}
+
+#[test]
+fn prune_drops_entries_of_rebuilt_guests() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProofCache::new(dir.path());
+    cache.put("a", IMAGE_A, &fake_receipt(IMAGE_A, b"a")).unwrap();
+    cache.put("b", IMAGE_B, &fake_receipt(IMAGE_B, b"b")).unwrap();
+    fs::create_dir(dir.path().join("unrelated")).unwrap();
+
+    assert_eq!(cache.prune(&[IMAGE_A]).unwrap(), 1);
+
+    assert!(cache.get("a", IMAGE_A).unwrap().is_some());
+    assert!(cache.get("b", IMAGE_B).unwrap().is_none());
+    assert!(dir.path().join("unrelated").is_dir());
+}
@@ -0,0 +1,67 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Canonical ballot text, which is what voters sign.
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, MessageFormat};
+use k256::ecdsa::{signature::Verifier, Signature};
+use k256_example::{sign, VoterKey};
+use sha2::{Digest, Sha256};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+#[test]
+fn whitespace_and_key_order_do_not_matter() {
+    let canonical = canonicalize_ballot(BALLOT).unwrap();
+    assert_eq!(canonical, r#"{"age":30,"id":123,"is_student":true,"name":"Alice Wonderland"}"#);
+
+    let reordered = "{ \"name\":\"Alice Wonderland\",\n\t\"is_student\" : true, \"id\":123,\"age\":30 }";
+    assert_eq!(canonicalize_ballot(reordered).unwrap(), canonical);
+    assert_eq!(canonicalize_ballot(&canonical).unwrap(), canonical);
+}
+
+#[test]
+fn nested_values_are_canonicalized() {
+    let ballot = r#"{"choice": [ "b", "a" ], "meta": {"z": 1, "a": {"y": null, "b": "é\n"}}}"#;
+    assert_eq!(
+        canonicalize_ballot(ballot).unwrap(),
+        r#"{"choice":["b","a"],"meta":{"a":{"b":"é\n","y":null},"z":1}}"#
+    );
+}
+
+#[test]
+fn different_content_stays_different() {
+    let older = BALLOT.replace("\"age\": 30", "\"age\": 31");
+    assert_ne!(canonicalize_ballot(&older).unwrap(), canonicalize_ballot(BALLOT).unwrap());
+    assert!(canonicalize_ballot("{\"id\": 1,").is_err());
+}
+
+#[test]
+fn signature_over_one_formatting_verifies_another() {
+    let key = k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap();
+    let signature = sign(
+        &VoterKey::K256(key.clone()),
+        &canonicalize_ballot(BALLOT).unwrap(),
+        MessageFormat::Sha256,
+    )
+    .unwrap();
+    let signature =
+        Signature::from_slice(&general_purpose::STANDARD.decode(signature).unwrap()).unwrap();
+
+    let reordered = r#"{"is_student":true,"name":"Alice Wonderland","age":30,"id":123}"#;
+    let digest = Sha256::digest(canonicalize_ballot(reordered).unwrap());
+    assert!(key.verifying_key().verify(&digest, &signature).is_ok());
+}
@@ -0,0 +1,142 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Resumable batch runs: a run that stops mid-batch and is resumed from its checkpoint proves
+//! every ballot exactly once.
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use clap::Parser;
+use k256_example::{
+    checkpoint::{run_resumable, Checkpoint, CheckpointState, RateLimit, ResumableRun},
+    cli::Cli,
+};
+
+fn ballots(count: usize) -> Vec<(String, usize)> {
+    (0..count).map(|n| (format!("{n:064x}"), n)).collect()
+}
+
+fn no_limit() -> RateLimit {
+    RateLimit::new(Duration::ZERO)
+}
+
+#[test]
+fn a_restarted_run_proves_every_ballot_exactly_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.json");
+    let ballots = ballots(5);
+    let mut proven = Vec::new();
+
+    // The first run crashes while proving the third ballot.
+    let mut checkpoint = Checkpoint::open(&path, false).unwrap();
+    let err = run_resumable(&ballots, &mut checkpoint, &mut no_limit(), |_, &n| {
+        if n == 2 {
+            bail!("prover crashed");
+        }
+        proven.push(n);
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("prover crashed"), "{err:#}");
+    assert_eq!(proven, [0, 1]);
+
+    // The restart picks up at the ballot that failed.
+    let mut checkpoint = Checkpoint::open(&path, true).unwrap();
+    assert_eq!(checkpoint.state().completed.len(), 2);
+    let run = run_resumable(&ballots, &mut checkpoint, &mut no_limit(), |_, &n| {
+        proven.push(n);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(run, ResumableRun { proven: 3, skipped: 2 });
+    assert_eq!(proven, [0, 1, 2, 3, 4]);
+
+    // Resuming a finished run proves nothing.
+    let mut checkpoint = Checkpoint::open(&path, true).unwrap();
+    let run = run_resumable(&ballots, &mut checkpoint, &mut no_limit(), |_, _| {
+        panic!("every ballot is already proven")
+    })
+    .unwrap();
+    assert_eq!(run, ResumableRun { proven: 0, skipped: 5 });
+}
+
+#[test]
+fn the_checkpoint_is_saved_after_every_proof() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.json");
+    let ballots = ballots(3);
+    let mut checkpoint = Checkpoint::open(&path, false).unwrap();
+    run_resumable(&ballots, &mut checkpoint, &mut no_limit(), |_, &n| {
+        // When ballot `n` is proven, the file already records the `n` before it.
+        let saved = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str::<CheckpointState>(&json).unwrap().completed.len(),
+            Err(_) => 0,
+        };
+        assert_eq!(saved, n);
+        Ok(())
+    })
+    .unwrap();
+
+    let saved: CheckpointState = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved.completed, ballots.iter().map(|(hash, _)| hash.clone()).collect());
+}
+
+#[test]
+fn an_existing_checkpoint_needs_resume() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.json");
+    // Resuming a run that never started starts from nothing.
+    assert!(Checkpoint::open(&path, true).unwrap().state().completed.is_empty());
+
+    Checkpoint::open(&path, false).unwrap().record("ab").unwrap();
+    let err = Checkpoint::open(&path, false).unwrap_err();
+    assert!(err.to_string().contains("--resume"), "{err}");
+    assert!(Checkpoint::open(&path, true).unwrap().is_completed("ab"));
+
+    fs::write(&path, "not json").unwrap();
+    assert!(Checkpoint::open(&path, true).is_err());
+}
+
+#[test]
+fn proofs_are_spaced_out_by_the_rate_limit() {
+    let mut rate = RateLimit::new(Duration::from_millis(50));
+    let start = Instant::now();
+    for _ in 0..3 {
+        rate.wait();
+    }
+    // The first proof starts at once, each later one 50 ms after the one before.
+    assert!(start.elapsed() >= Duration::from_millis(100), "{:?}", start.elapsed());
+}
+
+#[test]
+fn checkpoint_flags_apply_to_jsonl_runs() {
+    let argv = [
+        "checkvote", "--jsonl", "ballots.jsonl", "--poll-id", "1001", "--checkpoint", "run.json",
+        "--resume", "--min-proof-interval", "30",
+    ];
+    let args = Cli::try_parse_from(argv).unwrap().prove.unwrap();
+    assert_eq!(args.checkpoint.as_deref().and_then(|path| path.to_str()), Some("run.json"));
+    assert!(args.resume);
+    assert_eq!(args.min_proof_interval, Some(30));
+
+    let without_jsonl = ["checkvote", "b.json", "--poll-id", "1", "--checkpoint", "run.json"];
+    assert!(Cli::try_parse_from(without_jsonl).is_err());
+    let without_checkpoint = ["checkvote", "--jsonl", "b.jsonl", "--poll-id", "1", "--resume"];
+    assert!(Cli::try_parse_from(without_checkpoint).is_err());
+}
@@ -0,0 +1,427 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Argument parsing and the parameters derived from it, without running the prover.
+
+use std::time::Duration;
+
+use castvote_core::{AgeRange, BallotType, Date, MessageFormat};
+use clap::Parser;
+use k256_example::{
+    cli::{Args, Cli, Command, Curve, OutputFormat, Scheme},
+    estimate::DEFAULT_CYCLES_PER_SECOND,
+    prove::{ProveMode, ProverChoice, RetryPolicy},
+};
+use k256_methods::{
+    ED25519_VERIFY_ID, K256_VERIFY_ENCRYPTED_ID, K256_VERIFY_ID, K256_VERIFY_MULTI_ID, P256_VERIFY_ID,
+};
+
+fn parse_args(argv: &[&str]) -> Args {
+    let cli = Cli::try_parse_from([&["checkvote"], argv].concat()).unwrap();
+    assert!(cli.command.is_none());
+    cli.prove.unwrap()
+}
+
+#[test]
+fn defaults_to_k256_single_choice() {
+    let args = parse_args(&["ballot.json", "--poll-id", "1001"]);
+    assert_eq!(args.filenames, ["ballot.json"]);
+    assert_eq!(args.poll_id(), 1001);
+    assert_eq!((args.scheme, args.curve), (Scheme::Ecdsa, Curve::K256));
+    assert_eq!(args.method().1, K256_VERIFY_ID);
+
+    let params = args.vote_params().unwrap();
+    assert_eq!(params.message_format, MessageFormat::Sha256);
+    assert_eq!(params.ballot_type, BallotType::Single);
+    assert!(params.as_of.is_some());
+}
+
+#[test]
+fn poll_id_is_required_unless_the_ballot_carries_one() {
+    let ballot = concat!(env!("CARGO_MANIFEST_DIR"), "/res/person.json");
+    let mut args = parse_args(&[ballot]);
+    let err = args.resolve_poll_id().unwrap_err();
+    assert!(err.to_string().contains("--poll-id is required"), "{err}");
+}
+
+#[test]
+fn scheme_and_curve_select_the_method() {
+    let p256 = parse_args(&["b.json", "--poll-id", "1", "--curve", "p256"]);
+    assert_eq!(p256.method().1, P256_VERIFY_ID);
+    let ed25519 = parse_args(&["b.json", "--poll-id", "1", "--scheme", "ed25519"]);
+    assert_eq!(ed25519.method().1, ED25519_VERIFY_ID);
+}
+
+#[test]
+fn vote_params_reflect_flags() {
+    let args = parse_args(&[
+        "b.json",
+        "--poll-id",
+        "1",
+        "--as-of",
+        "2025-06-30",
+        "--eip191",
+        "--ballot-type",
+        "ranked",
+        "--options",
+        "yes,no",
+    ]);
+    let params = args.vote_params().unwrap();
+    assert_eq!(params.as_of, Some("2025-06-30".parse::<Date>().unwrap()));
+    assert_eq!(params.message_format, MessageFormat::Eip191);
+    assert_eq!(params.ballot_type, BallotType::Ranked);
+    assert_eq!(params.options, ["yes", "no"]);
+}
+
+#[test]
+fn age_range_defaults_and_flags() {
+    let params = parse_args(&["b.json", "--poll-id", "1"]).vote_params().unwrap();
+    assert_eq!(params.age_range, AgeRange { min: 18, max: 120 });
+    let args = parse_args(&["b.json", "--poll-id", "1", "--min-age", "16", "--max-age", "99"]);
+    assert_eq!(args.vote_params().unwrap().age_range, AgeRange { min: 16, max: 99 });
+    let inverted = parse_args(&["b.json", "--poll-id", "1", "--min-age", "50", "--max-age", "40"]);
+    assert!(inverted.vote_params().is_err());
+}
+
+#[test]
+fn prove_retries_default_to_none() {
+    assert_eq!(parse_args(&["b.json", "--poll-id", "1"]).retry_policy().retries, 0);
+    let args = parse_args(&["b.json", "--poll-id", "1", "--prove-retries", "3"]);
+    assert_eq!(args.retry_policy(), RetryPolicy::new(3));
+}
+
+#[test]
+fn prove_timeout_limits_each_attempt() {
+    assert_eq!(parse_args(&["b.json", "--poll-id", "1"]).retry_policy().timeout, None);
+    let args = parse_args(&["b.json", "--poll-id", "1", "--prove-timeout", "90"]);
+    assert_eq!(args.retry_policy().timeout, Some(Duration::from_secs(90)));
+    let zero = ["checkvote", "b.json", "--poll-id", "1", "--prove-timeout", "0"];
+    assert!(Cli::try_parse_from(zero).is_err());
+}
+
+#[test]
+fn bonsai_poll_interval_requires_the_bonsai_prover() {
+    let args = parse_args(&["b.json", "--poll-id", "1"]);
+    assert_eq!(args.prover().unwrap(), ProverChoice::Default);
+    let args = parse_args(&["b.json", "--poll-id", "1", "--prover", "bonsai"]);
+    let poll_interval = Duration::from_secs(1);
+    assert_eq!(args.prover().unwrap(), ProverChoice::Bonsai { poll_interval });
+    let argv = ["b.json", "--poll-id", "1", "--prover", "bonsai", "--bonsai-poll-interval", "15"];
+    let poll_interval = Duration::from_secs(15);
+    assert_eq!(parse_args(&argv).prover().unwrap(), ProverChoice::Bonsai { poll_interval });
+    let args = parse_args(&["b.json", "--poll-id", "1", "--bonsai-poll-interval", "15"]);
+    let err = args.prover().unwrap_err().to_string();
+    assert_eq!(err, "--bonsai-poll-interval requires --prover bonsai");
+}
+
+#[test]
+fn proof_type_selects_succinct_or_groth16() {
+    assert_eq!(parse_args(&["b.json", "--poll-id", "1"]).prove_mode(), ProveMode::Groth16);
+    let args = parse_args(&["b.json", "--poll-id", "1", "--proof-type", "succinct"]);
+    assert_eq!(args.prove_mode(), ProveMode::Succinct);
+
+    let cli = Cli::try_parse_from(["checkvote", "compress", "--receipt", "succinct.bin"]).unwrap();
+    let Some(Command::Compress { receipt, out_dir, .. }) = cli.command else {
+        panic!("expected the compress subcommand");
+    };
+    assert_eq!((receipt.to_str(), out_dir.to_str()), (Some("succinct.bin"), Some("/tmp/castvote")));
+    assert!(Cli::try_parse_from(["checkvote", "compress"]).is_err());
+}
+
+#[test]
+fn bundle_is_written_by_proving_and_read_by_verify_bundle() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--bundle", "vote.bundle.json"]);
+    assert_eq!(args.bundle.as_deref().and_then(|path| path.to_str()), Some("vote.bundle.json"));
+    assert!(Cli::try_parse_from(["checkvote", "b.json", "--no-prove", "--bundle", "x"]).is_err());
+
+    let cli = Cli::try_parse_from(["checkvote", "verify-bundle", "vote.bundle.json"]).unwrap();
+    let Some(Command::VerifyBundle { bundle, .. }) = cli.command else {
+        panic!("expected the verify-bundle subcommand");
+    };
+    assert_eq!(bundle.to_str(), Some("vote.bundle.json"));
+    assert!(Cli::try_parse_from(["checkvote", "verify-bundle"]).is_err());
+}
+
+#[test]
+fn eip191_needs_secp256k1() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--eip191", "--curve", "p256"]);
+    assert!(args.vote_params().is_err());
+}
+
+#[test]
+fn quiet_and_output_apply_to_every_command() {
+    let cli = Cli::try_parse_from(["checkvote", "b.json", "--poll-id", "1"]).unwrap();
+    assert_eq!((cli.quiet, cli.output), (false, OutputFormat::Text));
+
+    let cli = Cli::try_parse_from(["checkvote", "-q", "b.json", "--poll-id", "1", "--output", "json"])
+        .unwrap();
+    assert_eq!((cli.quiet, cli.output), (true, OutputFormat::Json));
+
+    let argv = ["checkvote", "estimate", "b.json", "--poll-id", "1", "--quiet", "--output", "json"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!((cli.quiet, cli.output), (true, OutputFormat::Json));
+    assert!(Cli::try_parse_from(["checkvote", "b.json", "--poll-id", "1", "--output", "xml"]).is_err());
+}
+
+#[test]
+fn report_subcommand_needs_no_ballot() {
+    let cli = Cli::try_parse_from(["checkvote", "report", "--journals", "*.dat", "--out", "r.csv"])
+        .unwrap();
+    assert!(cli.prove.is_none());
+    assert!(matches!(cli.command, Some(Command::Report { .. })));
+}
+
+#[test]
+fn age_buckets_require_demographics() {
+    let argv = ["checkvote", "report", "--journals", "*.dat", "--out", "r.csv"];
+    let buckets = ["--age-buckets", "18,30"];
+    assert!(Cli::try_parse_from([argv.as_slice(), &buckets].concat()).is_err());
+    let demographics = ["--demographics", "d.json"];
+    let cli = Cli::try_parse_from([argv.as_slice(), &demographics, &buckets].concat()).unwrap();
+    let Some(Command::Report { age_buckets, .. }) = cli.command else {
+        panic!("expected the report subcommand");
+    };
+    assert_eq!(age_buckets, [18, 30]);
+}
+
+#[test]
+fn estimate_subcommand_takes_the_proving_arguments() {
+    let argv = ["checkvote", "estimate", "b.json", "--poll-id", "7", "--curve", "p256"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert!(cli.prove.is_none());
+    let Some(Command::Estimate { args, cycles_per_second, cost_per_mcycle }) = cli.command else {
+        panic!("expected the estimate subcommand");
+    };
+    assert_eq!((args.poll_id(), args.curve), (7, Curve::P256));
+    assert_eq!(args.method().1, P256_VERIFY_ID);
+    assert_eq!((cycles_per_second, cost_per_mcycle), (DEFAULT_CYCLES_PER_SECOND, None));
+
+    assert!(Cli::try_parse_from(["checkvote", "estimate", "--poll-id", "7"]).is_err());
+    let rate = ["checkvote", "estimate", "b.json", "--poll-id", "7", "--cycles-per-second", "0"];
+    assert!(Cli::try_parse_from(rate).is_err());
+}
+
+#[test]
+fn simulate_onchain_takes_the_artifacts() {
+    let argv = ["checkvote", "simulate-onchain", "--seal", "s.dat", "--journal", "j.dat"];
+    assert!(Cli::try_parse_from(argv).is_err());
+    let cli = Cli::try_parse_from([argv.as_slice(), &["--image-id", "id.dat"]].concat()).unwrap();
+    assert!(cli.prove.is_none());
+    let Some(Command::SimulateOnchain { seal, image_id, .. }) = cli.command else {
+        panic!("expected the simulate-onchain subcommand");
+    };
+    assert_eq!((seal.to_str(), image_id.as_str()), (Some("s.dat"), "id.dat"));
+}
+
+#[test]
+fn expected_verifier_params_is_a_full_digest() {
+    let argv = ["checkvote", "simulate-onchain", "--seal", "s", "--journal", "j"];
+    let digest = "ab".repeat(32);
+    let argv = [&argv[..], &["--image-id", "i", "--expected-verifier-params", &digest]].concat();
+    let Some(Command::SimulateOnchain { expected_verifier_params, .. }) =
+        Cli::try_parse_from(argv).unwrap().command
+    else {
+        panic!("expected the simulate-onchain subcommand");
+    };
+    assert_eq!(expected_verifier_params.map(|digest| digest.to_string()), Some(digest.clone()));
+
+    let prefixed = format!("0x{digest}");
+    let argv = ["checkvote", "verify-bundle", "b.json", "--expected-verifier-params", &prefixed];
+    let Some(Command::VerifyBundle { expected_verifier_params, .. }) =
+        Cli::try_parse_from(argv).unwrap().command
+    else {
+        panic!("expected the verify-bundle subcommand");
+    };
+    assert!(expected_verifier_params.is_some());
+
+    // Only the 4-byte selector is not enough.
+    let argv = ["checkvote", "verify-bundle", "b.json", "--expected-verifier-params", "abababab"];
+    assert!(Cli::try_parse_from(argv).is_err());
+}
+
+#[test]
+fn verify_sig_hashes_the_raw_file_by_default() {
+    let argv = ["checkvote", "verify-sig", "--pubkey", "cGs=", "--message-file", "m.txt"];
+    assert!(Cli::try_parse_from(argv).is_err());
+    let cli = Cli::try_parse_from([argv.as_slice(), &["--signature", "c2ln"]].concat()).unwrap();
+    assert!(cli.prove.is_none());
+    let Some(Command::VerifySig { pubkey, message_format, eip191, scheme, .. }) = cli.command else {
+        panic!("expected the verify-sig subcommand");
+    };
+    assert_eq!(pubkey, "cGs=");
+    assert_eq!(MessageFormat::from(message_format), MessageFormat::Raw);
+    assert_eq!((eip191, scheme), (false, Scheme::Ecdsa));
+
+    let both = [argv.as_slice(), &["--signature", "c2ln", "--eip191", "--message-format", "raw"]];
+    assert!(Cli::try_parse_from(both.concat()).is_err());
+}
+
+#[test]
+fn sig_out_requires_no_prove() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--no-prove", "--sig-out", "sigs.json"]);
+    assert!(args.no_prove);
+    assert_eq!(args.sig_out.as_deref(), Some(std::path::Path::new("sigs.json")));
+    assert!(!parse_args(&["b.json", "--poll-id", "1"]).no_prove);
+
+    let without_no_prove = ["checkvote", "b.json", "--poll-id", "1", "--sig-out", "s.json"];
+    assert!(Cli::try_parse_from(without_no_prove).is_err());
+}
+
+#[test]
+fn receipt_out_needs_a_proof() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--receipt-out", "r.json.gz"]);
+    assert_eq!(args.receipt_out.as_deref(), Some(std::path::Path::new("r.json.gz")));
+    let signing_only = ["checkvote", "b.json", "--poll-id", "1", "--no-prove", "--receipt-out", "r"];
+    assert!(Cli::try_parse_from(signing_only).is_err());
+}
+
+#[test]
+fn no_cache_disables_the_receipt_cache() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--cache-dir", "/tmp/receipts"]);
+    assert_eq!(args.proof_cache().unwrap().dir(), std::path::Path::new("/tmp/receipts"));
+    assert!(parse_args(&["b.json", "--poll-id", "1", "--no-cache"]).proof_cache().is_none());
+}
+
+#[test]
+fn external_signature_needs_a_public_key() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--signature", "c2ln", "--public-key", "a2V5"]);
+    assert_eq!(args.external_signature().unwrap().as_deref(), Some("c2ln"));
+    assert_eq!(args.public_key.as_deref(), Some("a2V5"));
+    assert_eq!(parse_args(&["b.json", "--poll-id", "1"]).external_signature().unwrap(), None);
+
+    for argv in [
+        &["checkvote", "b.json", "--poll-id", "1", "--signature", "c2ln"][..],
+        &["checkvote", "b.json", "--poll-id", "1", "--public-key", "a2V5"],
+        &[
+            "checkvote", "b.json", "--poll-id", "1", "--signature", "c2ln", "--public-key", "a2V5",
+            "--no-prove",
+        ],
+        &[
+            "checkvote", "b.json", "--poll-id", "1", "--signature", "c2ln", "--signature-file", "s.txt",
+            "--public-key", "a2V5",
+        ],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
+
+#[test]
+fn signature_file_is_read_and_trimmed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signature.txt");
+    std::fs::write(&path, "c2ln\n").unwrap();
+    let path = path.to_str().unwrap();
+    let with_file = |path| {
+        parse_args(&["b.json", "--poll-id", "1", "--signature-file", path, "--public-key", "a2V5"])
+    };
+    assert_eq!(with_file(path).external_signature().unwrap().as_deref(), Some("c2ln"));
+
+    let missing = dir.path().join("missing.txt");
+    assert!(with_file(missing.to_str().unwrap()).external_signature().is_err());
+}
+
+#[test]
+fn jsonl_replaces_the_filenames() {
+    let args = parse_args(&["--jsonl", "ballots.jsonl", "--poll-id", "1"]);
+    assert!(args.filenames.is_empty());
+    assert_eq!(args.jsonl.as_deref(), Some(std::path::Path::new("ballots.jsonl")));
+
+    let signed = parse_args(&[
+        "--jsonl", "b.jsonl", "--poll-id", "1", "--jsonl-signatures", "s.txt", "--public-key", "a2V5",
+    ]);
+    assert!(signed.jsonl_signatures.is_some());
+
+    for argv in [
+        &["checkvote", "b.json", "--jsonl", "b.jsonl", "--poll-id", "1"][..],
+        &["checkvote", "--jsonl", "b.jsonl", "--poll-id", "1", "--no-prove"],
+        &["checkvote", "--jsonl", "b.jsonl", "--poll-id", "1", "--jsonl-signatures", "s.txt"],
+        &[
+            "checkvote", "b.json", "--poll-id", "1", "--jsonl-signatures", "s.txt", "--public-key",
+            "a2V5",
+        ],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
+
+#[test]
+fn encrypt_to_selects_the_encrypted_method() {
+    let args = parse_args(&["b.json", "--poll-id", "1", "--encrypt-to", "a2V5"]);
+    assert_eq!(args.encrypt_to.as_deref(), Some("a2V5"));
+    assert_eq!(args.method().1, K256_VERIFY_ENCRYPTED_ID);
+    args.vote_params().unwrap();
+
+    let p256 = parse_args(&["b.json", "--poll-id", "1", "--encrypt-to", "a2V5", "--curve", "p256"]);
+    assert!(p256.vote_params().is_err());
+    let no_prove = ["checkvote", "b.json", "--poll-id", "1", "--encrypt-to", "a2V5", "--no-prove"];
+    assert!(Cli::try_parse_from(no_prove).is_err());
+}
+
+#[test]
+fn input_replaces_the_ballot_and_poll_flags() {
+    let args = parse_args(&["--input", "input.json"]);
+    assert_eq!(args.input.as_deref(), Some(std::path::Path::new("input.json")));
+    assert!(args.filenames.is_empty() && args.poll_id.is_none());
+    let dump = parse_args(&["b.json", "--poll-id", "1", "--dump-input", "input.json"]);
+    assert!(dump.dump_input.is_some());
+
+    for argv in [
+        &["checkvote", "b.json", "--input", "input.json"][..],
+        &["checkvote", "--jsonl", "b.jsonl", "--input", "input.json"],
+        &["checkvote", "--input", "input.json", "--dump-input", "again.json"],
+        &["checkvote", "b.json", "--poll-id", "1", "--dump-input", "i.json", "--no-prove"],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
+
+#[test]
+fn from_env_replaces_the_ballot_and_prover_flags() {
+    let mut args = parse_args(&["--from-env", "repro.json"]);
+    assert_eq!(args.from_env.as_deref(), Some(std::path::Path::new("repro.json")));
+    args.resolve_poll_id().unwrap();
+    assert!(args.filenames.is_empty() && args.poll_id.is_none());
+    let dump = parse_args(&["b.json", "--poll-id", "1", "--dump-env", "repro.json"]);
+    assert!(dump.dump_env.is_some());
+
+    for argv in [
+        &["checkvote", "b.json", "--from-env", "repro.json"][..],
+        &["checkvote", "--from-env", "repro.json", "--input", "input.json"],
+        &["checkvote", "--from-env", "repro.json", "--proof-type", "succinct"],
+        &["checkvote", "--from-env", "repro.json", "--prover", "bonsai"],
+        &["checkvote", "--from-env", "repro.json", "--dump-env", "again.json"],
+        &["checkvote", "b.json", "--poll-id", "1", "--dump-env", "e.json", "--no-prove"],
+        &["checkvote", "b.json", "--poll-id", "1", "--dump-env", "e.json", "--dump-input", "i"],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
+
+#[test]
+fn polls_select_the_multi_poll_method() {
+    let args = parse_args(&["b.json", "--polls", "1001,1002"]);
+    assert_eq!(args.polls, [1001, 1002]);
+    assert_eq!(args.method().1, K256_VERIFY_MULTI_ID);
+    args.vote_params().unwrap();
+
+    let p256 = parse_args(&["b.json", "--polls", "1001,1002", "--curve", "p256"]);
+    assert!(p256.vote_params().is_err());
+    for argv in [
+        &["checkvote", "b.json", "--polls", "1001", "--poll-id", "1001"][..],
+        &["checkvote", "b.json", "--polls", "1001", "--no-prove"],
+        &["checkvote", "b.json", "--polls", "1001", "--encrypt-to", "a2V5"],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
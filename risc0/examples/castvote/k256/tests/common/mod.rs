@@ -0,0 +1,69 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers shared by the integration tests that run a guest on a ballot signed with the demo key.
+
+// Each test crate uses only some of these.
+#![allow(dead_code)]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, RevealInfo, VoteParams};
+use k256_example::{
+    decode_journal, keys::K256_PRIVATE_KEY, sign_ballot, BallotSigner, SoftwareSigner,
+};
+use k256_methods::K256_VERIFY_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+use serde::Serialize;
+
+/// The poll the signed inputs vote in.
+pub const POLL_ID: u64 = 1001;
+
+/// The guest input: signature, ballot, poll ID and Base64 public key.
+pub type Input = (String, String, u64, String);
+
+/// The demo secp256k1 voter key.
+pub fn signer() -> SoftwareSigner {
+    SoftwareSigner::from_base64(K256_PRIVATE_KEY).unwrap()
+}
+
+/// Base64 of the demo key's uncompressed public key.
+pub fn public_key() -> String {
+    general_purpose::STANDARD.encode(signer().public_key().to_encoded_point(false))
+}
+
+/// The guest input for the canonical `ballot` in [POLL_ID], signed with the demo key.
+pub fn signed_input(ballot: &str) -> anyhow::Result<Input> {
+    signed_input_for(ballot, POLL_ID)
+}
+
+/// The guest input for the canonical `ballot` in `poll_id`, signed with the demo key.
+pub fn signed_input_for(ballot: &str, poll_id: u64) -> anyhow::Result<Input> {
+    let ballot = canonicalize_ballot(ballot)?;
+    Ok((sign_ballot(&signer(), &ballot)?, ballot, poll_id, public_key()))
+}
+
+/// Runs `elf` on `input` and `params` and returns the journal.
+pub fn execute_journal(
+    elf: &[u8],
+    input: &impl Serialize,
+    params: &VoteParams,
+) -> anyhow::Result<Vec<u8>> {
+    let env = ExecutorEnv::builder().write(input)?.write(params)?.build()?;
+    Ok(default_executor().execute(env, elf)?.journal.bytes)
+}
+
+/// Signs `ballot` with the demo key, runs the k256 guest on it and decodes the journal.
+pub fn execute(ballot: &str, params: &VoteParams) -> anyhow::Result<RevealInfo> {
+    decode_journal(&execute_journal(K256_VERIFY_ELF, &signed_input(ballot)?, params)?)
+}
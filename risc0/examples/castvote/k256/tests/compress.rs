@@ -0,0 +1,63 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Split proving: the succinct receipt file and what `compress` reads back from it.
+
+use std::fs;
+
+use k256_example::prove::{read_receipt_file, receipt_method, write_receipt_file};
+use k256_methods::K256_VERIFY_ID;
+use risc0_zkvm::{sha::Digestible, FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+fn fake_receipt(image_id: [u32; 8], journal: &[u8]) -> Receipt {
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+    Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal.to_vec())
+}
+
+#[test]
+fn receipt_file_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("succinct.bin");
+    let receipt = fake_receipt(K256_VERIFY_ID, b"journal");
+
+    write_receipt_file(&receipt, &path).unwrap();
+    let read = read_receipt_file(&path).unwrap();
+    assert_eq!(read.journal.bytes, b"journal");
+    assert_eq!(read.claim().unwrap().digest(), receipt.claim().unwrap().digest());
+
+    fs::write(&path, b"garbage").unwrap();
+    let err = read_receipt_file(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("is not a receipt"), "{err:#}");
+}
+
+#[test]
+fn receipt_method_is_found_by_image_id() {
+    let (_, image_id) = receipt_method(&fake_receipt(K256_VERIFY_ID, b"journal")).unwrap();
+    assert_eq!(image_id, K256_VERIFY_ID);
+
+    let err = receipt_method(&fake_receipt([9; 8], b"journal")).unwrap_err();
+    assert!(err.to_string().contains("not a method of this build"), "{err}");
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn dev_mode_receipt_compresses_to_the_same_journal() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let receipt = fake_receipt(K256_VERIFY_ID, b"journal");
+
+    let (compressed, _, image_id) = k256_example::prove::compress_receipt(&receipt).unwrap();
+    assert_eq!(image_id, K256_VERIFY_ID);
+    assert_eq!(compressed.journal.bytes, b"journal");
+}
@@ -0,0 +1,114 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable output for scripts.
+
+use std::time::Duration;
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    parse_ballot, EncryptedChoice, EncryptedReveal, HomomorphicReveal, JournalCodec, RevealInfo,
+    VoteParams,
+};
+use k256_example::{
+    console::{summary_nullifiers, ProofSummary},
+    decode_journal,
+    timings::{Phase, Timings},
+};
+use k256_methods::{K256_VERIFY_ENCRYPTED_ID, K256_VERIFY_HOMOMORPHIC_ID};
+
+fn summary() -> ProofSummary {
+    ProofSummary {
+        seal: "00".into(),
+        journal: "11".into(),
+        journal_digest: "55".into(),
+        image_id: "22".into(),
+        nullifier: Some("33".into()),
+        nullifiers: None,
+        out_dir: "/tmp/castvote".into(),
+        receipt: None,
+        bundle: None,
+        author_signature: None,
+        timings: None,
+    }
+}
+
+#[test]
+fn single_ballot_summary_has_one_nullifier() {
+    let json = serde_json::to_value(summary()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "seal": "00",
+            "journal": "11",
+            "journal_digest": "55",
+            "image_id": "22",
+            "nullifier": "33",
+            "out_dir": "/tmp/castvote",
+        })
+    );
+}
+
+fn reveal() -> RevealInfo {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": false}"#).unwrap();
+    RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap()
+}
+
+#[test]
+fn cbor_encrypted_summary_has_the_wrapped_nullifier() {
+    let encrypted_choice = EncryptedChoice {
+        ephemeral_key: "AA==".into(),
+        nonce: "AQ==".into(),
+        ciphertext: "Ag==".into(),
+    };
+    let encrypted = EncryptedReveal { reveal: reveal(), encrypted_choice };
+    let journal = encrypted.encode_with(JournalCodec::Cbor).abi_encode();
+    // The reveal data is nested under `reveal`, so it does not pass for a plain reveal.
+    assert!(decode_journal(&journal).is_err());
+    let nullifiers = summary_nullifiers(K256_VERIFY_ENCRYPTED_ID, &journal).unwrap();
+    assert_eq!(nullifiers, (Some(reveal().nullifier), None));
+}
+
+#[test]
+fn cbor_homomorphic_summary_has_the_wrapped_nullifier() {
+    let homomorphic =
+        HomomorphicReveal { reveal: reveal(), election_key: vec![2; 33], ciphertexts: Vec::new() };
+    let journal = homomorphic.encode_with(JournalCodec::Cbor).abi_encode();
+    assert!(decode_journal(&journal).is_err());
+    let nullifiers = summary_nullifiers(K256_VERIFY_HOMOMORPHIC_ID, &journal).unwrap();
+    assert_eq!(nullifiers, (Some(reveal().nullifier), None));
+}
+
+#[test]
+fn batch_summary_lists_the_nullifiers() {
+    let batch = ProofSummary {
+        nullifier: None,
+        nullifiers: Some(vec!["33".into(), "44".into()]),
+        receipt: Some("receipt.json.gz".into()),
+        ..summary()
+    };
+    let json = serde_json::to_value(batch).unwrap();
+    assert!(json.get("nullifier").is_none());
+    assert_eq!(json["nullifiers"], serde_json::json!(["33", "44"]));
+    assert_eq!(json["receipt"], "receipt.json.gz");
+}
+
+#[test]
+fn proof_summary_carries_the_timings_in_seconds() {
+    let mut timings = Timings::default();
+    timings.add(Phase::Signing, Duration::from_millis(250));
+    timings.add(Phase::Proving, Duration::from_secs(90));
+    let json = serde_json::to_value(ProofSummary { timings: Some(timings), ..summary() }).unwrap();
+    assert_eq!(json["timings"], serde_json::json!({"signing": 0.25, "proving": 90.0}));
+}
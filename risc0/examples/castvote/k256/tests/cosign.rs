@@ -0,0 +1,149 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots that need a threshold of authorized co-signers.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    authorized_set_bytes, canonical_k256_key, count_cosigners, voter_key_hash, CosignError,
+    CosignInput, CosignedReveal, VoteParams,
+};
+use k256::ecdsa::SigningKey;
+use k256_example::{decode_cosigned_journal, sign_ballot, SoftwareSigner};
+use k256_methods::K256_COSIGN_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+/// Already canonical, so the signatures cover exactly these bytes.
+const BALLOT: &str = r#"{"age":40,"id":"proposal-7","is_student":false}"#;
+
+fn keys() -> [SigningKey; 3] {
+    [1u8, 2, 3].map(|seed| SigningKey::from_bytes((&[seed; 32]).into()).unwrap())
+}
+
+fn public_key(key: &SigningKey) -> Vec<u8> {
+    canonical_k256_key(key.verifying_key())
+}
+
+#[test]
+fn exactly_threshold_is_accepted() {
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    assert_eq!(count_cosigners(&authorized, &authorized[..2], 2), Ok(2));
+    assert_eq!(count_cosigners(&authorized, &authorized, 2), Ok(3));
+}
+
+#[test]
+fn below_threshold_is_rejected() {
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    assert_eq!(
+        count_cosigners(&authorized, &authorized[..1], 2),
+        Err(CosignError::BelowThreshold { found: 1, threshold: 2 })
+    );
+    let outsider = public_key(&SigningKey::from_bytes((&[9u8; 32]).into()).unwrap());
+    assert_eq!(
+        count_cosigners(&authorized, &[authorized[0].clone(), outsider], 2),
+        Err(CosignError::BelowThreshold { found: 1, threshold: 2 })
+    );
+}
+
+#[test]
+fn duplicate_signer_counts_once() {
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    let twice = [authorized[0].clone(), authorized[0].clone()];
+    assert_eq!(
+        count_cosigners(&authorized, &twice, 2),
+        Err(CosignError::BelowThreshold { found: 1, threshold: 2 })
+    );
+}
+
+#[test]
+fn threshold_must_be_reachable() {
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    assert_eq!(count_cosigners(&authorized, &authorized, 0), Err(CosignError::ZeroThreshold));
+    assert_eq!(
+        count_cosigners(&authorized, &authorized, 4),
+        Err(CosignError::ThresholdTooHigh { threshold: 4, authorized: 3 })
+    );
+    let repeated = [authorized[0].clone(), authorized[0].clone()];
+    assert!(count_cosigners(&repeated, &repeated, 2).is_err());
+}
+
+#[test]
+fn authorized_set_ignores_order_and_repeats() {
+    let [a, b, c] = keys().map(|key| public_key(&key));
+    let set = authorized_set_bytes(&[a.clone(), b.clone(), c.clone()]);
+    assert_eq!(authorized_set_bytes(&[c.clone(), a.clone(), b.clone(), a.clone()]), set);
+    assert_ne!(authorized_set_bytes(&[a, b]), set);
+}
+
+#[test]
+fn cosigned_reveal_round_trips() {
+    let reveal = castvote_core::RevealInfo::new(
+        &castvote_core::parse_ballot(BALLOT).unwrap(),
+        1001,
+        &[],
+        &VoteParams::default(),
+    )
+    .unwrap();
+    let cosigned = CosignedReveal { reveal, valid_signatures: 2, threshold: 2 };
+    assert_eq!(CosignedReveal::decode(&cosigned.encode()).unwrap(), cosigned);
+}
+
+/// Signs `BALLOT` with each of `signers` and runs the co-signing guest with all three demo keys
+/// authorized.
+fn execute(signers: &[&SigningKey], threshold: u32) -> anyhow::Result<CosignedReveal> {
+    let encode = |key: &SigningKey| general_purpose::STANDARD.encode(public_key(key));
+    let signatures = signers
+        .iter()
+        .map(|key| {
+            let signature = sign_ballot(&SoftwareSigner::new((*key).clone()), BALLOT)?;
+            Ok((encode(key), signature))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let input = CosignInput {
+        ballot: BALLOT.to_string(),
+        poll_id: 1001,
+        authorized_keys: keys().iter().map(encode).collect(),
+        signatures,
+        threshold,
+    };
+    let env = ExecutorEnv::builder().write(&input)?.write(&VoteParams::default())?.build()?;
+    let session = default_executor().execute(env, K256_COSIGN_ELF)?;
+    decode_cosigned_journal(&session.journal.bytes)
+}
+
+#[test]
+fn guest_accepts_exactly_threshold() {
+    let [a, b, _] = keys();
+    let cosigned = execute(&[&a, &b], 2).unwrap();
+    assert_eq!((cosigned.valid_signatures, cosigned.threshold), (2, 2));
+
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    let set_hash = hex::encode(voter_key_hash(&authorized_set_bytes(&authorized)));
+    assert_eq!(cosigned.reveal.voter_key_hash, set_hash);
+}
+
+#[test]
+fn guest_rejects_below_threshold() {
+    let [a, _, _] = keys();
+    assert!(execute(&[&a], 2).is_err());
+}
+
+#[test]
+fn guest_counts_a_duplicate_signer_once() {
+    let [a, b, _] = keys();
+    assert!(execute(&[&a, &a], 2).is_err());
+    assert_eq!(execute(&[&a, &a, &b], 2).unwrap().valid_signatures, 2);
+}
@@ -0,0 +1,120 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `diff-journal`: two journals compared field by field, with differing versions flagged.
+
+use alloy_sol_types::SolValue;
+use castvote_core::{parse_ballot, JournalCodec, RevealInfo, VoteParams, JOURNAL_VERSION};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    diff::{diff_journals, reveal_fields, FieldDiff, JournalVersion},
+    read_journal_file, write_hex_file,
+};
+use serde_json::json;
+
+const BALLOT: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes"}"#;
+
+fn reveal() -> RevealInfo {
+    let params = VoteParams { options: vec!["yes".into(), "no".into()], ..Default::default() };
+    RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[1; 33], &params).unwrap()
+}
+
+#[test]
+fn identical_journals_have_no_differences() {
+    let journal = reveal().encode().abi_encode();
+    let diff = diff_journals(&journal, &journal).unwrap();
+    assert!(diff.is_identical());
+    assert!(!diff.versions_differ());
+    assert!(diff.fields.is_empty());
+}
+
+#[test]
+fn journals_differing_in_one_field_report_that_field() {
+    let dir = tempfile::tempdir().unwrap();
+    let (expected, actual) = (dir.path().join("a.dat"), dir.path().join("b.dat"));
+    write_hex_file(&expected, &reveal().encode().abi_encode()).unwrap();
+    let other = RevealInfo { poll_id: 1002, ..reveal() };
+    write_hex_file(&actual, &other.encode().abi_encode()).unwrap();
+
+    let (expected, actual) = (read_journal_file(expected), read_journal_file(actual));
+    let diff = diff_journals(&expected.unwrap(), &actual.unwrap()).unwrap();
+    assert!(!diff.is_identical());
+    assert!(!diff.versions_differ());
+    assert_eq!(
+        diff.fields,
+        [FieldDiff { field: "poll_id", left: json!(1001), right: json!(1002) }]
+    );
+}
+
+#[test]
+fn different_versions_are_flagged() {
+    // Version 12 is the current version without the trailing `abstained`, encoded as a `None` byte.
+    let unflagged = RevealInfo { abstained: None, ..reveal() };
+    let mut v12 = unflagged.encode();
+    assert_eq!(v12.pop(), Some(0));
+    v12[0] = 12;
+    let current = unflagged.encode().abi_encode();
+
+    let diff = diff_journals(&v12.abi_encode(), &current).unwrap();
+    assert!(diff.versions_differ());
+    assert!(!diff.is_identical());
+    assert_eq!(diff.left_version, JournalVersion { version: 12, codec: JournalCodec::Bincode });
+    assert_eq!(diff.right_version.version, JOURNAL_VERSION);
+    assert!(diff.fields.is_empty());
+
+    let diff = diff_journals(&v12.abi_encode(), &reveal().encode().abi_encode()).unwrap();
+    assert_eq!(
+        diff.fields,
+        [FieldDiff { field: "abstained", left: json!(null), right: json!(false) }]
+    );
+}
+
+#[test]
+fn different_codecs_are_flagged() {
+    let bincode = reveal().encode().abi_encode();
+    let cbor = reveal().encode_with(JournalCodec::Cbor).abi_encode();
+    let diff = diff_journals(&bincode, &cbor).unwrap();
+    assert!(diff.versions_differ());
+    assert!(diff.fields.is_empty());
+    assert_eq!(diff.right_version.to_string(), format!("{JOURNAL_VERSION} (cbor)"));
+}
+
+#[test]
+fn every_committed_field_is_compared() {
+    let serialized = serde_json::to_value(reveal()).unwrap();
+    let mut committed: Vec<_> = serialized.as_object().unwrap().keys().cloned().collect();
+    let mut compared: Vec<_> =
+        reveal_fields(&reveal()).into_iter().map(|(field, _)| field.to_string()).collect();
+    committed.sort();
+    compared.sort();
+    assert_eq!(compared, committed);
+}
+
+#[test]
+fn malformed_journals_are_rejected() {
+    let journal = reveal().encode().abi_encode();
+    assert!(diff_journals(&journal, b"not a journal").is_err());
+    assert!(diff_journals(&Vec::<u8>::new().abi_encode(), &journal).is_err());
+}
+
+#[test]
+fn parses_diff_journal_command() {
+    let cli = Cli::try_parse_from(["checkvote", "diff-journal", "a.dat", "b.dat"]).unwrap();
+    let Some(Command::DiffJournal { left, right }) = cli.command else {
+        panic!("expected diff-journal");
+    };
+    assert_eq!((left.to_str(), right.to_str()), (Some("a.dat"), Some("b.dat")));
+    assert!(Cli::try_parse_from(["checkvote", "diff-journal", "a.dat"]).is_err());
+}
@@ -0,0 +1,139 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `--dry-run`: every check of a real run, and not a single file written.
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::MessageFormat;
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command, Curve, Scheme},
+    nullifier_store::NullifierStore,
+    resign::{dry_run_resign, KeyRotation},
+    sign, SignedBallot, VoterKey,
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn key(byte: u8) -> VoterKey {
+    VoterKey::from_base64(Scheme::Ecdsa, Curve::K256, &general_purpose::STANDARD.encode([byte; 32]))
+        .unwrap()
+}
+
+fn write_signed(path: &Path, key: &VoterKey, poll_ids: &[u64]) {
+    let entries: Vec<_> = poll_ids
+        .iter()
+        .map(|&poll_id| SignedBallot {
+            ballot: BALLOT.into(),
+            poll_id,
+            signature: sign(key, BALLOT, MessageFormat::Sha256).unwrap(),
+            public_key: key.public_key_base64(),
+        })
+        .collect();
+    fs::write(path, serde_json::to_string(&entries).unwrap()).unwrap();
+}
+
+fn rotation<'a>(old_public_key: &'a str, new_key: &'a VoterKey) -> KeyRotation<'a> {
+    KeyRotation {
+        scheme: Scheme::Ecdsa,
+        curve: Curve::K256,
+        message_format: MessageFormat::Sha256,
+        old_public_key,
+        new_key,
+    }
+}
+
+#[test]
+fn resign_lists_the_files_without_creating_the_output_directory() {
+    let (old, new) = (key(1), key(2));
+    let (input, scratch) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    write_signed(&input.path().join("a.json"), &old, &[1, 2]);
+    write_signed(&input.path().join("b.json"), &old, &[3]);
+    let out = scratch.path().join("rotated");
+
+    let old_public_key = old.public_key_base64();
+    let pattern = format!("{}/*.json", input.path().display());
+    let summary = dry_run_resign(&pattern, &rotation(&old_public_key, &new), &out).unwrap();
+    assert_eq!(summary.resigned, 3);
+    assert!(summary.rejected.is_empty());
+    assert_eq!(summary.files, [out.join("a.json"), out.join("b.json")]);
+    assert!(!out.exists());
+    assert_eq!(fs::read_dir(scratch.path()).unwrap().count(), 0);
+    assert_eq!(fs::read_dir(input.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn resign_still_checks_every_signature() {
+    let (old, new, other) = (key(1), key(2), key(3));
+    let (input, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    write_signed(&input.path().join("a.json"), &old, &[1]);
+    write_signed(&input.path().join("b.json"), &other, &[2]);
+
+    let old_public_key = old.public_key_base64();
+    let pattern = format!("{}/*.json", input.path().display());
+    let summary = dry_run_resign(&pattern, &rotation(&old_public_key, &new), out.path()).unwrap();
+    assert_eq!(summary.resigned, 0);
+    assert!(summary.files.is_empty());
+    assert_eq!(summary.rejected.len(), 1);
+    assert_eq!(fs::read_dir(out.path()).unwrap().count(), 0);
+
+    let error =
+        dry_run_resign(&pattern, &rotation(&old_public_key, &new), input.path()).unwrap_err();
+    assert!(error.to_string().contains("would overwrite"), "{error}");
+}
+
+#[test]
+fn checking_nullifiers_creates_no_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nullifiers.json");
+    let store = NullifierStore::open(&path).unwrap();
+    assert_eq!(store.path(), path);
+    store.check(&[(1, "ab".into()), (2, "ab".into())]).unwrap();
+    assert!(store.check(&[(1, "ab".into()), (1, "ab".into())]).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn with_prover_requires_dry_run() {
+    let cli = Cli::try_parse_from(["checkvote", "b.json", "--poll-id", "1", "--dry-run"]).unwrap();
+    let args = cli.prove.unwrap();
+    assert!(args.dry_run && !args.with_prover);
+    let argv = ["checkvote", "b.json", "--poll-id", "1", "--dry-run", "--with-prover"];
+    assert!(Cli::try_parse_from(argv).unwrap().prove.unwrap().with_prover);
+
+    for argv in [
+        &["checkvote", "b.json", "--poll-id", "1", "--with-prover"][..],
+        &["checkvote", "b.json", "--poll-id", "1", "--dry-run", "--no-prove"],
+        &["checkvote", "b.json", "--poll-id", "1", "--dry-run", "--dump-input", "i.json"],
+        &["checkvote", "b.json", "--poll-id", "1", "--dry-run", "--explain"],
+    ] {
+        assert!(Cli::try_parse_from(argv).is_err(), "{argv:?}");
+    }
+}
+
+#[test]
+fn parses_resign_dry_run() {
+    let cli = Cli::try_parse_from([
+        "checkvote", "resign", "--old-pub", "BLZg", "--new-priv", "new.key", "--ballots",
+        "signed/*.json", "--out-dir", "rotated", "--dry-run",
+    ])
+    .unwrap();
+    let Some(Command::Resign { dry_run, .. }) = cli.command else {
+        panic!("expected the resign command");
+    };
+    assert!(dry_run);
+}
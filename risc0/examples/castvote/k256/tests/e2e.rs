@@ -0,0 +1,109 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The full sign → prove → verify → decode pipeline, with a fake receipt from dev mode.
+#![cfg(feature = "prove")]
+
+use std::fs;
+
+use castvote_core::{compute_nullifier, parse_ballot, voter_key_hash};
+use risc0_zkvm::{FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+use sha2::{Digest, Sha256};
+use clap::Parser;
+use k256_example::{
+    cli::Cli,
+    decode_journal, import_keys, load_ballot,
+    prove::{encode_seal, journal_digest, prove_vote, prove_vote_with, write_artifacts, ProveMode},
+    read_hex_file, read_journal_file, sign,
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+#[test]
+fn dev_mode_pipeline_round_trips() {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+
+    let dir = tempfile::tempdir().unwrap();
+    let ballot_path = dir.path().join("ballot.json");
+    fs::write(&ballot_path, BALLOT).unwrap();
+    let out_dir = dir.path().join("out");
+    fs::create_dir(&out_dir).unwrap();
+
+    let args = Cli::try_parse_from([
+        "checkvote",
+        ballot_path.to_str().unwrap(),
+        "--poll-id",
+        "1001",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+    ])
+    .unwrap()
+    .prove
+    .unwrap();
+
+    let params = args.vote_params().unwrap();
+    let key = import_keys(&args).unwrap();
+    let ballot = load_ballot(&args.filenames[0], &params).unwrap();
+    let signature = sign(&key, &ballot, params.message_format).unwrap();
+
+    let (elf, image_id) = args.method();
+    let input = (signature, ballot.as_str(), args.poll_id(), key.public_key_base64());
+    let receipt = prove_vote(&input, &params, elf, image_id).unwrap();
+    receipt.verify(image_id).unwrap();
+    write_artifacts(&receipt, elf, image_id, &args.out_dir).unwrap();
+
+    let artifacts =
+        ["seal.dat", "journal.dat", "journal_abi.dat", "journal_digest.dat", "image_id.dat"];
+    for artifact in artifacts {
+        assert!(out_dir.join(artifact).is_file(), "{artifact} missing");
+    }
+    let journal = read_journal_file(out_dir.join("journal.dat")).unwrap();
+    assert_eq!(journal, receipt.journal.bytes);
+    let digest = read_hex_file(out_dir.join("journal_digest.dat")).unwrap();
+    assert_eq!(digest, Sha256::digest(&journal).to_vec());
+
+    let reveal = decode_journal(&journal).unwrap();
+    let expected = parse_ballot(BALLOT).unwrap();
+    assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&expected.id, 1001)));
+    assert_eq!(reveal.age, 30);
+    assert_eq!(reveal.poll_id, 1001);
+    assert_eq!(reveal.voter_key_hash, hex::encode(voter_key_hash(&key.canonical_public_key())));
+}
+
+#[test]
+fn reproducible_mode_gives_identical_receipts() {
+    let argv = ["checkvote", "b.json", "--poll-id", "1001", "--as-of", "2025-06-30"];
+    let args = Cli::try_parse_from(argv).unwrap().prove.unwrap();
+    let params = args.vote_params().unwrap();
+    let key = import_keys(&args).unwrap();
+    let (elf, image_id) = args.method();
+
+    let prove = || {
+        let signature = sign(&key, BALLOT, params.message_format).unwrap();
+        let input = (signature, BALLOT, args.poll_id(), key.public_key_base64());
+        prove_vote_with(&input, &params, elf, image_id, ProveMode::Reproducible).unwrap()
+    };
+    let (first, second) = (prove(), prove());
+
+    assert_eq!(first.journal.bytes, second.journal.bytes);
+    assert_eq!(encode_seal(&first).unwrap(), encode_seal(&second).unwrap());
+}
+
+#[test]
+fn journal_digest_is_the_sha256_of_the_journal() {
+    let journal = b"journal bytes".to_vec();
+    let claim = ReceiptClaim::ok([7; 8], journal.clone());
+    let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal.clone());
+    assert_eq!(journal_digest(&receipt).as_bytes(), &Sha256::digest(&journal)[..]);
+}
@@ -0,0 +1,118 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end checks of the ECDSA guest methods, run in the executor.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, voter_key_hash, MessageFormat, RevealInfo, VoteParams};
+use clap::Parser;
+use k256_example::{cli::Cli, decode_journal, import_keys, sign, VoterKey};
+use k256_methods::K256_VERIFY_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn run(
+    elf: &[u8],
+    signature: String,
+    message: &str,
+    public_key: String,
+) -> anyhow::Result<RevealInfo> {
+    let env = ExecutorEnv::builder()
+        .write(&(signature, message, 1001u64, public_key))?
+        .write(&VoteParams::default())?
+        .build()?;
+    let session = default_executor().execute(env, elf)?;
+    decode_journal(&session.journal.bytes)
+}
+
+/// Signs the canonical `BALLOT` with the demo key for `curve` and runs that curve's guest on `message`.
+fn execute(curve: &str, message: &str) -> anyhow::Result<RevealInfo> {
+    let args = Cli::try_parse_from(["checkvote", "b.json", "--poll-id", "1001", "--curve", curve])?
+        .prove
+        .unwrap();
+    let key = import_keys(&args)?;
+    let signature = sign(&key, &canonicalize_ballot(BALLOT)?, MessageFormat::Sha256)?;
+    run(args.method().0, signature, message, key.public_key_base64())
+}
+
+fn key_hash(key: &VoterKey) -> String {
+    hex::encode(voter_key_hash(&key.canonical_public_key()))
+}
+
+#[test]
+fn signed_ballot_is_accepted() {
+    for curve in ["k256", "p256"] {
+        let reveal = execute(curve, BALLOT).unwrap();
+        assert_eq!((reveal.age, reveal.poll_id), (30, 1001), "{curve}");
+    }
+}
+
+#[test]
+fn tampered_ballot_is_rejected() {
+    let tampered = BALLOT.replace("\"age\": 30", "\"age\": 31");
+    for curve in ["k256", "p256"] {
+        assert!(execute(curve, &tampered).is_err(), "{curve}");
+    }
+}
+
+#[test]
+fn two_voters_produce_distinct_proofs_with_one_guest() {
+    let voters = [[1u8; 32], [2u8; 32]]
+        .map(|seed| VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&seed).into()).unwrap()));
+
+    let reveals = voters.each_ref().map(|key| {
+        let signature = sign(key, &canonicalize_ballot(BALLOT).unwrap(), MessageFormat::Sha256).unwrap();
+        run(K256_VERIFY_ELF, signature, BALLOT, key.public_key_base64()).unwrap()
+    });
+
+    for (key, reveal) in voters.iter().zip(&reveals) {
+        assert_eq!(reveal.voter_key_hash, key_hash(key));
+    }
+    assert_ne!(reveals[0].voter_key_hash, reveals[1].voter_key_hash);
+}
+
+#[test]
+fn compressed_and_uncompressed_keys_commit_the_same_hash() {
+    let key = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap());
+    let VoterKey::K256(signing_key) = &key else { unreachable!() };
+    let compressed = general_purpose::STANDARD.encode(signing_key.verifying_key().to_sec1_bytes());
+    assert_eq!(general_purpose::STANDARD.decode(&compressed).unwrap().len(), 33);
+
+    let signature = sign(&key, &canonicalize_ballot(BALLOT).unwrap(), MessageFormat::Sha256).unwrap();
+    let uncompressed = run(K256_VERIFY_ELF, signature.clone(), BALLOT, key.public_key_base64()).unwrap();
+    let compressed = run(K256_VERIFY_ELF, signature, BALLOT, compressed).unwrap();
+    assert_eq!(uncompressed.voter_key_hash, compressed.voter_key_hash);
+    assert_eq!(compressed.voter_key_hash, key_hash(&key));
+}
+
+#[test]
+fn signature_from_another_key_is_rejected() {
+    let alice = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap());
+    let bob = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[2u8; 32]).into()).unwrap());
+    let signature = sign(&alice, &canonicalize_ballot(BALLOT).unwrap(), MessageFormat::Sha256).unwrap();
+    assert!(run(K256_VERIFY_ELF, signature, BALLOT, bob.public_key_base64()).is_err());
+}
+
+#[test]
+fn reformatted_ballot_verifies_the_same() {
+    let reordered = r#"{"is_student":true,"age":30,"name":"Alice Wonderland","id":123}"#;
+    assert_eq!(canonicalize_ballot(reordered).unwrap(), canonicalize_ballot(BALLOT).unwrap());
+    for curve in ["k256", "p256"] {
+        let pretty = execute(curve, BALLOT).unwrap();
+        let compact = execute(curve, reordered).unwrap();
+        assert_eq!(pretty.encode(), compact.encode(), "{curve}");
+    }
+}
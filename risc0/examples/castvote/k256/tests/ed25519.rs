@@ -0,0 +1,94 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end checks of the Ed25519 guest method, run in the executor.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, compute_nullifier, parse_ballot, voter_key_hash, RevealInfo, VoteParams};
+use ed25519_dalek::{Signer, SigningKey};
+use k256_methods::ED25519_VERIFY_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+use sha2::{Digest, Sha256};
+
+/// Demo Ed25519 secret key seed.
+const SECRET_KEY: &str = "B6tP4z9L8Ub/Obd3fH79CcPSSfjp92ipaLJGojSfkyc=";
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn signing_key() -> SigningKey {
+    let bytes = general_purpose::STANDARD.decode(SECRET_KEY).unwrap();
+    SigningKey::from_bytes(bytes.as_slice().try_into().unwrap())
+}
+
+fn sign(key: &SigningKey, message: &str) -> String {
+    let signature = key.sign(&Sha256::digest(canonicalize_ballot(message).unwrap().as_bytes()));
+    general_purpose::STANDARD.encode(signature.to_bytes())
+}
+
+fn execute(signature: &str, message: &str, poll_id: u64, public_key: &str) -> anyhow::Result<Vec<u8>> {
+    let env = ExecutorEnv::builder()
+        .write(&(signature, message, poll_id, public_key))?
+        .write(&VoteParams::default())?
+        .build()?;
+    let session = default_executor().execute(env, ED25519_VERIFY_ELF)?;
+    Ok(session.journal.bytes)
+}
+
+#[test]
+fn valid_signature_commits_reveal_info() {
+    let key = signing_key();
+    let public_key = general_purpose::STANDARD.encode(key.verifying_key().to_bytes());
+
+    let journal = execute(&sign(&key, BALLOT), BALLOT, 1001, &public_key).unwrap();
+
+    let encoded = Vec::<u8>::abi_decode(&journal).unwrap();
+    let reveal = RevealInfo::decode(&encoded).unwrap();
+    let ballot = parse_ballot(BALLOT).unwrap();
+    assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&ballot.id, 1001)));
+    assert_eq!(Some(reveal.age), ballot.age);
+    assert_eq!(reveal.is_student, ballot.is_student);
+    assert_eq!(reveal.poll_id, 1001);
+    assert_eq!(reveal.voter_key_hash, hex::encode(voter_key_hash(key.verifying_key().as_bytes())));
+}
+
+#[test]
+fn any_voter_key_is_accepted() {
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = general_purpose::STANDARD.encode(key.verifying_key().to_bytes());
+
+    let journal = execute(&sign(&key, BALLOT), BALLOT, 1001, &public_key).unwrap();
+
+    let reveal = RevealInfo::decode(&Vec::<u8>::abi_decode(&journal).unwrap()).unwrap();
+    assert_eq!(reveal.voter_key_hash, hex::encode(voter_key_hash(key.verifying_key().as_bytes())));
+}
+
+#[test]
+fn tampered_ballot_is_rejected() {
+    let key = signing_key();
+    let public_key = general_purpose::STANDARD.encode(key.verifying_key().to_bytes());
+    let signature = sign(&key, BALLOT);
+
+    let tampered = BALLOT.replace("\"age\": 30", "\"age\": 31");
+    assert!(execute(&signature, &tampered, 1001, &public_key).is_err());
+}
+
+#[test]
+fn signature_from_another_key_is_rejected() {
+    let other = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = general_purpose::STANDARD.encode(signing_key().verifying_key().to_bytes());
+
+    assert!(execute(&sign(&other, BALLOT), BALLOT, 1001, &public_key).is_err());
+}
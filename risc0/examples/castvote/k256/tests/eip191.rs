@@ -0,0 +1,93 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! EIP-191 `personal_sign` interop, checked against a wallet-library test vector and in the
+//! executor.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, eip191_hash, MessageFormat, VoteParams};
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, SigningKey};
+use k256_example::{parse_wallet_signature, sign_ballot, sign_ballot_eip191, SoftwareSigner};
+use k256_methods::K256_VERIFY_ELF;
+
+mod common;
+
+/// `Account.sign_message(encode_defunct(text="I♥SF"), private_key)` from the eth-account
+/// documentation. The message is 6 bytes but only 4 characters long.
+const WALLET_MESSAGE: &str = "I♥SF";
+const WALLET_PRIVATE_KEY: &str = "b25c7db31feed9122727bf0939dc769a96564b2de4c4726d035b36ecf1e5b364";
+const WALLET_MESSAGE_HASH: &str = "1476abb745d423bf09273f1afd887d951181d25adc66c4834a70491911b7f750";
+const WALLET_SIGNATURE: &str = "0xe6ca9bba58c88611fad66a6ce8f996908195593807c4b38bd528d2cff09d4eb33e5bfbbf4d3e39b1a2fd816a7680c19ebebaf3a141b239934ad43cb33fcec8ce1c";
+
+const BALLOT: &str = r#"{"id": 7, "name": "Zoë Ångström", "age": 33, "is_student": false}"#;
+
+fn wallet_key() -> SigningKey {
+    SigningKey::from_slice(&hex::decode(WALLET_PRIVATE_KEY).unwrap()).unwrap()
+}
+
+#[test]
+fn digest_counts_utf8_bytes() {
+    assert_eq!(hex::encode(eip191_hash(WALLET_MESSAGE.as_bytes())), WALLET_MESSAGE_HASH);
+}
+
+#[test]
+fn wallet_signature_verifies() {
+    let signature = parse_wallet_signature(WALLET_SIGNATURE).unwrap();
+    wallet_key()
+        .verifying_key()
+        .verify_prehash(&eip191_hash(WALLET_MESSAGE.as_bytes()), &signature)
+        .unwrap();
+}
+
+#[test]
+fn host_signature_matches_wallet() {
+    // RFC 6979 nonces make the signature deterministic, so the host must reproduce the wallet's.
+    let signer = SoftwareSigner::new(wallet_key());
+    let signature = sign_ballot_eip191(&signer, WALLET_MESSAGE).unwrap();
+    let expected = parse_wallet_signature(WALLET_SIGNATURE).unwrap();
+    assert_eq!(general_purpose::STANDARD.decode(signature).unwrap(), expected.to_bytes().to_vec());
+}
+
+#[test]
+fn malformed_wallet_signature_is_rejected() {
+    assert!(parse_wallet_signature("0x1234").is_err());
+    assert!(parse_wallet_signature("not hex").is_err());
+}
+
+fn execute(signature: String, message_format: MessageFormat) -> anyhow::Result<()> {
+    let params = VoteParams { message_format, ..Default::default() };
+    let input = (signature, BALLOT, common::POLL_ID, common::public_key());
+    common::execute_journal(K256_VERIFY_ELF, &input, &params)?;
+    Ok(())
+}
+
+fn canonical_ballot() -> String {
+    canonicalize_ballot(BALLOT).unwrap()
+}
+
+#[test]
+fn guest_accepts_eip191_ballot() {
+    let signature = sign_ballot_eip191(&common::signer(), &canonical_ballot()).unwrap();
+    execute(signature, MessageFormat::Eip191).unwrap();
+}
+
+#[test]
+fn guest_rejects_mismatched_format() {
+    let signer = common::signer();
+    let eip191 = sign_ballot_eip191(&signer, &canonical_ballot()).unwrap();
+    assert!(execute(eip191, MessageFormat::Sha256).is_err());
+    let sha256 = sign_ballot(&signer, &canonical_ballot()).unwrap();
+    assert!(execute(sha256, MessageFormat::Eip191).is_err());
+}
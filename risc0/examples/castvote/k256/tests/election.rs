@@ -0,0 +1,123 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Nullifier salts derived per election from an authority seed.
+#![cfg(feature = "prove")]
+
+use std::fs;
+
+use castvote_core::{
+    compute_nullifier, derive_nullifier_salt, parse_ballot, salted_nullifier, ElectionScope,
+    RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256_example::cli::Cli;
+
+mod common;
+
+const SEED: [u8; 32] = [7; 32];
+
+const BALLOT: &str = r#"{"age":30,"id":123,"is_student":true}"#;
+
+fn scoped(election_id: &str) -> VoteParams {
+    let election = ElectionScope { election_id: election_id.into(), authority_seed: SEED.to_vec() };
+    VoteParams { election: Some(election), ..Default::default() }
+}
+
+#[test]
+fn each_election_gets_its_own_salt() {
+    let spring = derive_nullifier_salt(&SEED, "2025-spring");
+    assert_eq!(spring.len(), 64);
+    assert_eq!(spring, derive_nullifier_salt(&SEED, "2025-spring"));
+    assert_ne!(spring, derive_nullifier_salt(&SEED, "2025-autumn"));
+    assert_ne!(spring, derive_nullifier_salt(&[8; 32], "2025-spring"));
+}
+
+#[test]
+fn nullifier_uses_the_election_salt_and_commits_the_id() {
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let spring = RevealInfo::new(&ballot, 1001, &[], &scoped("2025-spring")).unwrap();
+    let autumn = RevealInfo::new(&ballot, 1001, &[], &scoped("2025-autumn")).unwrap();
+    let unscoped = RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap();
+
+    let salt = derive_nullifier_salt(&SEED, "2025-spring");
+    assert_eq!(spring.nullifier, hex::encode(salted_nullifier(&salt, &ballot.id, 1001)));
+    assert_ne!(spring.nullifier, autumn.nullifier);
+    assert_eq!(unscoped.nullifier, hex::encode(compute_nullifier(&ballot.id, 1001)));
+
+    assert_eq!(spring.election_id.as_deref(), Some("2025-spring"));
+    assert_eq!(unscoped.election_id, None);
+    assert_eq!(RevealInfo::decode(&spring.encode()).unwrap(), spring);
+}
+
+#[test]
+fn seed_stays_out_of_debug_output() {
+    let debug = format!("{:?}", scoped("2025-spring"));
+    assert!(debug.contains("2025-spring"), "{debug}");
+    assert!(!debug.contains("7, 7"), "{debug}");
+}
+
+#[test]
+fn version_2_journals_decode_without_an_election() {
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let mut reveal = RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap();
+    reveal.message_format = None;
+    reveal.student_required = None;
+    reveal.abstained = None;
+    // Version 2 is the current version without the trailing `election_id`, `poll_hash`, `message_format`,
+    // `student_required`, `policy_hash`, `poll_name`, `receipt_id`, `id_commitment`,
+    // `max_poll_id`, `author_key_hash` and `abstained`, each encoded here as a single `None` byte.
+    let mut v2 = reveal.encode();
+    assert_eq!(v2.split_off(v2.len() - 11), [0; 11]);
+    v2[0] = 2;
+    assert_eq!(RevealInfo::decode(&v2).unwrap(), reveal);
+}
+
+#[test]
+fn cli_reads_the_authority_seed() {
+    let dir = tempfile::tempdir().unwrap();
+    let seed_file = dir.path().join("seed");
+    fs::write(&seed_file, SEED).unwrap();
+    let seed = seed_file.to_str().unwrap();
+
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "b.json", "--poll-id", "1"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let args = parse(&["--election-id", "2025-spring", "--authority-seed-file", seed]).unwrap();
+    assert_eq!(args.vote_params().unwrap().election, scoped("2025-spring").election);
+    assert_eq!(parse(&[]).unwrap().vote_params().unwrap().election, None);
+
+    assert!(parse(&["--election-id", "2025-spring"]).is_err());
+    // Without an election ID the seed is refused once the parameters are built, since a saved
+    // `--input` of an election takes the seed alone.
+    let err = parse(&["--authority-seed-file", seed]).unwrap().vote_params().unwrap_err();
+    assert!(format!("{err:#}").contains("needs --election-id"), "{err:#}");
+
+    fs::write(&seed_file, b"short").unwrap();
+    let args = parse(&["--election-id", "2025-spring", "--authority-seed-file", seed]).unwrap();
+    let err = args.vote_params().unwrap_err();
+    assert!(format!("{err:#}").contains("at least 32"), "{err:#}");
+}
+
+#[test]
+fn guest_commits_the_election_scoped_nullifier() {
+    let params = scoped("2025-spring");
+    let committed = common::execute(BALLOT, &params).unwrap();
+
+    let expected = RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[], &params).unwrap();
+    assert_eq!(committed.nullifier, expected.nullifier);
+    assert_eq!(committed.election_id.as_deref(), Some("2025-spring"));
+}
@@ -0,0 +1,165 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Exponential-ElGamal votes: encryption, aggregation and trustee decryption.
+#![cfg(feature = "prove")]
+
+use castvote_core::{
+    aggregate, combine_all_decryptions, election_key, encrypt_vote, one_hot, parse_randomness,
+    partial_decrypt, BallotType, ElGamalError, ElGamalInput, HomomorphicReveal, VoteParams,
+};
+use k256::{PublicKey, Scalar, SecretKey};
+use k256_example::{
+    decode_homomorphic_journal,
+    homomorphic::{aggregate_reveals, elgamal_input},
+};
+use k256_methods::K256_VERIFY_HOMOMORPHIC_ELF;
+
+mod common;
+
+fn trustee(seed: u8) -> SecretKey {
+    SecretKey::from_bytes((&[seed; 32]).into()).unwrap()
+}
+
+fn trustees() -> Vec<SecretKey> {
+    (1..=3).map(trustee).collect()
+}
+
+fn key_of(trustees: &[SecretKey]) -> PublicKey {
+    election_key(&trustees.iter().map(SecretKey::public_key).collect::<Vec<_>>()).unwrap()
+}
+
+fn options() -> Vec<String> {
+    vec!["yes".into(), "no".into(), "maybe".into()]
+}
+
+fn params() -> VoteParams {
+    VoteParams { options: options(), ..Default::default() }
+}
+
+fn randomness(seed: u64, count: usize) -> Vec<Scalar> {
+    (1..=count as u64).map(|i| Scalar::from(seed * 100 + i)).collect()
+}
+
+fn encrypt(key: &PublicKey, choice: &[&str], seed: u64) -> Vec<castvote_core::Ciphertext> {
+    let selection: Vec<String> = choice.iter().map(|&c| c.into()).collect();
+    let vote = one_hot(&selection, BallotType::Approval, &options()).unwrap();
+    encrypt_vote(key, &vote, &randomness(seed, vote.len())).unwrap()
+}
+
+#[test]
+fn trustees_decrypt_the_totals() {
+    let trustees = trustees();
+    let key = key_of(&trustees);
+    let votes = vec![
+        encrypt(&key, &["yes"], 1),
+        encrypt(&key, &["yes", "maybe"], 2),
+        encrypt(&key, &["no"], 3),
+        encrypt(&key, &["yes"], 4),
+    ];
+    let tally = aggregate(&votes).unwrap();
+    let partials: Vec<_> = trustees.iter().map(|t| partial_decrypt(t, &tally).unwrap()).collect();
+    assert_eq!(combine_all_decryptions(&tally, &partials, votes.len() as u64).unwrap(), [3, 1, 1]);
+}
+
+#[test]
+fn every_trustee_is_needed() {
+    let trustees = trustees();
+    let key = key_of(&trustees);
+    let tally = aggregate(&[encrypt(&key, &["yes"], 1), encrypt(&key, &["no"], 2)]).unwrap();
+    let partials: Vec<_> = trustees[..2].iter().map(|t| partial_decrypt(t, &tally).unwrap()).collect();
+    assert!(matches!(
+        combine_all_decryptions(&tally, &partials, 2),
+        Err(ElGamalError::TotalOutOfRange { .. })
+    ));
+    assert_eq!(combine_all_decryptions(&tally, &[], 2), Err(ElGamalError::Empty));
+}
+
+#[test]
+fn one_hot_follows_the_ballot_type() {
+    let selection = vec!["maybe".to_string()];
+    assert_eq!(one_hot(&selection, BallotType::Single, &options()).unwrap(), [0, 0, 1]);
+    assert_eq!(
+        one_hot(&selection, BallotType::Ranked, &options()),
+        Err(ElGamalError::UnsupportedBallotType(BallotType::Ranked))
+    );
+}
+
+#[test]
+fn malformed_inputs_are_rejected() {
+    let key = key_of(&trustees());
+    assert_eq!(
+        encrypt_vote(&key, &[1, 0, 0], &randomness(1, 2)),
+        Err(ElGamalError::LengthMismatch { expected: 3, actual: 2 })
+    );
+    assert_eq!(parse_randomness(&[0; 32]), Err(ElGamalError::InvalidScalar));
+    assert_eq!(parse_randomness(&[0xff; 32]), Err(ElGamalError::InvalidScalar));
+
+    let short = encrypt_vote(&key, &[1, 0], &randomness(2, 2)).unwrap();
+    assert_eq!(
+        aggregate(&[encrypt(&key, &["yes"], 1), short]),
+        Err(ElGamalError::LengthMismatch { expected: 3, actual: 2 })
+    );
+    let mut garbled = encrypt(&key, &["yes"], 3);
+    garbled[0].alpha = vec![0; 33];
+    assert_eq!(aggregate(&[garbled]), Err(ElGamalError::InvalidPoint));
+}
+
+#[test]
+fn host_randomness_is_fresh_and_valid() {
+    let key = key_of(&trustees());
+    let first = elgamal_input(&key, 3);
+    let second = elgamal_input(&key, 3);
+    assert_eq!(first.randomness.len(), 3);
+    assert_ne!(first.randomness, second.randomness);
+    assert_eq!(first.election_key, key.to_sec1_bytes().to_vec());
+    assert!(first.randomness.iter().all(|r| parse_randomness(r).is_ok()));
+}
+
+fn execute(
+    ballot: &str,
+    input: &ElGamalInput,
+    params: &VoteParams,
+) -> anyhow::Result<HomomorphicReveal> {
+    let input = (common::signed_input(ballot)?, input);
+    let journal = common::execute_journal(K256_VERIFY_HOMOMORPHIC_ELF, &input, params)?;
+    decode_homomorphic_journal(&journal)
+}
+
+#[test]
+fn guest_commits_an_encrypted_vote() {
+    let trustees = trustees();
+    let input = elgamal_input(&key_of(&trustees), 3);
+    let ballot = r#"{"id": 7, "age": 33, "is_student": false, "choice": "no"}"#;
+    let committed = execute(ballot, &input, &params()).unwrap();
+    assert!(committed.reveal.selection.is_empty());
+    assert_eq!(HomomorphicReveal::decode(&committed.encode()).unwrap(), committed);
+
+    let tally = aggregate_reveals(std::slice::from_ref(&committed), 1001).unwrap();
+    let partials: Vec<_> = trustees.iter().map(|t| partial_decrypt(t, &tally).unwrap()).collect();
+    assert_eq!(combine_all_decryptions(&tally, &partials, 1).unwrap(), [0, 1, 0]);
+    assert!(aggregate_reveals(&[committed], 1002).is_err());
+}
+
+#[test]
+fn guest_rejects_blank_and_ranked_ballots() {
+    let input = elgamal_input(&key_of(&trustees()), 3);
+    let blank = r#"{"id": 7, "age": 33, "is_student": false}"#;
+    assert!(execute(blank, &input, &params()).is_err());
+
+    let ranked = VoteParams { ballot_type: BallotType::Ranked, ..params() };
+    let ballot = r#"{"id": 7, "age": 33, "is_student": false, "choice": ["no", "yes"]}"#;
+    assert!(execute(ballot, &input, &ranked).is_err());
+}
@@ -0,0 +1,263 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of the Base64 signatures and public keys handed to the guest methods.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use castvote_core::{
+    canonical_k256_key, canonical_p256_key, decode_ed25519_public_key, decode_k256_signature,
+    decode_k256_verifying_key, decode_p256_verifying_key, decode_sec1_public_key, decode_signature, voter_key_hash,
+    EncodingError, MessageFormat,
+};
+use clap::Parser;
+use k256_example::{
+    check_encodings,
+    cli::{Cli, Curve, Scheme},
+    import_keys, sign, verify_signature, VoterKey,
+};
+#[cfg(feature = "prove")]
+use {
+    castvote_core::VoteParams,
+    risc0_zkvm::{default_executor, ExecutorEnv},
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn key_for(flags: &[&str]) -> VoterKey {
+    let argv = [&["checkvote", "b.json", "--poll-id", "1"], flags].concat();
+    import_keys(&Cli::try_parse_from(argv).unwrap().prove.unwrap()).unwrap()
+}
+
+fn signed(key: &VoterKey) -> String {
+    sign(key, BALLOT, MessageFormat::Sha256).unwrap()
+}
+
+#[test]
+fn truncated_signature_is_rejected() {
+    let signature = signed(&key_for(&[]));
+    // 60 Base64 characters decode to 45 bytes.
+    let err = decode_signature(&signature[..60]).unwrap_err();
+    assert_eq!(
+        err,
+        EncodingError::WrongLength { field: "signature", expected: "64", actual: 45 }
+    );
+    assert_eq!(err.to_string(), "signature must decode to 64 bytes, got 45");
+}
+
+#[test]
+fn non_base64_signature_is_rejected() {
+    for signature in ["not base64!", "ab$d", "-_-_"] {
+        let err = decode_signature(signature).unwrap_err();
+        assert!(
+            matches!(err, EncodingError::InvalidBase64 { field: "signature", .. }),
+            "{signature}: {err:?}"
+        );
+        assert!(err.to_string().starts_with("signature is not valid Base64"), "{err}");
+    }
+}
+
+/// The malleated twin `(r, n - s)` of a low-S secp256k1 signature, which verifies just the same.
+fn high_s(signature: &str) -> String {
+    let signature = decode_k256_signature(signature).unwrap();
+    let (r, s) = signature.split_scalars();
+    let twin = k256::ecdsa::Signature::from_scalars(r.to_bytes(), (-*s).to_bytes()).unwrap();
+    STANDARD.encode(twin.to_bytes())
+}
+
+#[test]
+fn high_s_signatures_are_rejected() {
+    let key = key_for(&[]);
+    let signature = signed(&key);
+    let malleated = high_s(&signature);
+    assert!(decode_signature(&malleated).is_ok(), "shape alone is fine");
+    let err = decode_k256_signature(&malleated).unwrap_err();
+    assert_eq!(err, EncodingError::HighS);
+    assert!(err.to_string().contains("lower half of the curve order"), "{err}");
+
+    let public_key = key.public_key_base64();
+    assert_eq!(check_encodings(&key, &malleated, &public_key), Err(EncodingError::HighS));
+    let verified = verify_signature(
+        Scheme::Ecdsa,
+        Curve::K256,
+        BALLOT,
+        &malleated,
+        &public_key,
+        MessageFormat::Sha256,
+    );
+    assert!(verified.is_err());
+}
+
+#[test]
+fn out_of_range_signature_scalars_are_rejected() {
+    assert_eq!(
+        decode_k256_signature(&STANDARD.encode([0u8; 64])).unwrap_err(),
+        EncodingError::InvalidSignature { curve: "secp256k1" }
+    );
+    assert!(decode_k256_signature(&STANDARD.encode([0xffu8; 64])).is_err());
+}
+
+#[test]
+fn whitespace_and_missing_padding_are_normalized() {
+    let signature = signed(&key_for(&[]));
+    let expected = decode_signature(&signature).unwrap();
+    let unpadded = signature.trim_end_matches('=');
+    assert_eq!(decode_signature(unpadded).unwrap(), expected);
+    assert_eq!(decode_signature(&format!(" {signature}\n")).unwrap(), expected);
+}
+
+#[test]
+fn public_keys_are_checked_per_scheme() {
+    let k256 = key_for(&[]).public_key_base64();
+    assert_eq!(decode_sec1_public_key(&k256).unwrap().len(), 65);
+
+    let ed25519 = key_for(&["--scheme", "ed25519"]).public_key_base64();
+    assert!(decode_ed25519_public_key(&ed25519).is_ok());
+    assert!(matches!(
+        decode_sec1_public_key(&ed25519),
+        Err(EncodingError::InvalidSec1Tag(_) | EncodingError::WrongLength { .. })
+    ));
+    assert_eq!(
+        decode_ed25519_public_key(&k256),
+        Err(EncodingError::WrongLength { field: "public key", expected: "32", actual: 65 })
+    );
+}
+
+/// An uncompressed SEC1 point with the right tag and length whose coordinates satisfy neither
+/// curve equation.
+fn off_curve_key() -> String {
+    let mut bytes = [0u8; 65];
+    bytes[0] = 0x04;
+    bytes[32] = 1;
+    bytes[64] = 1;
+    STANDARD.encode(bytes)
+}
+
+#[test]
+fn points_off_the_curve_are_rejected() {
+    let bad = off_curve_key();
+    assert!(decode_sec1_public_key(&bad).is_ok(), "shape alone is fine");
+    let err = decode_k256_verifying_key(&bad).unwrap_err();
+    assert_eq!(err, EncodingError::NotOnCurve { curve: "secp256k1" });
+    assert_eq!(err.to_string(), "public key is not a valid secp256k1 point");
+    assert_eq!(
+        decode_p256_verifying_key(&bad).unwrap_err(),
+        EncodingError::NotOnCurve { curve: "P-256" }
+    );
+
+    // No secp256k1 point has x = 0xff..ff, which is above the field modulus.
+    let compressed = STANDARD.encode([[0x02].as_slice(), &[0xff; 32]].concat());
+    assert!(decode_k256_verifying_key(&compressed).is_err());
+
+    let k256 = key_for(&[]).public_key_base64();
+    assert!(decode_k256_verifying_key(&k256).is_ok());
+    let p256 = key_for(&["--curve", "p256"]).public_key_base64();
+    assert!(decode_p256_verifying_key(&p256).is_ok());
+}
+
+#[test]
+fn point_at_infinity_is_rejected() {
+    let identity = STANDARD.encode([0x00]);
+    assert_eq!(decode_sec1_public_key(&identity), Err(EncodingError::IdentityPoint));
+    assert_eq!(decode_k256_verifying_key(&identity).unwrap_err(), EncodingError::IdentityPoint);
+    assert_eq!(
+        decode_p256_verifying_key(&identity).unwrap_err().to_string(),
+        "public key is the point at infinity"
+    );
+}
+
+#[test]
+fn both_sec1_forms_hash_the_same() {
+    let VoterKey::K256(k256) = key_for(&[]) else { unreachable!() };
+    let uncompressed = STANDARD.encode(k256.verifying_key().to_encoded_point(false));
+    let compressed = STANDARD.encode(k256.verifying_key().to_encoded_point(true));
+    let from_uncompressed = canonical_k256_key(&decode_k256_verifying_key(&uncompressed).unwrap());
+    let from_compressed = canonical_k256_key(&decode_k256_verifying_key(&compressed).unwrap());
+    assert_eq!(from_uncompressed, from_compressed);
+    assert_eq!(from_compressed.len(), 33);
+    assert_eq!(voter_key_hash(&from_uncompressed), voter_key_hash(&from_compressed));
+    assert_eq!(from_compressed, key_for(&[]).canonical_public_key());
+
+    let VoterKey::P256(p256) = key_for(&["--curve", "p256"]) else { unreachable!() };
+    let uncompressed = STANDARD.encode(p256.verifying_key().to_encoded_point(false));
+    let compressed = STANDARD.encode(p256.verifying_key().to_encoded_point(true));
+    assert_eq!(
+        canonical_p256_key(&decode_p256_verifying_key(&uncompressed).unwrap()),
+        canonical_p256_key(&decode_p256_verifying_key(&compressed).unwrap())
+    );
+}
+
+#[test]
+fn host_checks_encodings_before_proving() {
+    for flags in [&[][..], &["--curve", "p256"], &["--scheme", "ed25519"]] {
+        let key = key_for(flags);
+        let signature = signed(&key);
+        let public_key = key.public_key_base64();
+        check_encodings(&key, &signature, &public_key).unwrap();
+        assert!(check_encodings(&key, &signature[..60], &public_key).is_err(), "{flags:?}");
+        assert!(check_encodings(&key, "not base64!", &public_key).is_err(), "{flags:?}");
+        assert!(check_encodings(&key, &signature, &public_key[..20]).is_err(), "{flags:?}");
+    }
+    let key = key_for(&[]);
+    assert_eq!(
+        check_encodings(&key, &signed(&key), &off_curve_key()),
+        Err(EncodingError::NotOnCurve { curve: "secp256k1" })
+    );
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn guest_rejects_malformed_signature() {
+    let key = key_for(&[]);
+    let signature = signed(&key);
+    for bad in [&signature[..60], "not base64!"] {
+        let env = ExecutorEnv::builder()
+            .write(&(bad, BALLOT, 1001u64, key.public_key_base64()))
+            .unwrap()
+            .write(&VoteParams::default())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(default_executor().execute(env, k256_methods::K256_VERIFY_ELF).is_err(), "{bad}");
+    }
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn guest_rejects_a_key_off_the_curve() {
+    let key = key_for(&[]);
+    let env = ExecutorEnv::builder()
+        .write(&(signed(&key), BALLOT, 1001u64, off_curve_key()))
+        .unwrap()
+        .write(&VoteParams::default())
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(default_executor().execute(env, k256_methods::K256_VERIFY_ELF).is_err());
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn guest_rejects_a_high_s_signature() {
+    let key = key_for(&[]);
+    let ballot = castvote_core::canonicalize_ballot(BALLOT).unwrap();
+    let signature = sign(&key, &ballot, MessageFormat::Sha256).unwrap();
+    let env = ExecutorEnv::builder()
+        .write(&(high_s(&signature), BALLOT, 1001u64, key.public_key_base64()))
+        .unwrap()
+        .write(&VoteParams::default())
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(default_executor().execute(env, k256_methods::K256_VERIFY_ELF).is_err());
+}
@@ -0,0 +1,161 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots whose choice is encrypted to the election authority.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    parse_ballot, BallotType, Choice, EncodingError, EncryptedChoice, EncryptedReveal, RevealInfo,
+    VoteParams,
+};
+use k256::SecretKey;
+use k256_example::{
+    decode_encrypted_journal,
+    encryption::{
+        decrypt_choice, encrypt_ballot, encrypt_choice, parse_authority_key, reveal_selection,
+    },
+    validate_ballot,
+};
+use k256_methods::K256_VERIFY_ENCRYPTED_ELF;
+
+mod common;
+
+const BALLOT: &str = r#"{"id": 7, "age": 33, "is_student": false, "choice": ["yes", "maybe"]}"#;
+
+fn authority(seed: u8) -> SecretKey {
+    SecretKey::from_bytes((&[seed; 32]).into()).unwrap()
+}
+
+fn params() -> VoteParams {
+    VoteParams {
+        ballot_type: BallotType::Approval,
+        options: vec!["yes".into(), "no".into(), "maybe".into()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn authority_decrypts_the_choice() {
+    let choice = Choice::Many(vec!["yes".into(), "maybe".into()]);
+    let encrypted = encrypt_choice(&authority(1).public_key(), &choice).unwrap();
+    assert_eq!(decrypt_choice(&authority(1), &encrypted).unwrap(), choice);
+
+    // Fresh randomness every time, so equal choices are not linkable.
+    let again = encrypt_choice(&authority(1).public_key(), &choice).unwrap();
+    assert_ne!(again.ciphertext, encrypted.ciphertext);
+}
+
+#[test]
+fn other_keys_and_tampering_are_detected() {
+    let choice = Choice::One("yes".into());
+    let encrypted = encrypt_choice(&authority(1).public_key(), &choice).unwrap();
+    assert!(decrypt_choice(&authority(2), &encrypted).is_err());
+
+    let mut ciphertext = general_purpose::STANDARD.decode(&encrypted.ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    let tampered = EncryptedChoice {
+        ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        ..encrypted
+    };
+    assert!(decrypt_choice(&authority(1), &tampered).is_err());
+}
+
+#[test]
+fn malformed_ciphertexts_are_rejected() {
+    let encrypted = encrypt_choice(&authority(1).public_key(), &Choice::One("no".into())).unwrap();
+    let encode = |bytes: &[u8]| general_purpose::STANDARD.encode(bytes);
+    let short = EncryptedChoice { ciphertext: encode(&[0u8; 16]), ..encrypted.clone() };
+    assert_eq!(
+        short.decode(),
+        Err(EncodingError::WrongLength { field: "ciphertext", expected: "more than 16", actual: 16 })
+    );
+    let mut bad_tag = general_purpose::STANDARD.decode(&encrypted.ephemeral_key).unwrap();
+    bad_tag[0] = 0x04;
+    let wrong_tag = EncryptedChoice { ephemeral_key: encode(&bad_tag), ..encrypted.clone() };
+    assert_eq!(wrong_tag.decode(), Err(EncodingError::InvalidSec1Tag(0x04)));
+    let short_nonce = EncryptedChoice { nonce: encode(&[0u8; 8]), ..encrypted };
+    assert!(short_nonce.decode().is_err());
+}
+
+#[test]
+fn encrypted_ballot_replaces_the_plaintext_choice() {
+    let encoded_key = general_purpose::STANDARD.encode(authority(1).public_key().to_sec1_bytes());
+    let authority_key = parse_authority_key(&encoded_key).unwrap();
+    let ballot = encrypt_ballot(BALLOT, &authority_key).unwrap();
+    validate_ballot(&ballot).unwrap();
+    assert!(!ballot.contains("yes"), "{ballot}");
+
+    let parsed = parse_ballot(&ballot).unwrap();
+    assert!(parsed.choice.is_none());
+    let choice = decrypt_choice(&authority(1), &parsed.encrypted_choice.unwrap()).unwrap();
+    assert_eq!(choice, Choice::Many(vec!["yes".into(), "maybe".into()]));
+
+    assert!(encrypt_ballot(r#"{"id": 1, "age": 30, "is_student": true}"#, &authority_key).is_err());
+}
+
+#[test]
+fn plaintext_and_encrypted_choice_cannot_be_combined() {
+    let ballot = encrypt_ballot(BALLOT, &authority(1).public_key()).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&ballot).unwrap();
+    value["choice"] = "yes".into();
+    let err = validate_ballot(&value.to_string()).unwrap_err();
+    assert_eq!(err.path, "/encrypted_choice");
+
+    let err = validate_ballot(r#"{"id": 1, "age": 30, "is_student": true, "encrypted_choice": "x"}"#)
+        .unwrap_err();
+    assert_eq!(err.path, "/encrypted_choice");
+}
+
+#[test]
+fn authority_reveals_a_validated_selection() {
+    let ballot = parse_ballot(r#"{"id": 7, "age": 33, "is_student": false}"#).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[], &params()).unwrap();
+    let encrypt = |choice: Choice| EncryptedReveal {
+        reveal: reveal.clone(),
+        encrypted_choice: encrypt_choice(&authority(1).public_key(), &choice).unwrap(),
+    };
+
+    let valid = encrypt(Choice::Many(vec!["maybe".into(), "yes".into()]));
+    assert_eq!(EncryptedReveal::decode(&valid.encode()).unwrap(), valid);
+    let revealed = reveal_selection(&authority(1), &valid, &params()).unwrap();
+    assert_eq!(revealed.selection, ["maybe", "yes"]);
+
+    let unknown = encrypt(Choice::Many(vec!["never".into()]));
+    assert!(reveal_selection(&authority(1), &unknown, &params()).is_err());
+}
+
+fn execute(ballot: &str) -> anyhow::Result<EncryptedReveal> {
+    let input = common::signed_input(ballot)?;
+    let journal = common::execute_journal(K256_VERIFY_ENCRYPTED_ELF, &input, &params())?;
+    decode_encrypted_journal(&journal)
+}
+
+#[test]
+fn guest_commits_the_ciphertext_not_the_choice() {
+    let ballot = encrypt_ballot(BALLOT, &authority(1).public_key()).unwrap();
+    let committed = execute(&ballot).unwrap();
+    assert!(committed.reveal.selection.is_empty());
+    let expected = parse_ballot(&ballot).unwrap().encrypted_choice.unwrap();
+    assert_eq!(committed.encrypted_choice, expected);
+    let revealed = reveal_selection(&authority(1), &committed, &params()).unwrap();
+    assert_eq!(revealed.selection, ["yes", "maybe"]);
+}
+
+#[test]
+fn guest_rejects_plaintext_choices() {
+    let plaintext = castvote_core::canonicalize_ballot(BALLOT).unwrap();
+    assert!(execute(&plaintext).is_err());
+}
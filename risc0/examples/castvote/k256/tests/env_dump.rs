@@ -0,0 +1,156 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Saving executor inputs with `--dump-env` and reading them back for `--from-env`.
+
+use std::time::Duration;
+
+use castvote_core::{ElGamalInput, ElectionScope, VoteParams};
+use k256_example::{
+    input::{
+        read_env, write_env, BallotInput, EnvDump, GuestInput, InputDump, ProverSettings,
+        ENV_FORMAT_VERSION,
+    },
+    prove::{ProveMode, ProverChoice},
+};
+use k256_methods::{K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID};
+use risc0_zkvm::sha::{Digest, Impl, Sha256};
+
+fn ballot_input() -> BallotInput {
+    ("c2ln".into(), r#"{"id":"1","choice":"yes"}"#.into(), 1001, "a2V5".into())
+}
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], ..VoteParams::default() }
+}
+
+fn settings(choice: ProverChoice) -> ProverSettings {
+    ProverSettings {
+        choice,
+        mode: ProveMode::Groth16,
+        dev_mode: false,
+        hashfn: "poseidon2".into(),
+        receipt_kind: "Groth16".into(),
+    }
+}
+
+fn env_dump(input: GuestInput, image_id: [u32; 8], choice: ProverChoice) -> EnvDump {
+    EnvDump::new(input, &params(), image_id, settings(choice)).unwrap()
+}
+
+#[test]
+fn the_digest_covers_the_words_written_to_the_executor() {
+    let elgamal = ElGamalInput { election_key: vec![2; 33], randomness: vec![[7; 32]; 2] };
+    let input = (ballot_input(), elgamal);
+    let dump = env_dump(input.clone().into(), K256_VERIFY_HOMOMORPHIC_ID, ProverChoice::Default);
+
+    // What `ExecutorEnv::builder().write(&input)?.write(&params)?` hands the guest.
+    let mut words = risc0_zkvm::serde::to_vec(&input).unwrap();
+    words.extend(risc0_zkvm::serde::to_vec(&params()).unwrap());
+    assert_eq!(dump.input.input.env_words(&params()).unwrap(), words);
+    assert_eq!(dump.env_digest, Impl::hash_words(&words).to_string());
+    assert_eq!(dump.version, ENV_FORMAT_VERSION);
+    assert_eq!(dump.tool_version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn dumps_round_trip_through_a_private_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("repro.json");
+    let bonsai = ProverChoice::Bonsai { poll_interval: Duration::from_secs(7) };
+    let dump = env_dump(ballot_input().into(), K256_VERIFY_ID, bonsai);
+    write_env(&path, &dump).unwrap();
+
+    let read = read_env(&path).unwrap();
+    assert_eq!(read, dump);
+    read.check_digest().unwrap();
+    assert_eq!(read.input.image_id, Digest::from(K256_VERIFY_ID).to_string());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.to_lowercase().contains("api_key"), "{contents}");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn an_edited_input_fails_the_digest_check() {
+    let mut dump = env_dump(ballot_input().into(), K256_VERIFY_ID, ProverChoice::Default);
+    dump.input.params.options.push("maybe".into());
+    let err = dump.check_digest().unwrap_err().to_string();
+    assert!(err.contains(&dump.env_digest), "{err}");
+
+    let mut dump = env_dump(ballot_input().into(), K256_VERIFY_ID, ProverChoice::Default);
+    dump.env_digest = Digest::ZERO.to_string();
+    assert!(dump.check_digest().is_err());
+}
+
+#[test]
+fn the_digest_covers_the_seed_the_dump_leaves_out() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, seed_file) = (dir.path().join("repro.json"), dir.path().join("seed.bin"));
+    let seed = vec![0x5e; 32];
+    std::fs::write(&seed_file, &seed).unwrap();
+    let election = ElectionScope { election_id: "spring-2025".into(), authority_seed: seed.clone() };
+    let params = VoteParams { election: Some(election), ..params() };
+    let dump =
+        EnvDump::new(ballot_input().into(), &params, K256_VERIFY_ID, settings(ProverChoice::Default))
+            .unwrap();
+    write_env(&path, &dump).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    assert!(!contents.windows(seed.len()).any(|window| window == seed));
+
+    // Without the seed the input hashes differently, and with another seed as well.
+    let mut read = read_env(&path).unwrap();
+    assert!(read.check_digest().is_err());
+    read.input.restore_seed(Some(&seed_file)).unwrap();
+    read.check_digest().unwrap();
+
+    std::fs::write(&seed_file, [0x5f; 32]).unwrap();
+    let mut read = read_env(&path).unwrap();
+    read.input.restore_seed(Some(&seed_file)).unwrap();
+    assert!(read.check_digest().is_err());
+}
+
+#[test]
+fn other_versions_and_input_dumps_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("repro.json");
+    let mut dump = env_dump(ballot_input().into(), K256_VERIFY_ID, ProverChoice::Default);
+    dump.version = ENV_FORMAT_VERSION + 1;
+    write_env(&path, &dump).unwrap();
+    let err = read_env(&path).unwrap_err().to_string();
+    assert!(err.contains("environment format version"), "{err}");
+
+    // An `--dump-input` file has the same version but none of the prover settings.
+    let input = InputDump::new(ballot_input().into(), &params(), K256_VERIFY_ID).unwrap();
+    std::fs::write(&path, serde_json::to_vec(&input).unwrap()).unwrap();
+    assert!(read_env(&path).is_err());
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn settings_follow_the_proof_type() {
+    let reproducible = ProverSettings::current(ProverChoice::Default, ProveMode::Reproducible);
+    assert!(reproducible.dev_mode);
+    assert_eq!(reproducible.receipt_kind, "Groth16");
+    assert_eq!(reproducible.hashfn, "poseidon2");
+
+    let succinct = ProverSettings::current(ProverChoice::Default, ProveMode::Succinct);
+    assert_eq!(succinct.receipt_kind, "Succinct");
+    assert_eq!(succinct.mode, ProveMode::Succinct);
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The JSON object `--error-format json` reports a failure as.
+
+use anyhow::anyhow;
+use castvote_core::{MessageFormat, VoteParams};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command, Curve, ErrorFormat, Scheme},
+    error::{ErrorReport, VoteError, ERROR_OTHER},
+    keys::K256_PRIVATE_KEY,
+    prepare_ballot, read_text_file, sign, verify_signature, VoterKey,
+};
+use serde_json::json;
+
+const BALLOT: &str = r#"{"id":123,"name":"Alice","age":30,"is_student":false,"choice":"yes"}"#;
+
+fn report_json(err: &anyhow::Error) -> serde_json::Value {
+    serde_json::to_value(ErrorReport::new(err)).unwrap()
+}
+
+#[test]
+fn invalid_signature_is_reported_with_its_category_and_code() {
+    let key = VoterKey::from_base64(Scheme::Ecdsa, Curve::K256, K256_PRIVATE_KEY).unwrap();
+    let signature = sign(&key, BALLOT, MessageFormat::Sha256).unwrap();
+    let ballot = BALLOT.replace("yes", "no");
+    let public_key = key.public_key_base64();
+    let format = MessageFormat::Sha256;
+    let err = verify_signature(Scheme::Ecdsa, Curve::K256, &ballot, &signature, &public_key, format)
+        .unwrap_err();
+
+    let report = report_json(&err);
+    assert_eq!(report["error"], "InvalidSignature");
+    assert_eq!(report["code"], 3);
+    assert!(report["message"].as_str().is_some_and(|message| !message.is_empty()));
+    // Exactly these three fields, so consumers can rely on the shape.
+    assert_eq!(report.as_object().unwrap().len(), 3);
+}
+
+#[test]
+fn invalid_ballot_is_reported_as_invalid_input() {
+    let err = prepare_ballot("not json", &VoteParams::default()).unwrap_err();
+    let report = ErrorReport::new(&err);
+    assert_eq!((report.error.as_str(), report.code), ("InvalidInput", 2));
+}
+
+#[test]
+fn category_tags_are_left_out_of_the_message() {
+    let err = VoteError::VerificationFailed.error("the journal is not for this ballot");
+    let err = err.context("checking proofs/01");
+    assert_eq!(
+        report_json(&err),
+        json!({
+            "error": "VerificationFailed",
+            "message": "checking proofs/01: the journal is not for this ballot",
+            "code": 5,
+        })
+    );
+}
+
+#[test]
+fn uncategorized_failures_are_reported_as_other() {
+    let err = read_text_file("/nonexistent/ballot.json").unwrap_err();
+    let report = ErrorReport::new(&err);
+    assert_eq!((report.error.as_str(), report.code), (ERROR_OTHER, 1));
+    assert!(report.message.starts_with("reading /nonexistent/ballot.json: "));
+
+    assert_eq!(ErrorReport::new(&anyhow!("something unexpected")).error, ERROR_OTHER);
+}
+
+#[test]
+fn every_category_has_a_distinct_name() {
+    let names = [
+        VoteError::InvalidInput,
+        VoteError::InvalidSignature,
+        VoteError::ProvingFailed,
+        VoteError::VerificationFailed,
+        VoteError::DoubleVote,
+    ]
+    .map(VoteError::name);
+    let mut unique = names.to_vec();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), names.len());
+    assert!(!names.contains(&ERROR_OTHER));
+}
+
+#[test]
+fn error_format_is_a_global_flag() {
+    let cli = Cli::try_parse_from(["checkvote", "ballot.json", "--poll-id", "1001"]).unwrap();
+    assert_eq!(cli.error_format, ErrorFormat::Text);
+
+    let argv = ["checkvote", "inspect-seal", "--seal", "seal.dat", "--error-format", "json"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert!(matches!(cli.command, Some(Command::InspectSeal { .. })));
+    assert_eq!(cli.error_format, ErrorFormat::Json);
+}
@@ -0,0 +1,74 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Cost estimates from executing the guest without proving.
+#![cfg(feature = "prove")]
+
+use std::time::Duration;
+
+use castvote_core::{canonicalize_ballot, MessageFormat, VoteParams};
+use k256_example::{
+    estimate::{estimate_vote, CostModel, Estimate},
+    sign, VoterKey,
+};
+use k256_methods::{K256_VERIFY_ELF, K256_VERIFY_ID};
+
+const ESTIMATE: Estimate = Estimate { user_cycles: 700_000, total_cycles: 1 << 20, segments: 1 };
+
+#[test]
+fn time_and_cost_scale_with_proven_cycles() {
+    let model = CostModel { cycles_per_second: 1 << 18, cost_per_mcycle: Some(0.5) };
+    assert_eq!(ESTIMATE.proving_time(&model), Duration::from_secs(4));
+    assert_eq!(ESTIMATE.cost(&model), Some(0.524288));
+    assert_eq!(ESTIMATE.cost(&CostModel::default()), None);
+}
+
+#[test]
+fn report_lists_every_figure() {
+    let model = CostModel { cycles_per_second: 1 << 18, cost_per_mcycle: Some(0.5) };
+    let report = ESTIMATE.report(&model).to_string();
+    let expected = [
+        "segments:      1",
+        "user cycles:   700000",
+        "total cycles:  1048576",
+        "~4s at 262144 cycles/s",
+        "proving cost:  ~0.5243",
+    ];
+    for expected in expected {
+        assert!(report.contains(expected), "{expected:?} missing from\n{report}");
+    }
+    assert!(!ESTIMATE.report(&CostModel::default()).to_string().contains("cost"));
+}
+
+#[test]
+fn json_report_is_one_object() {
+    let model = CostModel { cycles_per_second: 1 << 18, cost_per_mcycle: None };
+    let json = ESTIMATE.to_json(&model);
+    assert_eq!(json["total_cycles"], 1 << 20);
+    assert_eq!(json["proving_time_secs"], 4.0);
+    assert!(json["cost"].is_null());
+}
+
+#[test]
+fn executes_the_guest_without_proving() {
+    let key = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap());
+    let ballot = canonicalize_ballot(include_str!("../res/person.json")).unwrap();
+    let signature = sign(&key, &ballot, MessageFormat::Sha256).unwrap();
+    let input = (signature, ballot, 1001u64, key.public_key_base64());
+    let params = VoteParams::default();
+    let estimate = estimate_vote(&input, &params, K256_VERIFY_ELF, K256_VERIFY_ID).unwrap();
+    assert!(estimate.segments >= 1);
+    assert!(estimate.user_cycles > 0 && estimate.user_cycles <= estimate.total_cycles);
+}
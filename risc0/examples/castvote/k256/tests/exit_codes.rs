@@ -0,0 +1,137 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The exit status `checkvote` ends with for each category of failure.
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, Context};
+use castvote_core::{simulate_onchain, MessageFormat, VoteParams};
+use k256_example::{
+    cli::{Curve, Scheme},
+    error::{VoteError, EXIT_OTHER},
+    keys::K256_PRIVATE_KEY,
+    prepare_ballot,
+    prove::Timeout,
+    read_text_file,
+    report::{self, Report},
+    sign, verify_signature, VoterKey,
+};
+use risc0_zkvm::sha::Digest;
+
+const BALLOT: &str = r#"{"id":123,"name":"Alice","age":30,"is_student":false,"choice":"yes"}"#;
+
+fn voter_key() -> VoterKey {
+    VoterKey::from_base64(Scheme::Ecdsa, Curve::K256, K256_PRIVATE_KEY).unwrap()
+}
+
+fn fixture_report() -> Report {
+    let pattern = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/report/journals/*.dat");
+    report::report_from_glob(pattern.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn categories_have_distinct_codes() {
+    let codes = [
+        VoteError::InvalidInput,
+        VoteError::InvalidSignature,
+        VoteError::ProvingFailed,
+        VoteError::VerificationFailed,
+        VoteError::DoubleVote,
+    ]
+    .map(VoteError::exit_code);
+    assert_eq!(codes, [2, 3, 4, 5, 6]);
+    assert_eq!(EXIT_OTHER, 1);
+}
+
+#[test]
+fn invalid_ballot_exits_with_2() {
+    let params = VoteParams { options: vec!["yes".into(), "no".into()], ..VoteParams::default() };
+    assert!(prepare_ballot(BALLOT, &params).is_ok());
+
+    let err = prepare_ballot(r#"{"id":123,"choice":"yes"}"#, &params).unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 2, "{err:#}");
+    let err = prepare_ballot(&BALLOT.replace("yes", "maybe"), &params).unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 2, "{err:#}");
+    let err = prepare_ballot("not json", &params).unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 2, "{err:#}");
+}
+
+#[test]
+fn bad_signature_exits_with_3() {
+    let key = voter_key();
+    let public_key = key.public_key_base64();
+    let signature = sign(&key, BALLOT, MessageFormat::Sha256).unwrap();
+    let check = |ballot: &str, signature: &str| {
+        let format = MessageFormat::Sha256;
+        verify_signature(Scheme::Ecdsa, Curve::K256, ballot, signature, &public_key, format)
+    };
+    check(BALLOT, &signature).unwrap();
+
+    let err = check(&BALLOT.replace("yes", "no"), &signature).unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 3, "{err:#}");
+    let err = check(BALLOT, "not base64!").unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), 3, "{err:#}");
+}
+
+#[test]
+fn prover_failures_exit_with_4() {
+    let err = anyhow::Error::new(Timeout(Duration::from_secs(1))).context("proving ballot.json");
+    assert_eq!(VoteError::exit_code_of(&err), 4);
+
+    let err = VoteError::ProvingFailed.tag(anyhow!("guest panicked: poll mismatch"));
+    assert_eq!(VoteError::exit_code_of(&err), 4);
+}
+
+#[test]
+fn failed_verification_exits_with_5() {
+    let err = simulate_onchain(&[0xde, 0xad, 0xbe, 0xef], b"journal", Digest::ZERO).unwrap_err();
+    let err = anyhow::Error::new(err).context("checking the seal");
+    assert_eq!(VoteError::exit_code_of(&err), 5);
+
+    let err = VoteError::VerificationFailed.error("the journal is not for this ballot");
+    assert_eq!(VoteError::exit_code_of(&err), 5);
+}
+
+#[test]
+fn double_vote_exits_with_6() {
+    let report = fixture_report();
+    assert_eq!(report.double_votes(), 1);
+
+    let err = VoteError::DoubleVote.error("1 journals reuse a nullifier");
+    assert_eq!(VoteError::exit_code_of(&err), 6);
+    assert_eq!(format!("{err}"), "double vote");
+}
+
+#[test]
+fn uncategorized_failures_exit_with_1() {
+    let err = read_text_file("/nonexistent/ballot.json").unwrap_err();
+    assert_eq!(VoteError::exit_code_of(&err), EXIT_OTHER, "{err:#}");
+    assert_eq!(VoteError::of(&anyhow!("something unexpected")), None);
+}
+
+#[test]
+fn the_category_closest_to_the_failure_wins() {
+    let err = VoteError::InvalidSignature.error("signature does not verify");
+    let err = VoteError::ProvingFailed.tag(err);
+    assert_eq!(VoteError::of(&err), Some(VoteError::InvalidSignature));
+
+    let err: anyhow::Result<()> = Err(anyhow!("receipt is for another image"));
+    let err = err
+        .context(VoteError::VerificationFailed)
+        .context("proving ballot.json")
+        .unwrap_err();
+    assert_eq!(VoteError::of(&err), Some(VoteError::VerificationFailed));
+}
@@ -0,0 +1,116 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `--explain` traces of the guest's checks, replayed on the host without the prover.
+
+use castvote_core::{canonicalize_ballot, MessageFormat, VoteParams};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Curve, Scheme},
+    explain::{explain_ballot, explain_commitment},
+    import_keys, sign, VoterKey,
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn key() -> VoterKey {
+    let argv = ["checkvote", "b.json", "--poll-id", "1"];
+    import_keys(&Cli::try_parse_from(argv).unwrap().prove.unwrap()).unwrap()
+}
+
+/// The guest input for `ballot` with a signature over `signed`, both canonicalized as the host
+/// prepares ballots.
+fn input(key: &VoterKey, signed: &str, ballot: &str) -> (String, String, u64, String) {
+    let signed = canonicalize_ballot(signed).unwrap();
+    let signature = sign(key, &signed, MessageFormat::Sha256).unwrap();
+    (signature, canonicalize_ballot(ballot).unwrap(), 1001, key.public_key_base64())
+}
+
+#[test]
+fn a_valid_ballot_passes_every_step() {
+    let key = key();
+    let params = VoteParams::default();
+    let (steps, reveal) =
+        explain_ballot(Scheme::Ecdsa, Curve::K256, &input(&key, BALLOT, BALLOT), &params);
+
+    let names: Vec<_> = steps.iter().map(|step| step.name).collect();
+    assert_eq!(
+        names,
+        [
+            "key import",
+            "message hash",
+            "signature verification",
+            "eligibility",
+            "nullifier derivation",
+            "reveal entry"
+        ]
+    );
+    assert!(steps.iter().all(|step| step.pass), "{steps:#?}");
+    let commitment = explain_commitment(&[reveal.unwrap()], false, &params);
+    assert!(commitment.pass && commitment.to_string().starts_with("PASS commitment: Bincode"));
+}
+
+#[test]
+fn a_bad_signature_fails_its_step_and_commits_nothing() {
+    let key = key();
+    let other = BALLOT.replace("Alice", "Mallory");
+    let (steps, reveal) = explain_ballot(
+        Scheme::Ecdsa,
+        Curve::K256,
+        &input(&key, &other, BALLOT),
+        &VoteParams::default(),
+    );
+
+    let failed: Vec<_> = steps.iter().filter(|step| !step.pass).map(|step| step.name).collect();
+    assert_eq!(failed, ["signature verification"]);
+    assert!(reveal.is_none());
+}
+
+#[test]
+fn an_underage_voter_fails_eligibility() {
+    let key = key();
+    let young = BALLOT.replace("\"age\": 30", "\"age\": 16");
+    let (steps, reveal) = explain_ballot(
+        Scheme::Ecdsa,
+        Curve::K256,
+        &input(&key, &young, &young),
+        &VoteParams::default(),
+    );
+
+    let eligibility = steps.iter().find(|step| step.name == "eligibility").unwrap();
+    assert!(!eligibility.pass);
+    assert!(eligibility.detail.starts_with("age 16 in 18..=120"), "{}", eligibility.detail);
+    assert!(reveal.is_none());
+}
+
+#[test]
+fn traces_leave_out_the_voter_and_full_keys() {
+    let key = key();
+    let input = input(&key, BALLOT, BALLOT);
+    let (steps, _) = explain_ballot(Scheme::Ecdsa, Curve::K256, &input, &VoteParams::default());
+
+    for step in steps {
+        assert!(!step.detail.contains("Alice"), "{step}");
+        assert!(!step.detail.contains(&input.0) && !step.detail.contains(&input.3), "{step}");
+    }
+}
+
+#[test]
+fn explain_replaces_proving() {
+    let argv = ["checkvote", "b.json", "--poll-id", "1", "--explain"];
+    assert!(Cli::try_parse_from(argv).unwrap().prove.unwrap().explain);
+    assert!(Cli::try_parse_from([argv.as_slice(), &["--dump-input", "in.json"]].concat()).is_err());
+    assert!(Cli::try_parse_from([argv.as_slice(), &["--no-prove"]].concat()).is_err());
+}
@@ -0,0 +1,59 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `--file-mode`, which is process-wide and so has a test binary to itself.
+#![cfg(unix)]
+
+use std::{fs, os::unix::fs::PermissionsExt};
+
+use clap::Parser;
+use k256_example::{
+    atomic_write,
+    cli::Cli,
+    output::{file_mode, set_file_mode},
+    write_hex_file,
+};
+
+fn mode(path: &std::path::Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn written_files_get_the_file_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    set_file_mode(Some(0o600));
+    assert_eq!(file_mode(), Some(0o600));
+    atomic_write(dir.path().join("input.json"), "{}").unwrap();
+    write_hex_file(dir.path().join("seal.dat"), &[1, 2]).unwrap();
+    set_file_mode(Some(0o640));
+    atomic_write(dir.path().join("input.json"), "{}").unwrap();
+    set_file_mode(None);
+
+    assert_eq!(mode(&dir.path().join("seal.dat")), 0o600);
+    assert_eq!(mode(&dir.path().join("input.json")), 0o640, "replacing a file applies the mode");
+}
+
+#[test]
+fn file_mode_is_octal() {
+    let parse = |mode: &str| {
+        let argv = ["checkvote", "report", "--journals", "*", "--out", "o.csv", "--file-mode", mode];
+        Cli::try_parse_from(argv)
+    };
+    assert_eq!(parse("600").unwrap().file_mode, Some(0o600));
+    assert_eq!(parse("0o640").unwrap().file_mode, Some(0o640));
+    for bad in ["800", "1777", "rw-------"] {
+        assert!(parse(bad).is_err(), "{bad}");
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots with non-ASCII names and choices, which are hashed and signed as UTF-8 bytes.
+#![cfg(feature = "prove")]
+
+use std::{fs, path::PathBuf};
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    canonicalize_ballot, compute_nullifier, parse_ballot, MessageFormat, RevealInfo, VoteParams,
+};
+use k256_example::{
+    cli::{Curve, Scheme},
+    jsonl::read_jsonl,
+    keys::K256_PRIVATE_KEY,
+    load_ballot, read_text_file, sign, verify_signature, VoterKey,
+};
+use k256_methods::K256_VERIFY_ELF;
+
+mod common;
+
+fn testdata(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/i18n").join(name)
+}
+
+fn params(message_format: MessageFormat) -> VoteParams {
+    VoteParams {
+        message_format,
+        options: vec!["はい".into(), "いいえ".into()],
+        ..VoteParams::default()
+    }
+}
+
+fn voter_key() -> VoterKey {
+    VoterKey::from_base64(Scheme::Ecdsa, Curve::K256, K256_PRIVATE_KEY).unwrap()
+}
+
+#[test]
+fn multibyte_text_is_kept_verbatim() {
+    let ballot = load_ballot(testdata("ballot.json"), &params(MessageFormat::Sha256)).unwrap();
+    assert!(ballot.contains(r#""name":"李小龍 🗳️ Zoë""#), "{ballot}");
+    assert!(ballot.len() > ballot.chars().count());
+    assert_eq!(canonicalize_ballot(&ballot).unwrap(), ballot);
+}
+
+#[test]
+fn byte_order_marks_are_ignored() {
+    let ballot = load_ballot(testdata("bom.json"), &params(MessageFormat::Sha256)).unwrap();
+    assert!(ballot.starts_with('{'));
+    assert!(!read_text_file(testdata("bom.json")).unwrap().starts_with('\u{feff}'));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ballots.jsonl");
+    let line = fs::read_to_string(testdata("bom.json")).unwrap();
+    fs::write(&path, format!("{line}{}\n", line.trim_start_matches('\u{feff}'))).unwrap();
+    let read = read_jsonl(&path, &params(MessageFormat::Sha256)).unwrap();
+    assert_eq!((read.ballots.len(), read.skipped.len()), (2, 0));
+}
+
+#[test]
+fn host_signature_over_multibyte_ballot_verifies() {
+    let key = voter_key();
+    for format in [MessageFormat::Sha256, MessageFormat::Eip191] {
+        let ballot = load_ballot(testdata("ballot.json"), &params(format)).unwrap();
+        let signature = sign(&key, &ballot, format).unwrap();
+        let public_key = key.public_key_base64();
+        verify_signature(Scheme::Ecdsa, Curve::K256, &ballot, &signature, &public_key, format)
+            .unwrap();
+
+        // One different character changes the bytes, and with them the digest.
+        let altered = ballot.replace("龍", "龙");
+        assert!(
+            verify_signature(Scheme::Ecdsa, Curve::K256, &altered, &signature, &public_key, format)
+                .is_err(),
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn guest_accepts_multibyte_ballot() {
+    let key = voter_key();
+    for format in [MessageFormat::Sha256, MessageFormat::Eip191] {
+        let params = params(format);
+        let ballot = load_ballot(testdata("ballot.json"), &params).unwrap();
+        let signature = sign(&key, &ballot, format).unwrap();
+        let input = (signature, ballot.clone(), 1001u64, key.public_key_base64());
+        let journal = common::execute_journal(K256_VERIFY_ELF, &input, &params).unwrap();
+
+        let encoded = Vec::<u8>::abi_decode(&journal).unwrap();
+        let reveal = RevealInfo::decode(&encoded).unwrap();
+        let id = parse_ballot(&ballot).unwrap().id;
+        assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&id, 1001)));
+        assert_eq!(reveal.selection, ["はい"]);
+    }
+}
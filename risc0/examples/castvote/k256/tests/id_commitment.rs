@@ -0,0 +1,164 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! ID commitments: what the guest commits next to the nullifier, and opening it with the one
+//! voter secret the authority discloses.
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    derive_voter_secret, encode_reveals, id_commitment, parse_ballot,
+    ElectionScope, RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    decode_journal,
+    nullifier::{
+        check_id_commitment_file, find_id_commitment, parse_voter_id, parse_voter_secret,
+        IdCommitmentMismatch, NullifierMatch,
+    },
+    write_hex_file,
+};
+use serde_json::json;
+
+const SEED: [u8; 32] = [7; 32];
+
+const ALICE: &str = r#"{"id": 123, "age": 30, "is_student": true, "choice": "yes"}"#;
+const BOB: &str = r#"{"id": 456, "age": 41, "is_student": false, "choice": "no"}"#;
+
+fn spring() -> ElectionScope {
+    ElectionScope { election_id: "2025-spring".into(), authority_seed: SEED.to_vec() }
+}
+
+fn reveal(ballot: &str, poll_id: u64, election: Option<ElectionScope>) -> RevealInfo {
+    let options = vec!["yes".into(), "no".into()];
+    let params = VoteParams { options, election, ..Default::default() };
+    RevealInfo::new(&parse_ballot(ballot).unwrap(), poll_id, &[], &params).unwrap()
+}
+
+#[test]
+fn guest_commits_an_id_commitment_only_for_election_scoped_ballots() {
+    assert_eq!(reveal(ALICE, 1001, None).id_commitment, None);
+
+    let committed = reveal(ALICE, 1001, Some(spring())).id_commitment.unwrap();
+    let secret = derive_voter_secret(&SEED, "2025-spring", &json!(123));
+    assert_eq!(committed, hex::encode(id_commitment(&json!(123), &secret)));
+    assert_eq!(committed, hex::encode(spring().id_commitment(&json!(123))));
+    // The commitment is the same in every poll of the election, unlike the nullifier.
+    assert_eq!(reveal(ALICE, 1002, Some(spring())).id_commitment.unwrap(), committed);
+}
+
+#[test]
+fn voter_secrets_differ_per_voter_and_election() {
+    let alice = derive_voter_secret(&SEED, "2025-spring", &json!(123));
+    assert_ne!(alice, derive_voter_secret(&SEED, "2025-spring", &json!(456)));
+    assert_ne!(alice, derive_voter_secret(&SEED, "2025-autumn", &json!(123)));
+    assert_ne!(alice, derive_voter_secret(&[8; 32], "2025-spring", &json!(123)));
+    // The ID is taken in its JSON form, so a number and a string of the same digits differ.
+    assert_ne!(alice, derive_voter_secret(&SEED, "2025-spring", &json!("123")));
+    // The election ID is length-prefixed, so it cannot run into the voter ID.
+    let shifted = derive_voter_secret(&SEED, "2025-spring1", &json!(23));
+    assert_ne!(alice, shifted);
+}
+
+#[test]
+fn the_voter_secret_opens_the_commitment_in_a_batch_journal() {
+    let journal = [reveal(ALICE, 1001, Some(spring())), reveal(BOB, 1001, Some(spring()))];
+    let bob_secret = spring().voter_secret(&json!(456));
+
+    let found = find_id_commitment(&json!(456), &bob_secret, &journal).unwrap();
+    assert_eq!(found, [NullifierMatch { index: 1, nullifier: journal[1].nullifier.clone() }]);
+
+    // Bob's secret opens nothing for Alice, and Alice's ID needs Alice's secret.
+    let err = find_id_commitment(&json!(123), &bob_secret, &journal).unwrap_err();
+    assert!(matches!(err, IdCommitmentMismatch::Commitment { .. }), "{err}");
+    let alice_secret = spring().voter_secret(&json!(123));
+    assert_eq!(find_id_commitment(&json!(123), &alice_secret, &journal).unwrap()[0].index, 0);
+}
+
+#[test]
+fn every_poll_of_a_multi_poll_journal_is_tied_to_the_voter() {
+    let journal = [reveal(ALICE, 1001, Some(spring())), reveal(ALICE, 1002, Some(spring()))];
+    let found =
+        find_id_commitment(&json!(123), &spring().voter_secret(&json!(123)), &journal).unwrap();
+    assert_eq!(found.iter().map(|found| found.index).collect::<Vec<_>>(), [0, 1]);
+    assert_ne!(found[0].nullifier, found[1].nullifier);
+}
+
+#[test]
+fn journals_without_an_election_have_nothing_to_open() {
+    let journal = [reveal(ALICE, 1001, None)];
+    let secret = spring().voter_secret(&json!(123));
+    let err = find_id_commitment(&json!(123), &secret, &journal).unwrap_err();
+    assert_eq!(err, IdCommitmentMismatch::NotCommitted);
+}
+
+#[test]
+fn opens_a_journal_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal.dat");
+    let reveals = [reveal(BOB, 1001, Some(spring()))];
+    write_hex_file(&path, &encode_reveals(&reveals).abi_encode()).unwrap();
+
+    let secret = parse_voter_secret(&hex::encode(spring().voter_secret(&json!(456)))).unwrap();
+    let found = check_id_commitment_file(&path, &parse_voter_id("456"), &secret).unwrap();
+    assert_eq!(found.unwrap()[0].nullifier, reveals[0].nullifier);
+    let err = check_id_commitment_file(&path, &parse_voter_id("\"456\""), &secret).unwrap();
+    assert!(err.is_err());
+}
+
+#[test]
+fn parses_voter_ids_and_secrets() {
+    assert_eq!(parse_voter_id("7"), json!(7));
+    assert_eq!(parse_voter_id("\"7\""), json!("7"));
+    assert_eq!(parse_voter_id("alice"), json!("alice"));
+
+    assert_eq!(parse_voter_secret(&"ab".repeat(32)).unwrap(), [0xab; 32]);
+    let err = parse_voter_secret(&"ab".repeat(16)).unwrap_err();
+    assert!(err.to_string().contains("expected 32"), "{err}");
+    assert!(parse_voter_secret("not hex").is_err());
+}
+
+#[test]
+fn version_9_journals_decode_without_an_id_commitment() {
+    let reveal = RevealInfo { abstained: None, ..reveal(ALICE, 1001, None) };
+    // Version 9 is the current version without the trailing `id_commitment`, `max_poll_id`,
+    // `author_key_hash` and `abstained`, each encoded as a `None` byte.
+    let mut v9 = reveal.encode();
+    assert_eq!(v9.split_off(v9.len() - 4), [0, 0, 0, 0]);
+    v9[0] = 9;
+    assert_eq!(decode_journal(&v9.abi_encode()).unwrap(), reveal);
+}
+
+#[test]
+fn parses_the_voter_secret_and_open_id_commitment_commands() {
+    let argv = [
+        "checkvote", "voter-secret", "--voter-id", "123", "--election-id", "2025-spring",
+        "--authority-seed-file", "authority.seed",
+    ];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert!(matches!(cli.command, Some(Command::VoterSecret { .. })));
+    assert!(Cli::try_parse_from(&argv[..6]).is_err());
+
+    let argv = [
+        "checkvote", "open-id-commitment", "--journal", "journal.dat", "--voter-id", "123",
+        "--voter-secret", "00",
+    ];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    let Some(Command::OpenIdCommitment { voter_id, .. }) = cli.command else {
+        panic!("expected the open-id-commitment subcommand");
+    };
+    assert_eq!(voter_id, "123");
+}
@@ -0,0 +1,51 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The image IDs generated into `k256_methods` match the ELFs they were built with.
+
+use k256_example::prove::checked_image_id;
+use k256_methods::{
+    ED25519_VERIFY_ELF, ED25519_VERIFY_ID, K256_COSIGN_ELF, K256_COSIGN_ID, K256_VERIFY_BATCH_ELF,
+    K256_VERIFY_BATCH_ID, K256_VERIFY_ELF, K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID,
+    K256_VERIFY_HOMOMORPHIC_ELF, K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID,
+    K256_VERIFY_REGISTRY_ELF, K256_VERIFY_REGISTRY_ID, P256_VERIFY_ELF, P256_VERIFY_ID,
+};
+use risc0_zkvm::sha::Digest;
+
+#[test]
+fn every_method_matches_its_image_id() {
+    for (elf, id) in [
+        (K256_VERIFY_ELF, K256_VERIFY_ID),
+        (K256_VERIFY_BATCH_ELF, K256_VERIFY_BATCH_ID),
+        (K256_COSIGN_ELF, K256_COSIGN_ID),
+        (K256_VERIFY_REGISTRY_ELF, K256_VERIFY_REGISTRY_ID),
+        (K256_VERIFY_ENCRYPTED_ELF, K256_VERIFY_ENCRYPTED_ID),
+        (K256_VERIFY_HOMOMORPHIC_ELF, K256_VERIFY_HOMOMORPHIC_ID),
+        (P256_VERIFY_ELF, P256_VERIFY_ID),
+        (ED25519_VERIFY_ELF, ED25519_VERIFY_ID),
+    ] {
+        assert_eq!(checked_image_id(elf, id).unwrap(), Digest::from(id));
+        // Served from the cache the second time.
+        assert_eq!(checked_image_id(elf, id).unwrap(), Digest::from(id));
+    }
+}
+
+#[test]
+fn mismatched_image_id_is_reported() {
+    let err = checked_image_id(P256_VERIFY_ELF, K256_VERIFY_ID).unwrap_err();
+    assert!(err.to_string().contains("rebuild the guest methods"), "{err}");
+    // The computed ID is cached, but a mismatch is reported on every call.
+    assert!(checked_image_id(P256_VERIFY_ELF, K256_VERIFY_ID).is_err());
+    assert!(checked_image_id(P256_VERIFY_ELF, P256_VERIFY_ID).is_ok());
+}
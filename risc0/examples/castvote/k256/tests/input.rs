@@ -0,0 +1,151 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Saving guest inputs with `--dump-input` and reading them back for `--input`.
+
+use castvote_core::{ElGamalInput, ElectionScope, MessageFormat, VoteParams};
+use k256_example::{
+    error::VoteError,
+    input::{
+        method_by_name, method_name, read_input, write_input, BallotInput, GuestInput, InputDump,
+        INPUT_FORMAT_VERSION, METHODS,
+    },
+    sign, VoterKey,
+};
+use k256_methods::{K256_VERIFY_BATCH_ID, K256_VERIFY_HOMOMORPHIC_ID, K256_VERIFY_ID};
+use risc0_zkvm::sha::Digest;
+
+fn ballot_input() -> BallotInput {
+    ("c2ln".into(), r#"{"id":"1","choice":"yes"}"#.into(), 1001, "a2V5".into())
+}
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], ..VoteParams::default() }
+}
+
+#[test]
+fn every_input_kind_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let elgamal = ElGamalInput { election_key: vec![2; 33], randomness: vec![[7; 32]; 2] };
+    let cases = [
+        (GuestInput::from(ballot_input()), K256_VERIFY_ID),
+        (GuestInput::from(vec![ballot_input(), ballot_input()]), K256_VERIFY_BATCH_ID),
+        (GuestInput::from((ballot_input(), elgamal)), K256_VERIFY_HOMOMORPHIC_ID),
+    ];
+    for (input, image_id) in cases {
+        let path = dir.path().join("input.json");
+        let dump = InputDump::new(input, &params(), image_id).unwrap();
+        assert_eq!(dump.version, INPUT_FORMAT_VERSION);
+        assert_eq!(dump.image_id, Digest::from(image_id).to_string());
+        write_input(&path, &dump).unwrap();
+
+        let read = read_input(&path).unwrap();
+        assert_eq!(read, dump);
+        let (_, method_id, unchanged) = read.method().unwrap();
+        assert_eq!(method_id, image_id);
+        assert!(unchanged);
+    }
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("input.json");
+    let mut dump = InputDump::new(ballot_input().into(), &params(), K256_VERIFY_ID).unwrap();
+    dump.version = INPUT_FORMAT_VERSION + 1;
+    write_input(&path, &dump).unwrap();
+
+    let err = read_input(&path).unwrap_err().to_string();
+    assert!(err.contains("input format version"), "{err}");
+    std::fs::write(&path, "{}").unwrap();
+    assert!(read_input(&path).is_err());
+}
+
+#[test]
+fn a_rebuilt_method_is_reported() {
+    let mut dump = InputDump::new(ballot_input().into(), &params(), K256_VERIFY_ID).unwrap();
+    dump.image_id = Digest::ZERO.to_string();
+    let (_, image_id, unchanged) = dump.method().unwrap();
+    assert_eq!(image_id, K256_VERIFY_ID);
+    assert!(!unchanged);
+
+    dump.method = "k256_verify_v0".into();
+    assert!(dump.method().is_err());
+}
+
+#[test]
+fn methods_are_found_by_name_and_image_id() {
+    for (name, _, image_id) in METHODS {
+        assert_eq!(method_name(image_id), Some(name));
+        assert_eq!(method_by_name(name).unwrap().1, image_id);
+    }
+    assert_eq!(method_name([0; 8]), None);
+}
+
+fn signed_input(ballot: &str) -> BallotInput {
+    let key = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes(&[7; 32].into()).unwrap());
+    let signature = sign(&key, ballot, MessageFormat::Sha256).unwrap();
+    (signature, ballot.into(), 1001, key.public_key_base64())
+}
+
+#[test]
+fn signatures_are_checked_before_proving() {
+    let yes = signed_input(r#"{"id":"1","choice":"yes"}"#);
+    let input = GuestInput::from(vec![yes.clone(), signed_input(r#"{"id":"2","choice":"no"}"#)]);
+    input.check_signatures(K256_VERIFY_BATCH_ID, MessageFormat::Sha256).unwrap();
+
+    let mut forged = yes.clone();
+    forged.1 = r#"{"id":"1","choice":"no"}"#.into();
+    let input = GuestInput::from(vec![yes, forged]);
+    let err = input.check_signatures(K256_VERIFY_BATCH_ID, MessageFormat::Sha256).unwrap_err();
+    assert_eq!(VoteError::of(&err), Some(VoteError::InvalidSignature));
+    let message = format!("{err:#}");
+    assert!(message.contains("checking the signature of ballot 2 of 2"), "{message}");
+
+    let input = GuestInput::from(ballot_input());
+    let err = input.check_signatures(K256_VERIFY_ID, MessageFormat::Sha256).unwrap_err();
+    assert_eq!(VoteError::of(&err), Some(VoteError::InvalidSignature));
+}
+
+#[test]
+fn dumps_leave_out_the_authority_seed() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, seed_file) = (dir.path().join("input.json"), dir.path().join("seed.bin"));
+    let seed = b"authority seed that must stay secret".to_vec();
+    std::fs::write(&seed_file, &seed).unwrap();
+    let election = ElectionScope { election_id: "spring-2025".into(), authority_seed: seed.clone() };
+    let params = VoteParams { election: Some(election), ..params() };
+
+    let dump = InputDump::new(ballot_input().into(), &params, K256_VERIFY_ID).unwrap();
+    write_input(&path, &dump).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    assert!(!contents.windows(seed.len()).any(|window| window == seed));
+    assert!(!String::from_utf8_lossy(&contents).contains(&hex::encode(&seed)));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    let mut read = read_input(&path).unwrap();
+    assert!(read.params.election.as_ref().unwrap().authority_seed.is_empty());
+    let err = read.restore_seed(None).unwrap_err().to_string();
+    assert!(err.contains("--authority-seed-file"), "{err}");
+    read.restore_seed(Some(&seed_file)).unwrap();
+    assert_eq!(read.params, params);
+
+    let mut unscoped = InputDump::new(ballot_input().into(), &params(), K256_VERIFY_ID).unwrap();
+    assert!(unscoped.restore_seed(Some(&seed_file)).is_err());
+}
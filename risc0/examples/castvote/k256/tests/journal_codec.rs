@@ -0,0 +1,178 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--journal-codec`: journals serialized as bincode or CBOR, told apart by the version byte.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use std::collections::{BTreeMap, BTreeSet};
+
+use castvote_core::{
+    decode_reveals, encode_reveals_with, parse_ballot, AgeEligibility, AgeRange, CosignedReveal,
+    EncryptedChoice, EncryptedReveal, JournalCodec, JournalError, RevealInfo, VoteParams,
+    CBOR_VERSION_FLAG, JOURNAL_VERSION,
+};
+use ciborium::Value;
+use clap::Parser;
+use k256_example::{
+    cli::Cli, decode_batch_journal, decode_cosigned_journal, decode_eligibility_journal,
+    decode_encrypted_journal, decode_journal,
+};
+use k256_methods::K256_VERIFY_ELF;
+
+mod common;
+
+const BALLOT: &str = r#"{"id": 1, "age": 30, "is_student": false}"#;
+
+const CODECS: [JournalCodec; 2] = [JournalCodec::Bincode, JournalCodec::Cbor];
+
+fn reveal() -> RevealInfo {
+    RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[], &VoteParams::default()).unwrap()
+}
+
+#[test]
+fn flag_selects_the_codec() {
+    let parse = |flags: &[&str]| {
+        let argv = [&["checkvote", "b.json", "--poll-id", "1001"], flags].concat();
+        Cli::try_parse_from(argv).unwrap().prove.unwrap().vote_params().unwrap().journal_codec
+    };
+    assert_eq!(parse(&[]), JournalCodec::Bincode);
+    assert_eq!(parse(&["--journal-codec", "bincode"]), JournalCodec::Bincode);
+    assert_eq!(parse(&["--journal-codec", "cbor"]), JournalCodec::Cbor);
+}
+
+#[test]
+fn version_byte_records_the_codec() {
+    assert_eq!(reveal().encode_with(JournalCodec::Bincode)[0], JOURNAL_VERSION);
+    assert_eq!(reveal().encode_with(JournalCodec::Bincode), reveal().encode());
+    assert_eq!(reveal().encode_with(JournalCodec::Cbor)[0], JOURNAL_VERSION | CBOR_VERSION_FLAG);
+}
+
+#[test]
+fn every_journal_round_trips_in_both_codecs() {
+    for codec in CODECS {
+        let reveal = reveal();
+        let journal = reveal.encode_with(codec).abi_encode();
+        assert_eq!(decode_journal(&journal).unwrap(), reveal, "{codec:?}");
+
+        let reveals = vec![reveal.clone(), RevealInfo { poll_id: 1002, ..reveal.clone() }];
+        let journal = encode_reveals_with(&reveals, codec).abi_encode();
+        assert_eq!(decode_batch_journal(&journal).unwrap(), reveals, "{codec:?}");
+
+        let cosigned = CosignedReveal { reveal: reveal.clone(), valid_signatures: 2, threshold: 2 };
+        let journal = cosigned.encode_with(codec).abi_encode();
+        assert_eq!(decode_cosigned_journal(&journal).unwrap(), cosigned, "{codec:?}");
+
+        let encrypted_choice = EncryptedChoice {
+            ephemeral_key: "AA==".into(),
+            nonce: "AQ==".into(),
+            ciphertext: "Ag==".into(),
+        };
+        let encrypted = EncryptedReveal { reveal: reveal.clone(), encrypted_choice };
+        let journal = encrypted.encode_with(codec).abi_encode();
+        assert_eq!(decode_encrypted_journal(&journal).unwrap(), encrypted, "{codec:?}");
+
+        let ballot = parse_ballot(BALLOT).unwrap();
+        let eligibility =
+            AgeEligibility::new(&ballot, BALLOT, 1001, 18, &VoteParams::default()).unwrap();
+        let journal = eligibility.encode_with(codec).abi_encode();
+        assert_eq!(decode_eligibility_journal(&journal).unwrap(), eligibility, "{codec:?}");
+    }
+}
+
+#[test]
+fn cbor_payload_is_a_self_describing_map() {
+    let encoded = reveal().encode_with(JournalCodec::Cbor);
+    let value: Value = ciborium::from_reader(&encoded[1..]).unwrap();
+    let fields = value.into_map().unwrap();
+    let field = |name: &str| {
+        let key = Value::Text(name.into());
+        fields.iter().find(|(k, _)| *k == key).map(|(_, v)| v.clone())
+    };
+    assert_eq!(field("nullifier"), Some(Value::Text(reveal().nullifier)));
+    assert_eq!(field("poll_id"), Some(Value::Integer(1001.into())));
+    assert_eq!(field("is_student"), Some(Value::Bool(false)));
+}
+
+#[test]
+fn malformed_cbor_is_rejected() {
+    let mut encoded = reveal().encode_with(JournalCodec::Cbor);
+    encoded.truncate(encoded.len() / 2);
+    assert!(matches!(RevealInfo::decode(&encoded), Err(JournalError::MalformedCbor(_))));
+
+    // A bincode payload behind a CBOR version byte is not silently accepted.
+    let mut mislabeled = reveal().encode();
+    mislabeled[0] |= CBOR_VERSION_FLAG;
+    assert!(RevealInfo::decode(&mislabeled).is_err());
+}
+
+/// CBOR journals written by the builds of each journal version since CBOR was added: a single
+/// reveal, a batch of two reveals and a co-signed reveal, keyed by version.
+const CBOR_JOURNALS: &str = include_str!("../testdata/cbor-journals.json");
+
+#[test]
+fn cbor_journals_of_every_earlier_version_decode() {
+    let journals: BTreeMap<u8, BTreeMap<String, String>> =
+        serde_json::from_str(CBOR_JOURNALS).unwrap();
+    let versions: Vec<u8> = journals.keys().copied().collect();
+    assert_eq!(versions, (7..JOURNAL_VERSION).collect::<Vec<_>>());
+
+    let mut nullifiers = BTreeSet::new();
+    for (version, journal) in &journals {
+        let bytes = |name: &str| hex::decode(&journal[name]).unwrap();
+        assert_eq!(bytes("reveal")[0], version | CBOR_VERSION_FLAG);
+        let reveal = RevealInfo::decode(&bytes("reveal"))
+            .unwrap_or_else(|e| panic!("version {version}: {e}"));
+        assert_eq!((reveal.age, reveal.is_student, reveal.poll_id), (30, true, 1001));
+        assert_eq!(reveal.selection, ["yes"]);
+        assert_eq!(reveal.age_range, Some(AgeRange::default()));
+        assert_eq!(reveal.student_required, Some(false));
+        // Version 13 added `abstained`, committed for every ballot since.
+        assert_eq!(reveal.abstained, (*version >= 13).then_some(false), "version {version}");
+        nullifiers.insert(reveal.nullifier.clone());
+
+        let batch = decode_reveals(&bytes("batch"))
+            .unwrap_or_else(|e| panic!("version {version}: {e}"));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0], reveal);
+        assert_eq!(batch[1].age, 41);
+        assert_eq!(batch[1].selection, ["no"]);
+
+        let cosigned = CosignedReveal::decode(&bytes("cosigned"))
+            .unwrap_or_else(|e| panic!("version {version}: {e}"));
+        assert_eq!(cosigned, CosignedReveal { reveal, valid_signatures: 2, threshold: 2 });
+    }
+    // The nullifier derivation has not changed, so every version commits the same one.
+    assert_eq!(nullifiers.len(), 1);
+}
+
+#[test]
+fn cbor_journals_older_than_cbor_are_rejected() {
+    let mut encoded = reveal().encode_with(JournalCodec::Cbor);
+    encoded[0] = 6 | CBOR_VERSION_FLAG;
+    assert!(matches!(RevealInfo::decode(&encoded), Err(JournalError::UnsupportedVersion(6))));
+}
+
+#[test]
+fn guest_commits_in_the_selected_codec() {
+    let input = common::signed_input(BALLOT).unwrap();
+    let execute = |journal_codec| {
+        let params = VoteParams { journal_codec, ..Default::default() };
+        common::execute_journal(K256_VERIFY_ELF, &input, &params).unwrap()
+    };
+    let (bincode, cbor) = (execute(JournalCodec::Bincode), execute(JournalCodec::Cbor));
+    assert_ne!(bincode, cbor);
+    assert_eq!(Vec::<u8>::abi_decode(&cbor).unwrap()[0], JOURNAL_VERSION | CBOR_VERSION_FLAG);
+    assert_eq!(decode_journal(&bincode).unwrap(), decode_journal(&cbor).unwrap());
+}
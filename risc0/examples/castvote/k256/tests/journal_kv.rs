@@ -0,0 +1,120 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Flat key-value exports of journals, as `journal-to-kv` prints them.
+
+use std::path::PathBuf;
+
+use alloy_sol_types::SolValue;
+use castvote_core::{parse_ballot, ElectionScope, RevealInfo, VoteParams};
+use k256_example::{
+    cli::KvFormat,
+    kv::{journal_fields, render_fields},
+    read_journal_file,
+};
+use serde_json::json;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let journals = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/report/journals");
+    read_journal_file(journals.join(name)).unwrap()
+}
+
+fn current_journal() -> Vec<u8> {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": true, "choice": "yes"}"#);
+    let params = VoteParams {
+        options: vec!["yes".into(), "no".into()],
+        election: Some(ElectionScope {
+            election_id: "spring 2025".into(),
+            authority_seed: vec![7; 32],
+        }),
+        ..VoteParams::default()
+    };
+    let reveal = RevealInfo::new(&ballot.unwrap(), 1001, &[], &params).unwrap();
+    reveal.encode().abi_encode()
+}
+
+fn render(journal: &[u8], format: KvFormat) -> String {
+    render_fields(&journal_fields(journal).unwrap(), format).unwrap()
+}
+
+#[test]
+fn env_output_of_a_version_1_journal() {
+    let expected = "\
+version=1
+codec=bincode
+nullifier=f46c19183c117a0e875006151cde712b28e04a4d6f084fccb1159f836c0055f9
+age=52
+is_student=false
+poll_id=1003
+age_as_of=
+ballot_type=Approval
+selection=no,yes
+voter_key_hash=e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+age_range_min=
+age_range_max=
+election_id=
+poll_hash=
+message_format=
+student_required=
+policy_hash=
+poll_name=
+receipt_id=
+id_commitment=
+max_poll_id=
+author_key_hash=
+abstained=
+";
+    assert_eq!(render(&fixture("08-erin-approval.dat"), KvFormat::Env), expected);
+}
+
+#[test]
+fn every_version_exports_the_same_keys() {
+    let keys = |journal: &[u8]| -> Vec<String> {
+        journal_fields(journal).unwrap().into_iter().map(|(key, _)| key).collect()
+    };
+    assert_eq!(keys(&fixture("08-erin-approval.dat")), keys(&current_journal()));
+}
+
+#[test]
+fn env_values_are_quoted_for_a_shell() {
+    let env = render(&current_journal(), KvFormat::Env);
+    assert!(env.contains("\nelection_id='spring 2025'\n"), "{env}");
+    assert!(env.contains("\nage_range_min=18\nage_range_max=120\n"), "{env}");
+    assert!(env.contains("\nselection=yes\n"), "{env}");
+}
+
+#[test]
+fn json_and_csv_hold_the_same_fields() {
+    let journal = current_journal();
+    let fields = journal_fields(&journal).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&render(&journal, KvFormat::Json)).unwrap();
+    assert_eq!(json.as_object().unwrap().len(), fields.len());
+    assert_eq!(json["selection"], json!(["yes"]));
+    assert_eq!(json["age"], json!(30));
+    assert_eq!(json["poll_name"], json!(null));
+
+    let csv = render(&journal, KvFormat::Csv);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("key,value"));
+    assert_eq!(lines.count(), fields.len());
+    assert!(csv.contains("\nelection_id,spring 2025\n"), "{csv}");
+}
+
+#[test]
+fn other_data_is_rejected() {
+    assert!(journal_fields(b"not a journal").is_err());
+    assert!(journal_fields(&b"not reveal info".to_vec().abi_encode()).is_err());
+}
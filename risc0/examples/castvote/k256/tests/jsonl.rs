@@ -0,0 +1,86 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Reading bulk ballots from JSON Lines files.
+
+use std::fs;
+
+use castvote_core::{canonicalize_ballot, VoteParams};
+use k256_example::jsonl::{parse_jsonl, read_jsonl, read_signature_lines, SkippedLine};
+
+const BALLOTS: &str = r#"{"id": 1, "age": 30, "is_student": false}
+{"id": 2, "age": 19,
+not json at all
+
+{"age": 40, "is_student": true}
+{"is_student": true, "id": 5, "age": 22}
+"#;
+
+#[test]
+fn valid_lines_become_canonical_ballots() {
+    let read = parse_jsonl(BALLOTS, &VoteParams::default());
+    let lines: Vec<_> = read.ballots.iter().map(|ballot| ballot.line).collect();
+    assert_eq!(lines, [1, 6]);
+    let expected = canonicalize_ballot(r#"{"id": 5, "age": 22, "is_student": true}"#).unwrap();
+    assert_eq!(read.ballots[1].ballot, expected);
+}
+
+#[test]
+fn invalid_lines_are_skipped_with_their_line_number() {
+    let read = parse_jsonl(BALLOTS, &VoteParams::default());
+    let lines: Vec<_> = read.skipped.iter().map(|skipped| skipped.line).collect();
+    assert_eq!(lines, [2, 3, 5]);
+    assert!(read.skipped[2].reason.contains("/id"), "{}", read.skipped[2].reason);
+}
+
+#[test]
+fn choice_rules_apply_per_line() {
+    let params = VoteParams { options: vec!["yes".into(), "no".into()], ..Default::default() };
+    let text = r#"{"id": 1, "age": 30, "is_student": false, "choice": "yes"}
+{"id": 2, "age": 30, "is_student": false, "choice": "maybe"}"#;
+    let read = parse_jsonl(text, &params);
+    assert_eq!(read.ballots.len(), 1);
+    assert_eq!(read.skipped[0].line, 2);
+}
+
+#[test]
+fn skip_all_moves_ballots_to_the_skipped_lines() {
+    let mut read = parse_jsonl(BALLOTS, &VoteParams::default());
+    read.skip_all(vec![SkippedLine { line: 1, reason: "bad signature".into() }]);
+    assert_eq!(read.ballots.len(), 1);
+    assert_eq!(read.skipped[0], SkippedLine { line: 1, reason: "bad signature".into() });
+    assert_eq!(read.skipped.len(), 4);
+
+    read.skip_all(vec![SkippedLine { line: 6, reason: "no signature".into() }]);
+    assert!(read.ballots.is_empty());
+    let lines: Vec<_> = read.skipped.iter().map(|skipped| skipped.line).collect();
+    assert_eq!(lines, [1, 2, 3, 5, 6]);
+}
+
+#[test]
+fn files_are_read_line_by_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let ballots = dir.path().join("ballots.jsonl");
+    fs::write(&ballots, BALLOTS).unwrap();
+    assert_eq!(read_jsonl(&ballots, &VoteParams::default()).unwrap().ballots.len(), 2);
+    assert!(read_jsonl(dir.path().join("missing.jsonl"), &VoteParams::default()).is_err());
+
+    let signatures = dir.path().join("signatures.txt");
+    fs::write(&signatures, "c2lnMQ==\n\n  c2lnMw==  \n").unwrap();
+    assert_eq!(
+        read_signature_lines(&signatures).unwrap(),
+        [Some("c2lnMQ==".to_string()), None, Some("c2lnMw==".to_string())]
+    );
+}
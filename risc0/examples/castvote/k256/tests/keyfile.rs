@@ -0,0 +1,170 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Password-protected private key files: encryption round trips, and reading them as
+//! `--private-key-file`.
+
+use std::fs;
+
+use base64::{engine::general_purpose, Engine as _};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command, Curve, Scheme},
+    import_keys,
+    keyfile::{
+        encrypt_key_file, read_private_key_file, EncryptedKey, KeyPassword, ScryptParams,
+        MAX_SCRYPT_LOG_N,
+    },
+    VoterKey,
+};
+
+/// Cheap enough for tests; real files use [ScryptParams::default].
+const FAST: ScryptParams = ScryptParams { log_n: 4, r: 8, p: 1 };
+
+const SEED: &[u8] = b"castvote key file tests";
+
+fn password() -> KeyPassword {
+    KeyPassword::new("correct horse battery staple")
+}
+
+fn private_key(key: &VoterKey) -> [u8; 32] {
+    general_purpose::STANDARD.decode(key.private_key_base64()).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn encrypted_key_decrypts_to_the_original() {
+    for (scheme, curve) in [(Scheme::Ecdsa, Curve::K256), (Scheme::Ed25519, Curve::K256)] {
+        let key = VoterKey::from_seed(scheme, curve, SEED).unwrap();
+        let encrypted = EncryptedKey::encrypt(&private_key(&key), &password(), FAST).unwrap();
+        assert_eq!(*encrypted.decrypt(&password()).unwrap(), private_key(&key));
+    }
+}
+
+#[test]
+fn every_encryption_uses_a_fresh_salt_and_nonce() {
+    let key = private_key(&VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap());
+    let first = EncryptedKey::encrypt(&key, &password(), FAST).unwrap();
+    let second = EncryptedKey::encrypt(&key, &password(), FAST).unwrap();
+    assert_ne!(first.salt, second.salt);
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+    assert_eq!(*second.decrypt(&password()).unwrap(), key);
+}
+
+#[test]
+fn wrong_password_and_tampering_are_rejected() {
+    let key = private_key(&VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap());
+    let encrypted = EncryptedKey::encrypt(&key, &password(), FAST).unwrap();
+
+    let error = encrypted.decrypt(&KeyPassword::new("Tr0ub4dor&3")).unwrap_err();
+    assert!(error.to_string().contains("wrong password"), "{error:#}");
+
+    let weaker = EncryptedKey { scrypt: ScryptParams { log_n: 3, ..FAST }, ..encrypted.clone() };
+    assert!(weaker.decrypt(&password()).is_err());
+
+    let other_salt = EncryptedKey::encrypt(&key, &password(), FAST).unwrap().salt;
+    let resalted = EncryptedKey { salt: other_salt, ..encrypted.clone() };
+    assert!(resalted.decrypt(&password()).is_err());
+
+    let truncated = EncryptedKey { ciphertext: encrypted.ciphertext[4..].into(), ..encrypted };
+    assert!(truncated.decrypt(&password()).is_err());
+}
+
+#[test]
+fn excessive_scrypt_costs_are_refused_before_stretching() {
+    let key = private_key(&VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap());
+    let encrypted = EncryptedKey::encrypt(&key, &password(), FAST).unwrap();
+
+    // Either would take far too long to run if it were not refused first.
+    let costly = [
+        (ScryptParams { log_n: MAX_SCRYPT_LOG_N + 1, ..FAST }, "scrypt log_n is 21"),
+        (ScryptParams { r: 1 << 16, p: 1 << 16, ..FAST }, "scrypt r * p is 4294967296"),
+    ];
+    for (scrypt, message) in costly {
+        let error = EncryptedKey { scrypt, ..encrypted.clone() }.decrypt(&password()).unwrap_err();
+        assert!(error.to_string().contains(message), "{error:#}");
+        assert!(EncryptedKey::encrypt(&key, &password(), scrypt).is_err());
+    }
+}
+
+#[test]
+fn key_files_read_plain_or_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = VoterKey::from_seed(Scheme::Ecdsa, Curve::P256, SEED).unwrap();
+    let plain = dir.path().join("voter.key");
+    fs::write(&plain, format!("{}\n", key.private_key_base64())).unwrap();
+    let encrypted = dir.path().join("voter.key.json");
+    let file = EncryptedKey::encrypt(&private_key(&key), &password(), FAST).unwrap();
+    fs::write(&encrypted, serde_json::to_string(&file).unwrap()).unwrap();
+
+    let cases = [(&plain, None), (&plain, Some(password())), (&encrypted, Some(password()))];
+    for (path, password) in cases {
+        let read = read_private_key_file(path, Scheme::Ecdsa, Curve::P256, password.as_ref());
+        assert_eq!(read.unwrap().public_key_base64(), key.public_key_base64());
+    }
+
+    let error = read_private_key_file(&encrypted, Scheme::Ecdsa, Curve::P256, None).unwrap_err();
+    assert!(format!("{error:#}").contains("CHECKVOTE_KEY_PASSWORD"), "{error:#}");
+}
+
+#[test]
+fn encrypt_key_writes_a_private_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = VoterKey::from_seed(Scheme::Ed25519, Curve::K256, SEED).unwrap();
+    let plain = dir.path().join("voter.key");
+    fs::write(&plain, key.private_key_base64()).unwrap();
+    let out = dir.path().join("voter.key.json");
+    encrypt_key_file(&plain, &out, &password(), FAST).unwrap();
+
+    let file: EncryptedKey = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(file.scrypt, FAST);
+    assert!(!fs::read_to_string(&out).unwrap().contains(&key.private_key_base64()));
+    let read = read_private_key_file(&out, Scheme::Ed25519, Curve::K256, Some(&password()));
+    assert_eq!(read.unwrap().public_key_base64(), key.public_key_base64());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(&out).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn private_key_file_is_the_signing_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap();
+    let path = dir.path().join("voter.key.json");
+    let file = EncryptedKey::encrypt(&private_key(&key), &password(), FAST).unwrap();
+    fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+    let password = password();
+    let argv = ["checkvote", "b.json", "--poll-id", "1", "--private-key-file"];
+    let argv = [&argv[..], &[path.to_str().unwrap(), "--key-password", password.as_str()]].concat();
+    let args = Cli::try_parse_from(argv).unwrap().prove.unwrap();
+    assert_eq!(import_keys(&args).unwrap().public_key_base64(), key.public_key_base64());
+}
+
+#[test]
+fn default_cost_is_the_recommended_one() {
+    assert_eq!(ScryptParams::default(), ScryptParams { log_n: 15, r: 8, p: 1 });
+}
+
+#[test]
+fn passwords_stay_out_of_debug_output() {
+    let argv = ["checkvote", "encrypt-key", "--key", "k", "--out", "o"];
+    let argv = [&argv[..], &["--key-password", "hunter2"]].concat();
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert!(matches!(cli.command, Some(Command::EncryptKey { .. })));
+    assert!(!format!("{cli:?}").contains("hunter2"));
+}
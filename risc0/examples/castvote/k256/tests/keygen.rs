@@ -0,0 +1,107 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `keygen`: random key pairs, and deterministic ones derived from a `--seed` for test vectors.
+
+use castvote_core::{canonicalize_ballot, MessageFormat};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command, Curve, Scheme},
+    sign, verify_signature, VoterKey,
+};
+
+const SEED: &[u8] = b"castvote test vectors";
+
+const SCHEMES: [(Scheme, Curve); 3] =
+    [(Scheme::Ecdsa, Curve::K256), (Scheme::Ecdsa, Curve::P256), (Scheme::Ed25519, Curve::K256)];
+
+#[test]
+fn same_seed_yields_the_same_key_pair() {
+    for (scheme, curve) in SCHEMES {
+        let first = VoterKey::from_seed(scheme, curve, SEED).unwrap();
+        let second = VoterKey::from_seed(scheme, curve, SEED).unwrap();
+        assert_eq!(first.public_key_base64(), second.public_key_base64());
+        assert_eq!(first.private_key_base64(), second.private_key_base64());
+    }
+}
+
+#[test]
+fn seeded_key_is_a_fixed_test_vector() {
+    // Pinned so that a change to the derivation, which would break published vectors, is caught.
+    let key = VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap();
+    assert_eq!(
+        key.public_key_base64(),
+        "BECQAnKwRYaeGDoCFD+JuNc1aj5vdiuJHqhjKO0KR2CYSLGt8mkmRTCbcGgZBp9PRsFM85DgYIBxv61Gv6HkSfo="
+    );
+}
+
+#[test]
+fn different_seeds_and_schemes_yield_unrelated_keys() {
+    let k256 = VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, SEED).unwrap();
+    let other = VoterKey::from_seed(Scheme::Ecdsa, Curve::K256, b"another seed").unwrap();
+    assert_ne!(k256.public_key_base64(), other.public_key_base64());
+
+    let private_key = |(scheme, curve)| VoterKey::from_seed(scheme, curve, SEED).unwrap();
+    let private_keys = SCHEMES.map(|scheme| private_key(scheme).private_key_base64());
+    assert_ne!(private_keys[0], private_keys[1]);
+    assert_ne!(private_keys[0], private_keys[2]);
+    assert_ne!(private_keys[1], private_keys[2]);
+}
+
+#[test]
+fn generated_keys_are_random() {
+    for (scheme, curve) in SCHEMES {
+        let first = VoterKey::generate(scheme, curve).unwrap();
+        let second = VoterKey::generate(scheme, curve).unwrap();
+        assert_ne!(first.public_key_base64(), second.public_key_base64());
+    }
+}
+
+#[test]
+fn printed_private_key_imports_and_signs() {
+    let ballot = canonicalize_ballot(include_str!("../res/person.json")).unwrap();
+    for (scheme, curve) in SCHEMES {
+        let key = VoterKey::from_seed(scheme, curve, SEED).unwrap();
+        let imported = VoterKey::from_base64(scheme, curve, &key.private_key_base64()).unwrap();
+        assert_eq!(imported.public_key_base64(), key.public_key_base64());
+
+        let signature = sign(&imported, &ballot, MessageFormat::default()).unwrap();
+        let public_key = key.public_key_base64();
+        verify_signature(scheme, curve, &ballot, &signature, &public_key, MessageFormat::default())
+            .unwrap();
+    }
+}
+
+#[test]
+fn age_only_has_no_keys() {
+    assert!(VoterKey::from_seed(Scheme::AgeOnly, Curve::K256, SEED).is_err());
+    assert!(VoterKey::generate(Scheme::AgeOnly, Curve::K256).is_err());
+}
+
+#[test]
+fn parses_the_keygen_command() {
+    let cli = Cli::try_parse_from(["checkvote", "keygen", "--seed", "00ff"]).unwrap();
+    match cli.command {
+        Some(Command::Keygen { scheme, curve, seed }) => {
+            assert_eq!((scheme, curve), (Scheme::Ecdsa, Curve::K256));
+            assert_eq!(seed.as_deref(), Some("00ff"));
+        }
+        command => panic!("parsed {command:?}"),
+    }
+    let cli = Cli::try_parse_from(["checkvote", "keygen", "--scheme", "ed25519"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Keygen { scheme: Scheme::Ed25519, seed: None, .. })
+    ));
+}
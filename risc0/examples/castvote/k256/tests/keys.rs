@@ -0,0 +1,182 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key import, ballot loading and signing, without running the prover.
+
+use std::fs;
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{canonicalize_ballot, BallotType, MessageFormat, VoteParams};
+use clap::Parser;
+use k256::ecdsa::{signature::Verifier, Signature};
+use k256_example::{
+    cli::{Cli, Curve, Scheme},
+    import_keys, load_ballot, sign, sign_files, verify_signature, SignedBallot, VoterKey,
+};
+use sha2::{Digest, Sha256};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn key_for(flags: &[&str]) -> VoterKey {
+    let argv = [&["checkvote", "b.json", "--poll-id", "1"], flags].concat();
+    import_keys(&Cli::try_parse_from(argv).unwrap().prove.unwrap()).unwrap()
+}
+
+#[test]
+fn imports_the_keys_the_guests_expect() {
+    // Public keys of the demo keys, as passed to the guest methods.
+    assert_eq!(
+        key_for(&[]).public_key_base64(),
+        "BLZgb3PHEJ6B7Xta+jR4CEn1g3NluqLxNNRlrDfhPTbMATkwv04TOAJJMWuSlrtOfuO9SQNIdGeLlL+ppflRHN4="
+    );
+    assert_eq!(
+        key_for(&["--curve", "p256"]).public_key_base64(),
+        "BIGUuG0MjwkQkGXp0fBJM0t2UE876IfsyrKpaq8dJ6eCuMA5xNP9R7lShafTjgfmybJpOMAb4ZX846MQ/GJLZJY="
+    );
+    assert_eq!(
+        key_for(&["--scheme", "ed25519"]).public_key_base64(),
+        "91nHueo4OP9o5mjEGeNnZNVW3zUSOsllIvkf7pVhf+8="
+    );
+}
+
+#[test]
+fn debug_output_hides_the_private_key() {
+    let debug = format!("{:?}", key_for(&[]));
+    assert!(debug.starts_with("VoterKey::K256(BLZgb3"));
+}
+
+#[test]
+fn every_scheme_produces_a_64_byte_signature() {
+    for flags in [&[][..], &["--curve", "p256"], &["--scheme", "ed25519"]] {
+        let signature = sign(&key_for(flags), BALLOT, MessageFormat::Sha256).unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(signature).unwrap().len(), 64);
+    }
+}
+
+#[test]
+fn eip191_is_refused_for_non_secp256k1_keys() {
+    assert!(sign(&key_for(&[]), BALLOT, MessageFormat::Eip191).is_ok());
+    assert!(sign(&key_for(&["--curve", "p256"]), BALLOT, MessageFormat::Eip191).is_err());
+    assert!(sign(&key_for(&["--scheme", "ed25519"]), BALLOT, MessageFormat::Eip191).is_err());
+}
+
+#[test]
+fn tampered_ballot_fails_verification() {
+    let VoterKey::K256(key) = key_for(&[]) else { unreachable!() };
+    let signature = sign(&VoterKey::K256(key.clone()), BALLOT, MessageFormat::Sha256).unwrap();
+    let signature =
+        Signature::from_slice(&general_purpose::STANDARD.decode(signature).unwrap()).unwrap();
+
+    let verifying_key = key.verifying_key();
+    assert!(verifying_key.verify(&Sha256::digest(BALLOT), &signature).is_ok());
+    let tampered = Sha256::digest(b"This is a tampered message.");
+    assert!(verifying_key.verify(&tampered, &signature).is_err());
+}
+
+#[test]
+fn load_ballot_checks_schema_and_choice() {
+    let dir = tempfile::tempdir().unwrap();
+    let params = VoteParams {
+        ballot_type: BallotType::Single,
+        options: vec!["yes".into(), "no".into()],
+        ..Default::default()
+    };
+
+    let valid = dir.path().join("valid.json");
+    fs::write(&valid, r#"{"id": 1, "age": 30, "is_student": false, "choice": "yes"}"#).unwrap();
+    assert!(load_ballot(&valid, &params).unwrap().contains("\"yes\""));
+
+    let bad_schema = dir.path().join("bad_schema.json");
+    fs::write(&bad_schema, r#"{"id": 1, "is_student": false}"#).unwrap();
+    assert!(load_ballot(&bad_schema, &params).is_err());
+
+    let unknown = dir.path().join("unknown.json");
+    fs::write(&unknown, r#"{"id": 1, "age": 30, "is_student": false, "choice": "maybe"}"#).unwrap();
+    assert!(load_ballot(&unknown, &params).is_err());
+
+    assert!(load_ballot(dir.path().join("missing.json"), &params).is_err());
+}
+
+#[test]
+fn sign_files_outputs_what_the_guest_reads() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = ["a.json", "b.json"].map(|name| dir.path().join(name));
+    fs::write(&paths[0], BALLOT).unwrap();
+    fs::write(&paths[1], r#"{"id": 2, "age": 40, "is_student": true}"#).unwrap();
+    let key = key_for(&[]);
+
+    let signed = sign_files(&key, &paths, 1001, &VoteParams::default()).unwrap();
+
+    assert_eq!(signed.len(), 2);
+    assert_eq!(signed[0].ballot, canonicalize_ballot(BALLOT).unwrap());
+    for entry in &signed {
+        assert_eq!(entry.poll_id, 1001);
+        assert_eq!(entry.public_key, key.public_key_base64());
+        assert_eq!(entry.signature, sign(&key, &entry.ballot, MessageFormat::Sha256).unwrap());
+    }
+    let json = serde_json::to_string(&signed).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<SignedBallot>>(&json).unwrap(), signed);
+
+    let missing = [dir.path().join("missing.json")];
+    assert!(sign_files(&key, &missing, 1001, &VoteParams::default()).is_err());
+}
+
+#[test]
+fn external_signatures_are_checked_per_scheme() {
+    let ballot = canonicalize_ballot(BALLOT).unwrap();
+    let tampered = ballot.replace("\"age\":30", "\"age\":31");
+    let schemes = [
+        (&[][..], Scheme::Ecdsa, Curve::K256),
+        (&["--curve", "p256"], Scheme::Ecdsa, Curve::P256),
+        (&["--scheme", "ed25519"], Scheme::Ed25519, Curve::K256),
+    ];
+    for (flags, scheme, curve) in schemes {
+        let key = key_for(flags);
+        let signature = sign(&key, &ballot, MessageFormat::Sha256).unwrap();
+        let public_key = key.public_key_base64();
+        let verify = |ballot: &str, signature: &str, public_key: &str| {
+            verify_signature(scheme, curve, ballot, signature, public_key, MessageFormat::Sha256)
+        };
+
+        verify(&ballot, &signature, &public_key).unwrap();
+        assert!(verify(&tampered, &signature, &public_key).is_err(), "{flags:?}");
+        assert!(verify(&ballot, &signature[..60], &public_key).is_err(), "{flags:?}");
+        assert!(verify(&ballot, &signature, &public_key[..20]).is_err(), "{flags:?}");
+    }
+}
+
+#[test]
+fn external_signature_from_another_key_is_rejected() {
+    let ballot = canonicalize_ballot(BALLOT).unwrap();
+    let other = VoterKey::K256(k256::ecdsa::SigningKey::from_bytes((&[1u8; 32]).into()).unwrap());
+    let signature = sign(&other, &ballot, MessageFormat::Sha256).unwrap();
+    let public_key = key_for(&[]).public_key_base64();
+    let format = MessageFormat::Sha256;
+    let result = verify_signature(Scheme::Ecdsa, Curve::K256, &ballot, &signature, &public_key, format);
+    assert!(result.is_err());
+}
+
+#[test]
+fn external_eip191_signature_needs_matching_format() {
+    let ballot = canonicalize_ballot(BALLOT).unwrap();
+    let key = key_for(&[]);
+    let public_key = key.public_key_base64();
+    let signature = sign(&key, &ballot, MessageFormat::Eip191).unwrap();
+    let verify = |curve, format| {
+        verify_signature(Scheme::Ecdsa, curve, &ballot, &signature, &public_key, format)
+    };
+    verify(Curve::K256, MessageFormat::Eip191).unwrap();
+    assert!(verify(Curve::K256, MessageFormat::Sha256).is_err());
+    assert!(verify(Curve::P256, MessageFormat::Eip191).is_err());
+}
@@ -0,0 +1,228 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--message-format`: each format signs and verifies on the host and in the guest, and the guest
+//! commits the format it verified.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::{eip712_domain, sol, SolStruct, SolValue};
+use castvote_core::{
+    canonicalize_ballot, decode_k256_signature, decode_k256_verifying_key, eip712_hash,
+    parse_ballot, verify_signed_ballot, BallotError, MessageFormat, RevealInfo, VoteParams,
+    EIP712_DOMAIN_NAME, EIP712_DOMAIN_VERSION,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{Args, Cli, Curve, MessageFormatArg, Scheme},
+    decode_journal, import_keys, prepare_ballot, sign, verify_signature, VoterKey,
+};
+use k256_methods::K256_VERIFY_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+const FORMATS: [MessageFormat; 3] =
+    [MessageFormat::Raw, MessageFormat::Sha256, MessageFormat::Eip712];
+
+sol! {
+    struct Ballot {
+        string ballot;
+    }
+}
+
+fn args(flags: &[&str]) -> clap::error::Result<Args> {
+    let argv = [&["checkvote", "b.json", "--poll-id", "1"], flags].concat();
+    Ok(Cli::try_parse_from(argv)?.prove.unwrap())
+}
+
+fn key() -> VoterKey {
+    import_keys(&args(&[]).unwrap()).unwrap()
+}
+
+fn signed_text(message_format: MessageFormat) -> String {
+    prepare_ballot(BALLOT, &VoteParams { message_format, ..Default::default() }).unwrap()
+}
+
+#[test]
+fn parses_the_flag() {
+    assert_eq!(args(&[]).unwrap().message_format(), MessageFormat::Sha256);
+    let cases = [
+        ("raw", MessageFormat::Raw),
+        ("json-canonical", MessageFormat::Sha256),
+        ("eip712", MessageFormat::Eip712),
+    ];
+    for (flag, format) in cases {
+        let args = args(&["--message-format", flag]).unwrap();
+        assert_eq!(args.message_format(), format);
+        assert_eq!(args.vote_params().unwrap().message_format, format);
+    }
+    assert_eq!(args(&["--eip191"]).unwrap().message_format(), MessageFormat::Eip191);
+    assert_eq!(MessageFormat::from(MessageFormatArg::JsonCanonical), MessageFormat::Sha256);
+}
+
+#[test]
+fn eip191_conflicts_with_message_format() {
+    assert!(args(&["--eip191", "--message-format", "raw"]).is_err());
+}
+
+#[test]
+fn eip712_needs_a_secp256k1_key() {
+    for flags in [&["--curve", "p256"][..], &["--scheme", "ed25519"]] {
+        let args = args(&[flags, &["--message-format", "eip712"]].concat()).unwrap();
+        let error = args.vote_params().unwrap_err().to_string();
+        assert_eq!(error, "--message-format eip712 requires --scheme ecdsa --curve k256");
+    }
+    // Raw and canonical ballots are plain SHA-256 digests, which every scheme signs.
+    let args = args(&["--scheme", "ed25519", "--message-format", "raw"]).unwrap();
+    assert_eq!(args.vote_params().unwrap().message_format, MessageFormat::Raw);
+}
+
+#[test]
+fn raw_signs_the_file_verbatim() {
+    assert_eq!(signed_text(MessageFormat::Raw), BALLOT);
+    let canonical = canonicalize_ballot(BALLOT).unwrap();
+    assert_eq!(signed_text(MessageFormat::Sha256), canonical);
+    assert_eq!(signed_text(MessageFormat::Eip712), canonical);
+}
+
+#[test]
+fn every_format_round_trips() {
+    let key = key();
+    for format in FORMATS {
+        let ballot = signed_text(format);
+        let signature = sign(&key, &ballot, format).unwrap();
+        verify_signature(
+            Scheme::Ecdsa,
+            Curve::K256,
+            &ballot,
+            &signature,
+            &key.public_key_base64(),
+            format,
+        )
+        .unwrap_or_else(|e| panic!("{format:?}: {e:#}"));
+        // A signature in one format never verifies as another.
+        for other in FORMATS.into_iter().filter(|&other| other != format) {
+            let ballot = signed_text(other);
+            let public_key = key.public_key_base64();
+            let result = verify_signature(
+                Scheme::Ecdsa,
+                Curve::K256,
+                &ballot,
+                &signature,
+                &public_key,
+                other,
+            );
+            assert!(result.is_err(), "{format:?} signature verified as {other:?}");
+        }
+    }
+}
+
+#[test]
+fn raw_signatures_do_not_survive_reformatting() {
+    let key = key();
+    let value: serde_json::Value = serde_json::from_str(BALLOT).unwrap();
+    let reformatted = serde_json::to_string(&value).unwrap();
+    assert_ne!(reformatted, BALLOT);
+    let verify = |ballot: &str, signature: &str, format: MessageFormat| {
+        let public_key = key.public_key_base64();
+        verify_signature(Scheme::Ecdsa, Curve::K256, ballot, signature, &public_key, format)
+    };
+
+    let raw = sign(&key, &signed_text(MessageFormat::Raw), MessageFormat::Raw).unwrap();
+    let ballot = MessageFormat::Raw.signed_text(&reformatted).unwrap();
+    assert!(verify(&ballot, &raw, MessageFormat::Raw).is_err());
+
+    let canonical = sign(&key, &signed_text(MessageFormat::Sha256), MessageFormat::Sha256).unwrap();
+    let ballot = MessageFormat::Sha256.signed_text(&reformatted).unwrap();
+    verify(&ballot, &canonical, MessageFormat::Sha256).unwrap();
+}
+
+#[test]
+fn guests_verify_the_text_they_parse() {
+    let key = key();
+    let verifying_key = decode_k256_verifying_key(&key.public_key_base64()).unwrap();
+    for format in FORMATS {
+        let params = VoteParams { message_format: format, ..Default::default() };
+        let signature = sign(&key, &signed_text(format), format).unwrap();
+        let signature = decode_k256_signature(&signature).unwrap();
+        // The guest is handed the ballot file as is and derives the signed text itself.
+        let (ballot, verified) = verify_signed_ballot(&params, BALLOT, |_, digest| {
+            Ok(digest.verify_k256(&verifying_key, &signature).is_ok())
+        })
+        .unwrap();
+        assert!(verified, "{format:?}");
+        assert_eq!(ballot.id, parse_ballot(BALLOT).unwrap().id);
+    }
+
+    // An oversized ballot never reaches the signature check.
+    let params = VoteParams { max_ballot_size: Some(8), ..Default::default() };
+    let error = verify_signed_ballot(&params, BALLOT, |_, _| -> Result<(), _> {
+        panic!("verified an oversized ballot")
+    })
+    .unwrap_err();
+    let error = error.downcast_ref::<BallotError>();
+    assert!(matches!(error, Some(BallotError::BallotTooLarge { .. })));
+}
+
+#[test]
+fn eip712_digest_matches_alloy() {
+    let ballot = signed_text(MessageFormat::Eip712);
+    let domain = eip712_domain! {
+        name: EIP712_DOMAIN_NAME,
+        version: EIP712_DOMAIN_VERSION,
+    };
+    let expected = Ballot { ballot: ballot.clone() }.eip712_signing_hash(&domain);
+    assert_eq!(eip712_hash(ballot.as_bytes()), expected.0);
+}
+
+fn execute(
+    ballot: &str,
+    signature: String,
+    message_format: MessageFormat,
+) -> anyhow::Result<RevealInfo> {
+    let params = VoteParams { message_format, ..Default::default() };
+    let input = (signature, ballot, 1001u64, key().public_key_base64());
+    let env = ExecutorEnv::builder().write(&input)?.write(&params)?.build()?;
+    let session = default_executor().execute(env, K256_VERIFY_ELF)?;
+    decode_journal(&session.journal.bytes)
+}
+
+#[test]
+fn guest_commits_the_message_format() {
+    let key = key();
+    for format in FORMATS {
+        let ballot = signed_text(format);
+        let reveal = execute(&ballot, sign(&key, &ballot, format).unwrap(), format).unwrap();
+        assert_eq!(reveal.message_format, Some(format));
+    }
+    // The guest derives the signed text itself, so a raw signature fails once canonicalized.
+    let raw = sign(&key, BALLOT, MessageFormat::Raw).unwrap();
+    assert!(execute(BALLOT, raw, MessageFormat::Sha256).is_err());
+}
+
+#[test]
+fn version_4_journals_decode_without_a_message_format() {
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap();
+    let reveal =
+        RevealInfo { message_format: None, student_required: None, abstained: None, ..reveal };
+    // Version 4 is the current version without the trailing `message_format`, `student_required`,
+    // `policy_hash`, `poll_name`, `receipt_id`, `id_commitment`, `max_poll_id`, `author_key_hash`
+    // and `abstained`, each encoded here as a single `None` byte.
+    let mut v4 = reveal.encode();
+    assert_eq!(v4.split_off(v4.len() - 9), [0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    v4[0] = 4;
+    assert_eq!(decode_journal(&v4.abi_encode()).unwrap(), reveal);
+
+}
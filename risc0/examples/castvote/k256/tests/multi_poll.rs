@@ -0,0 +1,142 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots voting in several polls under one signature, with one reveal entry per poll.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, canonicalize_ballot, compute_nullifier, encode_reveals, parse_ballot,
+    BallotError, RevealInfo, VoteParams,
+};
+use k256_example::{
+    decode_batch_journal, decode_reveal_entries, prepare_ballot, report::Report, sign_ballot,
+    validate_ballot, BallotSigner,
+};
+use k256_methods::K256_VERIFY_MULTI_ELF;
+
+mod common;
+
+const BALLOT: &str =
+    r#"{"id": 7, "age": 30, "is_student": false, "choices": {"1001": "yes", "1002": "no"}}"#;
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], ..VoteParams::default() }
+}
+
+fn signed(poll_ids: Vec<u64>) -> (String, String, Vec<u64>, String) {
+    let signer = common::signer();
+    let signature = sign_ballot(&signer, &canonicalize_ballot(BALLOT).unwrap()).unwrap();
+    let public_key = signer.public_key().to_encoded_point(true);
+    (signature, BALLOT.into(), poll_ids, general_purpose::STANDARD.encode(public_key.as_bytes()))
+}
+
+fn execute(input: &(String, String, Vec<u64>, String)) -> anyhow::Result<Vec<u8>> {
+    common::execute_journal(K256_VERIFY_MULTI_ELF, input, &params())
+}
+
+fn reveals(poll_ids: &[u64]) -> Result<Vec<RevealInfo>, BallotError> {
+    let signer = common::signer();
+    let public_key = canonical_k256_key(&signer.public_key());
+    RevealInfo::for_polls(&parse_ballot(BALLOT).unwrap(), poll_ids, &public_key, &params())
+}
+
+#[test]
+fn guest_commits_a_nullifier_per_poll() {
+    let journal = execute(&signed(vec![1001, 1002])).unwrap();
+    let reveals = decode_batch_journal(&journal).unwrap();
+
+    assert_eq!(reveals.len(), 2);
+    assert_ne!(reveals[0].nullifier, reveals[1].nullifier);
+    let id = parse_ballot(BALLOT).unwrap().id;
+    for (reveal, (poll_id, choice)) in reveals.iter().zip([(1001, "yes"), (1002, "no")]) {
+        assert_eq!(reveal.poll_id, poll_id);
+        assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&id, poll_id)));
+        assert_eq!(reveal.selection, [choice]);
+    }
+}
+
+#[test]
+fn guest_rejects_a_poll_the_ballot_does_not_vote_in() {
+    assert!(execute(&signed(vec![1001, 1003])).is_err());
+    assert!(execute(&signed(vec![1001])).is_err());
+}
+
+#[test]
+fn every_poll_gets_its_own_entry() {
+    let reveals = reveals(&[1002, 1001]).unwrap();
+    assert_eq!(reveals.iter().map(|r| r.poll_id).collect::<Vec<_>>(), [1002, 1001]);
+    assert_eq!((&reveals[0].selection, &reveals[1].selection), (&vec!["no".to_string()], &vec!["yes".to_string()]));
+    assert_ne!(reveals[0].nullifier, reveals[1].nullifier);
+    assert_eq!(reveals[0].voter_key_hash, reveals[1].voter_key_hash);
+}
+
+#[test]
+fn polls_must_match_the_signed_choices() {
+    assert_eq!(reveals(&[]), Err(BallotError::NoPolls));
+    for (poll_ids, poll_id, reason) in [
+        (&[1001, 1003][..], 1003, "has no choice in the ballot"),
+        (&[1001, 1001, 1002], 1001, "is listed more than once"),
+        (&[1001], 1002, "has a choice in the ballot but is not proven"),
+    ] {
+        assert_eq!(reveals(poll_ids), Err(BallotError::UnmatchedPoll { poll_id, reason }));
+    }
+}
+
+#[test]
+fn single_and_multi_poll_choices_do_not_mix() {
+    let single = parse_ballot(BALLOT).unwrap();
+    assert!(matches!(single.selection(&params()), Err(BallotError::InvalidChoice { .. })));
+
+    let both =
+        r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes", "choices": {"1001": "yes"}}"#;
+    assert!(validate_ballot(both).is_err());
+    let ballot = parse_ballot(both).unwrap();
+    assert!(matches!(ballot.poll_selections(&params()), Err(BallotError::InvalidChoice { .. })));
+}
+
+#[test]
+fn choices_are_validated_before_signing() {
+    prepare_ballot(BALLOT, &params()).unwrap();
+    for ballot in [
+        r#"{"id": 7, "age": 30, "is_student": false, "choices": {}}"#,
+        r#"{"id": 7, "age": 30, "is_student": false, "choices": {"poll": "yes"}}"#,
+        r#"{"id": 7, "age": 30, "is_student": false, "choices": {"1001": ""}}"#,
+    ] {
+        assert!(validate_ballot(ballot).is_err(), "{ballot}");
+    }
+    let unknown = r#"{"id": 7, "age": 30, "is_student": false, "choices": {"1001": "maybe"}}"#;
+    assert!(prepare_ballot(unknown, &params()).is_err());
+}
+
+#[test]
+fn report_tallies_every_entry_of_a_vector_journal() {
+    let journal = encode_reveals(&reveals(&[1001, 1002]).unwrap()).abi_encode();
+    assert_eq!(decode_reveal_entries(&journal).unwrap().len(), 2);
+
+    let mut report = Report::new();
+    report.add_journal("multi.dat", &journal);
+    assert!(report.rejected().is_empty());
+    assert_eq!(report.counts().get(&(None, 1001, "yes".to_string())), Some(&1));
+    assert_eq!(report.counts().get(&(None, 1002, "no".to_string())), Some(&1));
+}
+
+#[test]
+fn single_reveal_journals_still_decode_as_one_entry() {
+    let reveal = reveals(&[1001, 1002]).unwrap().remove(0);
+    let journal = reveal.encode().abi_encode();
+    assert_eq!(decode_reveal_entries(&journal).unwrap(), [reveal]);
+}
@@ -0,0 +1,143 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Auditing journals by recomputing the nullifier of a ballot.
+
+use std::{fs, path::Path};
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    encode_reveals, parse_ballot, scoped_nullifier, ElectionScope, PollIdentifier, RevealInfo,
+    VoteParams,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    nullifier::{check_nullifier_files, find_nullifier, NullifierMatch, NullifierMismatch},
+    write_hex_file,
+};
+
+const SEED: [u8; 32] = [7; 32];
+
+const ALICE: &str = r#"{"id": 123, "age": 30, "is_student": true, "choice": "yes"}"#;
+const BOB: &str = r#"{"id": 456, "age": 41, "is_student": false, "choice": "no"}"#;
+
+fn scope(election_id: &str, seed: [u8; 32]) -> ElectionScope {
+    ElectionScope { election_id: election_id.into(), authority_seed: seed.to_vec() }
+}
+
+fn reveal(ballot: &str, poll_id: u64, election: Option<ElectionScope>) -> RevealInfo {
+    let options = vec!["yes".into(), "no".into()];
+    let params = VoteParams { options, election, ..Default::default() };
+    RevealInfo::new(&parse_ballot(ballot).unwrap(), poll_id, &[], &params).unwrap()
+}
+
+fn id(ballot: &str) -> serde_json::Value {
+    parse_ballot(ballot).unwrap().id
+}
+
+#[test]
+fn guest_and_audit_share_the_nullifier() {
+    for election in [None, Some(scope("2025-spring", SEED))] {
+        let committed = reveal(ALICE, 1001, election.clone()).nullifier;
+        assert_eq!(committed, hex::encode(scoped_nullifier(&id(ALICE), 1001, election.as_ref())));
+    }
+}
+
+#[test]
+fn finds_the_ballot_in_single_and_batch_journals() {
+    let single = [reveal(ALICE, 1001, None)];
+    let found = find_nullifier(&id(ALICE), 1001, None, &single).unwrap();
+    assert_eq!(found, NullifierMatch { index: 0, nullifier: single[0].nullifier.clone() });
+
+    let batch = [reveal(ALICE, 1001, None), reveal(BOB, 1001, None)];
+    assert_eq!(find_nullifier(&id(BOB), 1001, None, &batch).unwrap().index, 1);
+}
+
+#[test]
+fn another_ballot_or_poll_does_not_match() {
+    let journal = [reveal(ALICE, 1001, None)];
+    let err = find_nullifier(&id(BOB), 1001, None, &journal).unwrap_err();
+    assert!(matches!(err, NullifierMismatch::Nullifier { .. }), "{err}");
+
+    let err = find_nullifier(&id(ALICE), 1002, None, &journal).unwrap_err();
+    assert_eq!(err, NullifierMismatch::PollNotInJournal { poll_id: 1002, committed: vec![1001] });
+}
+
+#[test]
+fn salt_parameters_must_match_the_prover() {
+    let spring = scope("2025-spring", SEED);
+    let journal = [reveal(ALICE, 1001, Some(spring.clone()))];
+    find_nullifier(&id(ALICE), 1001, Some(&spring), &journal).unwrap();
+
+    let err = find_nullifier(&id(ALICE), 1001, None, &journal).unwrap_err();
+    assert_eq!(
+        err,
+        NullifierMismatch::ElectionMismatch { expected: None, committed: Some("2025-spring".into()) }
+    );
+    assert!(err.to_string().contains("--election-id"), "{err}");
+
+    let autumn = scope("2025-autumn", SEED);
+    let err = find_nullifier(&id(ALICE), 1001, Some(&autumn), &journal).unwrap_err();
+    assert!(matches!(err, NullifierMismatch::ElectionMismatch { .. }), "{err}");
+
+    // The right election with the wrong seed can only show up as a different nullifier.
+    let wrong_seed = scope("2025-spring", [8; 32]);
+    let err = find_nullifier(&id(ALICE), 1001, Some(&wrong_seed), &journal).unwrap_err();
+    assert!(matches!(err, NullifierMismatch::Nullifier { .. }), "{err}");
+
+    let unscoped = [reveal(ALICE, 1001, None)];
+    let err = find_nullifier(&id(ALICE), 1001, Some(&spring), &unscoped).unwrap_err();
+    assert!(err.to_string().contains("fixed salt"), "{err}");
+}
+
+fn write_journal(path: &Path, reveals: &[RevealInfo]) {
+    write_hex_file(path, &encode_reveals(reveals).abi_encode()).unwrap();
+}
+
+#[test]
+fn checks_ballot_and_journal_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ballot, journal) = (dir.path().join("ballot.json"), dir.path().join("journal.dat"));
+    // Key order and whitespace do not matter: the nullifier is taken over the canonical ballot.
+    let reordered = r#"{
+        "is_student": true, "choice": "yes", "age": 30, "id": 123
+    }"#;
+    fs::write(&ballot, reordered).unwrap();
+    write_journal(&journal, &[reveal(BOB, 1001, None), reveal(ALICE, 1001, None)]);
+
+    let found = check_nullifier_files(&ballot, 1001, &journal, None).unwrap().unwrap();
+    assert_eq!(found.index, 1);
+    assert!(check_nullifier_files(&ballot, 1002, &journal, None).unwrap().is_err());
+
+    fs::write(&journal, "not hex").unwrap();
+    assert!(check_nullifier_files(&ballot, 1001, &journal, None).is_err());
+}
+
+#[test]
+fn parses_the_check_nullifier_command() {
+    let argv = [
+        "checkvote", "check-nullifier", "--ballot", "b.json", "--poll-id", "1001", "--journal",
+        "journal.dat",
+    ];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::CheckNullifier { poll_id: PollIdentifier::Numeric(1001), .. })
+    ));
+
+    let half_scoped = [&argv[..], &["--election-id", "2025-spring"]].concat();
+    assert!(Cli::try_parse_from(half_scoped).is_err());
+}
@@ -0,0 +1,233 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--nullifier-store`: nullifiers computed on the host before proving, so a known double vote
+//! is rejected without running the prover.
+
+use std::{cell::Cell, fs};
+
+use castvote_core::{parse_ballot, ElectionScope, RevealInfo, VoteParams};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    error::VoteError,
+    input::GuestInput,
+    nullifier_store::{
+        expected_nullifier, merge_stores, write_store, CrossStoreDuplicate, NullifierStore,
+        UsedNullifier,
+    },
+};
+
+const ALICE: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes"}"#;
+const ALICE_AGAIN: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "no"}"#;
+const BOB: &str = r#"{"id": 8, "age": 41, "is_student": false, "choice": "no"}"#;
+
+fn params(election: Option<ElectionScope>) -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], election, ..VoteParams::default() }
+}
+
+fn ballot_input(ballot: &str) -> GuestInput {
+    (String::new(), ballot.to_string(), 1001, String::new()).into()
+}
+
+#[test]
+fn host_nullifier_matches_the_committed_one() {
+    let spring = || ElectionScope { election_id: "spring".into(), authority_seed: vec![9; 32] };
+    for params in [params(None), params(Some(spring()))] {
+        let reveal = RevealInfo::new(&parse_ballot(ALICE).unwrap(), 1001, &[], &params).unwrap();
+        let expected = expected_nullifier(ALICE, 1001, params.election.as_ref()).unwrap();
+        assert_eq!(expected, reveal.nullifier);
+        assert_eq!(ballot_input(ALICE).nullifiers(&params).unwrap(), [(1001, expected)]);
+    }
+}
+
+#[test]
+fn every_entry_of_an_input_has_a_nullifier() {
+    let params = params(None);
+    let nullifier = |ballot, poll_id| expected_nullifier(ballot, poll_id, None).unwrap();
+
+    let batch: GuestInput = vec![
+        (String::new(), ALICE.to_string(), 1001, String::new()),
+        (String::new(), BOB.to_string(), 1001, String::new()),
+    ]
+    .into();
+    let expected = [(1001, nullifier(ALICE, 1001)), (1001, nullifier(BOB, 1001))];
+    assert_eq!(batch.nullifiers(&params).unwrap(), expected);
+
+    let multi: GuestInput =
+        (String::new(), ALICE.to_string(), vec![1001, 1002], String::new()).into();
+    let expected = [(1001, nullifier(ALICE, 1001)), (1002, nullifier(ALICE, 1002))];
+    assert_eq!(multi.nullifiers(&params).unwrap(), expected);
+}
+
+#[test]
+fn known_duplicate_is_rejected_without_proving() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nullifiers.json");
+    let params = params(None);
+    let first = ballot_input(ALICE).nullifiers(&params).unwrap();
+
+    let mut store = NullifierStore::open(&path).unwrap();
+    assert!(store.nullifiers().is_empty());
+    store.prove_once(&first, || Ok(())).unwrap();
+    assert!(path.exists());
+
+    // A second ballot from the same voter, in a later run.
+    let mut store = NullifierStore::open(&path).unwrap();
+    let again = ballot_input(ALICE_AGAIN).nullifiers(&params).unwrap();
+    assert_eq!(again, first);
+    let proved = Cell::new(false);
+    let prove = || {
+        proved.set(true);
+        Ok(())
+    };
+    let err = store.prove_once(&again, prove).unwrap_err();
+    assert!(!proved.get(), "the prover ran for a known double vote");
+    assert_eq!(VoteError::exit_code_of(&err), 6, "{err:#}");
+    let used = err.chain().find_map(|cause| cause.downcast_ref::<UsedNullifier>()).unwrap();
+    assert_eq!(used, &UsedNullifier { poll_id: 1001, nullifier: first[0].1.clone() });
+
+    // Another voter, or the same one in another poll, is proven.
+    let bob = ballot_input(BOB).nullifiers(&params).unwrap();
+    store.prove_once(&bob, || Ok(())).unwrap();
+    let other_poll = vec![(1002, expected_nullifier(ALICE, 1002, None).unwrap())];
+    store.prove_once(&other_poll, || Ok(())).unwrap();
+    assert_eq!(NullifierStore::open(&path).unwrap().nullifiers().len(), 3);
+}
+
+#[test]
+fn duplicate_within_one_input_is_rejected() {
+    let store = NullifierStore::open(&tempfile::tempdir().unwrap().path().join("n.json")).unwrap();
+    let batch: GuestInput = vec![
+        (String::new(), ALICE.to_string(), 1001, String::new()),
+        (String::new(), ALICE_AGAIN.to_string(), 1001, String::new()),
+    ]
+    .into();
+    let nullifiers = batch.nullifiers(&params(None)).unwrap();
+    assert_eq!(store.check(&nullifiers).unwrap_err().nullifier, nullifiers[1].1);
+}
+
+#[test]
+fn failed_proof_records_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nullifiers.json");
+    let nullifiers = ballot_input(ALICE).nullifiers(&params(None)).unwrap();
+    let mut store = NullifierStore::open(&path).unwrap();
+    assert!(store.prove_once::<()>(&nullifiers, || anyhow::bail!("prover crashed")).is_err());
+    assert!(store.nullifiers().is_empty());
+    assert!(!path.exists());
+    store.prove_once(&nullifiers, || Ok(())).unwrap();
+}
+
+#[test]
+fn malformed_store_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nullifiers.json");
+    fs::write(&path, "not json").unwrap();
+    assert!(NullifierStore::open(&path).is_err());
+}
+
+#[test]
+fn flag_only_applies_when_proving() {
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "ballot.json", "--poll-id", "1001"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let args = parse(&["--nullifier-store", "nullifiers.json"]).unwrap();
+    assert_eq!(args.nullifier_store.as_deref(), Some("nullifiers.json".as_ref()));
+    assert!(parse(&["--nullifier-store", "n.json", "--no-prove"]).is_err());
+    assert!(parse(&["--nullifier-store", "n.json", "--explain"]).is_err());
+}
+
+/// A store at `dir/name` holding the nullifiers of `ballots` in poll 1001, as a run on one
+/// machine would leave it.
+fn machine_store(dir: &std::path::Path, name: &str, ballots: &[&str]) -> std::path::PathBuf {
+    let path = dir.join(name);
+    let mut store = NullifierStore::open(&path).unwrap();
+    for ballot in ballots {
+        let nullifiers = ballot_input(ballot).nullifiers(&params(None)).unwrap();
+        store.prove_once(&nullifiers, || Ok(())).unwrap();
+    }
+    path
+}
+
+#[test]
+fn merging_overlapping_stores_surfaces_the_cross_store_double_vote() {
+    let dir = tempfile::tempdir().unwrap();
+    // Each machine accepted one of Alice's two ballots: neither store alone sees a double vote.
+    let east = machine_store(dir.path(), "east.json", &[ALICE]);
+    let west = machine_store(dir.path(), "west.json", &[BOB, ALICE_AGAIN]);
+    let north = dir.path().join("north.json");
+    write_store(&north, &Default::default()).unwrap();
+
+    let merged = merge_stores(&[east.clone(), west.clone(), north.clone()]).unwrap();
+    assert_eq!(merged.nullifiers.len(), 2);
+    let alice = expected_nullifier(ALICE, 1001, None).unwrap();
+    let duplicate =
+        CrossStoreDuplicate { poll_id: 1001, nullifier: alice.clone(), sources: vec![east, west] };
+    let message = duplicate.to_string();
+    assert_eq!(merged.duplicates, [duplicate]);
+    assert!(message.contains(&alice) && message.contains("poll 1001"), "{message}");
+    assert!(message.contains("east.json, ") && message.ends_with("west.json"), "{message}");
+
+    // The merged store rejects Alice's ballots in later runs.
+    let combined = dir.path().join("combined.json");
+    write_store(&combined, &merged.nullifiers).unwrap();
+    let store = NullifierStore::open(&combined).unwrap();
+    let again = ballot_input(ALICE_AGAIN).nullifiers(&params(None)).unwrap();
+    assert!(store.check(&again).is_err());
+}
+
+#[test]
+fn merging_is_deterministic_and_idempotent() {
+    let dir = tempfile::tempdir().unwrap();
+    let east = machine_store(dir.path(), "east.json", &[ALICE]);
+    let west = machine_store(dir.path(), "west.json", &[BOB]);
+
+    let merged = merge_stores(&[east.clone(), west.clone()]).unwrap();
+    assert!(merged.duplicates.is_empty());
+    let (first, second) = (dir.path().join("first.json"), dir.path().join("second.json"));
+    write_store(&first, &merged.nullifiers).unwrap();
+    let reversed = merge_stores(&[west.clone(), east.clone()]).unwrap();
+    write_store(&second, &reversed.nullifiers).unwrap();
+    assert_eq!(fs::read(&first).unwrap(), fs::read(&second).unwrap());
+
+    // A store given twice is read once, and merging a merged store alone changes nothing.
+    assert_eq!(merge_stores(&[east.clone(), west, east.clone()]).unwrap(), merged);
+    let remerged = merge_stores(&[first]).unwrap();
+    assert_eq!(remerged.nullifiers, merged.nullifiers);
+    assert!(remerged.duplicates.is_empty());
+}
+
+#[test]
+fn merging_a_missing_or_malformed_store_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let east = machine_store(dir.path(), "east.json", &[ALICE]);
+    assert!(merge_stores(&[east.clone(), dir.path().join("typo.json")]).is_err());
+    let malformed = dir.path().join("malformed.json");
+    fs::write(&malformed, "not json").unwrap();
+    assert!(merge_stores(&[east, malformed]).is_err());
+}
+
+#[test]
+fn parses_the_merge_nullifiers_command() {
+    let argv = ["checkvote", "merge-nullifiers", "--out", "all.json", "a.json", "b.json"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    let Some(Command::MergeNullifiers { out, stores }) = cli.command else {
+        panic!("parsed {:?}", cli.command);
+    };
+    assert_eq!(out, std::path::Path::new("all.json"));
+    assert_eq!(stores, [std::path::PathBuf::from("a.json"), "b.json".into()]);
+    assert!(Cli::try_parse_from(["checkvote", "merge-nullifiers", "--out", "all.json"]).is_err());
+}
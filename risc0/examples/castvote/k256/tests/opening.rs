@@ -0,0 +1,131 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Openings of homomorphically encrypted votes.
+
+use castvote_core::{
+    canonicalize_ballot, encrypt_vote, one_hot, parse_ballot, parse_randomness, ElGamalInput,
+    HomomorphicReveal, RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256::SecretKey;
+use k256_example::{
+    cli::{Cli, Command},
+    homomorphic::elgamal_input,
+    opening::{find_opening, read_openings, save_opening, Opening},
+};
+
+const BALLOT: &str = r#"{"id": 123, "age": 30, "is_student": true, "choice": "no"}"#;
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into(), "maybe".into()], ..Default::default() }
+}
+
+fn elgamal() -> ElGamalInput {
+    let election_key = SecretKey::from_bytes((&[9; 32]).into()).unwrap().public_key();
+    elgamal_input(&election_key, params().options.len())
+}
+
+/// What the homomorphic guest commits for `ballot`.
+fn commit(ballot: &str, elgamal: &ElGamalInput) -> HomomorphicReveal {
+    let params = params();
+    let mut reveal = RevealInfo::new(&parse_ballot(ballot).unwrap(), 1001, &[], &params).unwrap();
+    let vote = one_hot(&reveal.selection, params.ballot_type, &params.options).unwrap();
+    let randomness: Vec<_> =
+        elgamal.randomness.iter().map(|r| parse_randomness(r).unwrap()).collect();
+    let election_key = k256::PublicKey::from_sec1_bytes(&elgamal.election_key).unwrap();
+    let ciphertexts = encrypt_vote(&election_key, &vote, &randomness).unwrap();
+    reveal.selection.clear();
+    HomomorphicReveal { reveal, election_key: elgamal.election_key.clone(), ciphertexts }
+}
+
+fn opening(elgamal: &ElGamalInput) -> Opening {
+    Opening::new(&canonicalize_ballot(BALLOT).unwrap(), 1001, &params(), elgamal).unwrap()
+}
+
+#[test]
+fn the_opening_reproduces_the_committed_ciphertexts() {
+    let elgamal = elgamal();
+    let committed = commit(BALLOT, &elgamal);
+    let opening = opening(&elgamal);
+    assert_eq!(opening.nullifier, committed.reveal.nullifier);
+    assert_eq!(opening.selection, ["no"]);
+    opening.verify(&committed).unwrap();
+}
+
+#[test]
+fn a_false_claim_does_not_open() {
+    let elgamal = elgamal();
+    let committed = commit(BALLOT, &elgamal);
+
+    let claimed_yes = Opening { selection: vec!["yes".into()], ..opening(&elgamal) };
+    let err = claimed_yes.verify(&committed).unwrap_err();
+    assert!(err.to_string().contains("does not reproduce"), "{err}");
+
+    let other_randomness = opening(&self::elgamal());
+    assert!(other_randomness.verify(&committed).is_err());
+
+    let other_voter = commit(&BALLOT.replace("123", "456"), &elgamal);
+    let err = opening(&elgamal).verify(&other_voter).unwrap_err();
+    assert!(err.to_string().contains("nullifier"), "{err}");
+}
+
+#[test]
+fn debug_output_hides_the_vote_and_the_randomness() {
+    let opening = opening(&elgamal());
+    let debug = format!("{opening:?}");
+    assert!(debug.contains(&opening.nullifier), "{debug}");
+    assert!(!debug.contains("\"no\"") && !debug.contains(&opening.randomness[0]), "{debug}");
+}
+
+#[test]
+fn openings_are_kept_per_nullifier() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("openings.json");
+    assert!(read_openings(&path).unwrap().is_empty());
+
+    let first = opening(&elgamal());
+    let second = opening(&elgamal());
+    let other = Opening::new(
+        &canonicalize_ballot(&BALLOT.replace("123", "456")).unwrap(),
+        1001,
+        &params(),
+        &elgamal(),
+    )
+    .unwrap();
+    save_opening(&path, first).unwrap();
+    save_opening(&path, other.clone()).unwrap();
+    save_opening(&path, second.clone()).unwrap();
+
+    assert_eq!(read_openings(&path).unwrap(), [other, second.clone()]);
+    assert_eq!(find_opening(&path, &second.nullifier.to_uppercase()).unwrap(), second);
+    assert!(find_opening(&path, "00").is_err());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn parses_the_open_command() {
+    let cli = Cli::try_parse_from(["checkvote", "open", "--nullifier", "ab12"]).unwrap();
+    let Some(Command::Open { nullifier, journal, openings }) = cli.command else {
+        panic!("expected the open command");
+    };
+    assert_eq!(nullifier, "ab12");
+    assert_eq!((journal, openings), (None, None));
+}
@@ -0,0 +1,142 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+
+use k256_example::{
+    atomic_write, atomic_write_with, ensure_writable_dir, write_hex_file, write_json_file,
+};
+
+fn entries(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn writes_and_replaces_without_leftovers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal.dat");
+
+    atomic_write(&path, "first").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+    atomic_write(&path, "second").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+    assert_eq!(entries(dir.path()), ["journal.dat"]);
+}
+
+#[test]
+fn failed_write_keeps_previous_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    // Renaming a file over a non-empty directory fails after the temporary file was written.
+    let path = dir.path().join("seal.dat");
+    fs::create_dir(&path).unwrap();
+    fs::write(path.join("keep"), "").unwrap();
+
+    assert!(atomic_write(&path, "seal").is_err());
+    assert!(path.is_dir());
+    assert_eq!(entries(dir.path()), ["seal.dat"]);
+}
+
+#[test]
+fn missing_directory_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(atomic_write(dir.path().join("missing/image_id.dat"), "id").is_err());
+}
+
+#[test]
+fn hex_is_written_in_chunks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seal.dat");
+    // Spans several chunks and ends in a partial one.
+    let bytes: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    write_hex_file(&path, &bytes).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), hex::encode(&bytes));
+
+    write_hex_file(&path, &[]).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+}
+
+#[test]
+fn json_is_streamed_into_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("receipt.json");
+    let value = serde_json::json!({"journal": [1, 2, 3], "seal": "ab"});
+    write_json_file(&path, &value).unwrap();
+    let written: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(written, value);
+    assert_eq!(entries(dir.path()), ["receipt.json"]);
+}
+
+#[test]
+fn failed_stream_leaves_no_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("receipt.json");
+    let result = atomic_write_with(&path, |writer| {
+        writer.write_all(b"{")?;
+        anyhow::bail!("serializer failed")
+    });
+    assert!(result.is_err());
+    assert!(entries(dir.path()).is_empty());
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_needs_the_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = write_json_file(dir.path().join("receipt.json.gz"), &1).unwrap_err();
+    assert!(format!("{err:#}").contains("gzip feature"), "{err:#}");
+    assert!(entries(dir.path()).is_empty());
+}
+
+#[test]
+fn output_directory_is_created() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("nested/out");
+    ensure_writable_dir(&out_dir).unwrap();
+    assert!(out_dir.is_dir());
+    assert!(entries(&out_dir).is_empty(), "the probe file is removed");
+}
+
+#[test]
+fn output_directory_under_a_file_is_not_writable() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("file"), "").unwrap();
+    let err = ensure_writable_dir(&dir.path().join("file/out")).unwrap_err().to_string();
+    assert!(err.contains("is not writable"), "{err}");
+}
+
+#[cfg(unix)]
+#[test]
+fn read_only_output_directory_is_not_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("out");
+    fs::create_dir(&out_dir).unwrap();
+    fs::set_permissions(&out_dir, fs::Permissions::from_mode(0o555)).unwrap();
+    // Root ignores permission bits, so there is nothing to simulate.
+    if fs::write(out_dir.join("probe"), "").is_ok() {
+        return;
+    }
+
+    let err = ensure_writable_dir(&out_dir).unwrap_err().to_string();
+    let expected = format!("output directory {} is not writable: ", out_dir.display());
+    assert!(err.starts_with(&expected), "{err}");
+    fs::set_permissions(&out_dir, fs::Permissions::from_mode(0o755)).unwrap();
+}
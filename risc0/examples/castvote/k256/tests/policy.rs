@@ -0,0 +1,186 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--policy`: eligibility rules evaluated in the guest, whose hash the journal commits.
+#![cfg(feature = "prove")]
+
+use std::fs;
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    parse_ballot, AgeRange, BallotError, EligibilityPolicy, RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{read_policy, Cli},
+    decode_journal, prepare_ballot,
+};
+
+mod common;
+
+const POLL_ID: u64 = 1001;
+
+fn ballot(age: u32, is_student: bool) -> String {
+    format!(r#"{{"id": 1, "age": {age}, "is_student": {is_student}}}"#)
+}
+
+fn params(policy: EligibilityPolicy) -> VoteParams {
+    // Wide enough that only the policy decides who is eligible.
+    let age_range = AgeRange { min: 0, max: 120 };
+    VoteParams { age_range, policy: Some(policy), ..Default::default() }
+}
+
+fn reveal(age: u32, is_student: bool, params: &VoteParams) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(&ballot(age, is_student)).unwrap(), POLL_ID, &[], params)
+}
+
+/// Whether `policy` admits a voter, restated independently of [EligibilityPolicy::check].
+fn admits(policy: &EligibilityPolicy, age: u32, is_student: bool) -> bool {
+    age >= policy.min_age
+        && (is_student || !policy.require_student)
+        && policy.allowed_polls.as_ref().is_none_or(|polls| polls.contains(&POLL_ID))
+}
+
+#[test]
+fn every_rule_combination_is_enforced() {
+    for min_age in [0, 21] {
+        for require_student in [false, true] {
+            for allowed_polls in [None, Some(vec![POLL_ID, 7]), Some(vec![7])] {
+                let policy = EligibilityPolicy { min_age, require_student, allowed_polls };
+                for (age, is_student) in [(20, false), (20, true), (30, false), (30, true)] {
+                    let case = format!("{policy:?}, age {age}, student {is_student}");
+                    match reveal(age, is_student, &params(policy.clone())) {
+                        Ok(committed) => {
+                            assert!(admits(&policy, age, is_student), "accepted {case}");
+                            assert_eq!(committed.policy_hash, Some(hex::encode(policy.hash())));
+                        }
+                        Err(BallotError::Ineligible { .. }) => {
+                            assert!(!admits(&policy, age, is_student), "rejected {case}");
+                        }
+                        Err(e) => panic!("{case}: {e}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn each_rule_reports_why() {
+    let reason = |policy: EligibilityPolicy, age: u32, is_student: bool| {
+        reveal(age, is_student, &params(policy)).unwrap_err().to_string()
+    };
+    let young = reason(EligibilityPolicy { min_age: 21, ..Default::default() }, 20, true);
+    assert!(young.contains("younger than `min_age`"), "{young}");
+    let policy = EligibilityPolicy { require_student: true, ..Default::default() };
+    let student = reason(policy, 30, false);
+    assert!(student.contains("not a student"), "{student}");
+    let policy = EligibilityPolicy { allowed_polls: Some(vec![]), ..Default::default() };
+    let polls = reason(policy, 30, true);
+    assert!(polls.contains("not in `allowed_polls`"), "{polls}");
+}
+
+#[test]
+fn hash_covers_the_rules_not_the_spelling() {
+    let parse = |json: &str| serde_json::from_str::<EligibilityPolicy>(json).unwrap();
+    let omitted = parse(r#"{"min_age": 18}"#);
+    let explicit = parse(r#"{"allowed_polls": null, "require_student": false, "min_age": 18}"#);
+    assert_eq!(omitted.hash(), explicit.hash());
+
+    let hashes = [
+        omitted.hash(),
+        parse(r#"{"min_age": 21}"#).hash(),
+        parse(r#"{"min_age": 18, "require_student": true}"#).hash(),
+        parse(r#"{"min_age": 18, "allowed_polls": [1, 2, 3]}"#).hash(),
+        parse(r#"{"min_age": 18, "allowed_polls": []}"#).hash(),
+    ];
+    for (i, a) in hashes.iter().enumerate() {
+        assert!(hashes[i + 1..].iter().all(|b| a != b), "policy {i} shares its hash");
+    }
+}
+
+#[test]
+fn misspelled_rules_are_rejected() {
+    assert!(serde_json::from_str::<EligibilityPolicy>(r#"{"min_agee": 18}"#).is_err());
+}
+
+#[test]
+fn no_policy_commits_no_hash() {
+    let params = VoteParams::default();
+    assert_eq!(reveal(30, false, &params).unwrap().policy_hash, None);
+}
+
+#[test]
+fn multi_poll_ballots_need_every_poll_allowed() {
+    let ballot = r#"{"id": 1, "age": 30, "is_student": false, "choices": {"1": "yes", "2": "no"}}"#;
+    let ballot = parse_ballot(ballot).unwrap();
+    let policy = EligibilityPolicy { allowed_polls: Some(vec![1]), ..Default::default() };
+    let params = VoteParams { options: vec!["yes".into(), "no".into()], ..params(policy) };
+    assert!(matches!(
+        RevealInfo::for_polls(&ballot, &[1, 2], &[], &params),
+        Err(BallotError::Ineligible { .. })
+    ));
+}
+
+fn write_policy(json: &str) -> tempfile::NamedTempFile {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    fs::write(file.path(), json).unwrap();
+    file
+}
+
+#[test]
+fn host_checks_the_policy_before_signing() {
+    let file = write_policy(r#"{"min_age": 21, "require_student": true, "allowed_polls": [1001]}"#);
+    let parse = |poll_id: &str| {
+        let argv = ["checkvote", "b.json", "--poll-id", poll_id, "--policy"];
+        let argv = argv.into_iter().chain([file.path().to_str().unwrap()]);
+        Cli::try_parse_from(argv).unwrap().prove.unwrap().vote_params()
+    };
+    let params = parse("1001").unwrap();
+    assert_eq!(params.policy, Some(read_policy(file.path()).unwrap()));
+    assert!(prepare_ballot(&ballot(30, true), &params).is_ok());
+    assert!(prepare_ballot(&ballot(20, true), &params).is_err());
+    assert!(prepare_ballot(&ballot(30, false), &params).is_err());
+
+    let error = parse("1002").unwrap_err();
+    assert!(format!("{error:#}").contains("not in `allowed_polls`"), "{error:#}");
+}
+
+#[test]
+fn version_6_journals_decode_without_a_policy_hash() {
+    let reveal = reveal(30, true, &VoteParams::default()).unwrap();
+    let reveal = RevealInfo { abstained: None, ..reveal };
+    // Version 6 is the current version without the trailing `policy_hash`, `poll_name`, `receipt_id`,
+    // `id_commitment`, `max_poll_id`, `author_key_hash` and `abstained`, each encoded here as a
+    // single `None` byte.
+    let mut v6 = reveal.encode();
+    assert_eq!(v6.split_off(v6.len() - 7), [0, 0, 0, 0, 0, 0, 0]);
+    v6[0] = 6;
+    assert_eq!(decode_journal(&v6.abi_encode()).unwrap(), reveal);
+}
+
+#[test]
+fn guest_commits_the_policy_hash() {
+    let policy = EligibilityPolicy { min_age: 21, require_student: true, allowed_polls: None };
+    let committed = common::execute(&ballot(30, true), &params(policy.clone())).unwrap();
+    assert_eq!(committed.policy_hash, Some(hex::encode(policy.hash())));
+}
+
+#[test]
+fn guest_aborts_on_an_ineligible_ballot() {
+    let too_young = EligibilityPolicy { min_age: 21, ..Default::default() };
+    assert!(common::execute(&ballot(20, true), &params(too_young)).is_err());
+    let elsewhere = EligibilityPolicy { allowed_polls: Some(vec![7]), ..Default::default() };
+    assert!(common::execute(&ballot(30, true), &params(elsewhere)).is_err());
+}
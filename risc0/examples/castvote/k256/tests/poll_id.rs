@@ -0,0 +1,128 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! String poll IDs: numbers keep their value, anything else is hashed to the numeric ID the
+//! nullifier is keyed by, and committed next to it.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    compute_nullifier, named_poll_id, parse_ballot, BallotError, PollIdentifier, RevealInfo,
+    VoteParams,
+};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    decode_journal,
+};
+use k256_methods::K256_VERIFY_ELF;
+use sha2::{Digest, Sha256};
+
+mod common;
+
+const BALLOT: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes"}"#;
+
+const UUID: &str = "3f2b8c1e-9d4a-4e7b-a6f0-2c5d8e1b7a94";
+
+const SLUG: &str = "city-budget-2025";
+
+fn params(poll_id: &str) -> VoteParams {
+    let argv = ["checkvote", "b.json", "--poll-id", poll_id, "--options", "yes,no"];
+    Cli::try_parse_from(argv).unwrap().prove.unwrap().vote_params().unwrap()
+}
+
+fn reveal(poll_id: &str) -> RevealInfo {
+    let poll_id_u64 = poll_id.parse::<PollIdentifier>().unwrap().poll_id();
+    RevealInfo::new(&parse_ballot(BALLOT).unwrap(), poll_id_u64, &[], &params(poll_id)).unwrap()
+}
+
+#[test]
+fn numeric_ids_keep_their_value() {
+    assert_eq!("1001".parse::<PollIdentifier>().unwrap(), PollIdentifier::Numeric(1001));
+    assert_eq!("1001".parse::<PollIdentifier>().unwrap().poll_id(), 1001);
+    assert_eq!(params("1001").poll_name, None);
+
+    let reveal = reveal("1001");
+    assert_eq!((reveal.poll_id, reveal.poll_name), (1001, None));
+    let voter_id = parse_ballot(BALLOT).unwrap().id;
+    assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&voter_id, 1001)));
+}
+
+#[test]
+fn uuid_and_slug_ids_are_hashed_and_committed() {
+    for name in [UUID, SLUG] {
+        let identifier = name.parse::<PollIdentifier>().unwrap();
+        assert_eq!(identifier, PollIdentifier::Named(name.into()));
+        assert_eq!(identifier.name(), Some(name));
+
+        let reveal = reveal(name);
+        assert_eq!(reveal.poll_id, named_poll_id(name));
+        assert_eq!(reveal.poll_name.as_deref(), Some(name));
+        let voter_id = parse_ballot(BALLOT).unwrap().id;
+        assert_eq!(reveal.nullifier, hex::encode(compute_nullifier(&voter_id, reveal.poll_id)));
+    }
+    assert_ne!(named_poll_id(UUID), named_poll_id(SLUG));
+    assert_ne!(named_poll_id(SLUG), named_poll_id(&SLUG.to_uppercase()));
+}
+
+#[test]
+fn named_poll_id_is_the_prefix_of_a_domain_separated_hash() {
+    let digest = Sha256::digest(format!("castvote/poll-id/{SLUG}"));
+    let expected = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    assert_eq!(named_poll_id(SLUG), expected);
+}
+
+#[test]
+fn empty_poll_ids_are_rejected() {
+    assert!(matches!("".parse::<PollIdentifier>(), Err(BallotError::InvalidPollId { .. })));
+    assert!(Cli::try_parse_from(["checkvote", "b.json", "--poll-id", " "]).is_err());
+}
+
+#[test]
+fn committed_name_must_hash_to_the_poll_id() {
+    let error = RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[], &params(SLUG));
+    assert!(matches!(error, Err(BallotError::PollMismatch { poll_id: 1001, .. })), "{error:?}");
+}
+
+#[test]
+fn check_nullifier_accepts_string_poll_ids() {
+    let argv = ["checkvote", "check-nullifier", "--ballot", "b.json", "--poll-id", UUID];
+    let argv = [&argv[..], &["--journal", "journal.dat"]].concat();
+    match Cli::try_parse_from(argv).unwrap().command {
+        Some(Command::CheckNullifier { poll_id, .. }) => {
+            assert_eq!(poll_id.poll_id(), named_poll_id(UUID))
+        }
+        command => panic!("parsed {command:?}"),
+    }
+}
+
+#[test]
+fn version_7_journals_decode_without_a_poll_name() {
+    let reveal = RevealInfo { abstained: None, ..reveal("1001") };
+    // Version 7 is the current version without the trailing `poll_name`, `receipt_id`, `id_commitment`,
+    // `max_poll_id`, `author_key_hash` and `abstained`, each encoded here as a single `None` byte.
+    let mut v7 = reveal.encode();
+    assert_eq!(v7.split_off(v7.len() - 6), [0, 0, 0, 0, 0, 0]);
+    v7[0] = 7;
+    assert_eq!(decode_journal(&v7.abi_encode()).unwrap(), reveal);
+}
+
+#[test]
+fn guest_commits_the_poll_name() {
+    let input = common::signed_input_for(BALLOT, named_poll_id(SLUG)).unwrap();
+    let journal = common::execute_journal(K256_VERIFY_ELF, &input, &params(SLUG)).unwrap();
+    let committed = decode_journal(&journal).unwrap();
+    assert_eq!(committed.poll_name.as_deref(), Some(SLUG));
+    assert_eq!(committed.poll_id, named_poll_id(SLUG));
+}
@@ -0,0 +1,103 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Poll ID 0 is always rejected, and poll IDs above `--max-poll-id` are rejected with the bound
+//! committed to the journal.
+
+use castvote_core::{parse_ballot, AgeEligibility, Ballot, BallotError, RevealInfo, VoteParams};
+use clap::Parser;
+use k256_example::{cli::Cli, error::VoteError};
+
+const BALLOT: &str = r#"{"id": 7, "age": 30, "is_student": false, "choice": "yes"}"#;
+
+fn ballot() -> Ballot {
+    parse_ballot(BALLOT).unwrap()
+}
+
+fn params(max_poll_id: Option<u64>) -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into()], max_poll_id, ..VoteParams::default() }
+}
+
+#[test]
+fn zero_poll_id_is_rejected() {
+    let params = params(None);
+    let err = RevealInfo::new(&ballot(), 0, &[1; 33], &params).unwrap_err();
+    assert_eq!(err, BallotError::ZeroPollId);
+    let err = AgeEligibility::new(&ballot(), BALLOT, 0, 18, &params).unwrap_err();
+    assert_eq!(err, BallotError::ZeroPollId);
+}
+
+#[test]
+fn poll_id_above_the_bound_is_rejected() {
+    let params = params(Some(1000));
+    let err = RevealInfo::new(&ballot(), 1001, &[1; 33], &params).unwrap_err();
+    assert_eq!(err, BallotError::PollIdAboveMax { poll_id: 1001, max: 1000 });
+    let err = AgeEligibility::new(&ballot(), BALLOT, u64::MAX, 18, &params).unwrap_err();
+    assert_eq!(err, BallotError::PollIdAboveMax { poll_id: u64::MAX, max: 1000 });
+}
+
+#[test]
+fn bound_is_committed() {
+    let reveal = RevealInfo::new(&ballot(), 1000, &[1; 33], &params(Some(1000))).unwrap();
+    assert_eq!(reveal.max_poll_id, Some(1000));
+    assert_eq!(RevealInfo::decode(&reveal.encode()).unwrap(), reveal);
+
+    let reveal = RevealInfo::new(&ballot(), u64::MAX, &[1; 33], &params(None)).unwrap();
+    assert_eq!(reveal.max_poll_id, None);
+}
+
+#[test]
+fn max_poll_id_flag_is_checked_before_signing() {
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "ballot.json"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let args = parse(&["--poll-id", "1001", "--max-poll-id", "1001"]).unwrap();
+    assert_eq!(args.vote_params().unwrap().max_poll_id, Some(1001));
+    assert_eq!(parse(&["--poll-id", "1001"]).unwrap().vote_params().unwrap().max_poll_id, None);
+    assert!(parse(&["--poll-id", "1001", "--max-poll-id", "0"]).is_err());
+
+    for args in [
+        &["--poll-id", "0"][..],
+        &["--poll-id", "1001", "--max-poll-id", "1000"],
+        &["--polls", "1,2000", "--max-poll-id", "1000"],
+    ] {
+        let err = parse(args).unwrap().vote_params().unwrap_err();
+        assert_eq!(VoteError::exit_code_of(&err), 2, "{args:?}: {err:#}");
+    }
+}
+
+/// The guest refuses poll ID 0 even if the host let it through.
+#[cfg(feature = "prove")]
+#[test]
+fn guest_refuses_poll_id_zero() {
+    use base64::{engine::general_purpose, Engine as _};
+    use castvote_core::canonical_k256_key;
+    use k256::ecdsa::SigningKey;
+    use k256_example::{sign_ballot, SoftwareSigner};
+    use k256_methods::K256_VERIFY_ELF;
+    use risc0_zkvm::{default_executor, ExecutorEnv};
+
+    let key = SigningKey::from_bytes((&[1u8; 32]).into()).unwrap();
+    let public_key = general_purpose::STANDARD.encode(canonical_k256_key(key.verifying_key()));
+    let signature = sign_ballot(&SoftwareSigner::new(key), BALLOT).unwrap();
+    let env = ExecutorEnv::builder()
+        .write(&(signature, BALLOT, 0u64, public_key))
+        .unwrap()
+        .write(&VoteParams::default())
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(default_executor().execute(env, K256_VERIFY_ELF).is_err());
+}
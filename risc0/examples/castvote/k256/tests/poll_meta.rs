@@ -0,0 +1,134 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Binding proofs to a poll definition through its committed hash.
+#![cfg(feature = "prove")]
+
+use std::fs;
+
+use castvote_core::{parse_ballot, BallotError, PollMeta, RevealInfo, VoteParams};
+use clap::Parser;
+use k256_example::cli::Cli;
+
+mod common;
+
+const BALLOT: &str = r#"{"age":30,"choice":"yes","id":123,"is_student":true}"#;
+
+fn poll(options: &[&str]) -> PollMeta {
+    PollMeta {
+        poll_id: 1001,
+        title: "Extend the library hours?".into(),
+        options: options.iter().map(|&option| option.into()).collect(),
+    }
+}
+
+fn params(poll: PollMeta) -> VoteParams {
+    VoteParams { options: poll.options.clone(), poll: Some(poll), ..Default::default() }
+}
+
+fn reveal(params: &VoteParams) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1001, &[], params)
+}
+
+#[test]
+fn the_same_choice_under_other_options_commits_another_poll_hash() {
+    let yes_no = reveal(&params(poll(&["yes", "no"]))).unwrap();
+    let yes_no_abstain = reveal(&params(poll(&["yes", "no", "abstain"]))).unwrap();
+
+    assert_eq!(yes_no.selection, yes_no_abstain.selection);
+    assert_eq!(yes_no.nullifier, yes_no_abstain.nullifier);
+    assert_eq!(yes_no.poll_hash, Some(hex::encode(poll(&["yes", "no"]).hash())));
+    assert_ne!(yes_no.poll_hash, yes_no_abstain.poll_hash);
+    assert_eq!(RevealInfo::decode(&yes_no.encode()).unwrap(), yes_no);
+}
+
+#[test]
+fn any_edit_to_the_definition_changes_its_hash() {
+    let original = poll(&["yes", "no"]);
+    let retitled = PollMeta { title: "Shorten the library hours?".into(), ..original.clone() };
+    let reordered = poll(&["no", "yes"]);
+    let renumbered = PollMeta { poll_id: 1002, ..original.clone() };
+    for edited in [retitled, reordered, renumbered] {
+        assert_ne!(edited.hash(), original.hash(), "{edited:?}");
+    }
+}
+
+#[test]
+fn reveals_without_a_definition_commit_no_hash() {
+    let params = VoteParams { options: vec!["yes".into(), "no".into()], ..Default::default() };
+    assert_eq!(reveal(&params).unwrap().poll_hash, None);
+}
+
+#[test]
+fn choice_is_checked_against_the_definition() {
+    let params = params(poll(&["no", "abstain"]));
+    assert_eq!(reveal(&params), Err(BallotError::UnknownOption("yes".into())));
+
+    // Options that differ from the definition's would let the host validate against one list
+    // and commit the hash of another.
+    let smuggled = VoteParams { options: vec!["yes".into()], ..params.clone() };
+    assert!(matches!(reveal(&smuggled), Err(BallotError::PollMismatch { poll_id: 1001, .. })));
+
+    let params = self::params(poll(&["yes", "no"]));
+    let other_poll = RevealInfo::new(&parse_ballot(BALLOT).unwrap(), 1002, &[], &params);
+    assert!(matches!(other_poll, Err(BallotError::PollMismatch { poll_id: 1002, .. })));
+}
+
+#[test]
+fn version_3_journals_decode_without_a_poll_hash() {
+    let params = VoteParams { options: vec!["yes".into()], ..Default::default() };
+    let reveal =
+        RevealInfo { message_format: None, student_required: None, ..reveal(&params).unwrap() };
+    let reveal = RevealInfo { abstained: None, ..reveal };
+    // Version 3 is the current version without the trailing `poll_hash`, `message_format`,
+    // `student_required`, `policy_hash`, `poll_name`, `receipt_id`, `id_commitment`,
+    // `max_poll_id`, `author_key_hash` and `abstained`, each encoded here as a single `None` byte.
+    let mut v3 = reveal.encode();
+    assert_eq!(v3.split_off(v3.len() - 10), [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    v3[0] = 3;
+    assert_eq!(RevealInfo::decode(&v3).unwrap(), reveal);
+}
+
+#[test]
+fn cli_reads_the_poll_definition() {
+    let dir = tempfile::tempdir().unwrap();
+    let meta = dir.path().join("poll.json");
+    fs::write(&meta, serde_json::to_string(&poll(&["yes", "no"])).unwrap()).unwrap();
+    let meta = meta.to_str().unwrap();
+
+    let parse = |extra: &[&str]| {
+        let argv = [&["checkvote", "b.json"], extra].concat();
+        Cli::try_parse_from(argv).map(|cli| cli.prove.unwrap())
+    };
+    let params = parse(&["--poll-id", "1001", "--poll-meta", meta]).unwrap().vote_params().unwrap();
+    assert_eq!(params.options, ["yes", "no"]);
+    assert_eq!(params.poll, Some(poll(&["yes", "no"])));
+
+    let err = parse(&["--poll-id", "1002", "--poll-meta", meta]).unwrap().vote_params().unwrap_err();
+    assert!(err.to_string().contains("not --poll-id 1002"), "{err}");
+
+    assert!(parse(&["--poll-id", "1001", "--poll-meta", meta, "--options", "yes"]).is_err());
+}
+
+#[test]
+fn guest_commits_the_poll_hash() {
+    let mut hashes = Vec::new();
+    for options in [&["yes", "no"][..], &["yes", "no", "abstain"]] {
+        let committed = common::execute(BALLOT, &params(poll(options))).unwrap();
+        assert_eq!(committed.poll_hash, Some(hex::encode(poll(options).hash())));
+        hashes.push(committed.poll_hash);
+    }
+    assert_ne!(hashes[0], hashes[1]);
+}
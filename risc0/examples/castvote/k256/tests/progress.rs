@@ -0,0 +1,51 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{thread, time::Duration};
+
+use k256_example::progress::with_progress_to;
+
+fn run(interactive: bool) -> (u32, String) {
+    let mut out = Vec::new();
+    let result = with_progress_to(&mut out, interactive, Duration::from_millis(10), "proving", || {
+        thread::sleep(Duration::from_millis(100));
+        42
+    });
+    (result, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn returns_the_result_and_reports_start_and_finish() {
+    let (result, out) = run(false);
+    assert_eq!(result, 42);
+    let lines: Vec<_> = out.lines().collect();
+    assert!(lines.first().unwrap().ends_with("] proving started"));
+    assert!(lines.last().unwrap().contains("] proving finished in "));
+}
+
+#[test]
+fn non_interactive_output_is_plain_lines() {
+    let (_, out) = run(false);
+    assert!(out.contains("proving running, "));
+    assert!(!out.contains(['\r', '\x1b']));
+    assert!(out.lines().all(|line| line.starts_with('[')));
+}
+
+#[test]
+fn interactive_output_redraws_a_spinner() {
+    let (_, out) = run(true);
+    assert!(out.contains("\r| proving... 0s"));
+    assert!(out.contains("\r\x1b[2K"));
+    assert!(out.ends_with('\n'));
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Receipt IDs: a voter-chosen random ID committed next to the nullifier, for lookup only.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use castvote_core::{check_receipt_id, parse_ballot, BallotError, RevealInfo, VoteParams};
+use k256_example::{decode_journal, validate_ballot};
+
+mod common;
+
+const FIRST: &str = "5f0c3a9e71d24b86a0e9c4f2d81b7a63";
+
+const SECOND: &str = "c17e94b2086d4f3a9b5e2d0c7a41f896";
+
+fn ballot(receipt_id: &str) -> String {
+    format!(r#"{{"id": 7, "age": 30, "is_student": false, "receipt_id": "{receipt_id}"}}"#)
+}
+
+fn reveal(ballot: &str) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(ballot).unwrap(), 1001, &[], &VoteParams::default())
+}
+
+#[test]
+fn receipt_ids_are_16_to_32_bytes_of_lowercase_hex() {
+    check_receipt_id(FIRST).unwrap();
+    check_receipt_id(&"ab".repeat(32)).unwrap();
+    for bad in ["", "ab", &"ab".repeat(33), &FIRST.to_uppercase(), &FIRST[1..], "not hex at all"] {
+        assert!(
+            matches!(check_receipt_id(bad), Err(BallotError::InvalidReceiptId { .. })),
+            "{bad:?} was accepted"
+        );
+    }
+}
+
+#[test]
+fn the_receipt_id_is_committed_apart_from_the_nullifier() {
+    let first = reveal(&ballot(FIRST)).unwrap();
+    let second = reveal(&ballot(SECOND)).unwrap();
+    assert_eq!(first.receipt_id.as_deref(), Some(FIRST));
+    assert_eq!(second.receipt_id.as_deref(), Some(SECOND));
+    // The receipt ID is for lookup only: the same voter in the same poll keeps one nullifier.
+    assert_eq!(first.nullifier, second.nullifier);
+
+    let without = reveal(r#"{"id": 7, "age": 30, "is_student": false}"#).unwrap();
+    assert_eq!((without.receipt_id, without.nullifier), (None, first.nullifier));
+}
+
+#[test]
+fn a_malformed_receipt_id_is_rejected() {
+    let error = reveal(&ballot("1234")).unwrap_err();
+    assert_eq!(error.to_string(), "receipt ID must be 16 to 32 bytes");
+    let error = validate_ballot(&ballot(&FIRST.to_uppercase())).unwrap_err();
+    assert_eq!(error.path, "/receipt_id");
+    let number = r#"{"id": 7, "age": 30, "is_student": false, "receipt_id": 5}"#;
+    assert_eq!(validate_ballot(number).unwrap_err().path, "/receipt_id");
+}
+
+#[test]
+fn version_8_journals_decode_without_a_receipt_id() {
+    let reveal = reveal(r#"{"id": 7, "age": 30, "is_student": false}"#).unwrap();
+    let reveal = RevealInfo { abstained: None, ..reveal };
+    // Version 8 is the current version without the trailing `receipt_id`, `id_commitment`, `max_poll_id`,
+    // `author_key_hash` and `abstained`, each encoded as a `None` byte.
+    let mut v8 = reveal.encode();
+    assert_eq!(v8.split_off(v8.len() - 5), [0, 0, 0, 0, 0]);
+    v8[0] = 8;
+    assert_eq!(decode_journal(&v8.abi_encode()).unwrap(), reveal);
+}
+
+#[test]
+fn ballots_differing_only_in_receipt_id_both_verify() {
+    let first = common::execute(&ballot(FIRST), &VoteParams::default()).unwrap();
+    let second = common::execute(&ballot(SECOND), &VoteParams::default()).unwrap();
+    assert_eq!(first.receipt_id.as_deref(), Some(FIRST));
+    assert_eq!(second.receipt_id.as_deref(), Some(SECOND));
+    assert_eq!(first.nullifier, second.nullifier);
+}
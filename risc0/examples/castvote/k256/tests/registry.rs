@@ -0,0 +1,92 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Ballots signed by any one key of a flat registry of authorized voters.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, find_signer, registry_key_hash, RegistryError, RegistryInput, VoteParams,
+};
+use k256::ecdsa::SigningKey;
+use k256_example::{decode_journal, sign_ballot, SoftwareSigner};
+use k256_methods::K256_VERIFY_REGISTRY_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+/// Already canonical, so the signature covers exactly these bytes.
+const BALLOT: &str = r#"{"age":40,"id":"voter-2","is_student":false}"#;
+
+fn keys() -> [SigningKey; 3] {
+    [1u8, 2, 3].map(|seed| SigningKey::from_bytes((&[seed; 32]).into()).unwrap())
+}
+
+fn public_key(key: &SigningKey) -> Vec<u8> {
+    canonical_k256_key(key.verifying_key())
+}
+
+#[test]
+fn first_matching_key_is_found() {
+    let registry = [10, 20, 30];
+    assert_eq!(find_signer(&registry, |key| *key == 20), Ok(1));
+    assert_eq!(find_signer(&registry, |key| *key >= 20), Ok(1));
+}
+
+#[test]
+fn no_matching_key_is_rejected() {
+    assert_eq!(
+        find_signer(&[10, 20, 30], |key| *key == 40),
+        Err(RegistryError::NoMatchingKey { keys: 3 })
+    );
+    assert_eq!(find_signer::<u8>(&[], |_| true), Err(RegistryError::Empty));
+}
+
+#[test]
+fn registry_hash_ignores_key_order_and_duplicates() {
+    let [a, b, c] = keys().map(|key| public_key(&key));
+    let hash = registry_key_hash(&[a.clone(), b.clone(), c.clone()]);
+    assert_eq!(registry_key_hash(&[c.clone(), a.clone(), b.clone(), a.clone()]), hash);
+    assert_ne!(registry_key_hash(&[a, b]), hash);
+}
+
+/// Signs `BALLOT` with `signer` and runs the registry guest with all three demo keys authorized.
+fn execute(signer: &SigningKey) -> anyhow::Result<castvote_core::RevealInfo> {
+    let encode = |key: &SigningKey| general_purpose::STANDARD.encode(public_key(key));
+    let input = RegistryInput {
+        signature: sign_ballot(&SoftwareSigner::new(signer.clone()), BALLOT)?,
+        ballot: BALLOT.to_string(),
+        poll_id: 1001,
+        authorized_keys: keys().iter().map(encode).collect(),
+    };
+    let env = ExecutorEnv::builder().write(&input)?.write(&VoteParams::default())?.build()?;
+    let session = default_executor().execute(env, K256_VERIFY_REGISTRY_ELF)?;
+    decode_journal(&session.journal.bytes)
+}
+
+#[test]
+fn guest_accepts_the_second_of_three_keys() {
+    let [_, signer, _] = keys();
+    let reveal = execute(&signer).unwrap();
+
+    // The journal names the registry, not the key that signed.
+    let authorized = keys().map(|key| public_key(&key)).to_vec();
+    assert_eq!(reveal.voter_key_hash, hex::encode(registry_key_hash(&authorized)));
+    assert_eq!(reveal.poll_id, 1001);
+}
+
+#[test]
+fn guest_rejects_a_key_outside_the_registry() {
+    let outsider = SigningKey::from_bytes((&[9u8; 32]).into()).unwrap();
+    assert!(execute(&outsider).is_err());
+}
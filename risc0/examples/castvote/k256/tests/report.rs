@@ -0,0 +1,150 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks of the results report against the journals in `testdata/report`, and in
+//! `testdata/report-elections` for two elections reusing a poll ID.
+
+use std::{fs, path::PathBuf};
+
+use k256_example::report::{self, AgeBuckets, RejectReason, Report};
+use serde_json::{json, Value};
+
+fn testdata() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/report")
+}
+
+fn fixture_report() -> Report {
+    let pattern = testdata().join("journals/*.dat");
+    report::report_from_glob(pattern.to_str().unwrap()).unwrap()
+}
+
+fn elections_report() -> Report {
+    let pattern = testdata().with_file_name("report-elections").join("journals/*.dat");
+    report::report_from_glob(pattern.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn report_matches_expected_tally() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("results.csv");
+    fixture_report().write_counts_csv(&out).unwrap();
+
+    let expected = fs::read_to_string(testdata().join("expected.csv")).unwrap();
+    assert_eq!(fs::read_to_string(out).unwrap(), expected);
+}
+
+#[test]
+fn report_rejects_duplicate_abstaining_and_undecodable_journals() {
+    let report = fixture_report();
+    let rejected: Vec<_> = report
+        .rejected()
+        .iter()
+        .map(|r| {
+            let name = PathBuf::from(&r.source).file_name().unwrap().to_owned();
+            (name.into_string().unwrap(), r.reason.clone())
+        })
+        .collect();
+
+    assert_eq!(rejected.len(), 3);
+    assert_eq!(rejected[0], ("04-alice-again.dat".into(), RejectReason::DuplicateNullifier));
+    assert_eq!(rejected[1], ("06-dave-abstain.dat".into(), RejectReason::MissingChoice));
+    assert_eq!(rejected[2].0, "07-garbage.dat");
+    assert!(matches!(rejected[2].1, RejectReason::Undecodable(_)));
+}
+
+#[test]
+fn same_voter_may_vote_in_different_polls() {
+    let report = fixture_report();
+    assert_eq!(report.counts().get(&(None, 1002, "yes".to_string())), Some(&1));
+}
+
+#[test]
+fn rejected_entries_are_written_next_to_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("results.csv");
+    let rejected_out = report::rejected_path(&out);
+    assert_eq!(rejected_out, dir.path().join("results.rejected.csv"));
+
+    fixture_report().write_rejected_csv(&rejected_out).unwrap();
+    let contents = fs::read_to_string(rejected_out).unwrap();
+    let mut lines = contents.lines();
+    assert_eq!(lines.next(), Some("source,nullifier,reason"));
+    assert_eq!(lines.count(), 3);
+}
+
+#[test]
+fn demographics_skip_duplicates_and_abstentions() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("demographics.json");
+    let buckets = AgeBuckets::new(vec![25, 40]).unwrap();
+    report::write_demographics_json(&fixture_report(), &buckets, &out).unwrap();
+
+    let demographics: Value = serde_json::from_str(&fs::read_to_string(out).unwrap()).unwrap();
+    assert_eq!(demographics["age_buckets"], json!([25, 40]));
+    let polls = demographics["polls"].as_array().unwrap();
+    assert_eq!(polls.len(), 4);
+    let counts: Vec<_> = polls[0]["age"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bucket| (bucket["bucket"].as_str().unwrap(), bucket["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(counts, [("0-24", 1), ("25-39", 1), ("40+", 1)]);
+    assert_eq!(polls[0]["poll_id"], 1001);
+    assert_eq!(polls[0]["ballots"], 3);
+    assert_eq!(polls[0]["is_student"], json!({"true": 1, "false": 2}));
+}
+
+#[test]
+fn age_buckets_must_increase_from_above_zero() {
+    assert_eq!(AgeBuckets::default().bounds(), report::DEFAULT_AGE_BUCKETS);
+    assert_eq!(AgeBuckets::new(vec![18]).unwrap().ranges(), [(0, Some(17)), (18, None)]);
+    assert!(AgeBuckets::new(vec![0, 18]).is_err());
+    let err = AgeBuckets::new(vec![18, 65, 30]).unwrap_err();
+    assert_eq!(err.to_string(), "age bucket bounds must increase, but 30 follows 65");
+}
+
+#[test]
+fn elections_are_tallied_and_deduplicated_separately() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("results.csv");
+    let report = elections_report();
+    report.write_counts_csv(&out).unwrap();
+
+    let expected = testdata().with_file_name("report-elections").join("expected.csv");
+    assert_eq!(fs::read_to_string(out).unwrap(), fs::read_to_string(expected).unwrap());
+    let rejected: Vec<_> = report.rejected().iter().map(|r| r.reason.clone()).collect();
+    assert_eq!(rejected, [RejectReason::DuplicateNullifier]);
+    assert!(report.rejected()[0].source.ends_with("04-alice-spring-again.dat"));
+}
+
+#[test]
+fn json_report_nests_polls_under_their_election() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = report::json_path(&dir.path().join("results.csv"));
+    assert_eq!(out, dir.path().join("results.json"));
+    elections_report().write_json(&out).unwrap();
+
+    let nested: Value = serde_json::from_str(&fs::read_to_string(out).unwrap()).unwrap();
+    assert_eq!(
+        nested,
+        json!({"elections": [
+            {"election_id": null, "polls": [{"poll_id": 1, "counts": {"yes": 1}}]},
+            {"election_id": "2025-fall", "polls": [{"poll_id": 1, "counts": {"no": 1}}]},
+            {"election_id": "2025-spring", "polls": [
+                {"poll_id": 1, "counts": {"no": 1, "yes": 1}},
+            ]},
+        ]})
+    );
+}
@@ -0,0 +1,187 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Re-signing `--no-prove` signed ballots under a rotated voter key.
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::MessageFormat;
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command, Curve, MessageFormatArg, Scheme},
+    resign::{resign_files, KeyRotation},
+    sign, verify_signature, SignedBallot, VoterKey,
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+fn key(byte: u8) -> VoterKey {
+    VoterKey::from_base64(Scheme::Ecdsa, Curve::K256, &general_purpose::STANDARD.encode([byte; 32]))
+        .unwrap()
+}
+
+fn signed(key: &VoterKey, poll_id: u64) -> SignedBallot {
+    signed_as(key, poll_id, MessageFormat::Sha256)
+}
+
+fn signed_as(key: &VoterKey, poll_id: u64, message_format: MessageFormat) -> SignedBallot {
+    SignedBallot {
+        ballot: BALLOT.into(),
+        poll_id,
+        signature: sign(key, BALLOT, message_format).unwrap(),
+        public_key: key.public_key_base64(),
+    }
+}
+
+fn write(path: &Path, entries: &[SignedBallot]) {
+    fs::write(path, serde_json::to_string(entries).unwrap()).unwrap();
+}
+
+fn rotation<'a>(old_public_key: &'a str, new_key: &'a VoterKey) -> KeyRotation<'a> {
+    KeyRotation {
+        scheme: Scheme::Ecdsa,
+        curve: Curve::K256,
+        message_format: MessageFormat::Sha256,
+        old_public_key,
+        new_key,
+    }
+}
+
+#[test]
+fn resigned_ballots_verify_under_the_new_key() {
+    let (old, new) = (key(1), key(2));
+    let (input, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    write(&input.path().join("a.json"), &[signed(&old, 1), signed(&old, 2)]);
+    write(&input.path().join("b.json"), &[signed(&old, 3)]);
+
+    let old_public_key = old.public_key_base64();
+    let pattern = format!("{}/*.json", input.path().display());
+    let summary = resign_files(&pattern, &rotation(&old_public_key, &new), out.path()).unwrap();
+    assert_eq!(summary.resigned, 3);
+    assert!(summary.rejected.is_empty());
+    assert_eq!(summary.files, [out.path().join("a.json"), out.path().join("b.json")]);
+
+    let resigned: Vec<SignedBallot> =
+        serde_json::from_str(&fs::read_to_string(out.path().join("a.json")).unwrap()).unwrap();
+    assert_eq!(resigned.iter().map(|e| e.poll_id).collect::<Vec<_>>(), [1, 2]);
+    for entry in &resigned {
+        assert_eq!(entry.ballot, BALLOT);
+        assert_eq!(entry.public_key, new.public_key_base64());
+        let verify = |public_key: &str| {
+            verify_signature(
+                Scheme::Ecdsa,
+                Curve::K256,
+                &entry.ballot,
+                &entry.signature,
+                public_key,
+                MessageFormat::Sha256,
+            )
+        };
+        verify(&new.public_key_base64()).unwrap();
+        assert!(verify(&old_public_key).is_err());
+    }
+}
+
+#[test]
+fn raw_and_eip712_ballots_keep_their_message_format() {
+    for message_format in [MessageFormat::Raw, MessageFormat::Eip712] {
+        let (old, new) = (key(1), key(2));
+        let (input, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        write(&input.path().join("a.json"), &[signed_as(&old, 1, message_format)]);
+
+        let old_public_key = old.public_key_base64();
+        let rotation = KeyRotation { message_format, ..rotation(&old_public_key, &new) };
+        let pattern = format!("{}/*.json", input.path().display());
+        let summary = resign_files(&pattern, &rotation, out.path()).unwrap();
+        assert_eq!(summary.resigned, 1, "{message_format:?}");
+
+        let resigned: Vec<SignedBallot> =
+            serde_json::from_str(&fs::read_to_string(out.path().join("a.json")).unwrap()).unwrap();
+        let verify = |message_format| {
+            verify_signature(
+                Scheme::Ecdsa,
+                Curve::K256,
+                &resigned[0].ballot,
+                &resigned[0].signature,
+                &new.public_key_base64(),
+                message_format,
+            )
+        };
+        verify(message_format).unwrap();
+        assert!(verify(MessageFormat::Sha256).is_err(), "{message_format:?}");
+    }
+}
+
+#[test]
+fn a_signature_by_another_key_rejects_the_whole_rotation() {
+    let (old, new, other) = (key(1), key(2), key(3));
+    let (input, out) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    write(&input.path().join("a.json"), &[signed(&old, 1)]);
+    write(&input.path().join("b.json"), &[signed(&old, 2), signed(&other, 3)]);
+
+    let old_public_key = old.public_key_base64();
+    let pattern = format!("{}/*.json", input.path().display());
+    let summary = resign_files(&pattern, &rotation(&old_public_key, &new), out.path()).unwrap();
+    assert_eq!(summary.resigned, 0);
+    assert!(summary.files.is_empty());
+    assert_eq!(summary.rejected.len(), 1);
+    assert_eq!(summary.rejected[0].source, input.path().join("b.json"));
+    assert_eq!(summary.rejected[0].index, 1);
+    assert_eq!(fs::read_dir(out.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn refuses_to_overwrite_its_input() {
+    let (old, new) = (key(1), key(2));
+    let input = tempfile::tempdir().unwrap();
+    write(&input.path().join("a.json"), &[signed(&old, 1)]);
+
+    let old_public_key = old.public_key_base64();
+    let pattern = format!("{}/*.json", input.path().display());
+    let error = resign_files(&pattern, &rotation(&old_public_key, &new), input.path()).unwrap_err();
+    assert!(error.to_string().contains("would overwrite"), "{error}");
+}
+
+#[test]
+fn parses_the_resign_command() {
+    let cli = Cli::try_parse_from([
+        "checkvote", "resign", "--old-pub", "BLZg", "--new-priv", "new.key", "--ballots",
+        "signed/*.json", "--out-dir", "rotated",
+    ])
+    .unwrap();
+    let Some(Command::Resign { old_pub, ballots, scheme, curve, message_format, eip191, .. }) =
+        cli.command
+    else {
+        panic!("expected the resign command");
+    };
+    assert_eq!((old_pub.as_str(), ballots.as_str()), ("BLZg", "signed/*.json"));
+    assert_eq!((scheme, curve, eip191), (Scheme::Ecdsa, Curve::K256, false));
+    assert_eq!(message_format, MessageFormatArg::JsonCanonical);
+}
+
+#[test]
+fn parses_the_resign_message_format() {
+    let args = [
+        "checkvote", "resign", "--old-pub", "BLZg", "--new-priv", "new.key", "--ballots",
+        "signed/*.json", "--out-dir", "rotated", "--message-format", "eip712",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+    let Some(Command::Resign { message_format, .. }) = cli.command else {
+        panic!("expected the resign command");
+    };
+    assert_eq!(MessageFormat::from(message_format), MessageFormat::Eip712);
+    assert!(Cli::try_parse_from(args.into_iter().chain(["--eip191"])).is_err());
+}
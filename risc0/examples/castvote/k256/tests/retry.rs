@@ -0,0 +1,139 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Retrying failed proofs with backoff, and timing out hung ones.
+
+use std::{
+    cell::Cell,
+    io,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use k256_example::prove::{is_transient, with_retries, with_timeout, RetryPolicy, Timeout};
+
+fn instant(retries: u32) -> RetryPolicy {
+    RetryPolicy { initial_backoff: Duration::ZERO, ..RetryPolicy::new(retries) }
+}
+
+/// Fails with `err()` for the first `failures` calls, counting every call.
+fn flaky(
+    calls: &Cell<u32>,
+    failures: u32,
+    err: fn() -> anyhow::Error,
+) -> impl FnMut() -> Result<u32> + '_ {
+    move || {
+        calls.set(calls.get() + 1);
+        if calls.get() <= failures {
+            return Err(err());
+        }
+        Ok(calls.get())
+    }
+}
+
+fn timeout() -> anyhow::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "prover did not answer").into()
+}
+
+fn guest_panic() -> anyhow::Error {
+    anyhow!("Guest panicked: signature is not valid")
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = RetryPolicy::new(10);
+    let waits: Vec<_> = (0..7).map(|retry| policy.backoff(retry).as_secs()).collect();
+    assert_eq!(waits, [2, 4, 8, 16, 32, 60, 60]);
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(60));
+}
+
+#[test]
+fn transient_failures_are_retried() {
+    let calls = Cell::new(0);
+    assert_eq!(with_retries(&instant(3), flaky(&calls, 2, timeout)).unwrap(), 3);
+}
+
+#[test]
+fn deterministic_failures_are_not_retried() {
+    let calls = Cell::new(0);
+    assert!(with_retries(&instant(3), flaky(&calls, 5, guest_panic)).is_err());
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn retries_are_bounded() {
+    let calls = Cell::new(0);
+    let err = with_retries(&instant(2), flaky(&calls, 5, timeout)).unwrap_err();
+    assert_eq!(calls.get(), 3);
+    assert!(format!("{err:#}").contains("after 3 attempts"), "{err:#}");
+
+    let calls = Cell::new(0);
+    assert!(with_retries(&RetryPolicy::default(), flaky(&calls, 1, timeout)).is_err());
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn errors_are_classified() {
+    assert!(is_transient(&timeout()));
+    assert!(is_transient(&anyhow!("request failed: 503 Service Unavailable")));
+    assert!(is_transient(&timeout().context("proving")));
+    assert!(!is_transient(&io::Error::new(io::ErrorKind::NotFound, "missing").into()));
+    assert!(!is_transient(&guest_panic()));
+    assert!(!is_transient(&anyhow!("guest panicked: connection id is not a number")));
+    assert!(!is_transient(&anyhow!("something unexpected")));
+
+    let unsatisfiable = || -> Result<()> { bail!("ExitCode(Fault)") };
+    assert!(!is_transient(&unsatisfiable().unwrap_err()));
+}
+
+const LIMIT: Duration = Duration::from_millis(50);
+
+#[test]
+fn slow_attempts_time_out() {
+    let err = with_timeout(Some(LIMIT), || {
+        thread::sleep(Duration::from_secs(2));
+        Ok(())
+    })
+    .unwrap_err();
+    assert_eq!(err.downcast_ref::<Timeout>(), Some(&Timeout(LIMIT)));
+    assert!(is_transient(&err));
+
+    assert_eq!(with_timeout(Some(Duration::from_secs(5)), || Ok(7)).unwrap(), 7);
+    assert_eq!(with_timeout(None, || Ok(8)).unwrap(), 8);
+    assert!(with_timeout(Some(LIMIT), || -> Result<()> { bail!("no") }).is_err());
+}
+
+#[test]
+fn timed_out_attempts_are_retried() {
+    let policy = RetryPolicy { timeout: Some(LIMIT), ..instant(1) };
+    let calls = Arc::new(AtomicU32::new(0));
+    let proved = with_retries(&policy, || {
+        let calls = calls.clone();
+        with_timeout(policy.timeout, move || {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                // The first attempt hangs and is abandoned.
+                thread::sleep(Duration::from_secs(2));
+            }
+            Ok("receipt")
+        })
+    });
+    assert_eq!(proved.unwrap(), "receipt");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,147 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The reveal phase of homomorphically tallied polls: only aggregates are decrypted, and the
+//! totals match the plaintext choices.
+
+use std::fs;
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    election_key, encode_reveals, encrypt_vote, one_hot, parse_ballot, parse_randomness,
+    HomomorphicReveal, RevealInfo, VoteParams,
+};
+use clap::Parser;
+use k256::{PublicKey, SecretKey};
+use k256_example::{
+    cli::{Cli, Command},
+    homomorphic::{
+        elgamal_input, read_authority_secret, read_homomorphic_journals, reveal_tallies,
+        RevealedTally,
+    },
+    write_hex_file,
+};
+
+fn params() -> VoteParams {
+    VoteParams { options: vec!["yes".into(), "no".into(), "maybe".into()], ..Default::default() }
+}
+
+fn trustees() -> Vec<SecretKey> {
+    [1u8, 2].iter().map(|&seed| SecretKey::from_bytes((&[seed; 32]).into()).unwrap()).collect()
+}
+
+fn key_of(trustees: &[SecretKey]) -> PublicKey {
+    election_key(&trustees.iter().map(SecretKey::public_key).collect::<Vec<_>>()).unwrap()
+}
+
+/// What the homomorphic guest commits for voter `id` choosing `choice` in `poll_id`, encrypted
+/// to `key`.
+fn commit(id: u32, choice: &str, poll_id: u64, key: &PublicKey) -> HomomorphicReveal {
+    let params = params();
+    let ballot = format!(r#"{{"id":{id},"age":30,"is_student":false,"choice":"{choice}"}}"#);
+    let mut reveal =
+        RevealInfo::new(&parse_ballot(&ballot).unwrap(), poll_id, &[], &params).unwrap();
+    let vote = one_hot(&reveal.selection, params.ballot_type, &params.options).unwrap();
+    let elgamal = elgamal_input(key, vote.len());
+    let randomness: Vec<_> =
+        elgamal.randomness.iter().map(|r| parse_randomness(r).unwrap()).collect();
+    let ciphertexts = encrypt_vote(key, &vote, &randomness).unwrap();
+    reveal.selection.clear();
+    HomomorphicReveal { reveal, election_key: elgamal.election_key, ciphertexts }
+}
+
+fn votes(key: &PublicKey) -> Vec<HomomorphicReveal> {
+    let choices = [(1, "yes"), (2, "no"), (3, "yes"), (4, "maybe"), (5, "yes")];
+    let mut votes: Vec<_> =
+        choices.iter().map(|&(id, choice)| commit(id, choice, 1001, key)).collect();
+    // Voter 2 again, with a different choice: the first ballot is the one counted.
+    votes.push(commit(2, "yes", 1001, key));
+    votes.push(commit(1, "no", 1002, key));
+    votes
+}
+
+#[test]
+fn tallies_match_the_plaintext_choices() {
+    let trustees = trustees();
+    let tallies = reveal_tallies(&votes(&key_of(&trustees)), &trustees).unwrap();
+    assert_eq!(
+        tallies,
+        [
+            RevealedTally { poll_id: 1001, ballots: 5, duplicates: 1, totals: vec![3, 1, 1] },
+            RevealedTally { poll_id: 1002, ballots: 1, duplicates: 0, totals: vec![0, 1, 0] },
+        ]
+    );
+}
+
+#[test]
+fn every_trustee_key_is_needed() {
+    let trustees = trustees();
+    let votes = votes(&key_of(&trustees));
+    let err = reveal_tallies(&votes, &trustees[..1]).unwrap_err();
+    assert!(format!("{err:#}").contains("not encrypted to the authority's key"), "{err:#}");
+}
+
+#[test]
+fn journals_are_read_back_and_others_rejected() {
+    let trustees = trustees();
+    let dir = tempfile::tempdir().unwrap();
+    for (index, vote) in votes(&key_of(&trustees)).iter().enumerate() {
+        let path = dir.path().join(format!("{index:02}.dat"));
+        write_hex_file(path, &vote.encode().abi_encode()).unwrap();
+    }
+    let pattern = dir.path().join("*.dat");
+    let reveals = read_homomorphic_journals(pattern.to_str().unwrap()).unwrap();
+    assert_eq!(reveal_tallies(&reveals, &trustees).unwrap()[0].totals, [3, 1, 1]);
+
+    let plain = commit(9, "no", 1001, &key_of(&trustees)).reveal;
+    write_hex_file(dir.path().join("99.dat"), &encode_reveals(&[plain]).abi_encode()).unwrap();
+    assert!(read_homomorphic_journals(pattern.to_str().unwrap()).is_err());
+}
+
+#[test]
+fn authority_keys_are_read_from_key_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("trustee.key");
+    let secret = &trustees()[0];
+    fs::write(&path, general_purpose::STANDARD.encode(secret.to_bytes())).unwrap();
+    assert_eq!(read_authority_secret(&path, None).unwrap(), *secret);
+}
+
+#[test]
+fn parses_the_reveal_command() {
+    let argv = [
+        "checkvote",
+        "reveal",
+        "--journals",
+        "proofs/*/journal.dat",
+        "--authority-key",
+        "a.key",
+        "--authority-key",
+        "b.key",
+        "--options",
+        "yes,no,maybe",
+    ];
+    let Some(Command::Reveal { journals, authority_key, options, .. }) =
+        Cli::try_parse_from(argv).unwrap().command
+    else {
+        panic!("expected the reveal command");
+    };
+    assert_eq!(journals, "proofs/*/journal.dat");
+    assert_eq!(authority_key.len(), 2);
+    assert_eq!(options, ["yes", "no", "maybe"]);
+
+    let without_key = ["checkvote", "reveal", "--journals", "proofs/*/journal.dat"];
+    assert!(Cli::try_parse_from(without_key).is_err());
+}
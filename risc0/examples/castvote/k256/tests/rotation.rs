@@ -0,0 +1,123 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ballots verified against the authority key that was valid when they were signed.
+#![cfg(feature = "prove")]
+
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    canonical_k256_key, key_schedule_hash, select_epoch, KeyEpoch, RotatedKeysInput,
+    RotationError, VoteParams,
+};
+use k256::ecdsa::SigningKey;
+use k256_example::{decode_epoch_journal, sign_ballot, SoftwareSigner};
+use k256_methods::K256_VERIFY_ROTATED_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+/// The old key signed until this time, the new key from then on.
+const ROTATED_AT: u64 = 1_700_000_000;
+
+fn keys() -> [SigningKey; 2] {
+    [1u8, 2].map(|seed| SigningKey::from_bytes((&[seed; 32]).into()).unwrap())
+}
+
+fn epoch(key: &SigningKey, valid_from: u64, valid_until: Option<u64>) -> KeyEpoch {
+    let public_key = general_purpose::STANDARD.encode(canonical_k256_key(key.verifying_key()));
+    KeyEpoch { public_key, valid_from, valid_until }
+}
+
+/// The old key from time 0 until [ROTATED_AT], the new key after.
+fn schedule() -> Vec<KeyEpoch> {
+    let [old, new] = keys();
+    vec![epoch(&old, 0, Some(ROTATED_AT)), epoch(&new, ROTATED_AT, None)]
+}
+
+/// Already canonical, so the signature covers exactly these bytes.
+fn ballot(signed_at: u64) -> String {
+    format!(r#"{{"age":40,"id":"voter-2","is_student":false,"signed_at":{signed_at}}}"#)
+}
+
+#[test]
+fn epoch_is_picked_by_signing_time() {
+    let epochs = schedule();
+    assert_eq!(select_epoch(&epochs, 0), Ok(0));
+    assert_eq!(select_epoch(&epochs, ROTATED_AT - 1), Ok(0));
+    assert_eq!(select_epoch(&epochs, ROTATED_AT), Ok(1));
+    assert_eq!(select_epoch(&epochs, u64::MAX), Ok(1));
+}
+
+#[test]
+fn gaps_overlaps_and_empty_schedules_are_rejected() {
+    let [old, new] = keys();
+    let gap = [epoch(&old, 0, Some(100)), epoch(&new, 200, None)];
+    assert_eq!(select_epoch(&gap, 150), Err(RotationError::NoEpochAt { signed_at: 150 }));
+
+    let overlap = [epoch(&old, 0, Some(200)), epoch(&new, 100, None)];
+    assert_eq!(
+        select_epoch(&overlap, 50),
+        Err(RotationError::Overlapping { first: 0, second: 1 })
+    );
+
+    let backwards = [epoch(&old, 100, Some(100))];
+    assert_eq!(select_epoch(&backwards, 100), Err(RotationError::EmptyEpoch { epoch: 0 }));
+    assert_eq!(select_epoch(&[], 0), Err(RotationError::Empty));
+}
+
+#[test]
+fn schedule_hash_ignores_epoch_order_but_not_windows() {
+    let [old, new] = keys().map(|key| canonical_k256_key(key.verifying_key()));
+    let hash =
+        key_schedule_hash(&[(old.clone(), 0, Some(ROTATED_AT)), (new.clone(), ROTATED_AT, None)]);
+    assert_eq!(
+        key_schedule_hash(&[(new.clone(), ROTATED_AT, None), (old.clone(), 0, Some(ROTATED_AT))]),
+        hash
+    );
+    assert_ne!(key_schedule_hash(&[(old, 0, None), (new, ROTATED_AT, None)]), hash);
+}
+
+/// Signs a ballot signed at `signed_at` with `signer` and runs the rotated-keys guest against
+/// [schedule].
+fn execute(signer: &SigningKey, signed_at: u64) -> anyhow::Result<castvote_core::EpochReveal> {
+    let ballot = ballot(signed_at);
+    let input = RotatedKeysInput {
+        signature: sign_ballot(&SoftwareSigner::new(signer.clone()), &ballot)?,
+        ballot,
+        poll_id: 1001,
+        epochs: schedule(),
+    };
+    let env = ExecutorEnv::builder().write(&input)?.write(&VoteParams::default())?.build()?;
+    let session = default_executor().execute(env, K256_VERIFY_ROTATED_ELF)?;
+    decode_epoch_journal(&session.journal.bytes)
+}
+
+#[test]
+fn guest_verifies_a_ballot_from_each_epoch() {
+    let [old, new] = keys();
+    let before = execute(&old, ROTATED_AT - 1).unwrap();
+    assert_eq!((before.epoch, before.valid_from, before.valid_until), (0, 0, Some(ROTATED_AT)));
+
+    let after = execute(&new, ROTATED_AT).unwrap();
+    assert_eq!((after.epoch, after.valid_from, after.valid_until), (1, ROTATED_AT, None));
+
+    // Both name the same schedule.
+    assert_eq!(before.reveal.voter_key_hash, after.reveal.voter_key_hash);
+    assert_eq!(before.reveal.poll_id, 1001);
+}
+
+#[test]
+fn guest_rejects_a_key_used_outside_its_epoch() {
+    let [old, new] = keys();
+    assert!(execute(&old, ROTATED_AT).is_err());
+    assert!(execute(&new, ROTATED_AT - 1).is_err());
+}
@@ -0,0 +1,249 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Property tests of signing and of the journal encoding, run without the prover.
+
+use alloy_sol_types::SolValue;
+use base64::{engine::general_purpose, Engine as _};
+use castvote_core::{
+    decode_reveals, encode_reveals, encode_reveals_with, parse_ballot, AgeRange, BallotType, Date,
+    JournalCodec, JournalError, MessageFormat, RevealInfo, VoteParams, JOURNAL_VERSION,
+};
+use k256::ecdsa::signature::Verifier;
+use k256_example::{decode_journal, sign, VoterKey};
+use proptest::prelude::*;
+use sha2::{Digest, Sha256};
+
+/// A key of any supported scheme, derived from 32 random bytes.
+fn voter_key() -> impl Strategy<Value = VoterKey> {
+    (0..3u8, any::<[u8; 32]>()).prop_filter_map("invalid scalar", |(scheme, bytes)| match scheme {
+        0 => k256::ecdsa::SigningKey::from_bytes((&bytes).into()).ok().map(VoterKey::K256),
+        1 => p256::ecdsa::SigningKey::from_bytes((&bytes).into()).ok().map(VoterKey::P256),
+        _ => Some(VoterKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&bytes))),
+    })
+}
+
+/// Verifies `signature` over `message` the way the guest methods do.
+fn verifies(key: &VoterKey, message: &[u8], signature: &[u8]) -> bool {
+    let digest = Sha256::digest(message);
+    match key {
+        VoterKey::K256(key) => k256::ecdsa::Signature::from_slice(signature)
+            .is_ok_and(|s| key.verifying_key().verify(&digest, &s).is_ok()),
+        VoterKey::P256(key) => p256::ecdsa::Signature::from_slice(signature)
+            .is_ok_and(|s| key.verifying_key().verify(&digest, &s).is_ok()),
+        VoterKey::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+            .is_ok_and(|s| key.verifying_key().verify(&digest, &s).is_ok()),
+    }
+}
+
+fn sign_bytes(key: &VoterKey, message: &str) -> Vec<u8> {
+    let signature = sign(key, message, MessageFormat::Sha256).unwrap();
+    general_purpose::STANDARD.decode(signature).unwrap()
+}
+
+fn date() -> impl Strategy<Value = Date> {
+    (1900..2100i32, 1..=12u8, 1..=28u8).prop_map(|(y, m, d)| Date::new(y, m, d).unwrap())
+}
+
+fn ballot_type() -> impl Strategy<Value = BallotType> {
+    prop_oneof![Just(BallotType::Single), Just(BallotType::Approval), Just(BallotType::Ranked)]
+}
+
+fn message_format() -> impl Strategy<Value = MessageFormat> {
+    prop_oneof![
+        Just(MessageFormat::Sha256),
+        Just(MessageFormat::Eip191),
+        Just(MessageFormat::Raw),
+        Just(MessageFormat::Eip712),
+    ]
+}
+
+fn age_range() -> impl Strategy<Value = AgeRange> {
+    (any::<u32>(), any::<u32>()).prop_map(|(min, max)| AgeRange { min, max })
+}
+
+fn sample_reveal() -> RevealInfo {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": false}"#).unwrap();
+    RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap()
+}
+
+prop_compose! {
+    fn reveal_info()(
+        nullifier in "[0-9a-f]{64}",
+        age in any::<u32>(),
+        is_student in any::<bool>(),
+        poll_id in any::<u64>(),
+        age_as_of in proptest::option::of(date()),
+        ballot_type in ballot_type(),
+        selection in proptest::collection::vec("\\PC{0,12}", 0..4),
+        voter_key_hash in "[0-9a-f]{64}",
+        age_range in proptest::option::of(age_range()),
+        election_id in proptest::option::of("\\PC{0,16}"),
+        poll_hash in proptest::option::of("[0-9a-f]{64}"),
+        message_format in proptest::option::of(message_format()),
+        student_required in proptest::option::of(any::<bool>()),
+        policy_hash in proptest::option::of("[0-9a-f]{64}"),
+        poll_name in proptest::option::of("\\PC{0,16}"),
+        receipt_id in proptest::option::of("[0-9a-f]{32,64}"),
+        id_commitment in proptest::option::of("[0-9a-f]{64}"),
+        max_poll_id in proptest::option::of(any::<u64>()),
+        author_key_hash in proptest::option::of("[0-9a-f]{64}"),
+        abstained in proptest::option::of(any::<bool>()),
+    ) -> RevealInfo {
+        RevealInfo {
+            nullifier,
+            age,
+            is_student,
+            poll_id,
+            age_as_of,
+            ballot_type,
+            selection,
+            voter_key_hash,
+            age_range,
+            election_id,
+            poll_hash,
+            message_format,
+            student_required,
+            policy_hash,
+            poll_name,
+            receipt_id,
+            id_commitment,
+            max_poll_id,
+            author_key_hash,
+            abstained,
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn signature_verifies(key in voter_key(), message in "\\PC{0,256}") {
+        prop_assert!(verifies(&key, message.as_bytes(), &sign_bytes(&key, &message)));
+    }
+
+    #[test]
+    fn mutated_signature_fails(
+        key in voter_key(),
+        message in "\\PC{0,256}",
+        index in any::<prop::sample::Index>(),
+        mask in 1..=255u8,
+    ) {
+        let mut signature = sign_bytes(&key, &message);
+        let i = index.index(signature.len());
+        signature[i] ^= mask;
+        prop_assert!(!verifies(&key, message.as_bytes(), &signature));
+    }
+
+    #[test]
+    fn mutated_message_fails(
+        key in voter_key(),
+        message in "\\PC{1,256}",
+        index in any::<prop::sample::Index>(),
+        mask in 1..=255u8,
+    ) {
+        let signature = sign_bytes(&key, &message);
+        let mut bytes = message.into_bytes();
+        let i = index.index(bytes.len());
+        bytes[i] ^= mask;
+        prop_assert!(!verifies(&key, &bytes, &signature));
+    }
+
+    #[test]
+    fn journal_round_trips(reveal in reveal_info()) {
+        let journal = reveal.encode().abi_encode();
+        prop_assert_eq!(decode_journal(&journal).unwrap(), reveal);
+    }
+
+    #[test]
+    fn batch_journal_round_trips(reveals in proptest::collection::vec(reveal_info(), 0..8)) {
+        let journal = encode_reveals(&reveals).abi_encode();
+        let encoded = Vec::<u8>::abi_decode(&journal).unwrap();
+        prop_assert_eq!(decode_reveals(&encoded).unwrap(), reveals);
+    }
+
+    #[test]
+    fn cbor_journal_round_trips(reveal in reveal_info()) {
+        let journal = reveal.encode_with(JournalCodec::Cbor).abi_encode();
+        prop_assert_eq!(decode_journal(&journal).unwrap(), reveal);
+    }
+
+    #[test]
+    fn cbor_batch_journal_round_trips(reveals in proptest::collection::vec(reveal_info(), 0..8)) {
+        let encoded = encode_reveals_with(&reveals, JournalCodec::Cbor);
+        prop_assert_eq!(decode_reveals(&encoded).unwrap(), reveals);
+    }
+}
+
+#[test]
+fn journal_starts_with_version_byte() {
+    let reveals = [sample_reveal()];
+    assert_eq!(sample_reveal().encode()[0], JOURNAL_VERSION);
+    assert_eq!(encode_reveals(&reveals)[0], JOURNAL_VERSION);
+}
+
+#[test]
+fn unknown_journal_version_is_rejected() {
+    let mut encoded = sample_reveal().encode();
+    encoded[0] = JOURNAL_VERSION + 1;
+    assert!(matches!(
+        RevealInfo::decode(&encoded),
+        Err(JournalError::UnsupportedVersion(v)) if v == JOURNAL_VERSION + 1
+    ));
+    assert!(matches!(decode_reveals(&encoded), Err(JournalError::UnsupportedVersion(_))));
+
+    let err = decode_journal(&encoded.abi_encode()).unwrap_err();
+    assert!(format!("{err:#}").contains("not supported"), "{err:#}");
+
+    assert!(matches!(RevealInfo::decode(&[]), Err(JournalError::Empty)));
+}
+
+#[test]
+fn bincode_journals_of_every_earlier_version_decode() {
+    let reveal = RevealInfo {
+        age_range: None,
+        election_id: None,
+        poll_hash: None,
+        message_format: None,
+        student_required: None,
+        policy_hash: None,
+        poll_name: None,
+        receipt_id: None,
+        id_commitment: None,
+        max_poll_id: None,
+        author_key_hash: None,
+        abstained: None,
+        ..sample_reveal()
+    };
+    let current = reveal.encode_with(JournalCodec::Bincode);
+
+    // Version 1 held the eight base fields and versions 2 to 13 each appended one more, unset here
+    // and so a single `None` byte. Version 14 only changed the age-eligibility journal.
+    for version in 1..JOURNAL_VERSION {
+        let dropped = usize::from(13u8.saturating_sub(version));
+        let mut encoded = current[..current.len() - dropped].to_vec();
+        encoded[0] = version;
+        assert_eq!(RevealInfo::decode(&encoded).unwrap(), reveal, "version {version}");
+
+        let mut batch = vec![version];
+        batch.extend(2u64.to_le_bytes());
+        batch.extend_from_slice(&encoded[1..]);
+        batch.extend_from_slice(&encoded[1..]);
+        assert_eq!(decode_reveals(&batch).unwrap(), [reveal.clone(), reveal.clone()]);
+
+        encoded.pop();
+        assert!(matches!(RevealInfo::decode(&encoded), Err(JournalError::Malformed(_))));
+    }
+}
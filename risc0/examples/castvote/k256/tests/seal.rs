@@ -0,0 +1,83 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `inspect-seal`: telling fake and Groth16 seals apart, hex or binary, without verifying them.
+
+use std::fs;
+
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    seal::{inspect_seal_file, read_seal_file, SealEncoding, SealInfo, SealKind, FAKE_SELECTOR},
+};
+use risc0_zkvm::{sha::Digestible, Groth16ReceiptVerifierParameters};
+
+/// The dev-mode seal of the first proof in `testdata/audit`.
+const FIXTURE: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/audit/proofs/01-alice/seal.dat");
+
+fn groth16_selector() -> [u8; 4] {
+    Groth16ReceiptVerifierParameters::default().digest().as_bytes()[..4].try_into().unwrap()
+}
+
+#[test]
+fn fake_seals_have_an_all_zero_selector() {
+    let info = inspect_seal_file(FIXTURE).unwrap();
+    assert_eq!(info.encoding, SealEncoding::Hex);
+    assert_eq!((info.selector, info.kind), (FAKE_SELECTOR, SealKind::Fake));
+    assert_eq!((info.seal_len, info.proof_len), (36, 32));
+    assert_eq!(info.expected_proof_len(), Some(info.proof_len));
+}
+
+#[test]
+fn groth16_and_unknown_selectors_are_told_apart() {
+    let seal = [groth16_selector().as_slice(), &[7; 256]].concat();
+    let info = SealInfo::parse(&seal, SealEncoding::Binary).unwrap();
+    assert_eq!((info.kind, info.proof_len), (SealKind::Groth16, 256));
+    assert_eq!(info.expected_proof_len(), Some(256));
+
+    let info = SealInfo::parse(&[0xde, 0xad, 0xbe, 0xef, 1, 2], SealEncoding::Binary).unwrap();
+    assert_eq!((info.kind, info.selector), (SealKind::Unknown, [0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(info.expected_proof_len(), None);
+}
+
+#[test]
+fn binary_and_hex_files_are_sniffed() {
+    let dir = tempfile::tempdir().unwrap();
+    let seal = [groth16_selector().as_slice(), &[0xab; 256]].concat();
+
+    let binary = dir.path().join("seal.bin");
+    fs::write(&binary, &seal).unwrap();
+    assert_eq!(read_seal_file(&binary).unwrap(), (seal.clone(), SealEncoding::Binary));
+
+    let hex = dir.path().join("seal.dat");
+    fs::write(&hex, format!("0x{}\n", hex::encode(&seal))).unwrap();
+    assert_eq!(read_seal_file(&hex).unwrap(), (seal, SealEncoding::Hex));
+}
+
+#[test]
+fn seals_shorter_than_a_selector_are_rejected() {
+    let err = SealInfo::parse(&[0, 0, 0], SealEncoding::Binary).unwrap_err();
+    assert!(err.to_string().contains("too short"), "{err}");
+}
+
+#[test]
+fn inspect_seal_takes_the_seal_file() {
+    let cli = Cli::try_parse_from(["checkvote", "inspect-seal", "--seal", "s.dat"]).unwrap();
+    let Some(Command::InspectSeal { seal }) = cli.command else {
+        panic!("expected the inspect-seal subcommand");
+    };
+    assert_eq!(seal.to_str(), Some("s.dat"));
+}
@@ -0,0 +1,77 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The committed test vector in `testdata/self-test`, checked as `checkvote self-test` does.
+
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    self_test::{run_self_test, TestVector, BALLOT},
+};
+
+fn failed(vector: &TestVector, ballot: &str) -> Vec<&'static str> {
+    run_self_test(vector, ballot).iter().filter(|c| !c.pass).map(|c| c.name).collect()
+}
+
+#[test]
+fn builtin_vector_passes() {
+    let vector = TestVector::builtin().unwrap();
+    let checks = run_self_test(&vector, BALLOT);
+    let names: Vec<_> = checks.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["public key", "signature", "nullifier", "journal encoding"]);
+    assert!(checks.iter().all(|c| c.pass), "{checks:?}");
+}
+
+#[test]
+fn a_wrong_expectation_fails_its_check() {
+    let mut vector = TestVector::builtin().unwrap();
+    vector.nullifier = "00".repeat(32);
+    assert_eq!(failed(&vector, BALLOT), ["nullifier"]);
+
+    let mut vector = TestVector::builtin().unwrap();
+    vector.poll_id += 1;
+    assert_eq!(failed(&vector, BALLOT), ["nullifier", "journal encoding"]);
+}
+
+#[test]
+fn a_different_ballot_fails_the_signature() {
+    let vector = TestVector::builtin().unwrap();
+    let ballot = BALLOT.replace("\"age\": 40", "\"age\": 41");
+    assert_eq!(failed(&vector, &ballot), ["signature", "journal encoding"]);
+}
+
+#[test]
+fn an_unusable_vector_fails_the_pipeline() {
+    let mut vector = TestVector::builtin().unwrap();
+    vector.key_seed = "not hex".into();
+    let checks = run_self_test(&vector, BALLOT);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].name, "pipeline");
+    assert!(checks[0].to_string().starts_with("FAIL pipeline: "), "{}", checks[0]);
+}
+
+#[test]
+fn self_test_takes_host_only() {
+    let cli = Cli::try_parse_from(["checkvote", "self-test", "--host-only"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::SelfTest { host_only: true })));
+}
+
+#[cfg(feature = "prove")]
+#[test]
+fn builtin_vector_proves_in_dev_mode() {
+    let vector = TestVector::builtin().unwrap();
+    let check = k256_example::self_test::prove_self_test(&vector, BALLOT);
+    assert!(check.pass, "{check}");
+}
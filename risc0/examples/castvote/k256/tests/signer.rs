@@ -0,0 +1,96 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+
+use anyhow::Result;
+use base64::{engine::general_purpose, Engine as _};
+use k256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature, SigningKey, VerifyingKey,
+};
+use k256_example::{sign_ballot, BallotSigner, SoftwareSigner};
+use sha2::{Digest, Sha256};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+/// Stands in for a hardware signer: signs with its own key and records every digest it sees.
+struct MockSigner {
+    key: SigningKey,
+    seen: RefCell<Vec<Vec<u8>>>,
+    sign_with: Option<SigningKey>,
+}
+
+impl MockSigner {
+    fn new(seed: u8) -> Self {
+        Self {
+            key: SigningKey::from_bytes((&[seed; 32]).into()).unwrap(),
+            seen: RefCell::new(Vec::new()),
+            sign_with: None,
+        }
+    }
+}
+
+impl BallotSigner for MockSigner {
+    fn sign(&self, msg_hash: &[u8]) -> Result<Signature> {
+        self.seen.borrow_mut().push(msg_hash.to_vec());
+        Ok(self.sign_with.as_ref().unwrap_or(&self.key).sign(msg_hash))
+    }
+
+    fn public_key(&self) -> VerifyingKey {
+        *self.key.verifying_key()
+    }
+}
+
+fn decode_signature(signature: &str) -> Signature {
+    let bytes = general_purpose::STANDARD.decode(signature).unwrap();
+    Signature::from_slice(&bytes).unwrap()
+}
+
+#[test]
+fn mock_signer_receives_ballot_digest() {
+    let signer = MockSigner::new(1);
+    let signature = sign_ballot(&signer, BALLOT).unwrap();
+
+    let digest = Sha256::digest(BALLOT.as_bytes());
+    assert_eq!(*signer.seen.borrow(), vec![digest.to_vec()]);
+    assert!(signer
+        .public_key()
+        .verify(&digest, &decode_signature(&signature))
+        .is_ok());
+}
+
+#[test]
+fn signature_from_wrong_key_is_rejected() {
+    let mut signer = MockSigner::new(1);
+    signer.sign_with = Some(SigningKey::from_bytes((&[2u8; 32]).into()).unwrap());
+
+    assert!(sign_ballot(&signer, BALLOT).is_err());
+}
+
+#[test]
+fn software_signer_matches_wrapped_key() {
+    let key = SigningKey::from_bytes((&[3u8; 32]).into()).unwrap();
+    let signer = SoftwareSigner::from_base64(&general_purpose::STANDARD.encode(key.to_bytes())).unwrap();
+    assert_eq!(signer.public_key(), *key.verifying_key());
+
+    let signature = sign_ballot(&signer, BALLOT).unwrap();
+    let digest = Sha256::digest(BALLOT.as_bytes());
+    assert!(key.verifying_key().verify(&digest, &decode_signature(&signature)).is_ok());
+}
+
+#[test]
+fn software_signer_rejects_short_key() {
+    assert!(SoftwareSigner::from_base64(&general_purpose::STANDARD.encode([1u8; 31])).is_err());
+}
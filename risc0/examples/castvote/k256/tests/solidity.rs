@@ -0,0 +1,151 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The generated Solidity decoder's layout, held to the journals the guest commits.
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    AgeRange, BallotType, Date, JournalCodec, MessageFormat, RevealInfo, JOURNAL_VERSION,
+};
+use k256_example::solidity::{generate_solidity, read_reveal_fields, FieldValue, REVEAL_LAYOUT};
+
+fn full_reveal() -> RevealInfo {
+    RevealInfo {
+        nullifier: "ab".repeat(32),
+        age: 42,
+        is_student: true,
+        poll_id: 1001,
+        age_as_of: Some(Date::new(2025, 6, 1).unwrap()),
+        ballot_type: BallotType::Ranked,
+        selection: vec!["yes".to_string(), "maybe".to_string()],
+        voter_key_hash: "cd".repeat(32),
+        age_range: Some(AgeRange { min: 18, max: 65 }),
+        election_id: Some("city-2025".to_string()),
+        poll_hash: Some("ef".repeat(32)),
+        message_format: Some(MessageFormat::Eip712),
+        student_required: Some(false),
+        policy_hash: Some("01".repeat(32)),
+        poll_name: Some("budget".to_string()),
+        receipt_id: Some("23".repeat(16)),
+        id_commitment: Some("45".repeat(32)),
+        max_poll_id: Some(5000),
+        author_key_hash: Some("67".repeat(32)),
+        abstained: Some(true),
+    }
+}
+
+/// The journal as the single-ballot guest commits it.
+fn journal(reveal: &RevealInfo, codec: JournalCodec) -> Vec<u8> {
+    reveal.encode_with(codec).abi_encode()
+}
+
+fn string(value: &str) -> FieldValue {
+    FieldValue::String(value.to_string())
+}
+
+#[test]
+fn layout_reads_every_field_the_guest_commits() {
+    let reveal = full_reveal();
+    let fields = read_reveal_fields(&journal(&reveal, JournalCodec::Bincode)).unwrap();
+    let expected = vec![
+        ("nullifier", string(&reveal.nullifier)),
+        ("age", FieldValue::U32(42)),
+        ("isStudent", FieldValue::Bool(true)),
+        ("pollId", FieldValue::U64(1001)),
+        ("ageAsOf", string("2025-06-01")),
+        ("ballotType", FieldValue::U32(2)),
+        ("selection", FieldValue::StringList(reveal.selection.clone())),
+        ("voterKeyHash", string(&reveal.voter_key_hash)),
+        ("ageRange", FieldValue::AgeRange { min: 18, max: 65 }),
+        ("electionId", string("city-2025")),
+        ("pollHash", string(&"ef".repeat(32))),
+        ("messageFormat", FieldValue::U32(3)),
+        ("studentRequired", FieldValue::Bool(false)),
+        ("policyHash", string(&"01".repeat(32))),
+        ("pollName", string("budget")),
+        ("receiptId", string(&"23".repeat(16))),
+        ("idCommitment", string(&"45".repeat(32))),
+        ("maxPollId", FieldValue::U64(5000)),
+        ("authorKeyHash", string(&"67".repeat(32))),
+        ("abstained", FieldValue::Bool(true)),
+    ];
+    assert_eq!(fields, expected);
+}
+
+#[test]
+fn layout_reads_absent_optional_fields() {
+    let reveal = RevealInfo {
+        age_as_of: None,
+        age_range: None,
+        election_id: None,
+        poll_hash: None,
+        message_format: None,
+        student_required: None,
+        policy_hash: None,
+        poll_name: None,
+        receipt_id: None,
+        id_commitment: None,
+        max_poll_id: None,
+        author_key_hash: None,
+        abstained: None,
+        ..full_reveal()
+    };
+    let fields = read_reveal_fields(&journal(&reveal, JournalCodec::Bincode)).unwrap();
+    for ((name, value), field) in fields.iter().zip(REVEAL_LAYOUT) {
+        assert_eq!(field.optional, *value == FieldValue::Absent, "{name}");
+    }
+}
+
+#[test]
+fn only_current_bincode_journals_are_read() {
+    let cbor = journal(&full_reveal(), JournalCodec::Cbor);
+    let err = read_reveal_fields(&cbor).unwrap_err().to_string();
+    assert_eq!(err, "CBOR journals cannot be decoded on-chain");
+
+    let mut payload = full_reveal().encode();
+    payload[0] = JOURNAL_VERSION - 1;
+    let err = read_reveal_fields(&payload.abi_encode()).unwrap_err().to_string();
+    assert_eq!(err, format!("journal version {} is not {JOURNAL_VERSION}", JOURNAL_VERSION - 1));
+
+    let mut payload = full_reveal().encode();
+    payload.push(0);
+    let err = read_reveal_fields(&payload.abi_encode()).unwrap_err().to_string();
+    assert_eq!(err, "1 trailing bytes after the last field");
+}
+
+#[test]
+fn generated_library_follows_the_layout() {
+    let solidity = generate_solidity();
+    assert!(solidity.contains(&format!("uint8 internal constant VERSION = {JOURNAL_VERSION};")));
+    let members: Vec<&str> = solidity
+        .lines()
+        .skip_while(|line| line.trim() != "struct RevealInfo {")
+        .skip(1)
+        .take_while(|line| line.trim() != "}")
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .map(str::trim)
+        .collect();
+    let mut expected = Vec::new();
+    for field in REVEAL_LAYOUT {
+        if field.optional {
+            let name = field.name;
+            expected.push(format!("bool has{}{};", name[..1].to_uppercase(), &name[1..]));
+        }
+        expected.push(format!("{} {};", field.ty.solidity_type(), field.name));
+    }
+    assert_eq!(members, expected);
+    for field in REVEAL_LAYOUT {
+        assert!(solidity.contains(&format!("(r.{}, p) = ", field.name)), "{}", field.name);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `--require-student`: student-only polls reject non-student ballots and commit the rule.
+#![cfg(feature = "prove")]
+
+use alloy_sol_types::SolValue;
+use castvote_core::{parse_ballot, BallotError, RevealInfo, VoteParams};
+use clap::Parser;
+use k256_example::{cli::Cli, decode_journal, prepare_ballot};
+
+mod common;
+
+fn ballot(is_student: bool) -> String {
+    format!(r#"{{"id": 1, "age": 20, "is_student": {is_student}}}"#)
+}
+
+fn student_only() -> VoteParams {
+    VoteParams { require_student: true, ..Default::default() }
+}
+
+fn reveal(is_student: bool, params: &VoteParams) -> Result<RevealInfo, BallotError> {
+    RevealInfo::new(&parse_ballot(&ballot(is_student)).unwrap(), 1001, &[], params)
+}
+
+#[test]
+fn flag_reaches_the_vote_params() {
+    let parse = |flags: &[&str]| {
+        let argv = [&["checkvote", "b.json", "--poll-id", "1001"], flags].concat();
+        Cli::try_parse_from(argv).unwrap().prove.unwrap().vote_params().unwrap()
+    };
+    assert!(!parse(&[]).require_student);
+    assert!(parse(&["--require-student"]).require_student);
+}
+
+#[test]
+fn student_only_poll_rejects_non_students() {
+    assert_eq!(reveal(false, &student_only()), Err(BallotError::NotAStudent));
+    assert!(prepare_ballot(&ballot(false), &student_only()).is_err());
+}
+
+#[test]
+fn student_only_poll_accepts_students() {
+    let committed = reveal(true, &student_only()).unwrap();
+    assert!(committed.is_student);
+    assert_eq!(committed.student_required, Some(true));
+    assert_eq!(RevealInfo::decode(&committed.encode()).unwrap(), committed);
+    assert!(prepare_ballot(&ballot(true), &student_only()).is_ok());
+}
+
+#[test]
+fn general_poll_only_discloses_is_student() {
+    let committed = reveal(false, &VoteParams::default()).unwrap();
+    assert!(!committed.is_student);
+    assert_eq!(committed.student_required, Some(false));
+}
+
+#[test]
+fn version_5_journals_decode_without_the_rule() {
+    let reveal = reveal(true, &student_only()).unwrap();
+    let reveal = RevealInfo { student_required: None, abstained: None, ..reveal };
+    // Version 5 is the current version without the trailing `student_required`, `policy_hash`,
+    // `poll_name`, `receipt_id`, `id_commitment`, `max_poll_id`, `author_key_hash` and
+    // `abstained`, each encoded here as a single `None` byte.
+    let mut v5 = reveal.encode();
+    assert_eq!(v5.split_off(v5.len() - 8), [0, 0, 0, 0, 0, 0, 0, 0]);
+    v5[0] = 5;
+    assert_eq!(decode_journal(&v5.abi_encode()).unwrap(), reveal);
+}
+
+#[test]
+fn guest_commits_the_student_requirement() {
+    let committed = common::execute(&ballot(true), &student_only()).unwrap();
+    assert_eq!((committed.is_student, committed.student_required), (true, Some(true)));
+}
+
+#[test]
+fn guest_aborts_on_a_non_student_ballot() {
+    assert!(common::execute(&ballot(false), &student_only()).is_err());
+}
@@ -0,0 +1,44 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Catching exact resubmissions within a batch.
+
+use k256_example::submission::{DuplicateSubmission, SubmissionTracker};
+
+#[test]
+fn the_same_signed_ballot_twice_is_a_duplicate() {
+    let mut tracker = SubmissionTracker::new();
+    tracker.check("a.json", "c2ln", r#"{"id":1}"#).unwrap();
+    let duplicate = tracker.check("b.json", "c2ln", r#"{"id":1}"#).unwrap_err();
+    assert_eq!(duplicate, DuplicateSubmission { entry: "b.json".into(), first: "a.json".into() });
+    assert_eq!(duplicate.to_string(), "b.json resubmits the signed ballot of a.json");
+    assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn a_different_signature_or_message_is_not_a_duplicate() {
+    let mut tracker = SubmissionTracker::new();
+    tracker.check("line 1", "c2ln", r#"{"id":1}"#).unwrap();
+    tracker.check("line 2", "b3RoZXI=", r#"{"id":1}"#).unwrap();
+    tracker.check("line 3", "c2ln", r#"{"id":2}"#).unwrap();
+    assert_eq!(tracker.len(), 3);
+}
+
+#[test]
+fn the_boundary_between_signature_and_message_counts() {
+    let mut tracker = SubmissionTracker::new();
+    tracker.check("line 1", "ab", "c").unwrap();
+    tracker.check("line 2", "a", "bc").unwrap();
+}
@@ -0,0 +1,101 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `template`: example ballots that pass the schema and the guest's parser for every poll kind.
+
+use castvote_core::{parse_ballot, BallotType, PollIdentifier, VoteParams};
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    prepare_ballot,
+    template::{render_template, template_fields, TemplateOptions, TEMPLATE_BIRTHDATE},
+};
+use serde_json::{json, Value};
+
+fn options(ballot_type: BallotType) -> TemplateOptions {
+    TemplateOptions {
+        ballot_type,
+        options: vec!["yes".into(), "no".into()],
+        poll_id: None,
+        birthdate: false,
+    }
+}
+
+fn params(ballot_type: BallotType) -> VoteParams {
+    VoteParams { ballot_type, options: vec!["yes".into(), "no".into()], ..Default::default() }
+}
+
+#[test]
+fn default_template_is_a_ballot_the_host_would_sign() {
+    let template = render_template(&options(BallotType::Single), false).unwrap();
+    let value: Value = serde_json::from_str(&template).unwrap();
+    assert_eq!(value, json!({"id": 12345, "age": 30, "is_student": false, "choice": "yes"}));
+    prepare_ballot(&template, &params(BallotType::Single)).unwrap();
+}
+
+#[test]
+fn approval_and_ranked_templates_list_their_choices() {
+    for (ballot_type, choice) in
+        [(BallotType::Approval, json!(["yes"])), (BallotType::Ranked, json!(["yes", "no"]))]
+    {
+        let template = render_template(&options(ballot_type), false).unwrap();
+        let value: Value = serde_json::from_str(&template).unwrap();
+        assert_eq!(value["choice"], choice);
+        prepare_ballot(&template, &params(ballot_type)).unwrap();
+    }
+}
+
+#[test]
+fn optional_fields_follow_the_options() {
+    let options = TemplateOptions {
+        poll_id: Some(PollIdentifier::Named("board-2025".into())),
+        birthdate: true,
+        ..options(BallotType::Single)
+    };
+    let ballot = parse_ballot(&render_template(&options, false).unwrap()).unwrap();
+    assert_eq!(ballot.age, None);
+    assert_eq!(ballot.birthdate, Some(TEMPLATE_BIRTHDATE.parse().unwrap()));
+    assert_eq!(ballot.poll_id, options.poll_id);
+}
+
+#[test]
+fn annotated_template_explains_every_field() {
+    let options = options(BallotType::Single);
+    let annotated = render_template(&options, true).unwrap();
+    for field in template_fields(&options) {
+        assert!(annotated.contains(&format!("// {}", field.comment)), "{}", field.name);
+    }
+    // Without the comments it is the plain template.
+    let stripped: Vec<_> =
+        annotated.lines().filter(|line| !line.trim_start().starts_with("//")).collect();
+    assert_eq!(stripped.join("\n"), render_template(&options, false).unwrap());
+}
+
+#[test]
+fn template_without_options_is_rejected() {
+    let options = TemplateOptions { options: vec![], ..options(BallotType::Single) };
+    let err = render_template(&options, false).unwrap_err();
+    assert!(format!("{err:#}").contains("does not match the schema"), "{err:#}");
+}
+
+#[test]
+fn template_command_parses() {
+    let cli = Cli::try_parse_from(["checkvote", "template", "--ballot-type", "ranked"]).unwrap();
+    let Some(Command::Template { options, annotated, .. }) = cli.command else {
+        panic!("expected the template command");
+    };
+    assert_eq!(options, ["yes", "no"]);
+    assert!(!annotated);
+}
@@ -0,0 +1,107 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Per-phase proof timings and their percentiles over a run.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use k256_example::timings::{
+    percentile, read_timings, take_timings, timed, write_timings, Phase, PhaseStats, RunTimings,
+    Timings, TIMINGS_FILE,
+};
+
+fn timings(phases: &[(Phase, f64)]) -> Timings {
+    Timings(phases.iter().copied().collect())
+}
+
+#[test]
+fn phases_are_recorded_until_taken() {
+    // The only test in this binary touching the process-wide record.
+    take_timings();
+    let signature = timed(Phase::Signing, || "c2ln");
+    timed(Phase::Signing, || std::thread::sleep(Duration::from_millis(5)));
+    timed(Phase::Proving, || ());
+    assert_eq!(signature, "c2ln");
+
+    let taken = take_timings();
+    assert_eq!(taken.0.keys().copied().collect::<Vec<_>>(), [Phase::Signing, Phase::Proving]);
+    assert!(taken.get(Phase::Signing).unwrap() >= 0.005);
+    assert_eq!(taken.get(Phase::KeyImport), None);
+    assert_eq!(take_timings(), Timings::default());
+}
+
+#[test]
+fn timings_serialize_by_phase_name() {
+    let phases =
+        [Phase::KeyImport, Phase::Signing, Phase::Execution, Phase::Proving, Phase::Verification];
+    for phase in phases {
+        assert_eq!(serde_json::to_value(phase).unwrap(), phase.name());
+    }
+    let json = serde_json::to_value(timings(&[(Phase::KeyImport, 0.5), (Phase::Proving, 2.0)]));
+    assert_eq!(json.unwrap(), serde_json::json!({"key_import": 0.5, "proving": 2.0}));
+}
+
+#[test]
+fn timings_round_trip_through_the_output_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(read_timings(dir.path()).unwrap(), None);
+    let written = timings(&[(Phase::Execution, 1.25), (Phase::Verification, 0.01)]);
+    write_timings(dir.path(), &written).unwrap();
+    assert!(dir.path().join(TIMINGS_FILE).exists());
+    assert_eq!(read_timings(dir.path()).unwrap(), Some(written));
+    assert!((timings(&[(Phase::Signing, 1.0), (Phase::Proving, 2.5)]).total() - 3.5).abs() < 1e-9);
+}
+
+#[test]
+fn percentiles_use_the_nearest_rank() {
+    let sorted: Vec<f64> = (1..=10).map(f64::from).collect();
+    assert_eq!(percentile(&sorted, 50), 5.0);
+    assert_eq!(percentile(&sorted, 90), 9.0);
+    assert_eq!(percentile(&sorted, 99), 10.0);
+    assert_eq!(percentile(&[7.0], 50), 7.0);
+    assert_eq!(percentile(&sorted, 0), 1.0);
+
+    let stats = PhaseStats::of(&[3.0, 1.0, 2.0]).unwrap();
+    assert_eq!((stats.count, stats.min, stats.mean, stats.max), (3, 1.0, 2.0, 3.0));
+    assert_eq!(stats.percentiles["p50"], 2.0);
+    assert_eq!(stats.percentiles["p99"], 3.0);
+    assert_eq!(PhaseStats::of(&[]), None);
+}
+
+#[test]
+fn a_run_aggregates_each_phase_over_its_ballots() {
+    let ballots: BTreeMap<String, Timings> = [
+        ("a".to_string(), timings(&[(Phase::Proving, 10.0), (Phase::Verification, 1.0)])),
+        ("b".to_string(), timings(&[(Phase::Proving, 30.0), (Phase::Verification, 1.0)])),
+        // A cache hit: nothing proven.
+        ("c".to_string(), Timings::default()),
+    ]
+    .into();
+    let setup = timings(&[(Phase::KeyImport, 0.1), (Phase::Signing, 0.3)]);
+    let run = RunTimings::new(setup.clone(), ballots);
+
+    assert_eq!(run.setup, setup);
+    assert_eq!(run.phases.keys().collect::<Vec<_>>(), ["proving", "total", "verification"]);
+    assert_eq!(run.phases["proving"].count, 2);
+    assert_eq!(run.phases["proving"].percentiles["p90"], 30.0);
+    assert_eq!(run.phases["total"].count, 3);
+    assert_eq!(run.phases["total"].min, 0.0);
+    assert_eq!(run.phases["total"].max, 31.0);
+
+    let json = serde_json::to_value(&run).unwrap();
+    assert_eq!(json["ballots"]["a"]["proving"], 10.0);
+    assert_eq!(json["phases"]["proving"]["percentiles"]["p50"], 10.0);
+    assert_eq!(json["setup"]["signing"], 0.3);
+}
@@ -0,0 +1,134 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Test guests that record a bad signature in the journal instead of aborting, and production
+//! guests that refuse to.
+
+use castvote_core::{
+    parse_ballot, JournalCodec, JournalError, RevealInfo, UncheckedReveal, VoteParams,
+    CBOR_VERSION_FLAG, JOURNAL_VERSION,
+};
+use k256_example::{decode_journal, decode_unchecked_journal};
+#[cfg(feature = "prove")]
+use {
+    castvote_core::MessageFormat,
+    clap::Parser,
+    k256_example::{cli::Cli, import_keys, sign, VoterKey},
+};
+
+const BALLOT: &str = include_str!("../res/person.json");
+
+#[cfg(feature = "prove")]
+fn key() -> VoterKey {
+    let argv = ["checkvote", "b.json", "--poll-id", "1"];
+    import_keys(&Cli::try_parse_from(argv).unwrap().prove.unwrap()).unwrap()
+}
+
+#[cfg(feature = "prove")]
+fn unchecked_params() -> VoteParams {
+    VoteParams { allow_invalid_signature: true, ..VoteParams::default() }
+}
+
+#[test]
+fn unchecked_journals_do_not_pass_for_reveal_info() {
+    use alloy_sol_types::SolValue;
+
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[2; 33], &VoteParams::default()).unwrap();
+    let unchecked = UncheckedReveal { is_signature_valid: false, reveal };
+    let journal = unchecked.encode_with(Default::default()).abi_encode();
+
+    assert_eq!(decode_unchecked_journal(&journal).unwrap(), unchecked);
+    assert!(decode_journal(&journal).is_err());
+}
+
+#[test]
+fn unchecked_journals_of_earlier_versions_decode() {
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[2; 33], &VoteParams::default()).unwrap();
+    let reveal = RevealInfo { abstained: None, ..reveal };
+    let unchecked = UncheckedReveal { is_signature_valid: false, reveal };
+
+    // Version 12 is the current one without its last field, `abstained`, encoded as a `None` byte.
+    let mut previous = unchecked.encode_with(JournalCodec::Bincode);
+    assert_eq!(previous.pop(), Some(0));
+    previous[0] = 12;
+    assert_eq!(UncheckedReveal::decode(&previous).unwrap(), unchecked);
+
+    let mut previous = unchecked.encode_with(JournalCodec::Cbor);
+    previous[0] = 12 | CBOR_VERSION_FLAG;
+    assert_eq!(UncheckedReveal::decode(&previous).unwrap(), unchecked);
+
+    previous[0] = JOURNAL_VERSION + 1;
+    assert!(matches!(
+        UncheckedReveal::decode(&previous),
+        Err(JournalError::UnsupportedVersion(v)) if v == JOURNAL_VERSION + 1
+    ));
+}
+
+#[test]
+fn unchecked_journals_predate_version_9() {
+    let ballot = parse_ballot(BALLOT).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[2; 33], &VoteParams::default()).unwrap();
+    let unchecked = UncheckedReveal { is_signature_valid: false, reveal };
+
+    // Test builds committed no unchecked journal before version 9, in either codec.
+    for (codec, flag) in [(JournalCodec::Bincode, 0), (JournalCodec::Cbor, CBOR_VERSION_FLAG)] {
+        let mut encoded = unchecked.encode_with(codec);
+        encoded[0] = 8 | flag;
+        assert!(matches!(
+            UncheckedReveal::decode(&encoded),
+            Err(JournalError::UnsupportedVersion(8))
+        ));
+    }
+}
+
+#[cfg(all(feature = "prove", not(feature = "test-signatures")))]
+#[test]
+fn production_guest_refuses_to_record_a_bad_signature() {
+    use risc0_zkvm::{default_executor, ExecutorEnv};
+
+    let key = key();
+    let signature = sign(&key, BALLOT, MessageFormat::Sha256).unwrap();
+    let env = ExecutorEnv::builder()
+        .write(&(signature, BALLOT, 1001u64, key.public_key_base64()))
+        .unwrap()
+        .write(&unchecked_params())
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(default_executor().execute(env, k256_methods::K256_VERIFY_ELF).is_err());
+}
+
+#[cfg(feature = "test-signatures")]
+#[test]
+fn test_guest_commits_whether_the_signature_verified() {
+    use k256_example::prove::prove_vote;
+    use k256_methods::{K256_VERIFY_ELF, K256_VERIFY_ID};
+
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let key = key();
+    let other_ballot = BALLOT.replace("Alice", "Mallory");
+    for (signed, is_signature_valid) in [(BALLOT, true), (other_ballot.as_str(), false)] {
+        let signature = sign(&key, signed, MessageFormat::Sha256).unwrap();
+        let input = (signature, BALLOT, 1001u64, key.public_key_base64());
+        let receipt = prove_vote(&input, &unchecked_params(), K256_VERIFY_ELF, K256_VERIFY_ID)
+            .unwrap();
+
+        let unchecked = decode_unchecked_journal(&receipt.journal.bytes).unwrap();
+        assert_eq!(unchecked.is_signature_valid, is_signature_valid);
+        assert_eq!(unchecked.reveal.poll_id, 1001);
+    }
+}
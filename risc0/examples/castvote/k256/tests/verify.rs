@@ -0,0 +1,171 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Seal and journal verification as a browser viewer runs it, without the prover.
+
+use alloy_sol_types::SolValue;
+use castvote_core::{
+    check_verifier_parameters, parse_ballot, simulate_onchain, verify_journal_and_seal,
+    JournalError, RevealInfo, VerifyError, VoteParams, GROTH16_SEAL_LEN,
+};
+use k256_example::{read_hex_file, read_image_id};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    Groth16ReceiptVerifierParameters, InnerReceipt, Receipt,
+};
+
+const IMAGE_ID: [u32; 8] = [7; 8];
+
+fn journal() -> Vec<u8> {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": false}"#).unwrap();
+    let reveal = RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap();
+    reveal.encode().abi_encode()
+}
+
+fn groth16_selector() -> Vec<u8> {
+    Groth16ReceiptVerifierParameters::default().digest().as_bytes()[..4].to_vec()
+}
+
+/// A real Groth16 proof: `res/receipt_selective_disclosure_groth16.json` as the `seal.dat`,
+/// `journal.dat` and `image_id.dat` the prover writes. Its guest predates the version byte.
+fn groth16_fixture() -> (Vec<u8>, Vec<u8>, Digest) {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/groth16");
+    let seal = read_hex_file(format!("{dir}/seal.dat")).unwrap();
+    let journal = read_hex_file(format!("{dir}/journal.dat")).unwrap();
+    (seal, journal, read_image_id(&format!("{dir}/image_id.dat")).unwrap())
+}
+
+#[test]
+fn groth16_fixture_is_the_committed_receipt() {
+    let receipt: Receipt = serde_json::from_str(include_str!(
+        "../res/receipt_selective_disclosure_groth16.json"
+    ))
+    .unwrap();
+    let (seal, journal, image_id) = groth16_fixture();
+    receipt.verify(image_id).unwrap();
+
+    let InnerReceipt::Groth16(inner) = &receipt.inner else { panic!("not a Groth16 receipt") };
+    assert_eq!(seal, [groth16_selector(), inner.seal.clone()].concat());
+    assert_eq!(journal, receipt.journal.bytes);
+}
+
+#[test]
+fn groth16_seal_verifies() {
+    let (seal, journal, image_id) = groth16_fixture();
+    assert_eq!(simulate_onchain(&seal, &journal, image_id).unwrap(), journal.digest());
+    let actual = Groth16ReceiptVerifierParameters::default().digest();
+    check_verifier_parameters(&seal, actual).unwrap();
+
+    // The proof checks out, so decoding is what fails: the payload starts with the length of
+    // the nullifier, 64, where journals now have their version.
+    let err = verify_journal_and_seal(&seal, &journal, image_id).unwrap_err();
+    assert!(matches!(err, VerifyError::Journal(JournalError::UnsupportedVersion(64))), "{err}");
+    let payload = Vec::<u8>::abi_decode(&journal).unwrap();
+    assert_eq!(payload[..8], 64u64.to_le_bytes());
+}
+
+#[test]
+fn groth16_seal_is_bound_to_its_journal_and_image_id() {
+    let (seal, journal, image_id) = groth16_fixture();
+
+    let mut tampered = journal.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    let err = simulate_onchain(&seal, &tampered, image_id).unwrap_err();
+    assert_eq!(err.failed_check(), "invalid proof");
+    let err = verify_journal_and_seal(&seal, &tampered, image_id).unwrap_err();
+    assert!(matches!(err, VerifyError::Receipt(_)), "{err}");
+
+    let err = simulate_onchain(&seal, &journal, IMAGE_ID).unwrap_err();
+    assert_eq!(err.failed_check(), "invalid proof");
+
+    // The seal covers the ABI-encoded bytes of `journal.dat`, not the payload inside.
+    let payload = Vec::<u8>::abi_decode(&journal).unwrap();
+    let err = simulate_onchain(&seal, &payload, image_id).unwrap_err();
+    assert!(matches!(err, VerifyError::JournalDigestMismatch { .. }), "{err}");
+}
+
+#[test]
+fn seal_without_selector_is_rejected() {
+    assert!(matches!(
+        verify_journal_and_seal(&[0, 1], &journal(), IMAGE_ID),
+        Err(VerifyError::MissingSelector)
+    ));
+}
+
+#[test]
+fn fake_dev_mode_seal_is_rejected() {
+    // `encode_seal` writes a zero selector followed by the claim digest for fake receipts.
+    let seal = [[0u8; 4].as_slice(), &[0u8; 32]].concat();
+    assert!(matches!(
+        verify_journal_and_seal(&seal, &journal(), IMAGE_ID),
+        Err(VerifyError::UnknownSelector([0, 0, 0, 0]))
+    ));
+}
+
+#[test]
+fn forged_groth16_seal_is_rejected() {
+    let seal = [groth16_selector(), vec![1u8; 256]].concat();
+    let err = verify_journal_and_seal(&seal, &journal(), IMAGE_ID).unwrap_err();
+    assert!(matches!(err, VerifyError::Receipt(_)), "{err}");
+}
+
+#[test]
+fn simulation_names_the_failed_check() {
+    let err = simulate_onchain(&[0u8; 36], &journal(), IMAGE_ID).unwrap_err();
+    assert_eq!(err.failed_check(), "selector mismatch");
+
+    let short = [groth16_selector(), vec![1u8; 100]].concat();
+    let err = simulate_onchain(&short, &journal(), IMAGE_ID).unwrap_err();
+    assert!(matches!(err, VerifyError::SealLength(100)), "{err}");
+    assert_eq!(err.failed_check(), "seal length");
+
+    let forged = [groth16_selector(), vec![1u8; GROTH16_SEAL_LEN]].concat();
+    let err = simulate_onchain(&forged, &journal(), IMAGE_ID).unwrap_err();
+    assert_eq!(err.failed_check(), "invalid proof");
+}
+
+#[test]
+fn verifier_parameters_must_match_in_full() {
+    let actual = Groth16ReceiptVerifierParameters::default().digest();
+    let seal = [groth16_selector(), vec![1u8; GROTH16_SEAL_LEN]].concat();
+    check_verifier_parameters(&seal, actual).unwrap();
+
+    // Same selector, different parameters: a verifier configured otherwise.
+    let mut expected = actual;
+    expected.as_mut_bytes()[31] ^= 1;
+    let err = check_verifier_parameters(&seal, expected).unwrap_err();
+    assert!(matches!(err, VerifyError::VerifierParameters { .. }), "{err}");
+    assert_eq!(err.failed_check(), "verifier parameters");
+    let message = format!("verifier parameters digest is {actual}, expected {expected}");
+    assert_eq!(err.to_string(), message);
+
+    let fake = [[0u8; 4].as_slice(), &[0u8; 32]].concat();
+    let err = check_verifier_parameters(&fake, Digest::ZERO).unwrap_err();
+    assert!(matches!(err, VerifyError::UnknownSelector([0, 0, 0, 0])), "{err}");
+}
+
+#[test]
+fn image_id_is_read_from_hex_or_file() {
+    let hex_id = "07".repeat(32);
+    let expected = k256_example::read_image_id(&hex_id).unwrap();
+    assert_eq!(k256_example::read_image_id(&format!("0x{hex_id}")).unwrap(), expected);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("image_id.dat");
+    std::fs::write(&path, format!("{hex_id}\n")).unwrap();
+    assert_eq!(k256_example::read_image_id(path.to_str().unwrap()).unwrap(), expected);
+
+    std::fs::write(&path, "0707").unwrap();
+    assert!(k256_example::read_image_id(path.to_str().unwrap()).is_err());
+}
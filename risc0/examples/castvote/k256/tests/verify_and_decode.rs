@@ -0,0 +1,84 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Receipt verification and journal decoding in one call, exercised with fake dev-mode receipts.
+
+use alloy_sol_types::SolValue;
+use castvote_core::{parse_ballot, voter_key_hash, RevealInfo, VoteParams};
+use k256_example::{error::VoteError, verify_and_decode, verify_vote};
+use k256_methods::K256_VERIFY_ID;
+use risc0_zkvm::{FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+fn reveal() -> RevealInfo {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": false}"#).unwrap();
+    RevealInfo::new(&ballot, 1001, &[], &VoteParams::default()).unwrap()
+}
+
+fn fake_receipt(image_id: [u32; 8], journal: &[u8]) -> Receipt {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+    Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal.to_vec())
+}
+
+#[test]
+fn valid_receipt_decodes() {
+    let receipt = fake_receipt(K256_VERIFY_ID, &reveal().encode().abi_encode());
+    assert_eq!(verify_and_decode(&receipt).unwrap(), reveal());
+}
+
+#[test]
+fn corrupted_journal_fails_verification() {
+    let mut receipt = fake_receipt(K256_VERIFY_ID, &reveal().encode().abi_encode());
+    let last = receipt.journal.bytes.len() - 1;
+    receipt.journal.bytes[last] ^= 1;
+    let error = verify_and_decode(&receipt).unwrap_err();
+    assert_eq!(VoteError::of(&error), Some(VoteError::VerificationFailed));
+
+    receipt.journal.bytes.truncate(3);
+    assert!(verify_and_decode(&receipt).is_err());
+}
+
+#[test]
+fn receipt_of_another_method_fails_verification() {
+    let other_image = K256_VERIFY_ID.map(|word| word ^ 1);
+    let receipt = fake_receipt(other_image, &reveal().encode().abi_encode());
+    let error = verify_and_decode(&receipt).unwrap_err();
+    assert_eq!(VoteError::of(&error), Some(VoteError::VerificationFailed));
+}
+
+#[test]
+fn verified_journal_without_reveal_info_is_an_error() {
+    for journal in [&b""[..], b"garbage", &b"garbage".to_vec().abi_encode()] {
+        let error = verify_and_decode(&fake_receipt(K256_VERIFY_ID, journal)).unwrap_err();
+        assert_eq!(VoteError::of(&error), None, "{error:#}");
+    }
+}
+
+#[test]
+fn committed_key_hash_must_match_the_expected_one() {
+    let ballot = parse_ballot(r#"{"id": 1, "age": 30, "is_student": false}"#).unwrap();
+    let voter = [2u8; 33];
+    let reveal = RevealInfo::new(&ballot, 1001, &voter, &VoteParams::default()).unwrap();
+    let receipt = fake_receipt(K256_VERIFY_ID, &reveal.encode().abi_encode());
+
+    assert_eq!(verify_vote(&receipt, None).unwrap(), reveal);
+    assert_eq!(verify_vote(&receipt, Some(&voter_key_hash(&voter))).unwrap(), reveal);
+
+    let other = voter_key_hash(&[3u8; 33]);
+    let error = verify_vote(&receipt, Some(&other)).unwrap_err();
+    assert_eq!(VoteError::of(&error), Some(VoteError::VerificationFailed));
+    let message = format!("{error:#}");
+    assert!(message.contains(&reveal.voter_key_hash), "{message}");
+    assert!(message.contains(&hex::encode(other)), "{message}");
+}
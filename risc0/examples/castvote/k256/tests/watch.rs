@@ -0,0 +1,151 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The live tally of `watch-tally`, fed the journals in `testdata/report` one proof directory
+//! at a time.
+
+use std::{fs, path::Path};
+
+use clap::Parser;
+use k256_example::{
+    cli::{Cli, Command},
+    read_journal_file,
+    seal::FAKE_SELECTOR,
+    watch::{TallyWatcher, STATE_FILE},
+};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    ReceiptClaim,
+};
+
+const IMAGE_ID: [u32; 8] = [3; 8];
+
+/// Copies the fixture journal `name` into its own proof directory under `dir`, next to a
+/// dev-mode seal of it, which verifies with `RISC0_DEV_MODE` set.
+fn add_proof(dir: &Path, name: &str) {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/report/journals").join(name);
+    let proof_dir = dir.join(name.trim_end_matches(".dat"));
+    fs::create_dir_all(&proof_dir).unwrap();
+    fs::copy(&fixture, proof_dir.join("journal.dat")).unwrap();
+
+    let journal = read_journal_file(&fixture).unwrap();
+    let claim = ReceiptClaim::ok(Digest::from(IMAGE_ID), journal).digest();
+    let seal = [&FAKE_SELECTOR, claim.as_bytes()].concat();
+    fs::write(proof_dir.join("seal.dat"), hex::encode(seal)).unwrap();
+    fs::write(proof_dir.join("image_id.dat"), hex::encode(Digest::from(IMAGE_ID))).unwrap();
+}
+
+fn count(watcher: &TallyWatcher, poll_id: u64, choice: &str) -> u64 {
+    let counts = watcher.state().counts.get(&poll_id);
+    counts.and_then(|counts| counts.get(choice)).copied().unwrap_or(0)
+}
+
+#[test]
+fn new_journals_are_tallied_as_they_appear() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = TallyWatcher::open(dir.path(), &dir.path().join(STATE_FILE)).unwrap();
+    assert_eq!(watcher.scan().unwrap().tallied, 0);
+
+    add_proof(dir.path(), "01-alice.dat");
+    add_proof(dir.path(), "02-bob.dat");
+    assert_eq!(watcher.scan().unwrap().tallied, 2);
+    // Nothing new: nothing is counted again.
+    assert_eq!(watcher.scan().unwrap().tallied, 0);
+
+    add_proof(dir.path(), "03-carol.dat");
+    add_proof(dir.path(), "04-alice-again.dat");
+    let outcome = watcher.scan().unwrap();
+    assert_eq!((outcome.tallied, outcome.duplicates), (2, 1));
+    assert_eq!((count(&watcher, 1001, "yes"), count(&watcher, 1001, "no")), (2, 1));
+    assert_eq!(watcher.state().duplicates, 1);
+}
+
+#[test]
+fn a_restarted_watcher_does_not_count_twice() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    add_proof(dir.path(), "01-alice.dat");
+    add_proof(dir.path(), "05-alice-poll2.dat");
+    TallyWatcher::open(dir.path(), &state).unwrap().scan().unwrap();
+
+    let mut restarted = TallyWatcher::open(dir.path(), &state).unwrap();
+    assert_eq!(restarted.state().journals.len(), 2);
+    add_proof(dir.path(), "04-alice-again.dat");
+    let outcome = restarted.scan().unwrap();
+    // Only the new journal is read, and its nullifier is known from before the restart.
+    assert_eq!((outcome.tallied, outcome.duplicates), (1, 1));
+    assert_eq!((count(&restarted, 1001, "yes"), count(&restarted, 1002, "yes")), (1, 1));
+}
+
+#[test]
+fn a_partly_written_journal_is_read_again_on_the_next_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = TallyWatcher::open(dir.path(), &dir.path().join(STATE_FILE)).unwrap();
+    add_proof(dir.path(), "02-bob.dat");
+    let journal = dir.path().join("02-bob/journal.dat");
+    let full = fs::read_to_string(&journal).unwrap();
+    fs::write(&journal, &full[..full.len() / 2 + 1]).unwrap();
+
+    let outcome = watcher.scan().unwrap();
+    assert_eq!(outcome.tallied, 0);
+    assert_eq!(outcome.pending.len(), 1);
+    assert!(outcome.pending[0].0.contains("02-bob"), "{:?}", outcome.pending);
+    // The same failure is reported only once.
+    assert!(watcher.scan().unwrap().pending.is_empty());
+
+    fs::write(&journal, full).unwrap();
+    let outcome = watcher.scan().unwrap();
+    assert_eq!((outcome.tallied, outcome.pending.len()), (1, 0));
+    assert_eq!(count(&watcher, 1001, "no"), 1);
+}
+
+#[test]
+fn a_journal_without_a_valid_seal_is_not_counted() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = TallyWatcher::open(dir.path(), &dir.path().join(STATE_FILE)).unwrap();
+    add_proof(dir.path(), "01-alice.dat");
+    add_proof(dir.path(), "02-bob.dat");
+    // Alice's journal swapped for Carol's under Alice's seal, and Bob's seal not written yet.
+    let carol = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/report/journals/03-carol.dat");
+    fs::copy(carol, dir.path().join("01-alice/journal.dat")).unwrap();
+    let bob_seal = dir.path().join("02-bob/seal.dat");
+    let seal = fs::read(&bob_seal).unwrap();
+    fs::remove_file(&bob_seal).unwrap();
+
+    let outcome = watcher.scan().unwrap();
+    assert_eq!(outcome.tallied, 0);
+    assert_eq!(outcome.pending.len(), 2, "{:?}", outcome.pending);
+    assert!(outcome.pending[0].1.contains("does not match"), "{:?}", outcome.pending);
+    assert!(watcher.state().counts.is_empty());
+
+    // Once the seal is written, the proof verifies and is counted; the swapped one never is.
+    fs::write(&bob_seal, seal).unwrap();
+    let outcome = watcher.scan().unwrap();
+    assert_eq!((outcome.tallied, outcome.pending.len()), (1, 0));
+    assert_eq!((count(&watcher, 1001, "yes"), count(&watcher, 1001, "no")), (0, 1));
+}
+
+#[test]
+fn parses_the_watch_tally_command() {
+    let argv = ["checkvote", "watch-tally", "--dir", "proofs", "--once"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    let Some(Command::WatchTally { dir, state, interval, once }) = cli.command else {
+        panic!("expected the watch-tally subcommand");
+    };
+    assert_eq!((dir.to_str(), state, interval, once), (Some("proofs"), None, 5, true));
+    let argv = ["checkvote", "watch-tally", "--dir", "proofs", "--interval", "0"];
+    assert!(Cli::try_parse_from(argv).is_err());
+}
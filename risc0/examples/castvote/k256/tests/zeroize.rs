@@ -0,0 +1,51 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Secret key material is wiped once it is no longer needed: every type holding a private key,
+//! seed or password zeroizes itself on drop.
+
+use castvote_core::ElectionScope;
+use k256_example::{keyfile::KeyPassword, keys::decode_private_key, signer::SoftwareSigner, VoterKey};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+/// Fails to compile unless `T` wipes its secrets when dropped.
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+/// Fails to compile unless `value` is a [Zeroizing] wrapper.
+fn assert_zeroizing<T: zeroize::Zeroize>(value: &Zeroizing<T>) -> &T {
+    value
+}
+
+#[test]
+fn key_types_zeroize_on_drop() {
+    assert_zeroize_on_drop::<VoterKey>();
+    assert_zeroize_on_drop::<SoftwareSigner>();
+    assert_zeroize_on_drop::<KeyPassword>();
+    assert_zeroize_on_drop::<ElectionScope>();
+}
+
+#[test]
+fn wrapped_signing_keys_zeroize_on_drop() {
+    // `VoterKey` and `SoftwareSigner` rely on these to wipe the scalar.
+    assert_zeroize_on_drop::<k256::ecdsa::SigningKey>();
+    assert_zeroize_on_drop::<p256::ecdsa::SigningKey>();
+    assert_zeroize_on_drop::<ed25519_dalek::SigningKey>();
+}
+
+#[test]
+fn decoded_private_keys_are_zeroizing() {
+    let decoded = decode_private_key("AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=").unwrap();
+    assert_eq!(assert_zeroizing(&decoded), &[1u8; 32]);
+}